@@ -1085,6 +1085,23 @@ pub enum AnalyzerError {
         token_source: TokenSource,
     },
 
+    #[diagnostic(
+        severity(Error),
+        code(misplaced_attribute),
+        help("place it at {expected}"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#{}", self.code().unwrap())
+    )]
+    #[error("\"{name}\" attribute can't be placed here")]
+    MisplacedAttribute {
+        name: String,
+        expected: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+        token_source: TokenSource,
+    },
+
     #[diagnostic(
         severity(Error),
         code(missing_clock_domain),
@@ -2065,6 +2082,7 @@ impl AnalyzerError {
             AnalyzerError::MismatchFunctionArity { input, .. } => input,
             AnalyzerError::MismatchGenericsArity { input, .. } => input,
             AnalyzerError::MismatchType { input, .. } => input,
+            AnalyzerError::MisplacedAttribute { input, .. } => input,
             AnalyzerError::MissingClockDomain { input, .. } => input,
             AnalyzerError::MissingClockSignal { input, .. } => input,
             AnalyzerError::MissingDefaultArgument { input, .. } => input,
@@ -2184,6 +2202,7 @@ impl AnalyzerError {
             AnalyzerError::MismatchFunctionArity { token_source, .. } => *token_source,
             AnalyzerError::MismatchGenericsArity { token_source, .. } => *token_source,
             AnalyzerError::MismatchType { token_source, .. } => *token_source,
+            AnalyzerError::MisplacedAttribute { token_source, .. } => *token_source,
             AnalyzerError::MissingClockDomain { token_source, .. } => *token_source,
             AnalyzerError::MissingClockSignal { token_source, .. } => *token_source,
             AnalyzerError::MissingDefaultArgument { token_source, .. } => *token_source,
@@ -2858,6 +2877,15 @@ impl AnalyzerError {
             token_source: token.source(),
         }
     }
+    pub fn misplaced_attribute(name: &str, expected: &str, token: &TokenRange) -> Self {
+        AnalyzerError::MisplacedAttribute {
+            name: name.to_string(),
+            expected: expected.to_string(),
+            input: source(token),
+            error_location: token.into(),
+            token_source: token.source(),
+        }
+    }
     pub fn missing_clock_domain(token: &TokenRange) -> Self {
         AnalyzerError::MissingClockDomain {
             input: source(token),
@@ -3731,12 +3759,24 @@ impl fmt::Display for InvalidForStepKind {
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum InvalidTestKind {
     NoTopModuleCocotb,
+    MatrixTopModule,
+    MatrixUndeclaredParameter,
+    MatrixValueOverflow,
+    MatrixWithoutTest,
+    MatrixWithGolden,
+    TemporalWithoutClock,
 }
 
 impl fmt::Display for InvalidTestKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             InvalidTestKind::NoTopModuleCocotb => "`cocotb` test requires top module name at the second argument of `#[test]` attribute".fmt(f),
+            InvalidTestKind::MatrixTopModule => "`#[matrix]` requires the test module itself to be the top module".fmt(f),
+            InvalidTestKind::MatrixUndeclaredParameter => "`#[matrix]` parameter is not declared by the test module".fmt(f),
+            InvalidTestKind::MatrixValueOverflow => "`#[matrix]` value does not fit the type of its parameter".fmt(f),
+            InvalidTestKind::MatrixWithoutTest => "`#[matrix]` requires `#[test]` on the same module".fmt(f),
+            InvalidTestKind::MatrixWithGolden => "`#[golden]` can't be used with `#[matrix]`, whose cases would share the golden files".fmt(f),
+            InvalidTestKind::TemporalWithoutClock => "temporal assertions in a testbench require exactly one `$tb::clock_gen`".fmt(f),
         }
     }
 }
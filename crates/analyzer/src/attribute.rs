@@ -1,11 +1,15 @@
+use crate::value::Value;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 use veryl_parser::resource_table::{self, StrId};
 use veryl_parser::veryl_grammar_trait::{
-    AttributeOpt, DescriptionGroup, DescriptionGroupGroup, DescriptionItem,
+    AttributeOpt, DescriptionGroup, DescriptionGroupGroup, DescriptionItem, ModuleGroup,
+    StatementBlockGroup,
 };
 use veryl_parser::veryl_token::Token;
 
@@ -25,6 +29,11 @@ pub enum Attribute {
     Format(Vec<FormatItem>),
     Expand(Vec<ExpandItem>),
     Ignore,
+    Assert,
+    Timeout(TimeoutItem),
+    Matrix(Vec<MatrixItem>),
+    Fork(JoinItem),
+    Golden(GoldenItem),
 }
 
 impl Attribute {
@@ -98,6 +107,14 @@ impl fmt::Display for Attribute {
                 format!("expand({arg})")
             }
             Attribute::Ignore => String::from("ignore"),
+            Attribute::Assert => String::from("assert"),
+            Attribute::Timeout(x) => format!("timeout({x})"),
+            Attribute::Matrix(x) => {
+                let args: Vec<_> = x.iter().map(|x| format!("\"{x}\"")).collect();
+                format!("matrix({})", args.join(", "))
+            }
+            Attribute::Fork(x) => format!("fork({x})"),
+            Attribute::Golden(x) => format!("golden({x})"),
         };
         text.fmt(f)
     }
@@ -216,6 +233,13 @@ struct Pattern {
     pub expand: StrId,
     pub modport: StrId,
     pub ignore: StrId,
+    pub assert: StrId,
+    pub timeout: StrId,
+    pub matrix: StrId,
+    pub fork: StrId,
+    pub join: StrId,
+    pub join_any: StrId,
+    pub golden: StrId,
 }
 
 impl Pattern {
@@ -251,6 +275,13 @@ impl Pattern {
             expand: resource_table::insert_str("expand"),
             modport: resource_table::insert_str("modport"),
             ignore: resource_table::insert_str("ignore"),
+            assert: resource_table::insert_str("assert"),
+            timeout: resource_table::insert_str("timeout"),
+            matrix: resource_table::insert_str("matrix"),
+            fork: resource_table::insert_str("fork"),
+            join: resource_table::insert_str("join"),
+            join_any: resource_table::insert_str("join_any"),
+            golden: resource_table::insert_str("golden"),
         }
     }
 }
@@ -268,6 +299,29 @@ pub fn has_test_attribute(group: &DescriptionGroup) -> bool {
     })
 }
 
+/// Syntactic check for `#[assert]`-marked module items, which are emitted
+/// as a separate bind-ready checker module instead of inline.
+pub fn has_assert_attribute(group: &ModuleGroup) -> bool {
+    PAT.with_borrow(|pat| {
+        group
+            .module_group_list
+            .iter()
+            .any(|x| x.attribute.identifier.identifier_token.token.text == pat.assert)
+    })
+}
+
+/// Syntactic lookup of a `#[fork]` on a statement block group, for the
+/// IR conversion which walks groups rather than attribute ranges (a fork's
+/// range also covers its nested groups).
+pub fn fork_attribute(group: &StatementBlockGroup) -> Option<JoinItem> {
+    group.statement_block_group_list.iter().find_map(|x| {
+        match Attribute::try_from(x.attribute.as_ref()) {
+            Ok(Attribute::Fork(x)) => Some(x),
+            _ => None,
+        }
+    })
+}
+
 /// Flattens a description group like `From<&DescriptionGroup> for
 /// Vec<&DescriptionItem>`, but drops `#[test]`-marked subgroups.
 pub fn description_items_excluding_tests(group: &DescriptionGroup) -> Vec<&DescriptionItem> {
@@ -525,11 +579,235 @@ impl TryFrom<&veryl_parser::veryl_grammar_trait::Attribute> for Attribute {
                     Ok(Attribute::Ignore)
                 }
             }
+            x if x == pat.assert => {
+                if value.attribute_opt.is_some() {
+                    Err(AttributeError::MismatchArgs("no argument".to_string()))
+                } else {
+                    Ok(Attribute::Assert)
+                }
+            }
+            x if x == pat.matrix => {
+                let err = AttributeError::MismatchArgs(
+                    "one \"NAME = value, ...\" per parameter".to_string(),
+                );
+
+                let mut items: Vec<MatrixItem> = Vec::new();
+                for i in 0..arg_count(&value.attribute_opt) {
+                    let arg = get_arg_string(&value.attribute_opt, i).ok_or(err.clone())?;
+                    let item = MatrixItem::parse(&arg.text.to_string()).ok_or(err.clone())?;
+                    if items.iter().any(|x| x.name == item.name) {
+                        return Err(err);
+                    }
+                    items.push(item);
+                }
+
+                if items.is_empty() {
+                    Err(err)
+                } else {
+                    Ok(Attribute::Matrix(items))
+                }
+            }
+            x if x == pat.fork => {
+                reject_extra_args(&value.attribute_opt, 1)?;
+                let err =
+                    AttributeError::MismatchArgs(format!("join type: ({})", JoinItem::available()));
+
+                if arg_count(&value.attribute_opt) == 0 {
+                    return Ok(Attribute::Fork(JoinItem::All));
+                }
+                match get_arg_ident(&value.attribute_opt, 0).map(|x| x.text) {
+                    Some(x) if x == pat.join => Ok(Attribute::Fork(JoinItem::All)),
+                    Some(x) if x == pat.join_any => Ok(Attribute::Fork(JoinItem::Any)),
+                    _ => Err(err),
+                }
+            }
+            x if x == pat.timeout => {
+                reject_extra_args(&value.attribute_opt, 2)?;
+                let err = AttributeError::MismatchArgs(
+                    "cycle count (\"10000\") and/or duration (\"30s\", \"500ms\")".to_string(),
+                );
+
+                let mut item = TimeoutItem::default();
+                for i in 0..arg_count(&value.attribute_opt) {
+                    let arg = get_arg_string(&value.attribute_opt, i).ok_or(err.clone())?;
+                    if !item.parse(&arg.text.to_string()) {
+                        return Err(err);
+                    }
+                }
+
+                if item == TimeoutItem::default() {
+                    Err(err)
+                } else {
+                    Ok(Attribute::Timeout(item))
+                }
+            }
+            x if x == pat.golden => {
+                reject_extra_args(&value.attribute_opt, 2)?;
+                let err = AttributeError::MismatchArgs(
+                    "golden file path and optional output file path".to_string(),
+                );
+
+                let golden = get_arg_string(&value.attribute_opt, 0).ok_or(err.clone())?;
+                let output = if arg_count(&value.attribute_opt) == 2 {
+                    Some(get_arg_string(&value.attribute_opt, 1).ok_or(err)?)
+                } else {
+                    None
+                };
+                let path =
+                    |x: Token| resource_table::insert_str(x.text.to_string().trim_matches('"'));
+                Ok(Attribute::Golden(GoldenItem {
+                    golden: path(golden),
+                    output: output.map(path),
+                }))
+            }
             _ => Err(AttributeError::UnknownAttribute),
         })
     }
 }
 
+/// One parameter of a test matrix and the values it takes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixItem {
+    pub name: StrId,
+    pub values: Vec<i128>,
+}
+
+impl MatrixItem {
+    /// Parses `NAME = value, ...`; each value is a Veryl integral literal
+    /// (`8`, `32'hff`, `'b101`), optionally negated (`-1`).
+    fn parse(text: &str) -> Option<Self> {
+        let (name, values) = text.trim_matches('"').split_once('=')?;
+        let name = name.trim();
+        let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            return None;
+        }
+
+        let values = values
+            .split(',')
+            .map(|x| parse_matrix_value(x.trim()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            name: resource_table::insert_str(name),
+            values,
+        })
+    }
+}
+
+/// A base-less or based literal without X/Z digits, read by the same number
+/// parser as expressions; all-bit literals (`'1`) have no width to fill here.
+fn parse_matrix_value(text: &str) -> Option<i128> {
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(x) => (true, x.trim_start()),
+        None => (false, text),
+    };
+    let digits = |x: &str, radix: u32| {
+        x.starts_with(|c: char| c.is_digit(radix))
+            && x.chars().all(|c| c == '_' || c.is_digit(radix))
+    };
+    let valid = match text.split_once('\'') {
+        None => digits(text, 10),
+        Some((width, rest)) => {
+            let rest = rest.strip_prefix('s').unwrap_or(rest);
+            let radix = match rest.chars().next()? {
+                'b' => 2,
+                'o' => 8,
+                'd' => 10,
+                'h' => 16,
+                _ => return None,
+            };
+            (width.is_empty() || digits(width, 10)) && digits(&rest[1..], radix)
+        }
+    };
+    if !valid {
+        return None;
+    }
+    let value = i128::from(Value::from_str(text).ok()?.to_u64()?);
+    Some(if negative { -value } else { value })
+}
+
+impl fmt::Display for MatrixItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values: Vec<_> = self.values.iter().map(|x| x.to_string()).collect();
+        write!(f, "{} = {}", self.name, values.join(", "))
+    }
+}
+
+/// A golden file a native test is checked against: the captured
+/// `$display` / `$write` output, or the file `output` the test writes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenItem {
+    /// Relative to the directory of the test source.
+    pub golden: StrId,
+    /// As opened by the test, e.g. with `$tb::file`.
+    pub output: Option<StrId>,
+}
+
+impl fmt::Display for GoldenItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.golden)?;
+        if let Some(x) = self.output {
+            write!(f, ", \"{x}\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// Limits of a native test run, overriding `[test] timeout_cycles` and
+/// `timeout_secs`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeoutItem {
+    pub cycles: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl TimeoutItem {
+    /// Fills each limit left unset from `other`.
+    pub fn or(self, other: TimeoutItem) -> Self {
+        Self {
+            cycles: self.cycles.or(other.cycles),
+            time: self.time.or(other.time),
+        }
+    }
+
+    /// Takes one quoted argument: a cycle count, or seconds / milliseconds
+    /// with an `s` / `ms` suffix. Each limit may be given once.
+    fn parse(&mut self, text: &str) -> bool {
+        let text = text.trim_matches('"').trim();
+        if let Ok(x) = text.parse::<u64>() {
+            return self.cycles.replace(x).is_none();
+        }
+        let time = if let Some(x) = text.strip_suffix("ms") {
+            x.trim().parse::<u64>().ok().map(Duration::from_millis)
+        } else if let Some(x) = text.strip_suffix('s') {
+            x.trim()
+                .parse::<f64>()
+                .ok()
+                .and_then(|x| Duration::try_from_secs_f64(x).ok())
+        } else {
+            None
+        };
+        match time {
+            Some(x) => self.time.replace(x).is_none(),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for TimeoutItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut args = Vec::new();
+        if let Some(x) = self.cycles {
+            args.push(format!("\"{x}\""));
+        }
+        if let Some(x) = self.time {
+            args.push(format!("\"{}s\"", x.as_secs_f64()));
+        }
+        args.join(", ").fmt(f)
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum AllowItem {
     MissingPort,
@@ -595,6 +873,38 @@ impl fmt::Display for EnumEncodingItem {
     }
 }
 
+/// When a `#[fork]` block resumes: after every thread (`join`) or the first
+/// one (`join_any`) has finished.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum JoinItem {
+    #[default]
+    All,
+    Any,
+}
+
+impl JoinItem {
+    pub fn available() -> String {
+        let mut ret = String::new();
+        for (i, x) in Self::iter().enumerate() {
+            if i != 0 {
+                ret.push('|');
+            }
+            ret.push_str(&format!("{x}"));
+        }
+        ret
+    }
+}
+
+impl fmt::Display for JoinItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            JoinItem::All => "join",
+            JoinItem::Any => "join_any",
+        };
+        text.fmt(f)
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum CondTypeItem {
     Unique,
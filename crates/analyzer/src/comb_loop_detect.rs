@@ -893,8 +893,10 @@ fn walk_stmt(stmt: &Statement, state: &mut DominanceState, ctx: &mut Context) {
         Statement::Case(c) => walk_case(c, state, ctx),
         Statement::For(f) => walk_for(f, state, ctx),
         Statement::FunctionCall(c) => walk_function_call(c.as_ref(), state, ctx),
-        // IfReset is always_ff-only; the rest have no LHS to track.
+        // IfReset is always_ff-only and Fork initial-only; the rest have no
+        // LHS to track.
         Statement::IfReset(_)
+        | Statement::Fork(_)
         | Statement::SystemFunctionCall(_)
         | Statement::TbMethodCall(_)
         | Statement::Break
@@ -6,6 +6,7 @@ pub mod expression;
 pub mod instance;
 pub mod ir;
 pub mod statement;
+pub mod temporal;
 pub mod utils;
 pub mod var;
 pub use context::Context;
@@ -412,6 +412,35 @@ pub fn check_generic_refereence(context: &mut Context, path: &GenericSymbolPath)
                             ));
                         }
                     }
+
+                    // `$tb::queue::<T>` / `$tb::scoreboard::<T>` store values
+                    // of any fixed-width type, but not arrays.
+                    if let SymbolKind::TbComponent(tb) = &symbol.found.kind
+                        && matches!(
+                            tb.kind,
+                            TbComponentKind::Queue | TbComponentKind::Scoreboard
+                        )
+                        && matches!(&expr.value, ir::ValueVariant::Type(_))
+                    {
+                        let ok = eval_type(context, &arg, TypePosition::Variable)
+                            .ok()
+                            .map(|t| t.array.as_slice().is_empty() && t.total_width().is_some())
+                            .unwrap_or(false);
+                        if !ok {
+                            let actual = match &expr.value {
+                                ir::ValueVariant::Type(t) => t.to_string(),
+                                _ => expr.r#type.to_string(),
+                            };
+                            context.insert_error(AnalyzerError::mismatch_type(
+                                MismatchTypeKind::GenericArgument {
+                                    name: expr.token.end.to_string(),
+                                    expected: "fixed-width type".to_string(),
+                                    actual,
+                                },
+                                &expr.token,
+                            ));
+                        }
+                    }
                 }
             }
         }
@@ -1,10 +1,18 @@
 use crate::analyzer_error::AnalyzerError;
 use crate::conv::Context;
-use crate::symbol::{SymbolId, SymbolKind};
+use crate::symbol::{Symbol, SymbolId, SymbolKind};
 use crate::symbol_table;
+use veryl_parser::resource_table;
 use veryl_parser::veryl_grammar_trait::*;
 use veryl_parser::veryl_token::Token;
 
+/// Methods of `$tb` components live in `$tb::<component>`.
+fn is_tb_method(symbol: &Symbol) -> bool {
+    let paths = &symbol.namespace.paths;
+    paths.len() >= 2
+        && resource_table::get_str_value(paths[paths.len() - 2]).as_deref() == Some("$tb")
+}
+
 fn check_path(
     context: &mut Context,
     full_path: &mut Vec<SymbolId>,
@@ -26,10 +34,12 @@ fn check_path(
     let expect_dot_separator = if let SymbolKind::Function(x) = &this_symbol.kind
         && !x.is_proto
     {
-        matches!(
-            preceed_symbol.kind,
-            SymbolKind::Instance(_) // member function of interface
-        )
+        match preceed_symbol.kind {
+            SymbolKind::Instance(_) => true, // member function of interface
+            // method of a testbench component declared as `var`
+            SymbolKind::Variable(_) => is_tb_method(&this_symbol),
+            _ => false,
+        }
     } else {
         matches!(
             this_symbol.kind,
@@ -110,6 +110,11 @@ pub struct Context {
     /// `check_compatibility` to pick the clock/reset diagnostic.
     pub in_inst_port: bool,
     pub current_clock: Option<Comptime>,
+    /// Hidden registers of temporal assertions and sampled-value functions;
+    /// `Some` only while an `always_ff` or a testbench `initial` converts.
+    pub temporal: Option<super::temporal::TemporalState>,
+    /// Names those hidden registers uniquely within a module.
+    pub temporal_count: usize,
     /// Comptimes of the enclosing if/case/switch statement conditions: a
     /// condition gates the writes below it like a mux select, so its clock
     /// domain is checked against each assignment destination.
@@ -118,6 +123,9 @@ pub struct Context {
     pub tb_reset_cycles: HashMap<StrId, Expression>,
     pub tb_clock_period: HashMap<StrId, Expression>,
     pub tb_reset_clock: HashMap<StrId, StrId>,
+    /// `$tb::clock_gen` instances of the current module, which clock the
+    /// temporal assertions of its `initial`.
+    pub tb_clocks: Vec<StrId>,
     hierarchy: Vec<StrId>,
     hierarchical_variables: Vec<Vec<VarPath>>,
     hierarchical_functions: Vec<Vec<FuncPath>>,
@@ -430,7 +438,10 @@ impl Context {
         }
 
         // import non-variable VarPath
-        for (mut path, (id, mut comptime)) in component.var_paths {
+        // Sorted so fresh ids don't depend on the `StrId`-keyed hash order.
+        let mut var_paths: Vec<_> = component.var_paths.into_iter().collect();
+        var_paths.sort_by_cached_key(|(path, (id, _))| (*id, path.to_string()));
+        for (mut path, (id, mut comptime)) in var_paths {
             if !inserted.contains(&path) {
                 path.add_prelude(&[base]);
                 comptime.r#type.prepend_array(array);
@@ -14,6 +14,7 @@ use crate::conv::checker::import::check_import;
 use crate::conv::checker::inst::check_inst;
 use crate::conv::checker::modport::{check_modport, check_modport_default, check_modport_in_port};
 use crate::conv::checker::port::{check_direction, check_port_default_value, check_port_direction};
+use crate::conv::temporal::TemporalState;
 use crate::conv::utils::{
     TypePosition, assign_rhs_context_type, check_assign_clock_domain, eval_array_range_assign,
     eval_assign_statement, eval_clock, eval_const_assign, eval_expr, eval_factor_symbol,
@@ -145,7 +146,7 @@ impl Conv<&GenerateItem> for ir::DeclarationBlock {
             GenerateItem::InitialDeclaration(x) => {
                 let token: TokenRange = x.initial_declaration.as_ref().into();
                 match Conv::conv(context, x.initial_declaration.as_ref()) {
-                    Ok(decl) => Ok(ir::DeclarationBlock::new(decl)),
+                    Ok(block) => Ok(block),
                     Err(_) if context.in_generic => Ok(ir::DeclarationBlock::default()),
                     Err(_) => Ok(ir::DeclarationBlock::new(ir::Declaration::Unsupported(
                         token,
@@ -661,13 +662,17 @@ impl Conv<&AlwaysFfDeclaration> for ir::Declaration {
         context.current_clock = Some(clock.comptime.clone());
 
         context.push_affiliation(Affiliation::AlwaysFf);
+        context.temporal = Some(TemporalState::default());
 
         let statements: IrResult<ir::StatementBlock> =
             context.block(|c| Conv::conv(c, value.statement_block.as_ref()));
 
+        let temporal = context.temporal.take().unwrap();
         context.pop_affiliation();
 
-        Ok(ir::Declaration::new_ff(clock, reset, statements?.0))
+        let mut statements = statements?.0;
+        temporal.finish(context, &mut statements);
+        Ok(ir::Declaration::new_ff(clock, reset, statements))
     }
 }
 
@@ -713,9 +718,19 @@ impl Conv<&VarDeclaration> for ir::Declaration {
                 && let SymbolKind::TbComponent(c) = &ty_symbol.found.kind
             {
                 match &c.kind {
-                    TbComponentKind::File | TbComponentKind::Random => {
-                        // `file` handle and `random` generator are never assigned
-                        // (their state lives in the simulator), so suppress the
+                    TbComponentKind::File
+                    | TbComponentKind::Random
+                    | TbComponentKind::Wave
+                    | TbComponentKind::Checkpoint
+                    | TbComponentKind::Force
+                    | TbComponentKind::Coverage
+                    | TbComponentKind::Thread
+                    | TbComponentKind::Queue
+                    | TbComponentKind::Scoreboard => {
+                        // `file` handle, `random` generator, `wave` control,
+                        // `checkpoint`, `force`, `coverage`, `thread`, `queue`
+                        // and `scoreboard` handles are never assigned (their
+                        // state lives in the simulator), so suppress the
                         // unassigned lint.
                         attribute_table::insert(
                             variable_token,
@@ -1354,17 +1369,28 @@ impl Conv<&EnumDeclaration> for () {
     }
 }
 
-impl Conv<&InitialDeclaration> for ir::Declaration {
+impl Conv<&InitialDeclaration> for ir::DeclarationBlock {
     fn conv(context: &mut Context, value: &InitialDeclaration) -> IrResult<Self> {
         context.in_tb_block = true;
         context.tb_hoist = Some(Vec::new());
+        if context.in_test_module {
+            context.temporal = Some(TemporalState::testbench());
+        }
         let statements: IrResult<ir::StatementBlock> =
             Conv::conv(context, value.statement_block.as_ref());
+        let temporal = context.temporal.take();
         context.tb_hoist = None;
         context.in_tb_block = false;
-        Ok(ir::Declaration::Initial(ir::InitialDeclaration {
+
+        let mut ret = ir::DeclarationBlock::new(ir::Declaration::Initial(ir::InitialDeclaration {
             statements: statements?.0,
-        }))
+        }));
+        // The hidden registers of temporal assertions are clocked apart from
+        // the testbench statements.
+        if let Some(x) = temporal.and_then(|x| x.into_declaration(context)) {
+            ret.0.push(x);
+        }
+        Ok(ret)
     }
 }
 
@@ -1411,8 +1437,20 @@ impl Conv<&InstDeclaration> for ir::Declaration {
                 return Ok(ir::Declaration::Null);
             }
 
-            // `$tb::file` is declared with `var`, not `inst`.
-            if matches!(tb_prop.kind, TbComponentKind::File) {
+            // `$tb::file`, `$tb::wave`, `$tb::checkpoint`, `$tb::force`,
+            // `$tb::coverage`, `$tb::thread`, `$tb::queue` and
+            // `$tb::scoreboard` are declared with `var`, not `inst`.
+            if matches!(
+                tb_prop.kind,
+                TbComponentKind::File
+                    | TbComponentKind::Wave
+                    | TbComponentKind::Checkpoint
+                    | TbComponentKind::Force
+                    | TbComponentKind::Coverage
+                    | TbComponentKind::Thread
+                    | TbComponentKind::Queue
+                    | TbComponentKind::Scoreboard
+            ) {
                 let token: TokenRange = value
                     .component_instantiation
                     .scoped_identifier
@@ -1436,10 +1474,23 @@ impl Conv<&InstDeclaration> for ir::Declaration {
                 .text;
             let var_path = ir::VarPath::new(inst_name);
             let type_kind = match tb_prop.kind {
-                TbComponentKind::ClockGen => ir::TypeKind::Clock,
+                TbComponentKind::ClockGen => {
+                    context.tb_clocks.push(inst_name);
+                    ir::TypeKind::Clock
+                }
                 TbComponentKind::ResetGen => ir::TypeKind::Reset,
-                // `file`/`random` are `var`-form; external handled above.
-                TbComponentKind::File | TbComponentKind::Random | TbComponentKind::External(_) => {
+                // `file`/`random`/`wave`/`checkpoint`/`force`/`coverage` are
+                // `var`-form; external handled above.
+                TbComponentKind::File
+                | TbComponentKind::Random
+                | TbComponentKind::Wave
+                | TbComponentKind::Checkpoint
+                | TbComponentKind::Force
+                | TbComponentKind::Coverage
+                | TbComponentKind::Thread
+                | TbComponentKind::Queue
+                | TbComponentKind::Scoreboard
+                | TbComponentKind::External(_) => {
                     unreachable!()
                 }
             };
@@ -1511,9 +1562,18 @@ impl Conv<&InstDeclaration> for ir::Declaration {
                             let type_name = match tb_prop.kind {
                                 TbComponentKind::ClockGen => "$tb::clock_gen",
                                 TbComponentKind::ResetGen => "$tb::reset_gen",
-                                // `file`/`random` are `var`-form; external handled above.
+                                // `file`/`random`/`wave`/`checkpoint`/`force`/
+                                // `coverage` are `var`-form; external handled
+                                // above.
                                 TbComponentKind::File
                                 | TbComponentKind::Random
+                                | TbComponentKind::Wave
+                                | TbComponentKind::Checkpoint
+                                | TbComponentKind::Force
+                                | TbComponentKind::Coverage
+                                | TbComponentKind::Thread
+                                | TbComponentKind::Queue
+                                | TbComponentKind::Scoreboard
                                 | TbComponentKind::External(_) => {
                                     unreachable!()
                                 }
@@ -1,12 +1,17 @@
+use crate::AnalyzerError;
+use crate::analyzer_error::InvalidTestKind;
 use crate::conv::checker::alias::{AliasType, check_alias_target};
 use crate::conv::checker::clock_domain::check_clock_domain;
 use crate::conv::checker::generic::check_generic_bound;
 use crate::conv::checker::proto::check_proto;
-use crate::conv::utils::{check_module_with_unevaluable_generic_parameters, get_component};
+use crate::conv::utils::{
+    TypePosition, check_module_with_unevaluable_generic_parameters, get_component,
+};
 use crate::conv::{Affiliation, Context, Conv};
-use crate::ir::{self, IrResult, VarPath};
+use crate::ir::{self, IrResult, ValueVariant, VarPath};
 use crate::symbol::SymbolKind;
 use crate::symbol_table;
+use crate::value::Value;
 use crate::{HashMap, ir_error};
 use veryl_parser::token_range::TokenRange;
 use veryl_parser::veryl_grammar_trait::*;
@@ -26,6 +31,103 @@ fn top_level_memo_sig(context: &Context, identifier: &Identifier) -> Option<ir::
     }
 }
 
+/// Elaborates each `#[matrix]` case of a test module with its parameter set,
+/// named after the case so the simulator builds it as that test's top. A case
+/// which fails is recorded on the test so `get_tests` leaves it out, while the
+/// remaining cases are still elaborated.
+fn conv_test_matrix(
+    context: &mut Context,
+    decl: &ModuleDeclaration,
+    sig: &ir::Signature,
+    components: &mut Vec<ir::Component>,
+) {
+    let Ok(symbol) = symbol_table::resolve(decl.identifier.as_ref()) else {
+        return;
+    };
+    let SymbolKind::Module(module) = &symbol.found.kind else {
+        return;
+    };
+    let Some(test) = &module.test else {
+        return;
+    };
+
+    let token: TokenRange = decl.identifier.as_ref().into();
+    let mut rejected = vec![];
+    'case: for (case, params) in test.cases(symbol.found.token.text) {
+        let mut sig = sig.clone();
+        let mut overrides = HashMap::default();
+        for (name, value) in params {
+            // Undeclared names are reported by `create_symbol_table`.
+            let Some(param) = module.parameters.iter().find(|x| x.name == name) else {
+                rejected.push(case);
+                continue 'case;
+            };
+            let Ok(r#type) = param
+                .property()
+                .r#type
+                .to_ir_type(context, TypePosition::Variable)
+            else {
+                rejected.push(case);
+                continue 'case;
+            };
+            let width = r#type.total_width().unwrap_or(64);
+            let bits = width.clamp(1, 64);
+            let fits = if value < 0 {
+                value >= -(1i128 << (bits - 1))
+            } else {
+                value >> bits == 0
+            };
+            if !fits {
+                context.insert_error(AnalyzerError::invalid_test(
+                    InvalidTestKind::MatrixValueOverflow,
+                    &token,
+                ));
+                rejected.push(case);
+                continue 'case;
+            }
+
+            // Negative values are stored as their two's complement in `width` bits.
+            let payload = value as u64;
+            let payload = if bits < 64 {
+                payload & ((1 << bits) - 1)
+            } else {
+                payload
+            };
+            let value = Value::new(payload, width, r#type.signed);
+            sig.add_parameter(name, ValueVariant::Numeric(value.clone()));
+            let expr = (
+                ir::Comptime::create_value(value.clone(), token),
+                ir::Expression::create_value(value, token),
+            );
+            overrides.insert(VarPath::new(name), expr);
+        }
+
+        context.push_override(overrides);
+        let component = context.block(|c| get_component(c, &sig, token));
+        context.pop_override();
+
+        if let Ok(component) = component
+            && let ir::Component::Module(x) = component.as_ref()
+        {
+            let mut x = x.clone();
+            x.name = case;
+            components.push(ir::Component::Module(x));
+        } else {
+            rejected.push(case);
+        }
+    }
+
+    if !rejected.is_empty() {
+        let mut symbol = (*symbol.found).clone();
+        if let SymbolKind::Module(x) = &mut symbol.kind
+            && let Some(test) = &mut x.test
+        {
+            test.rejected_cases = rejected;
+        }
+        symbol_table::update(symbol);
+    }
+}
+
 impl Conv<&Veryl> for ir::Ir {
     fn conv(context: &mut Context, value: &Veryl) -> IrResult<Self> {
         let mut components = vec![];
@@ -64,6 +166,7 @@ impl Conv<&Veryl> for ir::Ir {
                                             component.suppress_unassigned = true;
                                         }
                                         components.push(ir::Component::Module(component));
+                                        conv_test_matrix(context, decl, &sig, &mut components);
                                     }
                                 } else {
                                     let ret: IrResult<ir::Module> = Conv::conv(context, decl);
@@ -1,4 +1,5 @@
 use crate::analyzer_error::{ComponentInterfaceMismatchKind, MismatchTypeKind};
+use crate::attribute;
 use crate::conv::utils::{
     TbMethodCallPosition, TypePosition, argument_list, assign_rhs_context_type, build_for_range,
     build_for_statement, case_patterns, check_assign_clock_domain, eval_array_range_assign,
@@ -6,7 +7,7 @@ use crate::conv::utils::{
     function_call, get_return_str, hoist_component_method_call, single_function_call_factor,
     switch_condition, tb_method_call, try_infer_decl_type, try_infer_var_assign,
 };
-use crate::conv::{Context, Conv};
+use crate::conv::{Context, Conv, temporal};
 use crate::ir::{
     self, Comptime, IrResult, Shape, TypeKind, VarIndex, VarKind, VarPath, VarPathSelect, VarSelect,
 };
@@ -19,13 +20,53 @@ use veryl_parser::veryl_grammar_trait::*;
 
 impl Conv<&StatementBlock> for ir::StatementBlock {
     fn conv(context: &mut Context, value: &StatementBlock) -> IrResult<Self> {
-        let statements: Vec<_> = value.into();
         let mut ret = vec![];
-        for s in statements {
-            let x: IrResult<ir::StatementBlock> = Conv::conv(context, s);
+        for x in &value.statement_block_list {
+            conv_statement_block_group(context, &x.statement_block_group, &mut ret);
+        }
+        Ok(ir::StatementBlock(ret))
+    }
+}
+
+/// Flattens a statement block group into `ret`. A `#[fork]` block becomes a
+/// `Fork` statement with one thread per item.
+fn conv_statement_block_group(
+    context: &mut Context,
+    group: &StatementBlockGroup,
+    ret: &mut Vec<ir::Statement>,
+) {
+    match &*group.statement_block_group_group {
+        StatementBlockGroupGroup::BlockLBraceStatementBlockGroupGroupListRBrace(x) => {
+            let define_context: DefineContext = (&x.block.block_token).into();
+            let fork = attribute::fork_attribute(group)
+                .filter(|_| define_context.is_active(&context.config.defines));
+            if let Some(join) = fork {
+                let threads = x
+                    .statement_block_group_group_list
+                    .iter()
+                    .map(|x| {
+                        let mut thread = vec![];
+                        conv_statement_block_group(context, &x.statement_block_group, &mut thread);
+                        thread
+                    })
+                    .collect();
+                ret.push(ir::Statement::Fork(ir::ForkStatement {
+                    join,
+                    threads,
+                    token: group.into(),
+                }));
+            } else {
+                for x in &x.statement_block_group_group_list {
+                    conv_statement_block_group(context, &x.statement_block_group, ret);
+                }
+            }
+        }
+        StatementBlockGroupGroup::StatementBlockItem(x) => {
+            let x: IrResult<ir::StatementBlock> =
+                Conv::conv(context, x.statement_block_item.as_ref());
             match x {
                 Ok(x) => {
-                    ret.append(&mut x.0.into_iter().filter(|x| !x.is_null()).collect());
+                    ret.extend(x.0.into_iter().filter(|x| !x.is_null()));
                 }
                 Err(e) => {
                     if !context.in_generic {
@@ -34,7 +75,6 @@ impl Conv<&StatementBlock> for ir::StatementBlock {
                 }
             }
         }
-        Ok(ir::StatementBlock(ret))
     }
 }
 
@@ -471,6 +511,10 @@ impl Conv<&IdentifierStatement> for ir::StatementBlock {
                     SymbolKind::SystemFunction(_) => {
                         let name = symbol.found.token.text;
                         let args = args.to_system_function_args(context, &symbol.found);
+                        if let Some(name) = temporal::assertion_name(name) {
+                            let ret = temporal::assertion(context, name, args, token)?;
+                            return Ok(ir::StatementBlock(ret));
+                        }
                         let ret = ir::SystemFunctionCall::new(context, name, args, token)?;
                         Ok(ir::StatementBlock(vec![ir::Statement::SystemFunctionCall(
                             Box::new(ret),
@@ -0,0 +1,490 @@
+//! Temporal assertions (`$assert_implies`, `$assert_eventually`) and
+//! sampled-value functions (`$past`, `$rose`, `$fell`, `$stable`) in
+//! `always_ff`.
+//!
+//! Both lower to hidden flip-flops of the enclosing `always_ff` plus plain
+//! `$assert` checks, so every simulator backend evaluates them as ordinary
+//! statements. Sampled values are taken at every clock edge, in reset or not,
+//! like their SVA counterparts; the open attempts of an assertion are cleared
+//! while the `always_ff` is in reset, which mirrors the `disable iff` of the
+//! SVA the emitter produces.
+//!
+//! In the `initial` of a native testbench, the hidden flip-flops go to an
+//! extra `always_ff` clocked by the testbench's `$tb::clock_gen`, and an
+//! assertion only starts attempts once its statement has run.
+
+use crate::AnalyzerError;
+use crate::analyzer_error::{InvalidTestKind, UnevaluableValueKind};
+use crate::conv::Context;
+use crate::ir::{
+    self, AssertKind, AssignDestination, Comptime, Expression, Factor, FfClock, IrResult, Op,
+    Shape, SystemFunctionCall, SystemFunctionInput, SystemFunctionKind, Type, TypeKind,
+    ValueVariant, VarId, VarIndex, VarKind, VarPath, VarPathSelect, VarSelect, Variable,
+};
+use crate::ir_error;
+use crate::symbol::{Affiliation, ClockDomain};
+use crate::value::{Value, byte_value_to_string, string_to_byte_value};
+use veryl_parser::resource_table::{self, StrId};
+use veryl_parser::token_range::TokenRange;
+
+/// Hidden state created while converting one `always_ff` or testbench
+/// `initial`.
+#[derive(Default)]
+pub struct TemporalState {
+    /// Updates of the hidden registers and the assertion checks, run every
+    /// clock outside reset.
+    updates: Vec<ir::Statement>,
+    /// Clears of the hidden registers, run in reset.
+    resets: Vec<ir::Statement>,
+    /// Updates of the sampled-value registers, run on every clock edge.
+    samples: Vec<ir::Statement>,
+    /// Free-running edge counter, for the start cycle of failing attempts.
+    cycle: Option<Hidden>,
+    /// Converting the `initial` of a native testbench.
+    testbench: bool,
+}
+
+impl TemporalState {
+    pub fn testbench() -> Self {
+        Self {
+            testbench: true,
+            ..Default::default()
+        }
+    }
+
+    /// Appends the hidden registers to the converted `always_ff` body.
+    ///
+    /// With a reset, the clears and updates join the branches of the leading
+    /// `if_reset`, which simulators split into the reset and clock paths.
+    pub fn finish(self, context: &mut Context, statements: &mut Vec<ir::Statement>) {
+        if let Some(ir::Statement::IfReset(x)) = statements.first_mut() {
+            x.true_side.extend(self.resets);
+            x.false_side.extend(self.updates);
+        } else {
+            statements.extend(self.updates);
+        }
+        // Runs on every clock edge, in reset or not.
+        statements.extend(self.samples);
+        if let Some(cycle) = &self.cycle {
+            let token = cycle.comptime.token;
+            let next = binary(cycle.expr(), Op::Add, literal(1, 32, token));
+            statements.push(cycle.assign(context, next));
+        }
+    }
+
+    /// The `always_ff` holding the hidden registers of a testbench `initial`,
+    /// if it created any.
+    pub fn into_declaration(self, context: &mut Context) -> Option<ir::Declaration> {
+        if self.updates.is_empty() && self.samples.is_empty() {
+            return None;
+        }
+        // Checked by `testbench_clock` when the first register was created.
+        let [name] = context.tb_clocks[..] else {
+            return None;
+        };
+        let (id, comptime) = context.find_path(&VarPath::new(name))?;
+        let clock = FfClock {
+            id,
+            index: VarIndex::default(),
+            select: VarSelect::default(),
+            comptime,
+        };
+        let mut statements = vec![];
+        self.finish(context, &mut statements);
+        Some(ir::Declaration::new_ff(clock, None, statements))
+    }
+}
+
+/// A testbench has no clock of its own, so its hidden registers follow its
+/// only `$tb::clock_gen`.
+fn testbench_clock(context: &mut Context, token: TokenRange) -> IrResult<()> {
+    let testbench = context.temporal.as_ref().is_some_and(|x| x.testbench);
+    if testbench && context.tb_clocks.len() != 1 {
+        context.insert_error(AnalyzerError::invalid_test(
+            InvalidTestKind::TemporalWithoutClock,
+            &token,
+        ));
+        return Err(ir_error!(token));
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+struct Hidden {
+    id: VarId,
+    path: VarPath,
+    comptime: Comptime,
+    width: usize,
+}
+
+impl Hidden {
+    fn new(context: &mut Context, kind: &str, width: usize, token: TokenRange) -> Self {
+        let name = format!("__sva_{kind}_{}", context.temporal_count);
+        context.temporal_count += 1;
+        let path = VarPath::new(resource_table::insert_str(&name));
+        let mut r#type = Type::new(TypeKind::Bit);
+        r#type.set_concrete_width(Shape::new(vec![Some(width)]));
+
+        let comptime = Comptime::from_type(r#type.clone(), ClockDomain::None, token);
+        let id = context.insert_var_path(path.clone(), comptime.clone());
+        let array_limit = context.config.evaluate_array_limit;
+        let variable = Variable::new(
+            id,
+            path.clone(),
+            VarKind::Variable,
+            r#type,
+            vec![Value::new(0, width, false)],
+            // Module-level, so the registers get nonblocking semantics
+            // unlike locals declared inside the `always_ff`.
+            Affiliation::Module,
+            &token,
+            array_limit,
+        );
+        context.insert_variable(id, variable);
+
+        Self {
+            id,
+            path,
+            comptime,
+            width,
+        }
+    }
+
+    fn expr(&self) -> Expression {
+        Expression::Term(Box::new(Factor::Variable(
+            self.id,
+            VarIndex::default(),
+            VarSelect::default(),
+            self.comptime.clone(),
+        )))
+    }
+
+    fn assign(&self, context: &mut Context, mut expr: Expression) -> ir::Statement {
+        expr.eval_comptime(context, Some(self.width));
+        let token = self.comptime.token;
+        ir::Statement::Assign(ir::AssignStatement {
+            dst: vec![AssignDestination {
+                id: self.id,
+                path: self.path.clone(),
+                index: VarIndex::default(),
+                select: VarSelect::default(),
+                comptime: self.comptime.clone(),
+                token,
+            }],
+            width: Some(self.width),
+            expr,
+            token,
+        })
+    }
+
+    /// Registers `next` as the value of this register at every clock edge
+    /// outside reset.
+    fn update(&self, context: &mut Context, state: &mut TemporalState, next: Expression) {
+        let token = self.comptime.token;
+        state.updates.push(self.assign(context, next));
+        let clear = self.assign(context, literal(0, self.width, token));
+        state.resets.push(clear);
+    }
+
+    /// Registers `next` as the value of this register at every clock edge.
+    fn sample(&self, context: &mut Context, state: &mut TemporalState, next: Expression) {
+        state.samples.push(self.assign(context, next));
+    }
+}
+
+fn literal(value: u64, width: usize, token: TokenRange) -> Expression {
+    Expression::create_value(Value::new(value, width, false), token)
+}
+
+fn binary(x: Expression, op: Op, y: Expression) -> Expression {
+    let token = TokenRange::from_range(&x.token_range(), &y.token_range());
+    Expression::Binary(
+        Box::new(x),
+        op,
+        Box::new(y),
+        Box::new(Comptime::create_unknown(token)),
+    )
+}
+
+/// `x != 0`, the truth value of a condition of any width.
+fn truth(x: Expression) -> Expression {
+    let token = x.token_range();
+    binary(x, Op::Ne, literal(0, 1, token))
+}
+
+/// Whether bit `index` of `x` is set.
+fn bit(x: Expression, index: usize) -> Expression {
+    let token = x.token_range();
+    let shifted = binary(x, Op::LogicShiftR, literal(index as u64, 32, token));
+    truth(binary(shifted, Op::BitAnd, literal(1, 1, token)))
+}
+
+fn string_literal(text: &str, token: TokenRange) -> Expression {
+    let value = string_to_byte_value(&text.replace('\\', "\\\\"));
+    let mut r#type = Type::new(TypeKind::String);
+    r#type.set_concrete_width(Shape::new(vec![Some(value.width())]));
+    Expression::Term(Box::new(Factor::Value(Comptime {
+        value: ValueVariant::Numeric(value),
+        r#type,
+        is_const: true,
+        is_global: true,
+        token,
+        ..Default::default()
+    })))
+}
+
+fn string_value(expr: &Expression) -> Option<String> {
+    if let Expression::Term(factor) = expr
+        && let Factor::Value(x) = factor.as_ref()
+        && x.r#type.kind == TypeKind::String
+        && let ValueVariant::Numeric(value) = &x.value
+    {
+        byte_value_to_string(value)
+    } else {
+        None
+    }
+}
+
+fn constant(
+    context: &mut Context,
+    name: &'static str,
+    arg: (Expression, Vec<VarPathSelect>, TokenRange),
+) -> IrResult<usize> {
+    let (mut expr, _, token) = arg;
+    let comptime = expr.eval_comptime(context, None);
+    if comptime.is_const
+        && let Ok(value) = comptime.get_value()
+        && let Some(value) = value.to_usize()
+    {
+        Ok(value)
+    } else {
+        context.insert_error(AnalyzerError::unevaluable_value(
+            UnevaluableValueKind::SystemFunctionArg(name),
+            &token,
+        ));
+        Err(ir_error!(token))
+    }
+}
+
+/// The sampled-value function `name` refers to, if any.
+pub fn sampled_function_name(name: StrId) -> Option<&'static str> {
+    match name.to_string().as_str() {
+        "$past" => Some("$past"),
+        "$rose" => Some("$rose"),
+        "$fell" => Some("$fell"),
+        "$stable" => Some("$stable"),
+        _ => None,
+    }
+}
+
+/// Lowers a sampled-value function call inside an `always_ff`.
+pub fn sampled_function(
+    context: &mut Context,
+    name: &'static str,
+    mut args: Vec<(Expression, Vec<VarPathSelect>, TokenRange)>,
+    token: TokenRange,
+) -> IrResult<Expression> {
+    let arity = if name == "$past" { 2 } else { 1 };
+    if args.is_empty() || args.len() > arity {
+        context.insert_error(AnalyzerError::mismatch_function_arity(
+            name,
+            arity,
+            args.len(),
+            &token,
+        ));
+        return Err(ir_error!(token));
+    }
+    let depth = if args.len() == 2 {
+        constant(context, name, args.remove(1))?
+    } else {
+        1
+    };
+    if depth == 0 {
+        context.insert_error(AnalyzerError::unevaluable_value(
+            UnevaluableValueKind::SystemFunctionArg(name),
+            &token,
+        ));
+        return Err(ir_error!(token));
+    }
+
+    let (mut expr, _, _) = args.remove(0);
+    let comptime = expr.eval_comptime(context, None);
+    let Some(width) = comptime.r#type.total_width().filter(|x| *x > 0) else {
+        return Err(ir_error!(token));
+    };
+    testbench_clock(context, token)?;
+
+    // `$past(x, n)` is the last of a chain of `n` registers fed by `x`.
+    let mut state = context.temporal.take().unwrap();
+    let mut prev = expr.clone();
+    for _ in 0..depth {
+        let reg = Hidden::new(context, "past", width, token);
+        reg.sample(context, &mut state, prev);
+        prev = reg.expr();
+    }
+    context.temporal = Some(state);
+
+    let lsb = |x: Expression| bit(x, 0);
+    let ret = match name {
+        "$past" => prev,
+        "$rose" => binary(lsb(expr), Op::Greater, lsb(prev)),
+        "$fell" => binary(lsb(expr), Op::Less, lsb(prev)),
+        _ => binary(expr, Op::Eq, prev),
+    };
+    Ok(ret)
+}
+
+/// The temporal assertion `name` refers to, if any.
+pub fn assertion_name(name: StrId) -> Option<&'static str> {
+    match name.to_string().as_str() {
+        "$assert_implies" => Some("$assert_implies"),
+        "$assert_eventually" => Some("$assert_eventually"),
+        _ => None,
+    }
+}
+
+/// Lowers `$assert_implies(antecedent, delay, consequent, ...)` and
+/// `$assert_eventually(antecedent, within, consequent, ...)` into checks run
+/// at the end of the enclosing `always_ff`.
+///
+/// Each clock edge at which the antecedent holds starts an attempt. An
+/// implication attempt fails when the consequent is false exactly `delay`
+/// edges later; an eventually attempt fails when the consequent stays false
+/// up to `within` edges later.
+///
+/// Returns the statements replacing the call: none in an `always_ff`, and the
+/// arming of the checks in a testbench `initial`.
+pub fn assertion(
+    context: &mut Context,
+    name: &'static str,
+    mut args: Vec<(Expression, Vec<VarPathSelect>, TokenRange)>,
+    token: TokenRange,
+) -> IrResult<Vec<ir::Statement>> {
+    let eventually = name == "$assert_eventually";
+    if args.len() < 3 {
+        context.insert_error(AnalyzerError::mismatch_function_arity(
+            name,
+            3,
+            args.len(),
+            &token,
+        ));
+        return Err(ir_error!(token));
+    }
+    // Rejected by `CheckStatement` outside the top level of an `always_ff`
+    // or a testbench `initial`.
+    if context.temporal.is_none() {
+        return Ok(vec![]);
+    }
+    testbench_clock(context, token)?;
+
+    let mut message: Vec<_> = args.split_off(3);
+    let consequent = args.pop().unwrap();
+    let n = constant(context, name, args.pop().unwrap())?;
+    let antecedent = args.pop().unwrap();
+
+    let mut eval = |arg: (Expression, Vec<VarPathSelect>, TokenRange)| {
+        let (mut expr, _, _) = arg;
+        expr.eval_comptime(context, None);
+        truth(expr)
+    };
+    let mut antecedent = eval(antecedent);
+    let consequent = eval(consequent);
+
+    // A testbench assertion starts attempts from the edge after its
+    // statement runs.
+    let mut arming = vec![];
+    if context.temporal.as_ref().unwrap().testbench {
+        let armed = Hidden::new(context, "armed", 1, token);
+        arming.push(armed.assign(context, literal(1, 1, token)));
+        antecedent = binary(antecedent, Op::LogicAnd, armed.expr());
+    }
+
+    let format = if message.is_empty() {
+        if eventually {
+            format!("consequent not met within {n} cycle(s) of the antecedent")
+        } else {
+            format!("consequent not met {n} cycle(s) after the antecedent")
+        }
+    } else {
+        let (mut expr, _, arg_token) = message.remove(0);
+        expr.eval_comptime(context, None);
+        let Some(format) = string_value(&expr) else {
+            context.insert_error(AnalyzerError::mismatch_function_arg(
+                name,
+                &expr.comptime().r#type.to_string(),
+                &arg_token,
+            ));
+            return Err(ir_error!(token));
+        };
+        format
+    };
+    let format = format!("{format} (attempt started at cycle %d)");
+
+    let mut state = context.temporal.take().unwrap();
+    let cycle = state
+        .cycle
+        .get_or_insert_with(|| Hidden::new(context, "cycle", 32, token))
+        .expr();
+    let start = binary(cycle, Op::Sub, literal(n as u64, 32, token));
+
+    // Bit `k` of `pending` is an attempt started `k + 1` edges ago that is
+    // still open; `current` adds the one starting now as bit 0.
+    let (failed, next) = if n == 0 {
+        (antecedent, None)
+    } else {
+        let pending = Hidden::new(context, "pending", n + 1, token);
+        let shifted = binary(pending.expr(), Op::LogicShiftL, literal(1, 32, token));
+        let current = binary(shifted, Op::BitOr, antecedent);
+        let next = if eventually {
+            Expression::Ternary(
+                Box::new(consequent.clone()),
+                Box::new(literal(0, n + 1, token)),
+                Box::new(current.clone()),
+                Box::new(Comptime::create_unknown(token)),
+            )
+        } else {
+            current.clone()
+        };
+        (bit(current, n), Some((pending, next)))
+    };
+    let not_failed = binary(
+        Expression::Unary(
+            Op::LogicNot,
+            Box::new(failed),
+            Box::new(Comptime::create_unknown(token)),
+        ),
+        Op::LogicOr,
+        consequent,
+    );
+
+    let mut inputs = vec![string_literal(&format, token)];
+    inputs.extend(message.into_iter().map(|(expr, _, _)| expr));
+    inputs.push(start);
+    let inputs: Vec<_> = inputs
+        .into_iter()
+        .map(|mut x| {
+            x.eval_comptime(context, None);
+            SystemFunctionInput(x)
+        })
+        .collect();
+    let mut cond = not_failed;
+    cond.eval_comptime(context, None);
+    state
+        .updates
+        .push(ir::Statement::SystemFunctionCall(Box::new(
+            SystemFunctionCall {
+                kind: SystemFunctionKind::Assert {
+                    kind: AssertKind::Fatal,
+                    cond: SystemFunctionInput(cond),
+                    args: inputs,
+                },
+                comptime: Comptime::create_unknown(token),
+            },
+        )));
+    if let Some((pending, next)) = next {
+        pending.update(context, &mut state, next);
+    }
+    context.temporal = Some(state);
+
+    Ok(arming)
+}
@@ -6,7 +6,7 @@ use crate::conv::checker::anonymous::check_anonymous;
 use crate::conv::checker::clock_domain::check_clock_domain;
 use crate::conv::checker::generic::check_generic_refereence;
 use crate::conv::instance::InstanceHistoryError;
-use crate::conv::{Context, Conv};
+use crate::conv::{Context, Conv, temporal};
 use crate::definition_table::{self, Definition, DefinitionId};
 use crate::ir::{
     self, Arguments, Comptime, FuncPath, FuncProto, IrResult, Op, PartSelectPath, Shape, ShapeRef,
@@ -1418,6 +1418,13 @@ pub fn eval_type(
                         TbComponentKind::File
                             | TbComponentKind::External(_)
                             | TbComponentKind::Random
+                            | TbComponentKind::Wave
+                            | TbComponentKind::Checkpoint
+                            | TbComponentKind::Force
+                            | TbComponentKind::Coverage
+                            | TbComponentKind::Thread
+                            | TbComponentKind::Queue
+                            | TbComponentKind::Scoreboard
                     ) =>
                 {
                     if !context.in_test_module {
@@ -1955,6 +1962,11 @@ pub fn eval_function_call(
             SymbolKind::SystemFunction(_) => {
                 let name = symbol.found.token.text;
                 let args = args.to_system_function_args(context, &symbol.found);
+                if context.temporal.is_some()
+                    && let Some(name) = temporal::sampled_function_name(name)
+                {
+                    return temporal::sampled_function(context, name, args, token);
+                }
                 let ret = ir::SystemFunctionCall::new(context, name, args, token)?;
                 Ok(ir::Expression::Term(Box::new(
                     ir::Factor::SystemFunctionCall(ret),
@@ -2633,6 +2645,7 @@ pub fn eval_factor_symbol_external(
     ret
 }
 
+#[allow(clippy::collapsible_match)]
 pub fn eval_factor_symbol(
     context: &mut Context,
     path: GenericSymbolPath,
@@ -4455,10 +4468,318 @@ pub fn tb_method_call(
                 signed,
             }
         }
+        (TbComponentKind::Random, "get_dist") | (TbComponentKind::Random, "get_inside") => {
+            let count = if let Some(ir::Arguments::Positional(ref positional)) = args {
+                positional.len()
+            } else {
+                0
+            };
+            // `get_dist` takes value/weight pairs, `get_inside` plain values.
+            let dist = method_name == "get_dist";
+            let arity = if dist {
+                count.max(1).next_multiple_of(2)
+            } else {
+                count.max(1)
+            };
+            if count != arity {
+                context.insert_error(AnalyzerError::mismatch_function_arity(
+                    method_name,
+                    arity,
+                    count,
+                    &token,
+                ));
+                return Err(ir_error!(token));
+            }
+            let mut values = Vec::new();
+            let mut weights = Vec::new();
+            for i in 0..count {
+                let arg = random_arg(context, &args, i, method_name, arity, &token)?;
+                if dist && i % 2 == 1 {
+                    weights.push(arg);
+                } else {
+                    values.push(arg);
+                }
+            }
+            let (width, signed) = resolve_random_elem_type(context, type_path);
+            ret_width = Some(width);
+            ret_signed = signed;
+            if dist {
+                TbMethod::RandomGetDist {
+                    values,
+                    weights,
+                    width,
+                    signed,
+                }
+            } else {
+                TbMethod::RandomGetInside {
+                    values,
+                    width,
+                    signed,
+                }
+            }
+        }
+        (TbComponentKind::Random, "get_cyclic") => {
+            let min = random_arg(context, &args, 0, method_name, 2, &token)?;
+            let max = random_arg(context, &args, 1, method_name, 2, &token)?;
+            let (width, signed) = resolve_random_elem_type(context, type_path);
+            ret_width = Some(width);
+            ret_signed = signed;
+            TbMethod::RandomGetCyclic {
+                min,
+                max,
+                width,
+                signed,
+            }
+        }
+        (TbComponentKind::Random, "get_where") => {
+            let min = random_arg(context, &args, 0, method_name, 3, &token)?;
+            let max = random_arg(context, &args, 1, method_name, 3, &token)?;
+            let cond = random_arg(context, &args, 2, method_name, 3, &token)?;
+            // Trailing arguments name further variables drawn with the
+            // destination on every attempt.
+            let count = if let Some(ir::Arguments::Positional(ref positional)) = args {
+                positional.len()
+            } else {
+                0
+            };
+            let mut others = Vec::new();
+            for i in 3..count {
+                others.push(variable_arg(context, &args, i, method_name, count, &token)?);
+            }
+            let (width, signed) = resolve_random_elem_type(context, type_path);
+            ret_width = Some(width);
+            ret_signed = signed;
+            TbMethod::RandomGetWhere {
+                min,
+                max,
+                cond,
+                others,
+                width,
+                signed,
+            }
+        }
+        (TbComponentKind::Wave, "start") => TbMethod::WaveStart,
+        (TbComponentKind::Wave, "stop") => TbMethod::WaveStop,
+        (TbComponentKind::Checkpoint, "fork") => {
+            let count = random_arg(context, &args, 0, method_name, 1, &token)?;
+            TbMethod::CheckpointFork { count }
+        }
+        (TbComponentKind::Checkpoint, "branch") => {
+            ret_width = Some(32);
+            TbMethod::CheckpointBranch
+        }
+        (TbComponentKind::Checkpoint, "save") | (TbComponentKind::Checkpoint, "load") => {
+            let name = if let Some(ir::Arguments::Positional(ref positional)) = args
+                && let Some(arg) = positional.first()
+            {
+                SystemFunctionInput(arg.0.clone())
+            } else {
+                context.insert_error(AnalyzerError::mismatch_function_arity(
+                    method_name,
+                    1,
+                    0,
+                    &token,
+                ));
+                return Err(ir_error!(token));
+            };
+            if method_name == "save" {
+                TbMethod::CheckpointSave { name }
+            } else {
+                TbMethod::CheckpointLoad { name }
+            }
+        }
+        (TbComponentKind::Force, "force") | (TbComponentKind::Force, "deposit") => {
+            let target = force_target(context, &args, method_name, 2, &token)?;
+            let value = random_arg(context, &args, 1, method_name, 2, &token)?;
+            if method_name == "force" {
+                TbMethod::Force { target, value }
+            } else {
+                TbMethod::Deposit { target, value }
+            }
+        }
+        (TbComponentKind::Force, "release") => {
+            let target = force_target(context, &args, method_name, 1, &token)?;
+            TbMethod::Release { target }
+        }
         (TbComponentKind::Random, "get_seed") => {
             ret_width = Some(64);
             TbMethod::RandomGetSeed
         }
+        (TbComponentKind::Coverage, "point") => {
+            let name = coverage_name(context, &args, 0, method_name, 1, &token)?;
+            TbMethod::CoverPoint { name }
+        }
+        (TbComponentKind::Coverage, "bins") => {
+            let count = positional_count(&args).max(2);
+            let name = coverage_name(context, &args, 0, method_name, count, &token)?;
+            let mut values = Vec::new();
+            for i in 1..count {
+                values.push(random_arg(context, &args, i, method_name, count, &token)?);
+            }
+            TbMethod::CoverBins { name, values }
+        }
+        (TbComponentKind::Coverage, "range") => {
+            // `range(name, min, max)` is one bin; a fourth argument splits
+            // the range into that many.
+            let arity = positional_count(&args).clamp(3, 4);
+            let name = coverage_name(context, &args, 0, method_name, arity, &token)?;
+            let min = random_arg(context, &args, 1, method_name, arity, &token)?;
+            let max = random_arg(context, &args, 2, method_name, arity, &token)?;
+            let count = if arity == 4 {
+                Some(random_arg(context, &args, 3, method_name, arity, &token)?)
+            } else {
+                None
+            };
+            if positional_count(&args) > arity {
+                context.insert_error(AnalyzerError::mismatch_function_arity(
+                    method_name,
+                    arity,
+                    positional_count(&args),
+                    &token,
+                ));
+                return Err(ir_error!(token));
+            }
+            TbMethod::CoverRange {
+                name,
+                min,
+                max,
+                count,
+            }
+        }
+        (TbComponentKind::Coverage, "cross") => {
+            let count = positional_count(&args).max(3);
+            let mut names = Vec::new();
+            for i in 0..count {
+                names.push(coverage_name(
+                    context,
+                    &args,
+                    i,
+                    method_name,
+                    count,
+                    &token,
+                )?);
+            }
+            let name = names.remove(0);
+            TbMethod::CoverCross {
+                name,
+                points: names,
+            }
+        }
+        (TbComponentKind::Coverage, "sample") => {
+            // Name/value pairs, one per coverpoint sampled together.
+            let count = positional_count(&args).max(1).next_multiple_of(2);
+            let mut names = Vec::new();
+            let mut values = Vec::new();
+            for i in (0..count).step_by(2) {
+                names.push(coverage_name(
+                    context,
+                    &args,
+                    i,
+                    method_name,
+                    count,
+                    &token,
+                )?);
+                values.push(random_arg(
+                    context,
+                    &args,
+                    i + 1,
+                    method_name,
+                    count,
+                    &token,
+                )?);
+            }
+            TbMethod::CoverSample { names, values }
+        }
+        (TbComponentKind::ClockGen, "wait_until") => {
+            // `wait_until(cond)` waits indefinitely; a second argument
+            // bounds the wait to that many cycles.
+            let arity = positional_count(&args).clamp(1, 2);
+            let cond = random_arg(context, &args, 0, method_name, arity, &token)?;
+            let within = if arity == 2 {
+                Some(random_arg(context, &args, 1, method_name, arity, &token)?)
+            } else {
+                None
+            };
+            if positional_count(&args) > arity {
+                context.insert_error(AnalyzerError::mismatch_function_arity(
+                    method_name,
+                    arity,
+                    positional_count(&args),
+                    &token,
+                ));
+                return Err(ir_error!(token));
+            }
+            let period = context
+                .tb_clock_period
+                .get(&inst_name)
+                .cloned()
+                .map(Box::new);
+            TbMethod::ClockWaitUntil {
+                cond,
+                within,
+                period,
+            }
+        }
+        (TbComponentKind::Thread, "wait_until") => {
+            let cond = random_arg(context, &args, 0, method_name, 1, &token)?;
+            if positional_count(&args) > 1 {
+                context.insert_error(AnalyzerError::mismatch_function_arity(
+                    method_name,
+                    1,
+                    positional_count(&args),
+                    &token,
+                ));
+                return Err(ir_error!(token));
+            }
+            TbMethod::ThreadWaitUntil { cond }
+        }
+        (TbComponentKind::Queue, "push") => {
+            let (value, width, signed) = elem_arg(context, &args, method_name, type_path, &token)?;
+            TbMethod::QueuePush {
+                value,
+                width,
+                signed,
+            }
+        }
+        (TbComponentKind::Queue, "pop") | (TbComponentKind::Queue, "peek") => {
+            let (width, signed) = resolve_random_elem_type(context, type_path);
+            ret_width = Some(width);
+            ret_signed = signed;
+            TbMethod::QueuePop {
+                peek: method_name == "peek",
+                width,
+                signed,
+            }
+        }
+        (TbComponentKind::Queue, "size") => {
+            ret_width = Some(32);
+            TbMethod::QueueSize
+        }
+        (TbComponentKind::Queue, "empty") => {
+            ret_width = Some(1);
+            TbMethod::QueueEmpty
+        }
+        (TbComponentKind::Scoreboard, "expect") => {
+            let (value, width, signed) = elem_arg(context, &args, method_name, type_path, &token)?;
+            TbMethod::ScoreboardExpect {
+                value,
+                width,
+                signed,
+            }
+        }
+        (TbComponentKind::Scoreboard, "observe") | (TbComponentKind::Scoreboard, "observe_any") => {
+            let (value, width, signed) = elem_arg(context, &args, method_name, type_path, &token)?;
+            TbMethod::ScoreboardObserve {
+                value,
+                ordered: method_name == "observe",
+                width,
+                signed,
+            }
+        }
+        (TbComponentKind::Scoreboard, "pending") => {
+            ret_width = Some(32);
+            TbMethod::ScoreboardPending
+        }
         // Any method name is accepted on a user-defined component; the
         // component validates it at run time. With a manifest present the
         // name and arity are also diagnosed here.
@@ -4479,6 +4800,7 @@ pub fn tb_method_call(
         ret_strict: false,
         ret_width,
         ret_signed,
+        token,
     })))
 }
 
@@ -4515,15 +4837,193 @@ fn random_arg(
     }
 }
 
-/// Resolves the element type `T` of a `$tb::random::<T>` variable through the
-/// generic maps in effect, returning its `(width, signed)`. The element type's
-/// validity (fixed integer / `bbool`) is diagnosed by `check_generic_refereence`;
-/// this falls back to a 32-bit unsigned width when resolution fails so a broken
-/// type does not also cause a spurious ICE.
+/// Captures the single value argument of a `$tb::queue`/`$tb::scoreboard`
+/// method together with the width/signedness of the element type `T`. The
+/// argument is checked against `T`: one that needs more bits than `T` has
+/// would be silently truncated, so it is rejected.
+fn elem_arg(
+    context: &mut Context,
+    args: &Option<ir::Arguments>,
+    method_name: &str,
+    type_path: &GenericSymbolPath,
+    token: &TokenRange,
+) -> IrResult<(ir::Expression, u32, bool)> {
+    let elem_type = resolve_elem_type(context, type_path);
+    let (width, signed) = elem_type
+        .as_ref()
+        .and_then(|x| Some((x.total_width()? as u32, x.signed)))
+        .unwrap_or((32, false));
+    let value = random_arg(context, args, 0, method_name, 1, token)?;
+    if positional_count(args) > 1 {
+        context.insert_error(AnalyzerError::mismatch_function_arity(
+            method_name,
+            1,
+            positional_count(args),
+            token,
+        ));
+        return Err(ir_error!(*token));
+    }
+
+    let comptime = value.comptime();
+    let fits = needed_width(&value)
+        .map(|x| x <= width as usize)
+        .unwrap_or(true);
+    if !fits && let Some(elem_type) = elem_type {
+        context.insert_error(AnalyzerError::mismatch_assignment(
+            &comptime.r#type.to_string(),
+            &elem_type.to_string(),
+            &value.token_range(),
+            &[],
+        ));
+    }
+    Ok((value, width, signed))
+}
+
+/// Bits `expr` needs to be stored without truncation. Constants count by
+/// the bits their value needs, so `din + 1` is as wide as `din` rather than
+/// the 32-bit literal; everything else counts by its type.
+fn needed_width(expr: &ir::Expression) -> Option<usize> {
+    let comptime = expr.comptime();
+    if comptime.is_const
+        && let Ok(x) = comptime.get_value()
+    {
+        let width = if let Value::U64(x) = x
+            && x.signed
+            && let Some(x) = x.to_i64()
+            && x < 0
+        {
+            // Two's complement: the magnitude bits plus the sign bit.
+            (64 - (!x).leading_zeros() + 1) as usize
+        } else {
+            x.payload().bits().max(x.mask_xz().bits()) as usize
+        };
+        return Some(width);
+    }
+    match expr {
+        ir::Expression::Binary(
+            x,
+            Op::Add
+            | Op::Sub
+            | Op::Mul
+            | Op::Div
+            | Op::Rem
+            | Op::BitAnd
+            | Op::BitOr
+            | Op::BitXor
+            | Op::BitXnor,
+            y,
+            _,
+        ) => Some(needed_width(x)?.max(needed_width(y)?)),
+        ir::Expression::Ternary(_, x, y, _) => Some(needed_width(x)?.max(needed_width(y)?)),
+        _ => comptime.r#type.total_width(),
+    }
+}
+
+fn positional_count(args: &Option<ir::Arguments>) -> usize {
+    if let Some(ir::Arguments::Positional(positional)) = args {
+        positional.len()
+    } else {
+        0
+    }
+}
+
+/// Captures the `i`-th positional argument of a `$tb::coverage` method as a
+/// coverpoint/cross name, which must be a string literal.
+fn coverage_name(
+    context: &mut Context,
+    args: &Option<ir::Arguments>,
+    i: usize,
+    method_name: &str,
+    arity: usize,
+    token: &TokenRange,
+) -> IrResult<SystemFunctionInput> {
+    let name = random_arg(context, args, i, method_name, arity, token)?;
+    if !name.comptime().r#type.is_string() {
+        let token = name.token_range();
+        context.insert_error(AnalyzerError::mismatch_type(
+            MismatchTypeKind::SymbolKind {
+                name: name.to_string(),
+                expected: "string".to_string(),
+                actual: "value".to_string(),
+            },
+            &token,
+        ));
+        return Err(ir_error!(token));
+    }
+    Ok(SystemFunctionInput(name))
+}
+
+/// Captures the target of a `$tb::force` method: a whole variable, local or
+/// hierarchical (`dut.u_core.pc`), optionally with a constant array index.
+/// Bit selects and runtime indices are rejected, since the simulator
+/// overrides a variable's storage as a unit.
+fn force_target(
+    context: &mut Context,
+    args: &Option<ir::Arguments>,
+    method_name: &str,
+    arity: usize,
+    token: &TokenRange,
+) -> IrResult<ir::Expression> {
+    let target = random_arg(context, args, 0, method_name, arity, token)?;
+    let whole_variable = match &target {
+        ir::Expression::Term(factor) => match factor.as_ref() {
+            ir::Factor::Variable(_, index, select, _) => index.is_const() && select.is_empty(),
+            ir::Factor::HierVariable(x) => x.index.is_const() && x.select.is_empty(),
+            _ => false,
+        },
+        _ => false,
+    };
+    if !whole_variable {
+        let target_token = target.token_range();
+        context.insert_error(AnalyzerError::mismatch_function_arg(
+            method_name,
+            "non-variable",
+            &target_token,
+        ));
+        return Err(ir_error!(target_token));
+    }
+    Ok(target)
+}
+
+/// Argument `i` of a method which writes it, e.g. the extra variables of
+/// `get_where`: a local variable without index or select.
+fn variable_arg(
+    context: &mut Context,
+    args: &Option<ir::Arguments>,
+    i: usize,
+    method_name: &str,
+    arity: usize,
+    token: &TokenRange,
+) -> IrResult<ir::AssignDestination> {
+    let target = random_arg(context, args, i, method_name, arity, token)?;
+    let dst = if let Some(ir::Arguments::Positional(positional)) = args
+        && let [path] = positional[i].1.as_slice()
+    {
+        path.clone().to_assign_destination(context, false)
+    } else {
+        None
+    };
+    match dst {
+        Some(dst) if dst.index.0.is_empty() && dst.select.is_empty() => Ok(dst),
+        _ => {
+            let target_token = target.token_range();
+            context.insert_error(AnalyzerError::mismatch_function_arg(
+                method_name,
+                "non-variable",
+                &target_token,
+            ));
+            Err(ir_error!(target_token))
+        }
+    }
+}
+
+/// Resolves the element type `T` of a `$tb::random::<T>` (or queue /
+/// scoreboard) variable through the generic maps in effect, returning its
+/// `(width, signed)`. The element type's validity is diagnosed by
+/// `check_generic_refereence`; this falls back to a 32-bit unsigned width
+/// when resolution fails so a broken type does not also cause a spurious ICE.
 fn resolve_random_elem_type(context: &mut Context, type_path: &GenericSymbolPath) -> (u32, bool) {
-    let resolved = context.resolve_path(type_path.clone());
-    if let Some(arg) = resolved.paths.last().and_then(|p| p.arguments.first())
-        && let Ok(ty) = eval_type(context, &arg.clone(), TypePosition::Variable)
+    if let Some(ty) = resolve_elem_type(context, type_path)
         && let Some(width) = ty.total_width()
     {
         (width as u32, ty.signed)
@@ -4532,6 +5032,14 @@ fn resolve_random_elem_type(context: &mut Context, type_path: &GenericSymbolPath
     }
 }
 
+/// The element type `T` of a generic `$tb` component, i.e. the generic
+/// argument of its declared type.
+fn resolve_elem_type(context: &mut Context, type_path: &GenericSymbolPath) -> Option<ir::Type> {
+    let resolved = context.resolve_path(type_path.clone());
+    let arg = resolved.paths.last()?.arguments.first()?.clone();
+    eval_type(context, &arg, TypePosition::Variable).ok()
+}
+
 /// Hoists a component method call out of an expression: the call runs as
 /// its own zero-time statement immediately before the enclosing testbench
 /// statement, its return value lands in a synthetic temporary, and the
@@ -24,6 +24,7 @@ pub struct CheckAttribute {
     ifdef_state: IfdefState,
     ifdef_pos: Vec<StrId>,
     ifdef_neg: Vec<StrId>,
+    in_module_items: bool,
 }
 
 impl CheckAttribute {
@@ -160,9 +161,27 @@ impl CheckAttribute {
         }
     }
 
+    /// `#[assert]` marks items of a module body, which are moved into its
+    /// bind-ready checker module; anywhere else it would be ignored.
+    fn check_assert(&mut self, attrs: &[Option<(Attr, TokenRange)>]) {
+        if self.in_module_items {
+            return;
+        }
+        for (attr, range) in attrs.iter().flatten() {
+            if matches!(attr, Attr::Assert) {
+                self.errors.push(AnalyzerError::misplaced_attribute(
+                    "assert",
+                    "an item of a module body",
+                    range,
+                ));
+            }
+        }
+    }
+
     fn attrs(&mut self, args: &[&Attribute], range: TokenRange, last: bool) {
         let mut attrs = self.gen_attrs(args);
         self.check_ifdef(&mut attrs, last);
+        self.check_assert(&attrs);
         self.set_attrs(attrs, range);
     }
 
@@ -359,6 +378,7 @@ impl VerylGrammarTrait for CheckAttribute {
     fn module_declaration(&mut self, arg: &ModuleDeclaration) -> Result<(), ParolError> {
         if let HandlerPoint::Before = self.point {
             self.reset_ifdef();
+            self.in_module_items = true;
             for x in &arg.module_declaration_list {
                 let x = x.module_group.as_ref();
                 let attrs: Vec<_> = x
@@ -368,6 +388,27 @@ impl VerylGrammarTrait for CheckAttribute {
                     .collect();
                 self.attrs(&attrs, x.range(), false);
             }
+            self.in_module_items = false;
+        }
+        Ok(())
+    }
+
+    fn module_group(&mut self, arg: &ModuleGroup) -> Result<(), ParolError> {
+        // Only the outermost groups of a module body are split out.
+        if let HandlerPoint::Before = self.point
+            && let ModuleGroupGroup::LBraceModuleGroupGroupListRBrace(x) = &*arg.module_group_group
+        {
+            for x in &x.module_group_group_list {
+                for x in &x.module_group.module_group_list {
+                    if let Ok(Attr::Assert) = Attr::try_from(x.attribute.as_ref()) {
+                        self.errors.push(AnalyzerError::misplaced_attribute(
+                            "assert",
+                            "an item of a module body",
+                            &x.attribute.as_ref().into(),
+                        ));
+                    }
+                }
+            }
         }
         Ok(())
     }
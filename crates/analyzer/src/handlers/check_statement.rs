@@ -1,5 +1,5 @@
 use crate::analyzer_error::{AnalyzerError, InvalidForRangeKind};
-use crate::attribute::Attribute;
+use crate::attribute::{self, Attribute};
 use crate::attribute_table;
 use veryl_parser::ParolError;
 use veryl_parser::token_range::TokenRange;
@@ -18,6 +18,7 @@ pub struct CheckStatement {
     in_test_module: bool,
     statement_depth_in_always_ff: usize,
     statement_depth_in_loop: usize,
+    block_depth: usize,
 }
 
 impl CheckStatement {
@@ -40,6 +41,54 @@ impl VerylGrammarTrait for CheckStatement {
         Ok(())
     }
 
+    fn statement_block(&mut self, _arg: &StatementBlock) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => self.block_depth += 1,
+            HandlerPoint::After => self.block_depth -= 1,
+        }
+        Ok(())
+    }
+
+    fn identifier_statement(&mut self, arg: &IdentifierStatement) -> Result<(), ParolError> {
+        // Temporal assertions are checked at every clock edge, so they can't
+        // be gated by procedural conditions. A testbench arms them when the
+        // statement runs.
+        if let HandlerPoint::Before = self.point
+            && let IdentifierStatementGroup::FunctionCall(_) = &*arg.identifier_statement_group
+        {
+            let name = arg.expression_identifier.scoped_identifier.identifier();
+            let name = name.to_string();
+            if matches!(name.as_str(), "$assert_implies" | "$assert_eventually")
+                && !((self.in_always_ff || (self.in_initial && self.in_test_module))
+                    && self.block_depth == 1)
+            {
+                let token: TokenRange = arg.expression_identifier.as_ref().into();
+                self.errors
+                    .push(AnalyzerError::invalid_statement(&name, &token));
+            }
+        }
+        Ok(())
+    }
+
+    fn statement_block_group(&mut self, arg: &StatementBlockGroup) -> Result<(), ParolError> {
+        // Threads only exist in the testbench scheduler, and a thread is
+        // each item of a `block`.
+        if let HandlerPoint::Before = self.point
+            && attribute::fork_attribute(arg).is_some()
+        {
+            let is_block = matches!(
+                &*arg.statement_block_group_group,
+                StatementBlockGroupGroup::BlockLBraceStatementBlockGroupGroupListRBrace(_)
+            );
+            if !(is_block && self.in_initial && self.in_test_module) {
+                let token: TokenRange = arg.into();
+                self.errors
+                    .push(AnalyzerError::invalid_statement("fork", &token));
+            }
+        }
+        Ok(())
+    }
+
     fn module_declaration(&mut self, arg: &ModuleDeclaration) -> Result<(), ParolError> {
         match self.point {
             HandlerPoint::Before => {
@@ -152,6 +201,7 @@ impl VerylGrammarTrait for CheckStatement {
             HandlerPoint::Before => {
                 self.in_always_ff = true;
                 self.statement_depth_in_always_ff = 0;
+                self.block_depth = 0;
             }
             HandlerPoint::After => self.in_always_ff = false,
         }
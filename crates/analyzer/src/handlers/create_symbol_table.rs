@@ -3,7 +3,7 @@ use crate::analyzer_error::{
     MultipleDefaultKind,
 };
 use crate::attribute::Attribute as Attr;
-use crate::attribute::{AllowItem, EnumEncodingItem};
+use crate::attribute::{AllowItem, EnumEncodingItem, TimeoutItem};
 use crate::attribute_table;
 use crate::definition_table::{self, Definition};
 use crate::generic_inference_table::{self, PendingEntry};
@@ -182,6 +182,40 @@ impl CreateSymbolTable {
         }
     }
 
+    /// A `#[matrix]` overrides parameters of the simulated module, so it
+    /// must be the test module itself and declare each of them.
+    fn check_test_matrix(
+        &mut self,
+        test: &TestProperty,
+        name: StrId,
+        parameters: &[Parameter],
+        identifier: &Identifier,
+    ) {
+        if test.matrix.is_empty() {
+            return;
+        }
+
+        let kind = if test.top != Some(name) {
+            Some(InvalidTestKind::MatrixTopModule)
+        } else if test
+            .matrix
+            .iter()
+            .any(|x| !parameters.iter().any(|y| y.name == x.name))
+        {
+            Some(InvalidTestKind::MatrixUndeclaredParameter)
+        } else if !test.golden.is_empty() {
+            // Every case would compare against, and bless, the same files.
+            Some(InvalidTestKind::MatrixWithGolden)
+        } else {
+            None
+        };
+
+        if let Some(kind) = kind {
+            self.errors
+                .push(AnalyzerError::invalid_test(kind, &identifier.into()));
+        }
+    }
+
     fn check_identifer_with_type(&mut self, identifier: &Identifier, r#type: &SymType) -> bool {
         if let Some(user_defined) = r#type.get_user_defined() {
             self.check_identifer_with_type_path(identifier, &user_defined.path)
@@ -2020,13 +2054,25 @@ impl VerylGrammarTrait for CreateSymbolTable {
                     let attrs = attribute_table::get(&arg.module.module_token.token);
                     let mut test_attr = None;
                     let mut ignored = false;
+                    let mut timeout = TimeoutItem::default();
+                    let mut matrix = vec![];
+                    let mut golden = vec![];
                     for attr in &attrs {
                         if let Attr::Test(_, top) = attr {
                             test_attr = Some(*top);
                         }
+                        if let Attr::Matrix(x) = attr {
+                            matrix = x.clone();
+                        }
                         if matches!(attr, Attr::Ignore) {
                             ignored = true;
                         }
+                        if let Attr::Timeout(x) = attr {
+                            timeout = *x;
+                        }
+                        if let Attr::Golden(x) = attr {
+                            golden.push(x.clone());
+                        }
                     }
                     if let Some(top) = test_attr {
                         let path = if let TokenSource::File { path, .. } =
@@ -2042,11 +2088,27 @@ impl VerylGrammarTrait for CreateSymbolTable {
                             path,
                             top,
                             ignored,
+                            timeout,
+                            matrix,
+                            golden,
+                            rejected_cases: vec![],
                         });
                     }
                     None
                 })();
 
+                if let Some(test) = &test {
+                    self.check_test_matrix(test, name, &parameters, &arg.identifier);
+                } else if attribute_table::get(&arg.module.module_token.token)
+                    .iter()
+                    .any(|x| matches!(x, Attr::Matrix(_)))
+                {
+                    self.errors.push(AnalyzerError::invalid_test(
+                        InvalidTestKind::MatrixWithoutTest,
+                        &arg.identifier.as_ref().into(),
+                    ));
+                }
+
                 let property = ModuleProperty {
                     range: arg.into(),
                     is_proto: false,
@@ -2601,6 +2663,8 @@ impl VerylGrammarTrait for CreateSymbolTable {
                 let way = arg.identifier.identifier_token.to_string();
                 let mut test_attr = None;
                 let mut ignored = false;
+                let mut timeout = TimeoutItem::default();
+                let mut golden = vec![];
 
                 let attrs = attribute_table::get(&arg.embed.embed_token.token);
                 for attr in &attrs {
@@ -2610,6 +2674,12 @@ impl VerylGrammarTrait for CreateSymbolTable {
                     if matches!(attr, Attr::Ignore) {
                         ignored = true;
                     }
+                    if let Attr::Timeout(x) = attr {
+                        timeout = *x;
+                    }
+                    if let Attr::Golden(x) = attr {
+                        golden.push(x.clone());
+                    }
                 }
 
                 let content = &arg.embed_content;
@@ -2640,6 +2710,10 @@ impl VerylGrammarTrait for CreateSymbolTable {
                         path,
                         top,
                         ignored,
+                        timeout,
+                        matrix: vec![],
+                        golden,
+                        rejected_cases: vec![],
                     };
                     (token, SymbolKind::Test(property))
                 } else {
@@ -2664,6 +2738,8 @@ impl VerylGrammarTrait for CreateSymbolTable {
             let way = arg.identifier.identifier_token.to_string();
             let mut test_attr = None;
             let mut ignored = false;
+            let mut timeout = TimeoutItem::default();
+            let mut golden = vec![];
 
             let attrs = attribute_table::get(&arg.include.include_token.token);
             for attr in &attrs {
@@ -2673,6 +2749,12 @@ impl VerylGrammarTrait for CreateSymbolTable {
                 if matches!(attr, Attr::Ignore) {
                     ignored = true;
                 }
+                if let Attr::Timeout(x) = attr {
+                    timeout = *x;
+                }
+                if let Attr::Golden(x) = attr {
+                    golden.push(x.clone());
+                }
             }
 
             let content = &arg.string_literal.string_literal_token.token;
@@ -2701,6 +2783,10 @@ impl VerylGrammarTrait for CreateSymbolTable {
                     path,
                     top,
                     ignored,
+                    timeout,
+                    matrix: vec![],
+                    golden,
+                    rejected_cases: vec![],
                 };
                 self.insert_symbol(&token, SymbolKind::Test(property), false);
             }
@@ -9,6 +9,7 @@ mod interface;
 #[allow(clippy::module_inception)]
 mod ir;
 mod module;
+pub mod mutation;
 mod op;
 mod shape;
 mod signature;
@@ -38,8 +39,8 @@ pub use shape::{Shape, ShapeRef};
 pub use signature::Signature;
 pub use statement::{
     AssignDestination, AssignStatement, CaseArm, CasePattern, CaseStatement, ControlFlow, ForBound,
-    ForRange, ForStatement, IfResetStatement, IfStatement, Statement, StatementBlock, TbMethod,
-    TbMethodCall,
+    ForRange, ForStatement, ForkStatement, IfResetStatement, IfStatement, Statement,
+    StatementBlock, TbMethod, TbMethodCall,
 };
 pub use system_function::{
     AssertKind, Input as SystemFunctionInput, SystemFunctionCall, SystemFunctionKind,
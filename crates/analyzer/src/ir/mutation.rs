@@ -0,0 +1,256 @@
+//! Systematic source-level mutations of the elaborated IR, used by
+//! `veryl test --mutate` to measure how well the testbenches constrain the
+//! design.  A mutation is identified by its kind and the token range of
+//! the construct it rewrites, so a single mutant covers every instance of
+//! the mutated module (generic instantiations, generate copies) at once.
+//!
+//! Sites are gathered from the hierarchy below a test top; the top itself
+//! (the testbench) and initial/final blocks are never mutated.
+
+use crate::HashSet;
+use crate::ir::{Component, Declaration, Expression, Op, Statement};
+use crate::value::{Value, ValueBigUint};
+use std::fmt;
+use std::sync::Arc;
+use veryl_parser::token_range::TokenRange;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MutationKind {
+    /// Replace a binary operator with its counterpart.
+    Op(Op, Op),
+    /// Swap the branches of an `if` statement or a conditional expression.
+    InvertCondition,
+    /// Replace the whole right-hand side of an assignment with all-zeros
+    /// (`false`) or all-ones (`true`).
+    ConstantAssign(bool),
+    /// Remove an assignment from the reset branch of `if_reset`.
+    DropReset,
+}
+
+impl fmt::Display for MutationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MutationKind::Op(from, to) => write!(f, "replaced `{from}` with `{to}`"),
+            MutationKind::InvertCondition => "inverted condition".fmt(f),
+            MutationKind::ConstantAssign(false) => "assignment replaced with 0".fmt(f),
+            MutationKind::ConstantAssign(true) => "assignment replaced with all ones".fmt(f),
+            MutationKind::DropReset => "dropped reset assignment".fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Mutation {
+    pub kind: MutationKind,
+    pub token: TokenRange,
+}
+
+/// Operator replaced by `MutationKind::Op`, if `op` has a counterpart.
+fn swap_op(op: Op) -> Option<Op> {
+    let ret = match op {
+        Op::Add => Op::Sub,
+        Op::Sub => Op::Add,
+        Op::ArithShiftL => Op::ArithShiftR,
+        Op::ArithShiftR => Op::ArithShiftL,
+        Op::LogicShiftL => Op::LogicShiftR,
+        Op::LogicShiftR => Op::LogicShiftL,
+        Op::Eq => Op::Ne,
+        Op::Ne => Op::Eq,
+        Op::EqWildcard => Op::NeWildcard,
+        Op::NeWildcard => Op::EqWildcard,
+        Op::Less => Op::GreaterEq,
+        Op::GreaterEq => Op::Less,
+        Op::Greater => Op::LessEq,
+        Op::LessEq => Op::Greater,
+        Op::LogicAnd => Op::LogicOr,
+        Op::LogicOr => Op::LogicAnd,
+        Op::BitAnd => Op::BitOr,
+        Op::BitOr => Op::BitAnd,
+        Op::BitXor => Op::BitXnor,
+        Op::BitXnor => Op::BitXor,
+        _ => return None,
+    };
+    Some(ret)
+}
+
+enum Node<'a> {
+    /// A statement, and whether it sits in the reset branch of `if_reset`.
+    Statement(&'a mut Statement, bool),
+    Expression(&'a mut Expression),
+}
+
+/// Collect the mutation sites of every module instantiated (transitively)
+/// by `top`.  Only sites whose token satisfies `filter` are returned, so
+/// callers can restrict mutation to the project's own sources.
+pub fn collect(top: &Component, filter: &dyn Fn(&TokenRange) -> bool) -> Vec<Mutation> {
+    let Component::Module(module) = top else {
+        return vec![];
+    };
+
+    let mut ret = vec![];
+    let mut seen = HashSet::default();
+    let mut push = |kind, token: TokenRange| {
+        let mutation = Mutation { kind, token };
+        if filter(&token) && seen.insert(mutation) {
+            ret.push(mutation);
+        }
+    };
+
+    // The walker is shared with `apply`, so it needs a mutable hierarchy.
+    let mut module = module.clone();
+    for decl in &mut module.declarations {
+        if let Declaration::Inst(x) = decl {
+            walk_component(Arc::make_mut(&mut x.component), &mut |node| match node {
+                Node::Statement(Statement::If(x), _) if !x.cond.comptime().is_const => {
+                    push(MutationKind::InvertCondition, x.token);
+                }
+                Node::Statement(Statement::Assign(x), in_reset) => {
+                    if in_reset {
+                        push(MutationKind::DropReset, x.token);
+                    } else if x.width.is_some() && !x.expr.comptime().is_const {
+                        push(MutationKind::ConstantAssign(false), x.token);
+                        push(MutationKind::ConstantAssign(true), x.token);
+                    }
+                }
+                Node::Expression(Expression::Binary(_, op, _, comptime)) => {
+                    if !comptime.is_const
+                        && let Some(to) = swap_op(*op)
+                    {
+                        push(MutationKind::Op(*op, to), comptime.token);
+                    }
+                }
+                Node::Expression(Expression::Ternary(_, _, _, comptime)) if !comptime.is_const => {
+                    push(MutationKind::InvertCondition, comptime.token);
+                }
+                _ => (),
+            });
+        }
+    }
+
+    ret
+}
+
+/// Apply `mutation` to every matching site below `top`, returning the
+/// number of rewritten sites.  Zero means `top` does not exercise it.
+pub fn apply(top: &mut Component, mutation: &Mutation) -> usize {
+    let mut count = 0;
+    walk_component(top, &mut |node| match (node, mutation.kind) {
+        (Node::Statement(Statement::If(x), _), MutationKind::InvertCondition)
+            if x.token == mutation.token =>
+        {
+            std::mem::swap(&mut x.true_side, &mut x.false_side);
+            count += 1;
+        }
+        (Node::Statement(Statement::Assign(x), false), MutationKind::ConstantAssign(ones))
+            if x.token == mutation.token =>
+        {
+            let width = x.width.unwrap_or(1);
+            let value = if ones {
+                Value::new_biguint(ValueBigUint::gen_mask(width), width, false)
+            } else {
+                Value::new(0, width, false)
+            };
+            x.expr = Expression::create_value(value, x.token);
+            count += 1;
+        }
+        (Node::Statement(x, true), MutationKind::DropReset) => {
+            if let Statement::Assign(assign) = x
+                && assign.token == mutation.token
+            {
+                *x = Statement::Null;
+                count += 1;
+            }
+        }
+        (Node::Expression(Expression::Binary(_, op, _, comptime)), MutationKind::Op(from, to))
+            if comptime.token == mutation.token && *op == from =>
+        {
+            *op = to;
+            count += 1;
+        }
+        (
+            Node::Expression(Expression::Ternary(_, x, y, comptime)),
+            MutationKind::InvertCondition,
+        ) if comptime.token == mutation.token => {
+            std::mem::swap(x, y);
+            count += 1;
+        }
+        _ => (),
+    });
+    count
+}
+
+fn walk_component(component: &mut Component, f: &mut dyn FnMut(Node)) {
+    let Component::Module(module) = component else {
+        return;
+    };
+    for decl in &mut module.declarations {
+        match decl {
+            Declaration::Comb(x) => walk_statements(&mut x.statements, false, f),
+            Declaration::Ff(x) => walk_statements(&mut x.statements, false, f),
+            Declaration::Inst(x) => walk_component(Arc::make_mut(&mut x.component), f),
+            _ => (),
+        }
+    }
+}
+
+fn walk_statements(statements: &mut [Statement], in_reset: bool, f: &mut dyn FnMut(Node)) {
+    for x in statements {
+        f(Node::Statement(x, in_reset));
+        match x {
+            Statement::Assign(x) => walk_expression(&mut x.expr, f),
+            Statement::If(x) => {
+                walk_expression(&mut x.cond, f);
+                walk_statements(&mut x.true_side, in_reset, f);
+                walk_statements(&mut x.false_side, in_reset, f);
+            }
+            Statement::IfReset(x) => {
+                walk_statements(&mut x.true_side, true, f);
+                walk_statements(&mut x.false_side, in_reset, f);
+            }
+            Statement::Case(x) => {
+                walk_expression(&mut x.case_target, f);
+                for arm in &mut x.arms {
+                    walk_statements(&mut arm.body, in_reset, f);
+                }
+                walk_statements(&mut x.default, in_reset, f);
+            }
+            Statement::For(x) => walk_statements(&mut x.body, in_reset, f),
+            Statement::Fork(x) => {
+                for thread in &mut x.threads {
+                    walk_statements(thread, in_reset, f);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn walk_expression(expr: &mut Expression, f: &mut dyn FnMut(Node)) {
+    f(Node::Expression(expr));
+    match expr {
+        Expression::Unary(_, x, _) => walk_expression(x, f),
+        Expression::Binary(x, _, y, _) => {
+            walk_expression(x, f);
+            walk_expression(y, f);
+        }
+        Expression::Ternary(x, y, z, _) => {
+            walk_expression(x, f);
+            walk_expression(y, f);
+            walk_expression(z, f);
+        }
+        Expression::Concatenation(x, _) => {
+            for (x, y) in x {
+                walk_expression(x, f);
+                if let Some(y) = y {
+                    walk_expression(y, f);
+                }
+            }
+        }
+        Expression::StructConstructor(_, x, _) => {
+            for (_, x) in x {
+                walk_expression(x, f);
+            }
+        }
+        _ => (),
+    }
+}
@@ -1,4 +1,5 @@
 use crate::AnalyzerError;
+use crate::attribute::JoinItem;
 use crate::conv::Context;
 use crate::ir::assign_table::{AssignContext, AssignTable};
 use crate::ir::ff_table::AssignTarget;
@@ -34,11 +35,21 @@ pub enum Statement {
     SystemFunctionCall(Box<SystemFunctionCall>),
     FunctionCall(Box<FunctionCall>),
     TbMethodCall(TbMethodCall),
+    Fork(ForkStatement),
     Break,
     Unsupported(TokenRange),
     Null,
 }
 
+/// A `#[fork]` block: each item of the block runs as a thread of its own,
+/// and the block resumes as `join` says.
+#[derive(Clone)]
+pub struct ForkStatement {
+    pub join: JoinItem,
+    pub threads: Vec<Vec<Statement>>,
+    pub token: TokenRange,
+}
+
 #[derive(Clone)]
 pub struct ForStatement {
     pub var_id: VarId,
@@ -258,6 +269,7 @@ pub struct TbMethodCall {
     /// Signedness of the returned value. Only `$tb::random::<iN>` returns a
     /// signed value; component/file methods leave this `false`.
     pub ret_signed: bool,
+    pub token: TokenRange,
 }
 
 #[derive(Clone)]
@@ -301,7 +313,136 @@ pub enum TbMethod {
         width: u32,
         signed: bool,
     },
+    /// `get_dist(v0, w0, v1, w1, ...)`: `values[i]` with probability
+    /// proportional to `weights[i]`.
+    RandomGetDist {
+        values: Vec<Expression>,
+        weights: Vec<Expression>,
+        width: u32,
+        signed: bool,
+    },
+    /// `get_inside(v0, v1, ...)`: one of the listed values, uniformly.
+    RandomGetInside {
+        values: Vec<Expression>,
+        width: u32,
+        signed: bool,
+    },
+    /// `get_cyclic(min, max)`: every value of `[min, max]` once, in random
+    /// order, before any repeats (`randc`).
+    RandomGetCyclic {
+        min: Expression,
+        max: Expression,
+        width: u32,
+        signed: bool,
+    },
+    /// `get_where(min, max, cond, others...)`: a value in `[min, max]` for
+    /// which `cond` holds once it is assigned to the destination. Each of
+    /// `others` (whole variables) is drawn from the same range together with
+    /// the destination, so `cond` may relate them.
+    RandomGetWhere {
+        min: Expression,
+        max: Expression,
+        cond: Expression,
+        others: Vec<AssignDestination>,
+        width: u32,
+        signed: bool,
+    },
     RandomGetSeed,
+    /// `$tb::queue::<T>` methods: a FIFO of `T` values. `pop`/`peek`
+    /// (`QueuePop`) fail the test on an empty queue.
+    QueuePush {
+        value: Expression,
+        width: u32,
+        signed: bool,
+    },
+    QueuePop {
+        peek: bool,
+        width: u32,
+        signed: bool,
+    },
+    QueueSize,
+    QueueEmpty,
+    /// `$tb::scoreboard::<T>` methods. `observe` must match the oldest
+    /// expected item (`ordered`), `observe_any` any pending one; items still
+    /// expected when the test ends are reported as unmatched.
+    ScoreboardExpect {
+        value: Expression,
+        width: u32,
+        signed: bool,
+    },
+    ScoreboardObserve {
+        value: Expression,
+        ordered: bool,
+        width: u32,
+        signed: bool,
+    },
+    ScoreboardPending,
+    /// `$tb::wave` methods: resume/suspend waveform dumping.
+    WaveStart,
+    WaveStop,
+    /// `$tb::checkpoint` methods. `fork(n)` runs the rest of the initial
+    /// block `n` times from one snapshot; `branch()` reads the current run's
+    /// index; `save`/`load` write and restore a snapshot file.
+    CheckpointFork {
+        count: Expression,
+    },
+    CheckpointBranch,
+    CheckpointSave {
+        name: SystemFunctionInput,
+    },
+    CheckpointLoad {
+        name: SystemFunctionInput,
+    },
+    /// `$tb::force` methods. `force` holds `target` at `value`, ignoring its
+    /// drivers, until `release`; `deposit` writes `value` once and leaves
+    /// the drivers in charge.
+    Force {
+        target: Expression,
+        value: Expression,
+    },
+    Release {
+        target: Expression,
+    },
+    Deposit {
+        target: Expression,
+        value: Expression,
+    },
+    /// `$tb::coverage` methods. `point` declares a coverpoint with automatic
+    /// bins, `bins`/`range` add value and range bins to one, `cross` crosses
+    /// coverpoints, and `sample` records one value per named coverpoint.
+    CoverPoint {
+        name: SystemFunctionInput,
+    },
+    CoverBins {
+        name: SystemFunctionInput,
+        values: Vec<Expression>,
+    },
+    CoverRange {
+        name: SystemFunctionInput,
+        min: Expression,
+        max: Expression,
+        count: Option<Expression>,
+    },
+    CoverCross {
+        name: SystemFunctionInput,
+        points: Vec<SystemFunctionInput>,
+    },
+    CoverSample {
+        names: Vec<SystemFunctionInput>,
+        values: Vec<Expression>,
+    },
+    /// `clk.wait_until(cond[, within])`: waits for clock edges until `cond`
+    /// holds, failing after `within` cycles.
+    ClockWaitUntil {
+        cond: Expression,
+        within: Option<Expression>,
+        period: Option<Box<Expression>>,
+    },
+    /// `th.wait_until(cond)`: waits, without driving a clock, until another
+    /// thread makes `cond` hold.
+    ThreadWaitUntil {
+        cond: Expression,
+    },
 }
 
 impl TbMethod {
@@ -313,7 +454,16 @@ impl TbMethod {
             TbMethod::Component { .. }
                 | TbMethod::RandomGet { .. }
                 | TbMethod::RandomGetRange { .. }
+                | TbMethod::RandomGetDist { .. }
+                | TbMethod::RandomGetInside { .. }
+                | TbMethod::RandomGetCyclic { .. }
+                | TbMethod::RandomGetWhere { .. }
                 | TbMethod::RandomGetSeed
+                | TbMethod::QueuePop { .. }
+                | TbMethod::QueueSize
+                | TbMethod::QueueEmpty
+                | TbMethod::ScoreboardPending
+                | TbMethod::CheckpointBranch
         )
     }
 }
@@ -363,6 +513,12 @@ impl Statement {
                 ControlFlow::Continue
             }
             Statement::TbMethodCall(_) => ControlFlow::Continue,
+            Statement::Fork(x) => {
+                for s in x.threads.iter().flatten() {
+                    s.eval_value(context);
+                }
+                ControlFlow::Continue
+            }
             Statement::Break => ControlFlow::Break,
             Statement::Unsupported(_) => ControlFlow::Continue,
             Statement::Null => ControlFlow::Continue,
@@ -397,6 +553,16 @@ impl Statement {
                 if let Some(ret) = &x.ret {
                     ret.eval_assign(context, assign_table, assign_context);
                 }
+                if let TbMethod::RandomGetWhere { others, .. } = &x.method {
+                    for dst in others {
+                        dst.eval_assign(context, assign_table, assign_context);
+                    }
+                }
+            }
+            Statement::Fork(x) => {
+                for s in x.threads.iter().flatten() {
+                    s.eval_assign(context, assign_table, assign_context, base_tables);
+                }
             }
             Statement::Break => (),
             Statement::Unsupported(_) => (),
@@ -417,6 +583,11 @@ impl Statement {
                     s.gather_ff(context, table, decl);
                 }
             }
+            Statement::Fork(x) => {
+                for s in x.threads.iter().flatten() {
+                    s.gather_ff(context, table, decl);
+                }
+            }
             Statement::TbMethodCall(_)
             | Statement::Break
             | Statement::Unsupported(_)
@@ -456,6 +627,11 @@ impl Statement {
                     s.gather_ff_comb_assign(context, table, decl);
                 }
             }
+            Statement::Fork(x) => {
+                for s in x.threads.iter().flatten() {
+                    s.gather_ff_comb_assign(context, table, decl);
+                }
+            }
             _ => (),
         }
     }
@@ -473,6 +649,11 @@ impl Statement {
                     s.set_index(index);
                 }
             }
+            Statement::Fork(x) => {
+                for s in x.threads.iter_mut().flatten() {
+                    s.set_index(index);
+                }
+            }
             Statement::TbMethodCall(_) | Statement::Break => (),
             Statement::Unsupported(_) => (),
             Statement::Null => (),
@@ -523,7 +704,103 @@ impl fmt::Display for Statement {
                 TbMethod::RandomGetRange { min, max, .. } => {
                     write!(f, "{}.get_range({min}, {max});", x.inst)
                 }
+                TbMethod::RandomGetDist {
+                    values, weights, ..
+                } => {
+                    let args_str: Vec<_> = values
+                        .iter()
+                        .zip(weights)
+                        .map(|(v, w)| format!("{v}, {w}"))
+                        .collect();
+                    write!(f, "{}.get_dist({});", x.inst, args_str.join(", "))
+                }
+                TbMethod::RandomGetInside { values, .. } => {
+                    let args_str: Vec<_> = values.iter().map(|a| format!("{a}")).collect();
+                    write!(f, "{}.get_inside({});", x.inst, args_str.join(", "))
+                }
+                TbMethod::RandomGetCyclic { min, max, .. } => {
+                    write!(f, "{}.get_cyclic({min}, {max});", x.inst)
+                }
+                TbMethod::RandomGetWhere {
+                    min,
+                    max,
+                    cond,
+                    others,
+                    ..
+                } => {
+                    let args_str: Vec<_> = others.iter().map(|a| format!(", {a}")).collect();
+                    write!(
+                        f,
+                        "{}.get_where({min}, {max}, {cond}{});",
+                        x.inst,
+                        args_str.concat()
+                    )
+                }
                 TbMethod::RandomGetSeed => write!(f, "{}.get_seed();", x.inst),
+                TbMethod::QueuePush { value, .. } => write!(f, "{}.push({value});", x.inst),
+                TbMethod::QueuePop { peek, .. } => {
+                    let method = if *peek { "peek" } else { "pop" };
+                    write!(f, "{}.{method}();", x.inst)
+                }
+                TbMethod::QueueSize => write!(f, "{}.size();", x.inst),
+                TbMethod::QueueEmpty => write!(f, "{}.empty();", x.inst),
+                TbMethod::ScoreboardExpect { value, .. } => {
+                    write!(f, "{}.expect({value});", x.inst)
+                }
+                TbMethod::ScoreboardObserve { value, ordered, .. } => {
+                    let method = if *ordered { "observe" } else { "observe_any" };
+                    write!(f, "{}.{method}({value});", x.inst)
+                }
+                TbMethod::ScoreboardPending => write!(f, "{}.pending();", x.inst),
+                TbMethod::WaveStart => write!(f, "{}.start();", x.inst),
+                TbMethod::WaveStop => write!(f, "{}.stop();", x.inst),
+                TbMethod::CheckpointFork { count } => write!(f, "{}.fork({count});", x.inst),
+                TbMethod::CheckpointBranch => write!(f, "{}.branch();", x.inst),
+                TbMethod::CheckpointSave { name } => write!(f, "{}.save({name});", x.inst),
+                TbMethod::CheckpointLoad { name } => write!(f, "{}.load({name});", x.inst),
+                TbMethod::Force { target, value } => {
+                    write!(f, "{}.force({target}, {value});", x.inst)
+                }
+                TbMethod::Release { target } => write!(f, "{}.release({target});", x.inst),
+                TbMethod::Deposit { target, value } => {
+                    write!(f, "{}.deposit({target}, {value});", x.inst)
+                }
+                TbMethod::CoverPoint { name } => write!(f, "{}.point({name});", x.inst),
+                TbMethod::CoverBins { name, values } => {
+                    let args_str: Vec<_> = values.iter().map(|a| format!(", {a}")).collect();
+                    write!(f, "{}.bins({name}{});", x.inst, args_str.concat())
+                }
+                TbMethod::CoverRange {
+                    name,
+                    min,
+                    max,
+                    count,
+                } => {
+                    let count = count.as_ref().map(|x| format!(", {x}")).unwrap_or_default();
+                    write!(f, "{}.range({name}, {min}, {max}{count});", x.inst)
+                }
+                TbMethod::CoverCross { name, points } => {
+                    let args_str: Vec<_> = points.iter().map(|a| format!(", {a}")).collect();
+                    write!(f, "{}.cross({name}{});", x.inst, args_str.concat())
+                }
+                TbMethod::CoverSample { names, values } => {
+                    let args_str: Vec<_> = names
+                        .iter()
+                        .zip(values)
+                        .map(|(n, v)| format!("{n}, {v}"))
+                        .collect();
+                    write!(f, "{}.sample({});", x.inst, args_str.join(", "))
+                }
+                TbMethod::ClockWaitUntil { cond, within, .. } => {
+                    let within = within
+                        .as_ref()
+                        .map(|x| format!(", {x}"))
+                        .unwrap_or_default();
+                    write!(f, "{}.wait_until({cond}{within});", x.inst)
+                }
+                TbMethod::ThreadWaitUntil { cond } => {
+                    write!(f, "{}.wait_until({cond});", x.inst)
+                }
             },
             Statement::For(x) => {
                 let range_op = if let ForRange::Reverse { .. } = &x.range {
@@ -576,6 +853,17 @@ impl fmt::Display for Statement {
                 }
                 write!(f, "}}")
             }
+            Statement::Fork(x) => {
+                writeln!(f, "#[fork({})] {{", x.join)?;
+                for thread in &x.threads {
+                    writeln!(f, "  {{")?;
+                    for s in thread {
+                        writeln!(f, "    {s}")?;
+                    }
+                    writeln!(f, "  }}")?;
+                }
+                write!(f, "}}")
+            }
             Statement::Break => "break;".fmt(f),
             Statement::Unsupported(_) => "/* unsupported */".fmt(f),
             Statement::Null => "".fmt(f),
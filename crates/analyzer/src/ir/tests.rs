@@ -1,5 +1,5 @@
 use crate::conv::Context;
-use crate::ir::Ir;
+use crate::ir::{Ir, Op};
 use crate::{Analyzer, attribute_table, symbol_table};
 use similar::{ChangeTag, TextDiff};
 use veryl_metadata::Metadata;
@@ -1546,8 +1546,8 @@ fn interface_function() {
     var11 = var3;
   }
   func var13(if_a.FuncA) -> var14 {
-    var15 = var1;
-    var14 = var1;
+    var15 = var0;
+    var14 = var0;
   }
 
   comb {
@@ -3336,3 +3336,76 @@ fn const_fold_switch_arm() {
 
     check_ir(code, exp);
 }
+
+#[test]
+fn mutation_sites() {
+    use crate::ir::Component;
+    use crate::ir::mutation::{self, MutationKind};
+
+    let code = r#"
+    module ModuleA (
+        clk: input  clock    ,
+        rst: input  reset    ,
+        a  : input  logic<8> ,
+        b  : output logic<8> ,
+    ) {
+        always_ff {
+            if_reset {
+                b = 0;
+            } else if a == 1 {
+                b = b + a;
+            }
+        }
+    }
+    module ModuleB {
+        var clk: clock;
+        var rst: reset;
+        var a  : logic<8>;
+        var b  : logic<8>;
+        assign a = 1 + 2;
+        inst u: ModuleA (clk, rst, a, b);
+    }
+    "#;
+
+    symbol_table::clear();
+    attribute_table::clear();
+
+    let metadata = Metadata::create_default("prj").unwrap();
+    let parser = Parser::parse(code, &"").unwrap();
+    let analyzer = Analyzer::new(&metadata);
+    let mut context = Context::default();
+    let mut ir = Ir::default();
+    analyzer.analyze_pass1("prj", &parser.veryl);
+    Analyzer::analyze_post_pass1();
+    analyzer.analyze_pass2(&parser.veryl, &mut context, Some(&mut ir));
+
+    let top = resource_table::insert_str("ModuleB");
+    let mut top = ir
+        .components
+        .into_iter()
+        .find(|x| matches!(x, Component::Module(x) if x.name == top))
+        .unwrap();
+
+    // The testbench's own `1 + 2` is not a site.
+    let mutations = mutation::collect(&top, &|_| true);
+    let kinds: Vec<_> = mutations.iter().map(|x| x.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            MutationKind::DropReset,
+            MutationKind::InvertCondition,
+            MutationKind::Op(Op::Eq, Op::Ne),
+            MutationKind::ConstantAssign(false),
+            MutationKind::ConstantAssign(true),
+            MutationKind::Op(Op::Add, Op::Sub),
+        ]
+    );
+
+    let before = top.to_string();
+    let add = mutations.last().unwrap();
+    assert_eq!(mutation::apply(&mut top, add), 1);
+    let after = top.to_string();
+    assert_ne!(before, after);
+    assert!(after.contains(" - "));
+    assert!(!mutation::collect(&top, &|_| true).contains(add));
+}
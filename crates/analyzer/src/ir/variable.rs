@@ -840,7 +840,7 @@ impl fmt::Display for VarSelect {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum VarKind {
     Param,
     Const,
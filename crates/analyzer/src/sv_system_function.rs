@@ -108,6 +108,16 @@ pub fn sv_system_functions() -> Vec<SvSystemFunction> {
             // it has optional args but not supported
             &[],
         ),
+        SvSystemFunction::new(
+            "$assert_implies",
+            // it has optional args but not supported
+            &[],
+        ),
+        SvSystemFunction::new(
+            "$assert_eventually",
+            // it has optional args but not supported
+            &[],
+        ),
         // Simulation time system functions
         SvSystemFunction::new("$time", &[]),
         SvSystemFunction::new("$stime", &[]),
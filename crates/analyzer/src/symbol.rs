@@ -1,6 +1,6 @@
 use crate::BigUint;
 use crate::HashMap;
-use crate::attribute::EnumEncodingItem;
+use crate::attribute::{EnumEncodingItem, GoldenItem, MatrixItem, TimeoutItem};
 use crate::conv::Context;
 use crate::conv::utils::{TypePosition, eval_generic_expr, eval_size, eval_type};
 use crate::definition_table::DefinitionId;
@@ -913,11 +913,22 @@ impl Symbol {
             SymbolKind::GenericInstance(x) => symbol_table::get(x.base)
                 .map(|x| x.is_variable_type())
                 .unwrap_or(false),
-            // `$tb::file` and `$tb::random` are testbench resources declared as `var`.
+            // `$tb::file`, `$tb::random`, `$tb::wave`, `$tb::checkpoint`,
+            // `$tb::force`, `$tb::coverage`, `$tb::thread`, `$tb::queue` and
+            // `$tb::scoreboard` are testbench resources declared as `var`.
             SymbolKind::TbComponent(x) => {
                 matches!(
                     x.kind,
-                    TbComponentKind::File | TbComponentKind::Random | TbComponentKind::External(_)
+                    TbComponentKind::File
+                        | TbComponentKind::Random
+                        | TbComponentKind::Wave
+                        | TbComponentKind::Checkpoint
+                        | TbComponentKind::Force
+                        | TbComponentKind::Coverage
+                        | TbComponentKind::Thread
+                        | TbComponentKind::Queue
+                        | TbComponentKind::Scoreboard
+                        | TbComponentKind::External(_)
                 )
             }
             _ => false,
@@ -2919,6 +2930,48 @@ pub struct TestProperty {
     pub path: PathId,
     pub top: Option<StrId>,
     pub ignored: bool,
+    /// Limits from `#[timeout]`; unset fields fall back to `[test]`.
+    pub timeout: TimeoutItem,
+    /// Parameter sets from `#[matrix]`; each combination runs as its own
+    /// test case.
+    pub matrix: Vec<MatrixItem>,
+    /// Golden files from `#[golden]`.
+    pub golden: Vec<GoldenItem>,
+    /// `#[matrix]` cases which failed to elaborate; left out of the test list.
+    pub rejected_cases: Vec<StrId>,
+}
+
+impl TestProperty {
+    /// The cases of a `#[matrix]` test named `name`, e.g.
+    /// `name[DEPTH=4,WIDTH=8]`, with the parameter values of each: the
+    /// cartesian product of the matrix, the last parameter varying fastest.
+    pub fn cases(&self, name: StrId) -> Vec<(StrId, Vec<(StrId, i128)>)> {
+        if self.matrix.is_empty() {
+            return vec![];
+        }
+
+        let mut sets: Vec<Vec<(StrId, i128)>> = vec![vec![]];
+        for item in &self.matrix {
+            sets = sets
+                .into_iter()
+                .flat_map(|set| {
+                    item.values.iter().map(move |x| {
+                        let mut set = set.clone();
+                        set.push((item.name, *x));
+                        set
+                    })
+                })
+                .collect();
+        }
+
+        sets.into_iter()
+            .map(|set| {
+                let args: Vec<_> = set.iter().map(|(k, v)| format!("{k}={v}")).collect();
+                let case = format!("{name}[{}]", args.join(","));
+                (resource_table::insert_str(&case), set)
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2930,6 +2983,21 @@ pub enum TbComponentKind {
     /// element type is a generic argument (see `TbComponentProperty`), so it
     /// is resolved through the normal generic pipeline.
     Random,
+    /// Waveform dump control declared as `var w: $tb::wave;`.
+    Wave,
+    /// Simulation snapshot handle declared as `var cp: $tb::checkpoint;`.
+    Checkpoint,
+    /// Signal override handle declared as `var f: $tb::force;`.
+    Force,
+    /// Functional coverage group declared as `var cov: $tb::coverage;`.
+    Coverage,
+    /// Thread synchronization handle declared as `var th: $tb::thread;`.
+    Thread,
+    /// FIFO of `T` values declared as `var q: $tb::queue::<T>;`.
+    Queue,
+    /// Expected/observed matcher of `T` values declared as
+    /// `var sb: $tb::scoreboard::<T>;`.
+    Scoreboard,
     /// User-defined verification component declared in `[[components]]` of
     /// Veryl.toml; the payload is the component name.
     External(StrId),
@@ -2942,6 +3010,13 @@ impl std::fmt::Display for TbComponentKind {
             TbComponentKind::ResetGen => write!(f, "reset_gen"),
             TbComponentKind::File => write!(f, "file"),
             TbComponentKind::Random => write!(f, "random"),
+            TbComponentKind::Wave => write!(f, "wave"),
+            TbComponentKind::Checkpoint => write!(f, "checkpoint"),
+            TbComponentKind::Force => write!(f, "force"),
+            TbComponentKind::Coverage => write!(f, "coverage"),
+            TbComponentKind::Thread => write!(f, "thread"),
+            TbComponentKind::Queue => write!(f, "queue"),
+            TbComponentKind::Scoreboard => write!(f, "scoreboard"),
             TbComponentKind::External(name) => write!(f, "{name}"),
         }
     }
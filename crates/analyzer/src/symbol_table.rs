@@ -1926,6 +1926,7 @@ impl SymbolTable {
         self.skip_generic_args = false;
     }
 
+    #[allow(clippy::collapsible_match)]
     fn get_package(
         &self,
         symbol: &Symbol,
@@ -2546,16 +2547,35 @@ impl SymbolTable {
         ret
     }
 
+    /// Each `#[matrix]` case is a test of its own, whose top is the
+    /// component elaborated for it under the case name.
     fn get_tests(&self, project_name: &str) -> Vec<(StrId, TestProperty)> {
         self.symbol_table
             .values()
             .filter(|s| s.namespace.to_string() == project_name)
-            .filter_map(|symbol| match &symbol.kind {
+            .flat_map(|symbol| match &symbol.kind {
                 SymbolKind::Module(x) if x.test.is_some() => {
-                    Some((symbol.token.text, x.test.clone().unwrap()))
+                    let test = x.test.clone().unwrap();
+                    let cases = test.cases(symbol.token.text);
+                    if cases.is_empty() {
+                        vec![(symbol.token.text, test)]
+                    } else {
+                        cases
+                            .into_iter()
+                            .filter(|(case, _)| !test.rejected_cases.contains(case))
+                            .map(|(case, _)| {
+                                let test = TestProperty {
+                                    top: Some(case),
+                                    matrix: vec![],
+                                    ..test.clone()
+                                };
+                                (case, test)
+                            })
+                            .collect()
+                    }
                 }
-                SymbolKind::Test(x) => Some((symbol.token.text, x.clone())),
-                _ => None,
+                SymbolKind::Test(x) => vec![(symbol.token.text, x.clone())],
+                _ => vec![],
             })
             .collect()
     }
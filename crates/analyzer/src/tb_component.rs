@@ -23,8 +23,8 @@ fn builtin_type(kind: TypeKind) -> Type {
     }
 }
 
-/// A `Type` referring to the generic parameter `T` (`base` token) of
-/// `$tb::random`. Left unresolved (`symbol: None`) so the return type is
+/// A `Type` referring to the generic parameter `T` (`base` token) of a
+/// generic `$tb` component. Left unresolved (`symbol: None`) so the return type is
 /// resolved through the generic map at the call site, like any `-> T`.
 fn generic_param_type(base: Token) -> Type {
     let range: TokenRange = base.into();
@@ -216,6 +216,9 @@ fn insert_clock_gen(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
         &[("count", Direction::Input)],
         None,
     );
+    // `wait_until(cond[, within])`: optional argument, checked by
+    // `tb_method_call`.
+    insert_method(symbol_table, &ns, "wait_until", &[], None);
 }
 
 fn insert_reset_gen(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
@@ -252,12 +255,20 @@ fn insert_file(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
     insert_method(symbol_table, &ns, "flush", &[], None);
 }
 
-fn insert_random(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
-    let random_token = Token::builtin_text("random");
+/// Inserts a `$tb::<name>::<T>` component symbol, whose element type is
+/// the generic type parameter `T`, and returns its inner namespace together
+/// with the `T` token for the `-> T` method returns.
+fn insert_generic_component(
+    symbol_table: &mut SymbolTable,
+    tb_ns: &Namespace,
+    name: &str,
+    kind: TbComponentKind,
+) -> Option<(Namespace, Token)> {
+    let token = Token::builtin_text(name);
     let mut ns = tb_ns.clone();
-    ns.push(random_token.text);
+    ns.push(token.text);
 
-    // Synthesize the generic type parameter `T` inside the `$tb::random`
+    // Synthesize the generic type parameter `T` inside the component
     // namespace so member methods can return `-> T`, resolved through the
     // normal generic pipeline at the call site.
     let t_token = Token::builtin_text("T");
@@ -271,26 +282,34 @@ fn insert_random(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
         false,
         DocComment::default(),
     );
-    let Some(t_id) = symbol_table.insert(&t_token, t_symbol) else {
-        return;
-    };
+    let t_id = symbol_table.insert(&t_token, t_symbol)?;
 
-    let random_symbol = Symbol::new(
-        &random_token,
+    let symbol = Symbol::new(
+        &token,
         SymbolKind::TbComponent(TbComponentProperty {
-            kind: TbComponentKind::Random,
+            kind,
             generic_parameters: vec![t_id],
         }),
         tb_ns,
         true,
         DocComment::default(),
     );
-    let _ = symbol_table.insert(&random_token, random_symbol);
+    let _ = symbol_table.insert(&token, symbol);
+    Some((ns, t_token))
+}
+
+fn insert_random(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let Some((ns, t_token)) =
+        insert_generic_component(symbol_table, tb_ns, "random", TbComponentKind::Random)
+    else {
+        return;
+    };
 
-    // `seed`/`get`/`get_range` take value arguments handled by
-    // `tb_method_call`; the ports are placeholders. Return types drive the
-    // static typing: `get`/`get_range` return `T`, `get_seed`
-    // returns a 64-bit unsigned, `seed` returns nothing.
+    // `seed`/`get`/`get_range` and the constrained `get_*` methods take
+    // value arguments handled by `tb_method_call`; the ports are
+    // placeholders (`get_dist`/`get_inside` are variadic, like `write`).
+    // Return types drive the static typing: the `get*` methods return `T`,
+    // `get_seed` returns a 64-bit unsigned, `seed` returns nothing.
     insert_method(
         symbol_table,
         &ns,
@@ -312,6 +331,34 @@ fn insert_random(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
         &[("min", Direction::Input), ("max", Direction::Input)],
         Some(generic_param_type(t_token)),
     );
+    insert_method(
+        symbol_table,
+        &ns,
+        "get_dist",
+        &[],
+        Some(generic_param_type(t_token)),
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "get_inside",
+        &[],
+        Some(generic_param_type(t_token)),
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "get_cyclic",
+        &[("min", Direction::Input), ("max", Direction::Input)],
+        Some(generic_param_type(t_token)),
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "get_where",
+        &[],
+        Some(generic_param_type(t_token)),
+    );
     insert_method(
         symbol_table,
         &ns,
@@ -321,6 +368,185 @@ fn insert_random(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
     );
 }
 
+fn insert_queue(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let Some((ns, t_token)) =
+        insert_generic_component(symbol_table, tb_ns, "queue", TbComponentKind::Queue)
+    else {
+        return;
+    };
+    insert_method(
+        symbol_table,
+        &ns,
+        "push",
+        &[("value", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "pop",
+        &[],
+        Some(generic_param_type(t_token)),
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "peek",
+        &[],
+        Some(generic_param_type(t_token)),
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "size",
+        &[],
+        Some(builtin_type(TypeKind::U32)),
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "empty",
+        &[],
+        Some(builtin_type(TypeKind::Bit)),
+    );
+}
+
+fn insert_scoreboard(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let Some((ns, _)) = insert_generic_component(
+        symbol_table,
+        tb_ns,
+        "scoreboard",
+        TbComponentKind::Scoreboard,
+    ) else {
+        return;
+    };
+    // `observe` matches the oldest expected item, `observe_any` any of them.
+    insert_method(
+        symbol_table,
+        &ns,
+        "expect",
+        &[("value", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "observe",
+        &[("value", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "observe_any",
+        &[("value", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "pending",
+        &[],
+        Some(builtin_type(TypeKind::U32)),
+    );
+}
+
+fn insert_wave(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let ns = insert_component(symbol_table, tb_ns, "wave", TbComponentKind::Wave);
+    insert_method(symbol_table, &ns, "start", &[], None);
+    insert_method(symbol_table, &ns, "stop", &[], None);
+}
+
+fn insert_checkpoint(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let ns = insert_component(
+        symbol_table,
+        tb_ns,
+        "checkpoint",
+        TbComponentKind::Checkpoint,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "fork",
+        &[("count", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "branch",
+        &[],
+        Some(builtin_type(TypeKind::U32)),
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "save",
+        &[("name", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "load",
+        &[("name", Direction::Input)],
+        None,
+    );
+}
+
+fn insert_force(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let ns = insert_component(symbol_table, tb_ns, "force", TbComponentKind::Force);
+    insert_method(
+        symbol_table,
+        &ns,
+        "force",
+        &[("target", Direction::Input), ("value", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "release",
+        &[("target", Direction::Input)],
+        None,
+    );
+    insert_method(
+        symbol_table,
+        &ns,
+        "deposit",
+        &[("target", Direction::Input), ("value", Direction::Input)],
+        None,
+    );
+}
+
+fn insert_coverage(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let ns = insert_component(symbol_table, tb_ns, "coverage", TbComponentKind::Coverage);
+    // Only `point` has a fixed arity; the others are variadic and, like
+    // `write`, registered port-less for `tb_method_call` to check.
+    insert_method(
+        symbol_table,
+        &ns,
+        "point",
+        &[("name", Direction::Input)],
+        None,
+    );
+    insert_method(symbol_table, &ns, "bins", &[], None);
+    insert_method(symbol_table, &ns, "range", &[], None);
+    insert_method(symbol_table, &ns, "cross", &[], None);
+    insert_method(symbol_table, &ns, "sample", &[], None);
+}
+
+fn insert_thread(symbol_table: &mut SymbolTable, tb_ns: &Namespace) {
+    let ns = insert_component(symbol_table, tb_ns, "thread", TbComponentKind::Thread);
+    insert_method(
+        symbol_table,
+        &ns,
+        "wait_until",
+        &[("cond", Direction::Input)],
+        None,
+    );
+}
+
 pub fn insert_symbols(symbol_table: &mut SymbolTable, namespace: &Namespace) {
     let mut tb_ns = namespace.clone();
 
@@ -332,4 +558,11 @@ pub fn insert_symbols(symbol_table: &mut SymbolTable, namespace: &Namespace) {
     insert_reset_gen(symbol_table, &tb_ns);
     insert_file(symbol_table, &tb_ns);
     insert_random(symbol_table, &tb_ns);
+    insert_queue(symbol_table, &tb_ns);
+    insert_scoreboard(symbol_table, &tb_ns);
+    insert_wave(symbol_table, &tb_ns);
+    insert_checkpoint(symbol_table, &tb_ns);
+    insert_force(symbol_table, &tb_ns);
+    insert_coverage(symbol_table, &tb_ns);
+    insert_thread(symbol_table, &tb_ns);
 }
@@ -1,3 +1,4 @@
+use crate::analyzer_error::InvalidTestKind;
 use crate::conv::Context;
 use crate::ir::Ir;
 use crate::{Analyzer, AnalyzerError, attribute_table, symbol_table};
@@ -2664,6 +2665,96 @@ fn mismatch_attribute_args() {
             .iter()
             .any(|e| matches!(e, AnalyzerError::MismatchAttributeArgs { .. }))
     );
+
+    for args in [r#""#, r#"("10x")"#, r#"(cycles)"#, r#"("10", "20")"#] {
+        let code = format!(
+            r#"
+    #[test(test_mod)]
+    #[timeout{args}]
+    module test_mod {{}}
+    "#
+        );
+
+        let errors = analyze(&code);
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchAttributeArgs { .. })),
+            "{args}"
+        );
+    }
+
+    for args in [
+        r#""#,
+        r#"("W")"#,
+        r#"("W = 1, x")"#,
+        r#"("W = 1", "W = 2")"#,
+        r#"("W = 'hx")"#,
+        r#"("W = '1")"#,
+        r#"("W = 8'q1")"#,
+    ] {
+        let code = format!(
+            r#"
+    #[test(test_mod)]
+    #[matrix{args}]
+    module test_mod #(
+        param W: u32 = 1,
+    ) {{}}
+    "#
+        );
+
+        let errors = analyze(&code);
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchAttributeArgs { .. })),
+            "{args}"
+        );
+    }
+
+    for args in [r#""#, r#"(out)"#, r#"("a.log", "b.log", "c.log")"#] {
+        let code = format!(
+            r#"
+    #[test(test_mod)]
+    #[golden{args}]
+    module test_mod {{}}
+    "#
+        );
+
+        let errors = analyze(&code);
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchAttributeArgs { .. })),
+            "{args}"
+        );
+    }
+
+    let code = r#"
+    #[test(test_mod)]
+    #[timeout("10000", "1.5s")]
+    #[golden("test_mod.log")]
+    #[golden("test_mod.csv", "out.csv")]
+    module test_mod {}
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    // Kept on a non-native test too, so `veryl test` can warn about it.
+    let code = r#"
+    #[test(test_sv)]
+    #[golden("test_sv.log")]
+    embed (inline) sv {{{
+        module test_sv;
+        endmodule
+    }}}
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+    let tests = symbol_table::get_tests("prj");
+    assert_eq!(tests[0].1.golden.len(), 1);
 }
 
 #[test]
@@ -6878,6 +6969,69 @@ fn unknown_attribute() {
     assert!(matches!(errors[0], AnalyzerError::UnknownAttribute { .. }));
 }
 
+#[test]
+fn misplaced_attribute() {
+    let code = r#"
+    module ModuleA (
+        i_clk: input clock,
+        i_a  : input logic,
+    ) {
+        #[assert]
+        always_ff {
+            $assert(i_a);
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty(), "{errors:?}");
+
+    let code = r#"
+    #[assert]
+    module ModuleA {}
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::MisplacedAttribute { .. }
+    ));
+
+    let code = r#"
+    module ModuleA (
+        i_clk: input clock,
+        i_a  : input logic,
+    ) {
+        {
+            #[assert]
+            always_ff {
+                $assert(i_a);
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::MisplacedAttribute { .. }
+    ));
+
+    let code = r#"
+    interface InterfaceA {
+        #[assert]
+        var a: logic;
+        assign a = 1;
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::MisplacedAttribute { .. }
+    ));
+}
+
 #[test]
 fn invalid_embed() {
     let code = r#"
@@ -11492,6 +11646,131 @@ fn invalid_test() {
 
     let errors = analyze(code);
     assert!(matches!(errors[0], AnalyzerError::InvalidTest { .. }));
+
+    let code = r#"
+    #[test(test_mod)]
+    #[matrix("W = 1, 2", "DEPTH = 4")]
+    module test_mod #(
+        param W: u32 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::InvalidTest {
+            cause: InvalidTestKind::MatrixUndeclaredParameter,
+            ..
+        }
+    ));
+
+    let code = r#"
+    module ModuleA #(
+        param W: u32 = 1,
+    ) {}
+
+    #[test(test_mod, ModuleA)]
+    #[matrix("W = 1, 2")]
+    module test_mod #(
+        param W: u32 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::InvalidTest {
+            cause: InvalidTestKind::MatrixTopModule,
+            ..
+        }
+    ));
+
+    let code = r#"
+    #[matrix("W = 1, 2")]
+    module test_mod #(
+        param W: u32 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::InvalidTest {
+            cause: InvalidTestKind::MatrixWithoutTest,
+            ..
+        }
+    ));
+
+    let code = r#"
+    #[test(test_mod)]
+    #[matrix("W = 1, 2")]
+    #[golden("golden/test_mod.log")]
+    module test_mod #(
+        param W: u32 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::InvalidTest {
+            cause: InvalidTestKind::MatrixWithGolden,
+            ..
+        }
+    ));
+
+    // An overflowing case is reported and left out; the next case still runs.
+    let code = r#"
+    #[test(test_mod)]
+    #[matrix("W = 300, 8'h02")]
+    module test_mod #(
+        param W: u8 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze_with_ir(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::InvalidTest {
+            cause: InvalidTestKind::MatrixValueOverflow,
+            ..
+        }
+    ));
+    let tests: Vec<_> = symbol_table::get_tests("prj")
+        .into_iter()
+        .map(|(x, _)| x.to_string())
+        .collect();
+    assert_eq!(tests, ["test_mod[W=2]"]);
+
+    let code = r#"
+    #[test(test_mod)]
+    #[matrix("W = -1, -128, 'sh7f")]
+    module test_mod #(
+        param W: i8 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze_with_ir(code);
+    assert!(errors.is_empty());
+    assert_eq!(symbol_table::get_tests("prj").len(), 3);
+
+    let code = r#"
+    #[test(test_mod)]
+    #[matrix("W = -129")]
+    module test_mod #(
+        param W: i8 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze_with_ir(code);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::InvalidTest {
+            cause: InvalidTestKind::MatrixValueOverflow,
+            ..
+        }
+    ));
+    assert!(symbol_table::get_tests("prj").is_empty());
 }
 
 #[test]
@@ -18299,6 +18578,7 @@ fn tb_random_analyze() {
         var b: $tb::random::<bbool>;
         var x: u32;
         var y: i16;
+        var w: u32;
         var sd: u64;
         initial {
             r.seed(42);
@@ -18307,6 +18587,11 @@ fn tb_random_analyze() {
             sd = r.get_seed();
             y = s.get();
             let z: u32 = r.get() + 1;
+            x = r.get_dist(1, 10, 2, 90);
+            x = r.get_inside(1, 2, 3);
+            y = s.get_cyclic(-4, 4);
+            x = r.get_where(0, 100, x != z);
+            x = r.get_where(0, 100, x != w, w);
             $finish();
         }
     }
@@ -18321,6 +18606,433 @@ fn tb_random_analyze() {
     );
 }
 
+#[test]
+fn tb_coverage_analyze() {
+    let code = r#"
+    #[test(test_cov)]
+    module test_cov {
+        var cov: $tb::coverage;
+        var op: logic<2>;
+        initial {
+            cov.point("op");
+            cov.bins("op", 0, 1);
+            cov.range("op", 2, 3);
+            cov.range("op", 0, 3, 2);
+            cov.point("op2");
+            cov.cross("op_x_op2", "op", "op2");
+            op = 1;
+            cov.sample("op", op, "op2", op + 1);
+            $finish();
+        }
+    }
+    "#;
+    let errors = analyze(code);
+    assert!(errors.is_empty(), "{errors:?}");
+
+    // Names are string literals; `sample` takes name/value pairs and
+    // `cross` at least two coverpoints.
+    for call in [
+        "cov.point(op)",
+        r#"cov.sample("op")"#,
+        r#"cov.cross("x", "op")"#,
+        r#"cov.range("op", 0)"#,
+        r#"cov.bins("op")"#,
+    ] {
+        let code = format!(
+            r#"
+    #[test(test_cov)]
+    module test_cov {{
+        var cov: $tb::coverage;
+        var op: logic<2>;
+        initial {{
+            {call};
+            $finish();
+        }}
+    }}
+    "#
+        );
+        let errors = analyze(&code);
+        assert!(
+            errors.iter().any(|e| matches!(
+                e,
+                AnalyzerError::MismatchFunctionArity { .. } | AnalyzerError::MismatchType { .. }
+            )),
+            "{call}: {errors:?}"
+        );
+    }
+}
+
+#[test]
+fn tb_random_constrained_arity() {
+    // `get_dist` takes value/weight pairs and `get_inside` at least one value.
+    for call in [
+        "r.get_dist(1, 10, 2)",
+        "r.get_inside()",
+        "r.get_where(0, 1)",
+    ] {
+        let code = format!(
+            r#"
+    #[test(test_random)]
+    module test_random {{
+        var r: $tb::random::<u32>;
+        var x: u32;
+        initial {{
+            x = {call};
+            $finish();
+        }}
+    }}
+    "#
+        );
+        let errors = analyze(&code);
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchFunctionArity { .. })),
+            "{call}: {errors:?}"
+        );
+    }
+
+    // The variables drawn with the destination must be writable as a whole.
+    for call in [
+        "r.get_where(0, 1, x != y, y + 1)",
+        "r.get_where(0, 1, 1, y[0])",
+    ] {
+        let code = format!(
+            r#"
+    #[test(test_random)]
+    module test_random {{
+        var r: $tb::random::<u32>;
+        var x: u32;
+        var y: u32;
+        initial {{
+            x = {call};
+            $finish();
+        }}
+    }}
+    "#
+        );
+        let errors = analyze(&code);
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchFunctionArg { .. })),
+            "{call}: {errors:?}"
+        );
+    }
+}
+
+#[test]
+fn tb_queue_scoreboard_analyze() {
+    let code = |call: &str| {
+        format!(
+            r#"
+    #[test(test_queue)]
+    module test_queue {{
+        gen byte_t: type = logic<8>;
+
+        var q : $tb::queue::<byte_t>;
+        var sb: $tb::scoreboard::<byte_t>;
+        var x : logic<8>;
+        var n : u32;
+        var e : bit;
+        initial {{
+            {call};
+            $finish();
+        }}
+    }}
+    "#
+        )
+    };
+
+    let errors = analyze(&code(
+        "x = 1;
+            q.push(x + 1);
+            x = q.peek();
+            x = q.pop();
+            n = q.size();
+            e = q.empty();
+            sb.expect(8'hff);
+            sb.observe(x);
+            sb.observe_any(x + e);
+            n = sb.pending() + n;
+            $display(\"%d\", q.empty())",
+    ));
+    assert!(errors.is_empty(), "{errors:?}");
+
+    // Items wider than `T` would be silently truncated.
+    for call in ["q.push(9'h100)", "sb.expect(n)", "sb.observe(x, x)"] {
+        let errors = analyze(&code(call));
+        assert!(
+            errors.iter().any(|e| matches!(
+                e,
+                AnalyzerError::MismatchAssignment { .. }
+                    | AnalyzerError::MismatchFunctionArity { .. }
+            )),
+            "{call}: {errors:?}"
+        );
+    }
+}
+
+#[test]
+fn tb_fork_analyze() {
+    let code = |attr: &str, body: &str| {
+        format!(
+            r#"
+    #[test(test_fork)]
+    module test_fork {{
+        inst clk: $tb::clock_gen;
+        var th: $tb::thread;
+        var a: logic;
+        initial {{
+            a = 0;
+            {attr}
+            block {{
+                {body}
+                block {{
+                    clk.next(2);
+                    a = 1;
+                }}
+            }}
+            $finish();
+        }}
+    }}
+    "#
+        )
+    };
+
+    for (attr, body) in [
+        ("#[fork]", "th.wait_until(a);"),
+        ("#[fork(join)]", "th.wait_until(a); clk.wait_until(a, 4);"),
+        ("#[fork(join_any)]", "th.wait_until(a); clk.wait_until(a);"),
+    ] {
+        let errors = analyze(&code(attr, body));
+        assert!(errors.is_empty(), "{attr}: {errors:?}");
+    }
+
+    let errors = analyze(&code("#[fork(join_none)]", ""));
+    assert!(
+        errors
+            .iter()
+            .any(|e| matches!(e, AnalyzerError::MismatchAttributeArgs { .. })),
+        "{errors:?}"
+    );
+
+    for body in [
+        "th.wait_until();",
+        "clk.wait_until(a, 1, 2);",
+        "th.wait_until(a, 1);",
+    ] {
+        let errors = analyze(&code("#[fork]", body));
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchFunctionArity { .. })),
+            "{body}: {errors:?}"
+        );
+    }
+
+    // Threads only exist in the initial block of a test module.
+    let code = r#"
+    module ModuleA (
+        clk: input clock,
+        a  : output logic,
+    ) {
+        always_ff {
+            #[fork]
+            block {
+                a = 1;
+            }
+        }
+    }
+    "#;
+    let errors = analyze(code);
+    assert!(
+        errors
+            .iter()
+            .any(|e| matches!(e, AnalyzerError::InvalidStatement { .. })),
+        "{errors:?}"
+    );
+}
+
+#[test]
+fn temporal_assertion_analyze() {
+    let code = |body: &str| {
+        format!(
+            r#"
+    module ModuleA (
+        clk: input clock,
+        rst: input reset,
+        req: input logic,
+        ack: input logic,
+    ) {{
+        var r: logic;
+        always_ff {{
+            if_reset {{
+                r = 0;
+            }} else {{
+                r = req;
+            }}
+            {body}
+        }}
+    }}
+    "#
+        )
+    };
+
+    for body in [
+        "$assert_implies(req, 1, r);",
+        "$assert_eventually($rose(req), 4, ack, \"no ack %d\", req);",
+        "$assert_implies($past(req, 2) && !$fell(ack), 0, $stable(r));",
+    ] {
+        let errors = analyze(&code(body));
+        assert!(errors.is_empty(), "{body}: {errors:?}");
+    }
+
+    for body in [
+        "$assert_implies(req, 1);",
+        "$assert_implies($past(), 0, ack);",
+        "$assert_implies($rose(req, 1), 0, ack);",
+    ] {
+        let errors = analyze(&code(body));
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchFunctionArity { .. })),
+            "{body}: {errors:?}"
+        );
+    }
+
+    for body in [
+        "$assert_implies(req, r, ack);",
+        "$assert_implies($past(req, 0), 0, ack);",
+    ] {
+        let errors = analyze(&code(body));
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::UnevaluableValue { .. })),
+            "{body}: {errors:?}"
+        );
+    }
+
+    // Attempts start at every clock edge, so the assertion can't be gated.
+    let errors = analyze(&code("if req { $assert_implies(req, 1, r); }"));
+    assert!(
+        errors
+            .iter()
+            .any(|e| matches!(e, AnalyzerError::InvalidStatement { .. })),
+        "{errors:?}"
+    );
+
+    let testbench = |attr: &str, clocks: &str, body: &str| {
+        format!(
+            r#"
+    {attr}
+    module test_temporal {{
+        {clocks}
+        var req: logic;
+        var ack: logic;
+        initial {{
+            req = 0;
+            ack = 0;
+            {body}
+        }}
+    }}
+    "#
+        )
+    };
+    let clock = "inst clk: $tb::clock_gen;";
+
+    for body in [
+        "$assert_implies(req, 1, ack);",
+        "$assert_eventually($rose(req), 4, ack, \"no ack\");",
+        "$assert_implies(req, 0, ack == $past(req, 2));",
+    ] {
+        let errors = analyze(&testbench("#[test(test_temporal)]", clock, body));
+        assert!(errors.is_empty(), "{body}: {errors:?}");
+    }
+
+    // The hidden registers need a single testbench clock.
+    for clocks in ["", "inst clk: $tb::clock_gen; inst clk2: $tb::clock_gen;"] {
+        let body = "$assert_implies(req, 1, ack);";
+        let errors = analyze(&testbench("#[test(test_temporal)]", clocks, body));
+        assert!(
+            errors.iter().any(|e| matches!(
+                e,
+                AnalyzerError::InvalidTest {
+                    cause: InvalidTestKind::TemporalWithoutClock,
+                    ..
+                }
+            )),
+            "{clocks}: {errors:?}"
+        );
+    }
+
+    for (attr, body) in [
+        (
+            "#[test(test_temporal)]",
+            "if req { $assert_implies(req, 1, ack); }",
+        ),
+        ("", "$assert_implies(req, 1, ack);"),
+    ] {
+        let errors = analyze(&testbench(attr, clock, body));
+        assert!(
+            errors.iter().any(|e| matches!(
+                e,
+                AnalyzerError::InvalidStatement { kind, .. } if kind == "$assert_implies"
+            )),
+            "{attr} {body}: {errors:?}"
+        );
+    }
+}
+
+#[test]
+fn tb_force_target_must_be_variable() {
+    // `$tb::force` overrides a whole variable; an expression or a part
+    // select has no single storage slot to hold.
+    let code = |target: &str| {
+        format!(
+            r#"
+    module Sub {{
+        #[allow(unused_variable)]
+        var v: logic<8>;
+        assign v = 1;
+    }}
+
+    #[test(test_force)]
+    module test_force {{
+        var f: $tb::force;
+        inst dut: Sub;
+        initial {{
+            f.force({target}, 3);
+            f.deposit({target}, 4);
+            f.release({target});
+            $finish();
+        }}
+    }}
+    "#
+        )
+    };
+    let errors: Vec<_> = analyze(&code("dut.v"))
+        .into_iter()
+        .filter(|e| !matches!(e, AnalyzerError::UnusedVariable { .. }))
+        .collect();
+    assert!(
+        errors.is_empty(),
+        "expected no analyzer errors, got: {errors:?}"
+    );
+
+    for target in ["dut.v + 1", "dut.v[3:0]"] {
+        let errors = analyze(&code(target));
+        assert!(
+            errors
+                .iter()
+                .any(|e| matches!(e, AnalyzerError::MismatchFunctionArg { .. })),
+            "`{target}` should be rejected, got: {errors:?}"
+        );
+    }
+}
+
 #[test]
 fn maybe_driver_cross_process_conflict() {
     // A non-const index/select write has an empty definite mask, so the
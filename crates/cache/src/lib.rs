@@ -64,6 +64,10 @@ pub struct FileEntry {
     /// `None` if it produced none.
     #[serde(default)]
     pub diagnostics: Option<String>,
+    /// Whether the file emitted an `_sva` assertion checker in the last
+    /// successful build, so a warm build restoring it still lists the checker.
+    #[serde(default)]
+    pub assertions: bool,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -204,6 +208,7 @@ impl Store {
                 dependents: Vec::new(),
                 tests: Vec::new(),
                 diagnostics: None,
+                assertions: false,
             },
         );
     }
@@ -262,6 +267,14 @@ impl Store {
         }
     }
 
+    /// Sets whether a file emitted an assertion checker for the build in
+    /// progress.
+    pub fn set_assertions(&mut self, src: &str, assertions: bool) {
+        if let Some(entry) = self.next_files.get_mut(src) {
+            entry.assertions = assertions;
+        }
+    }
+
     /// Writes the manifest for the build in progress and removes blobs no
     /// longer referenced by it. Call only after a successful build.
     pub fn save(&mut self) {
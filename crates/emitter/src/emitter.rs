@@ -5,7 +5,9 @@ use std::rc::Rc;
 use veryl_aligner::{Aligner, Location, PadKind, align_kind};
 use veryl_analyzer::attribute;
 use veryl_analyzer::attribute::Attribute as Attr;
-use veryl_analyzer::attribute::{AlignItem, AllowItem, CondTypeItem, EnumEncodingItem, FormatItem};
+use veryl_analyzer::attribute::{
+    AlignItem, AllowItem, CondTypeItem, EnumEncodingItem, FormatItem, JoinItem,
+};
 use veryl_analyzer::attribute_table;
 use veryl_analyzer::connect_operation_table;
 use veryl_analyzer::conv::{Context, Conv};
@@ -99,6 +101,7 @@ pub struct Emitter {
 
     // ----- Walker context flags ("are we currently inside X?") -------------
     in_always_ff: bool,
+    in_always_ff_with_if_reset: bool,
     in_direction_modport: bool,
     in_direction_with_var: bool,
     in_import: bool,
@@ -135,6 +138,15 @@ pub struct Emitter {
     // ----- Modport expansion -----------------------------------------------
     modport_connections_tables: Vec<ExpandModportConnectionsTable>,
     modport_ports_table: Option<ExpandedModportPortTable>,
+
+    // ----- Assertion checker (`#[assert]`) ----------------------------------
+    /// Module-level ports, variables and parameters observed while emitting
+    /// an `#[assert]` body. `Some` only while such a body is being emitted.
+    assert_ports: Option<Vec<SymbolId>>,
+    /// Checker modules and their `bind` statements, rendered into
+    /// `assertion_string` apart from the main output.
+    assertion_docs: Vec<Doc>,
+    assertion_string: String,
 }
 
 impl Default for Emitter {
@@ -165,6 +177,7 @@ impl Default for Emitter {
             emit_package_prefix: false,
 
             in_always_ff: false,
+            in_always_ff_with_if_reset: false,
             in_direction_modport: false,
             in_direction_with_var: false,
             in_import: false,
@@ -198,6 +211,10 @@ impl Default for Emitter {
 
             modport_connections_tables: Vec::new(),
             modport_ports_table: None,
+
+            assert_ports: None,
+            assertion_docs: Vec::new(),
+            assertion_string: String::new(),
         }
     }
 }
@@ -289,6 +306,7 @@ impl Emitter {
         }
         self.mode = Mode::Build;
         self.doc_buffer = vec![Vec::new()];
+        self.assertion_docs.clear();
         self.duplicated_index = 0;
         self.veryl(input);
         let top = self.doc_buffer.pop().unwrap_or_default();
@@ -303,6 +321,12 @@ impl Emitter {
         };
         let rendered = render_with_anchors(&doc, &opts);
         self.string = rendered.text;
+        let assertion_docs = std::mem::take(&mut self.assertion_docs);
+        self.assertion_string = if assertion_docs.is_empty() {
+            String::new()
+        } else {
+            render_with_anchors(&doc::concat(assertion_docs), &opts).text
+        };
         if let Some(ref mut map) = self.source_map {
             for a in &rendered.anchors {
                 map.add(a.dst_line, a.dst_column, a.src_line, a.src_column, &a.text);
@@ -315,6 +339,12 @@ impl Emitter {
         &self.string
     }
 
+    /// Checker modules and `bind` statements generated from `#[assert]`
+    /// blocks. Empty if the source has no such block.
+    pub fn assertion_str(&self) -> &str {
+        &self.assertion_string
+    }
+
     pub fn source_map(&mut self) -> &mut SourceMap {
         self.source_map.as_mut().unwrap()
     }
@@ -737,6 +767,10 @@ impl Emitter {
             self.align_start(align_kind::IDENTIFIER);
         }
 
+        if let Some(symbol) = symbol {
+            self.record_assert_port(symbol);
+        }
+
         let text = if let Some(symbol) = symbol
             && symbol.is_global_function()
         {
@@ -1056,6 +1090,7 @@ impl Emitter {
 
     fn always_ff_implicit_clock_event(&mut self) {
         let symbol = symbol_table::get(self.default_clock.unwrap()).unwrap();
+        self.record_assert_port(&symbol);
         let (clock_kind, prefix, suffix) = match symbol.kind {
             SymbolKind::Port(x) => (x.r#type.kind, x.prefix.clone(), x.suffix.clone()),
             SymbolKind::Variable(x) => (x.r#type.kind, x.prefix.clone(), x.suffix.clone()),
@@ -1097,6 +1132,7 @@ impl Emitter {
 
     fn always_ff_implicit_reset_event(&mut self) {
         let symbol = symbol_table::get(self.default_reset.unwrap()).unwrap();
+        self.record_assert_port(&symbol);
         let reset_type = get_variable_type_kind(&symbol)
             .map(|x| match x {
                 TypeKind::ResetAsyncHigh => ResetType::AsyncHigh,
@@ -1536,13 +1572,28 @@ impl Emitter {
 
         match &*group.statement_block_group_group {
             StatementBlockGroupGroup::BlockLBraceStatementBlockGroupGroupListRBrace(x) => {
-                for x in &x.statement_block_group_group_list {
-                    self.emit_statement_block_group(
-                        &x.statement_block_group,
-                        base,
-                        n_newlines,
-                        suppress_newline,
-                    );
+                if let Some(join) = attribute::fork_attribute(group) {
+                    if *suppress_newline {
+                        *suppress_newline = false;
+                    } else {
+                        self.newline_list(base + *n_newlines);
+                        *n_newlines += 1;
+                    }
+                    // Blank lines are counted from the attributes leading the block.
+                    if let Some(x) = group.statement_block_group_list.first() {
+                        let token = &x.attribute.hash_l_bracket.hash_l_bracket_token;
+                        self.consume_adjust_line(&token.token);
+                    }
+                    self.emit_fork(x, join);
+                } else {
+                    for x in &x.statement_block_group_group_list {
+                        self.emit_statement_block_group(
+                            &x.statement_block_group,
+                            base,
+                            n_newlines,
+                            suppress_newline,
+                        );
+                    }
                 }
             }
             StatementBlockGroupGroup::StatementBlockItem(x) => {
@@ -1577,6 +1628,41 @@ impl Emitter {
         }
     }
 
+    /// Emit a `#[fork]` block as `fork` ... `join`/`join_any`, each item of
+    /// the block a `begin`/`end` thread. Braces of a thread block become its
+    /// `begin`/`end` so source lines stay in step.
+    fn emit_fork(
+        &mut self,
+        arg: &StatementBlockGroupGroupBlockLBraceStatementBlockGroupGroupListRBrace,
+        join: JoinItem,
+    ) {
+        self.token(&arg.block.block_token.replace("fork"));
+        for (i, x) in arg.statement_block_group_group_list.iter().enumerate() {
+            self.newline_list(i);
+            let group = &x.statement_block_group;
+            let braces = match &*group.statement_block_group_group {
+                StatementBlockGroupGroup::BlockLBraceStatementBlockGroupGroupListRBrace(x) => {
+                    Some((&x.l_brace.l_brace_token, &x.r_brace.r_brace_token))
+                }
+                StatementBlockGroupGroup::StatementBlockItem(_) => None,
+            };
+            match braces {
+                Some((x, _)) => self.token(&x.replace("begin")),
+                None => self.str("begin"),
+            }
+            let mut n_newlines = 0;
+            let mut suppress_newline = false;
+            self.emit_statement_block_group(group, 0, &mut n_newlines, &mut suppress_newline);
+            self.newline_list_post(n_newlines == 0);
+            match braces {
+                Some((_, x)) => self.token(&x.replace("end")),
+                None => self.str("end"),
+            }
+        }
+        self.newline_list_post(arg.statement_block_group_group_list.is_empty());
+        self.token(&arg.r_brace.r_brace_token.replace(&join.to_string()));
+    }
+
     fn emit_declaration_in_statement_block(
         &mut self,
         arg: &StatementBlockItem,
@@ -2889,6 +2975,346 @@ impl Emitter {
         }
     }
 
+    fn record_assert_port(&mut self, symbol: &Symbol) {
+        let Some(ports) = self.assert_ports.as_ref() else {
+            return;
+        };
+        if ports.contains(&symbol.id) {
+            return;
+        }
+        let observable = match &symbol.kind {
+            SymbolKind::Port(x) => matches!(
+                x.direction,
+                SymDirection::Input | SymDirection::Output | SymDirection::Inout
+            ),
+            SymbolKind::Variable(x) => matches!(x.affiliation, Affiliation::Module),
+            SymbolKind::Parameter(_) => true,
+            _ => false,
+        };
+        // Only module-level signals are visible to an implicit `.*` bind.
+        if observable
+            && symbol
+                .get_parent()
+                .is_some_and(|x| matches!(x.kind, SymbolKind::Module(_)))
+        {
+            self.assert_ports.as_mut().unwrap().push(symbol.id);
+        }
+    }
+
+    /// Emit `#[assert]` module items as a standalone checker module observing
+    /// the signals they reference, followed by a `bind` into `module_name`.
+    fn emit_assertion_module(
+        &mut self,
+        module_name: &str,
+        assertions: &[&ModuleDeclarationList],
+        import_declarations: &[ImportDeclaration],
+    ) {
+        let src_line = self.src_line;
+        let last_token = self.last_token.clone();
+        let checker_name = format!("{module_name}_sva");
+
+        self.buf_begin();
+        self.align_reset();
+
+        // The body decides which signals the header has to declare, so emit
+        // it first and place the header in front of it afterwards.
+        self.buf_begin();
+        self.assert_ports = Some(Vec::new());
+        for (i, x) in assertions.iter().enumerate() {
+            self.newline_list(i);
+            if i == 0 {
+                self.force_duplicated = true;
+                let mut imports = self.file_scope_import.clone();
+                imports.extend_from_slice(import_declarations);
+                for x in &imports {
+                    self.emit_import_declaration(x, true);
+                    self.newline();
+                }
+                self.force_duplicated = false;
+            }
+            self.module_group(&x.module_group);
+        }
+        self.newline_list_post(false);
+        let body = self.buf_end_concat();
+        self.align_reset();
+
+        // Port types and parameter declarations may reference parameters the
+        // body doesn't, so collect the header's references until none is new.
+        loop {
+            let known = self.assert_ports.as_ref().map(|x| x.len()).unwrap_or(0);
+            let mut observed = self.assert_ports.clone().unwrap_or_default();
+            observed.sort();
+            self.buf_begin();
+            self.emit_assertion_header(&checker_name, &observed);
+            let _ = self.buf_end_concat();
+            self.align_reset();
+            if self.assert_ports.as_ref().map(|x| x.len()).unwrap_or(0) == known {
+                break;
+            }
+        }
+        let mut observed = self.assert_ports.take().unwrap_or_default();
+        observed.sort();
+
+        let params = self.emit_assertion_header(&checker_name, &observed);
+        self.emit_doc(body);
+        self.str("endmodule");
+        self.newline();
+        self.newline();
+
+        let param_connections: Vec<_> = params
+            .iter()
+            .map(|x| {
+                let name = emitting_identifier_token(&VerylToken::new(x.token), Some(x));
+                format!(".{name}({name})")
+            })
+            .collect();
+        self.str("bind");
+        self.space(1);
+        self.str(module_name);
+        self.space(1);
+        self.str(&checker_name);
+        self.space(1);
+        if !param_connections.is_empty() {
+            self.str(&format!("#({})", param_connections.join(", ")));
+            self.space(1);
+        }
+        self.str("u_sva (.*);");
+        self.newline();
+
+        let doc = self.buf_end_concat();
+        if matches!(self.mode, Mode::Build) {
+            self.assertion_docs.push(doc);
+        }
+        self.align_reset();
+
+        self.src_line = src_line;
+        self.last_token = last_token;
+        self.clear_adjust_line();
+    }
+
+    /// Emit the checker module header declaring `observed` and return its
+    /// parameters.
+    fn emit_assertion_header(&mut self, checker_name: &str, observed: &[SymbolId]) -> Vec<Symbol> {
+        let (params, ports): (Vec<_>, Vec<_>) = observed
+            .iter()
+            .filter_map(|x| symbol_table::get(*x))
+            .partition(|x| matches!(x.kind, SymbolKind::Parameter(_)));
+
+        self.str("module");
+        self.space(1);
+        self.str(checker_name);
+        self.space(1);
+        if !params.is_empty() {
+            self.str("#(");
+            self.newline_push();
+            self.force_duplicated = true;
+            for (i, x) in params.iter().enumerate() {
+                if i != 0 {
+                    self.str(",");
+                    self.newline();
+                }
+                self.emit_assertion_param(x);
+            }
+            self.align_reset();
+            self.force_duplicated = false;
+            self.newline_pop();
+            self.str(")");
+            self.space(1);
+        }
+        self.str("(");
+        if !ports.is_empty() {
+            self.newline_push();
+            self.force_duplicated = true;
+            self.in_direction_with_var = true;
+            self.aligner.disable_auto_finish();
+            for (i, x) in ports.iter().enumerate() {
+                if i != 0 {
+                    self.str(",");
+                    self.newline();
+                }
+                self.clear_adjust_line();
+                self.emit_assertion_port(x);
+            }
+            self.aligner.enable_auto_finish();
+            self.align_reset();
+            self.in_direction_with_var = false;
+            self.force_duplicated = false;
+            self.newline_pop();
+        }
+        self.str(");");
+        params
+    }
+
+    /// A checker parameter mirrors the declaration in the observed module;
+    /// its value is always overridden by the `bind`.
+    fn emit_assertion_param(&mut self, symbol: &Symbol) {
+        let SymbolKind::Parameter(ref param) = symbol.kind else {
+            return;
+        };
+
+        self.str("parameter");
+        self.space(1);
+        if matches!(param.r#type.kind, TypeKind::Type) {
+            self.str("type");
+            self.space(1);
+        } else if let Some(ref array_type) = param.r#type.array_type {
+            self.scalar_type(&array_type.scalar_type);
+            self.space(1);
+        }
+        let token = emitting_identifier_token(&VerylToken::new(symbol.token), Some(symbol));
+        self.duplicated_token(&token);
+        if let Some(x) = param
+            .r#type
+            .array_type
+            .as_ref()
+            .and_then(|x| x.array_type_opt.as_ref())
+        {
+            self.space(1);
+            self.array(&x.array);
+        }
+        if let Some(ref value) = param.value {
+            self.space(1);
+            self.str("=");
+            self.space(1);
+            self.expression(value);
+        }
+    }
+
+    fn emit_assertion_port(&mut self, symbol: &Symbol) {
+        let array_type = match &symbol.kind {
+            SymbolKind::Port(x) => x.r#type.array_type.clone(),
+            SymbolKind::Variable(x) => x.r#type.array_type.clone(),
+            _ => None,
+        };
+
+        self.str("input");
+        self.space(1);
+        if let Some(ref array_type) = array_type {
+            self.scalar_type(&array_type.scalar_type);
+        } else {
+            self.str("var logic");
+        }
+        self.space(1);
+        self.align_start(align_kind::IDENTIFIER);
+        let token = emitting_identifier_token(&VerylToken::new(symbol.token), Some(symbol));
+        self.duplicated_token(&token);
+        self.align_finish(align_kind::IDENTIFIER);
+        if let Some(ref x) = array_type.and_then(|x| x.array_type_opt) {
+            self.space(1);
+            self.array(&x.array);
+        }
+    }
+
+    /// `$assert(cond, args..)` in an `#[assert]` block becomes an SV
+    /// immediate assertion; `$assert` is fatal, `$assert_continue` is not.
+    fn emit_immediate_assertion(
+        &mut self,
+        arg: &IdentifierStatement,
+        function_call: &FunctionCall,
+        argument_list: &ArgumentList,
+        fatal: bool,
+    ) {
+        let name = arg.expression_identifier.scoped_identifier.identifier();
+        self.token(&name.replace("assert"));
+        self.space(1);
+        self.l_paren(&function_call.l_paren);
+        self.expression(&argument_list.argument_item.argument_expression.expression);
+        self.r_paren(&function_call.r_paren);
+
+        let messages = &argument_list.argument_list_list;
+        if fatal || !messages.is_empty() {
+            self.space(1);
+            self.str("else");
+            self.space(1);
+            if fatal {
+                self.str("$fatal(1");
+            } else {
+                self.str("$error(");
+            }
+            for (i, x) in messages.iter().enumerate() {
+                if i == 0 && !fatal {
+                    self.token(&x.comma.comma_token.replace(""));
+                } else {
+                    self.comma(&x.comma);
+                    self.space(1);
+                }
+                self.expression(&x.argument_item.argument_expression.expression);
+            }
+            self.str(")");
+        }
+        if let Some(ref x) = argument_list.argument_list_opt {
+            self.token(&x.comma.comma_token.replace(""));
+        }
+        self.semicolon(&arg.semicolon);
+    }
+
+    /// `$assert_implies(a, n, c, ...)` and `$assert_eventually(a, n, c, ...)`
+    /// as a procedural concurrent assertion clocked by the enclosing
+    /// `always_ff` and disabled while its `if_reset` holds.
+    fn emit_temporal_assertion(
+        &mut self,
+        arg: &IdentifierStatement,
+        argument_list: &ArgumentList,
+        eventually: bool,
+    ) {
+        let mut args = vec![&argument_list.argument_item.argument_expression.expression];
+        for x in &argument_list.argument_list_list {
+            args.push(&x.argument_item.argument_expression.expression);
+        }
+        if args.len() < 3 {
+            return;
+        }
+
+        let name = arg.expression_identifier.scoped_identifier.identifier();
+        self.token(&name.replace("assert"));
+        self.space(1);
+        self.str("property");
+        self.space(1);
+        self.str("(");
+        if self.in_always_ff_with_if_reset
+            && let Some(reset) = self.reset_signal.clone()
+        {
+            self.str("disable iff");
+            self.space(1);
+            self.str("(");
+            if self.reset_active_low {
+                self.str("!");
+            }
+            self.duplicated_token(&reset);
+            self.str(")");
+            self.space(1);
+        }
+        self.str("(");
+        self.expression(args[0]);
+        self.str(")");
+        self.space(1);
+        self.str("|->");
+        self.space(1);
+        if eventually {
+            self.str("##[0:");
+            self.expression(args[1]);
+            self.str("]");
+        } else {
+            self.str("##");
+            self.expression(args[1]);
+        }
+        self.space(1);
+        self.str("(");
+        self.expression(args[2]);
+        self.str("))");
+        self.space(1);
+        self.str("else");
+        self.space(1);
+        self.str("$fatal(1");
+        for x in &args[3..] {
+            self.str(",");
+            self.space(1);
+            self.expression(x);
+        }
+        self.str(")");
+        self.semicolon(&arg.semicolon);
+    }
+
     fn emit_generic_instance_name_comment(&mut self, generic_map: &GenericMap) {
         if generic_map.generic() && self.build_opt.hashed_mangled_name {
             let name = generic_map.name(false, false);
@@ -2903,6 +3329,11 @@ impl Emitter {
         generic_map: &GenericMap,
         omit_project_prefix: bool,
     ) {
+        let name = self.generic_instance_name(generic_map, omit_project_prefix);
+        self.token(&token.replace(&name));
+    }
+
+    fn generic_instance_name(&self, generic_map: &GenericMap, omit_project_prefix: bool) -> String {
         let name = generic_map.name(true, self.build_opt.hashed_mangled_name);
         let name = if self.build_opt.omit_project_prefix || omit_project_prefix {
             let project_name = format!("{}_", self.project_name.unwrap());
@@ -2914,9 +3345,7 @@ impl Emitter {
         } else {
             &name
         };
-        let name = name.replace("$std_", "__std_");
-
-        self.token(&token.replace(&name));
+        name.replace("$std_", "__std_")
     }
 }
 
@@ -4123,6 +4552,38 @@ impl VerylWalker for Emitter {
 
     /// Semantic action for non-terminal 'IdentifierStatement'
     fn identifier_statement(&mut self, arg: &IdentifierStatement) {
+        if self.in_always_ff
+            && let IdentifierStatementGroup::FunctionCall(x) = &*arg.identifier_statement_group
+            && let Some(ref args) = x.function_call.function_call_opt
+        {
+            let name = arg.expression_identifier.scoped_identifier.identifier();
+            let eventually = match name.to_string().as_str() {
+                "$assert_implies" => Some(false),
+                "$assert_eventually" => Some(true),
+                _ => None,
+            };
+            if let Some(eventually) = eventually {
+                self.emit_temporal_assertion(arg, &args.argument_list, eventually);
+                return;
+            }
+        }
+
+        if self.assert_ports.is_some()
+            && let IdentifierStatementGroup::FunctionCall(x) = &*arg.identifier_statement_group
+            && let Some(ref args) = x.function_call.function_call_opt
+        {
+            let name = arg.expression_identifier.scoped_identifier.identifier();
+            let fatal = match name.to_string().as_str() {
+                "$assert" => Some(true),
+                "$assert_continue" => Some(false),
+                _ => None,
+            };
+            if let Some(fatal) = fatal {
+                self.emit_immediate_assertion(arg, &x.function_call, &args.argument_list, fatal);
+                return;
+            }
+        }
+
         let connect_statement_emitted = self.emit_connect_statement(arg);
         if !connect_statement_emitted {
             // Aligning a call statement would pad its `(` out to the widest name.
@@ -4787,6 +5248,7 @@ impl VerylWalker for Emitter {
     /// Semantic action for non-terminal 'AlwaysFfDeclaration'
     fn always_ff_declaration(&mut self, arg: &AlwaysFfDeclaration) {
         self.in_always_ff = true;
+        self.in_always_ff_with_if_reset = self.always_ff_if_reset_exists(arg);
         self.always_ff(&arg.always_ff);
         self.space(1);
         self.str("@");
@@ -5985,17 +6447,17 @@ impl VerylWalker for Emitter {
             self.emit_generic_instance_name_comment(map);
             self.module(&arg.module);
             self.space(1);
-            if map.generic() {
-                self.emit_generic_instance_name(&arg.identifier.identifier_token, map, false);
+            let module_name = if map.generic() {
+                self.generic_instance_name(map, false)
             } else {
                 let context: SymbolContext = self.into();
-                let text = format!(
+                format!(
                     "{}{}",
                     namespace_string(&symbol.found.namespace, &symbol.generic_tables, &context),
                     arg.identifier.identifier_token
-                );
-                self.veryl_token(&arg.identifier.identifier_token.replace(&text));
-            }
+                )
+            };
+            self.veryl_token(&arg.identifier.identifier_token.replace(&module_name));
 
             let mut import_declarations = self.file_scope_import.clone();
             import_declarations.append(&mut arg.collect_import_declarations());
@@ -6023,7 +6485,11 @@ impl VerylWalker for Emitter {
                 self.port_declaration(&x.port_declaration);
             }
             self.token_will_push(&arg.l_brace.l_brace_token.replace(";"));
-            for (i, x) in arg.module_declaration_list.iter().enumerate() {
+            let (assertions, items): (Vec<_>, Vec<_>) = arg
+                .module_declaration_list
+                .iter()
+                .partition(|x| attribute::has_assert_attribute(&x.module_group));
+            for (i, x) in items.iter().enumerate() {
                 self.newline_list(i);
                 if i == 0 && !import_declarations.is_empty() && empty_header {
                     for x in &import_declarations {
@@ -6038,9 +6504,19 @@ impl VerylWalker for Emitter {
                 self.module_group(&x.module_group);
             }
             self.emit_global_functions(&symbol.found);
-            self.newline_list_post(arg.module_declaration_list.is_empty());
+            self.newline_list_post(items.is_empty());
+            if !assertions.is_empty() {
+                // The lines of the moved `#[assert]` items must not turn into
+                // a blank line before `endmodule`.
+                self.clear_adjust_line();
+            }
             self.token(&arg.r_brace.r_brace_token.replace("endmodule"));
 
+            if !assertions.is_empty() {
+                let import_declarations = arg.collect_import_declarations();
+                self.emit_assertion_module(&module_name, &assertions, &import_declarations);
+            }
+
             self.pop_generic_map();
             self.align_reset();
         }
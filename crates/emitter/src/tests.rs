@@ -6,6 +6,11 @@ use veryl_parser::Parser;
 
 #[track_caller]
 fn emit(metadata: &Metadata, code: &str) -> String {
+    emit_with_assertions(metadata, code).0
+}
+
+#[track_caller]
+fn emit_with_assertions(metadata: &Metadata, code: &str) -> (String, String) {
     symbol_table::clear();
     attribute_table::clear();
 
@@ -25,7 +30,10 @@ fn emit(metadata: &Metadata, code: &str) -> String {
         &PathBuf::from("test.sv.map"),
     );
     emitter.emit(&parser.veryl, code);
-    emitter.as_str().to_string()
+    (
+        emitter.as_str().to_string(),
+        emitter.assertion_str().to_string(),
+    )
 }
 
 #[test]
@@ -4351,6 +4359,59 @@ endmodule
     assert_eq!(ret, expect);
 }
 
+#[test]
+fn fork_block() {
+    let code = r#"module ModuleA {
+    var a: logic;
+    var b: logic;
+    initial {
+        #[fork]
+        block {
+            a = 1;
+            block {
+                b = 1;
+                $display("b");
+            }
+        }
+        #[fork(join_any)]
+        block {
+            a = 0;
+        }
+    }
+}
+"#;
+
+    let expect = r#"module prj_ModuleA;
+    logic a;
+    logic b;
+    initial begin
+        fork
+            begin
+                a = 1;
+            end
+            begin
+                b = 1;
+                $display("b");
+            end
+        join
+        fork
+            begin
+                a = 0;
+            end
+        join_any
+    end
+endmodule
+//# sourceMappingURL=test.sv.map
+"#;
+
+    let metadata = Metadata::create_default("prj").unwrap();
+
+    let ret = emit(&metadata, code);
+
+    println!("ret\n{}exp\n{}", ret, expect);
+    assert_eq!(ret, expect);
+}
+
 #[test]
 fn mixin_interface() {
     let code = r#"
@@ -4763,3 +4824,184 @@ fn struct_member_ifdef_keeps_semicolon_inside_guard() {
         "no stray ';' after `endif:\n{ret}"
     );
 }
+
+#[test]
+fn assert_block_emitted_as_bind_checker() {
+    let metadata = Metadata::create_default("prj").unwrap();
+
+    let code = r#"module ModuleA #(
+    param WIDTH: u32 = 8,
+) (
+    i_clk: input  clock           ,
+    i_rst: input  reset           ,
+    i_dat: input  logic    <WIDTH>,
+    o_dat: output logic    <WIDTH>,
+) {
+    var r_dat: logic<WIDTH>;
+
+    always_ff {
+        if_reset {
+            r_dat = 0;
+        } else {
+            r_dat = i_dat;
+        }
+    }
+
+    assign o_dat = r_dat;
+
+    #[assert]
+    always_ff {
+        if !i_rst {
+            $assert(o_dat == r_dat, "output mismatch");
+        }
+    }
+
+    #[assert]
+    always_comb {
+        $assert_continue(i_dat != WIDTH);
+    }
+}
+"#;
+
+    let expect = r#"module prj_ModuleA #(
+    parameter int unsigned WIDTH = 8
+) (
+    input  var logic             i_clk,
+    input  var logic             i_rst,
+    input  var logic [WIDTH-1:0] i_dat,
+    output var logic [WIDTH-1:0] o_dat
+);
+    logic [WIDTH-1:0] r_dat;
+
+    always_ff @ (posedge i_clk, negedge i_rst) begin
+        if (!i_rst) begin
+            r_dat <= 0;
+        end else begin
+            r_dat <= i_dat;
+        end
+    end
+
+    always_comb o_dat = r_dat;
+endmodule
+//# sourceMappingURL=test.sv.map
+"#;
+
+    let expect_assertions = r#"module prj_ModuleA_sva #(
+    parameter int unsigned WIDTH = 8
+) (
+    input var logic             i_clk,
+    input var logic             i_rst,
+    input var logic [WIDTH-1:0] i_dat,
+    input var logic [WIDTH-1:0] o_dat,
+    input var logic [WIDTH-1:0] r_dat
+);
+    always_ff @ (posedge i_clk) begin
+        if (!i_rst) begin
+            assert (o_dat == r_dat) else $fatal(1, "output mismatch");
+        end
+    end
+
+    always_comb begin
+        assert (i_dat != WIDTH);
+    end
+endmodule
+
+bind prj_ModuleA prj_ModuleA_sva #(.WIDTH(WIDTH)) u_sva (.*);
+"#;
+
+    let (ret, assertions) = emit_with_assertions(&metadata, code);
+    assert_eq!(ret, expect);
+    assert_eq!(assertions, expect_assertions);
+}
+
+#[test]
+fn assert_checker_declares_parameters_of_port_types() {
+    let metadata = Metadata::create_default("prj").unwrap();
+
+    let code = r#"module ModuleA #(
+    param T: type = logic<4>,
+    param N: u32  = 3       ,
+) (
+    i_a: input T,
+) {
+    #[assert]
+    always_comb {
+        $assert_continue(i_a != N);
+    }
+}
+"#;
+
+    let expect_assertions = r#"module prj_ModuleA_sva #(
+    parameter type T = logic [4-1:0],
+    parameter int unsigned N = 3
+) (
+    input var T i_a
+);
+    always_comb begin
+        assert (i_a != N);
+    end
+endmodule
+
+bind prj_ModuleA prj_ModuleA_sva #(.T(T), .N(N)) u_sva (.*);
+"#;
+
+    let (_, assertions) = emit_with_assertions(&metadata, code);
+    assert_eq!(assertions, expect_assertions);
+}
+
+#[test]
+fn temporal_assertion_emitted_as_sva() {
+    let metadata = Metadata::create_default("prj").unwrap();
+
+    let code = r#"module ModuleA (
+    i_clk: input clock,
+    i_rst: input reset,
+    i_req: input logic,
+    i_ack: input logic,
+) {
+    var r_req: logic;
+
+    always_ff {
+        if_reset {
+            r_req = 0;
+        } else {
+            r_req = i_req;
+        }
+        $assert_implies(i_req, 1, r_req);
+        $assert_eventually($rose(i_req), 4, i_ack, "no ack");
+    }
+
+    always_ff (i_clk) {
+        $assert_implies($past(i_req, 2), 0, $stable(i_ack));
+    }
+}
+"#;
+
+    let expect = r#"module prj_ModuleA (
+    input var logic i_clk,
+    input var logic i_rst,
+    input var logic i_req,
+    input var logic i_ack
+);
+    logic r_req;
+
+    always_ff @ (posedge i_clk, negedge i_rst) begin
+        if (!i_rst) begin
+            r_req <= 0;
+        end else begin
+            r_req <= i_req;
+        end
+        assert property (disable iff (!i_rst) (i_req) |-> ##1 (r_req)) else $fatal(1);
+        assert property (disable iff (!i_rst) ($rose(i_req)) |-> ##[0:4] (i_ack)) else $fatal(1, "no ack");
+    end
+
+    always_ff @ (posedge i_clk) begin
+        assert property (($past(i_req, 2)) |-> ##0 ($stable(i_ack))) else $fatal(1);
+    end
+endmodule
+//# sourceMappingURL=test.sv.map
+"#;
+
+    let ret = emit(&metadata, code);
+    assert_eq!(ret, expect);
+}
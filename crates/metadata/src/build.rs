@@ -56,6 +56,8 @@ pub struct Build {
     pub incremental: bool,
     #[serde(default)]
     pub error_count_limit: u32,
+    #[serde(default)]
+    pub strip_assertions: bool,
 }
 
 fn default_source() -> PathBuf {
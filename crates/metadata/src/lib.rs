@@ -11,6 +11,7 @@ mod lockfile_compat;
 mod metadata;
 mod metadata_error;
 mod metadata_output;
+mod profile;
 mod project;
 mod pubfile;
 mod publish;
@@ -38,6 +39,7 @@ pub use metadata_error::MetadataError;
 pub use metadata_output::{
     MetadataDependencyV2, MetadataOutputV2, MetadataProjectV2, MetadataSourceV2,
 };
+pub use profile::Profile;
 pub use project::Project;
 pub use pubfile::{Pubfile, Release};
 pub use publish::Publish;
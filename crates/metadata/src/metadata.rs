@@ -6,6 +6,7 @@ use crate::format::Format;
 use crate::git::Git;
 use crate::lint::Lint;
 use crate::lockfile::Lockfile;
+use crate::profile::Profile;
 use crate::project::Project;
 use crate::pubfile::{Pubfile, Release};
 use crate::publish::Publish;
@@ -56,6 +57,8 @@ pub struct Metadata {
     #[serde(default)]
     pub properties: BTreeMap<String, ProjectProperty>,
     #[serde(default)]
+    pub profile: BTreeMap<String, Profile>,
+    #[serde(default)]
     pub components: Vec<Component>,
     #[serde(default)]
     pub dependencies: HashMap<String, Dependency>,
@@ -78,6 +81,9 @@ pub struct Metadata {
     /// instead of the project path. Never read from Veryl.toml.
     #[serde(skip)]
     pub output_dir_override: Option<PathBuf>,
+    /// Defines of the profile selected by [`Metadata::apply_profile`].
+    #[serde(skip)]
+    pub profile_defines: Vec<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -343,6 +349,34 @@ impl Metadata {
         self.build_info.save(&build_info)
     }
 
+    /// Overlays `[profile.<name>]` onto `[build]` and redirects outputs to
+    /// the profile's directory (unless an output override is already set).
+    pub fn apply_profile(&mut self, name: &str) -> Result<(), MetadataError> {
+        let profile = self
+            .profile
+            .get(name)
+            .cloned()
+            .ok_or_else(|| MetadataError::UnknownProfile(name.to_string()))?;
+
+        let mut build = toml::Table::try_from(&self.build)?;
+        build.extend(profile.build);
+        self.build = build
+            .try_into()
+            .map_err(|source| MetadataError::InvalidProfile {
+                name: name.to_string(),
+                source,
+            })?;
+
+        if self.output_dir_override.is_none() {
+            let out_dir = profile
+                .out_dir
+                .unwrap_or_else(|| PathBuf::from("profile").join(name));
+            self.output_dir_override = Some(self.project_path().join(out_dir));
+        }
+        self.profile_defines = profile.defines;
+        Ok(())
+    }
+
     pub fn add_generated_file(&mut self, path: PathBuf) {
         self.build_info
             .generated_files
@@ -528,6 +562,7 @@ target = {{type = "directory", path = "target"}}"###
 .build/
 /target
 /dependencies
+/profile
 *.f
 
 # Verilator
@@ -118,6 +118,21 @@ pub enum MetadataError {
     #[error("property \"{property}\" is not defined in project \"{project}\"")]
     UnknownProperty { property: String, project: String },
 
+    #[diagnostic(
+        code(MetadataError::UnknownProfile),
+        help("profiles are declared as [profile.<name>] in Veryl.toml")
+    )]
+    #[error("build profile \"{0}\" is not defined")]
+    UnknownProfile(String),
+
+    #[diagnostic(code(MetadataError::InvalidProfile), help(""))]
+    #[error("build profile \"{name}\" is invalid")]
+    InvalidProfile {
+        name: String,
+        #[source]
+        source: toml::de::Error,
+    },
+
     #[diagnostic(code(MetadataError::MismatchType), help(""))]
     #[error("\"{name}\" is expected to \"{expected}\", but it is \"{actual}\"")]
     MismatchType {
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A named `[profile.<name>]` table, selected with `veryl build --profile`.
+/// Any `[build]` key may be overridden; the remaining keys are profile-only.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    /// Macros defined while analyzing `#[ifdef]` and listed in the filelist
+    #[serde(default)]
+    pub defines: Vec<String>,
+    /// Output root relative to the project; `profile/<name>` when unset
+    pub out_dir: Option<PathBuf>,
+    /// `[build]` overrides, validated against [`crate::Build`] when applied
+    #[serde(flatten)]
+    pub build: toml::Table,
+}
@@ -18,6 +18,23 @@ pub struct Test {
     pub waveform_target: WaveFormTarget,
     #[serde(default)]
     pub waveform_format: WaveFormFormat,
+    /// Hierarchical scopes (`top.u_dut`) dumped by the native simulator;
+    /// empty dumps the whole design.
+    #[serde(default)]
+    pub waveform_scopes: Vec<String>,
+    /// Glob patterns on hierarchical signal names (`*.u_core.*_valid`);
+    /// empty dumps every signal in scope.
+    #[serde(default)]
+    pub waveform_signals: Vec<String>,
+    /// Hierarchy levels dumped, the test module being level 1.
+    #[serde(default)]
+    pub waveform_depth: Option<usize>,
+    /// First clock cycle dumped.
+    #[serde(default)]
+    pub waveform_start: Option<u64>,
+    /// Clock cycle at which dumping stops (exclusive).
+    #[serde(default)]
+    pub waveform_end: Option<u64>,
     #[serde(default)]
     pub include_files: Vec<PathBuf>,
     #[serde(default)]
@@ -28,6 +45,13 @@ pub struct Test {
     pub seed: Option<u64>,
     #[serde(default)]
     pub four_state: bool,
+    /// Clock cycles after which a native test fails as hung. `#[timeout]`
+    /// on a test overrides it.
+    #[serde(default)]
+    pub timeout_cycles: Option<u64>,
+    /// Wall-clock seconds after which a native test fails as hung.
+    #[serde(default)]
+    pub timeout_secs: Option<f64>,
     /// Pins the verification-component transport. Unset: build from source
     /// when cargo is available, fall back to a committed prebuilt wasm
     /// otherwise.
@@ -325,6 +325,52 @@ four_state = true
     assert!(metadata.test.four_state);
 }
 
+#[test]
+fn test_timeouts_default_off_and_parse() {
+    let metadata: Metadata = toml::from_str(TEST_TOML).unwrap();
+    assert_eq!(metadata.test.timeout_cycles, None);
+    assert_eq!(metadata.test.timeout_secs, None);
+
+    let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+
+[test]
+timeout_cycles = 100000
+timeout_secs = 2.5
+"#;
+    let metadata: Metadata = toml::from_str(toml).unwrap();
+    assert_eq!(metadata.test.timeout_cycles, Some(100000));
+    assert_eq!(metadata.test.timeout_secs, Some(2.5));
+}
+
+#[test]
+fn waveform_filter_defaults_off_and_parses() {
+    let metadata: Metadata = toml::from_str(TEST_TOML).unwrap();
+    assert!(metadata.test.waveform_scopes.is_empty());
+    assert!(metadata.test.waveform_depth.is_none());
+
+    let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+
+[test]
+waveform_scopes = ["top.u_dut"]
+waveform_signals = ["*_valid"]
+waveform_depth = 3
+waveform_start = 100
+waveform_end = 200
+"#;
+    let metadata: Metadata = toml::from_str(toml).unwrap();
+    assert_eq!(metadata.test.waveform_scopes, vec!["top.u_dut"]);
+    assert_eq!(metadata.test.waveform_signals, vec!["*_valid"]);
+    assert_eq!(metadata.test.waveform_depth, Some(3));
+    assert_eq!(metadata.test.waveform_start, Some(100));
+    assert_eq!(metadata.test.waveform_end, Some(200));
+}
+
 #[test]
 fn synth_ram_thresholds_default_and_override() {
     // Omitted RAM thresholds fall back to the built-in defaults.
@@ -353,6 +399,45 @@ ram_max_ff_bits = 4096
     assert_eq!(metadata.synth.ram_max_ff_bits, 4096);
 }
 
+#[test]
+fn profile_overrides_build() {
+    let toml = r#"
+[project]
+name = "test"
+version = "0.1.0"
+
+[build]
+reset_type = "async_low"
+strip_comments = false
+
+[profile.asic]
+defines = ["ASIC"]
+reset_type = "sync_high"
+
+[profile.typo]
+reset_typ = "sync_high"
+"#;
+    let mut metadata: Metadata = toml::from_str(toml).unwrap();
+    metadata.metadata_path = PathBuf::from("/prj/Veryl.toml");
+    assert_eq!(metadata.profile.len(), 2);
+
+    let mut asic = metadata.clone();
+    asic.apply_profile("asic").unwrap();
+    assert_eq!(asic.build.reset_type, ResetType::SyncHigh);
+    assert!(!asic.build.strip_comments);
+    assert_eq!(asic.profile_defines, vec!["ASIC".to_string()]);
+    assert_eq!(asic.output_dir(), PathBuf::from("/prj/profile/asic"));
+
+    assert!(matches!(
+        metadata.clone().apply_profile("typo"),
+        Err(MetadataError::InvalidProfile { .. })
+    ));
+    assert!(matches!(
+        metadata.apply_profile("fpga"),
+        Err(MetadataError::UnknownProfile(_))
+    ));
+}
+
 #[test]
 fn load_extension_namespace_metadata() {
     let tempdir = tempfile::tempdir().unwrap();
@@ -4,11 +4,17 @@
 //! failures without panicking.
 
 use std::cell::RefCell;
+use std::path::PathBuf;
+use veryl_parser::resource_table;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::TokenSource;
 
 #[derive(Default)]
 struct State {
-    fatal: Option<String>,
-    continues: Vec<String>,
+    fatal: Option<(String, TokenRange)>,
+    continues: Vec<(String, TokenRange)>,
+    /// Sources of the failures returned by `take_failure`.
+    reported: Vec<TokenRange>,
 }
 
 thread_local! {
@@ -16,6 +22,7 @@ thread_local! {
         RefCell::new(State {
             fatal: None,
             continues: Vec::new(),
+            reported: Vec::new(),
         })
     };
 }
@@ -25,20 +32,21 @@ pub fn reset() {
         let mut s = s.borrow_mut();
         s.fatal = None;
         s.continues.clear();
+        s.reported.clear();
     });
 }
 
-pub fn record_fatal(msg: String) {
+pub fn record_fatal(msg: String, token: TokenRange) {
     STATE.with(|s| {
         let mut s = s.borrow_mut();
         if s.fatal.is_none() {
-            s.fatal = Some(msg);
+            s.fatal = Some((msg, token));
         }
     });
 }
 
-pub fn record_continue(msg: String) {
-    STATE.with(|s| s.borrow_mut().continues.push(msg));
+pub fn record_continue(msg: String, token: TokenRange) {
+    STATE.with(|s| s.borrow_mut().continues.push((msg, token)));
 }
 
 pub fn has_fatal() -> bool {
@@ -48,15 +56,33 @@ pub fn has_fatal() -> bool {
 pub fn take_failure() -> Option<String> {
     STATE.with(|s| {
         let mut s = s.borrow_mut();
-        if let Some(msg) = s.fatal.take() {
+        if let Some((msg, token)) = s.fatal.take() {
             s.continues.clear();
+            s.reported.push(token);
             return Some(msg);
         }
         if s.continues.is_empty() {
             None
         } else {
-            let msgs = std::mem::take(&mut s.continues);
+            let (msgs, tokens): (Vec<_>, Vec<_>) =
+                std::mem::take(&mut s.continues).into_iter().unzip();
+            s.reported.extend(tokens);
             Some(msgs.join("\n"))
         }
     })
 }
+
+/// Source file and line of each assertion whose failure `take_failure`
+/// returned since the last `reset`, for test reports.
+pub fn take_locations() -> Vec<(PathBuf, u32)> {
+    let tokens = STATE.with(|s| std::mem::take(&mut s.borrow_mut().reported));
+    tokens
+        .iter()
+        .filter_map(|x| match x.beg.source {
+            TokenSource::File { path, .. } => {
+                Some((resource_table::get_path_value(path)?, x.beg.line))
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -116,6 +116,10 @@ pub struct ChunkArtifact {
     /// still distinguishes chunks with different code. `None` before a stamp
     /// (non-`dut_reuse` path); `Debug` then falls back to the address.
     pub content_fp: Option<u128>,
+    /// Where the compiled statements came from, stamped by
+    /// `try_compile_chunk` under `Config::profile`; empty otherwise.  Like
+    /// `func`, left out of `Debug` and `Hash`.
+    pub origins: Vec<crate::profiler::Origin>,
 }
 
 impl std::fmt::Debug for ChunkArtifact {
@@ -8,6 +8,7 @@
 //! to Cranelift (per-module for comb, per-event for events).
 
 use crate::FuncPtr;
+use crate::coverage;
 use crate::ir::{
     ExpressionContext, ProtoAssignDynamicStatement, ProtoAssignStatement, ProtoExpression,
     ProtoForBound, ProtoForRange, ProtoForStatement, ProtoStatement, ProtoSystemFunctionCall,
@@ -22,6 +23,14 @@ use std::sync::{Arc, Mutex, OnceLock};
 use veryl_analyzer::ir::Op;
 use veryl_analyzer::value::Value;
 
+/// Coverage counter callback, wired to `coverage::veryl_cover_hit` at dlopen
+/// (see `compile_source`).  Emitted for every module; unused when the IR has
+/// no `Cover` statements.
+const COVER_C_DECLS: &str = "\
+typedef void (*veryl_cover_t)(uint32_t);\n\
+__attribute__((visibility(\"default\"))) veryl_cover_t veryl_cover_cb = 0;\n\
+__attribute__((visibility(\"default\"))) void veryl_set_cover_cb(void *p) { veryl_cover_cb = (veryl_cover_t)p; }\n";
+
 /// C declarations for the wide-op (>128-bit) helper function-pointer table.
 /// The emitted `.so` calls the SAME Rust `wide_ops::*` helpers Cranelift uses
 /// (via `call_indirect`), so AOT-C and Cranelift are bit-identical by
@@ -645,7 +654,9 @@ impl LocalAnalysis {
                         self.walk_reads(a, i);
                     }
                 }
-                ProtoSystemFunctionCall::Readmemh { .. } | ProtoSystemFunctionCall::Finish => {}
+                ProtoSystemFunctionCall::Readmemh { .. }
+                | ProtoSystemFunctionCall::Finish
+                | ProtoSystemFunctionCall::Cover { .. } => {}
             },
             ProtoStatement::CompiledBlock(_) => {
                 self.poison(s);
@@ -2605,6 +2616,7 @@ fn emit_event_function(stmts: &[ProtoStatement]) -> Option<String> {
          __attribute__((visibility(\"default\"))) veryl_sysfn_t veryl_sysfn_cb = 0;\n\
          __attribute__((visibility(\"default\"))) void veryl_set_sysfn_cb(void *p) { veryl_sysfn_cb = (veryl_sysfn_t)p; }\n",
     );
+    src.push_str(COVER_C_DECLS);
     src.push_str(WIDEOPS_C_DECLS);
     src.push_str(WIDEOPS_C_INLINE);
     src.push_str(
@@ -2874,6 +2886,12 @@ fn compile_source_in(cache_dir: &Path, src: &str) -> Result<EmittedModule, Strin
             veryl_aot_sysfn_print;
         unsafe { setter(cb as *mut c_void) };
     }
+    if let Ok(setter) =
+        unsafe { lib.get::<unsafe extern "C" fn(*mut c_void)>(b"veryl_set_cover_cb\0") }
+    {
+        let cb: extern "C" fn(u32) = coverage::veryl_cover_hit;
+        unsafe { setter(cb as *mut c_void) };
+    }
     Ok(EmittedModule { func, _lib: lib })
 }
 
@@ -2932,6 +2950,7 @@ pub fn emit_function(stmts: &[ProtoStatement]) -> Option<String> {
          typedef __uint128_t veryl_u128_ua __attribute__((__aligned__(1)));\n\
          typedef uint64_t veryl_u64_ua __attribute__((__aligned__(1)));\n",
     );
+    body.push_str(COVER_C_DECLS);
     body.push_str(WIDEOPS_C_DECLS);
     body.push_str(WIDEOPS_C_INLINE);
     body.push('\n');
@@ -3760,7 +3779,11 @@ pub fn emit_stmt(stmt: &ProtoStatement) -> Option<String> {
             // the whole clock event onto Cranelift.  $finish/$assert/$readmemh
             // affect sim state / need richer handling and stay on Cranelift.
             // Comb path has no output side effects, so bail there as before.
-            if event_mode() {
+            // Coverage counters are the exception: both paths call back into
+            // `coverage::veryl_cover_hit`.
+            if let ProtoSystemFunctionCall::Cover { id } = call {
+                Some(format!("if (veryl_cover_cb) veryl_cover_cb({id}u);"))
+            } else if event_mode() {
                 match call {
                     ProtoSystemFunctionCall::Display { format_str, args } => {
                         emit_event_print(format_str, args, true)
@@ -7229,6 +7252,7 @@ mod tests {
             func: stub,
             keepalive: None,
             content_fp: None,
+            origins: Vec::new(),
         })
     }
 
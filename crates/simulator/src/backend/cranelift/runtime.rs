@@ -32,6 +32,8 @@ pub enum HelperSig {
     WriteLogGrowPushNarrow,
     /// `(buf, offset, src_ptr, nb) -> ()` — write-log grow+push (wide).
     WriteLogGrowPushWide,
+    /// `(id) -> ()` — coverage counter.
+    CoverHit,
 }
 
 pub struct Context {
@@ -121,6 +123,9 @@ pub fn get_or_create_sig(
             sig.params.push(AbiParam::new(I64)); // src_ptr
             sig.params.push(AbiParam::new(I32)); // nb
         }
+        HelperSig::CoverHit => {
+            sig.params.push(AbiParam::new(I32)); // id
+        }
     }
 
     let sig_ref = builder.import_signature(sig);
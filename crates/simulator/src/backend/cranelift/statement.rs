@@ -3,12 +3,15 @@
 
 use super::helpers::*;
 use super::runtime::{
-    Context as CraneliftContext, emit_inline_write_log_push, emit_inline_write_log_push_wide,
+    Context as CraneliftContext, HelperSig, call_helper_void, emit_inline_write_log_push,
+    emit_inline_write_log_push_wide,
 };
+use crate::coverage;
 use crate::ir::variable::native_bytes as calc_native_bytes;
 use crate::ir::{
     ProtoAssignDynamicStatement, ProtoAssignStatement, ProtoCaseStatement, ProtoExpression,
     ProtoForBound, ProtoForRange, ProtoForStatement, ProtoIfStatement, ProtoStatement,
+    ProtoSystemFunctionCall,
 };
 use cranelift::codegen::ir::BlockArg;
 use cranelift::prelude::Value as CraneliftValue;
@@ -854,7 +857,9 @@ impl ProtoStatement {
             ProtoStatement::If(x) => x.can_build_binary(),
             ProtoStatement::Case(x) => x.can_build_binary(),
             ProtoStatement::For(x) => x.can_build_binary(),
-            ProtoStatement::SystemFunctionCall(_) => false,
+            ProtoStatement::SystemFunctionCall(x) => {
+                matches!(x, ProtoSystemFunctionCall::Cover { .. })
+            }
             ProtoStatement::CompiledBlock(_) => false,
             ProtoStatement::SequentialBlock(body) => body.iter().all(|s| s.can_build_binary()),
             ProtoStatement::TbMethodCall { .. } => false,
@@ -878,6 +883,12 @@ impl ProtoStatement {
             ProtoStatement::If(x) => x.build_binary(context, builder, is_last),
             ProtoStatement::Case(x) => x.build_binary(context, builder, is_last),
             ProtoStatement::For(x) => x.build_binary(context, builder, is_last),
+            ProtoStatement::SystemFunctionCall(ProtoSystemFunctionCall::Cover { id }) => {
+                let id = builder.ins().iconst(I32, *id as i64);
+                let addr = coverage::veryl_cover_hit as *const () as usize;
+                call_helper_void(context, builder, HelperSig::CoverHit, addr, &[id]);
+                Some(())
+            }
             ProtoStatement::SystemFunctionCall(_) => None,
             ProtoStatement::CompiledBlock(_) => None,
             ProtoStatement::SequentialBlock(body) => {
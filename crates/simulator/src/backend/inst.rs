@@ -511,7 +511,8 @@ pub fn try_compile_inst_chunks(
     all_event_statements: &mut HashMap<Event, Vec<ProtoStatement>>,
     all_comb_statements: &mut Vec<ProtoStatement>,
 ) {
-    if !context.config.use_jit {
+    // Inst chunks bypass `build_chunked`, where `xprop` gates branches.
+    if !context.config.use_jit || context.config.xprop_active() {
         return;
     }
     let ff_start_bytes = ff_start;
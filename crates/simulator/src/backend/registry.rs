@@ -8,6 +8,8 @@
 
 use super::{Backend, ChunkArtifact, CompileCtx, CompiledWhole};
 use crate::ir::{Config, Event, ProtoStatement};
+use crate::profiler;
+use crate::xprop;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use std::sync::{Arc, LazyLock, Mutex};
@@ -97,16 +99,22 @@ pub(crate) fn whole_comb_fingerprint(
 #[derive(Default)]
 pub struct BackendRegistry {
     backends: Vec<Box<dyn Backend>>,
+    /// Statements `build_chunked` left on the interpreter, as (index into
+    /// its input, reason).  Collected only under `Config::profile`; the
+    /// caller drains it after each call.
+    pub(crate) fallbacks: Vec<(usize, String)>,
 }
 
 impl BackendRegistry {
     /// Native: register AOT-C (when `config.aot_c`) then Cranelift
-    /// (when `config.use_jit`).  Wasm: always empty.
+    /// (when `config.use_jit`).  Wasm: always empty.  AOT-C compiles whole
+    /// blocks with the standard X semantic, so `config.xprop_active()`
+    /// leaves it out.
     pub fn for_config(_config: &Config) -> Self {
         let mut r = Self::default();
         #[cfg(not(target_family = "wasm"))]
         {
-            if _config.aot_c {
+            if _config.aot_c && !_config.xprop_active() {
                 r.register(Box::new(super::AotCBackend::new(
                     _config.aot_c_async,
                     _config.aot_c_event,
@@ -150,10 +158,18 @@ impl BackendRegistry {
         stmts: &[ProtoStatement],
     ) -> Option<Arc<ChunkArtifact>> {
         if !ctx.config.dut_reuse {
-            return self
+            let mut artifact = self
                 .backends
                 .iter_mut()
                 .find_map(|b| b.compile_chunk(ctx, stmts));
+            // Fresh Arc, so `get_mut` succeeds; the profiler maps the chunk's
+            // time back to the statements it replaced.
+            if ctx.config.profile
+                && let Some(a) = artifact.as_mut().and_then(Arc::get_mut)
+            {
+                a.origins = profiler::origins(stmts);
+            }
+            return artifact;
         }
         let key = chunk_fingerprint(ctx.use_4state, ctx.contains_compiled_block, stmts);
         if let Some(artifact) = CHUNK_ARTIFACT_CACHE.lock().unwrap().get(&key) {
@@ -208,46 +224,96 @@ impl BackendRegistry {
         let mut current_jittable: Option<bool> = None;
         let mut current_group: Vec<ProtoStatement> = Vec::new();
 
-        let flush =
-            |group: Vec<ProtoStatement>, was_jittable: bool, this: &mut Self, out: &mut Vec<_>| {
-                if was_jittable {
-                    Self::compile_group(this, ctx, group, max_chunk_size, out);
-                } else {
-                    out.push(ChunkOutput::Interpreted(group));
-                }
-            };
+        let mut group_start = 0;
+
+        let flush = |group: Vec<ProtoStatement>,
+                     base: usize,
+                     was_jittable: bool,
+                     this: &mut Self,
+                     out: &mut Vec<_>| {
+            if was_jittable {
+                Self::compile_group(this, ctx, group, base, max_chunk_size, out);
+            } else {
+                out.push(ChunkOutput::Interpreted(group));
+            }
+        };
 
-        for stmt in proto {
-            let jittable = self.any_supports_stmt(&stmt);
+        // Compiled branches take the standard X semantic; keep them on the
+        // interpreter, which hands X conditions to `xprop::Merger`.
+        let xprop = ctx.config.xprop_active();
+        for (i, stmt) in proto.into_iter().enumerate() {
+            let xprop_branch = xprop && xprop::has_branch(&stmt);
+            let jittable = self.any_supports_stmt(&stmt) && !xprop_branch;
+            if !jittable && ctx.config.profile && !matches!(stmt, ProtoStatement::CompiledBlock(_))
+            {
+                let reason = if xprop_branch {
+                    "X propagation keeps branches on the interpreter".to_string()
+                } else {
+                    format!(
+                        "not supported by {}: {}",
+                        self.names(),
+                        classify_proto_stmt(self.unsupported_leaf(&stmt))
+                    )
+                };
+                self.fallbacks.push((i, reason));
+            }
             if current_jittable == Some(jittable) {
                 current_group.push(stmt);
             } else {
                 if let Some(was_jittable) = current_jittable {
                     let group = std::mem::take(&mut current_group);
-                    flush(group, was_jittable, self, &mut out);
+                    flush(group, group_start, was_jittable, self, &mut out);
                 }
                 current_jittable = Some(jittable);
                 current_group.push(stmt);
+                group_start = i;
             }
         }
         if let Some(was_jittable) = current_jittable {
-            flush(current_group, was_jittable, self, &mut out);
+            flush(current_group, group_start, was_jittable, self, &mut out);
         }
         out
     }
 
+    fn names(&self) -> String {
+        let names: Vec<_> = self.backends.iter().map(|b| b.name()).collect();
+        names.join("/")
+    }
+
+    /// The innermost statement inside `stmt` no backend supports, or `stmt`
+    /// itself when all of its children are supported.
+    fn unsupported_leaf<'a>(&self, stmt: &'a ProtoStatement) -> &'a ProtoStatement {
+        let children: Vec<&ProtoStatement> = match stmt {
+            ProtoStatement::If(x) => x.true_side.iter().chain(&x.false_side).collect(),
+            ProtoStatement::Case(x) => x
+                .arms
+                .iter()
+                .flat_map(|arm| &arm.body)
+                .chain(&x.default)
+                .collect(),
+            ProtoStatement::For(x) => x.body.iter().collect(),
+            ProtoStatement::SequentialBlock(body) => body.iter().collect(),
+            _ => vec![],
+        };
+        children
+            .into_iter()
+            .find(|x| !self.any_supports_stmt(x))
+            .map_or(stmt, |x| self.unsupported_leaf(x))
+    }
+
     fn compile_group(
         &mut self,
         ctx: &CompileCtx,
         group: Vec<ProtoStatement>,
+        base: usize,
         max_chunk_size: usize,
         out: &mut Vec<ChunkOutput>,
     ) {
         if group.len() <= max_chunk_size {
-            self.compile_group_bisect(ctx, group, out);
+            self.compile_group_bisect(ctx, group, base, out);
         } else {
-            for chunk in group.chunks(max_chunk_size) {
-                self.compile_group_bisect(ctx, chunk.to_vec(), out);
+            for (i, chunk) in group.chunks(max_chunk_size).enumerate() {
+                self.compile_group_bisect(ctx, chunk.to_vec(), base + i * max_chunk_size, out);
             }
         }
     }
@@ -257,11 +323,13 @@ impl BackendRegistry {
     /// than dropping the WHOLE group to the interpreter — otherwise one stray
     /// emitter gap turns a whole large comb interpreted. Each sub-chunk reloads
     /// its inputs (per-chunk `load_cache`, no cross-group store elision), so the
-    /// split is value-preserving. Only fires on failure.
+    /// split is value-preserving. Only fires on failure. `base` is the index
+    /// of `group[0]` in the `build_chunked` input.
     fn compile_group_bisect(
         &mut self,
         ctx: &CompileCtx,
         group: Vec<ProtoStatement>,
+        base: usize,
         out: &mut Vec<ChunkOutput>,
     ) {
         if group.is_empty() {
@@ -279,20 +347,29 @@ impl BackendRegistry {
                     classify_proto_stmt(&group[0])
                 );
             }
+            if ctx.config.profile {
+                let reason = format!(
+                    "{} failed to compile {}",
+                    self.names(),
+                    classify_proto_stmt(&group[0])
+                );
+                self.fallbacks.push((base, reason));
+            }
             out.push(ChunkOutput::Interpreted(group));
             return;
         }
         let mut group = group;
-        let right = group.split_off(group.len() / 2);
-        self.compile_group_bisect(ctx, group, out);
-        self.compile_group_bisect(ctx, right, out);
+        let mid = group.len() / 2;
+        let right = group.split_off(mid);
+        self.compile_group_bisect(ctx, group, base, out);
+        self.compile_group_bisect(ctx, right, base + mid, out);
     }
 }
 
 /// One-line classification of a `ProtoStatement` for `VERYL_CHUNK_BISECT_DIAG`:
 /// names the construct (and width / dynamic-select dims) a chunk backend
 /// declined to emit, so emitter gaps can be prioritised by hotness.
-fn classify_proto_stmt(s: &ProtoStatement) -> String {
+pub(crate) fn classify_proto_stmt(s: &ProtoStatement) -> String {
     match s {
         ProtoStatement::Assign(a) => {
             let dynsel = a.dynamic_select.as_ref().map(|d| {
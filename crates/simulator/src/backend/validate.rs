@@ -1,4 +1,5 @@
-//! `VERYL_AOT_C_VALIDATE` dual-run validation.
+//! `VERYL_AOT_C_VALIDATE` dual-run validation, plus the same check for the
+//! partitioned comb settle (`Config::partition_validate`).
 //!
 //! When enabled, dispatches AOT-C (whole-comb) and Cranelift (per-chunk)
 //! on identical inputs, diffs ff/comb buffers + write_log, panics on
@@ -7,6 +8,7 @@
 //! directly rather than composing two backends.
 
 use crate::backend::{CompiledWhole, DispatchOutcome};
+use crate::ir::partition::CombPool;
 use crate::ir::{Ir, ModuleVariables};
 use crate::simulator::SimProfile;
 use veryl_analyzer::value::MaskCache;
@@ -270,6 +272,101 @@ fn diff_or_panic(
     }
 }
 
+/// `Config::partition_validate`: settle on `pool`, restore inputs, settle
+/// serially, and panic if the buffers or write-log counts differ.  On
+/// success the buffers reflect the serial output.  Strided like
+/// `settle_comb`.
+pub fn settle_partitioned(
+    ir: &Ir,
+    pool: &CombPool,
+    passes: usize,
+    mask_cache: &mut MaskCache,
+    profile: &mut SimProfile,
+) {
+    let stride = ir.aot_c_validate_stride;
+    if stride > 1 {
+        let sample = SETTLE_COUNT.with(|c| {
+            let v = c.get();
+            c.set(v.wrapping_add(1));
+            v % stride == 0
+        });
+        if !sample {
+            ir.run_serial_settle(passes, mask_cache, profile);
+            return;
+        }
+    }
+
+    let comb_ptr = ir.comb_values.as_ptr() as *mut u8;
+    let ff_snap_in: Vec<u8> = ir.ff_values.to_vec();
+    let comb_snap_in: Vec<u8> = ir.comb_values.to_vec();
+    let narrow_snap = ir.write_log_buffer.narrow_count();
+    let wide_snap = ir.write_log_buffer.wide_count();
+    let buf_mut = (&*ir.write_log_buffer) as *const _ as *mut crate::ir::write_log::WriteLogBuffer;
+
+    pool.settle(&ir.comb_statements, passes, mask_cache);
+
+    let ff_par_out: Vec<u8> = ir.ff_values.to_vec();
+    let comb_par_out: Vec<u8> = ir.comb_values.to_vec();
+    let count_par_out = ir.write_log_buffer.count();
+
+    unsafe {
+        let ff_dst = ir.ff_values.as_ptr() as *mut u8;
+        std::ptr::copy_nonoverlapping(ff_snap_in.as_ptr(), ff_dst, ff_snap_in.len());
+        std::ptr::copy_nonoverlapping(comb_snap_in.as_ptr(), comb_ptr, comb_snap_in.len());
+        (*buf_mut).narrow_count = narrow_snap;
+        (*buf_mut).wide_count = wide_snap;
+    }
+
+    ir.run_serial_settle(passes, mask_cache, profile);
+
+    let mut diverged = false;
+    if let Some(off) = comb_par_out
+        .iter()
+        .zip(ir.comb_values.iter())
+        .position(|(a, b)| a != b)
+    {
+        eprintln!(
+            "VERYL_SIM_THREADS validate: comb_values diverge at offset {} \
+             (partitioned={:#x}, serial={:#x}, len={}) var={}",
+            off,
+            comb_par_out[off],
+            ir.comb_values[off],
+            comb_par_out.len(),
+            lookup_comb_offset(&ir.module_variables, comb_ptr, off),
+        );
+        diverged = true;
+    }
+    if let Some(off) = ff_par_out
+        .iter()
+        .zip(ir.ff_values.iter())
+        .position(|(a, b)| a != b)
+    {
+        eprintln!(
+            "VERYL_SIM_THREADS validate: ff_values diverge at offset {} \
+             (partitioned={:#x}, serial={:#x}, len={})",
+            off,
+            ff_par_out[off],
+            ir.ff_values[off],
+            ff_par_out.len(),
+        );
+        diverged = true;
+    }
+    let count_serial_out = ir.write_log_buffer.count();
+    if count_par_out != count_serial_out {
+        eprintln!(
+            "VERYL_SIM_THREADS validate: write_log count diverges (partitioned={}, serial={})",
+            count_par_out, count_serial_out,
+        );
+        diverged = true;
+    }
+    if diverged {
+        panic!(
+            "partitioned / serial divergence in settle_comb ({} threads)",
+            pool.threads()
+        );
+    }
+}
+
 /// Map a byte offset back to a variable name via hierarchy walk.
 /// Used by the diff output to annotate diverging bytes.
 pub fn lookup_comb_offset(vars: &ModuleVariables, comb_base: *const u8, target: usize) -> String {
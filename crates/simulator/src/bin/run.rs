@@ -22,6 +22,8 @@ pub struct Opt {
     pub dump_asm: bool,
     #[arg(long)]
     pub disable_ff_opt: bool,
+    #[arg(long, default_value_t = 1)]
+    pub sim_threads: usize,
 }
 
 impl From<Opt> for Config {
@@ -32,6 +34,7 @@ impl From<Opt> for Config {
             dump_cranelift: value.dump_cranelift,
             dump_asm: value.dump_asm,
             disable_ff_opt: value.disable_ff_opt,
+            sim_threads: value.sim_threads,
             ..Default::default()
         };
         ret.apply_env();
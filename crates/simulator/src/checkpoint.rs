@@ -0,0 +1,81 @@
+//! Simulation checkpoints backing `Simulator::checkpoint` / `restore` and the
+//! `$tb::checkpoint` testbench handle.
+//!
+//! A [`Snapshot`] holds everything a run depends on besides the IR itself:
+//! the FF/comb value buffers, time and cycle counters, the `random_table`,
+//! `file_table` and `queue_table` state, and the opaque state of components that implement the
+//! `snapshot` / `restore` hook. It lives in memory for `fork()` and is written
+//! with postcard for `save()` / `load()`.
+//!
+//! The thread-local branch table records which fork branch the current run
+//! is on, for `branch()`.
+
+use crate::file_table::FileSnapshot;
+use crate::queue_table::QueueSnapshot;
+use crate::random_table::RandomSnapshot;
+use crate::simulator_error::SimulatorError;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use veryl_parser::resource_table::StrId;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Top module name; a snapshot only restores into the same design.
+    pub design: String,
+    pub time: u64,
+    pub cycle_count: u64,
+    pub wave_enabled: bool,
+    pub ff_values: Vec<u8>,
+    pub comb_values: Vec<u8>,
+    pub prev_derived_clock_values: Vec<u8>,
+    pub random: RandomSnapshot,
+    pub files: FileSnapshot,
+    /// (instance name, state) per component; `None` when the component has
+    /// no checkpoint hook and keeps its live state across a restore.
+    pub components: Vec<(String, Option<Vec<u8>>)>,
+    #[serde(default)]
+    pub queues: QueueSnapshot,
+}
+
+impl Snapshot {
+    pub fn save(&self, path: &Path) -> Result<(), SimulatorError> {
+        let bytes = postcard::to_allocvec(self).map_err(|e| SimulatorError::Checkpoint {
+            message: format!("failed to encode snapshot: {e}"),
+        })?;
+        std::fs::write(path, bytes).map_err(|e| SimulatorError::IoError {
+            message: format!("failed to write checkpoint {}: {e}", path.display()),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SimulatorError> {
+        let bytes = std::fs::read(path).map_err(|e| SimulatorError::IoError {
+            message: format!("failed to read checkpoint {}: {e}", path.display()),
+        })?;
+        postcard::from_bytes(&bytes).map_err(|e| SimulatorError::Checkpoint {
+            message: format!("{} is not a valid checkpoint: {e}", path.display()),
+        })
+    }
+}
+
+thread_local! {
+    static BRANCHES: RefCell<HashMap<StrId, u64>> = RefCell::new(HashMap::new());
+}
+
+/// Forget every handle's branch. Call before a test.
+pub fn reset() {
+    BRANCHES.with(|b| b.borrow_mut().clear());
+}
+
+/// Record that the run continues as branch `index` of handle `key`'s fork.
+pub fn set_branch(key: StrId, index: u64) {
+    BRANCHES.with(|b| {
+        b.borrow_mut().insert(key, index);
+    });
+}
+
+/// Branch index of handle `key`; 0 before (or without) a fork.
+pub fn branch(key: StrId) -> u64 {
+    BRANCHES.with(|b| b.borrow().get(&key).copied().unwrap_or(0))
+}
@@ -19,6 +19,12 @@ use veryl_metadata::{
 use veryl_parser::resource_table;
 use veryl_parser::resource_table::StrId;
 
+/// Optional checkpoint hook: a component method taking one string argument,
+/// the path of a file to write its state to (through the host file service).
+pub const SNAPSHOT_METHOD: &str = "snapshot";
+/// Counterpart of [`SNAPSHOT_METHOD`]: reads the state back from the path.
+pub const RESTORE_METHOD: &str = "restore";
+
 pub struct RuntimeComponent {
     pub name: String,
     pub name_id: resource_table::StrId,
@@ -28,6 +34,10 @@ pub struct RuntimeComponent {
     /// manifest. Native runs never block file access, so undeclared use is
     /// surfaced as a warning to catch it before the wasm form refuses it.
     file_declared: Option<bool>,
+    /// Whether the manifest declares both [`SNAPSHOT_METHOD`] and
+    /// [`RESTORE_METHOD`], making the instance's state part of simulation
+    /// checkpoints.
+    pub checkpoint_hook: bool,
     /// Inputs staged before every hook: (host port idx, source, width).
     inputs: Vec<(u32, InputSource, u32)>,
     /// Dirty outputs written back after hooks.
@@ -728,12 +738,18 @@ pub fn build_components(
         let file_declared = manifest
             .as_ref()
             .map(|m| m.requires.iter().any(|r| r == "file"));
+        let checkpoint_hook = manifest.as_ref().is_some_and(|m| {
+            [SNAPSHOT_METHOD, RESTORE_METHOD]
+                .iter()
+                .all(|name| m.methods.iter().any(|x| x.name == *name))
+        });
         result.push(RuntimeComponent {
             name: inst_name,
             name_id: ext.name,
             instance,
             host,
             file_declared,
+            checkpoint_hook,
             inputs,
             outputs,
             clock_events,
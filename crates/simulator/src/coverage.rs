@@ -0,0 +1,413 @@
+//! Code coverage for the native simulator.
+//!
+//! Coverage points are registered in a process-wide table while the
+//! simulator IR is built, so every instance of a module — and every test —
+//! shares one id per source location.  Hits are counted per thread (tests
+//! run on worker threads) and collected with [`take`] after each test.
+//!
+//! * Statement/branch: a `Cover` system call is inserted at the head of
+//!   every `if`/`case` arm and every `always_ff` body (and `always_comb`
+//!   bodies that branch).  The interpreter, Cranelift and AOT-C backends all
+//!   execute it, so the same counters are used whichever backend runs.
+//! * Toggle/FSM: [`Sampler`] reads ports, registers and enum-typed registers
+//!   after every simulation step, once comb logic has settled.
+//!
+//! Comb blocks are counted per evaluation, so their counts include settle
+//! passes; only zero vs non-zero is meaningful there.
+
+mod report;
+
+pub use report::{Branch, BranchKind, FileReport, Report, Totals, Transition};
+
+use crate::HashMap;
+use crate::ir::{ModuleVariables, ProtoStatement, ProtoSystemFunctionCall};
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use veryl_analyzer::ir as air;
+use veryl_analyzer::symbol::{Affiliation, SymbolId};
+use veryl_parser::resource_table;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::TokenSource;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub file: PathBuf,
+    pub line: u32,
+    pub column: u32,
+    pub kind: PointKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PointKind {
+    /// Entry into the statements at `lines`.  `arm` is the branch of the
+    /// `if`/`case` at this location; `None` for an `always_*` body.
+    Block {
+        arm: Option<u32>,
+        lines: Vec<u32>,
+    },
+    /// `bit` of `signal` going 0→1 (`rise`) or 1→0.
+    Toggle {
+        signal: String,
+        bit: u32,
+        rise: bool,
+    },
+    /// An enum-typed register; counts samples.  Its states are the members
+    /// of `r#enum`.
+    Fsm {
+        signal: String,
+        r#enum: SymbolId,
+    },
+    State {
+        signal: String,
+        value: u64,
+    },
+    Transition {
+        signal: String,
+        from: u64,
+        to: u64,
+    },
+}
+
+#[derive(Default)]
+struct Registry {
+    ids: HashMap<Point, u32>,
+    points: Vec<Point>,
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(Default::default);
+
+thread_local! {
+    static COUNTS: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Id of `point`, registering it on first use.
+pub fn register(point: Point) -> u32 {
+    let mut registry = REGISTRY.lock().unwrap();
+    if let Some(id) = registry.ids.get(&point) {
+        return *id;
+    }
+    let id = registry.points.len() as u32;
+    registry.points.push(point.clone());
+    registry.ids.insert(point, id);
+    id
+}
+
+/// Forgets every point, so ids restart for a fresh run.  Counters not yet
+/// collected with [`take`] become meaningless.
+pub fn clear() {
+    *REGISTRY.lock().unwrap() = Registry::default();
+}
+
+/// Every registered point, indexed by id.
+pub fn points() -> Vec<Point> {
+    REGISTRY.lock().unwrap().points.clone()
+}
+
+pub fn hit(id: u32) {
+    COUNTS.with(|c| {
+        let mut c = c.borrow_mut();
+        let i = id as usize;
+        if c.len() <= i {
+            c.resize(i + 1, 0);
+        }
+        c[i] += 1;
+    });
+}
+
+/// Called by JIT and AOT-C code for a `Cover` statement.
+pub extern "C" fn veryl_cover_hit(id: u32) {
+    hit(id);
+}
+
+/// Hits counted on this thread since the last call, indexed by id.
+pub fn take() -> Vec<u64> {
+    COUNTS.with(|c| std::mem::take(&mut *c.borrow_mut()))
+}
+
+/// Adds `counts` into `total`, both indexed by id.
+pub fn merge(total: &mut Vec<u64>, counts: &[u64]) {
+    if total.len() < counts.len() {
+        total.resize(counts.len(), 0);
+    }
+    for (x, y) in total.iter_mut().zip(counts) {
+        *x += y;
+    }
+}
+
+fn location(token: &TokenRange) -> Option<(PathBuf, u32, u32)> {
+    match token.beg.source {
+        TokenSource::File { path, .. } => Some((
+            resource_table::get_path_value(path)?,
+            token.beg.line,
+            token.beg.column,
+        )),
+        _ => None,
+    }
+}
+
+pub(crate) fn statement_token(stmt: &air::Statement) -> Option<TokenRange> {
+    match stmt {
+        air::Statement::Assign(x) => Some(x.token),
+        air::Statement::If(x) => Some(x.token),
+        air::Statement::IfReset(x) => Some(x.token),
+        air::Statement::Case(x) => Some(x.token),
+        air::Statement::For(x) => Some(x.token),
+        air::Statement::Fork(x) => Some(x.token),
+        air::Statement::SystemFunctionCall(x) => Some(x.comptime.token),
+        air::Statement::FunctionCall(x) => Some(x.comptime.token),
+        air::Statement::TbMethodCall(_)
+        | air::Statement::Break
+        | air::Statement::Unsupported(_)
+        | air::Statement::Null => None,
+    }
+}
+
+/// `Cover` statement for entering `stmts`; `token` locates the owning
+/// `if`/`case` (or the first statement of an `always_*` body).
+pub(crate) fn cover_block(
+    token: &TokenRange,
+    arm: Option<u32>,
+    stmts: &[air::Statement],
+) -> Option<ProtoStatement> {
+    let (file, line, column) = location(token)?;
+    let mut lines: Vec<u32> = stmts
+        .iter()
+        .filter_map(statement_token)
+        .map(|x| x.beg.line)
+        .collect();
+    lines.dedup();
+    let id = register(Point {
+        file,
+        line,
+        column,
+        kind: PointKind::Block { arm, lines },
+    });
+    Some(ProtoStatement::SystemFunctionCall(
+        ProtoSystemFunctionCall::Cover { id },
+    ))
+}
+
+/// Whether an `always_comb` body is worth a block counter: without a branch
+/// it runs on every settle, so its statements are trivially covered.
+pub(crate) fn has_branch(stmts: &[air::Statement]) -> bool {
+    stmts.iter().any(|x| {
+        matches!(
+            x,
+            air::Statement::If(_) | air::Statement::IfReset(_) | air::Statement::Case(_)
+        )
+    })
+}
+
+struct ToggleSignal {
+    ptr: *const u8,
+    native_bytes: usize,
+    width: usize,
+    /// `[fall, rise]` point ids per bit.
+    ids: Vec<[u32; 2]>,
+    last: Option<Vec<u8>>,
+}
+
+struct FsmSignal {
+    ptr: *const u8,
+    native_bytes: usize,
+    width: usize,
+    point: Point,
+    sample_id: u32,
+    states: HashMap<u64, u32>,
+    transitions: HashMap<(u64, u64), u32>,
+    last: Option<u64>,
+}
+
+impl FsmSignal {
+    fn register(&self, kind: PointKind) -> u32 {
+        register(Point {
+            kind,
+            ..self.point.clone()
+        })
+    }
+
+    fn signal(&self) -> String {
+        match &self.point.kind {
+            PointKind::Fsm { signal, .. } => signal.clone(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Toggle and FSM coverage, sampled by the simulator after each step.
+pub struct Sampler {
+    use_4state: bool,
+    toggles: Vec<ToggleSignal>,
+    fsms: Vec<FsmSignal>,
+}
+
+// SAFETY: the pointers refer to the owning `Ir`'s buffers and are only
+// dereferenced by the thread driving that `Ir`.
+unsafe impl Send for Sampler {}
+
+impl Sampler {
+    /// Ports and registers of every instance under `vars`.  Unpacked arrays,
+    /// clocks and resets are not sampled.
+    pub fn new(vars: &ModuleVariables, use_4state: bool) -> Self {
+        let mut ret = Sampler {
+            use_4state,
+            toggles: Vec::new(),
+            fsms: Vec::new(),
+        };
+        ret.collect(vars);
+        ret
+    }
+
+    fn collect(&mut self, vars: &ModuleVariables) {
+        let mut sorted: Vec<_> = vars.variables.iter().collect();
+        sorted.sort_by_key(|(id, _)| **id);
+        for (_, var) in sorted {
+            // Clocks and resets are driven as events, not as toggling values.
+            if var.current_values.len() != 1
+                || var.width == 0
+                || var.affiliation == Affiliation::Function
+                || var.r#type.is_clock()
+                || var.r#type.is_reset()
+            {
+                continue;
+            }
+            let is_ff = !var.next_values.is_empty();
+            if !(var.kind.is_port() || is_ff && var.kind == air::VarKind::Variable) {
+                continue;
+            }
+            let Some((file, line, column)) = location(&var.token) else {
+                continue;
+            };
+            let signal = var.path.to_string();
+            let ptr = var.current_values[0] as *const u8;
+
+            let ids = (0..var.width as u32)
+                .map(|bit| {
+                    [false, true].map(|rise| {
+                        register(Point {
+                            file: file.clone(),
+                            line,
+                            column,
+                            kind: PointKind::Toggle {
+                                signal: signal.clone(),
+                                bit,
+                                rise,
+                            },
+                        })
+                    })
+                })
+                .collect();
+            self.toggles.push(ToggleSignal {
+                ptr,
+                native_bytes: var.native_bytes,
+                width: var.width,
+                ids,
+                last: None,
+            });
+
+            if let air::TypeKind::Enum(x) = &var.r#type.kind
+                && is_ff
+                && var.width <= 64
+            {
+                let point = Point {
+                    file,
+                    line,
+                    column,
+                    kind: PointKind::Fsm {
+                        signal,
+                        r#enum: x.id,
+                    },
+                };
+                self.fsms.push(FsmSignal {
+                    ptr,
+                    native_bytes: var.native_bytes,
+                    width: var.width,
+                    sample_id: register(point.clone()),
+                    point,
+                    states: HashMap::default(),
+                    transitions: HashMap::default(),
+                    last: None,
+                });
+            }
+        }
+        for child in &vars.children {
+            self.collect(child);
+        }
+    }
+
+    pub fn sample(&mut self) {
+        let use_4state = self.use_4state;
+        for t in &mut self.toggles {
+            let nb = t.native_bytes;
+            let span = nb * (1 + use_4state as usize);
+            // SAFETY: `ptr` addresses `span` bytes of the variable's storage.
+            let cur = unsafe { std::slice::from_raw_parts(t.ptr, span) };
+            if let Some(last) = &mut t.last {
+                if last.as_slice() != cur {
+                    for (bit, ids) in t.ids.iter().enumerate() {
+                        let (byte, mask) = (bit / 8, 1u8 << (bit % 8));
+                        let (old, new) = (last[byte] & mask, cur[byte] & mask);
+                        let unknown = use_4state && (last[nb + byte] | cur[nb + byte]) & mask != 0;
+                        if old != new && !unknown {
+                            hit(ids[(new != 0) as usize]);
+                        }
+                    }
+                    last.copy_from_slice(cur);
+                }
+            } else {
+                t.last = Some(cur.to_vec());
+            }
+            debug_assert!(t.width <= nb * 8);
+        }
+
+        for f in &mut self.fsms {
+            let nb = f.native_bytes;
+            // SAFETY: as above.
+            let bytes =
+                unsafe { std::slice::from_raw_parts(f.ptr, nb * (1 + use_4state as usize)) };
+            if use_4state && bytes[nb..].iter().any(|x| *x != 0) {
+                continue;
+            }
+            let mut raw = [0u8; 8];
+            let n = nb.min(8);
+            raw[..n].copy_from_slice(&bytes[..n]);
+            let mut value = u64::from_le_bytes(raw);
+            if f.width < 64 {
+                value &= (1u64 << f.width) - 1;
+            }
+
+            hit(f.sample_id);
+            let id = match f.states.get(&value) {
+                Some(id) => *id,
+                None => {
+                    let id = f.register(PointKind::State {
+                        signal: f.signal(),
+                        value,
+                    });
+                    f.states.insert(value, id);
+                    id
+                }
+            };
+            hit(id);
+            if let Some(from) = f.last
+                && from != value
+            {
+                let id = match f.transitions.get(&(from, value)) {
+                    Some(id) => *id,
+                    None => {
+                        let id = f.register(PointKind::Transition {
+                            signal: f.signal(),
+                            from,
+                            to: value,
+                        });
+                        f.transitions.insert((from, value), id);
+                        id
+                    }
+                };
+                hit(id);
+            }
+            f.last = Some(value);
+        }
+    }
+}
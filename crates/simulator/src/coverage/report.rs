@@ -0,0 +1,401 @@
+use super::{Point, PointKind};
+use num_traits::ToPrimitive;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use veryl_analyzer::symbol::{SymbolId, SymbolKind};
+use veryl_analyzer::symbol_table;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchKind {
+    /// An `if`/`case` arm.
+    Branch,
+    /// A rising or falling edge of one bit.
+    Toggle,
+    /// A member of an enum register's type.
+    State,
+}
+
+#[derive(Clone, Debug)]
+pub struct Branch {
+    pub kind: BranchKind,
+    pub line: u32,
+    /// Numbered per file, one per `if`/`case`, toggled signal or FSM.
+    pub block: u32,
+    pub index: u32,
+    pub name: String,
+    pub count: u64,
+}
+
+/// An observed FSM transition.  Only transitions that happened are known,
+/// so they appear in the summary but not in LCOV/Cobertura.
+#[derive(Clone, Debug)]
+pub struct Transition {
+    pub line: u32,
+    pub signal: String,
+    pub from: String,
+    pub to: String,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FileReport {
+    /// Executions per source line.
+    pub lines: BTreeMap<u32, u64>,
+    pub branches: Vec<Branch>,
+    pub transitions: Vec<Transition>,
+}
+
+/// `(covered, total)` pairs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub lines: (usize, usize),
+    pub branches: (usize, usize),
+    pub toggles: (usize, usize),
+    pub states: (usize, usize),
+    pub transitions: usize,
+}
+
+impl AddAssign for Totals {
+    fn add_assign(&mut self, x: Totals) {
+        let add = |a: &mut (usize, usize), b: (usize, usize)| {
+            a.0 += b.0;
+            a.1 += b.1;
+        };
+        add(&mut self.lines, x.lines);
+        add(&mut self.branches, x.branches);
+        add(&mut self.toggles, x.toggles);
+        add(&mut self.states, x.states);
+        self.transitions += x.transitions;
+    }
+}
+
+impl FileReport {
+    pub fn totals(&self) -> Totals {
+        let covered = |kind| {
+            let all = self.branches.iter().filter(|x| x.kind == kind);
+            (all.clone().filter(|x| x.count != 0).count(), all.count())
+        };
+        Totals {
+            lines: (
+                self.lines.values().filter(|x| **x != 0).count(),
+                self.lines.len(),
+            ),
+            branches: covered(BranchKind::Branch),
+            toggles: covered(BranchKind::Toggle),
+            states: covered(BranchKind::State),
+            transitions: self.transitions.len(),
+        }
+    }
+}
+
+/// Coverage mapped back to `.veryl` files.  Build it on the thread that ran
+/// the analyzer: FSM state names come from its symbol table.
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    pub files: BTreeMap<PathBuf, FileReport>,
+}
+
+type SignalKey = (PathBuf, u32, u32, String);
+
+impl Report {
+    /// `counts` is indexed by point id, as returned by
+    /// [`super::points`] and [`super::take`].
+    pub fn new(points: &[Point], counts: &[u64]) -> Self {
+        let count = |id: usize| counts.get(id).copied().unwrap_or(0);
+        let mut sorted: Vec<_> = points
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x, count(i)))
+            .collect();
+        sorted.sort_by(|a, b| a.0.cmp(b.0));
+
+        // A re-analysis (e.g. `--watch`) registers the same register again
+        // under a new enum symbol; only the latest registration is current.
+        let mut fsms: BTreeMap<SignalKey, SymbolId> = BTreeMap::new();
+        for point in points {
+            if let PointKind::Fsm { signal, r#enum } = &point.kind {
+                let key = (point.file.clone(), point.line, point.column, signal.clone());
+                fsms.insert(key, *r#enum);
+            }
+        }
+
+        let mut states: BTreeMap<SignalKey, BTreeMap<u64, u64>> = BTreeMap::new();
+        let mut transitions: BTreeMap<SignalKey, Vec<(u64, u64, u64)>> = BTreeMap::new();
+        for (point, count) in &sorted {
+            let key =
+                |signal: &String| (point.file.clone(), point.line, point.column, signal.clone());
+            match &point.kind {
+                PointKind::State { signal, value } => {
+                    *states
+                        .entry(key(signal))
+                        .or_default()
+                        .entry(*value)
+                        .or_default() += count;
+                }
+                PointKind::Transition { signal, from, to } => {
+                    transitions
+                        .entry(key(signal))
+                        .or_default()
+                        .push((*from, *to, *count));
+                }
+                _ => (),
+            }
+        }
+
+        let mut ret = Report::default();
+        let mut blocks: BTreeMap<SignalKey, u32> = BTreeMap::new();
+        let mut block_id = |file: &Path, line, column, signal: &str| {
+            let next = blocks.keys().filter(|x| x.0 == file).count() as u32;
+            *blocks
+                .entry((file.to_path_buf(), line, column, signal.to_string()))
+                .or_insert(next)
+        };
+
+        for (point, count) in sorted {
+            let file = ret.files.entry(point.file.clone()).or_default();
+            match &point.kind {
+                PointKind::Block { arm, lines } => {
+                    for line in lines {
+                        *file.lines.entry(*line).or_default() += count;
+                    }
+                    if let Some(arm) = arm {
+                        file.branches.push(Branch {
+                            kind: BranchKind::Branch,
+                            line: point.line,
+                            block: block_id(&point.file, point.line, point.column, ""),
+                            index: *arm,
+                            name: format!("arm {arm}"),
+                            count,
+                        });
+                    }
+                }
+                PointKind::Toggle { signal, bit, rise } => {
+                    let edge = if *rise { "rise" } else { "fall" };
+                    file.branches.push(Branch {
+                        kind: BranchKind::Toggle,
+                        line: point.line,
+                        block: block_id(&point.file, point.line, point.column, signal),
+                        index: bit * 2 + *rise as u32,
+                        name: format!("{signal}[{bit}] {edge}"),
+                        count,
+                    });
+                }
+                PointKind::Fsm { signal, r#enum } => {
+                    let key = (point.file.clone(), point.line, point.column, signal.clone());
+                    if fsms.get(&key) != Some(r#enum) {
+                        continue;
+                    }
+                    let observed = states.remove(&key).unwrap_or_default();
+                    let members = enum_members(*r#enum);
+                    let name = |value: u64| {
+                        members
+                            .iter()
+                            .find(|x| x.1 == value)
+                            .map(|x| x.0.clone())
+                            .unwrap_or_else(|| format!("'h{value:x}"))
+                    };
+
+                    let mut values: Vec<u64> = members.iter().map(|x| x.1).collect();
+                    let extra: Vec<u64> = observed
+                        .keys()
+                        .filter(|x| !values.contains(x))
+                        .copied()
+                        .collect();
+                    values.extend(extra);
+                    let block = block_id(
+                        &point.file,
+                        point.line,
+                        point.column,
+                        &format!("fsm {signal}"),
+                    );
+                    for (index, value) in values.into_iter().enumerate() {
+                        file.branches.push(Branch {
+                            kind: BranchKind::State,
+                            line: point.line,
+                            block,
+                            index: index as u32,
+                            name: format!("{signal} == {}", name(value)),
+                            count: observed.get(&value).copied().unwrap_or(0),
+                        });
+                    }
+                    for (from, to, count) in transitions.remove(&key).unwrap_or_default() {
+                        file.transitions.push(Transition {
+                            line: point.line,
+                            signal: signal.clone(),
+                            from: name(from),
+                            to: name(to),
+                            count,
+                        });
+                    }
+                }
+                PointKind::State { .. } | PointKind::Transition { .. } => (),
+            }
+        }
+        ret
+    }
+
+    pub fn totals(&self) -> Totals {
+        let mut ret = Totals::default();
+        for file in self.files.values() {
+            ret += file.totals();
+        }
+        ret
+    }
+
+    /// LCOV tracefile.  Toggles and FSM states are emitted as branches on
+    /// the declaring line.
+    pub fn lcov(&self) -> String {
+        let mut ret = String::new();
+        for (path, file) in &self.files {
+            let totals = file.totals();
+            let _ = writeln!(ret, "TN:");
+            let _ = writeln!(ret, "SF:{}", path.to_string_lossy());
+            for x in &file.branches {
+                let taken = if x.count == 0 {
+                    "-".to_string()
+                } else {
+                    x.count.to_string()
+                };
+                let _ = writeln!(ret, "BRDA:{},{},{},{taken}", x.line, x.block, x.index);
+            }
+            let (hit, found) = (
+                totals.branches.0 + totals.toggles.0 + totals.states.0,
+                totals.branches.1 + totals.toggles.1 + totals.states.1,
+            );
+            let _ = writeln!(ret, "BRF:{found}");
+            let _ = writeln!(ret, "BRH:{hit}");
+            for (line, count) in &file.lines {
+                let _ = writeln!(ret, "DA:{line},{count}");
+            }
+            let _ = writeln!(ret, "LF:{}", totals.lines.1);
+            let _ = writeln!(ret, "LH:{}", totals.lines.0);
+            let _ = writeln!(ret, "end_of_record");
+        }
+        ret
+    }
+
+    /// Cobertura XML with file names relative to `base`.
+    pub fn cobertura(&self, base: &Path, timestamp: u64) -> String {
+        let totals = self.totals();
+        let branch_total = |x: &Totals| {
+            (
+                x.branches.0 + x.toggles.0 + x.states.0,
+                x.branches.1 + x.toggles.1 + x.states.1,
+            )
+        };
+        let (bh, bf) = branch_total(&totals);
+
+        let mut ret = String::new();
+        let _ = writeln!(ret, r#"<?xml version="1.0" ?>"#);
+        let _ = writeln!(
+            ret,
+            r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+        );
+        let _ = writeln!(
+            ret,
+            r#"<coverage line-rate="{}" branch-rate="{}" lines-covered="{}" lines-valid="{}" branches-covered="{bh}" branches-valid="{bf}" complexity="0" version="{}" timestamp="{timestamp}">"#,
+            rate(totals.lines),
+            rate((bh, bf)),
+            totals.lines.0,
+            totals.lines.1,
+            env!("CARGO_PKG_VERSION"),
+        );
+        let _ = writeln!(ret, "  <sources>");
+        let _ = writeln!(
+            ret,
+            "    <source>{}</source>",
+            escape(&base.to_string_lossy())
+        );
+        let _ = writeln!(ret, "  </sources>");
+        let _ = writeln!(ret, "  <packages>");
+        let _ = writeln!(
+            ret,
+            r#"    <package name="" line-rate="{}" branch-rate="{}" complexity="0">"#,
+            rate(totals.lines),
+            rate((bh, bf))
+        );
+        let _ = writeln!(ret, "      <classes>");
+        for (path, file) in &self.files {
+            let t = file.totals();
+            let name = path.strip_prefix(base).unwrap_or(path).to_string_lossy();
+            let _ = writeln!(
+                ret,
+                r#"        <class name="{0}" filename="{0}" line-rate="{1}" branch-rate="{2}" complexity="0">"#,
+                escape(&name),
+                rate(t.lines),
+                rate(branch_total(&t))
+            );
+            let _ = writeln!(ret, "          <methods/>");
+            let _ = writeln!(ret, "          <lines>");
+
+            let mut lines: BTreeMap<u32, (u64, usize, usize)> = BTreeMap::new();
+            for (line, count) in &file.lines {
+                lines.entry(*line).or_default().0 += count;
+            }
+            for x in &file.branches {
+                let entry = lines.entry(x.line).or_default();
+                entry.0 += x.count;
+                entry.1 += (x.count != 0) as usize;
+                entry.2 += 1;
+            }
+            for (line, (hits, covered, total)) in lines {
+                if let Some(percent) = (covered * 100).checked_div(total) {
+                    let _ = writeln!(
+                        ret,
+                        r#"            <line number="{line}" hits="{hits}" branch="true" condition-coverage="{percent}% ({covered}/{total})"/>"#
+                    );
+                } else {
+                    let _ = writeln!(
+                        ret,
+                        r#"            <line number="{line}" hits="{hits}" branch="false"/>"#
+                    );
+                }
+            }
+            let _ = writeln!(ret, "          </lines>");
+            let _ = writeln!(ret, "        </class>");
+        }
+        let _ = writeln!(ret, "      </classes>");
+        let _ = writeln!(ret, "    </package>");
+        let _ = writeln!(ret, "  </packages>");
+        let _ = writeln!(ret, "</coverage>");
+        ret
+    }
+}
+
+fn rate((covered, total): (usize, usize)) -> String {
+    if total == 0 {
+        "1".to_string()
+    } else {
+        format!("{:.4}", covered as f64 / total as f64)
+    }
+}
+
+fn escape(x: &str) -> String {
+    x.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `(name, value)` of every member of the enum `id`; empty when the symbol
+/// table does not know it.
+fn enum_members(id: SymbolId) -> Vec<(String, u64)> {
+    let Some(symbol) = symbol_table::get(id) else {
+        return Vec::new();
+    };
+    let SymbolKind::Enum(x) = symbol.kind else {
+        return Vec::new();
+    };
+    x.members
+        .iter()
+        .filter_map(|id| {
+            let member = symbol_table::get(*id)?;
+            let SymbolKind::EnumMember(x) = &member.kind else {
+                return None;
+            };
+            Some((member.token.to_string(), x.value.value()?.to_u64()?))
+        })
+        .collect()
+}
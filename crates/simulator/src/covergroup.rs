@@ -0,0 +1,452 @@
+//! Functional coverage collected through `$tb::coverage` handles.
+//!
+//! Like `random_table`, a thread-local keeps the current test's
+//! [`Database`] reachable from the testbench driver; [`take`] collects it
+//! once the test has run.  Groups are keyed by the handle's variable name,
+//! coverpoints and crosses by the names the testbench gives them, and bins
+//! by their label, so databases from different tests, seeds and runs merge
+//! with [`Database::merge`].
+//!
+//! * `point(name)` declares a coverpoint with automatic bins, laid out over
+//!   the width of the first sampled value ([`AUTO_BINS`] at most).
+//! * `bins(name, v, ...)` adds one bin per value; `range(name, min, max)`
+//!   one bin for the range, or `n` equal bins with a fourth argument.
+//! * `cross(name, a, b, ...)` counts the bin combinations of coverpoints
+//!   sampled together.  Only combinations that were hit are stored; a cross
+//!   of more than [`MAX_CROSS_BINS`] combinations fails the sample, since its
+//!   holes could not be listed.
+//! * `sample(name, value, ...)` records one value per coverpoint.  A value
+//!   hits every bin containing it; X/Z values hit none.
+
+use crate::ir::Value;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use veryl_parser::resource_table::{self, StrId};
+
+/// Most automatic bins of a coverpoint.
+pub const AUTO_BINS: u32 = 64;
+
+/// Ranges split into more bins than this are rejected.
+const MAX_BINS: u64 = 1 << 16;
+
+/// Most bin combinations of a cross.
+pub const MAX_CROSS_BINS: u64 = 1 << 16;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Database {
+    pub groups: BTreeMap<String, Group>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Group {
+    pub points: BTreeMap<String, Coverpoint>,
+    pub crosses: BTreeMap<String, Cross>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coverpoint {
+    /// Bins are laid out by the first sample rather than declared.
+    pub auto: bool,
+    pub bins: Vec<Bin>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bin {
+    pub label: String,
+    pub min: u64,
+    pub max: u64,
+    pub hits: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cross {
+    pub points: Vec<String>,
+    /// Hits per bin combination, keyed by the bin labels joined with `, `.
+    pub hits: BTreeMap<String, u64>,
+}
+
+/// Coverage of one coverpoint or cross, as reported by `veryl test`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Item {
+    /// `group.point` or `group.cross`.
+    pub name: String,
+    pub covered: usize,
+    pub total: usize,
+    /// Labels of the bins (or bin combinations) never hit.
+    pub holes: Vec<String>,
+}
+
+thread_local! {
+    static DB: RefCell<Database> = RefCell::new(Database::default());
+}
+
+/// The database recorded on this thread since the last call.
+pub fn take() -> Database {
+    DB.with(|x| std::mem::take(&mut *x.borrow_mut()))
+}
+
+fn with_group<R>(group: StrId, f: impl FnOnce(&mut Group) -> R) -> R {
+    let name = resource_table::get_str_value(group).unwrap_or_default();
+    DB.with(|x| f(x.borrow_mut().groups.entry(name).or_default()))
+}
+
+impl Group {
+    /// Number of bin combinations of a cross over `points`, saturating.
+    fn cross_size(&self, points: &[String]) -> u64 {
+        points.iter().fold(1u64, |size, x| {
+            let bins = self.points.get(x).map(|x| x.bins.len()).unwrap_or(0);
+            size.saturating_mul(bins as u64)
+        })
+    }
+}
+
+fn check_cross_size(group: &Group, name: &str, points: &[String]) -> Result<(), String> {
+    let size = group.cross_size(points);
+    if size > MAX_CROSS_BINS {
+        Err(format!(
+            "cross `{name}` has {size} bin combinations, more than {MAX_CROSS_BINS}"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+impl Coverpoint {
+    fn add_bin(&mut self, label: String, min: u64, max: u64) {
+        if self.auto {
+            self.auto = false;
+            self.bins.clear();
+        }
+        if !self.bins.iter().any(|x| x.label == label) {
+            self.bins.push(Bin {
+                label,
+                min,
+                max,
+                hits: 0,
+            });
+        }
+    }
+
+    fn layout_auto(&mut self, width: usize) {
+        let width = width.clamp(1, 64) as u32;
+        let bins = if width >= 6 { AUTO_BINS } else { 1 << width };
+        let shift = width - bins.trailing_zeros();
+        for i in 0..bins as u64 {
+            let min = i << shift;
+            let max = min + ((1u128 << shift) - 1) as u64;
+            let label = if min == max {
+                format!("auto[{min}]")
+            } else {
+                format!("auto[{min}:{max}]")
+            };
+            self.bins.push(Bin {
+                label,
+                min,
+                max,
+                hits: 0,
+            });
+        }
+    }
+}
+
+/// `point(name)`.
+pub fn declare_point(group: StrId, name: &str) {
+    with_group(group, |g| {
+        g.points
+            .entry(name.to_string())
+            .or_insert_with(|| Coverpoint {
+                auto: true,
+                bins: Vec::new(),
+            });
+    });
+}
+
+/// `bins(name, values...)`.
+pub fn declare_bins(group: StrId, name: &str, values: &[u64]) {
+    with_group(group, |g| {
+        let point = g.points.entry(name.to_string()).or_default();
+        for value in values {
+            point.add_bin(value.to_string(), *value, *value);
+        }
+    });
+}
+
+/// `range(name, min, max[, count])`.
+pub fn declare_range(
+    group: StrId,
+    name: &str,
+    min: u64,
+    max: u64,
+    count: Option<u64>,
+) -> Result<(), String> {
+    let (min, max) = (min.min(max), min.max(max));
+    let span = (max - min) as u128 + 1;
+    let count = count.unwrap_or(1);
+    if count == 0 || count > MAX_BINS || count as u128 > span {
+        return Err(format!(
+            "coverpoint `{name}`: cannot split [{min}:{max}] into {count} bin(s)"
+        ));
+    }
+    with_group(group, |g| {
+        let point = g.points.entry(name.to_string()).or_default();
+        // Leftover values go to the last bin, as SystemVerilog does.
+        let size = span / count as u128;
+        for i in 0..count as u128 {
+            let lo = min + (i * size) as u64;
+            let hi = if i + 1 == count as u128 {
+                max
+            } else {
+                lo + (size - 1) as u64
+            };
+            let label = if lo == hi {
+                lo.to_string()
+            } else {
+                format!("[{lo}:{hi}]")
+            };
+            point.add_bin(label, lo, hi);
+        }
+    });
+    Ok(())
+}
+
+/// `cross(name, points...)`.
+pub fn declare_cross(group: StrId, name: &str, points: &[String]) -> Result<(), String> {
+    with_group(group, |g| {
+        if let Some(x) = points.iter().find(|x| !g.points.contains_key(*x)) {
+            return Err(format!("cross `{name}`: coverpoint `{x}` is not declared"));
+        }
+        check_cross_size(g, name, points)?;
+        g.crosses.entry(name.to_string()).or_insert_with(|| Cross {
+            points: points.to_vec(),
+            hits: BTreeMap::new(),
+        });
+        Ok(())
+    })
+}
+
+/// `sample(name, value, ...)`.
+pub fn sample(group: StrId, names: &[String], values: &[Value]) -> Result<(), String> {
+    with_group(group, |g| {
+        let mut hit: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for (name, value) in names.iter().zip(values) {
+            let Some(point) = g.points.get_mut(name) else {
+                return Err(format!("coverpoint `{name}` is not declared"));
+            };
+            if point.auto && point.bins.is_empty() {
+                point.layout_auto(value.width());
+            }
+            if value.is_xz() {
+                continue;
+            }
+            let value = value.payload_u64();
+            let labels = hit.entry(name.as_str()).or_default();
+            for bin in point.bins.iter_mut() {
+                if bin.min <= value && value <= bin.max {
+                    bin.hits += 1;
+                    labels.push(bin.label.clone());
+                }
+            }
+        }
+        // Bins may be declared after the cross, so its size is checked here.
+        for (name, cross) in &g.crosses {
+            check_cross_size(g, name, &cross.points)?;
+        }
+        for cross in g.crosses.values_mut() {
+            let mut combos = vec![String::new()];
+            for point in &cross.points {
+                let Some(labels) = hit.get(point.as_str()) else {
+                    combos.clear();
+                    break;
+                };
+                combos = combos
+                    .iter()
+                    .flat_map(|x| {
+                        labels.iter().map(move |y| {
+                            if x.is_empty() {
+                                y.clone()
+                            } else {
+                                format!("{x}, {y}")
+                            }
+                        })
+                    })
+                    .collect();
+            }
+            for combo in combos {
+                *cross.hits.entry(combo).or_default() += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+impl Database {
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Adds `other`'s hits into `self`.  Bins and crosses only `other`
+    /// declares are taken over as they are.
+    pub fn merge(&mut self, other: Database) {
+        for (name, group) in other.groups {
+            let dst = self.groups.entry(name).or_default();
+            for (name, point) in group.points {
+                let Some(x) = dst.points.get_mut(&name) else {
+                    dst.points.insert(name, point);
+                    continue;
+                };
+                // Declared bins win over automatic ones.
+                if point.auto && !x.auto {
+                    continue;
+                }
+                if (x.auto && !point.auto) || x.bins.is_empty() {
+                    *x = point;
+                    continue;
+                }
+                for bin in point.bins {
+                    match x.bins.iter_mut().find(|x| x.label == bin.label) {
+                        Some(x) => x.hits += bin.hits,
+                        None => x.bins.push(bin),
+                    }
+                }
+            }
+            for (name, cross) in group.crosses {
+                let x = dst.crosses.entry(name).or_insert_with(|| Cross {
+                    points: cross.points.clone(),
+                    hits: BTreeMap::new(),
+                });
+                for (combo, hits) in cross.hits {
+                    *x.hits.entry(combo).or_default() += hits;
+                }
+            }
+        }
+    }
+
+    /// Every coverpoint and cross with its holes, in name order.
+    pub fn items(&self) -> Vec<Item> {
+        let mut ret = Vec::new();
+        for (group_name, group) in &self.groups {
+            for (name, point) in &group.points {
+                let mut item = Item {
+                    name: format!("{group_name}.{name}"),
+                    covered: 0,
+                    total: point.bins.len(),
+                    holes: Vec::new(),
+                };
+                for bin in &point.bins {
+                    if bin.hits == 0 {
+                        item.holes.push(bin.label.clone());
+                    } else {
+                        item.covered += 1;
+                    }
+                }
+                if point.bins.is_empty() {
+                    // Automatic bins of a point never sampled.
+                    item.total = 1;
+                    item.holes.push("no samples".to_string());
+                }
+                ret.push(item);
+            }
+            for (name, cross) in &group.crosses {
+                // A merged database may exceed the cap; report it without
+                // listing holes.
+                let size = group.cross_size(&cross.points);
+                if size > MAX_CROSS_BINS {
+                    ret.push(Item {
+                        name: format!("{group_name}.{name}"),
+                        covered: cross.hits.values().filter(|x| **x != 0).count(),
+                        total: size as usize,
+                        holes: vec![format!("more than {MAX_CROSS_BINS} combinations")],
+                    });
+                    continue;
+                }
+                let mut combos = vec![String::new()];
+                for point in &cross.points {
+                    let labels: Vec<_> = group
+                        .points
+                        .get(point)
+                        .map(|x| x.bins.iter().map(|x| x.label.as_str()).collect())
+                        .unwrap_or_default();
+                    combos = combos
+                        .iter()
+                        .flat_map(|x| {
+                            labels.iter().map(move |y| {
+                                if x.is_empty() {
+                                    y.to_string()
+                                } else {
+                                    format!("{x}, {y}")
+                                }
+                            })
+                        })
+                        .collect();
+                }
+                let mut item = Item {
+                    name: format!("{group_name}.{name}"),
+                    covered: 0,
+                    total: combos.len(),
+                    holes: Vec::new(),
+                };
+                for combo in combos {
+                    if cross.hits.get(&combo).copied().unwrap_or_default() == 0 {
+                        item.holes.push(format!("({combo})"));
+                    } else {
+                        item.covered += 1;
+                    }
+                }
+                ret.push(item);
+            }
+        }
+        ret
+    }
+
+    /// `veryl test` summary: one row per coverpoint and cross, followed by
+    /// up to `max_holes` of its holes.
+    pub fn table(&self, max_holes: usize) -> String {
+        fn percent(covered: usize, total: usize) -> f64 {
+            if total == 0 {
+                100.0
+            } else {
+                covered as f64 * 100.0 / total as f64
+            }
+        }
+        let items = self.items();
+        let width = items
+            .iter()
+            .map(|x| x.name.len())
+            .chain(["total".len()])
+            .max()
+            .unwrap_or_default();
+        let mut ret = String::new();
+        let (mut covered, mut total) = (0, 0);
+        for item in &items {
+            covered += item.covered;
+            total += item.total;
+            let cell = format!("{}/{}", item.covered, item.total);
+            let _ = writeln!(
+                ret,
+                "  {:<width$} {cell:>11} {:>6.1}%",
+                item.name,
+                percent(item.covered, item.total)
+            );
+            if !item.holes.is_empty() {
+                let shown: Vec<_> = item.holes.iter().take(max_holes).cloned().collect();
+                let rest = item.holes.len() - shown.len();
+                let more = if rest > 0 {
+                    format!(" (and {rest} more)")
+                } else {
+                    String::new()
+                };
+                let _ = writeln!(ret, "    holes: {}{more}", shown.join(" "));
+            }
+        }
+        let cell = format!("{covered}/{total}");
+        let _ = writeln!(
+            ret,
+            "  {:<width$} {cell:>11} {:>6.1}%",
+            "total",
+            percent(covered, total)
+        );
+        ret
+    }
+}
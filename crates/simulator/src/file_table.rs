@@ -6,15 +6,21 @@
 //! name (`StrId`): `f.open(...)` is a statement with no return value, so there
 //! is no descriptor for the user to hold.
 
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fs::{File, OpenOptions};
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
 use veryl_parser::resource_table::{self, StrId};
 
+struct OpenFile {
+    path: String,
+    writer: BufWriter<File>,
+}
+
 #[derive(Default)]
 struct FileTable {
-    files: HashMap<StrId, BufWriter<File>>,
+    files: HashMap<StrId, OpenFile>,
     /// Handles already warned about a write-before-open, so the diagnostic is
     /// emitted once per handle instead of on every dropped write in a loop.
     warned: HashSet<StrId>,
@@ -46,7 +52,13 @@ pub fn open_handle(key: StrId, path: &str, append: bool) {
         .open(path);
     if let Ok(file) = file {
         TABLE.with(|t| {
-            t.borrow_mut().files.insert(key, BufWriter::new(file));
+            t.borrow_mut().files.insert(
+                key,
+                OpenFile {
+                    path: path.to_string(),
+                    writer: BufWriter::new(file),
+                },
+            );
         });
     }
 }
@@ -58,8 +70,8 @@ pub fn open_handle(key: StrId, path: &str, append: bool) {
 pub fn write_handle(key: StrId, s: &str) {
     let warn = TABLE.with(|t| {
         let mut t = t.borrow_mut();
-        if let Some(w) = t.files.get_mut(&key) {
-            let _ = w.write_all(s.as_bytes());
+        if let Some(f) = t.files.get_mut(&key) {
+            let _ = f.writer.write_all(s.as_bytes());
             false
         } else {
             t.warned.insert(key)
@@ -74,8 +86,8 @@ pub fn write_handle(key: StrId, s: &str) {
 /// Flush and close handle `key`. Unknown handles are ignored.
 pub fn close_handle(key: StrId) {
     TABLE.with(|t| {
-        if let Some(mut w) = t.borrow_mut().files.remove(&key) {
-            let _ = w.flush();
+        if let Some(mut f) = t.borrow_mut().files.remove(&key) {
+            let _ = f.writer.flush();
         }
     });
 }
@@ -83,8 +95,8 @@ pub fn close_handle(key: StrId) {
 /// Flush handle `key`. Unknown handles are ignored.
 pub fn flush_handle(key: StrId) {
     TABLE.with(|t| {
-        if let Some(w) = t.borrow_mut().files.get_mut(&key) {
-            let _ = w.flush();
+        if let Some(f) = t.borrow_mut().files.get_mut(&key) {
+            let _ = f.writer.flush();
         }
     });
 }
@@ -94,9 +106,68 @@ pub fn flush_handle(key: StrId) {
 pub fn finalize() {
     TABLE.with(|t| {
         let mut t = t.borrow_mut();
-        for (_, mut w) in t.files.drain() {
-            let _ = w.flush();
+        for (_, mut f) in t.files.drain() {
+            let _ = f.writer.flush();
         }
         t.warned.clear();
     });
 }
+
+/// Open handles and how much each had written, for simulation checkpoints.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FileSnapshot {
+    /// (handle name, path, length at the snapshot).
+    files: Vec<(String, String, u64)>,
+}
+
+/// Flush every open handle and record its path and current length.
+pub fn snapshot() -> FileSnapshot {
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        let mut files: Vec<_> = t
+            .files
+            .iter_mut()
+            .map(|(key, f)| {
+                let _ = f.writer.flush();
+                let len = f.writer.get_ref().metadata().map(|m| m.len()).unwrap_or(0);
+                let name = resource_table::get_str_value(*key).unwrap_or_default();
+                (name, f.path.clone(), len)
+            })
+            .collect();
+        files.sort();
+        FileSnapshot { files }
+    })
+}
+
+/// Close every open handle, then reopen the ones captured by [`snapshot`]
+/// truncated back to their recorded length, so output written after the
+/// snapshot is discarded.
+pub fn restore(snapshot: &FileSnapshot) {
+    finalize();
+    for (name, path, len) in &snapshot.files {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path);
+        let Ok(file) = file else {
+            continue;
+        };
+        if file.set_len(*len).is_err() {
+            continue;
+        }
+        let mut writer = BufWriter::new(file);
+        if writer.seek(SeekFrom::End(0)).is_err() {
+            continue;
+        }
+        TABLE.with(|t| {
+            t.borrow_mut().files.insert(
+                resource_table::insert_str(name),
+                OpenFile {
+                    path: path.clone(),
+                    writer,
+                },
+            );
+        });
+    }
+}
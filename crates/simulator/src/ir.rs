@@ -5,11 +5,13 @@ pub mod derived_clock;
 mod event;
 mod expression;
 pub(crate) mod external;
+pub(crate) mod force;
 pub(crate) mod hier_ref;
 pub(crate) mod inst_layout;
 pub(crate) mod module;
 pub(crate) mod opt;
 pub(crate) mod partial_index;
+pub mod partition;
 pub(crate) mod site_table;
 mod statement;
 pub(crate) mod variable;
@@ -30,7 +32,7 @@ pub use statement::{
     ProtoForBound, ProtoForRange, ProtoForStatement, ProtoIfStatement, ProtoStatement,
     ProtoStatementBlock, ProtoStatements, ProtoSystemFunctionCall, RetWidthCheck, RuntimeForBound,
     RuntimeForRange, Statement, SystemFunctionCall, TbMethodKind, format_assert_message,
-    format_output, parse_hex_content, patch_stmt_log_buf, veryl_aot_sysfn_print,
+    format_output, parse_hex_content, patch_stmt_log_buf, patch_stmt_xprop, veryl_aot_sysfn_print,
 };
 pub use variable::{
     ModuleVariableMeta, ModuleVariables, VarOffset, Variable, VariableElement, VariableMeta,
@@ -44,6 +46,7 @@ use crate::HashMap;
 use crate::backend::{self, BackendRegistry, CompiledWhole, DispatchOutcome};
 use crate::simulator::SimProfile;
 use crate::simulator_error::SimulatorError;
+use crate::xprop;
 use std::sync::Arc;
 use std::sync::OnceLock;
 
@@ -129,6 +132,19 @@ pub struct Ir {
     pub component_file_base: Option<std::path::PathBuf>,
     /// See `Module::rtl_driven`.
     pub rtl_driven: crate::HashSet<VarId>,
+    /// Snapshotted from `Config::coverage`.
+    pub coverage: bool,
+    /// Worker threads for `Module::comb_groups`; `None` runs the comb
+    /// statements on the simulating thread only.
+    pub comb_pool: Option<partition::CombPool>,
+    /// Snapshotted from `Config::partition_validate`.
+    pub partition_validate: bool,
+    /// Snapshotted from `Config::xprop_report` (only with `use_4state`).
+    pub xprop_report: bool,
+    /// Statement timing under `Config::profile`; the `Simulator` takes it.
+    pub profiler: Option<crate::profiler::Profiler>,
+    /// Override slots of `Config::force_targets`, keyed by path.
+    pub(crate) force_slots: HashMap<String, force::ForceSlot>,
 }
 
 /// A built component library on disk and the type name to look up in it.
@@ -172,11 +188,27 @@ impl Ir {
             component_libraries: config.component_libraries.clone(),
             component_file_base: config.component_file_base.clone(),
             rtl_driven: module.rtl_driven,
+            coverage: config.coverage,
+            // The X merge snapshots whole buffers, which other threads
+            // would be writing concurrently; the profiler times the comb
+            // statements one by one on this thread.
+            comb_pool: if config.xprop_active() || module.profiler.is_some() {
+                None
+            } else {
+                partition::CombPool::new(&module.comb_groups)
+            },
+            partition_validate: config.partition_validate,
+            xprop_report: config.xprop_report && config.use_4state,
+            profiler: module.profiler,
+            force_slots: module.force_slots,
         };
         // Bake the WriteLogBuffer's heap-stable address into every
         // JIT-dispatched Compiled/CompiledBatch so emitted code can perform
         // inline log pushes without a TLS lookup.
         ir.install_write_log_ptr();
+        if config.xprop_active() {
+            ir.install_xprop(config.xprop);
+        }
         ir.backend_diag();
         ir
     }
@@ -318,6 +350,30 @@ impl Ir {
         }
     }
 
+    /// Hand X conditions in interpreted `if`/`case` to a shared
+    /// [`xprop::Merger`]; `build_chunked` keeps every branching statement
+    /// interpreted while `Config::xprop_active`.
+    fn install_xprop(&mut self, mode: xprop::XProp) {
+        let merger = Arc::new(xprop::Merger::new(
+            mode,
+            &self.module_variables,
+            &mut self.ff_values,
+            &mut self.comb_values,
+            &mut self.write_log_buffer,
+        ));
+        for stmts in self.event_statements.values_mut() {
+            for s in stmts {
+                patch_stmt_xprop(s, &merger);
+            }
+        }
+        for s in &mut self.comb_statements {
+            patch_stmt_xprop(s, &merger);
+        }
+        for s in &mut self.derived_clock_eval_stmts {
+            patch_stmt_xprop(s, &merger);
+        }
+    }
+
     /// Re-evaluate just the derived-clock dependency closure.
     pub fn partial_settle(&self, mask_cache: &mut MaskCache) {
         for stmt in &self.derived_clock_eval_stmts {
@@ -337,7 +393,6 @@ impl Ir {
         {
             profile.settle_comb_count += 1;
         }
-        let _ = profile; // suppress unused warning when profile feature is off
 
         // Dispatch: when a whole-comb backend (today: AOT-C) is ready,
         // invoke it in place of per-chunk Cranelift dispatch.  When
@@ -369,8 +424,13 @@ impl Ir {
             if !validate {
                 // Common case: passes == 1 (no SCC backward edges).
                 for _ in 0..passes {
+                    let start = profile.blocks.is_some().then(std::time::Instant::now);
                     match whole.try_dispatch(ff_ptr, comb_ptr, log_ptr) {
-                        DispatchOutcome::Done => {}
+                        DispatchOutcome::Done => {
+                            if let (Some(blocks), Some(start)) = (profile.blocks.as_mut(), start) {
+                                blocks.whole_comb(start);
+                            }
+                        }
                         DispatchOutcome::NotReady => {
                             // Async compile not finished yet — drop to
                             // Cranelift for this cycle.
@@ -394,9 +454,8 @@ impl Ir {
 
     /// Cranelift-only settle path, factored out so the validate mode can
     /// invoke it after AOT-C eval has run and the buffers have been restored.
+    /// Runs on `comb_pool` when the comb list was partitioned.
     pub(crate) fn run_chunked_settle(&self, mask_cache: &mut MaskCache, profile: &mut SimProfile) {
-        let _ = profile;
-
         // `VERYL_MIN_PASSES_OVERRIDE` is still honoured as a debug knob.
         static MIN_PASSES_OVERRIDE: OnceLock<Option<usize>> = OnceLock::new();
         let min_override = *MIN_PASSES_OVERRIDE.get_or_init(|| {
@@ -405,6 +464,28 @@ impl Ir {
                 .and_then(|s| s.parse().ok())
         });
         let passes = min_override.unwrap_or(self.required_comb_passes);
+        if let Some(pool) = self.comb_pool.as_ref() {
+            if self.partition_validate {
+                backend::validate::settle_partitioned(self, pool, passes, mask_cache, profile);
+            } else {
+                pool.settle(&self.comb_statements, passes, mask_cache);
+                #[cfg(feature = "profile")]
+                {
+                    profile.comb_eval_count += passes as u64;
+                }
+            }
+            return;
+        }
+        self.run_serial_settle(passes, mask_cache, profile);
+    }
+
+    /// `passes` passes of the comb statements on the simulating thread.
+    pub(crate) fn run_serial_settle(
+        &self,
+        passes: usize,
+        mask_cache: &mut MaskCache,
+        profile: &mut SimProfile,
+    ) {
         for _ in 0..passes {
             self.eval_comb_full(mask_cache, profile);
             #[cfg(feature = "profile")]
@@ -417,12 +498,15 @@ impl Ir {
     /// Evaluate unified comb once.
     /// Called by settle_comb() for each required pass.
     pub fn eval_comb_full(&self, mask_cache: &mut MaskCache, profile: &mut SimProfile) {
-        let _ = profile;
         #[cfg(feature = "profile")]
         let start = std::time::Instant::now();
 
-        for x in &self.comb_statements {
-            dispatch_stmt_fast(x, mask_cache);
+        if let Some(blocks) = profile.blocks.as_mut() {
+            blocks.run_comb(&self.comb_statements, mask_cache);
+        } else {
+            for x in &self.comb_statements {
+                dispatch_stmt_fast(x, mask_cache);
+            }
         }
 
         #[cfg(feature = "profile")]
@@ -681,9 +765,37 @@ pub struct Config {
     pub component_libraries: std::collections::HashMap<String, ComponentLibrary>,
     /// See `Ir::component_file_base`.
     pub component_file_base: Option<std::path::PathBuf>,
+    /// Instrument `if`/`case` arms and always-block bodies with coverage
+    /// counters and sample toggle/FSM coverage (see [`crate::coverage`]).
+    pub coverage: bool,
+    /// Worker threads for the comb settle (see [`partition`]). 0 or 1 keeps
+    /// the single-threaded loop.
+    pub sim_threads: usize,
+    /// Dual-run every partitioned settle against the single-threaded loop
+    /// and panic on the first divergence. Strided by `aot_c_validate_stride`.
+    pub partition_validate: bool,
+    /// What `if`/`case` do with an X condition under `use_4state` (see
+    /// [`crate::xprop`]).
+    pub xprop: crate::xprop::XProp,
+    /// Record where X first reaches each register and top-level output.
+    /// Requires `use_4state`.
+    pub xprop_report: bool,
+    /// Time every comb/event statement and attribute it to its instance and
+    /// always block (see [`crate::profiler`]). Runs the comb settle serially
+    /// and needs `dut_reuse` off, whose shared artifacts carry no origins.
+    pub profile: bool,
+    /// Hierarchical paths (`u_core.state`) given `$tb::force` override slots
+    /// so `Simulator::force_var` can hold them against their drivers.
+    pub force_targets: Vec<String>,
 }
 
 impl Config {
+    /// Whether `if`/`case` hand X conditions to an [`crate::xprop::Merger`],
+    /// which the JIT backends cannot do.
+    pub fn xprop_active(&self) -> bool {
+        self.use_4state && self.xprop != crate::xprop::XProp::Standard
+    }
+
     /// Apply environment-variable overrides on top of an existing config.
     pub fn apply_env(&mut self) {
         if std::env::var("VERYL_DUMP_ASM").ok().as_deref() == Some("1") {
@@ -719,6 +831,11 @@ impl Config {
         {
             self.aot_c_min_stmts = n;
         }
+        if let Ok(n) = std::env::var("VERYL_SIM_THREADS")
+            && let Ok(n) = n.parse::<usize>()
+        {
+            self.sim_threads = n;
+        }
         // On by default for the CLI; `VERYL_DUT_REUSE=0` opts out.  Off in the
         // unit-test harness, which never calls `apply_env` (see `Config::dut_reuse`).
         self.dut_reuse = std::env::var("VERYL_DUT_REUSE").ok().as_deref() != Some("0");
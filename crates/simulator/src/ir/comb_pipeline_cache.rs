@@ -41,6 +41,9 @@ pub struct CombPipeline {
     /// identical comb layout (delta = 0). Mostly `Compiled(Arc)` blocks, so a
     /// hit's clone is a handful of `Arc::clone`s, not a deep copy.
     pub comb_statements: ProtoStatements,
+    /// Blocks of `comb_statements` per partition group (see `ir::partition`);
+    /// empty when the comb list is not partitioned.
+    pub comb_groups: Vec<usize>,
     /// Dead offsets dropped by dead-var DCE. Re-applied to the caller's event
     /// statements on a hit so they match the miss path exactly.
     pub dead_offsets: Vec<VarOffset>,
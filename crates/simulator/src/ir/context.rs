@@ -1,6 +1,7 @@
 use crate::HashMap;
 use crate::HashSet;
 use crate::backend::{BackendRegistry, ChunkArtifact};
+use crate::coverage;
 use crate::ir::Config;
 use crate::ir::ProtoStatement;
 use crate::ir::VarId;
@@ -8,10 +9,12 @@ use crate::ir::VariableMeta;
 use crate::ir::event::Event;
 use crate::ir::statement::StmtDep;
 use crate::ir::variable::VarOffset;
+use crate::profiler;
 use crate::simulator_error::SimulatorError;
 use std::sync::Arc;
 use veryl_analyzer::ir as air;
 use veryl_analyzer::symbol::Affiliation;
+use veryl_parser::token_range::TokenRange;
 
 pub struct ScopeContext {
     pub variable_meta: HashMap<VarId, VariableMeta>,
@@ -98,6 +101,11 @@ pub struct Context {
     pub backends: BackendRegistry,
     /// See `alloc_internal_event_id`.
     pub internal_event_ids_allocated: u32,
+    /// True while converting an `always_comb`/`always_ff` body with
+    /// `Config::coverage` set; see `cover`.
+    pub cover_statements: bool,
+    /// Always blocks and interpreter fallbacks, under `Config::profile`.
+    pub profile: profiler::BuildInfo,
 }
 
 impl Context {
@@ -121,6 +129,21 @@ impl Context {
         );
         VarId::from_raw(u32::MAX - self.internal_event_ids_allocated)
     }
+
+    /// Coverage counter to put at the head of `stmts`, arm `arm` of the
+    /// `if`/`case` at `token`.  `None` outside `cover_statements`.
+    pub fn cover(
+        &self,
+        token: &TokenRange,
+        arm: Option<u32>,
+        stmts: &[air::Statement],
+    ) -> Option<ProtoStatement> {
+        if self.cover_statements {
+            coverage::cover_block(token, arm, stmts)
+        } else {
+            None
+        }
+    }
 }
 
 pub trait Conv<T>: Sized {
@@ -1,6 +1,7 @@
 use crate::backend::inst::{
     ReuseOutcome, port_alias_enabled, try_compile_inst_chunks, try_reuse_or_claim,
 };
+use crate::coverage;
 use crate::ir::context::{Context, Conv, ScopeContext};
 use crate::ir::expression::{ExpressionContext, build_dynamic_bit_select};
 use crate::ir::external::{ProtoExternalComponent, ProtoExternalConnect};
@@ -649,6 +650,44 @@ fn trace_first_scc_cycle(
     }
 }
 
+/// Converts the body of an `always_comb`/`always_ff`, instrumenting its
+/// branches when `Config::coverage` is set.
+fn conv_always(
+    context: &mut Context,
+    src: &[air::Statement],
+) -> Result<Vec<ProtoStatement>, SimulatorError> {
+    context.cover_statements = context.config.coverage;
+    let mut ret = vec![];
+    let mut conv_err = None;
+    for stmt in src {
+        match Conv::conv(context, stmt) {
+            Ok(stmts) => {
+                let stmts: Vec<ProtoStatement> = stmts;
+                ret.extend(stmts);
+            }
+            Err(e) => {
+                conv_err = Some(e);
+                break;
+            }
+        }
+    }
+    context.cover_statements = false;
+    match conv_err {
+        Some(e) => Err(e),
+        None => Ok(ret),
+    }
+}
+
+/// Coverage counter for a whole always-block body, located at its first
+/// statement.
+fn cover_body(context: &Context, src: &[air::Statement]) -> Option<ProtoStatement> {
+    if !context.config.coverage {
+        return None;
+    }
+    let token = src.iter().find_map(coverage::statement_token)?;
+    coverage::cover_block(&token, None, src)
+}
+
 pub struct ProtoDeclaration {
     pub event_statements: HashMap<Event, Vec<ProtoStatement>>,
     pub comb_statements: Vec<ProtoStatement>,
@@ -667,10 +706,14 @@ impl Conv<&air::Declaration> for ProtoDeclaration {
     fn conv(context: &mut Context, src: &air::Declaration) -> Result<Self, SimulatorError> {
         match src {
             air::Declaration::Comb(x) => {
-                let mut comb_statements = vec![];
-                for stmt in &x.statements {
-                    let stmts: Vec<ProtoStatement> = Conv::conv(context, stmt)?;
-                    comb_statements.extend(stmts);
+                if context.config.profile {
+                    context.profile.add_block("comb", &x.statements);
+                }
+                let mut comb_statements = conv_always(context, &x.statements)?;
+                if coverage::has_branch(&x.statements)
+                    && let Some(head) = cover_body(context, &x.statements)
+                {
+                    comb_statements.insert(0, head);
                 }
                 let comb_statements = if comb_statements.len() > 1 {
                     vec![ProtoStatement::SequentialBlock(comb_statements)]
@@ -687,11 +730,11 @@ impl Conv<&air::Declaration> for ProtoDeclaration {
                 })
             }
             air::Declaration::Ff(x) => {
-                let mut statements = vec![];
-                for stmt in &x.statements {
-                    let stmts: Vec<ProtoStatement> = Conv::conv(context, stmt)?;
-                    statements.extend(stmts);
+                if context.config.profile {
+                    context.profile.add_block("always_ff", &x.statements);
                 }
+                let mut statements = conv_always(context, &x.statements)?;
+                let head = cover_body(context, &x.statements);
 
                 let clock_event = Event::Clock(x.clock.id);
                 let mut event_statements: HashMap<Event, Vec<ProtoStatement>> = HashMap::default();
@@ -709,6 +752,11 @@ impl Conv<&air::Declaration> for ProtoDeclaration {
                 } else {
                     event_statements.insert(clock_event, statements);
                 }
+                if let Some(head) = head {
+                    for stmts in event_statements.values_mut() {
+                        stmts.insert(0, head.clone());
+                    }
+                }
 
                 Ok(ProtoDeclaration {
                     event_statements,
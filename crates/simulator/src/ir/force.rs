@@ -0,0 +1,554 @@
+//! Testbench overrides of design variables (`$tb::force`).
+//!
+//! A forced variable has to ignore its drivers on every backend, including
+//! the compiled ones, so the override is built into the design rather than
+//! re-applied at run time: once hierarchical references are resolved,
+//! [`install`] gives every forced target an enable/value slot pair in the
+//! comb buffer and rewrites each statement writing the target into
+//! `if enable { target = value } else { <driver> }`. `force`/`release` then
+//! only flip the slots, and the next settle or clock edge does the rest.
+//! Compiled child blocks writing a forced target fall back to their
+//! interpreted statements. Variables named in `Config::force_targets` get the
+//! same slots without a `$tb::force` call, so a harness such as the shell can
+//! force them through [`crate::Simulator::force_var`].
+
+use crate::HashMap;
+use crate::ir::context::Context;
+use crate::ir::event::Event;
+use crate::ir::expression::{ExpressionContext, ProtoExpression};
+use crate::ir::statement::{
+    CompiledBlockStatement, ProtoAssignDynamicStatement, ProtoAssignStatement, ProtoIfStatement,
+    ProtoStatement, ProtoTbMethodKind,
+};
+use crate::ir::variable::{
+    ModuleVariableMeta, VarOffset, VariableElement, VariableMeta, native_bytes, value_size,
+    write_native_value,
+};
+use crate::ir::{Value, VarId, VarPath};
+use crate::simulator_error::SimulatorError;
+use std::str::FromStr;
+use veryl_parser::token_range::TokenRange;
+
+/// Target of a `$tb::force` method as converted; [`install`] fills `slot`.
+#[derive(Clone, Debug)]
+pub struct ProtoForceTarget {
+    pub expr: ProtoExpression,
+    pub slot: Option<ProtoForceSlot>,
+    pub token: TokenRange,
+}
+
+// `token` only feeds diagnostics; it is left out of the fingerprint like
+// `ProtoAssignStatement::token`.
+impl std::hash::Hash for ProtoForceTarget {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let ProtoForceTarget {
+            expr,
+            slot,
+            token: _,
+        } = self;
+        expr.hash(state);
+        slot.hash(state);
+    }
+}
+
+/// Storage of a resolved force target.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct ProtoForceSlot {
+    pub current: VarOffset,
+    /// Next slot of a dual-slot FF.
+    pub next: Option<isize>,
+    pub width: usize,
+    /// Comb-buffer offsets of the enable and value slots; `None` for a
+    /// target that is only deposited.
+    pub overrides: Option<(isize, isize)>,
+}
+
+impl ProtoForceSlot {
+    pub fn adjust_offsets(&mut self, ff_delta: isize, comb_delta: isize) {
+        if let Some(next) = &mut self.next {
+            *next += ff_delta;
+        }
+        self.current = self.current.adjust(ff_delta, comb_delta);
+        if let Some((enable, value)) = &mut self.overrides {
+            *enable += comb_delta;
+            *value += comb_delta;
+        }
+    }
+
+    /// # Safety
+    /// `ff_values_ptr` and `comb_values_ptr` must point to valid buffers.
+    pub unsafe fn apply_values_ptr(
+        &self,
+        ff_values_ptr: *mut u8,
+        comb_values_ptr: *mut u8,
+        use_4state: bool,
+    ) -> ForceSlot {
+        unsafe {
+            let current = if self.current.is_ff() {
+                ff_values_ptr.offset(self.current.raw())
+            } else {
+                comb_values_ptr.offset(self.current.raw())
+            };
+            ForceSlot {
+                current,
+                next: self.next.map(|x| ff_values_ptr.offset(x)),
+                width: self.width,
+                use_4state,
+                overrides: self.overrides.map(|(enable, value)| {
+                    (
+                        comb_values_ptr.offset(enable),
+                        comb_values_ptr.offset(value),
+                    )
+                }),
+            }
+        }
+    }
+}
+
+/// Pointer-bound form of [`ProtoForceSlot`].
+#[derive(Clone, Copy)]
+pub struct ForceSlot {
+    current: *mut u8,
+    next: Option<*mut u8>,
+    width: usize,
+    use_4state: bool,
+    overrides: Option<(*mut u8, *mut u8)>,
+}
+
+impl ForceSlot {
+    /// Holds the target at `value` until [`ForceSlot::release`]. The target
+    /// is written right away, so readers see the value before the next
+    /// settle.
+    ///
+    /// # Safety
+    /// The buffers this slot was bound to must be alive.
+    pub unsafe fn force(&self, value: &Value) {
+        unsafe {
+            let value = self.deposit(value);
+            if let Some((enable, slot)) = self.overrides {
+                write_native_value(slot, native_bytes(self.width), self.use_4state, &value);
+                write_native_value(
+                    enable,
+                    native_bytes(1),
+                    self.use_4state,
+                    &Value::new(1, 1, false),
+                );
+            }
+        }
+    }
+
+    /// Hands the target back to its drivers. A comb variable follows them
+    /// from the next settle, an FF keeps the forced value until its next
+    /// write.
+    ///
+    /// # Safety
+    /// The buffers this slot was bound to must be alive.
+    pub unsafe fn release(&self) {
+        if let Some((enable, _)) = self.overrides {
+            unsafe {
+                write_native_value(
+                    enable,
+                    native_bytes(1),
+                    self.use_4state,
+                    &Value::new(0, 1, false),
+                );
+            }
+        }
+    }
+
+    /// Writes `value` to the target once, leaving the drivers in charge,
+    /// and returns it truncated to the target's width.
+    ///
+    /// # Safety
+    /// The buffers this slot was bound to must be alive.
+    pub unsafe fn deposit(&self, value: &Value) -> Value {
+        let mut value = value.clone();
+        value.trunc(self.width);
+        let nb = native_bytes(self.width);
+        for ptr in std::iter::once(self.current).chain(self.next) {
+            unsafe {
+                write_native_value(ptr, nb, self.use_4state, &value);
+            }
+        }
+        value
+    }
+}
+
+/// Resolves the slot of every `$tb::force` method call in the initial and
+/// final blocks and of every `Config::force_targets` path, then rewrites the
+/// drivers of forced targets in `event_statements` and `comb_statements`.
+/// Returns the slots of the configured paths.
+pub fn install(
+    context: &mut Context,
+    event_statements: &mut HashMap<Event, Vec<ProtoStatement>>,
+    comb_statements: &mut Vec<ProtoStatement>,
+    top: &HashMap<VarId, VariableMeta>,
+    children: &[ModuleVariableMeta],
+) -> Result<Vec<(String, ProtoForceSlot)>, SimulatorError> {
+    let mut forced: Vec<Forced> = vec![];
+    for event in [Event::Initial, Event::Final] {
+        if let Some(stmts) = event_statements.get_mut(&event) {
+            for stmt in stmts.iter_mut() {
+                resolve_stmt(stmt, context, top, children, &mut forced)?;
+            }
+        }
+    }
+
+    let mut named = vec![];
+    for path in context.config.force_targets.clone() {
+        // Arrays would need one slot pair per element; whole scalars only.
+        let (meta, element) = VarPath::from_str(&path)
+            .ok()
+            .and_then(|x| find_meta(top, children, &x))
+            .and_then(|meta| match meta.elements.as_slice() {
+                [element] => Some((meta, element)),
+                _ => None,
+            })
+            .ok_or_else(|| SimulatorError::ForceTarget { path: path.clone() })?;
+        let slot = allocate(element, meta.width, true, meta.token, context, &mut forced);
+        named.push((path, slot));
+    }
+
+    if forced.is_empty() {
+        return Ok(named);
+    }
+
+    for stmts in event_statements
+        .values_mut()
+        .chain(std::iter::once(comb_statements))
+    {
+        expand_compiled(stmts, &forced);
+        for stmt in stmts.iter_mut() {
+            rewrite_stmt(stmt, &forced);
+        }
+    }
+    Ok(named)
+}
+
+/// Replaces each compiled child block writing a forced target with its
+/// pre-JIT statements, which hold this instance's actual offsets, so the
+/// drivers inside become rewritable.
+fn expand_compiled(stmts: &mut Vec<ProtoStatement>, forced: &[Forced]) {
+    let writes_forced = |x: &CompiledBlockStatement| {
+        forced.iter().any(|f| {
+            x.output_offsets.contains(&f.current)
+                || (f.current.is_ff() && x.ff_canonical_offsets.contains(&f.current.raw()))
+        })
+    };
+    if !stmts
+        .iter()
+        .any(|s| matches!(s, ProtoStatement::CompiledBlock(x) if writes_forced(x)))
+    {
+        return;
+    }
+    *stmts = std::mem::take(stmts)
+        .into_iter()
+        .flat_map(|s| match s {
+            ProtoStatement::CompiledBlock(x) if writes_forced(&x) => x.original_stmts,
+            s => vec![s],
+        })
+        .collect();
+}
+
+/// A forced target and its override slots.
+struct Forced {
+    current: VarOffset,
+    width: usize,
+    enable: isize,
+    value: isize,
+    token: TokenRange,
+}
+
+impl Forced {
+    fn enable_expr(&self) -> ProtoExpression {
+        slot_expr(self.enable, 1)
+    }
+
+    /// `target = value`, stored at `dst` (the slot a driver writes).
+    fn assign(&self, dst: VarOffset, token: TokenRange) -> ProtoStatement {
+        ProtoStatement::Assign(ProtoAssignStatement {
+            dst,
+            dst_width: self.width,
+            select: None,
+            dynamic_select: None,
+            rhs_select: None,
+            expr: slot_expr(self.value, self.width),
+            dst_ff_current_offset: self.current.raw(),
+            token,
+        })
+    }
+}
+
+fn slot_expr(offset: isize, width: usize) -> ProtoExpression {
+    ProtoExpression::Variable {
+        var_offset: VarOffset::Comb(offset),
+        select: None,
+        dynamic_select: None,
+        width,
+        var_full_width: width,
+        expr_context: ExpressionContext {
+            width,
+            signed: false,
+        },
+    }
+}
+
+fn resolve_stmt(
+    stmt: &mut ProtoStatement,
+    context: &mut Context,
+    top: &HashMap<VarId, VariableMeta>,
+    children: &[ModuleVariableMeta],
+    forced: &mut Vec<Forced>,
+) -> Result<(), SimulatorError> {
+    match stmt {
+        ProtoStatement::TbMethodCall { method, .. } => match method {
+            ProtoTbMethodKind::Force { target, .. } | ProtoTbMethodKind::Release { target } => {
+                resolve_target(target, true, context, top, children, forced)?;
+            }
+            ProtoTbMethodKind::Deposit { target, .. } => {
+                resolve_target(target, false, context, top, children, forced)?;
+            }
+            _ => {}
+        },
+        ProtoStatement::If(x) => {
+            for s in x.true_side.iter_mut().chain(x.false_side.iter_mut()) {
+                resolve_stmt(s, context, top, children, forced)?;
+            }
+        }
+        ProtoStatement::Case(x) => {
+            for arm in &mut x.arms {
+                for s in &mut arm.body {
+                    resolve_stmt(s, context, top, children, forced)?;
+                }
+            }
+            for s in &mut x.default {
+                resolve_stmt(s, context, top, children, forced)?;
+            }
+        }
+        ProtoStatement::For(x) => {
+            for s in &mut x.body {
+                resolve_stmt(s, context, top, children, forced)?;
+            }
+        }
+        ProtoStatement::SequentialBlock(stmts) => {
+            for s in stmts {
+                resolve_stmt(s, context, top, children, forced)?;
+            }
+        }
+        ProtoStatement::Assign(_)
+        | ProtoStatement::AssignDynamic(_)
+        | ProtoStatement::SystemFunctionCall(_)
+        | ProtoStatement::CompiledBlock(_)
+        | ProtoStatement::Break => {}
+    }
+    Ok(())
+}
+
+fn resolve_target(
+    target: &mut ProtoForceTarget,
+    overrides: bool,
+    context: &mut Context,
+    top: &HashMap<VarId, VariableMeta>,
+    children: &[ModuleVariableMeta],
+    forced: &mut Vec<Forced>,
+) -> Result<(), SimulatorError> {
+    // The analyzer admits only whole variables with a constant index, which
+    // resolve to a plain `Variable`.
+    let ProtoExpression::Variable {
+        var_offset,
+        select: None,
+        dynamic_select: None,
+        var_full_width,
+        ..
+    } = &target.expr
+    else {
+        return Err(SimulatorError::unsupported_description(&target.token));
+    };
+    let current = *var_offset;
+    let width = *var_full_width;
+    let element = find_element(top, children, current)
+        .ok_or_else(|| SimulatorError::unsupported_description(&target.token))?;
+    target.slot = Some(allocate(
+        element,
+        width,
+        overrides,
+        target.token,
+        context,
+        forced,
+    ));
+    Ok(())
+}
+
+/// Builds the slot of `element`, giving it override slots (shared with an
+/// earlier target at the same storage) when `overrides` is set.
+fn allocate(
+    element: &VariableElement,
+    width: usize,
+    overrides: bool,
+    token: TokenRange,
+    context: &mut Context,
+    forced: &mut Vec<Forced>,
+) -> ProtoForceSlot {
+    let current = element.current;
+    let next = (element.is_ff() && element.next_offset != element.current_offset())
+        .then_some(element.next_offset);
+
+    let overrides = if overrides {
+        let index = match forced.iter().position(|x| x.current == current) {
+            Some(index) => index,
+            None => {
+                let use_4state = context.config.use_4state;
+                let enable = context.comb_total_bytes as isize;
+                context.comb_total_bytes += value_size(native_bytes(1), use_4state);
+                let value = context.comb_total_bytes as isize;
+                context.comb_total_bytes += value_size(native_bytes(width), use_4state);
+                forced.push(Forced {
+                    current,
+                    width,
+                    enable,
+                    value,
+                    token,
+                });
+                forced.len() - 1
+            }
+        };
+        Some((forced[index].enable, forced[index].value))
+    } else {
+        None
+    };
+
+    ProtoForceSlot {
+        current,
+        next,
+        width,
+        overrides,
+    }
+}
+
+/// Looks `path` up the way `Simulator::get_var` does: leading segments
+/// naming a child instance descend into it.
+fn find_meta<'a>(
+    top: &'a HashMap<VarId, VariableMeta>,
+    children: &'a [ModuleVariableMeta],
+    path: &VarPath,
+) -> Option<&'a VariableMeta> {
+    if path.0.len() > 1 {
+        for child in children.iter().filter(|x| x.name == path.0[0]) {
+            let sub = VarPath::from_slice(&path.0[1..]);
+            if let Some(meta) = find_meta(&child.variable_meta, &child.children, &sub) {
+                return Some(meta);
+            }
+        }
+    }
+    top.values().find(|x| x.path == *path)
+}
+
+fn find_element<'a>(
+    top: &'a HashMap<VarId, VariableMeta>,
+    children: &'a [ModuleVariableMeta],
+    current: VarOffset,
+) -> Option<&'a VariableElement> {
+    fn in_module(module: &ModuleVariableMeta, current: VarOffset) -> Option<&VariableElement> {
+        in_meta(&module.variable_meta, current).or_else(|| {
+            module
+                .children
+                .iter()
+                .find_map(|child| in_module(child, current))
+        })
+    }
+    fn in_meta(
+        meta: &HashMap<VarId, VariableMeta>,
+        current: VarOffset,
+    ) -> Option<&VariableElement> {
+        meta.values()
+            .flat_map(|x| x.elements.iter())
+            .find(|x| x.current == current)
+    }
+    in_meta(top, current).or_else(|| children.iter().find_map(|x| in_module(x, current)))
+}
+
+fn rewrite_stmt(stmt: &mut ProtoStatement, forced: &[Forced]) {
+    match stmt {
+        ProtoStatement::Assign(x) => {
+            let written = if x.dst.is_ff() {
+                VarOffset::Ff(x.dst_ff_current_offset)
+            } else {
+                x.dst
+            };
+            if let Some(f) = forced.iter().find(|f| f.current == written) {
+                let forced_side = f.assign(x.dst, x.token);
+                let driver = std::mem::replace(stmt, ProtoStatement::Break);
+                *stmt = ProtoStatement::If(ProtoIfStatement {
+                    cond: Some(f.enable_expr()),
+                    true_side: vec![forced_side],
+                    false_side: vec![driver],
+                });
+            }
+        }
+        ProtoStatement::AssignDynamic(x) => {
+            // The driver may hit any element, so it still runs while forced
+            // and the forced element is restored after it.
+            let hits: Vec<(&Forced, VarOffset)> = forced
+                .iter()
+                .filter_map(|f| Some((f, dynamic_element(x, f.current)?)))
+                .collect();
+            for (f, dst) in hits {
+                let driver = std::mem::replace(stmt, ProtoStatement::Break);
+                *stmt = ProtoStatement::If(ProtoIfStatement {
+                    cond: Some(f.enable_expr()),
+                    true_side: vec![driver.clone(), f.assign(dst, f.token)],
+                    false_side: vec![driver],
+                });
+            }
+        }
+        ProtoStatement::If(x) => {
+            for s in x.true_side.iter_mut().chain(x.false_side.iter_mut()) {
+                rewrite_stmt(s, forced);
+            }
+        }
+        ProtoStatement::Case(x) => {
+            for arm in &mut x.arms {
+                for s in &mut arm.body {
+                    rewrite_stmt(s, forced);
+                }
+            }
+            for s in &mut x.default {
+                rewrite_stmt(s, forced);
+            }
+        }
+        ProtoStatement::For(x) => {
+            for s in &mut x.body {
+                rewrite_stmt(s, forced);
+            }
+        }
+        ProtoStatement::SequentialBlock(stmts) => {
+            for s in stmts {
+                rewrite_stmt(s, forced);
+            }
+        }
+        // Blocks writing a forced target were expanded by `expand_compiled`.
+        ProtoStatement::CompiledBlock(_)
+        | ProtoStatement::SystemFunctionCall(_)
+        | ProtoStatement::TbMethodCall { .. }
+        | ProtoStatement::Break => {}
+    }
+}
+
+/// The slot `x` writes for the array element whose current slot is
+/// `current`, if `current` lies in `x`'s destination array.
+fn dynamic_element(x: &ProtoAssignDynamicStatement, current: VarOffset) -> Option<VarOffset> {
+    if x.dst_base.is_ff() != current.is_ff() || x.dst_stride <= 0 {
+        return None;
+    }
+    let base = if x.dst_base.is_ff() {
+        x.dst_ff_current_base_offset
+    } else {
+        x.dst_base.raw()
+    };
+    let delta = current.raw() - base;
+    if delta < 0 || delta % x.dst_stride != 0 || delta / x.dst_stride >= x.dst_num_elements as isize
+    {
+        return None;
+    }
+    Some(VarOffset::new(x.dst_base.is_ff(), x.dst_base.raw() + delta))
+}
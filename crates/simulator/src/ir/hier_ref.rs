@@ -131,7 +131,9 @@ fn resolve_stmt(
                     resolve_expr(arg, context, children)?;
                 }
             }
-            ProtoSystemFunctionCall::Readmemh { .. } | ProtoSystemFunctionCall::Finish => {}
+            ProtoSystemFunctionCall::Readmemh { .. }
+            | ProtoSystemFunctionCall::Finish
+            | ProtoSystemFunctionCall::Cover { .. } => {}
         },
         ProtoStatement::TbMethodCall { method, .. } => match method {
             crate::ir::statement::ProtoTbMethodKind::ClockNext { count, period } => {
@@ -159,15 +161,91 @@ fn resolve_stmt(
             crate::ir::statement::ProtoTbMethodKind::RandomSeed { value } => {
                 resolve_expr(value, context, children)?;
             }
-            crate::ir::statement::ProtoTbMethodKind::RandomGetRange { min, max, .. } => {
+            crate::ir::statement::ProtoTbMethodKind::RandomGetRange { min, max, .. }
+            | crate::ir::statement::ProtoTbMethodKind::RandomGetCyclic { min, max, .. } => {
                 resolve_expr(min, context, children)?;
                 resolve_expr(max, context, children)?;
             }
+            crate::ir::statement::ProtoTbMethodKind::RandomGetWhere { min, max, cond, .. } => {
+                resolve_expr(min, context, children)?;
+                resolve_expr(max, context, children)?;
+                resolve_expr(cond, context, children)?;
+            }
+            crate::ir::statement::ProtoTbMethodKind::RandomGetDist {
+                values, weights, ..
+            } => {
+                for e in values.iter_mut().chain(weights) {
+                    resolve_expr(e, context, children)?;
+                }
+            }
+            crate::ir::statement::ProtoTbMethodKind::RandomGetInside { values, .. } => {
+                for e in values {
+                    resolve_expr(e, context, children)?;
+                }
+            }
+            crate::ir::statement::ProtoTbMethodKind::CheckpointFork { count } => {
+                resolve_expr(count, context, children)?;
+            }
+            crate::ir::statement::ProtoTbMethodKind::Force { target, value }
+            | crate::ir::statement::ProtoTbMethodKind::Deposit { target, value } => {
+                resolve_expr(&mut target.expr, context, children)?;
+                resolve_expr(value, context, children)?;
+            }
+            crate::ir::statement::ProtoTbMethodKind::Release { target } => {
+                resolve_expr(&mut target.expr, context, children)?;
+            }
+            crate::ir::statement::ProtoTbMethodKind::CoverBins { values, .. }
+            | crate::ir::statement::ProtoTbMethodKind::CoverSample { values, .. } => {
+                for e in values {
+                    resolve_expr(e, context, children)?;
+                }
+            }
+            crate::ir::statement::ProtoTbMethodKind::CoverRange {
+                min, max, count, ..
+            } => {
+                resolve_expr(min, context, children)?;
+                resolve_expr(max, context, children)?;
+                if let Some(count) = count {
+                    resolve_expr(count, context, children)?;
+                }
+            }
+            crate::ir::statement::ProtoTbMethodKind::ClockWaitUntil {
+                cond,
+                within,
+                period,
+            } => {
+                resolve_expr(cond, context, children)?;
+                for e in within.iter_mut().chain(period) {
+                    resolve_expr(e, context, children)?;
+                }
+            }
+            crate::ir::statement::ProtoTbMethodKind::ThreadWaitUntil { cond } => {
+                resolve_expr(cond, context, children)?;
+            }
+            crate::ir::statement::ProtoTbMethodKind::QueuePush { value, .. }
+            | crate::ir::statement::ProtoTbMethodKind::ScoreboardExpect { value, .. }
+            | crate::ir::statement::ProtoTbMethodKind::ScoreboardObserve { value, .. } => {
+                resolve_expr(value, context, children)?;
+            }
             crate::ir::statement::ProtoTbMethodKind::FileOpen { .. }
             | crate::ir::statement::ProtoTbMethodKind::FileClose
             | crate::ir::statement::ProtoTbMethodKind::FileFlush
             | crate::ir::statement::ProtoTbMethodKind::RandomGet { .. }
-            | crate::ir::statement::ProtoTbMethodKind::RandomGetSeed { .. } => {}
+            | crate::ir::statement::ProtoTbMethodKind::RandomGetSeed { .. }
+            | crate::ir::statement::ProtoTbMethodKind::QueuePop { .. }
+            | crate::ir::statement::ProtoTbMethodKind::QueueSize { .. }
+            | crate::ir::statement::ProtoTbMethodKind::QueueEmpty { .. }
+            | crate::ir::statement::ProtoTbMethodKind::ScoreboardPending { .. }
+            | crate::ir::statement::ProtoTbMethodKind::CheckpointBranch { .. }
+            | crate::ir::statement::ProtoTbMethodKind::CheckpointSave { .. }
+            | crate::ir::statement::ProtoTbMethodKind::CheckpointLoad { .. }
+            | crate::ir::statement::ProtoTbMethodKind::CoverPoint { .. }
+            | crate::ir::statement::ProtoTbMethodKind::CoverCross { .. }
+            | crate::ir::statement::ProtoTbMethodKind::WaveStart
+            | crate::ir::statement::ProtoTbMethodKind::WaveStop
+            | crate::ir::statement::ProtoTbMethodKind::ForkBegin { .. }
+            | crate::ir::statement::ProtoTbMethodKind::ForkThread
+            | crate::ir::statement::ProtoTbMethodKind::ForkEnd => {}
         },
         ProtoStatement::SequentialBlock(stmts) => {
             for s in stmts {
@@ -1,4 +1,5 @@
 use crate::backend::inst::next_test_top_id;
+use crate::backend::registry::classify_proto_stmt;
 use crate::backend::{ChunkOutput, CompileCtx, CompiledWhole};
 use crate::ir::comb_pipeline_cache;
 use crate::ir::context::{Context, Conv, ScopeContext};
@@ -7,12 +8,15 @@ use crate::ir::derived_clock::{
     DerivedClockSchedule, build_schedule as build_derived_clock_schedule, extract_eval_proto_stmts,
 };
 use crate::ir::external::{ExternalComponentInst, ProtoExternalComponent};
+use crate::ir::force::{ForceSlot, ProtoForceSlot};
 use crate::ir::inst_layout::InstLayout;
 use crate::ir::opt::dead_var_dce;
 use crate::ir::opt::dup_assign_dce::dce_aggressive;
 use crate::ir::opt::multi_write_analysis::analyze_multi_write;
 use crate::ir::opt::multi_write_analysis::collect_dyn_indexed_vars;
+use crate::ir::partition;
 use crate::ir::site_table::{SiteInfo, SiteKind, SiteTable};
+use crate::ir::statement::blocks_to_statements;
 use crate::ir::variable::{
     ModuleVariableMeta, ModuleVariables, VarOffset, Variable, align_up_64, create_variable_meta,
     ff_cacheline_pad_enabled, value_size, write_native_value,
@@ -21,12 +25,14 @@ use crate::ir::{
     CompiledBatchStmt, Event, ProtoDeclaration, ProtoStatement, ProtoStatementBlock,
     ProtoStatements, Statement, VarId, VarPath,
 };
+use crate::profiler::{self, BuildInfo, Profiler};
 use crate::simulator_error::SimulatorError;
 use crate::{HashMap, HashSet};
 use daggy::Dag;
 use daggy::petgraph::Direction::Outgoing;
 use daggy::petgraph::algo;
 use std::collections::VecDeque;
+use std::ops::Range;
 use std::sync::Arc;
 use veryl_analyzer::ir as air;
 use veryl_parser::resource_table::StrId;
@@ -42,6 +48,9 @@ pub struct Module {
     /// Unified comb statements: all port connections, child comb, and internal
     /// comb combined into a single dependency-sorted list.
     pub comb_statements: Vec<Statement>,
+    /// Ranges of `comb_statements` that share no storage and may settle on
+    /// separate threads (see `ir::partition`); empty when not partitioned.
+    pub comb_groups: Vec<Range<usize>>,
     /// Number of eval_comb passes needed for full convergence.
     /// Pre-computed from backward edges in the sorted comb statement list.
     pub required_comb_passes: usize,
@@ -77,6 +86,10 @@ pub struct Module {
     /// Top-level variables written by RTL statements; component outputs
     /// must not overlap them (sole-driver check at load time).
     pub rtl_driven: crate::HashSet<air::VarId>,
+    /// Statement timing under `Config::profile`.
+    pub profiler: Option<Profiler>,
+    /// See `Ir::force_slots`.
+    pub force_slots: HashMap<String, ForceSlot>,
 }
 
 pub struct ProtoModule {
@@ -91,6 +104,9 @@ pub struct ProtoModule {
     /// Unified comb statements: all port connections, child comb, and internal
     /// comb combined into a single dependency-sorted list.
     pub comb_statements: ProtoStatements,
+    /// Blocks of `comb_statements` per partition group; see
+    /// `Module::comb_groups`.
+    pub comb_groups: Vec<usize>,
     /// Number of eval_comb passes needed for full convergence.
     pub required_comb_passes: usize,
     /// See `Module::site_table`.
@@ -113,6 +129,11 @@ pub struct ProtoModule {
     pub external_components: Vec<ProtoExternalComponent>,
     /// See `Module::rtl_driven`.
     pub rtl_driven: crate::HashSet<air::VarId>,
+    /// Always blocks and interpreter fallbacks under `Config::profile`;
+    /// `instantiate` builds a `Module::profiler` from it.
+    pub profile: Option<BuildInfo>,
+    /// See `Module::force_slots` (pre-pointer-binding form).
+    pub force_slots: Vec<(String, ProtoForceSlot)>,
 }
 
 fn create_buffers(
@@ -228,6 +249,9 @@ fn create_variables_recursive(
             Variable {
                 path: meta.path.clone(),
                 r#type: meta.r#type.clone(),
+                kind: meta.kind,
+                affiliation: meta.affiliation,
+                token: meta.token,
                 width: meta.width,
                 native_bytes: meta.native_bytes,
                 current_values,
@@ -276,22 +300,50 @@ impl ProtoModule {
         let ff_len = self.ff_bytes;
         let comb_len = self.comb_bytes;
 
+        // The profiler times statements one by one, so keep them unbatched.
+        let batch = |stmts: Vec<Statement>| {
+            if self.profile.is_some() {
+                stmts
+            } else {
+                batch_compiled_statements(stmts)
+            }
+        };
+
         let event_statements = self
             .event_statements
             .iter()
             .map(|(event, stmts)| {
                 let s = stmts.to_statements(ff_ptr, ff_len, comb_ptr, comb_len, self.use_4state);
-                (event.clone(), batch_compiled_statements(s))
+                (event.clone(), batch(s))
             })
             .collect();
 
-        let comb_statements = batch_compiled_statements(self.comb_statements.to_statements(
+        // Batch per partition group so no batch straddles two groups.
+        let mut comb_statements = Vec::new();
+        let mut comb_groups = Vec::with_capacity(self.comb_groups.len());
+        let mut blocks: &[ProtoStatementBlock] = &self.comb_statements.0;
+        for &len in &self.comb_groups {
+            let (group, rest) = blocks.split_at(len);
+            blocks = rest;
+            let start = comb_statements.len();
+            comb_statements.extend(batch(blocks_to_statements(
+                group,
+                ff_ptr,
+                ff_len,
+                comb_ptr,
+                comb_len,
+                self.use_4state,
+            )));
+            comb_groups.push(start..comb_statements.len());
+        }
+        comb_statements.extend(batch(blocks_to_statements(
+            blocks,
             ff_ptr,
             ff_len,
             comb_ptr,
             comb_len,
             self.use_4state,
-        ));
+        )));
 
         let derived_clock_eval_stmts = if self.derived_clock_eval.0.is_empty() {
             Vec::new()
@@ -308,6 +360,17 @@ impl ProtoModule {
         #[cfg(debug_assertions)]
         self.validate_offsets();
 
+        let profiler = self.profile.as_ref().map(|info| {
+            Profiler::new(
+                info,
+                &self.module_variable_meta,
+                &self.comb_statements,
+                &self.event_statements,
+                self.whole_comb.is_some(),
+                &self.whole_events,
+            )
+        });
+
         Module {
             name: self.name,
             ports: self.ports.clone(),
@@ -318,6 +381,7 @@ impl ProtoModule {
 
             event_statements,
             comb_statements,
+            comb_groups,
             required_comb_passes: self.required_comb_passes,
             site_table: self.site_table.clone(),
             inst_layout: self.inst_layout.clone(),
@@ -333,6 +397,15 @@ impl ProtoModule {
                 })
                 .collect(),
             rtl_driven: self.rtl_driven.clone(),
+            profiler,
+            force_slots: self
+                .force_slots
+                .iter()
+                .map(|(path, slot)| {
+                    let slot = unsafe { slot.apply_values_ptr(ff_ptr, comb_ptr, self.use_4state) };
+                    (path.clone(), slot)
+                })
+                .collect(),
         }
     }
 
@@ -481,6 +554,16 @@ fn build_chunked_via_registry(
     // `build_chunked` also needs `&mut context.backends` — distinct fields,
     // so Rust's split borrow permits both.
     let max_chunk_size = jit_chunk_size();
+    // Fallbacks are reported by index into `proto`, which `build_chunked`
+    // consumes.
+    let located: Vec<_> = if context.config.profile {
+        profiler::statement_tokens(&proto)
+            .into_iter()
+            .zip(proto.iter().map(classify_proto_stmt))
+            .collect()
+    } else {
+        vec![]
+    };
     let outputs = {
         let ctx = CompileCtx {
             config: &context.config,
@@ -489,6 +572,10 @@ fn build_chunked_via_registry(
         };
         context.backends.build_chunked(&ctx, proto, max_chunk_size)
     };
+    for (i, reason) in std::mem::take(&mut context.backends.fallbacks) {
+        let (token, statement) = located[i].clone();
+        context.profile.add_fallback(token, statement, reason);
+    }
 
     let mut blocks = Vec::with_capacity(outputs.len());
     for out in outputs {
@@ -517,6 +604,8 @@ fn pass_diag_phase(phase: &str) {
 /// and pointer-agnostic (see `ProtoAssignStatement`/`ChunkArtifact` `Debug`).
 fn comb_pipeline_key(
     use_4state: bool,
+    sim_threads: usize,
+    xprop: bool,
     unified: &[ProtoStatement],
     events: &HashMap<Event, Vec<ProtoStatement>>,
     protect: &HashSet<VarOffset>,
@@ -537,6 +626,10 @@ fn comb_pipeline_key(
     let mut h = DefaultHasher::new();
     evt.hash(&mut h);
     prot_offs.hash(&mut h);
+    // The partition (and so the chunking) depends on the thread count.
+    sim_threads.hash(&mut h);
+    // So does the chunking under `Config::xprop_active`.
+    xprop.hash(&mut h);
     whole_comb_fingerprint(use_4state, unified, h.finish() as u128)
 }
 
@@ -729,12 +822,31 @@ fn run_comb_pipeline(
 
     // Snapshot before JIT consumes it: the whole-comb backend needs the
     // pre-JIT stmts (JIT CompiledBlocks hide stmt-level I/O).
+    // Partitioning only regroups independent statements, so the reordered
+    // list is still a valid schedule for the serial and whole-comb paths.
+    let (unified_sorted, group_lens) =
+        partition::partition_comb(unified_sorted, context.config.sim_threads);
     let pre_jit_stmts = Arc::new(unified_sorted.clone());
-    let comb_statements = try_jit_no_cache(context, unified_sorted);
+    // JIT each group on its own so no chunk straddles two groups.
+    let mut comb_groups = Vec::with_capacity(group_lens.len());
+    let comb_statements = if group_lens.is_empty() {
+        try_jit_no_cache(context, unified_sorted)
+    } else {
+        let mut blocks = vec![];
+        let mut rest = unified_sorted.into_iter();
+        for len in group_lens {
+            let group = rest.by_ref().take(len).collect();
+            let group = try_jit_no_cache(context, group).0;
+            comb_groups.push(group.len());
+            blocks.extend(group);
+        }
+        ProtoStatements(blocks)
+    };
     Ok(comb_pipeline_cache::CombPipeline {
         pre_jit_stmts,
         required_comb_passes,
         comb_statements,
+        comb_groups,
         dead_offsets: dead_union.into_iter().collect(),
         nontrivial_comb_scc,
     })
@@ -2737,11 +2849,21 @@ impl Conv<&air::Module> for ProtoModule {
         // expanded on demand by `analyze_dependency` Phase 2 when fine-grained
         // ordering is needed.  This eliminates the false SCC artifact from
         // keeping both CB and its originals in the parent's `unified` list.
-        let unified: Vec<ProtoStatement> = all_comb_statements
+        let mut unified: Vec<ProtoStatement> = all_comb_statements
             .into_iter()
             .chain(all_post_comb_fns)
             .collect();
 
+        // `$tb::force` targets are design variables; override their drivers
+        // before optimization and the backends see them.
+        let force_slots = crate::ir::force::install(
+            context,
+            &mut all_event_statements,
+            &mut unified,
+            &variable_meta,
+            &all_child_modules,
+        )?;
+
         // Dead-var DCE protect set (also folded into the cache key): offsets
         // that must survive DCE.  `comb_to_ff_hoist` only rewrites `VarKind::Let`,
         // so the dead residue DCE targets is always Let-kind; user `var`s and
@@ -2792,6 +2914,8 @@ impl Conv<&air::Module> for ProtoModule {
         // Single-flight (see `comb_pipeline_cache`); gated to `dut_reuse`.
         let key = comb_pipeline_key(
             context.config.use_4state,
+            context.config.sim_threads,
+            context.config.xprop_active(),
             &unified,
             &all_event_statements,
             &dce_protect,
@@ -2833,6 +2957,7 @@ impl Conv<&air::Module> for ProtoModule {
         let pre_jit_stmts = Arc::clone(&cached.pre_jit_stmts);
         let required_comb_passes = cached.required_comb_passes;
         let comb_statements = cached.comb_statements.clone();
+        let comb_groups = cached.comb_groups.clone();
         let nontrivial_comb_scc = cached.nontrivial_comb_scc;
 
         // Top-level variables written by RTL (post-DCE), for the sole-driver
@@ -3273,6 +3398,27 @@ impl Conv<&air::Module> for ProtoModule {
             }
         }
 
+        // The profiler reports a whole-comb bail as one fallback for the
+        // comb list (the AOT-C backend is off for 4-state / X propagation).
+        #[cfg(not(target_family = "wasm"))]
+        if context.config.profile
+            && context.config.aot_c
+            && !context.config.use_4state
+            && !context.config.xprop_active()
+            && whole_comb.is_none()
+        {
+            let reason = if aot_size_ok {
+                crate::backend::aot_c::emit::comb_fallback_reason(&pre_jit_stmts)
+            } else {
+                format!("fewer than {} statements", context.config.aot_c_min_stmts)
+            };
+            context.profile.add_fallback(
+                None,
+                "whole comb".to_string(),
+                format!("aot_c declined: {reason}"),
+            );
+        }
+
         Ok(ProtoModule {
             name: src.name,
             ports: src.ports.clone(),
@@ -3282,6 +3428,7 @@ impl Conv<&air::Module> for ProtoModule {
             module_variable_meta,
             event_statements,
             comb_statements,
+            comb_groups,
             required_comb_passes,
             site_table,
             inst_layout,
@@ -3292,6 +3439,11 @@ impl Conv<&air::Module> for ProtoModule {
             whole_events,
             external_components: all_external_components,
             rtl_driven,
+            profile: context
+                .config
+                .profile
+                .then(|| std::mem::take(&mut context.profile)),
+            force_slots,
         })
     }
 }
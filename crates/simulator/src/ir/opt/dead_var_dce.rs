@@ -260,7 +260,7 @@ fn walk_stmt_liveness(stmt: &ProtoStatement, c: &mut Census) {
                     walk_expr_reads(a, c);
                 }
             }
-            ProtoSystemFunctionCall::Finish => {}
+            ProtoSystemFunctionCall::Finish | ProtoSystemFunctionCall::Cover { .. } => {}
         },
         ProtoStatement::CompiledBlock(x) => {
             for s in &x.original_stmts {
@@ -303,15 +303,93 @@ fn walk_stmt_liveness(stmt: &ProtoStatement, c: &mut Census) {
             ProtoTbMethodKind::RandomSeed { value } => {
                 walk_expr_reads(value, c);
             }
-            ProtoTbMethodKind::RandomGetRange { min, max, .. } => {
+            ProtoTbMethodKind::RandomGetRange { min, max, .. }
+            | ProtoTbMethodKind::RandomGetCyclic { min, max, .. } => {
                 walk_expr_reads(min, c);
                 walk_expr_reads(max, c);
             }
+            ProtoTbMethodKind::RandomGetWhere { min, max, cond, .. } => {
+                walk_expr_reads(min, c);
+                walk_expr_reads(max, c);
+                walk_expr_reads(cond, c);
+            }
+            ProtoTbMethodKind::RandomGetDist {
+                values, weights, ..
+            } => {
+                for e in values.iter().chain(weights) {
+                    walk_expr_reads(e, c);
+                }
+            }
+            ProtoTbMethodKind::RandomGetInside { values, .. } => {
+                for e in values {
+                    walk_expr_reads(e, c);
+                }
+            }
+            ProtoTbMethodKind::CheckpointFork { count } => {
+                walk_expr_reads(count, c);
+            }
+            // A force target counts as read so its drivers, which
+            // `force::install` rewrote around the override, stay alive.
+            ProtoTbMethodKind::Force { target, value }
+            | ProtoTbMethodKind::Deposit { target, value } => {
+                walk_expr_reads(&target.expr, c);
+                walk_expr_reads(value, c);
+            }
+            ProtoTbMethodKind::Release { target } => {
+                walk_expr_reads(&target.expr, c);
+            }
+            ProtoTbMethodKind::CoverBins { values, .. }
+            | ProtoTbMethodKind::CoverSample { values, .. } => {
+                for e in values {
+                    walk_expr_reads(e, c);
+                }
+            }
+            ProtoTbMethodKind::CoverRange {
+                min, max, count, ..
+            } => {
+                walk_expr_reads(min, c);
+                walk_expr_reads(max, c);
+                if let Some(count) = count {
+                    walk_expr_reads(count, c);
+                }
+            }
+            ProtoTbMethodKind::ClockWaitUntil {
+                cond,
+                within,
+                period,
+            } => {
+                walk_expr_reads(cond, c);
+                for e in within.iter().chain(period) {
+                    walk_expr_reads(e, c);
+                }
+            }
+            ProtoTbMethodKind::ThreadWaitUntil { cond } => {
+                walk_expr_reads(cond, c);
+            }
+            ProtoTbMethodKind::QueuePush { value, .. }
+            | ProtoTbMethodKind::ScoreboardExpect { value, .. }
+            | ProtoTbMethodKind::ScoreboardObserve { value, .. } => {
+                walk_expr_reads(value, c);
+            }
             ProtoTbMethodKind::FileOpen { .. }
             | ProtoTbMethodKind::FileClose
             | ProtoTbMethodKind::FileFlush
             | ProtoTbMethodKind::RandomGet { .. }
-            | ProtoTbMethodKind::RandomGetSeed { .. } => {}
+            | ProtoTbMethodKind::RandomGetSeed { .. }
+            | ProtoTbMethodKind::QueuePop { .. }
+            | ProtoTbMethodKind::QueueSize { .. }
+            | ProtoTbMethodKind::QueueEmpty { .. }
+            | ProtoTbMethodKind::ScoreboardPending { .. }
+            | ProtoTbMethodKind::CheckpointBranch { .. }
+            | ProtoTbMethodKind::CheckpointSave { .. }
+            | ProtoTbMethodKind::CheckpointLoad { .. }
+            | ProtoTbMethodKind::CoverPoint { .. }
+            | ProtoTbMethodKind::CoverCross { .. }
+            | ProtoTbMethodKind::WaveStart
+            | ProtoTbMethodKind::WaveStop
+            | ProtoTbMethodKind::ForkBegin { .. }
+            | ProtoTbMethodKind::ForkThread
+            | ProtoTbMethodKind::ForkEnd => {}
         },
         ProtoStatement::Break => {}
     }
@@ -127,6 +127,14 @@ fn count_writes_one(
         Statement::For(f) => {
             return count_writes_seq(&f.body, ctx);
         }
+        // Every thread of a fork runs, so their writes sum like a sequence.
+        Statement::Fork(x) => {
+            for thread in &x.threads {
+                for (k, n) in count_writes_seq(thread, ctx) {
+                    *result.entry(k).or_insert(0) += n;
+                }
+            }
+        }
         Statement::FunctionCall(call) => {
             for outputs in call.outputs.values() {
                 for dst in outputs {
@@ -210,6 +218,11 @@ fn collect_dyn_one(stmt: &air::Statement, out: &mut HashSet<VarId>) {
                 collect_dyn_one(s, out);
             }
         }
+        Statement::Fork(x) => {
+            for s in x.threads.iter().flatten() {
+                collect_dyn_one(s, out);
+            }
+        }
         Statement::FunctionCall(call) => {
             for outputs in call.outputs.values() {
                 for dst in outputs {
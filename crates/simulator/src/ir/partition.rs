@@ -0,0 +1,303 @@
+//! Partitioned multi-threaded comb settle (`Config::sim_threads`).
+//!
+//! `partition_comb` runs inside the comb pipeline, after dependency sorting
+//! and DCE, so its result is memoised by `comb_pipeline_cache` along with the
+//! rest of the pipeline. Statements that touch a common comb-written offset
+//! are unioned into one cluster; distinct clusters share no written bytes, so
+//! running each cluster's statements (in sorted order, for all passes)
+//! independently produces exactly the buffers of the single-threaded loop.
+//! Clusters are bin-packed into at most `sim_threads` groups, each
+//! JIT-compiled on its own so no chunk straddles two groups.
+//!
+//! Group 0 runs on the simulating thread and takes every statement with an
+//! effect beyond the comb buffers: FF writes (pushed to the shared write
+//! log), system function calls (output order, coverage counters) and
+//! testbench methods. `CombPool` runs the remaining groups on persistent
+//! worker threads, synchronized once per settle.
+//!
+//! Event statements stay on the simulating thread: their FF writes all go
+//! through the one write log.
+
+use super::dispatch_stmt_fast;
+use super::statement::{ProtoStatement, Statement};
+use super::variable::VarOffset;
+use crate::{HashMap, HashSet};
+use std::ops::Range;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use std::thread::JoinHandle;
+use veryl_analyzer::value::MaskCache;
+
+/// Reorders `stmts` so each group is contiguous and returns the group
+/// lengths (group 0 first). Returns `stmts` untouched with no groups when
+/// `threads < 2` or the list does not split into at least two groups.
+pub(crate) fn partition_comb(
+    stmts: Vec<ProtoStatement>,
+    threads: usize,
+) -> (Vec<ProtoStatement>, Vec<usize>) {
+    let n = stmts.len();
+    if threads < 2 || n < 2 {
+        return (stmts, vec![]);
+    }
+
+    // Only storage some comb statement writes orders its accessors; offsets
+    // that are only read (ports, FFs) may be shared freely.
+    let mut ins = vec![];
+    let mut outs = vec![];
+    let mut written: HashSet<VarOffset> = HashSet::default();
+    for stmt in &stmts {
+        stmt.gather_variable_offsets_expanded(&mut ins, &mut outs);
+        written.extend(outs.drain(..));
+        ins.clear();
+    }
+
+    let mut sets = DisjointSets::new(n);
+    let mut owner: HashMap<VarOffset, usize> = HashMap::default();
+    let mut pinned: Option<usize> = None;
+    let mut weight = Vec::with_capacity(n);
+    for (i, stmt) in stmts.iter().enumerate() {
+        ins.clear();
+        outs.clear();
+        // The expanded form keeps every array element and the internals of
+        // sequential blocks, which the dependency view hides.
+        stmt.gather_variable_offsets_expanded(&mut ins, &mut outs);
+        for off in ins.iter().chain(outs.iter()) {
+            if !written.contains(off) {
+                continue;
+            }
+            match owner.get(off) {
+                Some(&j) => sets.union(i, j),
+                None => {
+                    owner.insert(*off, i);
+                }
+            }
+        }
+        if outs.iter().any(|x| x.is_ff()) || has_side_effect(stmt) {
+            match pinned {
+                Some(j) => sets.union(i, j),
+                None => pinned = Some(i),
+            }
+        }
+        weight.push(1 + ins.len() + outs.len());
+    }
+
+    // Clusters in order of their first statement.
+    let mut cluster_of_root: HashMap<usize, usize> = HashMap::default();
+    let mut clusters: Vec<(usize, usize)> = vec![]; // (root, weight)
+    let mut cluster = Vec::with_capacity(n);
+    for (i, w) in weight.iter().enumerate() {
+        let root = sets.find(i);
+        let c = *cluster_of_root.entry(root).or_insert_with(|| {
+            clusters.push((root, 0));
+            clusters.len() - 1
+        });
+        clusters[c].1 += w;
+        cluster.push(c);
+    }
+    if clusters.len() < 2 {
+        return (stmts, vec![]);
+    }
+
+    // Longest-processing-time bin packing; the pinned cluster is fixed to
+    // group 0 first.
+    let pinned_cluster = pinned.map(|i| cluster[i]);
+    let mut load = vec![0usize; threads];
+    let mut group_of = vec![0usize; clusters.len()];
+    if let Some(c) = pinned_cluster {
+        load[0] = clusters[c].1;
+    }
+    let mut order: Vec<usize> = (0..clusters.len())
+        .filter(|c| Some(*c) != pinned_cluster)
+        .collect();
+    order.sort_by_key(|c| std::cmp::Reverse(clusters[*c].1));
+    for c in order {
+        let g = (0..threads).min_by_key(|g| load[*g]).unwrap();
+        load[g] += clusters[c].1;
+        group_of[c] = g;
+    }
+
+    let mut groups: Vec<Vec<ProtoStatement>> = (0..threads).map(|_| vec![]).collect();
+    for (i, stmt) in stmts.into_iter().enumerate() {
+        groups[group_of[cluster[i]]].push(stmt);
+    }
+    groups.retain(|g| !g.is_empty());
+    let lens = groups.iter().map(|g| g.len()).collect();
+    (groups.into_iter().flatten().collect(), lens)
+}
+
+/// Whether `stmt` has an effect beyond the value buffers, which must stay on
+/// the simulating thread.
+fn has_side_effect(stmt: &ProtoStatement) -> bool {
+    match stmt {
+        ProtoStatement::SystemFunctionCall(_) | ProtoStatement::TbMethodCall { .. } => true,
+        ProtoStatement::Assign(_) | ProtoStatement::AssignDynamic(_) | ProtoStatement::Break => {
+            false
+        }
+        ProtoStatement::If(x) => x
+            .true_side
+            .iter()
+            .chain(x.false_side.iter())
+            .any(has_side_effect),
+        ProtoStatement::Case(x) => x
+            .arms
+            .iter()
+            .flat_map(|arm| arm.body.iter())
+            .chain(x.default.iter())
+            .any(has_side_effect),
+        ProtoStatement::For(x) => x.body.iter().any(has_side_effect),
+        ProtoStatement::SequentialBlock(body) => body.iter().any(has_side_effect),
+        ProtoStatement::CompiledBlock(x) => x.original_stmts.iter().any(has_side_effect),
+    }
+}
+
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let a = self.find(a);
+        let b = self.find(b);
+        // Keep the smaller index as root so clusters are named by their
+        // first statement.
+        if a < b {
+            self.parent[b] = a;
+        } else if b < a {
+            self.parent[a] = b;
+        }
+    }
+}
+
+/// Persistent workers evaluating comb groups 1.. while the simulating thread
+/// runs group 0.
+pub struct CombPool {
+    local: Range<usize>,
+    shared: Arc<PoolShared>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+struct PoolShared {
+    start: Barrier,
+    done: Barrier,
+    stmts: AtomicPtr<Statement>,
+    passes: AtomicUsize,
+    shutdown: AtomicBool,
+    panicked: AtomicBool,
+}
+
+impl CombPool {
+    /// Spawns one worker per group after the first; `None` for fewer than
+    /// two groups.
+    pub fn new(groups: &[Range<usize>]) -> Option<Self> {
+        if groups.len() < 2 {
+            return None;
+        }
+        let shared = Arc::new(PoolShared {
+            start: Barrier::new(groups.len()),
+            done: Barrier::new(groups.len()),
+            stmts: AtomicPtr::new(std::ptr::null_mut()),
+            passes: AtomicUsize::new(0),
+            shutdown: AtomicBool::new(false),
+            panicked: AtomicBool::new(false),
+        });
+        let workers = groups[1..]
+            .iter()
+            .map(|range| {
+                let shared = Arc::clone(&shared);
+                let range = range.clone();
+                std::thread::Builder::new()
+                    .name("veryl-comb".to_string())
+                    .spawn(move || worker(&shared, range))
+                    .expect("failed to spawn comb worker thread")
+            })
+            .collect();
+        Some(Self {
+            local: groups[0].clone(),
+            shared,
+            workers,
+        })
+    }
+
+    pub fn threads(&self) -> usize {
+        self.workers.len() + 1
+    }
+
+    /// Runs `passes` passes of every group over `stmts`, returning once all
+    /// workers are done.
+    pub fn settle(&self, stmts: &[Statement], passes: usize, mask_cache: &mut MaskCache) {
+        self.shared
+            .stmts
+            .store(stmts.as_ptr() as *mut Statement, Ordering::Relaxed);
+        self.shared.passes.store(passes, Ordering::Relaxed);
+        // The barrier orders the stores above before the workers' loads.
+        self.shared.start.wait();
+        let local = &stmts[self.local.clone()];
+        // Always reach `done`, or the workers would wait forever.
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            for _ in 0..passes {
+                for stmt in local {
+                    dispatch_stmt_fast(stmt, mask_cache);
+                }
+            }
+        }));
+        self.shared.done.wait();
+        if let Err(err) = result {
+            resume_unwind(err);
+        }
+        if self.shared.panicked.load(Ordering::Relaxed) {
+            panic!("comb worker thread panicked");
+        }
+    }
+}
+
+fn worker(shared: &PoolShared, range: Range<usize>) {
+    let mut mask_cache = MaskCache::default();
+    loop {
+        shared.start.wait();
+        if shared.shutdown.load(Ordering::Relaxed) {
+            return;
+        }
+        let base = shared.stmts.load(Ordering::Relaxed);
+        let passes = shared.passes.load(Ordering::Relaxed);
+        // SAFETY: `settle` keeps the statement slice borrowed until the
+        // `done` barrier, and this group's statements write only storage no
+        // other group touches (see `partition_comb`).
+        let stmts = unsafe { std::slice::from_raw_parts(base.add(range.start), range.len()) };
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            for _ in 0..passes {
+                for stmt in stmts {
+                    dispatch_stmt_fast(stmt, &mut mask_cache);
+                }
+            }
+        }));
+        if result.is_err() {
+            shared.panicked.store(true, Ordering::Relaxed);
+        }
+        shared.done.wait();
+    }
+}
+
+impl Drop for CombPool {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+        self.shared.start.wait();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
@@ -2,11 +2,13 @@ use crate::HashMap;
 use crate::HashSet;
 use crate::assert_buffer;
 use crate::backend::ChunkArtifact;
+use crate::coverage;
 use crate::ir::context::{Context, Conv};
 use crate::ir::expression::{
     DynamicBitSelect, ExpressionContext, ProtoDynamicBitSelect, build_dynamic_bit_select,
     build_linear_index_expr,
 };
+use crate::ir::force::{ForceSlot, ProtoForceTarget};
 use crate::ir::partial_index::partial_index_base;
 use crate::ir::variable::{
     VarOffset, native_bytes as calc_native_bytes, read_native_value, write_native_value,
@@ -17,7 +19,9 @@ use crate::ir::write_log::{
 use crate::ir::{Expression, ProtoExpression, Value};
 use crate::output_buffer;
 use crate::simulator_error::SimulatorError;
+use crate::xprop;
 use std::sync::Arc;
+use veryl_analyzer::attribute::JoinItem;
 use veryl_analyzer::conv::utils::eval_array_literal;
 use veryl_analyzer::ir as air;
 use veryl_analyzer::ir::{
@@ -49,31 +53,44 @@ impl ProtoStatements {
         comb_len: usize,
         use_4state: bool,
     ) -> Vec<Statement> {
-        let mut result = Vec::new();
-        for block in &self.0 {
-            match block {
-                ProtoStatementBlock::Interpreted(proto) => {
-                    for s in proto {
-                        result.push(unsafe {
-                            s.apply_values_ptr(ff_ptr, ff_len, comb_ptr, comb_len, use_4state)
-                        });
-                    }
-                }
-                ProtoStatementBlock::Compiled(artifact) => {
-                    // log_buf populated by `Ir::install_write_log_ptr` after
-                    // WriteLogBuffer allocation; null until then.
-                    result.push(Statement::Compiled(CompiledStmt {
-                        artifact: Arc::clone(artifact),
-                        ff: ff_ptr as *const u8,
-                        comb: comb_ptr as *const u8,
-                        log_buf: std::ptr::null_mut(),
-                        ff_delta: 0,
-                    }));
+        blocks_to_statements(&self.0, ff_ptr, ff_len, comb_ptr, comb_len, use_4state)
+    }
+}
+
+/// `ProtoStatements::to_statements` over a sub-slice of blocks (one comb
+/// partition group).
+pub(crate) fn blocks_to_statements(
+    blocks: &[ProtoStatementBlock],
+    ff_ptr: *mut u8,
+    ff_len: usize,
+    comb_ptr: *mut u8,
+    comb_len: usize,
+    use_4state: bool,
+) -> Vec<Statement> {
+    let mut result = Vec::new();
+    for block in blocks {
+        match block {
+            ProtoStatementBlock::Interpreted(proto) => {
+                for s in proto {
+                    result.push(unsafe {
+                        s.apply_values_ptr(ff_ptr, ff_len, comb_ptr, comb_len, use_4state)
+                    });
                 }
             }
+            ProtoStatementBlock::Compiled(artifact) => {
+                // log_buf populated by `Ir::install_write_log_ptr` after
+                // WriteLogBuffer allocation; null until then.
+                result.push(Statement::Compiled(CompiledStmt {
+                    artifact: Arc::clone(artifact),
+                    ff: ff_ptr as *const u8,
+                    comb: comb_ptr as *const u8,
+                    log_buf: std::ptr::null_mut(),
+                    ff_delta: 0,
+                }));
+            }
         }
-        result
     }
+    result
 }
 
 #[derive(Clone, Debug, Hash)]
@@ -103,8 +120,13 @@ pub enum SystemFunctionCall {
         condition: Expression,
         format_str: String,
         args: Vec<Expression>,
+        token: Box<TokenRange>,
     },
     Finish,
+    /// Coverage counter; see [`crate::coverage`].
+    Cover {
+        id: u32,
+    },
 }
 
 #[derive(Clone)]
@@ -178,9 +200,128 @@ pub enum ProtoTbMethodKind {
         signed: bool,
         ret: Option<(VarId, RetWidthCheck)>,
     },
+    RandomGetDist {
+        values: Vec<ProtoExpression>,
+        weights: Vec<ProtoExpression>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    RandomGetInside {
+        values: Vec<ProtoExpression>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    RandomGetCyclic {
+        min: ProtoExpression,
+        max: ProtoExpression,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    RandomGetWhere {
+        min: ProtoExpression,
+        max: ProtoExpression,
+        cond: ProtoExpression,
+        others: Vec<VarId>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
     RandomGetSeed {
         ret: Option<(VarId, RetWidthCheck)>,
     },
+    QueuePush {
+        value: ProtoExpression,
+        width: u32,
+        signed: bool,
+    },
+    QueuePop {
+        peek: bool,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    QueueSize {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    QueueEmpty {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    ScoreboardExpect {
+        value: ProtoExpression,
+        width: u32,
+        signed: bool,
+    },
+    ScoreboardObserve {
+        value: ProtoExpression,
+        ordered: bool,
+        width: u32,
+        signed: bool,
+    },
+    ScoreboardPending {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    WaveStart,
+    WaveStop,
+    CheckpointFork {
+        count: ProtoExpression,
+    },
+    CheckpointBranch {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    CheckpointSave {
+        path: String,
+    },
+    CheckpointLoad {
+        path: String,
+    },
+    Force {
+        target: ProtoForceTarget,
+        value: ProtoExpression,
+    },
+    Release {
+        target: ProtoForceTarget,
+    },
+    Deposit {
+        target: ProtoForceTarget,
+        value: ProtoExpression,
+    },
+    CoverPoint {
+        name: String,
+    },
+    CoverBins {
+        name: String,
+        values: Vec<ProtoExpression>,
+    },
+    CoverRange {
+        name: String,
+        min: ProtoExpression,
+        max: ProtoExpression,
+        count: Option<ProtoExpression>,
+    },
+    CoverCross {
+        name: String,
+        points: Vec<String>,
+    },
+    CoverSample {
+        names: Vec<String>,
+        values: Vec<ProtoExpression>,
+    },
+    ClockWaitUntil {
+        cond: ProtoExpression,
+        within: Option<ProtoExpression>,
+        period: Option<ProtoExpression>,
+    },
+    ThreadWaitUntil {
+        cond: ProtoExpression,
+    },
+    ForkBegin {
+        any: bool,
+    },
+    ForkThread,
+    ForkEnd,
 }
 
 /// How a component method's returned width is validated before it lands
@@ -243,9 +384,128 @@ pub enum TbMethodKind {
         signed: bool,
         ret: Option<(VarId, RetWidthCheck)>,
     },
+    RandomGetDist {
+        values: Vec<Expression>,
+        weights: Vec<Expression>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    RandomGetInside {
+        values: Vec<Expression>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    RandomGetCyclic {
+        min: Expression,
+        max: Expression,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    RandomGetWhere {
+        min: Expression,
+        max: Expression,
+        cond: Expression,
+        others: Vec<VarId>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
     RandomGetSeed {
         ret: Option<(VarId, RetWidthCheck)>,
     },
+    QueuePush {
+        value: Expression,
+        width: u32,
+        signed: bool,
+    },
+    QueuePop {
+        peek: bool,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    QueueSize {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    QueueEmpty {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    ScoreboardExpect {
+        value: Expression,
+        width: u32,
+        signed: bool,
+    },
+    ScoreboardObserve {
+        value: Expression,
+        ordered: bool,
+        width: u32,
+        signed: bool,
+    },
+    ScoreboardPending {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    WaveStart,
+    WaveStop,
+    CheckpointFork {
+        count: Expression,
+    },
+    CheckpointBranch {
+        ret: Option<(VarId, RetWidthCheck)>,
+    },
+    CheckpointSave {
+        path: String,
+    },
+    CheckpointLoad {
+        path: String,
+    },
+    Force {
+        slot: ForceSlot,
+        value: Expression,
+    },
+    Release {
+        slot: ForceSlot,
+    },
+    Deposit {
+        slot: ForceSlot,
+        value: Expression,
+    },
+    CoverPoint {
+        name: String,
+    },
+    CoverBins {
+        name: String,
+        values: Vec<Expression>,
+    },
+    CoverRange {
+        name: String,
+        min: Expression,
+        max: Expression,
+        count: Option<Expression>,
+    },
+    CoverCross {
+        name: String,
+        points: Vec<String>,
+    },
+    CoverSample {
+        names: Vec<String>,
+        values: Vec<Expression>,
+    },
+    ClockWaitUntil {
+        cond: Expression,
+        within: Option<Expression>,
+        period: Option<Expression>,
+    },
+    ThreadWaitUntil {
+        cond: Expression,
+    },
+    ForkBegin {
+        any: bool,
+    },
+    ForkThread,
+    ForkEnd,
 }
 
 /// Pointer-bound form of [`ProtoComponentArg`].
@@ -315,6 +575,8 @@ pub enum Statement {
     TbMethodCall {
         inst: StrId,
         method: TbMethodKind,
+        /// Source of the call, reported when a test hangs in it.
+        token: TokenRange,
     },
 }
 
@@ -387,6 +649,47 @@ pub fn patch_stmt_log_buf(s: &mut Statement, log_buf: *mut u8) {
         | Statement::TbMethodCall { .. } => {}
     }
 }
+
+/// Hand `merger` to every interpreted `if`/`case` in `s`.
+pub fn patch_stmt_xprop(s: &mut Statement, merger: &Arc<xprop::Merger>) {
+    match s {
+        Statement::If(if_stmt) => {
+            if_stmt.xprop = Some(Arc::clone(merger));
+            for s in if_stmt.true_side.iter_mut().chain(&mut if_stmt.false_side) {
+                patch_stmt_xprop(s, merger);
+            }
+        }
+        Statement::Case(case_stmt) => {
+            case_stmt.xprop = Some(Arc::clone(merger));
+            for arm in &mut case_stmt.arms {
+                for s in &mut arm.body {
+                    patch_stmt_xprop(s, merger);
+                }
+            }
+            for s in &mut case_stmt.default {
+                patch_stmt_xprop(s, merger);
+            }
+        }
+        Statement::For(for_stmt) => {
+            for s in &mut for_stmt.body {
+                patch_stmt_xprop(s, merger);
+            }
+        }
+        Statement::SequentialBlock(body) => {
+            for s in body {
+                patch_stmt_xprop(s, merger);
+            }
+        }
+        Statement::Compiled(_)
+        | Statement::CompiledBatch(_)
+        | Statement::Assign(_)
+        | Statement::AssignDynamic(_)
+        | Statement::Break
+        | Statement::SystemFunctionCall(_)
+        | Statement::TbMethodCall { .. } => {}
+    }
+}
+
 unsafe impl Send for AssignStatement {}
 unsafe impl Send for AssignDynamicStatement {}
 unsafe impl Send for IfStatement {}
@@ -732,19 +1035,21 @@ impl SystemFunctionCall {
                 condition,
                 format_str,
                 args,
+                token,
             } => {
                 let val = condition.eval(mask_cache);
                 if val.payload_u64() == 0 {
                     let msg = format_assert_message(format_str, args, mask_cache);
                     match kind {
-                        AssertKind::Fatal => assert_buffer::record_fatal(msg),
-                        AssertKind::Continue => assert_buffer::record_continue(msg),
+                        AssertKind::Fatal => assert_buffer::record_fatal(msg, **token),
+                        AssertKind::Continue => assert_buffer::record_continue(msg, **token),
                     }
                 }
             }
             SystemFunctionCall::Finish => {
                 // Handled by testbench driver
             }
+            SystemFunctionCall::Cover { id } => coverage::hit(*id),
         }
     }
 
@@ -761,7 +1066,7 @@ impl SystemFunctionCall {
                 let mut dummy_outputs = vec![];
                 condition.gather_variable(inputs, &mut dummy_outputs);
             }
-            SystemFunctionCall::Finish => {}
+            SystemFunctionCall::Finish | SystemFunctionCall::Cover { .. } => {}
         }
     }
 }
@@ -786,8 +1091,13 @@ pub enum ProtoSystemFunctionCall {
         condition: ProtoExpression,
         format_str: String,
         args: Vec<ProtoExpression>,
+        token: Box<TokenRange>,
     },
     Finish,
+    /// Coverage counter; see [`crate::coverage`].
+    Cover {
+        id: u32,
+    },
 }
 
 #[derive(Clone, Debug, Hash)]
@@ -958,6 +1268,7 @@ pub enum ProtoStatement {
     TbMethodCall {
         inst: StrId,
         method: ProtoTbMethodKind,
+        token: TokenRange,
     },
 }
 
@@ -1036,7 +1347,7 @@ impl ProtoStatement {
                         arg.adjust_offsets(ff_delta, comb_delta);
                     }
                 }
-                ProtoSystemFunctionCall::Finish => {}
+                ProtoSystemFunctionCall::Finish | ProtoSystemFunctionCall::Cover { .. } => {}
             },
             ProtoStatement::CompiledBlock(_) => {
                 // CompiledBlocks use ff_delta_bytes/comb_delta_bytes at runtime.
@@ -1095,15 +1406,97 @@ impl ProtoStatement {
                 ProtoTbMethodKind::RandomSeed { value } => {
                     value.adjust_offsets(ff_delta, comb_delta);
                 }
-                ProtoTbMethodKind::RandomGetRange { min, max, .. } => {
+                ProtoTbMethodKind::RandomGetRange { min, max, .. }
+                | ProtoTbMethodKind::RandomGetCyclic { min, max, .. } => {
+                    min.adjust_offsets(ff_delta, comb_delta);
+                    max.adjust_offsets(ff_delta, comb_delta);
+                }
+                ProtoTbMethodKind::RandomGetWhere { min, max, cond, .. } => {
+                    min.adjust_offsets(ff_delta, comb_delta);
+                    max.adjust_offsets(ff_delta, comb_delta);
+                    cond.adjust_offsets(ff_delta, comb_delta);
+                }
+                ProtoTbMethodKind::RandomGetDist {
+                    values, weights, ..
+                } => {
+                    for e in values.iter_mut().chain(weights) {
+                        e.adjust_offsets(ff_delta, comb_delta);
+                    }
+                }
+                ProtoTbMethodKind::RandomGetInside { values, .. } => {
+                    for e in values {
+                        e.adjust_offsets(ff_delta, comb_delta);
+                    }
+                }
+                ProtoTbMethodKind::CheckpointFork { count } => {
+                    count.adjust_offsets(ff_delta, comb_delta);
+                }
+                ProtoTbMethodKind::Force { target, value }
+                | ProtoTbMethodKind::Deposit { target, value } => {
+                    target.expr.adjust_offsets(ff_delta, comb_delta);
+                    if let Some(slot) = &mut target.slot {
+                        slot.adjust_offsets(ff_delta, comb_delta);
+                    }
+                    value.adjust_offsets(ff_delta, comb_delta);
+                }
+                ProtoTbMethodKind::Release { target } => {
+                    target.expr.adjust_offsets(ff_delta, comb_delta);
+                    if let Some(slot) = &mut target.slot {
+                        slot.adjust_offsets(ff_delta, comb_delta);
+                    }
+                }
+                ProtoTbMethodKind::CoverBins { values, .. }
+                | ProtoTbMethodKind::CoverSample { values, .. } => {
+                    for e in values {
+                        e.adjust_offsets(ff_delta, comb_delta);
+                    }
+                }
+                ProtoTbMethodKind::CoverRange {
+                    min, max, count, ..
+                } => {
                     min.adjust_offsets(ff_delta, comb_delta);
                     max.adjust_offsets(ff_delta, comb_delta);
+                    if let Some(count) = count {
+                        count.adjust_offsets(ff_delta, comb_delta);
+                    }
+                }
+                ProtoTbMethodKind::ClockWaitUntil {
+                    cond,
+                    within,
+                    period,
+                } => {
+                    cond.adjust_offsets(ff_delta, comb_delta);
+                    for e in within.iter_mut().chain(period) {
+                        e.adjust_offsets(ff_delta, comb_delta);
+                    }
+                }
+                ProtoTbMethodKind::ThreadWaitUntil { cond } => {
+                    cond.adjust_offsets(ff_delta, comb_delta);
+                }
+                ProtoTbMethodKind::QueuePush { value, .. }
+                | ProtoTbMethodKind::ScoreboardExpect { value, .. }
+                | ProtoTbMethodKind::ScoreboardObserve { value, .. } => {
+                    value.adjust_offsets(ff_delta, comb_delta);
                 }
                 ProtoTbMethodKind::FileOpen { .. }
                 | ProtoTbMethodKind::FileClose
                 | ProtoTbMethodKind::FileFlush
                 | ProtoTbMethodKind::RandomGet { .. }
-                | ProtoTbMethodKind::RandomGetSeed { .. } => {}
+                | ProtoTbMethodKind::RandomGetSeed { .. }
+                | ProtoTbMethodKind::QueuePop { .. }
+                | ProtoTbMethodKind::QueueSize { .. }
+                | ProtoTbMethodKind::QueueEmpty { .. }
+                | ProtoTbMethodKind::ScoreboardPending { .. }
+                | ProtoTbMethodKind::WaveStart
+                | ProtoTbMethodKind::WaveStop
+                | ProtoTbMethodKind::CheckpointBranch { .. }
+                | ProtoTbMethodKind::CheckpointSave { .. }
+                | ProtoTbMethodKind::CheckpointLoad { .. }
+                | ProtoTbMethodKind::CoverPoint { .. }
+                | ProtoTbMethodKind::CoverCross { .. }
+                | ProtoTbMethodKind::ForkBegin { .. }
+                | ProtoTbMethodKind::ForkThread
+                | ProtoTbMethodKind::ForkEnd => {}
             },
             ProtoStatement::Break => {}
         }
@@ -1226,7 +1619,7 @@ impl ProtoStatement {
                         arg.remap_offsets(map);
                     }
                 }
-                ProtoSystemFunctionCall::Finish => {}
+                ProtoSystemFunctionCall::Finish | ProtoSystemFunctionCall::Cover { .. } => {}
             },
             ProtoStatement::CompiledBlock(_) => {}
             ProtoStatement::For(x) => {
@@ -1284,15 +1677,89 @@ impl ProtoStatement {
                 ProtoTbMethodKind::RandomSeed { value } => {
                     value.remap_offsets(map);
                 }
-                ProtoTbMethodKind::RandomGetRange { min, max, .. } => {
+                ProtoTbMethodKind::RandomGetRange { min, max, .. }
+                | ProtoTbMethodKind::RandomGetCyclic { min, max, .. } => {
+                    min.remap_offsets(map);
+                    max.remap_offsets(map);
+                }
+                ProtoTbMethodKind::RandomGetWhere { min, max, cond, .. } => {
+                    min.remap_offsets(map);
+                    max.remap_offsets(map);
+                    cond.remap_offsets(map);
+                }
+                ProtoTbMethodKind::RandomGetDist {
+                    values, weights, ..
+                } => {
+                    for e in values.iter_mut().chain(weights) {
+                        e.remap_offsets(map);
+                    }
+                }
+                ProtoTbMethodKind::RandomGetInside { values, .. } => {
+                    for e in values {
+                        e.remap_offsets(map);
+                    }
+                }
+                ProtoTbMethodKind::CheckpointFork { count } => {
+                    count.remap_offsets(map);
+                }
+                // Force targets are design variables, never function locals.
+                ProtoTbMethodKind::Force { value, .. }
+                | ProtoTbMethodKind::Deposit { value, .. } => {
+                    value.remap_offsets(map);
+                }
+                ProtoTbMethodKind::Release { .. } => {}
+                ProtoTbMethodKind::CoverBins { values, .. }
+                | ProtoTbMethodKind::CoverSample { values, .. } => {
+                    for e in values {
+                        e.remap_offsets(map);
+                    }
+                }
+                ProtoTbMethodKind::CoverRange {
+                    min, max, count, ..
+                } => {
                     min.remap_offsets(map);
                     max.remap_offsets(map);
+                    if let Some(count) = count {
+                        count.remap_offsets(map);
+                    }
+                }
+                ProtoTbMethodKind::ClockWaitUntil {
+                    cond,
+                    within,
+                    period,
+                } => {
+                    cond.remap_offsets(map);
+                    for e in within.iter_mut().chain(period) {
+                        e.remap_offsets(map);
+                    }
+                }
+                ProtoTbMethodKind::ThreadWaitUntil { cond } => {
+                    cond.remap_offsets(map);
+                }
+                ProtoTbMethodKind::QueuePush { value, .. }
+                | ProtoTbMethodKind::ScoreboardExpect { value, .. }
+                | ProtoTbMethodKind::ScoreboardObserve { value, .. } => {
+                    value.remap_offsets(map);
                 }
                 ProtoTbMethodKind::FileOpen { .. }
                 | ProtoTbMethodKind::FileClose
                 | ProtoTbMethodKind::FileFlush
                 | ProtoTbMethodKind::RandomGet { .. }
-                | ProtoTbMethodKind::RandomGetSeed { .. } => {}
+                | ProtoTbMethodKind::RandomGetSeed { .. }
+                | ProtoTbMethodKind::QueuePop { .. }
+                | ProtoTbMethodKind::QueueSize { .. }
+                | ProtoTbMethodKind::QueueEmpty { .. }
+                | ProtoTbMethodKind::ScoreboardPending { .. }
+                | ProtoTbMethodKind::WaveStart
+                | ProtoTbMethodKind::WaveStop
+                | ProtoTbMethodKind::CheckpointBranch { .. }
+                | ProtoTbMethodKind::CheckpointSave { .. }
+                | ProtoTbMethodKind::CheckpointLoad { .. }
+                | ProtoTbMethodKind::CoverPoint { .. }
+                | ProtoTbMethodKind::CoverCross { .. }
+                | ProtoTbMethodKind::ForkBegin { .. }
+                | ProtoTbMethodKind::ForkThread
+                | ProtoTbMethodKind::ForkEnd => {}
             },
             ProtoStatement::Break => {}
         }
@@ -1386,7 +1853,7 @@ impl ProtoStatement {
                         arg.gather_variable_offsets(inputs);
                     }
                 }
-                ProtoSystemFunctionCall::Finish => {}
+                ProtoSystemFunctionCall::Finish | ProtoSystemFunctionCall::Cover { .. } => {}
             },
             ProtoStatement::CompiledBlock(x) => {
                 // Only include comb (non-FF) offsets for dependency analysis.
@@ -1511,7 +1978,7 @@ impl ProtoStatement {
                         arg.gather_reads_with_ranges(out);
                     }
                 }
-                ProtoSystemFunctionCall::Finish => {}
+                ProtoSystemFunctionCall::Finish | ProtoSystemFunctionCall::Cover { .. } => {}
             },
             ProtoStatement::CompiledBlock(x) => {
                 if !x.stmt_deps.is_empty() {
@@ -1630,7 +2097,7 @@ impl ProtoStatement {
                         arg.gather_variable_offsets_expanded(inputs);
                     }
                 }
-                ProtoSystemFunctionCall::Finish => {}
+                ProtoSystemFunctionCall::Finish | ProtoSystemFunctionCall::Cover { .. } => {}
             },
             ProtoStatement::CompiledBlock(x) => {
                 // Prefer walking the original statements so AssignDynamic /
@@ -1740,7 +2207,7 @@ impl ProtoStatement {
                         arg.gather_dynamic_read_ranges(ranges);
                     }
                 }
-                ProtoSystemFunctionCall::Finish => {}
+                ProtoSystemFunctionCall::Finish | ProtoSystemFunctionCall::Cover { .. } => {}
             },
             ProtoStatement::CompiledBlock(x) => {
                 // Prefer the originals so their DynamicVariable reads register
@@ -1986,6 +2453,7 @@ impl ProtoStatement {
                         condition,
                         format_str,
                         args,
+                        token,
                     } => {
                         let condition = condition.apply_values_ptr(
                             ff_values_ptr,
@@ -2011,6 +2479,7 @@ impl ProtoStatement {
                             condition,
                             format_str: format_str.clone(),
                             args,
+                            token: token.clone(),
                         })
                     }
                     ProtoSystemFunctionCall::Write { format_str, args } => {
@@ -2034,6 +2503,9 @@ impl ProtoStatement {
                     ProtoSystemFunctionCall::Finish => {
                         Statement::SystemFunctionCall(SystemFunctionCall::Finish)
                     }
+                    ProtoSystemFunctionCall::Cover { id } => {
+                        Statement::SystemFunctionCall(SystemFunctionCall::Cover { id: *id })
+                    }
                 },
                 ProtoStatement::CompiledBlock(x) => {
                     // Use wrapping_offset because the adjusted pointer may temporarily
@@ -2153,7 +2625,11 @@ impl ProtoStatement {
                     Statement::SequentialBlock(stmts)
                 }
                 ProtoStatement::Break => Statement::Break,
-                ProtoStatement::TbMethodCall { inst, method } => {
+                ProtoStatement::TbMethodCall {
+                    inst,
+                    method,
+                    token,
+                } => {
                     let method = match method {
                         ProtoTbMethodKind::ClockNext { count, period } => {
                             let count = count.as_ref().map(|e| {
@@ -2278,27 +2754,372 @@ impl ProtoStatement {
                             signed: *signed,
                             ret: *ret,
                         },
-                        ProtoTbMethodKind::RandomGetSeed { ret } => {
-                            TbMethodKind::RandomGetSeed { ret: *ret }
-                        }
-                    };
-                    Statement::TbMethodCall {
-                        inst: *inst,
-                        method,
-                    }
-                }
-            }
-        }
-    }
-}
-
-#[derive(Clone)]
-pub struct AssignStatement {
-    pub dst: *mut u8,
-    pub dst_width: usize,
-    pub dst_native_bytes: usize,
-    pub dst_use_4state: bool,
-    pub select: Option<(usize, usize)>,
+                        ProtoTbMethodKind::RandomGetDist {
+                            values,
+                            weights,
+                            width,
+                            signed,
+                            ret,
+                        } => TbMethodKind::RandomGetDist {
+                            values: values
+                                .iter()
+                                .map(|e| {
+                                    e.apply_values_ptr(
+                                        ff_values_ptr,
+                                        ff_len,
+                                        comb_values_ptr,
+                                        comb_len,
+                                        use_4state,
+                                    )
+                                })
+                                .collect(),
+                            weights: weights
+                                .iter()
+                                .map(|e| {
+                                    e.apply_values_ptr(
+                                        ff_values_ptr,
+                                        ff_len,
+                                        comb_values_ptr,
+                                        comb_len,
+                                        use_4state,
+                                    )
+                                })
+                                .collect(),
+                            width: *width,
+                            signed: *signed,
+                            ret: *ret,
+                        },
+                        ProtoTbMethodKind::RandomGetInside {
+                            values,
+                            width,
+                            signed,
+                            ret,
+                        } => TbMethodKind::RandomGetInside {
+                            values: values
+                                .iter()
+                                .map(|e| {
+                                    e.apply_values_ptr(
+                                        ff_values_ptr,
+                                        ff_len,
+                                        comb_values_ptr,
+                                        comb_len,
+                                        use_4state,
+                                    )
+                                })
+                                .collect(),
+                            width: *width,
+                            signed: *signed,
+                            ret: *ret,
+                        },
+                        ProtoTbMethodKind::RandomGetCyclic {
+                            min,
+                            max,
+                            width,
+                            signed,
+                            ret,
+                        } => TbMethodKind::RandomGetCyclic {
+                            min: min.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            max: max.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            width: *width,
+                            signed: *signed,
+                            ret: *ret,
+                        },
+                        ProtoTbMethodKind::RandomGetWhere {
+                            min,
+                            max,
+                            cond,
+                            others,
+                            width,
+                            signed,
+                            ret,
+                        } => TbMethodKind::RandomGetWhere {
+                            min: min.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            max: max.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            cond: cond.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            others: others.clone(),
+                            width: *width,
+                            signed: *signed,
+                            ret: *ret,
+                        },
+                        ProtoTbMethodKind::RandomGetSeed { ret } => {
+                            TbMethodKind::RandomGetSeed { ret: *ret }
+                        }
+                        ProtoTbMethodKind::QueuePush {
+                            value,
+                            width,
+                            signed,
+                        } => TbMethodKind::QueuePush {
+                            value: value.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            width: *width,
+                            signed: *signed,
+                        },
+                        ProtoTbMethodKind::QueuePop {
+                            peek,
+                            width,
+                            signed,
+                            ret,
+                        } => TbMethodKind::QueuePop {
+                            peek: *peek,
+                            width: *width,
+                            signed: *signed,
+                            ret: *ret,
+                        },
+                        ProtoTbMethodKind::QueueSize { ret } => {
+                            TbMethodKind::QueueSize { ret: *ret }
+                        }
+                        ProtoTbMethodKind::QueueEmpty { ret } => {
+                            TbMethodKind::QueueEmpty { ret: *ret }
+                        }
+                        ProtoTbMethodKind::ScoreboardExpect {
+                            value,
+                            width,
+                            signed,
+                        } => TbMethodKind::ScoreboardExpect {
+                            value: value.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            width: *width,
+                            signed: *signed,
+                        },
+                        ProtoTbMethodKind::ScoreboardObserve {
+                            value,
+                            ordered,
+                            width,
+                            signed,
+                        } => TbMethodKind::ScoreboardObserve {
+                            value: value.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            ordered: *ordered,
+                            width: *width,
+                            signed: *signed,
+                        },
+                        ProtoTbMethodKind::ScoreboardPending { ret } => {
+                            TbMethodKind::ScoreboardPending { ret: *ret }
+                        }
+                        ProtoTbMethodKind::WaveStart => TbMethodKind::WaveStart,
+                        ProtoTbMethodKind::WaveStop => TbMethodKind::WaveStop,
+                        ProtoTbMethodKind::CheckpointFork { count } => {
+                            TbMethodKind::CheckpointFork {
+                                count: count.apply_values_ptr(
+                                    ff_values_ptr,
+                                    ff_len,
+                                    comb_values_ptr,
+                                    comb_len,
+                                    use_4state,
+                                ),
+                            }
+                        }
+                        ProtoTbMethodKind::CheckpointBranch { ret } => {
+                            TbMethodKind::CheckpointBranch { ret: *ret }
+                        }
+                        ProtoTbMethodKind::CheckpointSave { path } => {
+                            TbMethodKind::CheckpointSave { path: path.clone() }
+                        }
+                        ProtoTbMethodKind::CheckpointLoad { path } => {
+                            TbMethodKind::CheckpointLoad { path: path.clone() }
+                        }
+                        ProtoTbMethodKind::Force { target, value }
+                        | ProtoTbMethodKind::Deposit { target, value } => {
+                            // `force::install` resolves every target before
+                            // the module is built.
+                            let slot = target.slot.expect("unresolved force target");
+                            let slot =
+                                slot.apply_values_ptr(ff_values_ptr, comb_values_ptr, use_4state);
+                            let value = value.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            );
+                            if matches!(method, ProtoTbMethodKind::Force { .. }) {
+                                TbMethodKind::Force { slot, value }
+                            } else {
+                                TbMethodKind::Deposit { slot, value }
+                            }
+                        }
+                        ProtoTbMethodKind::Release { target } => {
+                            let slot = target.slot.expect("unresolved force target");
+                            TbMethodKind::Release {
+                                slot: slot.apply_values_ptr(
+                                    ff_values_ptr,
+                                    comb_values_ptr,
+                                    use_4state,
+                                ),
+                            }
+                        }
+                        ProtoTbMethodKind::CoverPoint { name } => {
+                            TbMethodKind::CoverPoint { name: name.clone() }
+                        }
+                        ProtoTbMethodKind::CoverBins { name, values } => TbMethodKind::CoverBins {
+                            name: name.clone(),
+                            values: values
+                                .iter()
+                                .map(|e| {
+                                    e.apply_values_ptr(
+                                        ff_values_ptr,
+                                        ff_len,
+                                        comb_values_ptr,
+                                        comb_len,
+                                        use_4state,
+                                    )
+                                })
+                                .collect(),
+                        },
+                        ProtoTbMethodKind::CoverRange {
+                            name,
+                            min,
+                            max,
+                            count,
+                        } => TbMethodKind::CoverRange {
+                            name: name.clone(),
+                            min: min.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            max: max.apply_values_ptr(
+                                ff_values_ptr,
+                                ff_len,
+                                comb_values_ptr,
+                                comb_len,
+                                use_4state,
+                            ),
+                            count: count.as_ref().map(|e| {
+                                e.apply_values_ptr(
+                                    ff_values_ptr,
+                                    ff_len,
+                                    comb_values_ptr,
+                                    comb_len,
+                                    use_4state,
+                                )
+                            }),
+                        },
+                        ProtoTbMethodKind::CoverCross { name, points } => {
+                            TbMethodKind::CoverCross {
+                                name: name.clone(),
+                                points: points.clone(),
+                            }
+                        }
+                        ProtoTbMethodKind::CoverSample { names, values } => {
+                            TbMethodKind::CoverSample {
+                                names: names.clone(),
+                                values: values
+                                    .iter()
+                                    .map(|e| {
+                                        e.apply_values_ptr(
+                                            ff_values_ptr,
+                                            ff_len,
+                                            comb_values_ptr,
+                                            comb_len,
+                                            use_4state,
+                                        )
+                                    })
+                                    .collect(),
+                            }
+                        }
+                        ProtoTbMethodKind::ClockWaitUntil {
+                            cond,
+                            within,
+                            period,
+                        } => {
+                            let apply = |e: &ProtoExpression| {
+                                e.apply_values_ptr(
+                                    ff_values_ptr,
+                                    ff_len,
+                                    comb_values_ptr,
+                                    comb_len,
+                                    use_4state,
+                                )
+                            };
+                            TbMethodKind::ClockWaitUntil {
+                                cond: apply(cond),
+                                within: within.as_ref().map(apply),
+                                period: period.as_ref().map(apply),
+                            }
+                        }
+                        ProtoTbMethodKind::ThreadWaitUntil { cond } => {
+                            TbMethodKind::ThreadWaitUntil {
+                                cond: cond.apply_values_ptr(
+                                    ff_values_ptr,
+                                    ff_len,
+                                    comb_values_ptr,
+                                    comb_len,
+                                    use_4state,
+                                ),
+                            }
+                        }
+                        ProtoTbMethodKind::ForkBegin { any } => {
+                            TbMethodKind::ForkBegin { any: *any }
+                        }
+                        ProtoTbMethodKind::ForkThread => TbMethodKind::ForkThread,
+                        ProtoTbMethodKind::ForkEnd => TbMethodKind::ForkEnd,
+                    };
+                    Statement::TbMethodCall {
+                        inst: *inst,
+                        method,
+                        token: *token,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AssignStatement {
+    pub dst: *mut u8,
+    pub dst_width: usize,
+    pub dst_native_bytes: usize,
+    pub dst_use_4state: bool,
+    pub select: Option<(usize, usize)>,
     pub dynamic_select: Option<DynamicBitSelect>,
     pub rhs_select: Option<(usize, usize)>,
     pub expr: Expression,
@@ -2708,14 +3529,20 @@ pub struct IfStatement {
     pub cond: Option<Expression>,
     pub true_side: Vec<Statement>,
     pub false_side: Vec<Statement>,
+    /// Set by `Ir::install_xprop` when X conditions merge both sides.
+    pub xprop: Option<Arc<xprop::Merger>>,
 }
 
 impl IfStatement {
     pub fn eval_step(&self, mask_cache: &mut MaskCache) -> ControlFlow {
-        let cond = self
-            .cond
-            .as_ref()
-            .is_some_and(|x| value_is_true(&x.eval(mask_cache)));
+        let cond = match (&self.cond, &self.xprop) {
+            (None, _) => false,
+            (Some(x), None) => value_is_true(&x.eval(mask_cache)),
+            (Some(x), Some(merger)) => match xprop::truth(&x.eval(mask_cache)) {
+                Some(cond) => cond,
+                None => return merger.merge(&[&self.true_side, &self.false_side], mask_cache),
+            },
+        };
 
         if cond {
             for x in &self.true_side {
@@ -2768,6 +3595,8 @@ fn value_is_true(v: &Value) -> bool {
 pub struct CaseStatement {
     pub arms: Vec<CaseArm>,
     pub default: Vec<Statement>,
+    /// Set by `Ir::install_xprop` when X conditions merge the candidate arms.
+    pub xprop: Option<Arc<xprop::Merger>>,
 }
 
 #[derive(Clone)]
@@ -2778,6 +3607,9 @@ pub struct CaseArm {
 
 impl CaseStatement {
     pub fn eval_step(&self, mask_cache: &mut MaskCache) -> ControlFlow {
+        if let Some(merger) = &self.xprop {
+            return self.eval_step_xprop(merger, mask_cache);
+        }
         for arm in &self.arms {
             if value_is_true(&arm.cond.eval(mask_cache)) {
                 for x in &arm.body {
@@ -2796,6 +3628,34 @@ impl CaseStatement {
         ControlFlow::Continue
     }
 
+    /// Arms whose condition is X are candidates along with the first arm
+    /// (or the default) that is definitely taken; more than one candidate
+    /// goes to the merger.
+    fn eval_step_xprop(&self, merger: &xprop::Merger, mask_cache: &mut MaskCache) -> ControlFlow {
+        let mut candidates: Vec<&[Statement]> = vec![];
+        let mut taken: &[Statement] = &self.default;
+        for arm in &self.arms {
+            match xprop::truth(&arm.cond.eval(mask_cache)) {
+                Some(true) => {
+                    taken = &arm.body;
+                    break;
+                }
+                Some(false) => {}
+                None => candidates.push(&arm.body),
+            }
+        }
+        if !candidates.is_empty() {
+            candidates.push(taken);
+            return merger.merge(&candidates, mask_cache);
+        }
+        for x in taken {
+            if x.eval_step(mask_cache) == ControlFlow::Break {
+                return ControlFlow::Break;
+            }
+        }
+        ControlFlow::Continue
+    }
+
     pub fn gather_variable(&self, inputs: &mut Vec<*const u8>, outputs: &mut Vec<*const u8>) {
         for arm in &self.arms {
             arm.cond.gather_variable(inputs, outputs);
@@ -2850,6 +3710,7 @@ impl ProtoIfStatement {
                 cond,
                 true_side,
                 false_side,
+                xprop: None,
             }
         }
     }
@@ -2914,7 +3775,11 @@ impl ProtoCaseStatement {
                 })
                 .collect();
 
-            CaseStatement { arms, default }
+            CaseStatement {
+                arms,
+                default,
+                xprop: None,
+            }
         }
     }
 }
@@ -2975,6 +3840,14 @@ fn extract_string_value(expr: &air::Expression) -> Option<String> {
     None
 }
 
+/// Name of a `$tb::coverage` coverpoint or cross (a string literal, which
+/// the analyzer checks).
+fn coverage_name(name: &SystemFunctionInput) -> Result<String, SimulatorError> {
+    let value = extract_string_value(&name.0)
+        .ok_or_else(|| SimulatorError::unsupported_description(&name.0.token_range()))?;
+    Ok(strip_string_quotes(&value).to_string())
+}
+
 /// Strips exactly the delimiting quotes of a string-literal token (one
 /// leading, one trailing); quote characters inside the content survive.
 fn strip_string_quotes(s: &str) -> &str {
@@ -3001,18 +3874,24 @@ impl Conv<&air::Statement> for Vec<ProtoStatement> {
                     Vec<ProtoStatement>,
                 )> = Vec::new();
                 let mut any_cond_pending = false;
-                for (arm, cond_expr) in c.arms.iter().zip(c.arm_conditions()) {
+                for (i, (arm, cond_expr)) in c.arms.iter().zip(c.arm_conditions()).enumerate() {
                     let cond: ProtoExpression = Conv::conv(context, &cond_expr)?;
                     let cond_pending = std::mem::take(&mut context.pending_statements);
                     any_cond_pending |= !cond_pending.is_empty();
-                    let mut body: Vec<ProtoStatement> = Vec::new();
+                    let mut body: Vec<ProtoStatement> = context
+                        .cover(&c.token, Some(i as u32), &arm.body)
+                        .into_iter()
+                        .collect();
                     for s in &arm.body {
                         let v: Vec<ProtoStatement> = Conv::conv(context, s)?;
                         body.extend(v);
                     }
                     converted.push((cond, cond_pending, body));
                 }
-                let mut default: Vec<ProtoStatement> = Vec::new();
+                let mut default: Vec<ProtoStatement> = context
+                    .cover(&c.token, Some(c.arms.len() as u32), &c.default)
+                    .into_iter()
+                    .collect();
                 for s in &c.default {
                     let v: Vec<ProtoStatement> = Conv::conv(context, s)?;
                     default.extend(v);
@@ -3106,6 +3985,7 @@ impl Conv<&air::Statement> for Vec<ProtoStatement> {
                             condition,
                             format_str,
                             args: exprs,
+                            token: Box::new(x.comptime.token),
                         },
                     )]
                 }
@@ -3240,13 +4120,247 @@ impl Conv<&air::Statement> for Vec<ProtoStatement> {
                             ret: tb_ret,
                         }
                     }
+                    air::TbMethod::RandomGetDist {
+                        values,
+                        weights,
+                        width,
+                        signed,
+                    } => {
+                        let values = values
+                            .iter()
+                            .map(|x| Conv::conv(context, x))
+                            .collect::<Result<Vec<ProtoExpression>, SimulatorError>>()?;
+                        let weights = weights
+                            .iter()
+                            .map(|x| Conv::conv(context, x))
+                            .collect::<Result<Vec<ProtoExpression>, SimulatorError>>()?;
+                        ProtoTbMethodKind::RandomGetDist {
+                            values,
+                            weights,
+                            width: *width,
+                            signed: *signed,
+                            ret: tb_ret,
+                        }
+                    }
+                    air::TbMethod::RandomGetInside {
+                        values,
+                        width,
+                        signed,
+                    } => {
+                        let values = values
+                            .iter()
+                            .map(|x| Conv::conv(context, x))
+                            .collect::<Result<Vec<ProtoExpression>, SimulatorError>>()?;
+                        ProtoTbMethodKind::RandomGetInside {
+                            values,
+                            width: *width,
+                            signed: *signed,
+                            ret: tb_ret,
+                        }
+                    }
+                    air::TbMethod::RandomGetCyclic {
+                        min,
+                        max,
+                        width,
+                        signed,
+                    } => {
+                        let min: ProtoExpression = Conv::conv(context, min)?;
+                        let max: ProtoExpression = Conv::conv(context, max)?;
+                        ProtoTbMethodKind::RandomGetCyclic {
+                            min,
+                            max,
+                            width: *width,
+                            signed: *signed,
+                            ret: tb_ret,
+                        }
+                    }
+                    air::TbMethod::RandomGetWhere {
+                        min,
+                        max,
+                        cond,
+                        others,
+                        width,
+                        signed,
+                    } => {
+                        let min: ProtoExpression = Conv::conv(context, min)?;
+                        let max: ProtoExpression = Conv::conv(context, max)?;
+                        let cond: ProtoExpression = Conv::conv(context, cond)?;
+                        let others = others.iter().map(|x| x.id).collect();
+                        ProtoTbMethodKind::RandomGetWhere {
+                            min,
+                            max,
+                            cond,
+                            others,
+                            width: *width,
+                            signed: *signed,
+                            ret: tb_ret,
+                        }
+                    }
                     air::TbMethod::RandomGetSeed => {
                         ProtoTbMethodKind::RandomGetSeed { ret: tb_ret }
                     }
+                    air::TbMethod::QueuePush {
+                        value,
+                        width,
+                        signed,
+                    } => ProtoTbMethodKind::QueuePush {
+                        value: Conv::conv(context, value)?,
+                        width: *width,
+                        signed: *signed,
+                    },
+                    air::TbMethod::QueuePop {
+                        peek,
+                        width,
+                        signed,
+                    } => ProtoTbMethodKind::QueuePop {
+                        peek: *peek,
+                        width: *width,
+                        signed: *signed,
+                        ret: tb_ret,
+                    },
+                    air::TbMethod::QueueSize => ProtoTbMethodKind::QueueSize { ret: tb_ret },
+                    air::TbMethod::QueueEmpty => ProtoTbMethodKind::QueueEmpty { ret: tb_ret },
+                    air::TbMethod::ScoreboardExpect {
+                        value,
+                        width,
+                        signed,
+                    } => ProtoTbMethodKind::ScoreboardExpect {
+                        value: Conv::conv(context, value)?,
+                        width: *width,
+                        signed: *signed,
+                    },
+                    air::TbMethod::ScoreboardObserve {
+                        value,
+                        ordered,
+                        width,
+                        signed,
+                    } => ProtoTbMethodKind::ScoreboardObserve {
+                        value: Conv::conv(context, value)?,
+                        ordered: *ordered,
+                        width: *width,
+                        signed: *signed,
+                    },
+                    air::TbMethod::ScoreboardPending => {
+                        ProtoTbMethodKind::ScoreboardPending { ret: tb_ret }
+                    }
+                    air::TbMethod::WaveStart => ProtoTbMethodKind::WaveStart,
+                    air::TbMethod::WaveStop => ProtoTbMethodKind::WaveStop,
+                    air::TbMethod::CheckpointFork { count } => {
+                        let count: ProtoExpression = Conv::conv(context, count)?;
+                        ProtoTbMethodKind::CheckpointFork { count }
+                    }
+                    air::TbMethod::CheckpointBranch => {
+                        ProtoTbMethodKind::CheckpointBranch { ret: tb_ret }
+                    }
+                    air::TbMethod::CheckpointSave { name }
+                    | air::TbMethod::CheckpointLoad { name } => {
+                        let path = extract_string_value(&name.0)
+                            .ok_or_else(|| {
+                                SimulatorError::unsupported_description(&name.0.token_range())
+                            })?
+                            .trim_matches('"')
+                            .to_string();
+                        if matches!(&x.method, air::TbMethod::CheckpointSave { .. }) {
+                            ProtoTbMethodKind::CheckpointSave { path }
+                        } else {
+                            ProtoTbMethodKind::CheckpointLoad { path }
+                        }
+                    }
+                    air::TbMethod::Force { target, value }
+                    | air::TbMethod::Deposit { target, value } => {
+                        let target = ProtoForceTarget {
+                            expr: Conv::conv(context, target)?,
+                            slot: None,
+                            token: target.token_range(),
+                        };
+                        let value: ProtoExpression = Conv::conv(context, value)?;
+                        if matches!(&x.method, air::TbMethod::Force { .. }) {
+                            ProtoTbMethodKind::Force { target, value }
+                        } else {
+                            ProtoTbMethodKind::Deposit { target, value }
+                        }
+                    }
+                    air::TbMethod::Release { target } => ProtoTbMethodKind::Release {
+                        target: ProtoForceTarget {
+                            expr: Conv::conv(context, target)?,
+                            slot: None,
+                            token: target.token_range(),
+                        },
+                    },
+                    air::TbMethod::CoverPoint { name } => ProtoTbMethodKind::CoverPoint {
+                        name: coverage_name(name)?,
+                    },
+                    air::TbMethod::CoverBins { name, values } => {
+                        let values = values
+                            .iter()
+                            .map(|x| Conv::conv(context, x))
+                            .collect::<Result<Vec<ProtoExpression>, SimulatorError>>()?;
+                        ProtoTbMethodKind::CoverBins {
+                            name: coverage_name(name)?,
+                            values,
+                        }
+                    }
+                    air::TbMethod::CoverRange {
+                        name,
+                        min,
+                        max,
+                        count,
+                    } => {
+                        let count = if let Some(count) = count {
+                            Some(Conv::conv(context, count)?)
+                        } else {
+                            None
+                        };
+                        ProtoTbMethodKind::CoverRange {
+                            name: coverage_name(name)?,
+                            min: Conv::conv(context, min)?,
+                            max: Conv::conv(context, max)?,
+                            count,
+                        }
+                    }
+                    air::TbMethod::CoverCross { name, points } => ProtoTbMethodKind::CoverCross {
+                        name: coverage_name(name)?,
+                        points: points.iter().map(coverage_name).collect::<Result<_, _>>()?,
+                    },
+                    air::TbMethod::CoverSample { names, values } => {
+                        let values = values
+                            .iter()
+                            .map(|x| Conv::conv(context, x))
+                            .collect::<Result<Vec<ProtoExpression>, SimulatorError>>()?;
+                        ProtoTbMethodKind::CoverSample {
+                            names: names.iter().map(coverage_name).collect::<Result<_, _>>()?,
+                            values,
+                        }
+                    }
+                    air::TbMethod::ClockWaitUntil {
+                        cond,
+                        within,
+                        period,
+                    } => {
+                        let within = if let Some(expr) = within {
+                            Some(Conv::conv(context, expr)?)
+                        } else {
+                            None
+                        };
+                        let period = if let Some(expr) = period {
+                            Some(Conv::conv(context, expr.as_ref())?)
+                        } else {
+                            None
+                        };
+                        ProtoTbMethodKind::ClockWaitUntil {
+                            cond: Conv::conv(context, cond)?,
+                            within,
+                            period,
+                        }
+                    }
+                    air::TbMethod::ThreadWaitUntil { cond } => ProtoTbMethodKind::ThreadWaitUntil {
+                        cond: Conv::conv(context, cond)?,
+                    },
                 };
                 vec![ProtoStatement::TbMethodCall {
                     inst: x.inst,
                     method,
+                    token: x.token,
                 }]
             }
             air::Statement::For(x) => {
@@ -3330,6 +4444,27 @@ impl Conv<&air::Statement> for Vec<ProtoStatement> {
                     body,
                 })]
             }
+            air::Statement::Fork(x) => {
+                // Lowered to a flat `ForkBegin`, (`ForkThread`, thread...)*,
+                // `ForkEnd` sequence, which the testbench driver regroups.
+                let marker = |method| ProtoStatement::TbMethodCall {
+                    inst: x.token.beg.text,
+                    method,
+                    token: x.token,
+                };
+                let mut ret = vec![marker(ProtoTbMethodKind::ForkBegin {
+                    any: x.join == JoinItem::Any,
+                })];
+                for thread in &x.threads {
+                    ret.push(marker(ProtoTbMethodKind::ForkThread));
+                    for stmt in thread {
+                        let stmts: Vec<ProtoStatement> = Conv::conv(context, stmt)?;
+                        ret.extend(stmts);
+                    }
+                }
+                ret.push(marker(ProtoTbMethodKind::ForkEnd));
+                ret
+            }
             air::Statement::Unsupported(token) => {
                 return Err(SimulatorError::unsupported_description(token));
             }
@@ -3979,6 +5114,13 @@ impl Conv<&air::IfStatement> for ProtoIfStatement {
             false_side.extend(stmts);
         }
 
+        if let Some(x) = context.cover(&src.token, Some(0), &src.true_side) {
+            true_side.insert(0, x);
+        }
+        if let Some(x) = context.cover(&src.token, Some(1), &src.false_side) {
+            false_side.insert(0, x);
+        }
+
         // Branch conversions drain their own per-statement pending, so anything
         // left now belongs after the condition's: keep condition-pending first.
         let mut pending = cond_pending;
@@ -4007,6 +5149,13 @@ impl Conv<&air::IfResetStatement> for ProtoIfStatement {
             false_side.extend(stmts);
         }
 
+        if let Some(x) = context.cover(&src.token, Some(0), &src.true_side) {
+            true_side.insert(0, x);
+        }
+        if let Some(x) = context.cover(&src.token, Some(1), &src.false_side) {
+            false_side.insert(0, x);
+        }
+
         Ok(ProtoIfStatement {
             cond: None,
             true_side,
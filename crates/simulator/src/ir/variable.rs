@@ -1,9 +1,11 @@
 use crate::{HashMap, HashSet};
 use std::fmt;
 use veryl_analyzer::ir as air;
-use veryl_analyzer::ir::{Type, VarId, VarPath};
+use veryl_analyzer::ir::{Type, VarId, VarKind, VarPath};
+use veryl_analyzer::symbol::Affiliation;
 use veryl_analyzer::value::Value;
 use veryl_parser::resource_table::StrId;
+use veryl_parser::token_range::TokenRange;
 
 /// Typed variable offset that encodes buffer identity (FF or Comb).
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -221,6 +223,9 @@ pub unsafe fn write_native_value(ptr: *mut u8, nb: usize, use_4state: bool, val:
 pub struct Variable {
     pub path: VarPath,
     pub r#type: Type,
+    pub kind: VarKind,
+    pub affiliation: Affiliation,
+    pub token: TokenRange,
     pub width: usize,
     pub native_bytes: usize,
     pub current_values: Vec<*mut u8>,
@@ -267,6 +272,10 @@ impl VariableElement {
 pub struct VariableMeta {
     pub path: VarPath,
     pub r#type: Type,
+    pub kind: VarKind,
+    pub affiliation: Affiliation,
+    /// Declaration site, for coverage reports.
+    pub token: TokenRange,
     pub width: usize,
     pub native_bytes: usize,
     pub elements: Vec<VariableElement>,
@@ -451,6 +460,9 @@ pub fn create_variable_meta(
         let meta = VariableMeta {
             path: v.path.clone(),
             r#type: v.r#type.clone(),
+            kind: v.kind,
+            affiliation: v.affiliation,
+            token: v.token,
             width,
             native_bytes: nb,
             elements,
@@ -226,7 +226,7 @@ impl WriteLogBuffer {
     }
 
     /// Append a narrow entry, growing the pool when full.
-    fn push_narrow(&mut self, offset: u32, payload: u64, width_class: u16) {
+    pub(crate) fn push_narrow(&mut self, offset: u32, payload: u64, width_class: u16) {
         if self.narrow_count >= self.narrow_capacity {
             self.grow_narrow_to(self.narrow_capacity as usize + 1);
         }
@@ -246,7 +246,12 @@ impl WriteLogBuffer {
     /// Append a wide entry, growing the pool when full.
     ///
     /// Safety: `payload` must be valid for reads of `native_bytes` (≤ 56) bytes.
-    unsafe fn push_wide(&mut self, offset: u32, payload: *const u8, native_bytes: usize) {
+    pub(crate) unsafe fn push_wide(
+        &mut self,
+        offset: u32,
+        payload: *const u8,
+        native_bytes: usize,
+    ) {
         if self.wide_count >= self.wide_capacity {
             self.grow_wide_to(self.wide_capacity as usize + 1);
         }
@@ -1,16 +1,26 @@
 pub mod assert_buffer;
 pub mod backend;
+pub mod checkpoint;
 pub mod component;
+pub mod coverage;
+pub mod covergroup;
 pub mod file_table;
 pub mod ir;
 pub mod output_buffer;
+pub mod profiler;
+pub mod queue_table;
 pub mod random_table;
+pub mod replay;
+pub mod saif;
+pub mod shell;
 pub mod simulator;
 pub mod simulator_error;
 pub mod testbench;
+pub mod watchdog;
 pub mod wave_dumper;
 pub mod wavedrom;
 pub mod wide_ops;
+pub mod xprop;
 
 pub use ir::Config;
 pub use simulator::Simulator;
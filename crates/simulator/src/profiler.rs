@@ -0,0 +1,861 @@
+//! Per-block simulation profiler behind `veryl test --profile`.
+//!
+//! With `Config::profile` set, the IR build records where every statement
+//! came from ([`Origin`]: the variable it writes and its source token) and
+//! why statements stayed on the interpreter ([`Fallback`]).  Compiled chunks
+//! carry the origins of the statements they replaced.  At run time the
+//! [`Profiler`] times each comb/event statement, i.e. each interpreted
+//! statement, each Cranelift chunk and each whole-comb/whole-event AOT-C
+//! function, and splits that time across the (instance, always block) pairs
+//! its origins resolve to:
+//!
+//! * the instance owns most of the variables the statement writes and
+//!   reads (a port is shared with the connected signal of the parent);
+//! * the block is the `always_ff`/comb declaration whose source span holds
+//!   the statement's token.
+//!
+//! Statements without an origin (system tasks) take the one of the
+//! statement before them in the same list.  The profiler rides on the
+//! simulator's own dispatch through [`crate::simulator::SimProfile`]:
+//! `Ir::settle_comb` and the event evaluation hand it each whole-list call
+//! and statement list they run.  Profiling runs the comb settle serially;
+//! under AOT-C validation only the Cranelift side of the dual-run is timed.
+
+use crate::HashMap;
+use crate::backend::CompiledWhole;
+use crate::coverage;
+use crate::ir::{
+    Event, ModuleVariableMeta, ProtoStatement, ProtoStatementBlock, ProtoStatements, Statement,
+    VarOffset, dispatch_stmt_fast,
+};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use veryl_analyzer::ir as air;
+use veryl_analyzer::value::MaskCache;
+use veryl_parser::resource_table;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::TokenSource;
+
+/// Cranelift is the only backend compiling chunks; AOT-C compiles whole
+/// comb/event lists.
+const CHUNK_BACKEND: &str = "cranelift";
+const WHOLE_BACKEND: &str = "aot_c";
+const INTERPRETER: &str = "interpreter";
+
+/// Where one assignment came from.
+#[derive(Clone, Debug)]
+pub struct Origin {
+    /// Variables written and read.
+    offsets: Vec<VarOffset>,
+    token: Option<TokenRange>,
+}
+
+/// Origins of every assignment in `stmts`, nested compiled blocks included.
+pub(crate) fn origins(stmts: &[ProtoStatement]) -> Vec<Origin> {
+    stmts.iter().flat_map(statement_origins).collect()
+}
+
+/// Origins of one top-level statement.  A dynamic-index assignment has no
+/// token of its own and takes the first one found in the statement.
+fn statement_origins(stmt: &ProtoStatement) -> Vec<Origin> {
+    fn offsets(stmt: &ProtoStatement) -> Vec<VarOffset> {
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+        stmt.gather_variable_offsets(&mut inputs, &mut outputs);
+        outputs.append(&mut inputs);
+        outputs
+    }
+    fn walk(stmt: &ProtoStatement, out: &mut Vec<Origin>) {
+        match stmt {
+            ProtoStatement::Assign(x) => out.push(Origin {
+                offsets: offsets(stmt),
+                token: Some(x.token),
+            }),
+            ProtoStatement::AssignDynamic(_) => out.push(Origin {
+                offsets: offsets(stmt),
+                token: None,
+            }),
+            ProtoStatement::If(x) => {
+                x.true_side
+                    .iter()
+                    .chain(&x.false_side)
+                    .for_each(|x| walk(x, out));
+            }
+            ProtoStatement::Case(x) => {
+                x.arms
+                    .iter()
+                    .flat_map(|arm| &arm.body)
+                    .chain(&x.default)
+                    .for_each(|x| walk(x, out));
+            }
+            ProtoStatement::For(x) => x.body.iter().for_each(|x| walk(x, out)),
+            ProtoStatement::SequentialBlock(body) => body.iter().for_each(|x| walk(x, out)),
+            ProtoStatement::CompiledBlock(x) => x.original_stmts.iter().for_each(|x| walk(x, out)),
+            ProtoStatement::Break
+            | ProtoStatement::SystemFunctionCall(_)
+            | ProtoStatement::TbMethodCall { .. } => {}
+        }
+    }
+    let mut ret = vec![];
+    walk(stmt, &mut ret);
+    if let Some(token) = ret.iter().find_map(|x| x.token) {
+        for x in &mut ret {
+            x.token.get_or_insert(token);
+        }
+    }
+    ret
+}
+
+/// Fill statements without origins from the statement before them (or, at
+/// the head of the list, after them).
+fn inherit_origins(items: &mut [Vec<Origin>]) {
+    let mut last: Option<usize> = None;
+    for i in 0..items.len() {
+        if !items[i].is_empty() {
+            last = Some(i);
+        } else if let Some(j) = last {
+            items[i] = items[j].clone();
+        }
+    }
+    if let Some(first) = items.iter().position(|x| !x.is_empty()) {
+        for i in 0..first {
+            items[i] = items[first].clone();
+        }
+    }
+}
+
+fn location(token: &TokenRange) -> Option<(PathBuf, u32)> {
+    match token.beg.source {
+        TokenSource::File { path, .. } => {
+            Some((resource_table::get_path_value(path)?, token.beg.line))
+        }
+        _ => None,
+    }
+}
+
+fn display_location(location: &Option<(PathBuf, u32)>, base: &Path) -> String {
+    match location {
+        Some((path, line)) => {
+            let path = path.strip_prefix(base).unwrap_or(path);
+            format!("{}:{line}", path.to_string_lossy())
+        }
+        None => "-".to_string(),
+    }
+}
+
+#[derive(Clone, Debug)]
+struct BlockSpan {
+    kind: &'static str,
+    source: TokenSource,
+    beg: u32,
+    end: u32,
+    location: Option<(PathBuf, u32)>,
+}
+
+/// Profiling data collected while the IR is built.
+#[derive(Clone, Debug, Default)]
+pub struct BuildInfo {
+    blocks: Vec<BlockSpan>,
+    fallbacks: Vec<Fallback>,
+}
+
+impl BuildInfo {
+    /// Register an always block; `kind` is `always_ff` or `comb`.
+    pub(crate) fn add_block(&mut self, kind: &'static str, stmts: &[air::Statement]) {
+        fn last_line(stmt: &air::Statement, source: TokenSource, max: &mut u32) {
+            if let Some(token) = coverage::statement_token(stmt)
+                && token.end.source == source
+            {
+                *max = (*max).max(token.end.line);
+            }
+            let nested: Vec<&air::Statement> = match stmt {
+                air::Statement::If(x) => x.true_side.iter().chain(&x.false_side).collect(),
+                air::Statement::IfReset(x) => x.true_side.iter().chain(&x.false_side).collect(),
+                air::Statement::Case(x) => x
+                    .arms
+                    .iter()
+                    .flat_map(|arm| &arm.body)
+                    .chain(&x.default)
+                    .collect(),
+                air::Statement::For(x) => x.body.iter().collect(),
+                air::Statement::Fork(x) => x.threads.iter().flatten().collect(),
+                _ => vec![],
+            };
+            for x in nested {
+                last_line(x, source, max);
+            }
+        }
+
+        let Some(first) = stmts.iter().find_map(coverage::statement_token) else {
+            return;
+        };
+        let source = first.beg.source;
+        let beg = first.beg.line;
+        // Every instance of a module registers the same blocks.
+        if self
+            .blocks
+            .iter()
+            .any(|x| x.source == source && x.beg == beg && x.kind == kind)
+        {
+            return;
+        }
+        let mut end = beg;
+        for x in stmts {
+            last_line(x, source, &mut end);
+        }
+        self.blocks.push(BlockSpan {
+            kind,
+            source,
+            beg,
+            end,
+            location: location(&first),
+        });
+    }
+
+    /// Innermost registered block holding `token`.
+    fn block_of(&self, token: &TokenRange) -> Option<usize> {
+        let line = token.beg.line;
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.source == token.beg.source && x.beg <= line && line <= x.end)
+            .min_by_key(|(_, x)| x.end - x.beg)
+            .map(|(i, _)| i)
+    }
+
+    /// Record a statement left on the interpreter.
+    pub(crate) fn add_fallback(
+        &mut self,
+        token: Option<TokenRange>,
+        statement: String,
+        reason: String,
+    ) {
+        let location = token.as_ref().and_then(location);
+        let block = token
+            .as_ref()
+            .and_then(|x| self.block_of(x))
+            .map_or("-", |i| self.blocks[i].kind)
+            .to_string();
+        if let Some(x) = self
+            .fallbacks
+            .iter_mut()
+            .find(|x| x.location == location && x.statement == statement && x.reason == reason)
+        {
+            x.count += 1;
+            return;
+        }
+        self.fallbacks.push(Fallback {
+            location,
+            block,
+            statement,
+            reason,
+            count: 1,
+        });
+    }
+}
+
+/// Token for each top-level statement of `stmts`, for locating fallbacks;
+/// statements without one take their neighbour's like [`inherit_origins`].
+pub(crate) fn statement_tokens(stmts: &[ProtoStatement]) -> Vec<Option<TokenRange>> {
+    let mut items: Vec<Vec<Origin>> = stmts.iter().map(statement_origins).collect();
+    inherit_origins(&mut items);
+    items
+        .iter()
+        .map(|x| x.iter().find_map(|x| x.token))
+        .collect()
+}
+
+#[derive(Debug)]
+struct Unit {
+    backend: &'static str,
+    chunk: usize,
+    /// (site, weight): how the unit's time is split.
+    shares: Vec<(usize, u64)>,
+    calls: u64,
+    ns: u64,
+}
+
+impl Unit {
+    #[inline]
+    fn add(&mut self, start: Instant) {
+        self.calls += 1;
+        self.ns += start.elapsed().as_nanos() as u64;
+    }
+}
+
+#[derive(Debug)]
+struct List {
+    name: String,
+    /// One per runtime statement.
+    units: Vec<Unit>,
+    /// Whole-list AOT-C function, when one was compiled.
+    whole: Option<Unit>,
+    /// (backend, statements) per run of statements on one backend.
+    chunks: Vec<(&'static str, usize)>,
+}
+
+impl List {
+    fn run(&mut self, stmts: &[Statement], mask_cache: &mut MaskCache) {
+        for (unit, stmt) in self.units.iter_mut().zip(stmts) {
+            let start = Instant::now();
+            dispatch_stmt_fast(stmt, mask_cache);
+            unit.add(start);
+        }
+    }
+}
+
+/// Runtime side of the profiler; built by `ProtoModule::instantiate` and
+/// driven from the simulator dispatch through `SimProfile::blocks`.
+#[derive(Debug)]
+pub struct Profiler {
+    top: String,
+    instances: Vec<String>,
+    blocks: Vec<BlockSpan>,
+    /// (instance, block) pairs; a block of `None` is code outside any
+    /// always block.
+    sites: Vec<(usize, Option<usize>)>,
+    comb: List,
+    events: HashMap<Event, List>,
+    fallbacks: Vec<Fallback>,
+}
+
+struct Resolver<'a> {
+    info: &'a BuildInfo,
+    /// Variable storage → instances.  Ports alias their connections, so
+    /// one offset may belong to several instances.
+    owners: HashMap<VarOffset, Vec<usize>>,
+    sites: HashMap<(usize, Option<usize>), usize>,
+}
+
+impl Resolver<'_> {
+    fn site(&mut self, origin: &Origin) -> usize {
+        // Ties go to the deeper instance: a child writing its output port
+        // is more common than a parent statement driving a child input.
+        let mut votes: Vec<(usize, usize)> = vec![];
+        for owners in origin.offsets.iter().filter_map(|x| self.owners.get(x)) {
+            for &owner in owners {
+                match votes.iter_mut().find(|x| x.1 == owner) {
+                    Some(x) => x.0 += 1,
+                    None => votes.push((1, owner)),
+                }
+            }
+        }
+        let instance = votes.into_iter().max().map_or(0, |x| x.1);
+        let block = origin.token.as_ref().and_then(|x| self.info.block_of(x));
+        let next = self.sites.len();
+        *self.sites.entry((instance, block)).or_insert(next)
+    }
+
+    fn shares(&mut self, origins: &[Origin]) -> Vec<(usize, u64)> {
+        let mut shares: Vec<(usize, u64)> = vec![];
+        for origin in origins {
+            let site = self.site(origin);
+            match shares.iter_mut().find(|x| x.0 == site) {
+                Some(x) => x.1 += 1,
+                None => shares.push((site, 1)),
+            }
+        }
+        if shares.is_empty() {
+            let next = self.sites.len();
+            shares.push((*self.sites.entry((0, None)).or_insert(next), 1));
+        }
+        shares
+    }
+
+    fn list(&mut self, name: String, stmts: &ProtoStatements, whole: bool) -> List {
+        let mut items: Vec<(bool, Vec<Origin>)> = vec![];
+        for block in &stmts.0 {
+            match block {
+                ProtoStatementBlock::Interpreted(x) => {
+                    for x in x {
+                        let compiled = matches!(x, ProtoStatement::CompiledBlock(_));
+                        items.push((compiled, statement_origins(x)));
+                    }
+                }
+                ProtoStatementBlock::Compiled(x) => items.push((true, x.origins.clone())),
+            }
+        }
+        let mut origins: Vec<Vec<Origin>> = items.iter().map(|x| x.1.clone()).collect();
+        inherit_origins(&mut origins);
+
+        let mut units = Vec::with_capacity(items.len());
+        let mut chunks: Vec<(&'static str, usize)> = vec![];
+        for ((compiled, _), origins) in items.iter().zip(&origins) {
+            let backend = if *compiled {
+                CHUNK_BACKEND
+            } else {
+                INTERPRETER
+            };
+            match chunks.last_mut() {
+                Some(x) if x.0 == backend => x.1 += 1,
+                _ => chunks.push((backend, 1)),
+            }
+            units.push(Unit {
+                backend,
+                chunk: chunks.len() - 1,
+                shares: self.shares(origins),
+                calls: 0,
+                ns: 0,
+            });
+        }
+        let whole = whole.then(|| {
+            let all: Vec<Origin> = origins.into_iter().flatten().collect();
+            chunks.push((WHOLE_BACKEND, units.len()));
+            Unit {
+                backend: WHOLE_BACKEND,
+                chunk: chunks.len() - 1,
+                shares: self.shares(&all),
+                calls: 0,
+                ns: 0,
+            }
+        });
+        List {
+            name,
+            units,
+            whole,
+            chunks,
+        }
+    }
+}
+
+impl Profiler {
+    pub(crate) fn new(
+        info: &BuildInfo,
+        meta: &ModuleVariableMeta,
+        comb: &ProtoStatements,
+        events: &HashMap<Event, ProtoStatements>,
+        whole_comb: bool,
+        whole_events: &HashMap<Event, Arc<dyn CompiledWhole>>,
+    ) -> Self {
+        fn walk(
+            meta: &ModuleVariableMeta,
+            prefix: &str,
+            instances: &mut Vec<String>,
+            owners: &mut HashMap<VarOffset, Vec<usize>>,
+        ) {
+            let path = if prefix.is_empty() {
+                meta.name.to_string()
+            } else {
+                format!("{prefix}.{}", meta.name)
+            };
+            let id = instances.len();
+            instances.push(path.clone());
+            let mut vars: Vec<_> = meta.variable_meta.iter().collect();
+            vars.sort_by_key(|(id, _)| **id);
+            for (_, var) in vars {
+                for element in &var.elements {
+                    let mut offsets = vec![element.current];
+                    if element.is_ff() {
+                        offsets.push(VarOffset::Ff(element.next_offset));
+                    }
+                    for offset in offsets {
+                        owners.entry(offset).or_default().push(id);
+                    }
+                }
+            }
+            for child in &meta.children {
+                walk(child, &path, instances, owners);
+            }
+        }
+
+        let mut instances = vec![];
+        let mut owners = HashMap::default();
+        walk(meta, "", &mut instances, &mut owners);
+        for x in owners.values_mut() {
+            x.dedup();
+        }
+
+        let mut resolver = Resolver {
+            info,
+            owners,
+            sites: HashMap::default(),
+        };
+        let comb = resolver.list("comb".to_string(), comb, whole_comb);
+        let events = events
+            .iter()
+            .map(|(event, stmts)| {
+                let name = event_name(event, meta);
+                let list = resolver.list(name, stmts, whole_events.contains_key(event));
+                (event.clone(), list)
+            })
+            .collect();
+
+        let mut sites: Vec<_> = resolver.sites.into_iter().collect();
+        sites.sort_by_key(|x| x.1);
+        Self {
+            top: meta.name.to_string(),
+            instances,
+            blocks: info.blocks.clone(),
+            sites: sites.into_iter().map(|x| x.0).collect(),
+            comb,
+            events,
+            fallbacks: info.fallbacks.clone(),
+        }
+    }
+
+    /// Records one whole-comb AOT-C pass started at `start`.
+    pub(crate) fn whole_comb(&mut self, start: Instant) {
+        if let Some(unit) = self.comb.whole.as_mut() {
+            unit.add(start);
+        }
+    }
+
+    /// One pass of the comb statements, timed.
+    pub(crate) fn run_comb(&mut self, stmts: &[Statement], mask_cache: &mut MaskCache) {
+        self.comb.run(stmts, mask_cache);
+    }
+
+    /// Records a whole-event AOT-C call for `event` started at `start`.
+    pub(crate) fn whole_event(&mut self, event: &Event, start: Instant) {
+        if let Some(unit) = self.events.get_mut(event).and_then(|x| x.whole.as_mut()) {
+            unit.add(start);
+        }
+    }
+
+    /// The statements of `event`, timed.
+    pub(crate) fn run_event(
+        &mut self,
+        event: &Event,
+        stmts: &[Statement],
+        mask_cache: &mut MaskCache,
+    ) {
+        match self.events.get_mut(event) {
+            Some(list) => list.run(stmts, mask_cache),
+            None => {
+                for x in stmts {
+                    dispatch_stmt_fast(x, mask_cache);
+                }
+            }
+        }
+    }
+
+    pub fn report(&self) -> Profile {
+        let mut ret = Profile::default();
+        let mut blocks: HashMap<(usize, &'static str), (u64, u64)> = HashMap::default();
+        let mut events: Vec<&List> = self.events.values().collect();
+        events.sort_by(|a, b| a.name.cmp(&b.name));
+        for list in std::iter::once(&self.comb).chain(events) {
+            let mut chunks = vec![(0u64, 0u64); list.chunks.len()];
+            for unit in list.units.iter().chain(&list.whole) {
+                let chunk = &mut chunks[unit.chunk];
+                chunk.0 = chunk.0.max(unit.calls);
+                chunk.1 += unit.ns;
+                let total: u64 = unit.shares.iter().map(|x| x.1).sum();
+                for &(site, weight) in &unit.shares {
+                    let block = blocks.entry((site, unit.backend)).or_default();
+                    block.0 = block.0.max(unit.calls);
+                    block.1 += (unit.ns as u128 * weight as u128 / total as u128) as u64;
+                }
+            }
+            for (index, ((backend, stmts), (calls, ns))) in
+                list.chunks.iter().zip(chunks).enumerate()
+            {
+                if calls == 0 {
+                    continue;
+                }
+                ret.chunks.push(ChunkProfile {
+                    top: self.top.clone(),
+                    list: list.name.clone(),
+                    index,
+                    backend: backend.to_string(),
+                    stmts: *stmts,
+                    calls,
+                    ns,
+                });
+            }
+        }
+        for ((site, backend), (evals, ns)) in blocks {
+            if evals == 0 {
+                continue;
+            }
+            let (instance, block) = self.sites[site];
+            let (kind, location) = match block {
+                Some(x) => (self.blocks[x].kind, self.blocks[x].location.clone()),
+                None => ("(other)", None),
+            };
+            ret.blocks.push(BlockProfile {
+                instance: self.instances[instance].clone(),
+                kind: kind.to_string(),
+                location,
+                backend: backend.to_string(),
+                evals,
+                ns,
+            });
+        }
+        ret.fallbacks = self.fallbacks.clone();
+        ret.sort();
+        ret
+    }
+}
+
+fn event_name(event: &Event, meta: &ModuleVariableMeta) -> String {
+    let var = |id| {
+        meta.variable_meta
+            .get(id)
+            .map(|x| x.path.to_string())
+            .unwrap_or_else(|| "(internal)".to_string())
+    };
+    match event {
+        Event::Clock(id) => format!("clock {}", var(id)),
+        Event::Reset(id) => format!("reset {}", var(id)),
+        Event::Initial => "initial".to_string(),
+        Event::Final => "final".to_string(),
+    }
+}
+
+/// Time spent in one always block of one instance on one backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockProfile {
+    /// Hierarchical instance name, from the test top down.
+    pub instance: String,
+    /// `always_ff`, `comb`, or `(other)` for code outside any always block
+    /// (port connections, system tasks without an owner).
+    pub kind: String,
+    /// Source line of the block's first statement.
+    pub location: Option<(PathBuf, u32)>,
+    pub backend: String,
+    /// Times any of the block's code ran.
+    pub evals: u64,
+    pub ns: u64,
+}
+
+impl BlockProfile {
+    pub fn label(&self, base: &Path) -> String {
+        match self.location {
+            Some(_) => format!("{} @ {}", self.kind, display_location(&self.location, base)),
+            None => self.kind.clone(),
+        }
+    }
+}
+
+/// Time spent in one run of statements dispatched by one backend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkProfile {
+    pub top: String,
+    /// `comb`, or the event: `clock clk`, `reset rst`, `initial`, `final`.
+    pub list: String,
+    pub index: usize,
+    pub backend: String,
+    pub stmts: usize,
+    pub calls: u64,
+    pub ns: u64,
+}
+
+/// A statement left on the interpreter while JIT backends were enabled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fallback {
+    pub location: Option<(PathBuf, u32)>,
+    /// Kind of the enclosing always block, or `-`.
+    pub block: String,
+    pub statement: String,
+    pub reason: String,
+    /// Instances sharing the statement.
+    pub count: u64,
+}
+
+/// Profile of one or more tests.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub blocks: Vec<BlockProfile>,
+    pub chunks: Vec<ChunkProfile>,
+    pub fallbacks: Vec<Fallback>,
+}
+
+impl Profile {
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty() && self.chunks.is_empty() && self.fallbacks.is_empty()
+    }
+
+    pub fn merge(&mut self, other: Profile) {
+        for x in other.blocks {
+            match self.blocks.iter_mut().find(|y| {
+                y.instance == x.instance
+                    && y.kind == x.kind
+                    && y.location == x.location
+                    && y.backend == x.backend
+            }) {
+                Some(y) => {
+                    y.evals += x.evals;
+                    y.ns += x.ns;
+                }
+                None => self.blocks.push(x),
+            }
+        }
+        for x in other.chunks {
+            match self.chunks.iter_mut().find(|y| {
+                y.top == x.top && y.list == x.list && y.index == x.index && y.backend == x.backend
+            }) {
+                Some(y) => {
+                    y.calls += x.calls;
+                    y.ns += x.ns;
+                }
+                None => self.chunks.push(x),
+            }
+        }
+        // Every test rebuilds its IR and reports the same fallbacks again.
+        for x in other.fallbacks {
+            match self.fallbacks.iter_mut().find(|y| {
+                y.location == x.location && y.statement == x.statement && y.reason == x.reason
+            }) {
+                Some(y) => y.count = y.count.max(x.count),
+                None => self.fallbacks.push(x),
+            }
+        }
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        self.blocks.sort_by(|a, b| {
+            b.ns.cmp(&a.ns)
+                .then_with(|| a.instance.cmp(&b.instance))
+                .then_with(|| a.location.cmp(&b.location))
+        });
+        self.chunks
+            .sort_by(|a, b| b.ns.cmp(&a.ns).then_with(|| a.list.cmp(&b.list)));
+        self.fallbacks.sort_by(|a, b| {
+            a.location
+                .cmp(&b.location)
+                .then_with(|| a.reason.cmp(&b.reason))
+        });
+    }
+
+    /// Self time per instance, heaviest first.
+    pub fn instances(&self) -> Vec<(String, u64)> {
+        let mut ret: Vec<(String, u64)> = vec![];
+        for x in &self.blocks {
+            match ret.iter_mut().find(|y| y.0 == x.instance) {
+                Some(y) => y.1 += x.ns,
+                None => ret.push((x.instance.clone(), x.ns)),
+            }
+        }
+        ret.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ret
+    }
+
+    /// Sorted tables of instances, blocks, chunks and fallbacks; source
+    /// paths are shown relative to `base`.
+    pub fn table(&self, base: &Path) -> String {
+        use std::fmt::Write;
+
+        let total: u64 = self.blocks.iter().map(|x| x.ns).sum();
+        let percent = |ns: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                ns as f64 * 100.0 / total as f64
+            }
+        };
+        let mut ret = String::new();
+
+        let _ = writeln!(ret, "instances:");
+        let _ = writeln!(ret, "  {:<40} {:>12} {:>7}", "instance", "self time", "%");
+        for (instance, ns) in self.instances() {
+            let _ = writeln!(
+                ret,
+                "  {instance:<40} {:>12} {:>6.1}%",
+                duration(ns),
+                percent(ns)
+            );
+        }
+
+        let _ = writeln!(ret, "\nblocks:");
+        let _ = writeln!(
+            ret,
+            "  {:<40} {:<40} {:<11} {:>10} {:>12} {:>7}",
+            "block", "instance", "backend", "evals", "time", "%"
+        );
+        for x in &self.blocks {
+            let _ = writeln!(
+                ret,
+                "  {:<40} {:<40} {:<11} {:>10} {:>12} {:>6.1}%",
+                x.label(base),
+                x.instance,
+                x.backend,
+                x.evals,
+                duration(x.ns),
+                percent(x.ns)
+            );
+        }
+
+        let _ = writeln!(ret, "\nchunks:");
+        let _ = writeln!(
+            ret,
+            "  {:<40} {:<11} {:>7} {:>10} {:>12}",
+            "chunk", "backend", "stmts", "calls", "time"
+        );
+        for x in &self.chunks {
+            let _ = writeln!(
+                ret,
+                "  {:<40} {:<11} {:>7} {:>10} {:>12}",
+                format!("{} {} #{}", x.top, x.list, x.index),
+                x.backend,
+                x.stmts,
+                x.calls,
+                duration(x.ns)
+            );
+        }
+
+        if !self.fallbacks.is_empty() {
+            let _ = writeln!(ret, "\ninterpreter fallbacks:");
+            for x in &self.fallbacks {
+                let count = if x.count > 1 {
+                    format!(" (x{})", x.count)
+                } else {
+                    String::new()
+                };
+                let _ = writeln!(
+                    ret,
+                    "  {} [{}] {}: {}{count}",
+                    display_location(&x.location, base),
+                    x.block,
+                    x.statement,
+                    x.reason
+                );
+            }
+        }
+        ret
+    }
+
+    /// Flamegraph folded stacks: instance path, block, backend, then the
+    /// time in nanoseconds.
+    pub fn folded(&self, base: &Path) -> String {
+        let mut lines: Vec<String> = self
+            .blocks
+            .iter()
+            .filter(|x| x.ns > 0)
+            .map(|x| {
+                let stack = x.instance.replace('.', ";");
+                let block = x.label(base).replace(';', ",");
+                format!("{stack};{block};{} {}", x.backend, x.ns)
+            })
+            .collect();
+        lines.sort();
+        lines.iter().map(|x| format!("{x}\n")).collect()
+    }
+}
+
+fn duration(ns: u64) -> String {
+    if ns >= 1_000_000_000 {
+        format!("{:.3} s", ns as f64 / 1e9)
+    } else if ns >= 1_000_000 {
+        format!("{:.3} ms", ns as f64 / 1e6)
+    } else {
+        format!("{:.3} us", ns as f64 / 1e3)
+    }
+}
+
+thread_local! {
+    static PROFILE: RefCell<Profile> = RefCell::new(Profile::default());
+}
+
+/// Hands a finished test's profile to the runner on this thread.
+pub fn record(profile: Profile) {
+    PROFILE.with(|x| x.borrow_mut().merge(profile));
+}
+
+/// Profiles recorded on this thread since the last call.
+pub fn take() -> Profile {
+    PROFILE.with(|x| std::mem::take(&mut *x.borrow_mut()))
+}
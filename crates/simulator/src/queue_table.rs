@@ -0,0 +1,242 @@
+//! Thread-local tables backing the `$tb::queue` and `$tb::scoreboard`
+//! testbench handles.
+//!
+//! Like `random_table`, a thread-local keeps the state reachable from the
+//! testbench driver (which has no `Simulator` handle) and isolates parallel
+//! tests. Both are keyed by the declaring variable's name (`StrId`) and hold
+//! values already fitted to the element type `T`, so comparisons are exact
+//! (4-state, like `===`).
+//!
+//! * A queue is a FIFO: `push` appends, `pop`/`peek` read the oldest item.
+//! * A scoreboard holds the items expected but not yet observed. `observe`
+//!   must match the oldest of them (in-order checking), `observe_any` any of
+//!   them (out-of-order checking). Items still pending when the test ends
+//!   are reported by [`unmatched`].
+
+use crate::ir::Value;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use veryl_parser::resource_table::{self, StrId};
+
+/// Unmatched items listed per scoreboard before the report is elided.
+const REPORT_LIMIT: usize = 8;
+
+#[derive(Default)]
+struct QueueTable {
+    queues: HashMap<StrId, VecDeque<Value>>,
+    scoreboards: HashMap<StrId, VecDeque<Value>>,
+}
+
+thread_local! {
+    static TABLE: RefCell<QueueTable> = RefCell::new(QueueTable::default());
+}
+
+/// Clear all queues and scoreboards. Call before a test.
+pub fn reset() {
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        t.queues.clear();
+        t.scoreboards.clear();
+    });
+}
+
+/// Zero-extend (or sign-extend, for a signed `T`) / truncate `value` to the
+/// element type, so items compare independently of the argument's width.
+fn fit(value: &Value, width: u32, signed: bool) -> Value {
+    let mut ret = value.expand(width as usize, signed).into_owned();
+    ret.trunc(width as usize);
+    ret.set_signed(signed);
+    ret
+}
+
+fn format_value(value: &Value) -> String {
+    format!("{}'h{}", value.width(), value.format_hex())
+}
+
+fn name(key: StrId) -> String {
+    resource_table::get_str_value(key).unwrap_or_default()
+}
+
+pub fn push(key: StrId, value: &Value, width: u32, signed: bool) {
+    let value = fit(value, width, signed);
+    TABLE.with(|t| {
+        t.borrow_mut()
+            .queues
+            .entry(key)
+            .or_default()
+            .push_back(value)
+    });
+}
+
+/// Remove (`peek = false`) or read the oldest item of queue `key`. Fails on
+/// an empty queue.
+pub fn pop(key: StrId, peek: bool) -> Result<Value, String> {
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        let queue = t.queues.entry(key).or_default();
+        let ret = if peek {
+            queue.front().cloned()
+        } else {
+            queue.pop_front()
+        };
+        ret.ok_or_else(|| {
+            let method = if peek { "peek" } else { "pop" };
+            format!("queue `{}`: `{method}` on an empty queue", name(key))
+        })
+    })
+}
+
+pub fn size(key: StrId) -> usize {
+    TABLE.with(|t| t.borrow().queues.get(&key).map(|x| x.len()).unwrap_or(0))
+}
+
+pub fn expect(key: StrId, value: &Value, width: u32, signed: bool) {
+    let value = fit(value, width, signed);
+    TABLE.with(|t| {
+        t.borrow_mut()
+            .scoreboards
+            .entry(key)
+            .or_default()
+            .push_back(value)
+    });
+}
+
+/// Match `value` against the items expected by scoreboard `key`: the oldest
+/// one when `ordered`, otherwise the oldest equal one. A matched item is
+/// consumed; a mismatch fails and leaves the expected items untouched.
+pub fn observe(
+    key: StrId,
+    value: &Value,
+    ordered: bool,
+    width: u32,
+    signed: bool,
+) -> Result<(), String> {
+    let value = fit(value, width, signed);
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        let expected = t.scoreboards.entry(key).or_default();
+        let pos = if ordered {
+            match expected.front() {
+                Some(x) if *x == value => Some(0),
+                Some(x) => {
+                    return Err(format!(
+                        "scoreboard `{}`: observed {} but expected {}",
+                        name(key),
+                        format_value(&value),
+                        format_value(x)
+                    ));
+                }
+                None => None,
+            }
+        } else {
+            expected.iter().position(|x| *x == value)
+        };
+        match pos {
+            Some(pos) => {
+                expected.remove(pos);
+                Ok(())
+            }
+            None if expected.is_empty() => Err(format!(
+                "scoreboard `{}`: observed {} with no item expected",
+                name(key),
+                format_value(&value)
+            )),
+            None => Err(format!(
+                "scoreboard `{}`: observed {} matches none of the {} expected item(s)",
+                name(key),
+                format_value(&value),
+                expected.len()
+            )),
+        }
+    })
+}
+
+/// Number of items scoreboard `key` still expects.
+pub fn pending(key: StrId) -> usize {
+    TABLE.with(|t| {
+        t.borrow()
+            .scoreboards
+            .get(&key)
+            .map(|x| x.len())
+            .unwrap_or(0)
+    })
+}
+
+/// One failure line per scoreboard with expected items never observed, in
+/// handle-name order. Call when the test ends.
+pub fn unmatched() -> Vec<String> {
+    TABLE.with(|t| {
+        let t = t.borrow();
+        let mut ret: Vec<_> = t
+            .scoreboards
+            .iter()
+            .filter(|(_, x)| !x.is_empty())
+            .map(|(key, expected)| {
+                let mut items: Vec<_> = expected
+                    .iter()
+                    .take(REPORT_LIMIT)
+                    .map(format_value)
+                    .collect();
+                if expected.len() > REPORT_LIMIT {
+                    items.push(format!("... {} more", expected.len() - REPORT_LIMIT));
+                }
+                format!(
+                    "scoreboard `{}`: {} expected item(s) never observed: {}",
+                    name(*key),
+                    expected.len(),
+                    items.join(", ")
+                )
+            })
+            .collect();
+        ret.sort();
+        ret
+    })
+}
+
+/// Contents of every queue and scoreboard, for simulation checkpoints.
+/// Handles are keyed by name since `StrId`s are not stable across runs.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    queues: Vec<(String, Vec<Value>)>,
+    scoreboards: Vec<(String, Vec<Value>)>,
+}
+
+pub fn snapshot() -> QueueSnapshot {
+    let export = |table: &HashMap<StrId, VecDeque<Value>>| {
+        let mut ret: Vec<_> = table
+            .iter()
+            .map(|(key, x)| (name(*key), x.iter().cloned().collect()))
+            .collect();
+        ret.sort_by(|a: &(String, Vec<Value>), b| a.0.cmp(&b.0));
+        ret
+    };
+    TABLE.with(|t| {
+        let t = t.borrow();
+        QueueSnapshot {
+            queues: export(&t.queues),
+            scoreboards: export(&t.scoreboards),
+        }
+    })
+}
+
+/// Replace every queue and scoreboard with the contents captured by
+/// [`snapshot`].
+pub fn restore(snapshot: &QueueSnapshot) {
+    let import = |table: &[(String, Vec<Value>)]| {
+        table
+            .iter()
+            .map(|(name, x)| {
+                (
+                    resource_table::insert_str(name),
+                    x.iter().cloned().collect(),
+                )
+            })
+            .collect()
+    };
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        t.queues = import(&snapshot.queues);
+        t.scoreboards = import(&snapshot.scoreboards);
+    });
+}
@@ -7,10 +7,19 @@
 //! / `[test].seed`, i.e. `Ir.seed`) mixed with the handle name, so a run is
 //! reproducible for a given seed and distinct handles get distinct streams.
 //! `seed()` overrides the seed explicitly; `get_seed()` reads it back.
+//!
+//! The constrained methods draw from the same per-handle stream:
+//! `get_dist` (weighted values), `get_inside` (a value set), `get_cyclic`
+//! (`randc`: a shuffled permutation of the range, kept per handle) and
+//! `get_where` (rejection sampling against a condition, driven by the
+//! testbench since the condition reads simulator state; extra variables
+//! passed to it are redrawn with the destination, so a relation between
+//! them is sampled jointly, within `WHERE_ATTEMPTS` tries).
 
 use crate::ir::Value;
 use rand::{RngExt, SeedableRng};
 use rand_pcg::Pcg64;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use veryl_parser::resource_table::{self, StrId};
@@ -23,8 +32,25 @@ struct RandomTable {
     /// A non-cryptographic PCG generator is used (fast and reproducible; the
     /// testbench does not need a crypto-strength RNG).
     rngs: HashMap<StrId, (Pcg64, u64)>,
+    /// `get_cyclic` state per handle: the range it was drawn for and the
+    /// values still pending in the current permutation.
+    cycles: HashMap<StrId, Cycle>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Cycle {
+    min: u64,
+    max: u64,
+    pending: Vec<u64>,
 }
 
+/// Largest range `get_cyclic` accepts, matching the 16-bit cap tools
+/// commonly place on `randc`.
+const CYCLIC_LIMIT: u64 = 1 << 16;
+
+/// Candidates `get_where` draws before giving up on its condition.
+pub const WHERE_ATTEMPTS: u32 = 10_000;
+
 thread_local! {
     static TABLE: RefCell<RandomTable> = RefCell::new(RandomTable::default());
 }
@@ -51,6 +77,7 @@ pub fn reset(base_seed: u64) {
         let mut t = t.borrow_mut();
         t.base_seed = base_seed;
         t.rngs.clear();
+        t.cycles.clear();
     });
 }
 
@@ -66,12 +93,69 @@ fn with_rng<R>(key: StrId, f: impl FnOnce(&mut Pcg64) -> R) -> R {
     })
 }
 
+/// Generator state of every handle, for simulation checkpoints. Handles are
+/// keyed by name since `StrId`s are not stable across runs.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomSnapshot {
+    base_seed: u64,
+    rngs: Vec<(String, Pcg64, u64)>,
+    #[serde(default)]
+    cycles: Vec<(String, Cycle)>,
+}
+
+pub fn snapshot() -> RandomSnapshot {
+    TABLE.with(|t| {
+        let t = t.borrow();
+        let mut rngs: Vec<_> = t
+            .rngs
+            .iter()
+            .map(|(key, (rng, seed))| {
+                let name = resource_table::get_str_value(*key).unwrap_or_default();
+                (name, rng.clone(), *seed)
+            })
+            .collect();
+        rngs.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut cycles: Vec<_> = t
+            .cycles
+            .iter()
+            .map(|(key, cycle)| {
+                let name = resource_table::get_str_value(*key).unwrap_or_default();
+                (name, cycle.clone())
+            })
+            .collect();
+        cycles.sort_by(|a, b| a.0.cmp(&b.0));
+        RandomSnapshot {
+            base_seed: t.base_seed,
+            rngs,
+            cycles,
+        }
+    })
+}
+
+/// Replace every generator with the state captured by [`snapshot`].
+pub fn restore(snapshot: &RandomSnapshot) {
+    TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        t.base_seed = snapshot.base_seed;
+        t.rngs = snapshot
+            .rngs
+            .iter()
+            .map(|(name, rng, seed)| (resource_table::insert_str(name), (rng.clone(), *seed)))
+            .collect();
+        t.cycles = snapshot
+            .cycles
+            .iter()
+            .map(|(name, cycle)| (resource_table::insert_str(name), cycle.clone()))
+            .collect();
+    });
+}
+
 /// Set handle `key`'s seed explicitly and reset its stream.
 pub fn seed_handle(key: StrId, seed: u64) {
     TABLE.with(|t| {
-        t.borrow_mut()
-            .rngs
-            .insert(key, (Pcg64::seed_from_u64(seed), seed));
+        let mut t = t.borrow_mut();
+        t.rngs.insert(key, (Pcg64::seed_from_u64(seed), seed));
+        t.cycles.remove(&key);
     });
 }
 
@@ -127,6 +211,93 @@ pub fn get_range(key: StrId, min: u64, max: u64, width: u32, signed: bool) -> Va
     Value::new(raw, width as usize, signed)
 }
 
+/// Pick one of `values` with probability proportional to its weight.
+/// Fails when every weight is zero.
+pub fn get_dist(
+    key: StrId,
+    values: &[u64],
+    weights: &[u64],
+    width: u32,
+    signed: bool,
+) -> Result<Value, String> {
+    let total: u128 = weights.iter().map(|x| *x as u128).sum();
+    if total == 0 {
+        return Err("`get_dist` weights sum to zero".to_string());
+    }
+    let mut pick = with_rng(key, |rng| rng.random_range(0..total));
+    let mut raw = 0;
+    for (value, weight) in values.iter().zip(weights) {
+        if pick < *weight as u128 {
+            raw = *value;
+            break;
+        }
+        pick -= *weight as u128;
+    }
+    Ok(Value::new(raw & mask(width), width as usize, signed))
+}
+
+/// Pick one of `values` uniformly.
+pub fn get_inside(key: StrId, values: &[u64], width: u32, signed: bool) -> Value {
+    let i = with_rng(key, |rng| rng.random_range(0..values.len()));
+    Value::new(values[i] & mask(width), width as usize, signed)
+}
+
+/// Draw the next value of handle `key`'s permutation of `[min, max]`,
+/// starting a freshly shuffled one when the current one is exhausted or the
+/// range changed. Ranges above [`CYCLIC_LIMIT`] values are rejected.
+pub fn get_cyclic(
+    key: StrId,
+    min: u64,
+    max: u64,
+    width: u32,
+    signed: bool,
+) -> Result<Value, String> {
+    let m = mask(width);
+    let (lo, hi) = if signed {
+        let a = sign_extend(min & m, width) as i128;
+        let b = sign_extend(max & m, width) as i128;
+        (a.min(b), a.max(b))
+    } else {
+        let a = (min & m) as i128;
+        let b = (max & m) as i128;
+        (a.min(b), a.max(b))
+    };
+    if hi - lo >= CYCLIC_LIMIT as i128 {
+        return Err(format!(
+            "`get_cyclic` range has {} values; at most {CYCLIC_LIMIT} are supported",
+            hi - lo + 1
+        ));
+    }
+    let (min, max) = ((lo as u64) & m, (hi as u64) & m);
+
+    let pending = TABLE.with(|t| {
+        let mut t = t.borrow_mut();
+        match t.cycles.remove(&key) {
+            Some(x) if x.min == min && x.max == max && !x.pending.is_empty() => x.pending,
+            _ => Vec::new(),
+        }
+    });
+    let mut pending = if pending.is_empty() {
+        let mut all: Vec<u64> = (lo..=hi).map(|x| (x as u64) & m).collect();
+        with_rng(key, |rng| {
+            for i in (1..all.len()).rev() {
+                let j = rng.random_range(0..=i);
+                all.swap(i, j);
+            }
+        });
+        all
+    } else {
+        pending
+    };
+    let raw = pending.pop().unwrap_or_default();
+    TABLE.with(|t| {
+        t.borrow_mut()
+            .cycles
+            .insert(key, Cycle { min, max, pending });
+    });
+    Ok(Value::new(raw, width as usize, signed))
+}
+
 /// Interpret the low `width` bits of `raw` as a two's-complement integer.
 fn sign_extend(raw: u64, width: u32) -> i64 {
     if width == 0 || width >= 64 {
@@ -0,0 +1,504 @@
+//! Stimulus replay: drives a module's top-level inputs from a VCD/FST
+//! capture (a commercial simulator's dump, an FPGA ILA export) and
+//! optionally checks its outputs against the recorded values.
+//!
+//! Ports match trace signals by name (see [`Trace::find`]). Every active
+//! edge of a matched clock port becomes one `step`; a clock port with no
+//! same-named signal is inferred from the trace. Inputs changing at the
+//! same time as an edge are applied after it, the way a flop samples the
+//! value before the edge, and outputs are compared against the recording
+//! just before each edge.
+
+use crate::ir::{Event, Value};
+use crate::simulator::Simulator;
+use crate::simulator_error::SimulatorError;
+use crate::testbench::TestResult;
+use crate::wavedrom::strip_port_prefix;
+use num_bigint::BigUint;
+use std::io::BufRead;
+use std::path::Path;
+use veryl_analyzer::ir::{TypeKind, VarKind};
+use veryl_metadata::{ClockType, ResetType};
+
+/// Value changes of every signal in a trace file.
+pub struct Trace {
+    pub signals: Vec<TraceSignal>,
+}
+
+pub struct TraceSignal {
+    /// Enclosing scopes, outermost first.
+    pub scope: Vec<String>,
+    pub name: String,
+    pub width: usize,
+    /// `(time, value)` in trace time units, in time order.
+    pub changes: Vec<(u64, Value)>,
+}
+
+impl TraceSignal {
+    fn path(&self) -> String {
+        let mut path = self.scope.join(".");
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(&self.name);
+        path
+    }
+
+    /// A 1-bit signal whose rising edges come at a fixed period.
+    fn is_periodic(&self) -> bool {
+        if self.width != 1 {
+            return false;
+        }
+        let rises: Vec<u64> = self
+            .changes
+            .windows(2)
+            .filter(|x| bit0(&x[0].1) == Some(false) && bit0(&x[1].1) == Some(true))
+            .map(|x| x[1].0)
+            .collect();
+        rises.len() >= 2 && rises.windows(2).all(|x| x[1] - x[0] == rises[1] - rises[0])
+    }
+}
+
+impl Trace {
+    /// Reads `path` as FST when it has the `.fst` extension, as VCD
+    /// otherwise.
+    pub fn load(path: &Path) -> Result<Self, SimulatorError> {
+        let io_error = |e: std::io::Error| SimulatorError::IoError {
+            message: format!("failed to read trace {}: {e}", path.display()),
+        };
+        let file = std::fs::File::open(path).map_err(io_error)?;
+        let reader = std::io::BufReader::new(file);
+        let trace = if path.extension().is_some_and(|x| x == "fst") {
+            Self::from_fst(reader)
+        } else {
+            Self::from_vcd(reader)
+        };
+        trace.map_err(|e| SimulatorError::Replay {
+            message: format!("{}: {e}", path.display()),
+        })
+    }
+
+    fn from_vcd(reader: impl BufRead) -> Result<Self, String> {
+        let mut signals: Vec<TraceSignal> = vec![];
+        // Aliased variables share an id code.
+        let mut codes: std::collections::HashMap<vcd::IdCode, Vec<usize>> = Default::default();
+        let mut scope = vec![];
+        let mut time = 0;
+        for command in vcd::Parser::new(reader) {
+            match command.map_err(|e| e.to_string())? {
+                vcd::Command::ScopeDef(_, name) => scope.push(name),
+                vcd::Command::Upscope => {
+                    scope.pop();
+                }
+                vcd::Command::VarDef(_, size, code, reference, _) => {
+                    codes.entry(code).or_default().push(signals.len());
+                    signals.push(TraceSignal {
+                        scope: scope.clone(),
+                        name: reference,
+                        width: size as usize,
+                        changes: vec![],
+                    });
+                }
+                vcd::Command::Timestamp(x) => time = x,
+                vcd::Command::ChangeScalar(code, value) => {
+                    push_change(&mut signals, codes.get(&code), time, &[vcd_bit(value)]);
+                }
+                vcd::Command::ChangeVector(code, value) => {
+                    let bits: Vec<u8> = value.iter().map(vcd_bit).collect();
+                    push_change(&mut signals, codes.get(&code), time, &bits);
+                }
+                _ => {}
+            }
+        }
+        Ok(Trace { signals })
+    }
+
+    fn from_fst(reader: std::io::BufReader<std::fs::File>) -> Result<Self, String> {
+        let mut reader = fst_reader::FstReader::open(reader).map_err(|e| e.to_string())?;
+        let mut signals: Vec<TraceSignal> = vec![];
+        let mut handles: std::collections::HashMap<usize, Vec<usize>> = Default::default();
+        let mut scope = vec![];
+        reader
+            .read_hierarchy(|entry| match entry {
+                fst_reader::FstHierarchyEntry::Scope { name, .. } => scope.push(name),
+                fst_reader::FstHierarchyEntry::UpScope => {
+                    scope.pop();
+                }
+                fst_reader::FstHierarchyEntry::Var {
+                    name,
+                    length,
+                    handle,
+                    ..
+                } => {
+                    handles
+                        .entry(handle.get_index())
+                        .or_default()
+                        .push(signals.len());
+                    // Writers append the bit range: `data [7:0]`.
+                    let name = name.split(' ').next().unwrap_or_default().to_string();
+                    signals.push(TraceSignal {
+                        scope: scope.clone(),
+                        name,
+                        width: length as usize,
+                        changes: vec![],
+                    });
+                }
+                _ => {}
+            })
+            .map_err(|e| e.to_string())?;
+        reader
+            .read_signals(&fst_reader::FstFilter::all(), |time, handle, value| {
+                if let fst_reader::FstSignalValue::String(bits) = value {
+                    push_change(&mut signals, handles.get(&handle.get_index()), time, bits);
+                }
+                Ok::<(), ()>(())
+            })
+            .map_err(|e| e.to_string())?;
+        Ok(Trace { signals })
+    }
+
+    /// The signal named `name` — or, failing that, named like `name` without
+    /// its port prefix (`i_data` / `data`) — in the shallowest scope, or in
+    /// `scope` (dot-separated) when given.
+    pub fn find(&self, name: &str, scope: Option<&str>) -> Option<&TraceSignal> {
+        let candidates = || {
+            self.signals
+                .iter()
+                .filter(|x| scope.is_none_or(|s| x.scope.join(".") == s))
+        };
+        let stripped = strip_port_prefix(name);
+        candidates()
+            .filter(|x| x.name == name)
+            .min_by_key(|x| x.scope.len())
+            .or_else(|| {
+                candidates()
+                    .filter(|x| strip_port_prefix(&x.name) == stripped)
+                    .min_by_key(|x| x.scope.len())
+            })
+    }
+}
+
+fn vcd_bit(value: vcd::Value) -> u8 {
+    match value {
+        vcd::Value::V0 => b'0',
+        vcd::Value::V1 => b'1',
+        vcd::Value::X => b'x',
+        vcd::Value::Z => b'z',
+    }
+}
+
+fn push_change(signals: &mut [TraceSignal], ids: Option<&Vec<usize>>, time: u64, bits: &[u8]) {
+    for &id in ids.into_iter().flatten() {
+        let signal = &mut signals[id];
+        let value = bits_to_value(bits, signal.width);
+        signal.changes.push((time, value));
+    }
+}
+
+/// Converts MSB-first `0`/`1`/`x`/`z` characters to a `width`-bit value.
+/// A short vector is extended the VCD way: with `0` after a `0`/`1`, and
+/// with its own leading bit after an `x`/`z`.
+fn bits_to_value(bits: &[u8], width: usize) -> Value {
+    let fill = match bits.first() {
+        Some(b'x' | b'X') => b'x',
+        Some(b'z' | b'Z') => b'z',
+        _ => b'0',
+    };
+    let mut payload = BigUint::default();
+    let mut mask = BigUint::default();
+    for i in 0..width as u64 {
+        let bit = bits
+            .len()
+            .checked_sub(1 + i as usize)
+            .map_or(fill, |x| bits[x]);
+        match bit {
+            b'0' | b'l' | b'L' => {}
+            b'1' | b'h' | b'H' => payload.set_bit(i, true),
+            b'z' | b'Z' => {
+                payload.set_bit(i, true);
+                mask.set_bit(i, true);
+            }
+            _ => mask.set_bit(i, true),
+        }
+    }
+    // `from_le_bytes` reads whole 32-bit words.
+    let bytes = |x: BigUint| -> Vec<u8> {
+        x.to_u32_digits()
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect()
+    };
+    Value::from_le_bytes(&bytes(payload), &bytes(mask), width, false)
+}
+
+fn bit0(value: &Value) -> Option<bool> {
+    (!value.mask_xz().bit(0)).then(|| value.payload().bit(0))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReplayOptions {
+    /// Compare outputs against the recording before each edge.
+    pub compare: bool,
+    /// Only match signals directly in this dot-separated scope.
+    pub scope: Option<String>,
+    /// Active edge of a plain `clock` port.
+    pub clock_type: ClockType,
+    /// Polarity of a plain `reset` port.
+    pub reset_type: ResetType,
+}
+
+#[derive(Debug)]
+pub struct ReplayOutcome {
+    pub result: TestResult,
+    /// Clock edges replayed.
+    pub cycles: u64,
+    /// Input ports with no signal in the trace; they keep their initial
+    /// value.
+    pub unmatched: Vec<String>,
+}
+
+/// A port driven or checked by the replay, with its trace signal.
+struct Bound<'a> {
+    name: String,
+    width: usize,
+    signal: &'a TraceSignal,
+    /// Next change of `signal` to apply.
+    next: usize,
+    /// Value as of the last applied change.
+    value: Option<Value>,
+}
+
+impl Bound<'_> {
+    /// The value `signal` takes at `time`, if it changes then.
+    fn change_at(&mut self, time: u64) -> Option<Value> {
+        let mut ret = None;
+        while let Some((t, value)) = self.signal.changes.get(self.next)
+            && *t == time
+        {
+            ret = Some(value.clone());
+            self.next += 1;
+        }
+        ret.map(|mut x| {
+            x.trunc(self.width);
+            x
+        })
+    }
+}
+
+enum Role {
+    Clock { event: Event, negedge: bool },
+    Reset { event: Event, active_low: bool },
+    Input,
+    Output,
+}
+
+/// Replays `trace` into `sim`, whose components (if any) are initialized.
+/// Setup problems — no clock to replay — are errors; a mismatch fails the
+/// returned result.
+pub fn run_replay(
+    sim: &mut Simulator,
+    trace: &Trace,
+    options: &ReplayOptions,
+) -> Result<ReplayOutcome, SimulatorError> {
+    let scope = options.scope.as_deref();
+    let mut ports: Vec<_> = sim
+        .ir
+        .ports
+        .iter()
+        .filter_map(|(path, id)| {
+            let x = sim.ir.module_variables.variables.get(id)?;
+            Some((path.to_string(), x.kind, x.r#type.kind.clone(), x.width))
+        })
+        .collect();
+    ports.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut bound: Vec<(Bound, Role)> = vec![];
+    let mut unmatched = vec![];
+    let mut unmatched_clocks = vec![];
+    for (name, kind, type_kind, width) in ports {
+        let role = match type_kind {
+            TypeKind::Clock | TypeKind::ClockPosedge | TypeKind::ClockNegedge => Role::Clock {
+                event: sim.get_clock(&name).unwrap(),
+                negedge: matches!(type_kind, TypeKind::ClockNegedge)
+                    || matches!(type_kind, TypeKind::Clock)
+                        && matches!(options.clock_type, ClockType::NegEdge),
+            },
+            TypeKind::Reset
+            | TypeKind::ResetAsyncHigh
+            | TypeKind::ResetAsyncLow
+            | TypeKind::ResetSyncHigh
+            | TypeKind::ResetSyncLow => Role::Reset {
+                event: sim.get_reset(&name).unwrap(),
+                active_low: match type_kind {
+                    TypeKind::ResetAsyncLow | TypeKind::ResetSyncLow => true,
+                    TypeKind::Reset => {
+                        matches!(options.reset_type, ResetType::AsyncLow | ResetType::SyncLow)
+                    }
+                    _ => false,
+                },
+            },
+            _ if kind == VarKind::Output => {
+                if !options.compare {
+                    continue;
+                }
+                Role::Output
+            }
+            _ if matches!(kind, VarKind::Input | VarKind::Inout) => Role::Input,
+            _ => continue,
+        };
+        match trace.find(&name, scope) {
+            Some(signal) => bound.push((
+                Bound {
+                    name,
+                    width,
+                    signal,
+                    next: 0,
+                    value: None,
+                },
+                role,
+            )),
+            None if matches!(role, Role::Clock { .. }) => unmatched_clocks.push((name, role)),
+            None if matches!(role, Role::Output) => {}
+            None => unmatched.push(name),
+        }
+    }
+
+    // Clock inference: a lone unmatched clock port takes the one periodic
+    // 1-bit signal no port claimed.
+    if let [(name, role)] = unmatched_clocks.as_slice() {
+        let claimed: Vec<*const TraceSignal> =
+            bound.iter().map(|(x, _)| x.signal as *const _).collect();
+        let mut candidates = trace.signals.iter().filter(|x| {
+            scope.is_none_or(|s| x.scope.join(".") == s)
+                && !claimed.contains(&(*x as *const _))
+                && x.is_periodic()
+        });
+        // Aliases of one net are the same clock.
+        if let Some(signal) = candidates.next()
+            && candidates.all(|x| x.changes == signal.changes)
+        {
+            let Role::Clock { event, negedge } = role else {
+                unreachable!()
+            };
+            log::info!("replay: clock `{name}` inferred from `{}`", signal.path());
+            bound.push((
+                Bound {
+                    name: name.clone(),
+                    width: 1,
+                    signal,
+                    next: 0,
+                    value: None,
+                },
+                Role::Clock {
+                    event: event.clone(),
+                    negedge: *negedge,
+                },
+            ));
+            unmatched_clocks.clear();
+        }
+    }
+    unmatched.extend(unmatched_clocks.into_iter().map(|x| x.0));
+    if !bound.iter().any(|x| matches!(x.1, Role::Clock { .. })) {
+        return Err(SimulatorError::Replay {
+            message: "no clock port of the module matches a trace signal".into(),
+        });
+    }
+
+    let mut times: Vec<u64> = bound
+        .iter()
+        .flat_map(|(x, _)| x.signal.changes.iter().map(|x| x.0))
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+
+    if sim.ir.event_statements.contains_key(&Event::Initial) {
+        sim.step(&Event::Initial);
+    }
+
+    let recording = sim.is_recording();
+    let mut cycles = 0;
+    for time in times {
+        let changes: Vec<Option<Value>> =
+            bound.iter_mut().map(|(x, _)| x.change_at(time)).collect();
+        sim.time = time;
+
+        // Clock edges at `time`, with the values from before it.
+        let mut edges = vec![];
+        for ((x, role), change) in bound.iter().zip(&changes) {
+            if let Role::Clock { event, negedge } = role
+                && let (Some(old), Some(new)) = (x.value.as_ref().and_then(bit0), change)
+                && bit0(new) == Some(!old)
+                && old == *negedge
+            {
+                edges.push(event.clone());
+            }
+        }
+
+        if !edges.is_empty() {
+            sim.ensure_comb_updated();
+            for (x, role) in &bound {
+                if let Role::Output = role
+                    && let Some(expected) = &x.value
+                    && let Some(actual) = sim.get(&x.name)
+                    && differs(&actual, expected)
+                {
+                    return Ok(ReplayOutcome {
+                        result: TestResult::Fail(format!(
+                            "cycle {cycles} (time {time}): output '{}' expected {:x} but got {:x}",
+                            x.name, expected, actual,
+                        )),
+                        cycles,
+                        unmatched,
+                    });
+                }
+            }
+            let reset = bound.iter().find_map(|(x, role)| match role {
+                Role::Reset { event, active_low } => x
+                    .value
+                    .as_ref()
+                    .and_then(bit0)
+                    .is_some_and(|x| x != *active_low)
+                    .then_some(event),
+                _ => None,
+            });
+            for edge in &edges {
+                sim.step(reset.unwrap_or(edge));
+                cycles += 1;
+            }
+        }
+
+        for ((x, role), change) in bound.iter_mut().zip(changes) {
+            let Some(value) = change else {
+                continue;
+            };
+            let drive = match role {
+                Role::Input | Role::Reset { .. } => true,
+                Role::Clock { .. } => recording,
+                Role::Output => false,
+            };
+            if drive {
+                sim.set(&x.name, value.clone());
+            }
+            x.value = Some(value);
+        }
+        if recording {
+            sim.dump_variables();
+        }
+    }
+
+    sim.cycle_count += cycles;
+    Ok(ReplayOutcome {
+        result: TestResult::Pass,
+        cycles,
+        unmatched,
+    })
+}
+
+/// True if `actual` differs from `expected` on a bit `expected` knows:
+/// X/Z bits of the recording are don't-cares.
+fn differs(actual: &Value, expected: &Value) -> bool {
+    let all = (BigUint::from(1u8) << expected.width()) - 1u8;
+    let care = all ^ &*expected.mask_xz();
+    let diff = (&*actual.payload() ^ &*expected.payload()) | &*actual.mask_xz();
+    diff & care != BigUint::default()
+}
@@ -0,0 +1,226 @@
+//! Switching activity (SAIF) recording for the native simulator.
+//!
+//! [`SaifRecorder`] is sampled wherever the simulator would dump a waveform
+//! (after each step, and at the falling half of each testbench clock), so
+//! clock nets see both edges.  Per bit it accumulates the time spent at 0, 1
+//! and X and the number of 0↔1 transitions, and writes them as a backward
+//! SAIF 2.0 file for power estimation (`veryl synth --saif`).
+//!
+//! Unpacked arrays are not recorded.
+
+use crate::ir::ModuleVariables;
+use std::io::{self, Write};
+use veryl_analyzer::ir as air;
+use veryl_analyzer::symbol::Affiliation;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BitActivity {
+    pub t0: u64,
+    pub t1: u64,
+    pub tx: u64,
+    /// 0→1 and 1→0 transitions.
+    pub tc: u64,
+}
+
+struct Net {
+    name: String,
+    ptr: *const u8,
+    native_bytes: usize,
+    width: usize,
+    last: Vec<u8>,
+    /// Time of the last change of `last`; durations since then are credited
+    /// lazily, when the value changes again or the file is written.
+    since: u64,
+    bits: Vec<BitActivity>,
+}
+
+struct Scope {
+    name: String,
+    nets: Vec<Net>,
+    children: Vec<Scope>,
+}
+
+/// Per-bit toggle counts and time-at-0/1/X of every net in the design.
+pub struct SaifRecorder {
+    use_4state: bool,
+    root: Scope,
+    start: Option<u64>,
+}
+
+// SAFETY: the pointers refer to the owning `Ir`'s buffers and are only
+// dereferenced by the thread driving that `Ir`.
+unsafe impl Send for SaifRecorder {}
+
+impl SaifRecorder {
+    pub fn new(vars: &ModuleVariables, use_4state: bool) -> Self {
+        SaifRecorder {
+            use_4state,
+            root: Self::scope(vars),
+            start: None,
+        }
+    }
+
+    fn scope(vars: &ModuleVariables) -> Scope {
+        let mut sorted: Vec<_> = vars.variables.iter().collect();
+        sorted.sort_by_key(|(id, _)| **id);
+        let nets = sorted
+            .into_iter()
+            .map(|(_, x)| x)
+            .filter(|x| {
+                x.current_values.len() == 1
+                    && x.width != 0
+                    && x.affiliation != Affiliation::Function
+                    && !matches!(x.kind, air::VarKind::Param | air::VarKind::Const)
+            })
+            .map(|x| Net {
+                name: x.path.to_string(),
+                ptr: x.current_values[0] as *const u8,
+                native_bytes: x.native_bytes,
+                width: x.width,
+                last: Vec::new(),
+                since: 0,
+                bits: vec![BitActivity::default(); x.width],
+            })
+            .collect();
+        Scope {
+            name: vars.name.to_string(),
+            nets,
+            children: vars.children.iter().map(Self::scope).collect(),
+        }
+    }
+
+    /// Records the current value of every net at `time`.
+    pub fn sample(&mut self, time: u64) {
+        let start = *self.start.get_or_insert(time);
+        let use_4state = self.use_4state;
+        let mut stack = vec![&mut self.root];
+        while let Some(scope) = stack.pop() {
+            for net in &mut scope.nets {
+                net.sample(time, start, use_4state);
+            }
+            stack.extend(scope.children.iter_mut());
+        }
+    }
+
+    /// Writes the activity from the first sample up to `end` as SAIF.
+    pub fn write(&self, w: &mut impl Write, end: u64) -> io::Result<()> {
+        let start = self.start.unwrap_or(end);
+        writeln!(w, "(SAIFILE")?;
+        writeln!(w, "(SAIFVERSION \"2.0\")")?;
+        writeln!(w, "(DIRECTION \"backward\")")?;
+        writeln!(w, "(DESIGN \"{}\")", self.root.name)?;
+        writeln!(w, "(VENDOR \"Veryl\")")?;
+        writeln!(w, "(PROGRAM_NAME \"veryl\")")?;
+        writeln!(w, "(VERSION \"{}\")", env!("CARGO_PKG_VERSION"))?;
+        writeln!(w, "(DIVIDER / )")?;
+        writeln!(w, "(TIMESCALE 1 us)")?;
+        writeln!(w, "(DURATION {})", end.saturating_sub(start))?;
+        self.write_scope(w, &self.root, end, 0)?;
+        writeln!(w, ")")
+    }
+
+    fn write_scope(
+        &self,
+        w: &mut impl Write,
+        scope: &Scope,
+        end: u64,
+        depth: usize,
+    ) -> io::Result<()> {
+        let indent = "  ".repeat(depth);
+        writeln!(w, "{indent}(INSTANCE {}", escape(&scope.name))?;
+        if !scope.nets.is_empty() {
+            writeln!(w, "{indent}  (NET")?;
+            for net in &scope.nets {
+                for (i, bit) in net.bits.iter().enumerate() {
+                    let mut bit = *bit;
+                    net.credit(&mut bit, i, end, self.use_4state);
+                    let name = if net.width == 1 {
+                        escape(&net.name)
+                    } else {
+                        format!("{}\\[{i}\\]", escape(&net.name))
+                    };
+                    writeln!(
+                        w,
+                        "{indent}    ({name} (T0 {}) (T1 {}) (TX {}) (TC {}) (IG 0))",
+                        bit.t0, bit.t1, bit.tx, bit.tc
+                    )?;
+                }
+            }
+            writeln!(w, "{indent}  )")?;
+        }
+        for child in &scope.children {
+            self.write_scope(w, child, end, depth + 1)?;
+        }
+        writeln!(w, "{indent})")
+    }
+}
+
+impl Net {
+    /// `start` is the first sample's time; every net is first sampled then.
+    fn sample(&mut self, time: u64, start: u64, use_4state: bool) {
+        let nb = self.native_bytes;
+        // SAFETY: `ptr` addresses the variable's payload (and mask in
+        // 4-state mode).
+        let cur = unsafe { std::slice::from_raw_parts(self.ptr, nb * (1 + use_4state as usize)) };
+        if self.last.is_empty() {
+            self.last = cur.to_vec();
+            self.since = start;
+            return;
+        }
+        if self.last.as_slice() == cur {
+            return;
+        }
+        for i in 0..self.width {
+            let mut bit = self.bits[i];
+            self.credit(&mut bit, i, time, use_4state);
+            let (old, new) = (
+                self.value(&self.last, i, use_4state),
+                self.value(cur, i, use_4state),
+            );
+            if old.is_some() && new.is_some() && old != new {
+                bit.tc += 1;
+            }
+            self.bits[i] = bit;
+        }
+        self.last.copy_from_slice(cur);
+        self.since = time;
+    }
+
+    /// `Some(bit)` of `bytes`, or `None` for X/Z.
+    fn value(&self, bytes: &[u8], i: usize, use_4state: bool) -> Option<bool> {
+        let (byte, mask) = (i / 8, 1u8 << (i % 8));
+        if use_4state && bytes[self.native_bytes + byte] & mask != 0 {
+            None
+        } else {
+            Some(bytes[byte] & mask != 0)
+        }
+    }
+
+    /// Adds the time from `since` to `time` to bit `i`'s current level.
+    fn credit(&self, bit: &mut BitActivity, i: usize, time: u64, use_4state: bool) {
+        if self.last.is_empty() {
+            return;
+        }
+        let dt = time.saturating_sub(self.since);
+        match self.value(&self.last, i, use_4state) {
+            Some(false) => bit.t0 += dt,
+            Some(true) => bit.t1 += dt,
+            None => bit.tx += dt,
+        }
+    }
+}
+
+/// Escapes SAIF identifier special characters.
+fn escape(name: &str) -> String {
+    let mut ret = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(
+            c,
+            '[' | ']' | '.' | '/' | '\\' | '(' | ')' | ':' | '<' | '>'
+        ) {
+            ret.push('\\');
+        }
+        ret.push(c);
+    }
+    ret
+}
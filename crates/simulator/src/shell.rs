@@ -0,0 +1,633 @@
+//! Line-oriented command interpreter behind `veryl sim`.
+//!
+//! A [`Shell`] owns a [`Simulator`] and executes one command per line, so
+//! the same code serves the interactive prompt and `source`d scripts.  Run
+//! `help` for the command list.
+//!
+//! Clock cycles are driven the way the native testbench drives them: the
+//! clock variable is raised and lowered around each step when a waveform is
+//! recorded, and each cycle advances time by two units.
+//!
+//! `force` holds a variable at a value through `$tb::force` override slots,
+//! so its drivers are ignored until `release`. The slots are built with the
+//! design, so the variables have to be listed in `Config::force_targets`
+//! (`veryl sim --forceable`); top-level input ports have no drivers and are
+//! held without one.
+
+use crate::ir::{Event, Value, VarPath};
+use crate::simulator::Simulator;
+use crate::simulator_error::SimulatorError;
+use crate::wave_dumper::WaveDumper;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+use veryl_analyzer::ir as air;
+
+/// Cycles `run until` gives up after unless `max` says otherwise.
+const DEFAULT_UNTIL_LIMIT: u64 = 1_000_000;
+
+/// `source` nesting limit, so a script sourcing itself fails instead of
+/// overflowing the stack.
+const MAX_SOURCE_DEPTH: usize = 16;
+
+const HELP: &str = "\
+commands:
+  get <name>...               print ports or hierarchical variables
+  set <name> <value>          write a port or hierarchical variable
+  force <name> <value>        hold a value until `release` (see --forceable)
+  release <name>|all          stop holding a forced value
+  step [<clock|reset>] [<n>]  run n cycles of a clock (or with reset asserted)
+  reset [<n>]                 run n cycles with the default reset asserted
+  run <n>                     run n cycles of the default clock
+  run until <expr> [max <n>]  run the default clock until expr holds
+  clock <name>                choose the default clock
+  ports                       list the top-level ports
+  time                        print the simulation time and cycle count
+  wave open <file>            start dumping a VCD (or FST for *.fst) file
+  wave close                  finish the waveform file
+  source <file>               execute the commands in a file
+  help                        print this message
+  quit                        leave the shell
+values are Veryl literals (`42`, `8'hff`, `'x`); expressions combine names
+and values with == != < <= > >= && || ! and parentheses";
+
+#[derive(Debug, Error)]
+pub enum ShellError {
+    #[error("unknown command `{0}`; try `help`")]
+    UnknownCommand(String),
+    #[error("usage: {0}")]
+    Usage(&'static str),
+    #[error("no port or variable named `{0}`")]
+    UnknownSignal(String),
+    #[error("`{0}` is not forceable; pass `--forceable {0}` to `veryl sim`")]
+    NotForceable(String),
+    #[error("`{0}` is forced; `release` it first")]
+    Forced(String),
+    #[error("`{0}` is not a clock or reset port")]
+    UnknownEvent(String),
+    #[error("no {0} port; name one explicitly")]
+    NoDefault(&'static str),
+    #[error("invalid value `{0}`")]
+    InvalidValue(String),
+    #[error("invalid expression: {0}")]
+    InvalidExpression(String),
+    #[error("`{expr}` did not hold within {cycles} cycles")]
+    UntilTimeout { expr: String, cycles: u64 },
+    #[error("no waveform is open")]
+    NoWave,
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("{path}:{line}: {source}")]
+    Script {
+        path: PathBuf,
+        line: usize,
+        source: Box<ShellError>,
+    },
+    #[error("`source` nested more than {MAX_SOURCE_DEPTH} levels")]
+    SourceDepth,
+    #[error(transparent)]
+    Simulator(#[from] SimulatorError),
+}
+
+/// What the caller should do after a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+pub struct Shell {
+    sim: Simulator,
+    clock: Option<String>,
+    reset: Option<String>,
+    /// Names currently forced, in the order they were forced.
+    forced: Vec<String>,
+    depth: usize,
+}
+
+impl Shell {
+    /// Takes a simulator whose components (if any) are initialized.  Runs
+    /// the design's `initial` blocks and picks the first clock and reset
+    /// ports, by name, as defaults.
+    pub fn new(mut sim: Simulator) -> Self {
+        let mut ports: Vec<_> = sim
+            .ir
+            .ports
+            .iter()
+            .filter_map(|(path, id)| {
+                Some((path.to_string(), sim.ir.module_variables.variables.get(id)?))
+            })
+            .collect();
+        ports.sort_by(|a, b| a.0.cmp(&b.0));
+        let clock = ports
+            .iter()
+            .find(|(_, x)| x.r#type.is_clock())
+            .map(|x| x.0.clone());
+        let reset = ports
+            .iter()
+            .find(|(_, x)| x.r#type.is_reset())
+            .map(|x| x.0.clone());
+        if sim.ir.event_statements.contains_key(&Event::Initial) {
+            sim.step(&Event::Initial);
+        }
+        Shell {
+            sim,
+            clock,
+            reset,
+            forced: vec![],
+            depth: 0,
+        }
+    }
+
+    pub fn sim(&mut self) -> &mut Simulator {
+        &mut self.sim
+    }
+
+    /// Executes one command line; blank lines and `#` comments do nothing.
+    pub fn exec(&mut self, line: &str, out: &mut dyn Write) -> Result<Flow, ShellError> {
+        let line = line.split_once('#').map_or(line, |x| x.0).trim();
+        let (cmd, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let args: Vec<&str> = rest.split_whitespace().collect();
+        match cmd {
+            "" => {}
+            "get" | "print" => {
+                if args.is_empty() {
+                    return Err(ShellError::Usage("get <name>..."));
+                }
+                for name in args {
+                    let value = self.read(name)?;
+                    self.print(out, format_args!("{name} = {value:x}"))?;
+                }
+            }
+            "set" => {
+                let [name, value] = args[..] else {
+                    return Err(ShellError::Usage("set <name> <value>"));
+                };
+                let value = parse_value(value)?;
+                if self.forced.iter().any(|x| x == name) {
+                    return Err(ShellError::Forced(name.to_string()));
+                }
+                self.write(name, value)?;
+            }
+            "force" => {
+                let [name, value] = args[..] else {
+                    return Err(ShellError::Usage("force <name> <value>"));
+                };
+                let value = parse_value(value)?;
+                self.force(name, value)?;
+                if !self.forced.iter().any(|x| x == name) {
+                    self.forced.push(name.to_string());
+                }
+            }
+            "release" => match args[..] {
+                ["all"] => {
+                    for name in std::mem::take(&mut self.forced) {
+                        self.sim.release_var(&name);
+                    }
+                }
+                [name] => {
+                    let len = self.forced.len();
+                    self.forced.retain(|x| x != name);
+                    if self.forced.len() == len {
+                        return Err(ShellError::UnknownSignal(name.to_string()));
+                    }
+                    self.sim.release_var(name);
+                }
+                _ => return Err(ShellError::Usage("release <name>|all")),
+            },
+            "step" => {
+                let (name, count) = match args[..] {
+                    [] => (None, 1),
+                    [x] => match x.parse() {
+                        Ok(n) => (None, n),
+                        Err(_) => (Some(x), 1),
+                    },
+                    [x, n] => (Some(x), parse_count(n, "step [<clock|reset>] [<n>]")?),
+                    _ => return Err(ShellError::Usage("step [<clock|reset>] [<n>]")),
+                };
+                let event = match name {
+                    Some(x) => self.event(x)?,
+                    None => self.default_clock()?,
+                };
+                for _ in 0..count {
+                    self.cycle(&event);
+                }
+            }
+            "reset" => {
+                let count = match args[..] {
+                    [] => 1,
+                    [n] => parse_count(n, "reset [<n>]")?,
+                    _ => return Err(ShellError::Usage("reset [<n>]")),
+                };
+                let name = self.reset.clone().ok_or(ShellError::NoDefault("reset"))?;
+                let event = self.event(&name)?;
+                for _ in 0..count {
+                    self.cycle(&event);
+                }
+            }
+            "run" => {
+                let event = self.default_clock()?;
+                if let Some(expr) = rest.strip_prefix("until") {
+                    let (expr, limit) = match expr.rsplit_once(" max ") {
+                        Some((expr, n)) => {
+                            (expr, parse_count(n.trim(), "run until <expr> [max <n>]")?)
+                        }
+                        None => (expr, DEFAULT_UNTIL_LIMIT),
+                    };
+                    let expr = expr.trim();
+                    let parsed = Expr::parse(expr)?;
+                    let mut cycles = 0;
+                    while !self.holds(&parsed)? {
+                        if cycles == limit {
+                            return Err(ShellError::UntilTimeout {
+                                expr: expr.to_string(),
+                                cycles,
+                            });
+                        }
+                        self.cycle(&event);
+                        cycles += 1;
+                    }
+                    self.print(out, format_args!("held after {cycles} cycles"))?;
+                } else {
+                    let [n] = args[..] else {
+                        return Err(ShellError::Usage("run <n> | run until <expr> [max <n>]"));
+                    };
+                    for _ in 0..parse_count(n, "run <n>")? {
+                        self.cycle(&event);
+                    }
+                }
+            }
+            "clock" => {
+                let [name] = args[..] else {
+                    return Err(ShellError::Usage("clock <name>"));
+                };
+                match self.event(name)? {
+                    Event::Clock(_) => self.clock = Some(name.to_string()),
+                    _ => return Err(ShellError::UnknownEvent(name.to_string())),
+                }
+            }
+            "ports" => {
+                let mut ports: Vec<_> = self
+                    .sim
+                    .ir
+                    .ports
+                    .iter()
+                    .filter_map(|(path, id)| {
+                        let var = self.sim.ir.module_variables.variables.get(id)?;
+                        Some((path.to_string(), var.kind, var.width))
+                    })
+                    .collect();
+                ports.sort_by(|a, b| a.0.cmp(&b.0));
+                for (name, kind, width) in ports {
+                    self.print(out, format_args!("{name}: {kind} {width}"))?;
+                }
+            }
+            "time" => {
+                let (time, cycle) = (self.sim.time, self.sim.cycle_count);
+                self.print(out, format_args!("time {time}, cycle {cycle}"))?;
+            }
+            "wave" => match args[..] {
+                ["open", path] => self.open_wave(Path::new(path))?,
+                ["close"] => drop(self.sim.detach_dump().ok_or(ShellError::NoWave)?),
+                _ => return Err(ShellError::Usage("wave open <file> | wave close")),
+            },
+            "source" => {
+                let [path] = args[..] else {
+                    return Err(ShellError::Usage("source <file>"));
+                };
+                return self.source(Path::new(path), out);
+            }
+            "help" => self.print(out, format_args!("{HELP}"))?,
+            "quit" | "exit" => return Ok(Flow::Quit),
+            _ => return Err(ShellError::UnknownCommand(cmd.to_string())),
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Executes every line of `path`, stopping at the first error.
+    pub fn source(&mut self, path: &Path, out: &mut dyn Write) -> Result<Flow, ShellError> {
+        if self.depth == MAX_SOURCE_DEPTH {
+            return Err(ShellError::SourceDepth);
+        }
+        let text = std::fs::read_to_string(path).map_err(|source| ShellError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.depth += 1;
+        let mut ret = Ok(Flow::Continue);
+        for (i, line) in text.lines().enumerate() {
+            match self.exec(line, out) {
+                Ok(Flow::Continue) => {}
+                Ok(Flow::Quit) => {
+                    ret = Ok(Flow::Quit);
+                    break;
+                }
+                Err(source) => {
+                    ret = Err(ShellError::Script {
+                        path: path.to_path_buf(),
+                        line: i + 1,
+                        source: Box::new(source),
+                    });
+                    break;
+                }
+            }
+        }
+        self.depth -= 1;
+        ret
+    }
+
+    fn print(&self, out: &mut dyn Write, args: std::fmt::Arguments) -> Result<(), ShellError> {
+        writeln!(out, "{args}").map_err(|source| ShellError::Io {
+            path: PathBuf::from("<stdout>"),
+            source,
+        })
+    }
+
+    fn is_port(&self, name: &str) -> bool {
+        self.sim
+            .ir
+            .ports
+            .contains_key(&VarPath::from_str(name).unwrap())
+    }
+
+    fn read(&mut self, name: &str) -> Result<Value, ShellError> {
+        let value = if self.is_port(name) {
+            self.sim.get(name)
+        } else {
+            self.sim.get_var(name)
+        };
+        value.ok_or_else(|| ShellError::UnknownSignal(name.to_string()))
+    }
+
+    fn write(&mut self, name: &str, value: Value) -> Result<(), ShellError> {
+        if self.is_port(name) {
+            self.sim.set(name, value);
+            Ok(())
+        } else if self.sim.set_var(name, value) {
+            Ok(())
+        } else {
+            Err(ShellError::UnknownSignal(name.to_string()))
+        }
+    }
+
+    /// Holds `name` at `value`: through its override slots when it was
+    /// declared forceable, or by writing it when it is a top-level input,
+    /// which nothing but the shell drives.
+    fn force(&mut self, name: &str, value: Value) -> Result<(), ShellError> {
+        if self.sim.force_var(name, &value) {
+            return Ok(());
+        }
+        let is_input = self
+            .sim
+            .ir
+            .ports
+            .get(&VarPath::from_str(name).unwrap())
+            .and_then(|id| self.sim.ir.module_variables.variables.get(id))
+            .is_some_and(|x| x.kind == air::VarKind::Input);
+        if is_input {
+            self.write(name, value)
+        } else {
+            // Distinguish a typo from a real but undeclared variable.
+            self.read(name)?;
+            Err(ShellError::NotForceable(name.to_string()))
+        }
+    }
+
+    fn event(&self, name: &str) -> Result<Event, ShellError> {
+        self.sim
+            .get_reset(name)
+            .filter(|_| self.port_type(name).is_some_and(|x| x.is_reset()))
+            .or_else(|| {
+                self.sim
+                    .get_clock(name)
+                    .filter(|_| self.port_type(name).is_some_and(|x| x.is_clock()))
+            })
+            .ok_or_else(|| ShellError::UnknownEvent(name.to_string()))
+    }
+
+    fn port_type(&self, name: &str) -> Option<&air::Type> {
+        let id = self.sim.ir.ports.get(&VarPath::from_str(name).unwrap())?;
+        Some(&self.sim.ir.module_variables.variables.get(id)?.r#type)
+    }
+
+    fn default_clock(&self) -> Result<Event, ShellError> {
+        let name = self
+            .clock
+            .as_deref()
+            .ok_or(ShellError::NoDefault("clock"))?;
+        self.event(name)
+    }
+
+    /// One cycle of `event`, recorded like the native testbench's
+    /// `clock_next`/`reset_assert`.
+    fn cycle(&mut self, event: &Event) {
+        let recording = self.sim.is_recording();
+        let clock = self
+            .clock
+            .as_deref()
+            .and_then(|x| self.sim.get_clock(x))
+            .and_then(|x| x.var_id());
+        let reset = matches!(event, Event::Reset(_))
+            .then(|| event.var_id())
+            .flatten();
+        let edge = if reset.is_some() {
+            clock
+        } else {
+            event.var_id()
+        };
+        if recording {
+            for id in reset.iter().chain(edge.iter()) {
+                self.sim.set_var_by_id(id, Value::new(1, 1, false));
+            }
+        }
+        self.sim.step(event);
+        self.sim.time += 1;
+        if recording {
+            for id in reset.iter().chain(edge.iter()) {
+                self.sim.set_var_by_id(id, Value::new(0, 1, false));
+            }
+            self.sim.dump_variables();
+        }
+        self.sim.time += 1;
+        self.sim.cycle_count += 1;
+    }
+
+    fn open_wave(&mut self, path: &Path) -> Result<(), ShellError> {
+        let dumper = if path.extension().is_some_and(|x| x == "fst") {
+            WaveDumper::new_fst(&path.to_string_lossy())
+        } else {
+            let file = std::fs::File::create(path).map_err(|source| ShellError::Io {
+                path: path.to_path_buf(),
+                source,
+            })?;
+            WaveDumper::new_vcd(Box::new(std::io::BufWriter::new(file)))
+        };
+        drop(self.sim.detach_dump());
+        self.sim.attach_dump(dumper.with_path(path.to_path_buf()));
+        self.sim.dump_start();
+        Ok(())
+    }
+
+    fn holds(&mut self, expr: &Expr) -> Result<bool, ShellError> {
+        Ok(expr.eval(self)?.is_some_and(|x| x != 0))
+    }
+}
+
+fn parse_value(text: &str) -> Result<Value, ShellError> {
+    let valid = text.starts_with(|c: char| c.is_ascii_digit() || c == '\'')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "'_.".contains(c));
+    match valid.then(|| Value::from_str(text)) {
+        Some(Ok(x)) => Ok(x),
+        _ => Err(ShellError::InvalidValue(text.to_string())),
+    }
+}
+
+fn parse_count(text: &str, usage: &'static str) -> Result<u64, ShellError> {
+    text.parse().map_err(|_| ShellError::Usage(usage))
+}
+
+/// `run until` condition.  Values are compared as unsigned 128-bit
+/// integers; any X/Z operand makes the result unknown, which does not hold.
+#[derive(Debug)]
+enum Expr {
+    Signal(String),
+    Const(Value),
+    Not(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn parse(text: &str) -> Result<Expr, ShellError> {
+        let tokens = tokenize(text)?;
+        let mut pos = 0;
+        let expr = Self::parse_or(&tokens, &mut pos)?;
+        match tokens.get(pos) {
+            None => Ok(expr),
+            Some(x) => Err(ShellError::InvalidExpression(format!("unexpected `{x}`"))),
+        }
+    }
+
+    fn parse_or(tokens: &[&str], pos: &mut usize) -> Result<Expr, ShellError> {
+        let mut lhs = Self::parse_and(tokens, pos)?;
+        while tokens.get(*pos) == Some(&"||") {
+            *pos += 1;
+            lhs = Expr::Binary("||", Box::new(lhs), Box::new(Self::parse_and(tokens, pos)?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(tokens: &[&str], pos: &mut usize) -> Result<Expr, ShellError> {
+        let mut lhs = Self::parse_cmp(tokens, pos)?;
+        while tokens.get(*pos) == Some(&"&&") {
+            *pos += 1;
+            lhs = Expr::Binary("&&", Box::new(lhs), Box::new(Self::parse_cmp(tokens, pos)?));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_cmp(tokens: &[&str], pos: &mut usize) -> Result<Expr, ShellError> {
+        let lhs = Self::parse_unary(tokens, pos)?;
+        let op = ["==", "!=", "<=", ">=", "<", ">"]
+            .into_iter()
+            .find(|x| tokens.get(*pos) == Some(x));
+        match op {
+            Some(op) => {
+                *pos += 1;
+                let rhs = Self::parse_unary(tokens, pos)?;
+                Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+            }
+            None => Ok(lhs),
+        }
+    }
+
+    fn parse_unary(tokens: &[&str], pos: &mut usize) -> Result<Expr, ShellError> {
+        let Some(&token) = tokens.get(*pos) else {
+            return Err(ShellError::InvalidExpression("unexpected end".to_string()));
+        };
+        *pos += 1;
+        match token {
+            "!" => Ok(Expr::Not(Box::new(Self::parse_unary(tokens, pos)?))),
+            "(" => {
+                let inner = Self::parse_or(tokens, pos)?;
+                if tokens.get(*pos) != Some(&")") {
+                    return Err(ShellError::InvalidExpression("missing `)`".to_string()));
+                }
+                *pos += 1;
+                Ok(inner)
+            }
+            x if x.starts_with(|c: char| c.is_ascii_digit() || c == '\'') => {
+                Ok(Expr::Const(parse_value(x)?))
+            }
+            x if x.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') => {
+                Ok(Expr::Signal(x.to_string()))
+            }
+            x => Err(ShellError::InvalidExpression(format!("unexpected `{x}`"))),
+        }
+    }
+
+    fn eval(&self, shell: &mut Shell) -> Result<Option<u128>, ShellError> {
+        let known = |x: Value| (x.mask_xz_u128() == 0).then(|| x.payload_u128());
+        Ok(match self {
+            Expr::Signal(name) => known(shell.read(name)?),
+            Expr::Const(x) => known(x.clone()),
+            Expr::Not(x) => x.eval(shell)?.map(|x| (x == 0) as u128),
+            Expr::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(shell)?, rhs.eval(shell)?);
+                match *op {
+                    // A known operand can decide the logical operators alone.
+                    "&&" if lhs == Some(0) || rhs == Some(0) => Some(0),
+                    "||" if lhs.is_some_and(|x| x != 0) || rhs.is_some_and(|x| x != 0) => Some(1),
+                    _ => {
+                        let (Some(lhs), Some(rhs)) = (lhs, rhs) else {
+                            return Ok(None);
+                        };
+                        let ret = match *op {
+                            "&&" => lhs != 0 && rhs != 0,
+                            "||" => lhs != 0 || rhs != 0,
+                            "==" => lhs == rhs,
+                            "!=" => lhs != rhs,
+                            "<=" => lhs <= rhs,
+                            ">=" => lhs >= rhs,
+                            "<" => lhs < rhs,
+                            _ => lhs > rhs,
+                        };
+                        Some(ret as u128)
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Longest first, so `<=` is not read as `<`.
+const OPERATORS: [&str; 11] = ["&&", "||", "==", "!=", "<=", ">=", "<", ">", "!", "(", ")"];
+
+fn tokenize(text: &str) -> Result<Vec<&str>, ShellError> {
+    let mut ret = vec![];
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let len = if let Some(op) = OPERATORS.iter().find(|x| rest.starts_with(**x)) {
+            op.len()
+        } else {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || "_.'[]".contains(c)))
+                .unwrap_or(rest.len());
+            if len == 0 {
+                return Err(ShellError::InvalidExpression(format!(
+                    "unexpected `{rest}`"
+                )));
+            }
+            len
+        };
+        ret.push(&rest[..len]);
+        rest = rest[len..].trim_start();
+    }
+    Ok(ret)
+}
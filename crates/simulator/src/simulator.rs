@@ -1,18 +1,29 @@
 use crate::backend::CompiledWhole;
+use crate::checkpoint::Snapshot;
 use crate::component::loader::ComponentError;
-use crate::component::runtime::{RuntimeComponent, build_components};
+use crate::component::runtime::{
+    RESTORE_METHOD, RuntimeComponent, SNAPSHOT_METHOD, build_components,
+};
+use crate::coverage::Sampler;
 use crate::ir::write_log::{
     WriteLogBuffer, clear_event_write_log, ff_commit_from_log, set_event_write_log,
 };
 use crate::ir::{
-    Event, Ir, ModuleVariables, Statement, Value, VarId, VarPath, dispatch_stmt_fast,
+    Event, Ir, ModuleVariables, Statement, Value, VarId, VarPath, Variable, dispatch_stmt_fast,
     read_native_value, write_native_value,
 };
+use crate::profiler::{Profile, Profiler};
+use crate::saif::SaifRecorder;
+use crate::simulator_error::SimulatorError;
+use crate::watchdog::Watchdog;
 use crate::wave_dumper::{DumpVar, WaveDumper};
+use crate::xprop;
 use smallvec::SmallVec;
 use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 use veryl_analyzer::value::MaskCache;
 
 #[cfg(feature = "profile")]
@@ -27,17 +38,25 @@ pub struct SimProfile {
     pub event_eval_ns: u64,
     pub ff_swap_ns: u64,
     pub eval_comb_full_ns: u64,
+    /// Per-statement timing when the IR was built with `Config::profile`.
+    pub(crate) blocks: Option<Profiler>,
 }
 
 #[cfg(not(feature = "profile"))]
 #[derive(Default, Debug)]
-pub struct SimProfile;
+pub struct SimProfile {
+    /// Per-statement timing when the IR was built with `Config::profile`.
+    pub(crate) blocks: Option<Profiler>,
+}
 
 pub struct Simulator {
     pub ir: Ir,
     pub time: u64,
     pub dump: Option<WaveDumper>,
     dump_vars: Vec<DumpVar>,
+    /// Cleared by `$tb::wave` `stop()`; dumping also needs the current cycle
+    /// to fall in the dumper's window.
+    wave_enabled: bool,
     pub mask_cache: MaskCache,
     comb_dirty: bool,
     pub profile: SimProfile,
@@ -61,6 +80,8 @@ pub struct Simulator {
     /// Stop the testbench after this many clock cycles; `None` runs to completion.
     pub cycle_limit: Option<u64>,
     pub cycle_count: u64,
+    /// Fails a hung testbench; see `watchdog`.
+    pub watchdog: Option<Watchdog>,
     /// Env-gated `VERYL_STEP_WATCH=path1,path2` debug watch: resolved
     /// variable pointers printed at each phase of `step_with_derived_clocks`.
     watch_vars: Vec<WatchVar>,
@@ -72,6 +93,12 @@ pub struct Simulator {
     /// Waveform handles for component trace variables:
     /// (handle, component index, trace variable index).
     trace_dump_vars: Vec<(crate::wave_dumper::VarHandle, usize, usize)>,
+    /// Toggle/FSM sampler when the IR was built with `Config::coverage`.
+    coverage: Option<Sampler>,
+    /// Switching-activity recorder, sampled alongside waveform dumps.
+    pub saif: Option<SaifRecorder>,
+    /// X report when the IR was built with `Config::xprop_report`.
+    xprop_monitor: Option<xprop::Monitor>,
 }
 
 struct WatchVar {
@@ -131,6 +158,7 @@ impl Simulator {
             time: 0,
             dump: None,
             dump_vars: Vec::new(),
+            wave_enabled: true,
             mask_cache: MaskCache::default(),
             comb_dirty: true,
             profile: Default::default(),
@@ -145,11 +173,22 @@ impl Simulator {
             },
             cycle_limit: None,
             cycle_count: 0,
+            watchdog: None,
             watch_vars: Vec::new(),
             components: Vec::new(),
             components_pending,
             trace_dump_vars: Vec::new(),
+            coverage: None,
+            saif: None,
+            xprop_monitor: None,
         };
+        ret.profile.blocks = ret.ir.profiler.take();
+        if ret.ir.coverage {
+            ret.coverage = Some(Sampler::new(&ret.ir.module_variables, ret.ir.use_4state));
+        }
+        if ret.ir.xprop_report {
+            ret.xprop_monitor = Some(xprop::Monitor::new(&ret.ir.module_variables));
+        }
 
         if std::env::var("VERYL_DERIVED_CLOCK_DUMP").as_deref() == Ok("1") {
             fn find_var_by_ptr(
@@ -360,6 +399,68 @@ impl Simulator {
         Self::find_var_in_module(&self.ir.module_variables, &target, self.ir.use_4state)
     }
 
+    /// Overwrite a variable by hierarchical path (e.g., "dut.cnt"). An FF
+    /// is written on both sides so the value survives the next commit, and
+    /// then evolves from its drivers again. Returns false if no variable
+    /// matches.
+    pub fn set_var(&mut self, path: &str, value: Value) -> bool {
+        let target = VarPath::from_str(path).unwrap();
+        let Some(var) = Self::find_variable(&self.ir.module_variables, &target) else {
+            return false;
+        };
+        let mut value = value;
+        value.trunc(var.width);
+        for &ptr in var
+            .current_values
+            .first()
+            .into_iter()
+            .chain(var.next_values.first())
+        {
+            unsafe {
+                write_native_value(ptr, var.native_bytes, self.ir.use_4state, &value);
+            }
+        }
+        self.comb_dirty = true;
+        true
+    }
+
+    /// Hold a variable listed in `Config::force_targets` at `value` against
+    /// its drivers until [`Simulator::release_var`]. Returns false if `path`
+    /// was not declared forceable.
+    pub fn force_var(&mut self, path: &str, value: &Value) -> bool {
+        let Some(slot) = self.ir.force_slots.get(path) else {
+            return false;
+        };
+        unsafe { slot.force(value) };
+        self.comb_dirty = true;
+        true
+    }
+
+    /// Hand a forced variable back to its drivers. Returns false if `path`
+    /// was not declared forceable.
+    pub fn release_var(&mut self, path: &str) -> bool {
+        let Some(slot) = self.ir.force_slots.get(path) else {
+            return false;
+        };
+        unsafe { slot.release() };
+        self.comb_dirty = true;
+        true
+    }
+
+    fn find_variable<'a>(module: &'a ModuleVariables, target: &VarPath) -> Option<&'a Variable> {
+        if target.0.len() > 1 {
+            for child in &module.children {
+                if child.name == target.0[0] {
+                    let sub = VarPath::from_slice(&target.0[1..]);
+                    if let Some(v) = Self::find_variable(child, &sub) {
+                        return Some(v);
+                    }
+                }
+            }
+        }
+        module.variables.values().find(|var| var.path == *target)
+    }
+
     fn find_var_in_module(
         module: &ModuleVariables,
         target: &VarPath,
@@ -501,6 +602,34 @@ impl Simulator {
         } else {
             self.step_with_derived_clocks(event);
         }
+
+        // Settling here only moves the next step's settle forward, unless the
+        // testbench changes an input in between.
+        if self.coverage.is_some() {
+            self.ensure_comb_updated();
+            if let Some(x) = &mut self.coverage {
+                x.sample();
+            }
+        }
+        if self.xprop_monitor.is_some() {
+            self.ensure_comb_updated();
+            let reset = matches!(event, Event::Reset(_));
+            if let Some(x) = &mut self.xprop_monitor {
+                x.sample(self.time, reset);
+            }
+        }
+    }
+
+    /// Where X first reached each register and output so far; empty unless
+    /// the IR was built with `Config::xprop_report`.
+    pub fn xprop_events(&self) -> &[xprop::XEvent] {
+        self.xprop_monitor.as_ref().map_or(&[], |x| x.events())
+    }
+
+    /// Time per instance, block and chunk so far, when the IR was built
+    /// with `Config::profile`.
+    pub fn profile_report(&self) -> Option<Profile> {
+        self.profile.blocks.as_ref().map(|x| x.report())
     }
 
     fn step_legacy(&mut self, event: &Event) {
@@ -734,10 +863,17 @@ impl Simulator {
             let validate = self.ir.aot_c_validate;
 
             if !validate {
-                matches!(
+                let start = self.profile.blocks.is_some().then(Instant::now);
+                let done = matches!(
                     whole.try_dispatch(ff_ptr, comb_ptr, log_ptr),
                     DispatchOutcome::Done,
-                )
+                );
+                if let (Some(blocks), Some(start), true) =
+                    (self.profile.blocks.as_mut(), start, done)
+                {
+                    blocks.whole_event(event, start);
+                }
+                done
             } else {
                 // For validate, the wrapper compares the whole-event
                 // dispatch against the per-stmt Cranelift path and panics
@@ -754,8 +890,12 @@ impl Simulator {
         if !dispatched && !stmts_ptr.is_null() {
             // SAFETY: event_statements is never mutated after Ir construction.
             let statements: &Vec<Statement> = unsafe { &*stmts_ptr };
-            for x in statements {
-                dispatch_stmt_fast(x, &mut self.mask_cache);
+            if let Some(blocks) = self.profile.blocks.as_mut() {
+                blocks.run_event(event, statements, &mut self.mask_cache);
+            } else {
+                for x in statements {
+                    dispatch_stmt_fast(x, &mut self.mask_cache);
+                }
             }
         }
 
@@ -1141,17 +1281,44 @@ impl Simulator {
         }
     }
 
+    pub fn set_wave_enabled(&mut self, enable: bool) {
+        self.wave_enabled = enable;
+    }
+
+    /// Whether `dump_variables` records the current state: a dumper is
+    /// attached, dumping is not stopped and the cycle is in its window.
+    pub fn is_dumping(&self) -> bool {
+        self.wave_enabled
+            && self
+                .dump
+                .as_ref()
+                .is_some_and(|x| x.filter().in_window(self.cycle_count))
+    }
+
+    /// Whether a waveform dumper or activity recorder is attached, so the
+    /// testbench drives clock/reset variables for them to observe.
+    pub fn is_recording(&self) -> bool {
+        self.dump.is_some() || self.saif.is_some()
+    }
+
     pub fn dump_variables(&mut self) {
-        if self.dump.is_some() {
-            if self.comb_dirty {
-                self.do_settle_comb();
-                self.comb_dirty = false;
-            }
+        let dumping = self.is_dumping();
+        if !dumping && self.saif.is_none() {
+            return;
+        }
+        if self.comb_dirty {
+            self.do_settle_comb();
+            self.comb_dirty = false;
+        }
+        if dumping {
             let dump = self.dump.as_mut().unwrap();
             dump.timestamp(self.time);
             dump.dump_all_vars(&self.dump_vars, self.ir.use_4state);
             Self::dump_trace_vars(dump, &self.trace_dump_vars, &self.components);
         }
+        if let Some(saif) = &mut self.saif {
+            saif.sample(self.time);
+        }
     }
 
     fn dump_trace_vars(
@@ -1177,11 +1344,16 @@ impl Simulator {
         dumper.timescale();
         dumper.setup_module(&self.ir.module_variables, &mut self.dump_vars);
         for (comp_idx, comp) in self.components.iter().enumerate() {
-            if comp.host.trace_vars.is_empty() {
+            let filter = dumper.filter();
+            let selected: Vec<_> = (0..comp.host.trace_vars.len())
+                .filter(|x| filter.selects(&comp.name, 1, &comp.host.trace_vars[*x].name))
+                .collect();
+            if selected.is_empty() {
                 continue;
             }
             dumper.add_module(&comp.name);
-            for (trace_idx, var) in comp.host.trace_vars.iter().enumerate() {
+            for trace_idx in selected {
+                let var = &comp.host.trace_vars[trace_idx];
                 let handle = dumper.add_wire(var.width, &var.name);
                 self.trace_dump_vars.push((handle, comp_idx, trace_idx));
             }
@@ -1195,4 +1367,168 @@ impl Simulator {
     pub fn attach_dump(&mut self, dumper: WaveDumper) {
         self.setup_dump(dumper);
     }
+
+    /// Stops waveform dumping and hands back the dumper; dropping it
+    /// finishes the file.
+    pub fn detach_dump(&mut self) -> Option<WaveDumper> {
+        self.dump_vars.clear();
+        self.trace_dump_vars.clear();
+        self.dump.take()
+    }
+
+    /// Starts recording switching activity of every net; see [`crate::saif`].
+    pub fn attach_saif(&mut self) {
+        let mut saif = SaifRecorder::new(&self.ir.module_variables, self.ir.use_4state);
+        saif.sample(self.time);
+        self.saif = Some(saif);
+    }
+
+    /// Writes the activity recorded since `attach_saif` to `path`.
+    pub fn write_saif(&self, path: &std::path::Path) -> Result<(), SimulatorError> {
+        let Some(saif) = &self.saif else {
+            return Ok(());
+        };
+        let io_error = |e: std::io::Error| SimulatorError::IoError {
+            message: format!("failed to write SAIF file {}: {e}", path.display()),
+        };
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path).map_err(io_error)?);
+        saif.write(&mut file, self.time).map_err(io_error)?;
+        file.flush().map_err(io_error)
+    }
+
+    /// Captures the state needed to resume the run later with [`restore`].
+    ///
+    /// Components join through their optional `snapshot` / `restore`
+    /// methods, which write and read a host-provided file; components
+    /// without the hook keep their live state across a restore.
+    ///
+    /// [`restore`]: Self::restore
+    pub fn checkpoint(&mut self) -> Result<Snapshot, SimulatorError> {
+        if self.comb_dirty {
+            self.do_settle_comb();
+            self.comb_dirty = false;
+        }
+        let mut components = Vec::with_capacity(self.components.len());
+        for idx in 0..self.components.len() {
+            let name = self.components[idx].name.clone();
+            if !self.components[idx].checkpoint_hook {
+                log::warn!(
+                    "component `{name}` has no `{SNAPSHOT_METHOD}`/`{RESTORE_METHOD}` methods; its state is not checkpointed"
+                );
+                components.push((name, None));
+                continue;
+            }
+            let path = self.component_state_path(idx);
+            self.call_checkpoint_hook(idx, SNAPSHOT_METHOD, &path)?;
+            let state = std::fs::read(&path);
+            let _ = std::fs::remove_file(&path);
+            let state = state.map_err(|e| SimulatorError::Checkpoint {
+                message: format!("component `{name}` did not write its state: {e}"),
+            })?;
+            components.push((name, Some(state)));
+        }
+        let design = veryl_parser::resource_table::get_str_value(self.ir.name).unwrap_or_default();
+        Ok(Snapshot {
+            design,
+            time: self.time,
+            cycle_count: self.cycle_count,
+            wave_enabled: self.wave_enabled,
+            ff_values: self.ir.ff_values.to_vec(),
+            comb_values: self.ir.comb_values.to_vec(),
+            prev_derived_clock_values: self.prev_derived_clock_values.clone(),
+            random: crate::random_table::snapshot(),
+            files: crate::file_table::snapshot(),
+            components,
+            queues: crate::queue_table::snapshot(),
+        })
+    }
+
+    /// Rewinds the run to `snapshot`, which must come from the same design
+    /// built with the same configuration.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), SimulatorError> {
+        let design = veryl_parser::resource_table::get_str_value(self.ir.name).unwrap_or_default();
+        if snapshot.design != design {
+            return Err(SimulatorError::Checkpoint {
+                message: format!(
+                    "snapshot of `{}` cannot be restored into `{design}`",
+                    snapshot.design
+                ),
+            });
+        }
+        if snapshot.ff_values.len() != self.ir.ff_values.len()
+            || snapshot.comb_values.len() != self.ir.comb_values.len()
+            || snapshot.prev_derived_clock_values.len() != self.prev_derived_clock_values.len()
+        {
+            return Err(SimulatorError::Checkpoint {
+                message: format!(
+                    "snapshot layout does not match `{design}`; it was taken with a different build configuration"
+                ),
+            });
+        }
+        self.ir.ff_values.copy_from_slice(&snapshot.ff_values);
+        self.ir.comb_values.copy_from_slice(&snapshot.comb_values);
+        self.prev_derived_clock_values
+            .copy_from_slice(&snapshot.prev_derived_clock_values);
+        self.time = snapshot.time;
+        self.cycle_count = snapshot.cycle_count;
+        self.wave_enabled = snapshot.wave_enabled;
+        self.comb_dirty = true;
+        crate::random_table::restore(&snapshot.random);
+        crate::file_table::restore(&snapshot.files);
+        crate::queue_table::restore(&snapshot.queues);
+        for (name, state) in &snapshot.components {
+            let Some(state) = state else {
+                continue;
+            };
+            let Some(idx) = self.components.iter().position(|c| c.name == *name) else {
+                return Err(SimulatorError::Checkpoint {
+                    message: format!("snapshot has state for unknown component `{name}`"),
+                });
+            };
+            if !self.components[idx].checkpoint_hook {
+                continue;
+            }
+            let path = self.component_state_path(idx);
+            std::fs::write(&path, state).map_err(|e| SimulatorError::IoError {
+                message: format!("failed to write {}: {e}", path.display()),
+            })?;
+            let result = self.call_checkpoint_hook(idx, RESTORE_METHOD, &path);
+            let _ = std::fs::remove_file(&path);
+            result?;
+        }
+        Ok(())
+    }
+
+    fn component_state_path(&self, idx: usize) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "veryl-checkpoint-{}-{:?}-{}.bin",
+            std::process::id(),
+            std::thread::current().id(),
+            self.components[idx].name
+        ))
+    }
+
+    fn call_checkpoint_hook(
+        &mut self,
+        idx: usize,
+        method: &str,
+        path: &std::path::Path,
+    ) -> Result<(), SimulatorError> {
+        use crate::component::host::HostValue;
+        let c = &mut self.components[idx];
+        c.host.time = self.time;
+        // Checkpoint files are host bookkeeping, not component output.
+        let touched = c.host.touched_files.len();
+        let arg = HostValue::Str(path.display().to_string());
+        let result = c.instance.call_method(&mut c.host, method, &[arg]);
+        c.host.touched_files.truncate(touched);
+        c.drain_logs();
+        let failures = c.host.take_failures();
+        match result {
+            Some(_) if failures.is_empty() => Ok(()),
+            _ => Err(SimulatorError::Checkpoint {
+                message: format!("component `{}` failed in `{method}`", c.name),
+            }),
+        }
+    }
 }
@@ -29,6 +29,18 @@ pub enum SimulatorError {
     #[error("{message}")]
     IoError { message: String },
 
+    #[diagnostic(severity(Error), code(checkpoint))]
+    #[error("checkpoint: {message}")]
+    Checkpoint { message: String },
+
+    #[diagnostic(severity(Error), code(replay))]
+    #[error("replay: {message}")]
+    Replay { message: String },
+
+    #[diagnostic(severity(Error), code(force_target))]
+    #[error("force target \"{path}\" is not a scalar variable of the design")]
+    ForceTarget { path: String },
+
     #[diagnostic(severity(Error), code(unresolved_expression))]
     #[error("unresolved expression")]
     UnresolvedExpression {
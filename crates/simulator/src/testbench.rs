@@ -1,16 +1,24 @@
 use crate::HashMap;
 use crate::assert_buffer;
+use crate::checkpoint::Snapshot;
+use crate::ir::force::ForceSlot;
 use crate::ir::{
     ComponentArg, Event, Expression, Ir, ModuleVariables, RuntimeForRange, Statement,
     SystemFunctionCall, TbMethodKind, Value, VarId, VarPath, format_assert_message, format_output,
     write_native_value,
 };
+use crate::profiler;
 use crate::simulator::Simulator;
 use crate::simulator_error::SimulatorError;
+use crate::watchdog::Watchdog;
 use crate::wave_dumper::WaveDumper;
+use crate::xprop;
+use std::path::Path;
+use veryl_analyzer::attribute::TimeoutItem;
 use veryl_analyzer::ir::{AssertKind, ControlFlow};
 use veryl_analyzer::value::MaskCache;
 use veryl_parser::resource_table::StrId;
+use veryl_parser::token_range::TokenRange;
 
 pub enum TestbenchStatement {
     /// Normal simulator statement (assign, $display, etc.)
@@ -21,6 +29,7 @@ pub enum TestbenchStatement {
         count: Option<Expression>,
         high_time: u64,
         low_time: u64,
+        token: TokenRange,
     },
     /// rst.assert(N)
     ResetAssert {
@@ -29,6 +38,7 @@ pub enum TestbenchStatement {
         duration: u64,
         high_time: u64,
         low_time: u64,
+        token: TokenRange,
     },
     /// $assert / $assert_continue
     Assert {
@@ -36,6 +46,7 @@ pub enum TestbenchStatement {
         condition: Expression,
         format_str: String,
         args: Vec<Expression>,
+        token: TokenRange,
     },
     /// `f.open(name)` / `f.append(name)` — `handle` is the `$tb::file`
     /// variable's name, used as the file-table key.
@@ -81,11 +92,137 @@ pub enum TestbenchStatement {
         signed: bool,
         ret: Option<(VarId, crate::ir::RetWidthCheck)>,
     },
+    /// `x = r.get_dist(v0, w0, ...)`.
+    RandomGetDist {
+        handle: StrId,
+        values: Vec<Expression>,
+        weights: Vec<Expression>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
+    /// `x = r.get_inside(v0, v1, ...)`.
+    RandomGetInside {
+        handle: StrId,
+        values: Vec<Expression>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
+    /// `x = r.get_cyclic(min, max)`.
+    RandomGetCyclic {
+        handle: StrId,
+        min: Expression,
+        max: Expression,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
+    /// `x = r.get_where(min, max, cond, others...)`; `cond` is evaluated
+    /// with each candidate already written to the destination and `others`.
+    RandomGetWhere {
+        handle: StrId,
+        min: Expression,
+        max: Expression,
+        cond: Expression,
+        others: Vec<VarId>,
+        width: u32,
+        signed: bool,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
     /// `x = r.get_seed()`.
     RandomGetSeed {
         handle: StrId,
         ret: Option<(VarId, crate::ir::RetWidthCheck)>,
     },
+    /// `q.push(v)` — `handle` is the `$tb::queue` variable's name.
+    QueuePush {
+        handle: StrId,
+        value: Expression,
+        width: u32,
+        signed: bool,
+    },
+    /// `x = q.pop()` / `x = q.peek()`.
+    QueuePop {
+        handle: StrId,
+        peek: bool,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
+    /// `n = q.size()` / `e = q.empty()`.
+    QueueSize {
+        handle: StrId,
+        empty: bool,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
+    /// `sb.expect(v)` — `handle` is the `$tb::scoreboard` variable's name.
+    ScoreboardExpect {
+        handle: StrId,
+        value: Expression,
+        width: u32,
+        signed: bool,
+    },
+    /// `sb.observe(v)` (`ordered`) / `sb.observe_any(v)`.
+    ScoreboardObserve {
+        handle: StrId,
+        value: Expression,
+        ordered: bool,
+        width: u32,
+        signed: bool,
+    },
+    /// `n = sb.pending()`.
+    ScoreboardPending {
+        handle: StrId,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
+    /// `w.start()` / `w.stop()` on a `$tb::wave` variable.
+    Wave { enable: bool },
+    /// `cp.fork(n)` — only valid at the top level of the initial block,
+    /// where `run_testbench` splits the statement list around it.
+    Fork { handle: StrId, count: Expression },
+    /// `x = cp.branch()`.
+    Branch {
+        handle: StrId,
+        ret: Option<(VarId, crate::ir::RetWidthCheck)>,
+    },
+    /// `cp.save(path)`.
+    CheckpointSave { path: String },
+    /// `cp.load(path)`.
+    CheckpointLoad { path: String },
+    /// `f.force(target, value)`.
+    Force { slot: ForceSlot, value: Expression },
+    /// `f.release(target)`.
+    Release { slot: ForceSlot },
+    /// `f.deposit(target, value)`.
+    Deposit { slot: ForceSlot, value: Expression },
+    /// `cov.point(name)` — `handle` is the `$tb::coverage` variable's name
+    /// (the coverage group).
+    CoverPoint { handle: StrId, name: String },
+    /// `cov.bins(name, values...)`.
+    CoverBins {
+        handle: StrId,
+        name: String,
+        values: Vec<Expression>,
+    },
+    /// `cov.range(name, min, max[, count])`.
+    CoverRange {
+        handle: StrId,
+        name: String,
+        min: Expression,
+        max: Expression,
+        count: Option<Expression>,
+    },
+    /// `cov.cross(name, points...)`.
+    CoverCross {
+        handle: StrId,
+        name: String,
+        points: Vec<String>,
+    },
+    /// `cov.sample(name, value, ...)`.
+    CoverSample {
+        handle: StrId,
+        names: Vec<String>,
+        values: Vec<Expression>,
+    },
     /// if-else (may contain next inside)
     If {
         condition: Expression,
@@ -98,6 +235,23 @@ pub enum TestbenchStatement {
         body: Vec<TestbenchStatement>,
         loop_var: Option<LoopVariable>,
     },
+    /// `#[fork]` block — one statement list per thread, run by the
+    /// cooperative scheduler until all (`join`) or the first (`join_any`)
+    /// of them have finished.
+    ForkJoin {
+        threads: Vec<Vec<TestbenchStatement>>,
+        any: bool,
+    },
+    /// `clk.wait_until(cond[, within])` / `th.wait_until(cond)` — `clock`
+    /// is `None` for the unclocked form.
+    WaitUntil {
+        clock: Option<Event>,
+        cond: Expression,
+        within: Option<Expression>,
+        high_time: u64,
+        low_time: u64,
+        token: TokenRange,
+    },
     /// $finish
     Finish,
 }
@@ -111,6 +265,95 @@ pub struct LoopVariable {
     pub range: RuntimeForRange,
 }
 
+impl LoopVariable {
+    fn write(&self, i: u64) {
+        let val = Value::new(i, self.width, self.signed);
+        unsafe {
+            write_native_value(self.ptr, self.native_bytes, self.use_4state, &val);
+        }
+    }
+}
+
+/// Iteration state of a testbench `for` loop, stepped one index at a time
+/// so fork threads can suspend inside the body.
+enum LoopIter {
+    Count {
+        left: u64,
+    },
+    /// Mirrors the emitted SV `for (int i = hi - 1; i >= lo; i -= step)`;
+    /// i64 makes underflow past lo terminate.
+    Reverse {
+        i: i64,
+        lo: i64,
+        step: i64,
+    },
+    Forward {
+        i: Option<u64>,
+        end: u64,
+        step: u64,
+        op: Option<veryl_analyzer::ir::Op>,
+    },
+}
+
+impl LoopIter {
+    fn new(sim: &mut Simulator, count: u64, loop_var: Option<&LoopVariable>) -> Self {
+        let Some(lv) = loop_var else {
+            return LoopIter::Count { left: count };
+        };
+        let r = &lv.range;
+        let start = r.start.eval(&mut sim.mask_cache);
+        let mut end = r.end.eval(&mut sim.mask_cache);
+        if r.inclusive {
+            end = end.saturating_add(1);
+        }
+        if r.reverse {
+            LoopIter::Reverse {
+                i: end as i64 - 1,
+                lo: start as i64,
+                step: r.step as i64,
+            }
+        } else {
+            LoopIter::Forward {
+                i: Some(start),
+                end,
+                step: r.step,
+                op: r.op,
+            }
+        }
+    }
+
+    fn next(&mut self) -> Option<u64> {
+        match self {
+            LoopIter::Count { left } => {
+                *left = left.checked_sub(1)?;
+                Some(0)
+            }
+            LoopIter::Reverse { i, lo, step } => {
+                let cur = *i;
+                if cur < *lo {
+                    return None;
+                }
+                *i -= *step;
+                Some(cur as u64)
+            }
+            LoopIter::Forward { i, end, step, op } => {
+                let cur = (*i).filter(|x| x < end)?;
+                *i = match op {
+                    // Progress guard: a stalled or faulting step would spin
+                    // forever (const-bound cases are rejected at analysis;
+                    // runtime bounds reach here).
+                    Some(op) => match op.eval(cur as usize, *step as usize) {
+                        Some(n) if n as u64 > cur => Some(n as u64),
+                        _ => None,
+                    },
+                    None => Some(cur + *step),
+                };
+                Some(cur)
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum TestResult {
     Pass,
@@ -165,8 +408,8 @@ fn collect_tb_insts(
 ) {
     for stmt in stmts {
         match stmt {
-            Statement::TbMethodCall { inst, method } => match method {
-                TbMethodKind::ClockNext { .. } => {
+            Statement::TbMethodCall { inst, method, .. } => match method {
+                TbMethodKind::ClockNext { .. } | TbMethodKind::ClockWaitUntil { .. } => {
                     if !clock_insts.contains(inst) {
                         clock_insts.push(*inst);
                     }
@@ -176,8 +419,9 @@ fn collect_tb_insts(
                         reset_insts.push(*inst);
                     }
                 }
-                // File handles, component methods and random generators
-                // drive no clock/reset event.
+                // File handles, component methods, random generators, queues,
+                // scoreboards, wave control, checkpoints, forces, coverage,
+                // thread waits and fork markers drive no clock/reset event.
                 TbMethodKind::FileOpen { .. }
                 | TbMethodKind::FileWrite { .. }
                 | TbMethodKind::FileClose
@@ -186,7 +430,36 @@ fn collect_tb_insts(
                 | TbMethodKind::RandomSeed { .. }
                 | TbMethodKind::RandomGet { .. }
                 | TbMethodKind::RandomGetRange { .. }
-                | TbMethodKind::RandomGetSeed { .. } => {}
+                | TbMethodKind::RandomGetDist { .. }
+                | TbMethodKind::RandomGetInside { .. }
+                | TbMethodKind::RandomGetCyclic { .. }
+                | TbMethodKind::RandomGetWhere { .. }
+                | TbMethodKind::RandomGetSeed { .. }
+                | TbMethodKind::QueuePush { .. }
+                | TbMethodKind::QueuePop { .. }
+                | TbMethodKind::QueueSize { .. }
+                | TbMethodKind::QueueEmpty { .. }
+                | TbMethodKind::ScoreboardExpect { .. }
+                | TbMethodKind::ScoreboardObserve { .. }
+                | TbMethodKind::ScoreboardPending { .. }
+                | TbMethodKind::WaveStart
+                | TbMethodKind::WaveStop
+                | TbMethodKind::CheckpointFork { .. }
+                | TbMethodKind::CheckpointBranch { .. }
+                | TbMethodKind::CheckpointSave { .. }
+                | TbMethodKind::CheckpointLoad { .. }
+                | TbMethodKind::Force { .. }
+                | TbMethodKind::Release { .. }
+                | TbMethodKind::Deposit { .. }
+                | TbMethodKind::CoverPoint { .. }
+                | TbMethodKind::CoverBins { .. }
+                | TbMethodKind::CoverRange { .. }
+                | TbMethodKind::CoverCross { .. }
+                | TbMethodKind::CoverSample { .. }
+                | TbMethodKind::ThreadWaitUntil { .. }
+                | TbMethodKind::ForkBegin { .. }
+                | TbMethodKind::ForkThread
+                | TbMethodKind::ForkEnd => {}
             },
             Statement::For(for_stmt) => {
                 collect_tb_insts(&for_stmt.body, clock_insts, reset_insts);
@@ -241,8 +514,9 @@ fn compute_half_periods(period: u64) -> (u64, u64) {
 fn collect_clock_periods(stmts: &[Statement], periods: &mut HashMap<StrId, u64>) {
     for stmt in stmts {
         match stmt {
-            Statement::TbMethodCall { inst, method } => {
-                if let TbMethodKind::ClockNext { period, .. } = method
+            Statement::TbMethodCall { inst, method, .. } => {
+                if let TbMethodKind::ClockNext { period, .. }
+                | TbMethodKind::ClockWaitUntil { period, .. } = method
                     && let Some(expr) = period
                 {
                     let val = expr.eval(&mut MaskCache::default());
@@ -288,16 +562,7 @@ pub fn convert_initial_to_testbench(
     clock_periods: &HashMap<StrId, u64>,
     default_reset_duration: u64,
 ) -> Vec<TestbenchStatement> {
-    let mut result = Vec::new();
-    for stmt in stmts {
-        result.push(convert_stmt(
-            stmt,
-            event_map,
-            clock_periods,
-            default_reset_duration,
-        ));
-    }
-    result
+    convert_stmts(stmts, event_map, clock_periods, default_reset_duration)
 }
 
 fn convert_stmt(
@@ -307,7 +572,11 @@ fn convert_stmt(
     default_reset_duration: u64,
 ) -> TestbenchStatement {
     match stmt {
-        Statement::TbMethodCall { inst, method } => match method {
+        Statement::TbMethodCall {
+            inst,
+            method,
+            token,
+        } => match method {
             TbMethodKind::ClockNext { count, period } => {
                 let clock = event_map.get(inst).cloned().unwrap_or(Event::Initial);
                 let p = if let Some(expr) = period {
@@ -322,8 +591,38 @@ fn convert_stmt(
                     count: count.clone(),
                     high_time,
                     low_time,
+                    token: *token,
+                }
+            }
+            TbMethodKind::ClockWaitUntil {
+                cond,
+                within,
+                period,
+            } => {
+                let clock = event_map.get(inst).cloned().unwrap_or(Event::Initial);
+                let p = if let Some(expr) = period {
+                    expr.eval(&mut MaskCache::default()).payload_u64()
+                } else {
+                    clock_periods.get(inst).copied().unwrap_or(2)
+                };
+                let (high_time, low_time) = compute_half_periods(p);
+                TestbenchStatement::WaitUntil {
+                    clock: Some(clock),
+                    cond: cond.clone(),
+                    within: within.clone(),
+                    high_time,
+                    low_time,
+                    token: *token,
                 }
             }
+            TbMethodKind::ThreadWaitUntil { cond } => TestbenchStatement::WaitUntil {
+                clock: None,
+                cond: cond.clone(),
+                within: None,
+                high_time: 0,
+                low_time: 0,
+                token: *token,
+            },
             TbMethodKind::ResetAssert { clock, duration } => {
                 let reset = event_map.get(inst).cloned().unwrap_or(Event::Initial);
                 let clock_event = event_map.get(clock).cloned().unwrap_or(Event::Initial);
@@ -341,6 +640,7 @@ fn convert_stmt(
                     duration: dur,
                     high_time,
                     low_time,
+                    token: *token,
                 }
             }
             TbMethodKind::FileOpen { path, append } => TestbenchStatement::FileOpen {
@@ -385,21 +685,191 @@ fn convert_stmt(
                 signed: *signed,
                 ret: *ret,
             },
+            TbMethodKind::RandomGetDist {
+                values,
+                weights,
+                width,
+                signed,
+                ret,
+            } => TestbenchStatement::RandomGetDist {
+                handle: *inst,
+                values: values.clone(),
+                weights: weights.clone(),
+                width: *width,
+                signed: *signed,
+                ret: *ret,
+            },
+            TbMethodKind::RandomGetInside {
+                values,
+                width,
+                signed,
+                ret,
+            } => TestbenchStatement::RandomGetInside {
+                handle: *inst,
+                values: values.clone(),
+                width: *width,
+                signed: *signed,
+                ret: *ret,
+            },
+            TbMethodKind::RandomGetCyclic {
+                min,
+                max,
+                width,
+                signed,
+                ret,
+            } => TestbenchStatement::RandomGetCyclic {
+                handle: *inst,
+                min: min.clone(),
+                max: max.clone(),
+                width: *width,
+                signed: *signed,
+                ret: *ret,
+            },
+            TbMethodKind::RandomGetWhere {
+                min,
+                max,
+                cond,
+                others,
+                width,
+                signed,
+                ret,
+            } => TestbenchStatement::RandomGetWhere {
+                handle: *inst,
+                min: min.clone(),
+                max: max.clone(),
+                cond: cond.clone(),
+                others: others.clone(),
+                width: *width,
+                signed: *signed,
+                ret: *ret,
+            },
             TbMethodKind::RandomGetSeed { ret } => TestbenchStatement::RandomGetSeed {
                 handle: *inst,
                 ret: *ret,
             },
+            TbMethodKind::QueuePush {
+                value,
+                width,
+                signed,
+            } => TestbenchStatement::QueuePush {
+                handle: *inst,
+                value: value.clone(),
+                width: *width,
+                signed: *signed,
+            },
+            TbMethodKind::QueuePop { peek, ret, .. } => TestbenchStatement::QueuePop {
+                handle: *inst,
+                peek: *peek,
+                ret: *ret,
+            },
+            TbMethodKind::QueueSize { ret } => TestbenchStatement::QueueSize {
+                handle: *inst,
+                empty: false,
+                ret: *ret,
+            },
+            TbMethodKind::QueueEmpty { ret } => TestbenchStatement::QueueSize {
+                handle: *inst,
+                empty: true,
+                ret: *ret,
+            },
+            TbMethodKind::ScoreboardExpect {
+                value,
+                width,
+                signed,
+            } => TestbenchStatement::ScoreboardExpect {
+                handle: *inst,
+                value: value.clone(),
+                width: *width,
+                signed: *signed,
+            },
+            TbMethodKind::ScoreboardObserve {
+                value,
+                ordered,
+                width,
+                signed,
+            } => TestbenchStatement::ScoreboardObserve {
+                handle: *inst,
+                value: value.clone(),
+                ordered: *ordered,
+                width: *width,
+                signed: *signed,
+            },
+            TbMethodKind::ScoreboardPending { ret } => TestbenchStatement::ScoreboardPending {
+                handle: *inst,
+                ret: *ret,
+            },
+            TbMethodKind::WaveStart => TestbenchStatement::Wave { enable: true },
+            TbMethodKind::WaveStop => TestbenchStatement::Wave { enable: false },
+            TbMethodKind::CheckpointFork { count } => TestbenchStatement::Fork {
+                handle: *inst,
+                count: count.clone(),
+            },
+            TbMethodKind::CheckpointBranch { ret } => TestbenchStatement::Branch {
+                handle: *inst,
+                ret: *ret,
+            },
+            TbMethodKind::CheckpointSave { path } => {
+                TestbenchStatement::CheckpointSave { path: path.clone() }
+            }
+            TbMethodKind::CheckpointLoad { path } => {
+                TestbenchStatement::CheckpointLoad { path: path.clone() }
+            }
+            TbMethodKind::Force { slot, value } => TestbenchStatement::Force {
+                slot: *slot,
+                value: value.clone(),
+            },
+            TbMethodKind::Release { slot } => TestbenchStatement::Release { slot: *slot },
+            TbMethodKind::Deposit { slot, value } => TestbenchStatement::Deposit {
+                slot: *slot,
+                value: value.clone(),
+            },
+            TbMethodKind::CoverPoint { name } => TestbenchStatement::CoverPoint {
+                handle: *inst,
+                name: name.clone(),
+            },
+            TbMethodKind::CoverBins { name, values } => TestbenchStatement::CoverBins {
+                handle: *inst,
+                name: name.clone(),
+                values: values.clone(),
+            },
+            TbMethodKind::CoverRange {
+                name,
+                min,
+                max,
+                count,
+            } => TestbenchStatement::CoverRange {
+                handle: *inst,
+                name: name.clone(),
+                min: min.clone(),
+                max: max.clone(),
+                count: count.clone(),
+            },
+            TbMethodKind::CoverCross { name, points } => TestbenchStatement::CoverCross {
+                handle: *inst,
+                name: name.clone(),
+                points: points.clone(),
+            },
+            TbMethodKind::CoverSample { names, values } => TestbenchStatement::CoverSample {
+                handle: *inst,
+                names: names.clone(),
+                values: values.clone(),
+            },
+            TbMethodKind::ForkBegin { .. } | TbMethodKind::ForkThread | TbMethodKind::ForkEnd => {
+                unreachable!("fork markers are regrouped by `convert_stmts`")
+            }
         },
         Statement::SystemFunctionCall(SystemFunctionCall::Assert {
             kind,
             condition,
             format_str,
             args,
+            token,
         }) => TestbenchStatement::Assert {
             kind: *kind,
             condition: condition.clone(),
             format_str: format_str.clone(),
             args: args.clone(),
+            token: **token,
         },
         Statement::SystemFunctionCall(SystemFunctionCall::Finish) => TestbenchStatement::Finish,
         Statement::If(if_stmt) => {
@@ -427,11 +897,12 @@ fn convert_stmt(
             }
         }
         Statement::For(for_stmt) => {
-            let body = for_stmt
-                .body
-                .iter()
-                .map(|s| convert_stmt(s, event_map, clock_periods, default_reset_duration))
-                .collect();
+            let body = convert_stmts(
+                &for_stmt.body,
+                event_map,
+                clock_periods,
+                default_reset_duration,
+            );
             TestbenchStatement::For {
                 count: 0, // unused when loop_var is Some
                 body,
@@ -476,38 +947,78 @@ fn convert_stmts(
     clock_periods: &HashMap<StrId, u64>,
     default_reset_duration: u64,
 ) -> Vec<TestbenchStatement> {
-    stmts
-        .iter()
-        .map(|s| convert_stmt(s, event_map, clock_periods, default_reset_duration))
-        .collect()
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < stmts.len() {
+        let Statement::TbMethodCall {
+            method: TbMethodKind::ForkBegin { any },
+            ..
+        } = &stmts[i]
+        else {
+            result.push(convert_stmt(
+                &stmts[i],
+                event_map,
+                clock_periods,
+                default_reset_duration,
+            ));
+            i += 1;
+            continue;
+        };
+        // A `#[fork]` statement is lowered to a flat `ForkBegin`,
+        // (`ForkThread`, thread...)*, `ForkEnd` sequence; regroup it here,
+        // skipping markers of nested forks.
+        let mut threads = Vec::new();
+        let mut start = None;
+        let mut depth = 0;
+        i += 1;
+        while i < stmts.len() {
+            let marker = match &stmts[i] {
+                Statement::TbMethodCall { method, .. } => method,
+                _ => {
+                    i += 1;
+                    continue;
+                }
+            };
+            match marker {
+                TbMethodKind::ForkBegin { .. } => depth += 1,
+                TbMethodKind::ForkThread | TbMethodKind::ForkEnd if depth == 0 => {
+                    if let Some(start) = start {
+                        threads.push(convert_stmts(
+                            &stmts[start..i],
+                            event_map,
+                            clock_periods,
+                            default_reset_duration,
+                        ));
+                    }
+                    if matches!(marker, TbMethodKind::ForkEnd) {
+                        break;
+                    }
+                    start = Some(i + 1);
+                }
+                TbMethodKind::ForkEnd => depth -= 1,
+                _ => {}
+            }
+            i += 1;
+        }
+        result.push(TestbenchStatement::ForkJoin { threads, any: *any });
+        i += 1;
+    }
+    result
 }
 
 pub fn run_testbench(sim: &mut Simulator, stmts: &[TestbenchStatement]) -> TestResult {
     assert_buffer::reset();
     crate::file_table::reset();
     crate::random_table::reset(sim.ir.seed);
-    let result: TestResult = exec(sim, stmts).into();
-    // End-of-test component hooks may still record failures.
-    sim.finish_components();
-    let component_failures = sim.take_component_failures();
+    crate::queue_table::reset();
+    crate::checkpoint::reset();
+    let result = run_forked(sim, stmts);
     crate::file_table::finalize();
     for component in &sim.components {
         for path in &component.host.touched_files {
             log::debug!("component `{}` touched file: {path}", component.name);
         }
     }
-    // Component failures (typically from `on_finish`) are appended to an
-    // already-failing result rather than dropped.
-    let failure = match (result, assert_buffer::take_failure()) {
-        (TestResult::Fail(msg), _) => Some(msg),
-        (TestResult::Pass, assert_failure) => assert_failure,
-    };
-    let result = match failure {
-        None if component_failures.is_empty() => TestResult::Pass,
-        None => TestResult::Fail(component_failures.join("\n")),
-        Some(msg) if component_failures.is_empty() => TestResult::Fail(msg),
-        Some(msg) => TestResult::Fail(format!("{msg}\n{}", component_failures.join("\n"))),
-    };
     // Component behavior may be seed-dependent; make failures reproducible.
     match result {
         TestResult::Fail(msg) if sim.components.is_empty() => TestResult::Fail(msg),
@@ -516,6 +1027,91 @@ pub fn run_testbench(sim: &mut Simulator, stmts: &[TestbenchStatement]) -> TestR
     }
 }
 
+/// Runs `stmts`, splitting them at the first top-level `fork(n)`: the prefix
+/// runs once, then each of the `n` branches restores the checkpoint taken
+/// there and runs the remainder as an independent test. Only branch 0 is
+/// recorded to the waveform and SAIF outputs, since later branches rewind
+/// time.
+fn run_forked(sim: &mut Simulator, stmts: &[TestbenchStatement]) -> TestResult {
+    let Some(pos) = stmts
+        .iter()
+        .position(|s| matches!(s, TestbenchStatement::Fork { .. }))
+    else {
+        let result = run(sim, stmts);
+        return finish(sim, result);
+    };
+    let TestbenchStatement::Fork { handle, count } = &stmts[pos] else {
+        unreachable!()
+    };
+    let prefix = run(sim, &stmts[..pos]);
+    if prefix.should_stop() {
+        return finish(sim, prefix);
+    }
+    sim.ensure_comb_updated();
+    let n = count.eval(&mut sim.mask_cache).payload_u64();
+    if n == 0 {
+        return finish(
+            sim,
+            ExecResult::Fail("`fork()` needs at least one branch".into()),
+        );
+    }
+    let snapshot = match sim.checkpoint() {
+        Ok(x) => x,
+        Err(err) => return finish(sim, ExecResult::Fail(err.to_string())),
+    };
+    // `$assert_continue` failures of the shared prefix are reported once.
+    let mut failures: Vec<String> = assert_buffer::take_failure().into_iter().collect();
+    let mut recorders = None;
+    for branch in 0..n {
+        if branch > 0 {
+            if recorders.is_none() {
+                recorders = Some((sim.dump.take(), sim.saif.take()));
+            }
+            if let Err(err) = sim.restore(&snapshot) {
+                failures.push(format!("fork branch {branch}: {err}"));
+                break;
+            }
+        }
+        crate::checkpoint::set_branch(*handle, branch);
+        if let TestResult::Fail(msg) = run_forked(sim, &stmts[pos + 1..]) {
+            failures.push(format!("fork branch {branch}: {msg}"));
+        }
+    }
+    if let Some((dump, saif)) = recorders {
+        sim.dump = dump;
+        sim.saif = saif;
+    }
+    if failures.is_empty() {
+        TestResult::Pass
+    } else {
+        TestResult::Fail(failures.join("\n"))
+    }
+}
+
+/// Fires the end-of-test component hooks and merges their failures, and the
+/// items scoreboards still expect, with the testbench result and any pending
+/// assertion failures.
+fn finish(sim: &mut Simulator, result: ExecResult) -> TestResult {
+    let result: TestResult = result.into();
+    // End-of-test component hooks may still record failures.
+    sim.finish_components();
+    let mut end_failures = sim.take_component_failures();
+    end_failures.extend(crate::queue_table::unmatched());
+    // Component failures (typically from `on_finish`) and unmatched
+    // scoreboard items are appended to an already-failing result rather than
+    // dropped.
+    let failure = match (result, assert_buffer::take_failure()) {
+        (TestResult::Fail(msg), _) => Some(msg),
+        (TestResult::Pass, assert_failure) => assert_failure,
+    };
+    match failure {
+        None if end_failures.is_empty() => TestResult::Pass,
+        None => TestResult::Fail(end_failures.join("\n")),
+        Some(msg) if end_failures.is_empty() => TestResult::Fail(msg),
+        Some(msg) => TestResult::Fail(format!("{msg}\n{}", end_failures.join("\n"))),
+    }
+}
+
 /// Run a native testbench from a simulator IR.
 ///
 /// `module_name` must be pre-resolved from `ir.name` on the main thread
@@ -536,7 +1132,28 @@ pub fn run_native_testbench_capped(
     module_name: String,
     max_cycles: Option<u64>,
 ) -> Result<TestResult, SimulatorError> {
-    // The dump attaches after `init_components` so component trace
+    run_native_testbench_with(
+        ir,
+        dump,
+        None,
+        module_name,
+        max_cycles,
+        TimeoutItem::default(),
+    )
+}
+
+/// Like [`run_native_testbench_capped`], additionally recording switching
+/// activity into the SAIF file `saif` when given, and failing the test once
+/// a `timeout` limit is exceeded.
+pub fn run_native_testbench_with(
+    ir: Ir,
+    dump: Option<WaveDumper>,
+    saif: Option<&Path>,
+    module_name: String,
+    max_cycles: Option<u64>,
+    timeout: TimeoutItem,
+) -> Result<TestResult, SimulatorError> {
+    // The dump attaches after `init_components` so component trace
     // variables (registered during `create`) land in the waveform header.
     let mut sim = Simulator::new(ir, None);
     sim.cycle_limit = max_cycles;
@@ -549,6 +1166,9 @@ pub fn run_native_testbench_capped(
     if let Some(dump) = dump {
         sim.attach_dump(dump);
     }
+    if saif.is_some() {
+        sim.attach_saif();
+    }
     let event_map = build_event_map(&sim.ir.event_statements, &sim.ir.module_variables);
     let clock_periods = build_clock_periods(&sim.ir.event_statements);
 
@@ -560,6 +1180,11 @@ pub fn run_native_testbench_capped(
         .ok_or_else(|| SimulatorError::no_initial_block(&module_name, &token))?;
 
     let tb_stmts = convert_initial_to_testbench(initial_stmts, &event_map, &clock_periods, 3);
+    // Started last so the wall-clock limit covers only the run itself.
+    if timeout != TimeoutItem::default() {
+        let watchdog = Watchdog::new(timeout, &sim.ir.module_variables, sim.ir.use_4state);
+        sim.watchdog = Some(watchdog);
+    }
     let result = run_testbench(&mut sim, &tb_stmts);
 
     #[cfg(feature = "profile")]
@@ -612,11 +1237,21 @@ pub fn run_native_testbench_capped(
         eprintln!("===========================");
     }
 
+    if let Some(path) = saif {
+        sim.write_saif(path)?;
+    }
+    xprop::record(sim.xprop_events());
+    if let Some(profile) = sim.profile_report() {
+        profiler::record(profile);
+    }
     Ok(result)
 }
 
 fn exec(sim: &mut Simulator, stmts: &[TestbenchStatement]) -> ExecResult {
     for stmt in stmts {
+        if let Some(msg) = watchdog_statement(sim) {
+            return ExecResult::Fail(msg);
+        }
         let result = exec_one(sim, stmt);
         if result.should_stop() {
             return result;
@@ -628,6 +1263,439 @@ fn exec(sim: &mut Simulator, stmts: &[TestbenchStatement]) -> ExecResult {
     ExecResult::Continue
 }
 
+/// Runs `stmts` in order, under the thread scheduler when they contain a
+/// `#[fork]` so threads left running by a `join_any` keep running alongside
+/// the statements after it.
+fn run(sim: &mut Simulator, stmts: &[TestbenchStatement]) -> ExecResult {
+    if stmts.iter().any(has_fork) {
+        run_threads(sim, stmts)
+    } else {
+        exec(sim, stmts)
+    }
+}
+
+fn has_fork(stmt: &TestbenchStatement) -> bool {
+    match stmt {
+        TestbenchStatement::ForkJoin { .. } => true,
+        TestbenchStatement::If {
+            then_block,
+            else_block,
+            ..
+        } => then_block.iter().chain(else_block).any(has_fork),
+        TestbenchStatement::For { body, .. } => body.iter().any(has_fork),
+        _ => false,
+    }
+}
+
+const WAIT_DEADLOCK: &str =
+    "`wait_until` condition is false and no running thread or clock can change it";
+
+fn wait_timeout(within: u64) -> String {
+    format!("`wait_until` condition not met within {within} cycle(s)")
+}
+
+fn eval_u64(sim: &mut Simulator, expr: &Expression) -> u64 {
+    sim.ensure_comb_updated();
+    expr.eval(&mut sim.mask_cache).payload_u64()
+}
+
+fn cond_met(sim: &mut Simulator, cond: &Expression) -> bool {
+    eval_u64(sim, cond) != 0
+}
+
+/// A clock a blocked thread waits on, with what [`clock_cycle`] needs to
+/// drive it.
+#[derive(Clone, Copy)]
+struct ClockWait<'a> {
+    event: &'a Event,
+    high_time: u64,
+    low_time: u64,
+    token: &'a TokenRange,
+}
+
+/// A `#[fork]` thread: the statement lists it is executing, innermost last.
+struct Thread<'a> {
+    frames: Vec<Frame<'a>>,
+    wait: Wait<'a>,
+    done: bool,
+}
+
+impl<'a> Thread<'a> {
+    fn new(stmts: &'a [TestbenchStatement]) -> Self {
+        Thread {
+            frames: vec![Frame::Block { stmts, pc: 0 }],
+            wait: Wait::Ready,
+            done: false,
+        }
+    }
+}
+
+enum Frame<'a> {
+    Block {
+        stmts: &'a [TestbenchStatement],
+        pc: usize,
+    },
+    Loop {
+        body: &'a [TestbenchStatement],
+        loop_var: Option<&'a LoopVariable>,
+        iter: LoopIter,
+    },
+}
+
+enum Wait<'a> {
+    Ready,
+    /// `clk.next(n)` — `left` more cycles of `clock`.
+    Clock {
+        clock: ClockWait<'a>,
+        left: u64,
+    },
+    /// `wait_until` — `left` is the remaining `within` budget, if any.
+    Until {
+        clock: Option<ClockWait<'a>>,
+        cond: &'a Expression,
+        left: Option<u64>,
+        within: u64,
+    },
+    /// A nested `#[fork]`, waiting for all or any of `children`.
+    Join {
+        children: Vec<usize>,
+        any: bool,
+    },
+}
+
+impl<'a> Wait<'a> {
+    fn clock(&self) -> Option<ClockWait<'a>> {
+        match self {
+            Wait::Clock { clock, .. } => Some(*clock),
+            Wait::Until { clock, .. } => *clock,
+            Wait::Ready | Wait::Join { .. } => None,
+        }
+    }
+}
+
+/// Runs `stmts` as the main thread of a cooperative scheduler. Threads run
+/// in spawn order until each blocks on a clock cycle, a `wait_until` or a
+/// join; once all are blocked, the clock whose next edge comes first (ties
+/// go to the earliest waiting thread) advances one cycle. The result is
+/// therefore deterministic. The test ends with the main thread; threads a
+/// `join_any` left running are dropped then. `rst.assert()` runs atomically
+/// inside its thread.
+fn run_threads(sim: &mut Simulator, stmts: &[TestbenchStatement]) -> ExecResult {
+    let mut threads = vec![Thread::new(stmts)];
+    // Virtual time of each clock's next edge, so clocks of different
+    // periods interleave by period.
+    let mut edges: Vec<(&Event, u64)> = Vec::new();
+    loop {
+        let mut progressed = true;
+        while progressed {
+            progressed = false;
+            let mut i = 0;
+            while i < threads.len() {
+                match poll(sim, &mut threads, i) {
+                    Ok(false) => {}
+                    Ok(true) => {
+                        progressed = true;
+                        let result = step(sim, &mut threads, i);
+                        if result.should_stop() {
+                            return result;
+                        }
+                        if threads[0].done {
+                            return ExecResult::Continue;
+                        }
+                    }
+                    Err(msg) => return ExecResult::Fail(msg),
+                }
+                i += 1;
+            }
+        }
+
+        let mut next: Option<(ClockWait, u64)> = None;
+        for thread in threads.iter().filter(|x| !x.done) {
+            let Some(clock) = thread.wait.clock() else {
+                continue;
+            };
+            let at = match edges.iter().find(|(x, _)| *x == clock.event) {
+                Some((_, at)) => *at,
+                None => {
+                    edges.push((clock.event, 0));
+                    0
+                }
+            };
+            if next.is_none_or(|(_, best)| at < best) {
+                next = Some((clock, at));
+            }
+        }
+        let Some((clock, at)) = next else {
+            return ExecResult::Fail(WAIT_DEADLOCK.into());
+        };
+        if let Some(result) = clock_cycle(
+            sim,
+            clock.event,
+            clock.high_time,
+            clock.low_time,
+            clock.token,
+        ) {
+            return result;
+        }
+        for (event, next_at) in &mut edges {
+            if *event == clock.event {
+                *next_at = at + clock.high_time + clock.low_time;
+            }
+        }
+        for thread in threads.iter_mut().filter(|x| !x.done) {
+            match &mut thread.wait {
+                Wait::Clock { clock: x, left } if x.event == clock.event => {
+                    *left -= 1;
+                    if *left == 0 {
+                        thread.wait = Wait::Ready;
+                    }
+                }
+                Wait::Until {
+                    clock: Some(x),
+                    left: Some(left),
+                    ..
+                } if x.event == clock.event => *left = left.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Whether thread `i` can run now, failing once a `within` budget is spent.
+fn poll(sim: &mut Simulator, threads: &mut [Thread], i: usize) -> Result<bool, String> {
+    let thread = &threads[i];
+    if thread.done {
+        return Ok(false);
+    }
+    let ready = match &thread.wait {
+        Wait::Ready => true,
+        Wait::Clock { .. } => false,
+        Wait::Until {
+            cond, left, within, ..
+        } => {
+            if cond_met(sim, cond) {
+                true
+            } else if *left == Some(0) {
+                return Err(wait_timeout(*within));
+            } else {
+                false
+            }
+        }
+        Wait::Join { children, any } => {
+            let mut done = children.iter().map(|x| threads[*x].done);
+            if *any {
+                done.any(|x| x)
+            } else {
+                done.all(|x| x)
+            }
+        }
+    };
+    if ready {
+        threads[i].wait = Wait::Ready;
+    }
+    Ok(ready)
+}
+
+/// Runs thread `i` until it blocks or finishes.
+fn step<'a>(sim: &mut Simulator, threads: &mut Vec<Thread<'a>>, i: usize) -> ExecResult {
+    loop {
+        let thread = &mut threads[i];
+        let stmt = match thread.frames.last_mut() {
+            None => {
+                thread.done = true;
+                return ExecResult::Continue;
+            }
+            Some(Frame::Loop {
+                body,
+                loop_var,
+                iter,
+            }) => {
+                if let Some(x) = iter.next() {
+                    if let Some(lv) = loop_var {
+                        lv.write(x);
+                    }
+                    let stmts = *body;
+                    thread.frames.push(Frame::Block { stmts, pc: 0 });
+                } else {
+                    thread.frames.pop();
+                }
+                continue;
+            }
+            Some(Frame::Block { stmts, pc }) => {
+                let stmts: &'a [TestbenchStatement] = stmts;
+                let Some(stmt) = stmts.get(*pc) else {
+                    thread.frames.pop();
+                    continue;
+                };
+                *pc += 1;
+                stmt
+            }
+        };
+        if let Some(msg) = watchdog_statement(sim) {
+            return ExecResult::Fail(msg);
+        }
+        match stmt {
+            TestbenchStatement::If {
+                condition,
+                then_block,
+                else_block,
+            } => {
+                let stmts = if cond_met(sim, condition) {
+                    then_block
+                } else {
+                    else_block
+                };
+                threads[i].frames.push(Frame::Block { stmts, pc: 0 });
+            }
+            TestbenchStatement::For {
+                count,
+                body,
+                loop_var,
+            } => {
+                let iter = LoopIter::new(sim, *count, loop_var.as_ref());
+                threads[i].frames.push(Frame::Loop {
+                    body,
+                    loop_var: loop_var.as_ref(),
+                    iter,
+                });
+            }
+            TestbenchStatement::ClockNext {
+                clock,
+                count,
+                high_time,
+                low_time,
+                token,
+            } => {
+                let left = count.as_ref().map_or(1, |x| eval_u64(sim, x));
+                if left > 0 {
+                    let clock = ClockWait {
+                        event: clock,
+                        high_time: *high_time,
+                        low_time: *low_time,
+                        token,
+                    };
+                    threads[i].wait = Wait::Clock { clock, left };
+                    return ExecResult::Continue;
+                }
+            }
+            TestbenchStatement::WaitUntil {
+                clock,
+                cond,
+                within,
+                high_time,
+                low_time,
+                token,
+            } => {
+                if !cond_met(sim, cond) {
+                    let within = within.as_ref().map(|x| eval_u64(sim, x));
+                    threads[i].wait = Wait::Until {
+                        clock: clock.as_ref().map(|event| ClockWait {
+                            event,
+                            high_time: *high_time,
+                            low_time: *low_time,
+                            token,
+                        }),
+                        cond,
+                        left: within,
+                        within: within.unwrap_or_default(),
+                    };
+                    return ExecResult::Continue;
+                }
+            }
+            TestbenchStatement::ForkJoin {
+                threads: bodies, ..
+            } if bodies.is_empty() => {}
+            TestbenchStatement::ForkJoin {
+                threads: bodies,
+                any,
+            } => {
+                let children = (threads.len()..threads.len() + bodies.len()).collect();
+                threads.extend(bodies.iter().map(|x| Thread::new(x)));
+                threads[i].wait = Wait::Join {
+                    children,
+                    any: *any,
+                };
+                return ExecResult::Continue;
+            }
+            other => {
+                match exec_one(sim, other) {
+                    ExecResult::Continue => {}
+                    // `break` leaves the innermost loop of the thread.
+                    ExecResult::Break => {
+                        let frames = &mut threads[i].frames;
+                        while let Some(frame) = frames.pop() {
+                            if matches!(frame, Frame::Loop { .. }) {
+                                break;
+                            }
+                        }
+                    }
+                    result => return result,
+                }
+                if assert_buffer::has_fatal() {
+                    return ExecResult::Fail(assert_buffer::take_failure().unwrap_or_default());
+                }
+            }
+        }
+    }
+}
+
+/// Ends a cycle driven by the statement at `token` on the watchdog, if any.
+fn watchdog_tick(sim: &mut Simulator, token: &TokenRange) -> Option<String> {
+    sim.watchdog.as_ref()?;
+    sim.ensure_comb_updated();
+    let time = sim.time;
+    sim.watchdog.as_mut()?.tick(time, token)
+}
+
+/// Counts one testbench statement on the watchdog, if any.
+fn watchdog_statement(sim: &mut Simulator) -> Option<String> {
+    sim.watchdog.as_mut()?.statement()
+}
+
+/// Runs one cycle of `clock`, returning the result that ends the test early
+/// (component failure or finish request, watchdog timeout, cycle cap).
+fn clock_cycle(
+    sim: &mut Simulator,
+    clock: &Event,
+    high_time: u64,
+    low_time: u64,
+    token: &TokenRange,
+) -> Option<ExecResult> {
+    let has_dump = sim.is_recording();
+    if has_dump && let Some(id) = clock.var_id() {
+        sim.set_var_by_id(&id, Value::new(1, 1, false));
+    }
+    sim.step(clock);
+    sim.time += high_time;
+    if has_dump {
+        if let Some(id) = clock.var_id() {
+            sim.set_var_by_id(&id, Value::new(0, 1, false));
+        }
+        sim.dump_variables();
+    }
+    sim.time += low_time;
+    // Component-requested termination, checked at cycle end (after commit
+    // and dump).
+    if !sim.components.is_empty() {
+        if sim.components_failed() {
+            return Some(ExecResult::Fail(sim.take_component_failures().join("\n")));
+        }
+        if sim.component_finish_requested() {
+            return Some(ExecResult::Finished);
+        }
+    }
+    if let Some(msg) = watchdog_tick(sim, token) {
+        return Some(ExecResult::Fail(msg));
+    }
+    // Stop once the optional clock-cycle cap is reached. The count also
+    // drives the waveform cycle window.
+    sim.cycle_count += 1;
+    if sim.cycle_limit.is_some_and(|x| sim.cycle_count >= x) {
+        return Some(ExecResult::Finished);
+    }
+    None
+}
+
 fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
     match stmt {
         TestbenchStatement::Stmt(s) => {
@@ -645,6 +1713,7 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
             count,
             high_time,
             low_time,
+            token,
         } => {
             let n = if let Some(expr) = count {
                 sim.ensure_comb_updated();
@@ -655,37 +1724,9 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
             } else {
                 1
             };
-            let has_dump = sim.dump.is_some();
-            let has_components = !sim.components.is_empty();
             for _ in 0..n {
-                if has_dump && let Some(id) = clock.var_id() {
-                    sim.set_var_by_id(&id, Value::new(1, 1, false));
-                }
-                sim.step(clock);
-                sim.time += high_time;
-                if has_dump {
-                    if let Some(id) = clock.var_id() {
-                        sim.set_var_by_id(&id, Value::new(0, 1, false));
-                    }
-                    sim.dump_variables();
-                }
-                sim.time += low_time;
-                // Component-requested termination, checked at cycle end
-                // (after commit and dump).
-                if has_components {
-                    if sim.components_failed() {
-                        return ExecResult::Fail(sim.take_component_failures().join("\n"));
-                    }
-                    if sim.component_finish_requested() {
-                        return ExecResult::Finished;
-                    }
-                }
-                // Stop once the optional clock-cycle cap is reached.
-                if let Some(limit) = sim.cycle_limit {
-                    sim.cycle_count += 1;
-                    if sim.cycle_count >= limit {
-                        return ExecResult::Finished;
-                    }
+                if let Some(result) = clock_cycle(sim, clock, *high_time, *low_time, token) {
+                    return result;
                 }
             }
             ExecResult::Continue
@@ -696,11 +1737,12 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
             duration,
             high_time,
             low_time,
+            token,
         } => {
             // Step reset event for `duration` cycles.
             // In this simulator, Event::Reset represents a clock edge
             // with reset asserted (executes the if_reset branch of always_ff).
-            let has_dump = sim.dump.is_some();
+            let has_dump = sim.is_recording();
             if has_dump && let Some(id) = reset.var_id() {
                 sim.set_var_by_id(&id, Value::new(1, 1, false));
             }
@@ -717,6 +1759,9 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
                     sim.dump_variables();
                 }
                 sim.time += low_time;
+                if let Some(msg) = watchdog_tick(sim, token) {
+                    return ExecResult::Fail(msg);
+                }
             }
             if has_dump && let Some(id) = reset.var_id() {
                 sim.set_var_by_id(&id, Value::new(0, 1, false));
@@ -728,14 +1773,15 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
             condition,
             format_str,
             args,
+            token,
         } => {
             sim.ensure_comb_updated();
             let val = condition.eval(&mut sim.mask_cache);
             if val.payload_u64() == 0 {
                 let msg = format_assert_message(format_str, args, &mut sim.mask_cache);
                 match kind {
-                    AssertKind::Fatal => assert_buffer::record_fatal(msg),
-                    AssertKind::Continue => assert_buffer::record_continue(msg),
+                    AssertKind::Fatal => assert_buffer::record_fatal(msg, *token),
+                    AssertKind::Continue => assert_buffer::record_continue(msg, *token),
                 }
             }
             ExecResult::Continue
@@ -758,80 +1804,17 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
             body,
             loop_var,
         } => {
-            if let Some(lv) = loop_var {
-                let r = &lv.range;
-                let start = r.start.eval(&mut sim.mask_cache);
-                let mut end = r.end.eval(&mut sim.mask_cache);
-                if r.inclusive {
-                    end = end.saturating_add(1);
-                }
-                let step = r.step;
-                let op = r.op;
-                let reverse = r.reverse;
-                let mut step_body = |i: u64| -> ExecResult {
-                    let val = Value::new(i, lv.width, lv.signed);
-                    unsafe {
-                        write_native_value(lv.ptr, lv.native_bytes, lv.use_4state, &val);
-                    }
-                    exec(sim, body)
-                };
-                let mut loop_result = ExecResult::Continue;
-                if reverse {
-                    // Mirror the emitted SV `for (int i = hi - 1; i >= lo;
-                    // i -= step)`; i64 makes underflow past lo terminate.
-                    let mut i = end as i64 - 1;
-                    let lo = start as i64;
-                    let step = step as i64;
-                    while i >= lo {
-                        let result = step_body(i as u64);
-                        if result.should_stop() {
-                            loop_result = result;
-                            break;
-                        }
-                        i -= step;
-                    }
-                } else if let Some(op) = op {
-                    let mut i = start;
-                    while i < end {
-                        let result = step_body(i);
-                        if result.should_stop() {
-                            loop_result = result;
-                            break;
-                        }
-                        // Progress guard: a stalled or faulting step would
-                        // spin forever (const-bound cases are rejected at
-                        // analysis; runtime bounds reach here).
-                        match op.eval(i as usize, step as usize) {
-                            Some(n) if n as u64 > i => i = n as u64,
-                            _ => break,
-                        }
-                    }
-                } else {
-                    let mut i = start;
-                    while i < end {
-                        let result = step_body(i);
-                        if result.should_stop() {
-                            loop_result = result;
-                            break;
-                        }
-                        i += step;
-                    }
+            let mut iter = LoopIter::new(sim, *count, loop_var.as_ref());
+            while let Some(i) = iter.next() {
+                if let Some(lv) = loop_var {
+                    lv.write(i);
                 }
-                if matches!(loop_result, ExecResult::Break) {
-                    return ExecResult::Continue;
+                let result = exec(sim, body);
+                if matches!(result, ExecResult::Break) {
+                    break;
                 }
-                if loop_result.should_stop() {
-                    return loop_result;
-                }
-            } else {
-                for _ in 0..*count {
-                    let result = exec(sim, body);
-                    if matches!(result, ExecResult::Break) {
-                        break;
-                    }
-                    if result.should_stop() {
-                        return result;
-                    }
+                if result.should_stop() {
+                    return result;
                 }
             }
             ExecResult::Continue
@@ -949,6 +1932,115 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
             }
             ExecResult::Continue
         }
+        TestbenchStatement::RandomGetDist {
+            handle,
+            values,
+            weights,
+            width,
+            signed,
+            ret,
+        } => {
+            sim.ensure_comb_updated();
+            let values: Vec<_> = values
+                .iter()
+                .map(|x| x.eval(&mut sim.mask_cache).payload_u64())
+                .collect();
+            let weights: Vec<_> = weights
+                .iter()
+                .map(|x| x.eval(&mut sim.mask_cache).payload_u64())
+                .collect();
+            match crate::random_table::get_dist(*handle, &values, &weights, *width, *signed) {
+                Ok(value) => {
+                    if let Some((ret, _)) = ret {
+                        sim.set_var_by_id(ret, value);
+                    }
+                    ExecResult::Continue
+                }
+                Err(msg) => ExecResult::Fail(msg),
+            }
+        }
+        TestbenchStatement::RandomGetInside {
+            handle,
+            values,
+            width,
+            signed,
+            ret,
+        } => {
+            sim.ensure_comb_updated();
+            let values: Vec<_> = values
+                .iter()
+                .map(|x| x.eval(&mut sim.mask_cache).payload_u64())
+                .collect();
+            let value = crate::random_table::get_inside(*handle, &values, *width, *signed);
+            if let Some((ret, _)) = ret {
+                sim.set_var_by_id(ret, value);
+            }
+            ExecResult::Continue
+        }
+        TestbenchStatement::RandomGetCyclic {
+            handle,
+            min,
+            max,
+            width,
+            signed,
+            ret,
+        } => {
+            sim.ensure_comb_updated();
+            let min_v = min.eval(&mut sim.mask_cache).payload_u64();
+            let max_v = max.eval(&mut sim.mask_cache).payload_u64();
+            match crate::random_table::get_cyclic(*handle, min_v, max_v, *width, *signed) {
+                Ok(value) => {
+                    if let Some((ret, _)) = ret {
+                        sim.set_var_by_id(ret, value);
+                    }
+                    ExecResult::Continue
+                }
+                Err(msg) => ExecResult::Fail(msg),
+            }
+        }
+        TestbenchStatement::RandomGetWhere {
+            handle,
+            min,
+            max,
+            cond,
+            others,
+            width,
+            signed,
+            ret,
+        } => {
+            // The condition sees each candidate through the destination, so
+            // `x = r.get_where(0, 15, x != y)` constrains `x` against `y`;
+            // with `y` listed in `others` both are drawn on every attempt.
+            let Some((ret, _)) = ret else {
+                return ExecResult::Fail(
+                    "`get_where` must be assigned to a variable its condition reads".into(),
+                );
+            };
+            sim.ensure_comb_updated();
+            let min_v = min.eval(&mut sim.mask_cache).payload_u64();
+            let max_v = max.eval(&mut sim.mask_cache).payload_u64();
+            for _ in 0..crate::random_table::WHERE_ATTEMPTS {
+                for var in std::iter::once(ret).chain(others) {
+                    let value =
+                        crate::random_table::get_range(*handle, min_v, max_v, *width, *signed);
+                    sim.set_var_by_id(var, value);
+                }
+                sim.ensure_comb_updated();
+                if cond.eval(&mut sim.mask_cache).payload_u64() != 0 {
+                    return ExecResult::Continue;
+                }
+            }
+            ExecResult::Fail(format!(
+                "`get_where` found no {} satisfying its condition in {} attempts (seed {})",
+                if others.is_empty() {
+                    "value"
+                } else {
+                    "set of values"
+                },
+                crate::random_table::WHERE_ATTEMPTS,
+                crate::random_table::get_seed_handle(*handle)
+            ))
+        }
         TestbenchStatement::RandomGetSeed { handle, ret } => {
             let seed = crate::random_table::get_seed_handle(*handle);
             if let Some((ret, _)) = ret {
@@ -956,6 +2048,211 @@ fn exec_one(sim: &mut Simulator, stmt: &TestbenchStatement) -> ExecResult {
             }
             ExecResult::Continue
         }
+        TestbenchStatement::Wave { enable } => {
+            sim.set_wave_enabled(*enable);
+            ExecResult::Continue
+        }
+        TestbenchStatement::Fork { .. } => {
+            ExecResult::Fail("`fork()` must be a top-level statement of the initial block".into())
+        }
+        TestbenchStatement::ForkJoin { .. } => run_threads(sim, std::slice::from_ref(stmt)),
+        TestbenchStatement::WaitUntil {
+            clock,
+            cond,
+            within,
+            high_time,
+            low_time,
+            token,
+        } => {
+            // Outside a `#[fork]` no other thread can make the condition
+            // true.
+            let Some(clock) = clock else {
+                return if cond_met(sim, cond) {
+                    ExecResult::Continue
+                } else {
+                    ExecResult::Fail(WAIT_DEADLOCK.into())
+                };
+            };
+            let within = within.as_ref().map(|x| eval_u64(sim, x));
+            let mut cycles = 0;
+            while !cond_met(sim, cond) {
+                if let Some(within) = within
+                    && cycles >= within
+                {
+                    return ExecResult::Fail(wait_timeout(within));
+                }
+                if let Some(result) = clock_cycle(sim, clock, *high_time, *low_time, token) {
+                    return result;
+                }
+                cycles += 1;
+            }
+            ExecResult::Continue
+        }
+        TestbenchStatement::Branch { handle, ret } => {
+            let branch = crate::checkpoint::branch(*handle);
+            if let Some((ret, _)) = ret {
+                sim.set_var_by_id(ret, Value::new(branch, 32, false));
+            }
+            ExecResult::Continue
+        }
+        TestbenchStatement::CheckpointSave { path } => {
+            match sim.checkpoint().and_then(|x| x.save(Path::new(path))) {
+                Ok(()) => ExecResult::Continue,
+                Err(err) => ExecResult::Fail(err.to_string()),
+            }
+        }
+        TestbenchStatement::CheckpointLoad { path } => {
+            // Rewinding would make recorded waveform time run backwards.
+            if sim.is_recording() {
+                return ExecResult::Fail(
+                    "`load()` cannot rewind a run that records a waveform or SAIF".into(),
+                );
+            }
+            match Snapshot::load(Path::new(path)).and_then(|x| sim.restore(&x)) {
+                Ok(()) => ExecResult::Continue,
+                Err(err) => ExecResult::Fail(err.to_string()),
+            }
+        }
+        TestbenchStatement::Force { slot, value } => {
+            sim.ensure_comb_updated();
+            let value = value.eval(&mut sim.mask_cache);
+            unsafe { slot.force(&value) };
+            sim.mark_comb_dirty();
+            ExecResult::Continue
+        }
+        TestbenchStatement::Release { slot } => {
+            unsafe { slot.release() };
+            sim.mark_comb_dirty();
+            ExecResult::Continue
+        }
+        TestbenchStatement::Deposit { slot, value } => {
+            sim.ensure_comb_updated();
+            let value = value.eval(&mut sim.mask_cache);
+            unsafe { slot.deposit(&value) };
+            sim.mark_comb_dirty();
+            ExecResult::Continue
+        }
+        TestbenchStatement::QueuePush {
+            handle,
+            value,
+            width,
+            signed,
+        } => {
+            sim.ensure_comb_updated();
+            let value = value.eval(&mut sim.mask_cache);
+            crate::queue_table::push(*handle, &value, *width, *signed);
+            ExecResult::Continue
+        }
+        TestbenchStatement::QueuePop { handle, peek, ret } => {
+            match crate::queue_table::pop(*handle, *peek) {
+                Ok(value) => {
+                    if let Some((ret, _)) = ret {
+                        sim.set_var_by_id(ret, value);
+                    }
+                    ExecResult::Continue
+                }
+                Err(msg) => ExecResult::Fail(msg),
+            }
+        }
+        TestbenchStatement::QueueSize { handle, empty, ret } => {
+            let size = crate::queue_table::size(*handle);
+            if let Some((ret, _)) = ret {
+                let value = if *empty {
+                    Value::new((size == 0) as u64, 1, false)
+                } else {
+                    Value::new(size as u64, 32, false)
+                };
+                sim.set_var_by_id(ret, value);
+            }
+            ExecResult::Continue
+        }
+        TestbenchStatement::ScoreboardExpect {
+            handle,
+            value,
+            width,
+            signed,
+        } => {
+            sim.ensure_comb_updated();
+            let value = value.eval(&mut sim.mask_cache);
+            crate::queue_table::expect(*handle, &value, *width, *signed);
+            ExecResult::Continue
+        }
+        TestbenchStatement::ScoreboardObserve {
+            handle,
+            value,
+            ordered,
+            width,
+            signed,
+        } => {
+            sim.ensure_comb_updated();
+            let value = value.eval(&mut sim.mask_cache);
+            match crate::queue_table::observe(*handle, &value, *ordered, *width, *signed) {
+                Ok(()) => ExecResult::Continue,
+                Err(msg) => ExecResult::Fail(msg),
+            }
+        }
+        TestbenchStatement::ScoreboardPending { handle, ret } => {
+            if let Some((ret, _)) = ret {
+                let pending = crate::queue_table::pending(*handle);
+                sim.set_var_by_id(ret, Value::new(pending as u64, 32, false));
+            }
+            ExecResult::Continue
+        }
+        TestbenchStatement::CoverPoint { handle, name } => {
+            crate::covergroup::declare_point(*handle, name);
+            ExecResult::Continue
+        }
+        TestbenchStatement::CoverBins {
+            handle,
+            name,
+            values,
+        } => {
+            sim.ensure_comb_updated();
+            let values: Vec<_> = values
+                .iter()
+                .map(|x| x.eval(&mut sim.mask_cache).payload_u64())
+                .collect();
+            crate::covergroup::declare_bins(*handle, name, &values);
+            ExecResult::Continue
+        }
+        TestbenchStatement::CoverRange {
+            handle,
+            name,
+            min,
+            max,
+            count,
+        } => {
+            sim.ensure_comb_updated();
+            let min = min.eval(&mut sim.mask_cache).payload_u64();
+            let max = max.eval(&mut sim.mask_cache).payload_u64();
+            let count = count
+                .as_ref()
+                .map(|x| x.eval(&mut sim.mask_cache).payload_u64());
+            match crate::covergroup::declare_range(*handle, name, min, max, count) {
+                Ok(()) => ExecResult::Continue,
+                Err(msg) => ExecResult::Fail(msg),
+            }
+        }
+        TestbenchStatement::CoverCross {
+            handle,
+            name,
+            points,
+        } => match crate::covergroup::declare_cross(*handle, name, points) {
+            Ok(()) => ExecResult::Continue,
+            Err(msg) => ExecResult::Fail(msg),
+        },
+        TestbenchStatement::CoverSample {
+            handle,
+            names,
+            values,
+        } => {
+            sim.ensure_comb_updated();
+            let values: Vec<_> = values.iter().map(|x| x.eval(&mut sim.mask_cache)).collect();
+            match crate::covergroup::sample(*handle, names, &values) {
+                Ok(()) => ExecResult::Continue,
+                Err(msg) => ExecResult::Fail(msg),
+            }
+        }
         TestbenchStatement::Finish => ExecResult::Finished,
     }
 }
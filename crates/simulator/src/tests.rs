@@ -5,9 +5,11 @@ use crate::simulator::Simulator;
 use crate::simulator_error::SimulatorError;
 use crate::testbench::{
     TestResult, TestbenchStatement, build_clock_periods, build_event_map,
-    convert_initial_to_testbench, run_native_testbench, run_testbench,
+    convert_initial_to_testbench, run_native_testbench, run_native_testbench_with, run_testbench,
 };
+use crate::xprop::{XCause, XProp, XSignalKind};
 use std::str::FromStr;
+use veryl_analyzer::attribute::TimeoutItem;
 use veryl_analyzer::ir as air;
 use veryl_analyzer::ir::VarId;
 use veryl_analyzer::{Analyzer, AnalyzerError, Context, symbol_table};
@@ -343,8 +345,13 @@ where
 
 mod component;
 mod component_sim;
+mod coverage;
+mod covergroup;
 mod derived_clock;
 mod error;
 mod hier_ref;
+mod profiler;
+mod replay;
+mod shell;
 mod simulation;
 mod testbench;
@@ -0,0 +1,105 @@
+//! Coverage instrumentation: statement/branch counters injected into
+//! always blocks, and toggle/FSM sampling, across `Config::all()`.
+
+use super::*;
+use crate::coverage::{self, BranchKind, Report};
+
+#[test]
+fn branch_toggle_and_fsm_coverage() {
+    let code = r#"
+    module Top (
+        i_clk : input  clock,
+        i_rst : input  reset,
+        i_go  : input  logic,
+        o_busy: output logic,
+    ) {
+        enum State: logic<2> {
+            Idle,
+            Run,
+            Done,
+            Halt,
+        }
+        var state: State;
+
+        always_ff (i_clk, i_rst) {
+            if_reset {
+                state = State::Idle;
+            } else {
+                case state {
+                    State::Idle: if i_go {
+                        state = State::Run;
+                    }
+                    State::Run : state = State::Done;
+                    State::Done: state = State::Idle;
+                    default    : state = State::Halt;
+                }
+            }
+        }
+
+        always_comb {
+            if state == State::Run {
+                o_busy = 1;
+            } else {
+                o_busy = 0;
+            }
+        }
+    }
+    "#;
+    let line_of = |text: &str| code.lines().position(|x| x.contains(text)).unwrap() as u32 + 1;
+
+    for config in Config::all() {
+        dbg!(&config);
+        let config = Config {
+            coverage: true,
+            ..config
+        };
+
+        coverage::take();
+        let ir = analyze(code, &config);
+        let mut sim = Simulator::new(ir, None);
+        let clk = sim.get_clock("i_clk").unwrap();
+        let rst = sim.get_reset("i_rst").unwrap();
+
+        sim.set("i_go", Value::new(0, 1, false));
+        sim.step(&rst);
+        sim.step(&clk);
+        sim.set("i_go", Value::new(1, 1, false));
+        for _ in 0..4 {
+            sim.step(&clk);
+        }
+        let counts = coverage::take();
+
+        let report = Report::new(&coverage::points(), &counts);
+        assert_eq!(report.files.len(), 1);
+        let file = report.files.values().next().unwrap();
+
+        let lines = |text| file.lines.get(&line_of(text)).copied();
+        assert!(lines("state = State::Run;").unwrap() > 0);
+        assert!(lines("state = State::Done;").unwrap() > 0);
+        assert_eq!(lines("state = State::Halt;"), Some(0));
+
+        let totals = file.totals();
+        // if_reset 2/2, `if i_go` 2/2, case 3/4, comb `if` 2/2.
+        assert_eq!(totals.branches, (9, 10));
+        assert_eq!(totals.states, (3, 4));
+        assert_eq!(totals.transitions, 3);
+
+        let toggled = |name: &str| {
+            file.branches
+                .iter()
+                .find(|x| x.kind == BranchKind::Toggle && x.name == name)
+                .unwrap()
+                .count
+        };
+        assert!(toggled("o_busy[0] rise") > 0);
+        assert!(toggled("o_busy[0] fall") > 0);
+        assert_eq!(toggled("i_go[0] fall"), 0);
+
+        let lcov = report.lcov();
+        assert!(lcov.contains(&format!("DA:{},0\n", line_of("state = State::Halt;"))));
+        assert!(lcov.contains("BRF:"));
+        assert!(lcov.ends_with("end_of_record\n"));
+        let xml = report.cobertura(std::path::Path::new(""), 0);
+        assert!(xml.contains("condition-coverage=\"75% (3/4)\""));
+    }
+}
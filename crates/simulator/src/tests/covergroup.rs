@@ -0,0 +1,191 @@
+//! `$tb::coverage` functional coverage: bins, crosses, holes and merging.
+
+use super::*;
+use crate::covergroup::{self, Database};
+
+fn run_coverage_tb(code: &str) -> (TestResult, Database) {
+    covergroup::take();
+    let config = Config::default();
+    let ir = analyze_top(code, &config, "test_cov").expect("analyze");
+    let mut sim = Simulator::new(ir, None);
+    let event_map = build_event_map(&sim.ir.event_statements, &sim.ir.module_variables);
+    let clock_periods = build_clock_periods(&sim.ir.event_statements);
+    let stmts = sim
+        .ir
+        .event_statements
+        .get(&Event::Initial)
+        .cloned()
+        .expect("initial block");
+    let tb_stmts = convert_initial_to_testbench(&stmts, &event_map, &clock_periods, 3);
+    let result = run_testbench(&mut sim, &tb_stmts);
+    (result, covergroup::take())
+}
+
+#[test]
+fn coverpoints_crosses_and_holes() {
+    let code = r#"
+    #[test(test_cov)]
+    module test_cov {
+        var cov: $tb::coverage;
+        var op: logic<2>;
+        var addr: logic<8>;
+        var len: logic<3>;
+        initial {
+            cov.bins("op", 0, 1, 2, 3);
+            cov.range("addr", 0, 255, 4);
+            cov.range("addr", 256, 511);
+            cov.point("len");
+            cov.cross("op_x_addr", "op", "addr");
+            for i in 0..3 {
+                op = i;
+                addr = i * 64;
+                cov.sample("op", op, "addr", addr);
+            }
+            len = 5;
+            cov.sample("len", len);
+            $finish();
+        }
+    }
+    "#;
+
+    let (result, db) = run_coverage_tb(code);
+    assert_eq!(result, TestResult::Pass);
+
+    let items = db.items();
+    let names: Vec<_> = items.iter().map(|x| x.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["cov.addr", "cov.len", "cov.op", "cov.op_x_addr"],
+        "{items:?}"
+    );
+
+    // addr: [0:63] [64:127] [128:191] hit; [192:255] and [256:511] not.
+    assert_eq!((items[0].covered, items[0].total), (3, 5));
+    assert_eq!(items[0].holes, ["[192:255]", "[256:511]"]);
+    // len: 8 automatic single-value bins for a 3-bit value.
+    assert_eq!((items[1].covered, items[1].total), (1, 8));
+    assert!(!items[1].holes.contains(&"auto[5]".to_string()));
+    // op: 3 of 4.
+    assert_eq!(items[2].holes, ["3"]);
+    // Only the diagonal of the 4x5 cross is hit.
+    assert_eq!((items[3].covered, items[3].total), (3, 20));
+    assert!(items[3].holes.contains(&"(3, [192:255])".to_string()));
+    assert!(!items[3].holes.contains(&"(1, [64:127])".to_string()));
+
+    let table = db.table(2);
+    assert!(table.contains("holes: [192:255] [256:511]"), "{table}");
+    assert!(table.contains("(and 15 more)"), "{table}");
+}
+
+#[test]
+fn coverage_databases_merge_by_label() {
+    let run = |a: u32, b: u32| {
+        let code = format!(
+            r#"
+    #[test(test_cov)]
+    module test_cov {{
+        var cov: $tb::coverage;
+        var op: logic<2>;
+        initial {{
+            cov.bins("op", 0, 1, 2, 3);
+            op = {a};
+            cov.sample("op", op);
+            op = {b};
+            cov.sample("op", op);
+            $finish();
+        }}
+    }}
+    "#
+        );
+        run_coverage_tb(&code).1
+    };
+
+    let mut db = run(0, 1);
+    let other = run(1, 2);
+    db.merge(other);
+
+    let point = &db.groups["cov"].points["op"];
+    let hits: Vec<_> = point.bins.iter().map(|x| x.hits).collect();
+    assert_eq!(hits, [1, 2, 1, 0]);
+
+    // The database round-trips through the `--coverage-db` file format.
+    let text = serde_json::to_string(&db).unwrap();
+    assert_eq!(serde_json::from_str::<Database>(&text).unwrap(), db);
+}
+
+#[test]
+fn coverage_errors_fail_the_test() {
+    let cases = [
+        (r#"cov.sample("op", 1);"#, "coverpoint `op` is not declared"),
+        (
+            r#"cov.cross("x", "op", "addr");"#,
+            "cross `x`: coverpoint `op` is not declared",
+        ),
+        (
+            r#"cov.range("op", 0, 3, 5);"#,
+            "cannot split [0:3] into 5 bin(s)",
+        ),
+        (
+            r#"cov.range("a", 0, 511, 512); cov.range("b", 0, 255, 256); cov.cross("x", "a", "b");"#,
+            "cross `x` has 131072 bin combinations, more than 65536",
+        ),
+        // Automatic bins are laid out by the first sample.
+        (
+            r#"cov.point("a"); cov.range("b", 0, 2047, 2048); cov.cross("x", "a", "b");
+            cov.sample("a", 8'd1, "b", 1);"#,
+            "cross `x` has 131072 bin combinations, more than 65536",
+        ),
+    ];
+    for (stmt, expected) in cases {
+        let code = format!(
+            r#"
+    #[test(test_cov)]
+    module test_cov {{
+        var cov: $tb::coverage;
+        initial {{
+            {stmt}
+            $finish();
+        }}
+    }}
+    "#
+        );
+        match run_coverage_tb(&code).0 {
+            TestResult::Fail(msg) => assert!(msg.contains(expected), "{stmt}: {msg}"),
+            x => panic!("{stmt}: expected a failure, got {x:?}"),
+        }
+    }
+}
+
+#[test]
+fn oversized_cross_is_not_enumerated() {
+    // A merged database can hold a cross past the cap; its holes are not
+    // listed.
+    let point = |n: u64| covergroup::Coverpoint {
+        auto: false,
+        bins: (0..n)
+            .map(|x| covergroup::Bin {
+                label: x.to_string(),
+                min: x,
+                max: x,
+                hits: 1,
+            })
+            .collect(),
+    };
+    let mut group = covergroup::Group::default();
+    group.points.insert("a".to_string(), point(300));
+    group.points.insert("b".to_string(), point(300));
+    group.crosses.insert(
+        "x".to_string(),
+        covergroup::Cross {
+            points: vec!["a".to_string(), "b".to_string()],
+            hits: [("0, 0".to_string(), 1)].into_iter().collect(),
+        },
+    );
+    let mut db = Database::default();
+    db.groups.insert("cov".to_string(), group);
+
+    let items = db.items();
+    let cross = items.iter().find(|x| x.name == "cov.x").unwrap();
+    assert_eq!((cross.covered, cross.total), (1, 90000));
+    assert_eq!(cross.holes, ["more than 65536 combinations"]);
+}
@@ -640,3 +640,174 @@ fn hier_ref_nested_test_module_resolves_locally() {
         assert_eq!(result, TestResult::Pass, "config: {config:?}");
     }
 }
+
+const FORCE_DUT: &str = r#"
+    module FSub (
+        clk: input clock,
+        rst: input reset,
+        din: input logic<8>,
+        dout: output logic<8>,
+    ) {
+        var mid: logic<8>;
+        var q: logic<8>;
+        assign mid = din + 1;
+        always_ff {
+            if_reset { q = 0; }
+            else { q = mid; }
+        }
+        assign dout = q;
+    }
+
+    module FTop (
+        clk: input clock,
+        rst: input reset,
+        din: input logic<8>,
+        dout: output logic<8>,
+    ) {
+        inst u_sub: FSub (clk, rst, din, dout);
+    }
+"#;
+
+fn force_testbench(body: &str) -> String {
+    format!(
+        r#"
+    {FORCE_DUT}
+
+    #[test(hier_test)]
+    module hier_test {{
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var f: $tb::force;
+
+        var din: logic<8>;
+        var dout: logic<8>;
+
+        inst dut: FTop (clk, rst, din, dout);
+
+        initial {{
+            rst.assert();
+            din = 5;
+            clk.next();
+            $assert(dout == 6, "before: dout %d", dout);
+            {body}
+            $finish();
+        }}
+    }}
+    "#
+    )
+}
+
+#[test]
+fn force_comb_overrides_driver_until_release() {
+    // A forced comb net ignores its driver through input changes and clock
+    // edges; after release the driver takes over again at the next settle.
+    let code = force_testbench(
+        r#"
+            f.force(dut.u_sub.mid, 20);
+            $assert(dut.u_sub.mid == 20, "forced: mid %d", dut.u_sub.mid);
+            clk.next();
+            $assert(dout == 20, "forced: dout %d", dout);
+            din = 9;
+            clk.next();
+            $assert(dout == 20, "forced after din change: dout %d", dout);
+            f.release(dut.u_sub.mid);
+            $assert(dut.u_sub.mid == 10, "released: mid %d", dut.u_sub.mid);
+            clk.next();
+            $assert(dout == 10, "released: dout %d", dout);
+        "#,
+    );
+    let results = run_hier_test(&code);
+    assert!(!results.is_empty());
+    for (config, result, _) in results {
+        assert_eq!(result, TestResult::Pass, "config: {config:?}");
+    }
+}
+
+#[test]
+fn force_ff_holds_across_clock_edges() {
+    // A forced FF shows the value at once and keeps it across edges; a
+    // released FF holds it until its next write.
+    let code = force_testbench(
+        r#"
+            f.force(dut.u_sub.q, 77);
+            $assert(dout == 77, "forced: dout %d", dout);
+            clk.next(2);
+            $assert(dout == 77, "forced after edges: dout %d", dout);
+            f.release(dut.u_sub.q);
+            $assert(dout == 77, "released before edge: dout %d", dout);
+            clk.next();
+            $assert(dout == 6, "released after edge: dout %d", dout);
+        "#,
+    );
+    let results = run_hier_test(&code);
+    assert!(!results.is_empty());
+    for (config, result, _) in results {
+        assert_eq!(result, TestResult::Pass, "config: {config:?}");
+    }
+}
+
+#[test]
+fn deposit_is_overwritten_by_driver() {
+    // A deposit writes once; the FF driver replaces it on the next edge.
+    let code = force_testbench(
+        r#"
+            f.deposit(dut.u_sub.q, 300);
+            $assert(dout == 44, "deposited (truncated): dout %d", dout);
+            clk.next();
+            $assert(dout == 6, "after edge: dout %d", dout);
+        "#,
+    );
+    let results = run_hier_test(&code);
+    assert!(!results.is_empty());
+    for (config, result, _) in results {
+        assert_eq!(result, TestResult::Pass, "config: {config:?}");
+    }
+}
+
+#[test]
+fn force_var_holds_configured_comb_target() {
+    // A harness-forced comb net reaches its readers through every settle
+    // and edge, and follows its driver again once released.
+    for config in Config::all() {
+        let config = Config {
+            force_targets: vec!["u_sub.mid".to_string()],
+            ..config
+        };
+        let ir = analyze_top(FORCE_DUT, &config, "FTop")
+            .unwrap_or_else(|x| panic!("build failed for {config:?}: {x:?}"));
+        let mut sim = Simulator::new(ir, None);
+        let clk = sim.get_clock("clk").unwrap();
+        let rst = sim.get_reset("rst").unwrap();
+        let dout = |sim: &mut Simulator| sim.get("dout").unwrap().payload_u64();
+
+        sim.set("din", Value::new(5, 8, false));
+        sim.step(&rst);
+        sim.step(&clk);
+        assert_eq!(dout(&mut sim), 6, "config: {config:?}");
+
+        assert!(sim.force_var("u_sub.mid", &Value::new(20, 8, false)));
+        sim.step(&clk);
+        assert_eq!(dout(&mut sim), 20, "config: {config:?}");
+        sim.set("din", Value::new(9, 8, false));
+        sim.step(&clk);
+        assert_eq!(dout(&mut sim), 20, "config: {config:?}");
+
+        assert!(sim.release_var("u_sub.mid"));
+        sim.step(&clk);
+        assert_eq!(dout(&mut sim), 10, "config: {config:?}");
+        assert!(!sim.force_var("u_sub.q", &Value::new(1, 8, false)));
+    }
+}
+
+#[test]
+fn force_target_must_name_a_variable() {
+    let config = Config {
+        force_targets: vec!["u_sub.nothing".to_string()],
+        ..Config::default()
+    };
+    let err = analyze_top(FORCE_DUT, &config, "FTop").err();
+    assert!(
+        matches!(err, Some(SimulatorError::ForceTarget { ref path }) if path == "u_sub.nothing"),
+        "{err:?}"
+    );
+}
@@ -0,0 +1,219 @@
+use super::*;
+use crate::profiler::Profile;
+use std::path::Path;
+
+// Per-instance, per-block statement timing for `veryl test --profile`.
+
+const PROFILE_DUT: &str = r#"
+    module Sub (
+        clk: input  clock   ,
+        rst: input  reset   ,
+        i  : input  logic<8>,
+        o  : output logic<8>,
+    ) {
+        var r: logic<8>;
+
+        always_ff {
+            if_reset {
+                r = 0;
+            } else {
+                r = r + i;
+            }
+        }
+
+        always_comb {
+            o = r + 1;
+        }
+    }
+
+    module Top (
+        clk: input  clock   ,
+        rst: input  reset   ,
+        a  : input  logic<8>,
+        y  : output logic<8>,
+        z  : output logic<8>,
+    ) {
+        inst u0: Sub (clk, rst, i: a, o: y);
+        inst u1: Sub (clk, rst, i: a, o: z);
+    }
+"#;
+
+fn line_of(code: &str, text: &str) -> u32 {
+    code.lines().position(|x| x.contains(text)).unwrap() as u32 + 1
+}
+
+#[track_caller]
+fn run(code: &str, config: &Config, cycles: usize) -> (Simulator, Profile) {
+    let ir = analyze(code, config);
+    let mut sim = Simulator::new(ir, None);
+    let clk = sim.get_clock("clk").unwrap();
+    let rst = sim.get_reset("rst").unwrap();
+    sim.set("a", Value::new(3, 8, false));
+    sim.step(&rst);
+    for _ in 0..cycles {
+        sim.step(&clk);
+    }
+    let profile = sim.profile_report().unwrap();
+    (sim, profile)
+}
+
+#[test]
+fn profile_attributes_time_to_instances_and_blocks() {
+    // Blocks are located by their first statement.
+    let ff_line = line_of(PROFILE_DUT, "if_reset");
+    let comb_line = line_of(PROFILE_DUT, "o = r + 1;");
+
+    for config in Config::all() {
+        let config = Config {
+            profile: true,
+            ..config
+        };
+        let (mut sim, profile) = run(PROFILE_DUT, &config, 5);
+        // Profiling leaves the results alone.
+        assert_eq!(
+            sim.get("y").unwrap(),
+            Value::new(16, 8, false),
+            "{config:?}"
+        );
+        assert_eq!(
+            sim.get("z").unwrap(),
+            Value::new(16, 8, false),
+            "{config:?}"
+        );
+
+        let instances: Vec<_> = profile.instances().into_iter().map(|x| x.0).collect();
+        assert!(
+            instances.contains(&"Top.u0".to_string()),
+            "{config:?}: {instances:?}"
+        );
+        assert!(
+            instances.contains(&"Top.u1".to_string()),
+            "{config:?}: {instances:?}"
+        );
+
+        for instance in ["Top.u0", "Top.u1"] {
+            let ff = profile
+                .blocks
+                .iter()
+                .find(|x| {
+                    x.instance == instance
+                        && x.kind == "always_ff"
+                        && x.location.as_ref().map(|x| x.1) == Some(ff_line)
+                })
+                .unwrap_or_else(|| panic!("{config:?}: {:?}", profile.blocks));
+            assert!(ff.evals >= 5, "{config:?}: {ff:?}");
+            assert!(
+                profile.blocks.iter().any(|x| x.instance == instance
+                    && x.kind == "comb"
+                    && x.location.as_ref().map(|x| x.1) == Some(comb_line)),
+                "{config:?}: {:?}",
+                profile.blocks
+            );
+        }
+
+        let backends: Vec<_> = profile.chunks.iter().map(|x| x.backend.as_str()).collect();
+        if config.aot_c {
+            assert!(backends.contains(&"aot_c"), "{config:?}: {backends:?}");
+        } else if config.use_jit {
+            assert!(backends.contains(&"cranelift"), "{config:?}: {backends:?}");
+        } else {
+            assert!(
+                backends.iter().all(|x| *x == "interpreter"),
+                "{config:?}: {backends:?}"
+            );
+        }
+
+        let folded = profile.folded(Path::new(""));
+        let prefix = format!("Top;u0;always_ff @ :{ff_line};");
+        let line = folded
+            .lines()
+            .find(|x| x.starts_with(&prefix))
+            .unwrap_or_else(|| panic!("{config:?}: {folded}"));
+        let (_, ns) = line.rsplit_once(' ').unwrap();
+        assert!(ns.parse::<u64>().is_ok(), "{line}");
+    }
+}
+
+#[test]
+fn profile_lists_interpreter_fallbacks() {
+    let code = r#"
+    module Top (
+        clk: input  clock   ,
+        rst: input  reset   ,
+        a  : input  logic<8>,
+        y  : output logic<8>,
+    ) {
+        var r: logic<8>;
+
+        always_ff {
+            if_reset {
+                r = 0;
+            } else {
+                r = r + a;
+                $display("r = %d", r);
+            }
+        }
+
+        assign y = r;
+    }
+    "#;
+    let assign_line = line_of(code, "r = r + a;");
+
+    let config = Config {
+        use_jit: true,
+        profile: true,
+        ..Default::default()
+    };
+    let (_, profile) = run(code, &config, 2);
+    let fallback = profile
+        .fallbacks
+        .iter()
+        .find(|x| x.statement == "SysFn")
+        .unwrap_or_else(|| panic!("{:?}", profile.fallbacks));
+    // `$display` has no source token; it is placed at the statement before it.
+    assert_eq!(fallback.block, "always_ff");
+    assert_eq!(fallback.location.as_ref().map(|x| x.1), Some(assign_line));
+    assert!(fallback.reason.contains("cranelift"), "{fallback:?}");
+    assert!(fallback.reason.contains("SysFn"), "{fallback:?}");
+    assert!(
+        profile
+            .blocks
+            .iter()
+            .any(|x| x.kind == "always_ff" && x.backend == "interpreter"),
+        "{:?}",
+        profile.blocks
+    );
+    assert!(
+        profile
+            .table(Path::new(""))
+            .contains("interpreter fallbacks:")
+    );
+
+    // The interpreter backend is chosen, not fallen back to.
+    let config = Config {
+        use_jit: false,
+        profile: true,
+        ..Default::default()
+    };
+    let (_, profile) = run(code, &config, 2);
+    assert!(profile.fallbacks.is_empty(), "{:?}", profile.fallbacks);
+}
+
+#[test]
+fn profile_merge_sums_tests() {
+    let config = Config {
+        use_jit: true,
+        profile: true,
+        ..Default::default()
+    };
+    let (_, first) = run(PROFILE_DUT, &config, 3);
+    let (_, second) = run(PROFILE_DUT, &config, 3);
+    let evals = |p: &Profile| -> u64 { p.blocks.iter().map(|x| x.evals).sum() };
+
+    let mut merged = first.clone();
+    merged.merge(second.clone());
+    assert_eq!(merged.blocks.len(), first.blocks.len());
+    assert_eq!(evals(&merged), evals(&first) + evals(&second));
+    // Each test reports the same fallbacks; merging keeps one copy.
+    assert_eq!(merged.fallbacks.len(), first.fallbacks.len());
+}
@@ -0,0 +1,151 @@
+use super::*;
+use crate::replay::{ReplayOptions, ReplayOutcome, Trace, run_replay};
+use crate::wave_dumper::WaveDumper;
+use std::path::PathBuf;
+
+// Replaying recorded VCD/FST stimulus into a design.
+
+const REPLAY_DUT: &str = r#"
+    module Top (
+        clk : input  clock    ,
+        rst : input  reset    ,
+        din : input  logic<8> ,
+        dout: output logic<8> ,
+    ) {
+        always_ff {
+            if_reset { dout = 0; }
+            else { dout = din; }
+        }
+    }
+"#;
+
+/// A VCD of `cycles` clock periods: `din` changes on the falling edge and
+/// `dout` follows it one rising edge later, after one cycle in reset.
+fn replay_vcd(clock: &str, cycles: u64, corrupt: Option<u64>) -> String {
+    let mut ret = format!(
+        r#"$timescale 1ns $end
+$scope module tb $end
+$var wire 1 ! {clock} $end
+$var wire 1 " rst $end
+$var wire 8 # din [7:0] $end
+$var wire 8 $ dout [7:0] $end
+$upscope $end
+$enddefinitions $end
+#0
+0!
+0"
+b0 #
+bx $
+"#
+    );
+    for i in 0..cycles {
+        if i != 0 {
+            ret.push_str(&format!("#{}\n0!\n", i * 10));
+            if i == 1 {
+                ret.push_str("1\"\n");
+            }
+            ret.push_str(&format!("b{:b} #\n", i));
+        }
+        let dout = if corrupt == Some(i) { 0xff } else { i };
+        ret.push_str(&format!("#{}\n1!\nb{:b} $\n", i * 10 + 5, dout));
+    }
+    ret
+}
+
+fn write_trace(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("veryl_replay_{}_{name}", std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[track_caller]
+fn replay(trace: &Trace, config: &Config, dump: Option<WaveDumper>) -> ReplayOutcome {
+    let ir = analyze(REPLAY_DUT, config);
+    let mut sim = Simulator::new(ir, dump);
+    let options = ReplayOptions {
+        compare: true,
+        ..Default::default()
+    };
+    run_replay(&mut sim, trace, &options).unwrap()
+}
+
+#[test]
+fn replay_matches_recorded_outputs() {
+    let path = write_trace("match.vcd", &replay_vcd("clk", 8, None));
+    let trace = Trace::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    for config in Config::all() {
+        let outcome = replay(&trace, &config, None);
+        assert_eq!(outcome.result, TestResult::Pass, "config: {config:?}");
+        assert_eq!(outcome.cycles, 8, "config: {config:?}");
+        assert!(outcome.unmatched.is_empty(), "config: {config:?}");
+    }
+}
+
+#[test]
+fn replay_reports_first_mismatch() {
+    let path = write_trace("mismatch.vcd", &replay_vcd("clk", 8, Some(3)));
+    let trace = Trace::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    for config in Config::all() {
+        let outcome = replay(&trace, &config, None);
+        // dout recorded at edge 3 is checked before edge 4.
+        let TestResult::Fail(message) = outcome.result else {
+            panic!("config: {config:?}: expected failure");
+        };
+        assert!(
+            message.starts_with("cycle 4 "),
+            "config: {config:?}: {message}"
+        );
+        assert!(
+            message.contains("output 'dout'"),
+            "config: {config:?}: {message}"
+        );
+    }
+}
+
+#[test]
+fn replay_infers_clock_from_periodic_signal() {
+    let path = write_trace("infer.vcd", &replay_vcd("tb_ck", 8, None));
+    let trace = Trace::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let outcome = replay(&trace, &Config::default(), None);
+    assert_eq!(outcome.result, TestResult::Pass);
+    assert_eq!(outcome.cycles, 8);
+}
+
+#[test]
+fn replay_without_clock_is_error() {
+    // A single change never makes a signal periodic.
+    let path = write_trace("noclock.vcd", &replay_vcd("tb_ck", 1, None));
+    let trace = Trace::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let ir = analyze(REPLAY_DUT, &Config::default());
+    let mut sim = Simulator::new(ir, None);
+    let err = run_replay(&mut sim, &trace, &ReplayOptions::default()).unwrap_err();
+    assert!(matches!(err, SimulatorError::Replay { .. }), "{err:?}");
+}
+
+#[test]
+fn replay_round_trips_through_fst() {
+    let vcd = write_trace("source.vcd", &replay_vcd("clk", 8, None));
+    let trace = Trace::load(&vcd).unwrap();
+    std::fs::remove_file(&vcd).unwrap();
+
+    // Recording a replay produces a trace that replays cleanly itself.
+    let fst = std::env::temp_dir().join(format!("veryl_replay_{}.fst", std::process::id()));
+    let dump = WaveDumper::new_fst(fst.to_str().unwrap());
+    let outcome = replay(&trace, &Config::default(), Some(dump));
+    assert_eq!(outcome.result, TestResult::Pass);
+
+    let recorded = Trace::load(&fst).unwrap();
+    std::fs::remove_file(&fst).unwrap();
+    assert!(recorded.find("dout", None).is_some());
+    let outcome = replay(&recorded, &Config::default(), None);
+    assert_eq!(outcome.result, TestResult::Pass);
+    assert_eq!(outcome.cycles, 8);
+}
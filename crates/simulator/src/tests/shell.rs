@@ -0,0 +1,192 @@
+//! `veryl sim` command interpreter.
+
+use super::*;
+use crate::shell::{Flow, Shell, ShellError};
+
+const COUNTER: &str = r#"
+module Top (
+    clk: input  clock,
+    rst: input  reset,
+    en : input  logic,
+    cnt: output logic<8>,
+) {
+    inst u: Sub (
+        clk,
+        rst,
+        en ,
+        cnt,
+    );
+}
+
+module Sub (
+    clk: input  clock,
+    rst: input  reset,
+    en : input  logic,
+    cnt: output logic<8>,
+) {
+    var r: logic<8>;
+
+    always_ff {
+        if_reset {
+            r = 0;
+        } else if en {
+            r += 1;
+        }
+    }
+    assign cnt = r;
+}
+"#;
+
+fn run(shell: &mut Shell, script: &str) -> Result<String, ShellError> {
+    let mut out = vec![];
+    for line in script.lines() {
+        if shell.exec(line, &mut out)? == Flow::Quit {
+            break;
+        }
+    }
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn step_run_and_force() {
+    for config in Config::all() {
+        dbg!(&config);
+        let config = Config {
+            force_targets: vec!["u.r".to_string()],
+            ..config
+        };
+        let mut shell = Shell::new(Simulator::new(analyze(COUNTER, &config), None));
+
+        let out = run(
+            &mut shell,
+            "
+            # comments and blank lines are skipped
+            reset 2
+            set en 1
+            run 5
+            get cnt u.r
+            run until cnt >= 8 && !(cnt == 9) max 10
+            force u.r 8'h40
+            step clk 3
+            get cnt
+            release u.r
+            step
+            get cnt
+            time
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "\
+cnt = 8'h05
+u.r = 8'h05
+held after 3 cycles
+cnt = 8'h40
+cnt = 8'h41
+time 28, cycle 14
+"
+        );
+    }
+}
+
+#[test]
+fn force_comb_reaches_readers() {
+    // `u.cnt` is recomputed from `u.r` on every settle; the forced value
+    // must still be what the parent port reads.
+    for config in Config::all() {
+        dbg!(&config);
+        let config = Config {
+            force_targets: vec!["u.cnt".to_string()],
+            ..config
+        };
+        let mut shell = Shell::new(Simulator::new(analyze(COUNTER, &config), None));
+
+        let out = run(
+            &mut shell,
+            "
+            reset
+            set en 1
+            force u.cnt 8'h80
+            get cnt
+            run 3
+            get cnt u.r
+            release u.cnt
+            get cnt
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            "\
+cnt = 8'h80
+cnt = 8'h80
+u.r = 8'h03
+cnt = 8'h03
+"
+        );
+    }
+}
+
+#[test]
+fn errors() {
+    let config = Config::default();
+    let mut shell = Shell::new(Simulator::new(analyze(COUNTER, &config), None));
+
+    let cases = [
+        ("frobnicate", "unknown command `frobnicate`; try `help`"),
+        ("get nope", "no port or variable named `nope`"),
+        ("set en zz", "invalid value `zz`"),
+        ("step en", "`en` is not a clock or reset port"),
+        ("run until cnt ==", "invalid expression: unexpected end"),
+        (
+            "run until cnt == 3 max 4",
+            "`cnt == 3` did not hold within 4 cycles",
+        ),
+        ("wave close", "no waveform is open"),
+        (
+            "force u.r 1",
+            "`u.r` is not forceable; pass `--forceable u.r` to `veryl sim`",
+        ),
+        ("force nope 1", "no port or variable named `nope`"),
+        ("force en 1\nset en 0", "`en` is forced; `release` it first"),
+    ];
+    for (line, message) in cases {
+        let err = run(&mut shell, line).unwrap_err();
+        assert_eq!(err.to_string(), message, "{line}");
+    }
+    assert_eq!(run(&mut shell, "quit\nget nope").unwrap(), "");
+}
+
+#[test]
+fn source_and_wave() {
+    let dir = std::env::temp_dir().join(format!("veryl_shell_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let wave = dir.join("out.vcd");
+    let script = dir.join("bringup.cmd");
+    std::fs::write(
+        &script,
+        format!(
+            "reset\nset en 1\nwave open {}\nrun 4\nwave close\nget cnt\nget missing\n",
+            wave.display()
+        ),
+    )
+    .unwrap();
+
+    let config = Config::default();
+    let mut shell = Shell::new(Simulator::new(analyze(COUNTER, &config), None));
+    let mut out = vec![];
+    let err = shell.source(&script, &mut out).unwrap_err();
+    assert_eq!(String::from_utf8(out).unwrap(), "cnt = 8'h04\n");
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "{}:7: no port or variable named `missing`",
+            script.display()
+        )
+    );
+    let vcd = std::fs::read_to_string(&wave).unwrap();
+    assert!(vcd.contains("$var wire 8"));
+    assert!(vcd.contains("b00000100"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
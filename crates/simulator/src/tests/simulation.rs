@@ -5112,6 +5112,33 @@ fn assert_continue_accumulates_failures() {
     assert_eq!(msg, "first\nsecond");
 }
 
+#[test]
+fn assert_failure_records_location() {
+    let code = r#"
+    module Top (
+        i_clk: input clock,
+    ) {
+        initial {
+            $assert_continue(1 == 1, "pass");
+            $assert_continue(0 == 1, "first");
+            $assert_continue(2 == 3, "second");
+        }
+    }
+    "#;
+    let config = Config::default();
+    let ir = analyze(code, &config);
+    let mut sim = Simulator::new(ir, None);
+    crate::assert_buffer::reset();
+    sim.step(&Event::Initial);
+    crate::assert_buffer::take_failure().unwrap();
+    let lines: Vec<_> = crate::assert_buffer::take_locations()
+        .into_iter()
+        .map(|x| x.1)
+        .collect();
+    assert_eq!(lines, vec![7, 8]);
+    assert!(crate::assert_buffer::take_locations().is_empty());
+}
+
 #[test]
 fn finish_in_initial() {
     let code = r#"
@@ -20327,3 +20354,214 @@ fn assign_rhs_sized_by_lhs_bit_select_width() {
         );
     }
 }
+
+#[test]
+fn partitioned_comb_settle() {
+    // Two comb cones that share only read-only inputs, plus a counter, so
+    // the comb list splits into independent partitions.
+    let code = r#"
+    module Top (
+        clk: input  clock,
+        rst: input  reset,
+        a  : input  logic<32>,
+        b  : input  logic<32>,
+        x  : output logic<32>,
+        y  : output logic<32>,
+        cnt: output logic<32>,
+    ) {
+        var s: logic<32>;
+        var t: logic<32>;
+        assign s = a + b;
+        assign x = s * 3;
+        assign t = a ^ b;
+        assign y = t + cnt;
+        always_ff {
+            if_reset {
+                cnt = 0;
+            } else {
+                cnt += x[3:0];
+            }
+        }
+    }
+    "#;
+
+    for config in Config::all() {
+        if config.aot_c {
+            continue;
+        }
+        dbg!(&config);
+
+        let run = |config: &Config| {
+            let ir = analyze(code, config);
+            let mut sim = Simulator::new(ir, None);
+            let clk = sim.get_clock("clk").unwrap();
+            let rst = sim.get_reset("rst").unwrap();
+            sim.step(&rst);
+            let mut trace = vec![];
+            for i in 0..50u64 {
+                sim.set("a", Value::new(i * 7, 32, false));
+                sim.set("b", Value::new(i * 13 + 5, 32, false));
+                sim.step(&clk);
+                trace.push((
+                    sim.get("x").unwrap(),
+                    sim.get("y").unwrap(),
+                    sim.get("cnt").unwrap(),
+                ));
+            }
+            (sim.ir.comb_pool.as_ref().map(|x| x.threads()), trace)
+        };
+
+        let (serial_pool, serial) = run(&config);
+        assert_eq!(serial_pool, None);
+
+        let threaded = Config {
+            sim_threads: 4,
+            partition_validate: true,
+            ..config.clone()
+        };
+        let (pool, partitioned) = run(&threaded);
+        assert!(pool.unwrap() >= 2);
+        assert_eq!(serial, partitioned);
+    }
+}
+
+#[test]
+fn xprop_modes() {
+    let code = r#"
+    module Top (
+        clk: input  clock,
+        rst: input  reset,
+        sel: input  logic,
+        s  : input  logic<2>,
+        y  : output logic<8>,
+        q  : output logic<8>,
+    ) {
+        var r: logic<8>;
+
+        always_comb {
+            if sel {
+                y = 8'hca;
+            } else {
+                y = 8'hc5;
+            }
+        }
+
+        always_ff {
+            if_reset {
+                r = 8'h00;
+            } else {
+                case s {
+                    2'd0   : r = 8'h11;
+                    2'd1   : r = 8'h13;
+                    default: r = 8'h11;
+                }
+            }
+        }
+        assign q = r;
+    }
+    "#;
+
+    // (y payload, y mask, q payload, q mask) for an X `sel` and `s`.
+    let expected = [
+        (XProp::Standard, (0xc5, 0x00, 0x11, 0x00)),
+        (XProp::Pessimistic, (0x00, 0xff, 0x00, 0xff)),
+        (XProp::TMerge, (0xc0, 0x0f, 0x11, 0x02)),
+    ];
+    for config in Config::all() {
+        if !config.use_4state {
+            continue;
+        }
+        for (xprop, expected) in expected {
+            let config = Config {
+                xprop,
+                ..config.clone()
+            };
+            dbg!(&config);
+            let ir = analyze(code, &config);
+            let mut sim = Simulator::new(ir, None);
+            let clk = sim.get_clock("clk").unwrap();
+            let rst = sim.get_reset("rst").unwrap();
+            sim.set("sel", Value::new(1, 1, false));
+            sim.set("s", Value::new(1, 2, false));
+            sim.step(&rst);
+            sim.step(&clk);
+            assert_eq!(sim.get("y").unwrap().payload_u64(), 0xca);
+            assert_eq!(sim.get("q").unwrap().payload_u64(), 0x13);
+
+            sim.set("sel", Value::new_x(1, false));
+            sim.set("s", Value::new_x(2, false));
+            sim.step(&clk);
+            let y = sim.get("y").unwrap();
+            let q = sim.get("q").unwrap();
+            let actual = (
+                y.payload_u64(),
+                y.mask_xz_u128() as u64,
+                q.payload_u64(),
+                q.mask_xz_u128() as u64,
+            );
+            assert_eq!(actual, expected);
+        }
+    }
+}
+
+#[test]
+fn xprop_report() {
+    let code = r#"
+    module Top (
+        clk: input  clock,
+        rst: input  reset,
+        d  : input  logic<8>,
+        q  : output logic<8>,
+    ) {
+        var a: logic<8>;
+        var b: logic<8>;
+
+        always_ff {
+            if_reset {
+                a = 8'h00;
+            } else {
+                a = d;
+            }
+        }
+
+        // No reset: `b` stays X until `a` is known and then keeps it.
+        always_ff {
+            b = b + a;
+        }
+        assign q = b;
+    }
+    "#;
+
+    let config = Config {
+        use_4state: true,
+        xprop_report: true,
+        // FF refinement would keep `b` in the comb buffer, unwatched.
+        disable_ff_opt: true,
+        ..Default::default()
+    };
+    let ir = analyze(code, &config);
+    let mut sim = Simulator::new(ir, None);
+    let clk = sim.get_clock("clk").unwrap();
+    let rst = sim.get_reset("rst").unwrap();
+    sim.set("d", Value::new(3, 8, false));
+    sim.step(&rst);
+    sim.step(&clk);
+    sim.step(&clk);
+    sim.set("d", Value::new_x(8, false));
+    sim.step(&clk);
+
+    let events: Vec<_> = sim
+        .xprop_events()
+        .iter()
+        .map(|x| (x.signal.as_str(), x.kind, x.cause))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            ("q", XSignalKind::Output, XCause::NotReset),
+            ("b", XSignalKind::Register, XCause::NotReset),
+            ("a", XSignalKind::Register, XCause::Propagated),
+        ]
+    );
+    assert!(sim.xprop_events().iter().all(|x| x.location.is_some()));
+}
@@ -1,4 +1,5 @@
 use super::*;
+use veryl_parser::token_range::TokenRange;
 
 #[test]
 fn testbench_counter_clock_next() {
@@ -29,6 +30,7 @@ fn testbench_counter_clock_next() {
                 duration: 3,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             TestbenchStatement::For {
                 count: 10,
@@ -37,6 +39,7 @@ fn testbench_counter_clock_next() {
                     count: None,
                     high_time: 1,
                     low_time: 1,
+                    token: TokenRange::default(),
                 }],
                 loop_var: None,
             },
@@ -78,6 +81,7 @@ fn testbench_reset_clears_counter() {
                 duration: 5,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             TestbenchStatement::Finish,
         ];
@@ -117,6 +121,7 @@ fn testbench_for_loop() {
                 duration: 1,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             // Step 5 times using For loop (each iteration steps 1 clock)
             TestbenchStatement::For {
@@ -126,6 +131,7 @@ fn testbench_for_loop() {
                     count: None,
                     high_time: 1,
                     low_time: 1,
+                    token: TokenRange::default(),
                 }],
                 loop_var: None,
             },
@@ -167,6 +173,7 @@ fn testbench_finish_stops_execution() {
                 duration: 1,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             TestbenchStatement::For {
                 count: 5,
@@ -175,6 +182,7 @@ fn testbench_finish_stops_execution() {
                     count: None,
                     high_time: 1,
                     low_time: 1,
+                    token: TokenRange::default(),
                 }],
                 loop_var: None,
             },
@@ -187,6 +195,7 @@ fn testbench_finish_stops_execution() {
                     count: None,
                     high_time: 1,
                     low_time: 1,
+                    token: TokenRange::default(),
                 }],
                 loop_var: None,
             },
@@ -964,6 +973,7 @@ fn testbench_vcd_clock_reset_waveform() {
                 duration: 2,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             TestbenchStatement::For {
                 count: 3,
@@ -972,6 +982,7 @@ fn testbench_vcd_clock_reset_waveform() {
                     count: None,
                     high_time: 1,
                     low_time: 1,
+                    token: TokenRange::default(),
                 }],
                 loop_var: None,
             },
@@ -1108,6 +1119,7 @@ fn testbench_fst_clock_reset_waveform() {
                 duration: 2,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             TestbenchStatement::For {
                 count: 3,
@@ -1116,6 +1128,7 @@ fn testbench_fst_clock_reset_waveform() {
                     count: None,
                     high_time: 1,
                     low_time: 1,
+                    token: TokenRange::default(),
                 }],
                 loop_var: None,
             },
@@ -1262,12 +1275,14 @@ fn testbench_fst_dumps_all_array_elements() {
                 duration: 2,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             TestbenchStatement::ClockNext {
                 clock: clk.clone(),
                 count: None,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
         ];
         let result = run_testbench(&mut sim, &stmts);
@@ -1381,6 +1396,7 @@ fn testbench_array_input_port() {
                 duration: 3,
                 high_time: 1,
                 low_time: 1,
+                token: TokenRange::default(),
             },
             TestbenchStatement::For {
                 count: 5,
@@ -1389,6 +1405,7 @@ fn testbench_array_input_port() {
                     count: None,
                     high_time: 1,
                     low_time: 1,
+                    token: TokenRange::default(),
                 }],
                 loop_var: None,
             },
@@ -2467,6 +2484,229 @@ fn tb_random_native() {
     let _ = std::fs::remove_file(&out_path);
 }
 
+#[test]
+fn tb_random_constrained() {
+    // Constrained methods: a zero weight is never picked, `get_inside` stays
+    // in its set, `get_cyclic` covers its range once before repeating, and
+    // `get_where` sees each candidate through the destination.
+    let dir = std::env::temp_dir();
+    let out_path = dir.join("veryl_test_tb_random_constrained.txt");
+    let out_str = out_path.to_str().unwrap().replace('\\', "\\\\");
+
+    let code = format!(
+        r#"
+    #[test(test_random_constrained)]
+    module test_random_constrained {{
+        var r: $tb::random::<u8>;
+        var f: $tb::file;
+        var x: u8;
+        var y: u8;
+        var z: u8;
+        initial {{
+            f.open("{0}");
+            for _i in 0..8 {{
+                x = r.get_dist(3, 1, 5, 0, 9, 2);
+                f.write("dist %d\n", x);
+                x = r.get_inside(2, 4, 6);
+                f.write("inside %d\n", x);
+                x = r.get_cyclic(0, 7);
+                f.write("cyclic %d\n", x);
+                y = r.get_range(0, 7);
+                x = r.get_where(0, 15, x >: y && x[0] == 1'b0);
+                f.write("where %d %d\n", y, x);
+                x = r.get_where(0, 15, x + y + z == 20 && z <: x, y, z);
+                f.write("joint %d %d %d\n", x, y, z);
+            }}
+            f.close();
+            $finish();
+        }}
+    }}
+    "#,
+        out_str
+    );
+
+    let read = || {
+        let _ = std::fs::remove_file(&out_path);
+        run_random_tb(&code, "test_random_constrained");
+        std::fs::read_to_string(&out_path).unwrap()
+    };
+
+    let content = read();
+    assert_eq!(content, read(), "same seed must reproduce the sequence");
+
+    let mut cyclic = Vec::new();
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split(' ').collect();
+        let v: u32 = fields[1].parse().unwrap();
+        match fields[0] {
+            "dist" => assert!(v == 3 || v == 9, "{line}"),
+            "inside" => assert!([2, 4, 6].contains(&v), "{line}"),
+            "cyclic" => cyclic.push(v),
+            "where" => {
+                let x: u32 = fields[2].parse().unwrap();
+                assert!(x > v && x.is_multiple_of(2) && x <= 15, "{line}");
+            }
+            "joint" => {
+                let y: u32 = fields[2].parse().unwrap();
+                let z: u32 = fields[3].parse().unwrap();
+                assert!(v + y + z == 20 && z < v && v.max(y) <= 15, "{line}");
+            }
+            _ => unreachable!(),
+        }
+    }
+    cyclic.sort();
+    assert_eq!(cyclic, (0..8).collect::<Vec<_>>());
+    let _ = std::fs::remove_file(&out_path);
+}
+
+#[test]
+fn tb_random_constrained_failures() {
+    let cases = [
+        ("x = r.get_dist(1, 0, 2, 0);", "weights sum to zero"),
+        ("x = r.get_cyclic(0, 70000);", "at most 65536"),
+        (
+            "x = r.get_where(0, 3, x >: 3);",
+            "no value satisfying its condition",
+        ),
+        (
+            "x = r.get_where(0, 3, x + y >: 6, y);",
+            "no set of values satisfying its condition in 10000 attempts",
+        ),
+    ];
+    for (stmt, expected) in cases {
+        let y = if stmt.ends_with(", y);") {
+            "var y: u32;"
+        } else {
+            ""
+        };
+        let code = format!(
+            r#"
+    #[test(test_random_fail)]
+    module test_random_fail {{
+        var r: $tb::random::<u32>;
+        var x: u32;
+        {y}
+        initial {{
+            {stmt}
+            $finish();
+        }}
+    }}
+    "#
+        );
+        let config = Config::default();
+        let ir = analyze_top(&code, &config, "test_random_fail").expect("analyze");
+        let mut sim = Simulator::new(ir, None);
+        let event_map = build_event_map(&sim.ir.event_statements, &sim.ir.module_variables);
+        let clock_periods = build_clock_periods(&sim.ir.event_statements);
+        let stmts = sim
+            .ir
+            .event_statements
+            .get(&Event::Initial)
+            .cloned()
+            .unwrap();
+        let tb_stmts = convert_initial_to_testbench(&stmts, &event_map, &clock_periods, 3);
+        match run_testbench(&mut sim, &tb_stmts) {
+            TestResult::Fail(msg) => assert!(msg.contains(expected), "{stmt}: {msg}"),
+            x => panic!("{stmt}: expected a failure, got {x:?}"),
+        }
+    }
+}
+
+#[test]
+fn tb_queue_scoreboard() {
+    // Queue items come back in FIFO order; `observe` consumes the oldest
+    // expected item and `observe_any` whichever one matches.
+    let code = r#"
+    #[test(test_queue)]
+    module test_queue {
+        gen byte_t: type = logic<8>;
+
+        var q : $tb::queue::<byte_t>;
+        var sb: $tb::scoreboard::<byte_t>;
+        var x : logic<8>;
+        initial {
+            $assert(q.empty());
+            x = 8'h10;
+            for _i in 0..4 {
+                q.push(x);
+                sb.expect(x + 1);
+                x += 1;
+            }
+            $assert(q.size() == 4);
+            $assert(q.peek() == 8'h10);
+            $assert(q.size() == 4);
+            x = q.pop();
+            sb.observe(x + 1);
+            x = q.pop();
+            sb.observe(x + 1);
+            $assert(q.pop() == 8'h12);
+            sb.observe_any(8'h14);
+            sb.observe_any(8'h13);
+            $assert(q.size() == 1 && !q.empty());
+            $assert(sb.pending() == 0);
+            $finish();
+        }
+    }
+    "#;
+    run_random_tb(code, "test_queue");
+}
+
+#[test]
+fn tb_queue_scoreboard_failures() {
+    let cases = [
+        ("x = q.pop();", "queue `q`: `pop` on an empty queue"),
+        (
+            "sb.expect(7); sb.expect(8); sb.observe(8);",
+            "scoreboard `sb`: observed 8'h08 but expected 8'h07",
+        ),
+        (
+            "sb.expect(7); sb.observe_any(9);",
+            "observed 8'h09 matches none of the 1 expected item(s)",
+        ),
+        ("sb.observe(7);", "observed 8'h07 with no item expected"),
+        (
+            "sb.expect(7); sb.expect(8); sb.observe(7);",
+            "scoreboard `sb`: 1 expected item(s) never observed: 8'h08",
+        ),
+    ];
+    for (stmt, expected) in cases {
+        let code = format!(
+            r#"
+    #[test(test_queue_fail)]
+    module test_queue_fail {{
+        gen byte_t: type = logic<8>;
+
+        var q : $tb::queue::<byte_t>;
+        var sb: $tb::scoreboard::<byte_t>;
+        var x : logic<8>;
+        initial {{
+            x = 0;
+            {stmt}
+            $display("%d %d %d", x, q.size(), sb.pending());
+            $finish();
+        }}
+    }}
+    "#
+        );
+        let config = Config::default();
+        let ir = analyze_top(&code, &config, "test_queue_fail").expect("analyze");
+        let mut sim = Simulator::new(ir, None);
+        let event_map = build_event_map(&sim.ir.event_statements, &sim.ir.module_variables);
+        let clock_periods = build_clock_periods(&sim.ir.event_statements);
+        let stmts = sim
+            .ir
+            .event_statements
+            .get(&Event::Initial)
+            .cloned()
+            .unwrap();
+        let tb_stmts = convert_initial_to_testbench(&stmts, &event_map, &clock_periods, 3);
+        match run_testbench(&mut sim, &tb_stmts) {
+            TestResult::Fail(msg) => assert!(msg.contains(expected), "{stmt}: {msg}"),
+            x => panic!("{stmt}: expected a failure, got {x:?}"),
+        }
+    }
+}
+
 #[test]
 fn tb_file_write_without_open_is_dropped() {
     // write/flush/close on a handle that was never opened are silent no-ops
@@ -2676,3 +2916,718 @@ fn if_in_initial_cond_hoist_temp_4state() {
         assert_eq!(result, TestResult::Pass, "config: {config:?}");
     }
 }
+
+#[test]
+fn wave_filter_scope_and_window() {
+    // Only `u_cnt`'s `cnt` is selected, and only cycles [2, 6) are dumped;
+    // `w.stop()`/`w.start()` additionally suspend cycles 3 and 4.
+    let code = r#"
+    module Counter (
+        clk: input  clock,
+        rst: input  reset,
+        cnt: output logic<8>,
+    ) {
+        var other: logic<8>;
+        always_ff {
+            if_reset {
+                cnt   = 0;
+                other = 0;
+            } else {
+                cnt   += 1;
+                other += 2;
+            }
+        }
+    }
+
+    #[test(test_wave)]
+    module test_wave {
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var w: $tb::wave;
+        var cnt: logic<8>;
+
+        inst u_cnt: Counter (
+            clk,
+            rst,
+            cnt,
+        );
+
+        initial {
+            rst.assert();
+            clk.next(3);
+            w.stop();
+            clk.next(2);
+            w.start();
+            clk.next(5);
+            $finish();
+        }
+    }
+    "#;
+
+    for config in Config::all() {
+        let ir = analyze_top(code, &config, "test_wave").unwrap();
+
+        use crate::wave_dumper::{SharedVec, WaveDumper, WaveFilter};
+        let dump_buf = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let filter = WaveFilter {
+            scopes: vec!["test_wave.u_cnt".to_string()],
+            signals: vec!["*.c?t".to_string()],
+            start: Some(2),
+            end: Some(6),
+            ..Default::default()
+        };
+        let dumper = WaveDumper::new_vcd(Box::new(SharedVec(dump_buf.clone()))).with_filter(filter);
+        let mut sim = Simulator::new(ir, Some(dumper));
+
+        let event_map = build_event_map(&sim.ir.event_statements, &sim.ir.module_variables);
+        let clock_periods = build_clock_periods(&sim.ir.event_statements);
+        let stmts = sim.ir.event_statements.get(&Event::Initial).unwrap();
+        let tb_stmts = convert_initial_to_testbench(stmts, &event_map, &clock_periods, 3);
+        assert_eq!(run_testbench(&mut sim, &tb_stmts), TestResult::Pass);
+        drop(sim);
+
+        let dump = std::sync::Arc::try_unwrap(dump_buf)
+            .unwrap()
+            .into_inner()
+            .unwrap();
+        let mut parser = vcd::Parser::new(dump.as_slice());
+        let header = parser.parse_header().unwrap();
+
+        let cnt = header
+            .find_var(&["test_wave", "u_cnt", "cnt"])
+            .unwrap()
+            .code;
+        assert!(header.find_var(&["test_wave", "u_cnt", "other"]).is_none());
+        assert!(header.find_var(&["test_wave", "cnt"]).is_none());
+        assert!(header.find_var(&["test_wave", "clk"]).is_none());
+
+        let mut values = Vec::new();
+        for cmd in parser {
+            if let vcd::Command::ChangeVector(code, vec) = cmd.unwrap() {
+                assert_eq!(code, cnt);
+                let value = vec
+                    .iter()
+                    .fold(0u64, |acc, x| acc << 1 | (x == vcd::Value::V1) as u64);
+                values.push(value);
+            }
+        }
+        values.dedup();
+        // Cycle n ends with `cnt == n + 1`; cycles 2 and 5 are dumped.
+        assert_eq!(values, vec![3, 6], "config: {config:?}");
+    }
+}
+
+#[test]
+fn saif_records_toggles() {
+    let code = r#"
+    module Counter (
+        clk: input  clock,
+        rst: input  reset,
+        cnt: output logic<2>,
+    ) {
+        always_ff {
+            if_reset {
+                cnt = 0;
+            } else {
+                cnt += 1;
+            }
+        }
+    }
+
+    #[test(test_saif)]
+    module test_saif {
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var cnt: logic<2>;
+
+        inst u_cnt: Counter (
+            clk,
+            rst,
+            cnt,
+        );
+
+        initial {
+            rst.assert();
+            clk.next(8);
+            $finish();
+        }
+    }
+    "#;
+
+    for config in Config::all() {
+        let ir = analyze_top(code, &config, "test_saif").unwrap();
+        let mut sim = Simulator::new(ir, None);
+        sim.attach_saif();
+
+        let event_map = build_event_map(&sim.ir.event_statements, &sim.ir.module_variables);
+        let clock_periods = build_clock_periods(&sim.ir.event_statements);
+        let stmts = sim.ir.event_statements.get(&Event::Initial).unwrap();
+        let tb_stmts = convert_initial_to_testbench(stmts, &event_map, &clock_periods, 3);
+        assert_eq!(run_testbench(&mut sim, &tb_stmts), TestResult::Pass);
+
+        let mut out = Vec::new();
+        sim.saif
+            .as_ref()
+            .unwrap()
+            .write(&mut out, sim.time)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        // 3 reset cycles + 8 counting cycles; the clock toggles twice each,
+        // except that its first rise leaves X in 4-state mode.
+        let clk_tc = 22 - config.use_4state as usize;
+        assert!(out.contains("(DURATION 22)"), "{out}");
+        assert!(out.contains("(INSTANCE u_cnt"), "{out}");
+        let clk = format!("(clk (T0 11) (T1 11) (TX 0) (TC {clk_tc}) (IG 0))");
+        assert!(out.contains(&clk), "{out}");
+        assert!(
+            out.contains("(cnt\\[0\\] (T0 14) (T1 8) (TX 0) (TC 8) (IG 0))"),
+            "{out}"
+        );
+        assert!(
+            out.contains("(cnt\\[1\\] (T0 14) (T1 8) (TX 0) (TC 4) (IG 0))"),
+            "{out}"
+        );
+    }
+}
+
+#[test]
+fn checkpoint_fork_and_reload() {
+    // The prefix runs once; each fork branch resumes from the same cycle,
+    // and a saved checkpoint rewinds the counter when loaded.
+    let dir = std::env::temp_dir();
+    let cp_path = dir.join("veryl_test_checkpoint_fork.bin");
+    let cp_str = cp_path.to_str().unwrap().replace('\\', "\\\\");
+
+    let code = format!(
+        r#"
+    module Counter (
+        clk: input clock,
+        rst: input reset,
+        cnt: output logic<32>,
+    ) {{
+        always_ff {{
+            if_reset {{ cnt = 0; }}
+            else {{ cnt += 1; }}
+        }}
+    }}
+
+    #[test(test_checkpoint)]
+    module test_checkpoint {{
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var cp: $tb::checkpoint;
+        var b: logic<32>;
+        var cnt: logic<32>;
+
+        inst dut: Counter (
+            clk: clk,
+            rst: rst,
+            cnt: cnt,
+        );
+
+        initial {{
+            rst.assert();
+            clk.next(3);
+            cp.save("{0}");
+            cp.fork(3);
+            b = cp.branch();
+            clk.next(b);
+            $assert_continue(cnt == 3 + b, "branch %d: cnt %d", b, cnt);
+            $assert(cnt != 5, "branch %d reached 5", b);
+            cp.load("{0}");
+            $assert(cnt == 3, "load: cnt %d", cnt);
+        }}
+    }}
+    "#,
+        cp_str
+    );
+
+    for config in Config::all() {
+        let _ = std::fs::remove_file(&cp_path);
+        let ir = match analyze_top(&code, &config, "test_checkpoint") {
+            Ok(ir) => ir,
+            Err(_) => continue,
+        };
+        let module_name = ir.name.to_string();
+        let result = run_native_testbench(ir, None, module_name).unwrap();
+        assert_eq!(
+            result,
+            TestResult::Fail("fork branch 2: branch 2 reached 5".to_string()),
+            "(jit={}, 4state={})",
+            config.use_jit,
+            config.use_4state,
+        );
+        assert!(cp_path.exists());
+    }
+    let _ = std::fs::remove_file(&cp_path);
+}
+
+#[test]
+fn tb_timeout_reports_blocked_statement() {
+    let code = r#"
+    module Handshake (
+        clk : input  clock    ,
+        rst : input  reset    ,
+        cnt : output logic<8> ,
+        done: output logic    ,
+    ) {
+        always_ff {
+            if_reset { cnt = 0; }
+            else { cnt += 1; }
+        }
+        assign done = 0;
+    }
+
+    #[test(test_hang)]
+    module test_hang {
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+
+        var cnt : logic<8>;
+        var done: logic   ;
+
+        inst dut: Handshake (clk, rst, cnt, done);
+
+        initial {
+            rst.assert();
+            for _i in 0..1000000 {
+                clk.next();
+                if done {
+                    break;
+                }
+            }
+            $finish();
+        }
+    }
+    "#;
+
+    let timeouts = [
+        TimeoutItem {
+            cycles: Some(20),
+            time: None,
+        },
+        TimeoutItem {
+            cycles: None,
+            time: Some(std::time::Duration::ZERO),
+        },
+    ];
+    for config in Config::all() {
+        for timeout in timeouts {
+            let ir = analyze_top(code, &config, "test_hang").unwrap();
+            let module_name = ir.name.to_string();
+            let result =
+                run_native_testbench_with(ir, None, None, module_name, None, timeout).unwrap();
+            let TestResult::Fail(msg) = result else {
+                panic!("expected timeout (jit={})", config.use_jit);
+            };
+            let lines: Vec<_> = msg.lines().collect();
+            if timeout.cycles.is_some() {
+                assert_eq!(lines[0], "timed out after 20 cycles");
+                // 3 reset cycles, then the counter runs.
+                assert!(lines[6].ends_with("8'h11"));
+            } else {
+                assert!(lines[0].ends_with("(64 cycles)"), "{msg}");
+            }
+            assert!(
+                lines[1].starts_with("blocked at ") && lines[1].ends_with(":28:17"),
+                "{msg}"
+            );
+            assert_eq!(lines[2], "last 8 cycle(s) of top-level signals:");
+            assert!(lines[3].starts_with("  cycle"));
+            assert!(lines[4].starts_with("  time"));
+            assert!(lines[6].starts_with("  cnt "));
+            assert!(lines[7].starts_with("  done") && lines[7].ends_with("1'h0"));
+        }
+    }
+}
+
+#[test]
+fn tb_timeout_catches_loop_without_clock() {
+    // No clock is ever driven, so only the statement count can reach the
+    // wall-clock limit.
+    let code = r#"
+    #[test(test_spin)]
+    module test_spin {
+        var x: logic<32>;
+
+        initial {
+            x = 0;
+            for _i in 0..1000000000 {
+                x += 1;
+                $assert(x != 0, "wrapped");
+            }
+            $finish();
+        }
+    }
+    "#;
+
+    let timeout = TimeoutItem {
+        cycles: Some(20),
+        time: Some(std::time::Duration::ZERO),
+    };
+    for config in Config::all() {
+        let ir = analyze_top(code, &config, "test_spin").unwrap();
+        let module_name = ir.name.to_string();
+        let result = run_native_testbench_with(ir, None, None, module_name, None, timeout).unwrap();
+        let TestResult::Fail(msg) = result else {
+            panic!("expected timeout (jit={})", config.use_jit);
+        };
+        assert!(
+            msg.starts_with("timed out after ")
+                && msg.contains("(0 cycles) running statements without a clock"),
+            "{msg}"
+        );
+    }
+}
+
+#[test]
+fn tb_fork_join_and_wait_until() {
+    let code = |tail: &str| {
+        format!(
+            r#"
+    module Counter (
+        clk: input clock,
+        rst: input reset,
+        cnt: output logic<32>,
+    ) {{
+        always_ff {{
+            if_reset {{ cnt = 0; }}
+            else {{ cnt += 1; }}
+        }}
+    }}
+
+    #[test(test_fork)]
+    module test_fork {{
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var th : $tb::thread;
+        var cnt: logic<32>;
+        var req: logic;
+        var a  : logic<32>;
+        var b  : logic<32>;
+
+        inst dut: Counter (
+            clk: clk,
+            rst: rst,
+            cnt: cnt,
+        );
+
+        initial {{
+            rst.assert();
+            req = 0;
+            #[fork]
+            block {{
+                block {{
+                    clk.next(4);
+                    a   = cnt;
+                    req = 1;
+                }}
+                block {{
+                    th.wait_until(req);
+                    b = cnt;
+                    clk.wait_until(cnt == 10);
+                }}
+            }}
+            $assert(a == 4 && b == 4 && cnt == 10, "join: %d %d %d", a, b, cnt);
+            // The slower thread keeps running after `join_any` returns.
+            #[fork(join_any)]
+            block {{
+                clk.next(2);
+                block {{
+                    clk.next(5);
+                    req = 0;
+                }}
+            }}
+            $assert(cnt == 12, "join_any: %d", cnt);
+            clk.next(4);
+            $assert(req == 0, "background thread did not finish");
+            {tail}
+        }}
+    }}
+    "#
+        )
+    };
+
+    let cases = [
+        ("", TestResult::Pass),
+        (
+            "clk.wait_until(cnt == 100, 3);",
+            TestResult::Fail("`wait_until` condition not met within 3 cycle(s)".to_string()),
+        ),
+        (
+            "#[fork] block { th.wait_until(req); th.wait_until(req); }",
+            TestResult::Fail(
+                "`wait_until` condition is false and no running thread or clock can change it"
+                    .to_string(),
+            ),
+        ),
+    ];
+    for config in Config::all() {
+        for (tail, expected) in &cases {
+            let ir = analyze_top(&code(tail), &config, "test_fork").unwrap();
+            let module_name = ir.name.to_string();
+            let result = run_native_testbench(ir, None, module_name).unwrap();
+            assert_eq!(
+                &result, expected,
+                "{tail} (jit={}, 4state={})",
+                config.use_jit, config.use_4state,
+            );
+        }
+    }
+}
+
+#[test]
+fn temporal_assertions_in_always_ff() {
+    let code = |late: u32| {
+        format!(
+            r#"
+    module Handshake #(
+        param LATE: bit = 0,
+    ) (
+        clk: input clock,
+        rst: input reset,
+        req: input logic,
+        ack: output logic,
+    ) {{
+        var d1: logic;
+        var d2: logic;
+        var d3: logic;
+        always_ff {{
+            if_reset {{
+                d1 = 0;
+                d2 = 0;
+                d3 = 0;
+            }} else {{
+                d1 = req;
+                d2 = d1;
+                d3 = d2;
+            }}
+            $assert_implies(req, 1, d1);
+            $assert_eventually($rose(req), 2, ack, "req %d not acked", req);
+            $assert_implies(!req && $past(!req), 0, $stable(d1) || $fell(d1));
+        }}
+        always_ff (clk) {{
+            $assert_implies($past(req, 2), 0, d2);
+        }}
+        assign ack = if LATE ? d3 : d1;
+    }}
+
+    #[test(test_temporal)]
+    module test_temporal {{
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var req: logic;
+        var ack: logic;
+
+        inst dut: Handshake #(LATE: {late}) (clk, rst, req, ack);
+
+        initial {{
+            req = 0;
+            rst.assert();
+            clk.next(3);
+            req = 1;
+            clk.next(5);
+            req = 0;
+            clk.next(5);
+        }}
+    }}
+    "#
+        )
+    };
+
+    let cases = [
+        (0, TestResult::Pass),
+        (
+            1,
+            TestResult::Fail("req 1 not acked (attempt started at cycle 6)".to_string()),
+        ),
+    ];
+    for config in Config::all() {
+        for (late, expected) in &cases {
+            let ir = analyze_top(&code(*late), &config, "test_temporal").unwrap();
+            let module_name = ir.name.to_string();
+            let result = run_native_testbench(ir, None, module_name).unwrap();
+            assert_eq!(
+                &result, expected,
+                "LATE={late} (jit={}, 4state={})",
+                config.use_jit, config.use_4state,
+            );
+        }
+    }
+}
+
+#[test]
+fn tb_matrix_elaborates_each_case() {
+    let code = r#"
+    #[test(test_matrix)]
+    #[matrix("W = 4, 8", "N = 2, 3")]
+    module test_matrix #(
+        param W: u32 = 1,
+        param N: u32 = 1,
+    ) {
+        var a: logic<W>;
+        var b: logic<32>;
+
+        assign a = '1;
+        assign b = W * N;
+
+        initial {
+            $assert(a == (1 << W) - 1);
+            $assert(b == W * N);
+            $finish();
+        }
+    }
+    "#;
+
+    let config = Config::default();
+    let cases = [
+        ("test_matrix[W=4,N=2]", 4, 8),
+        ("test_matrix[W=4,N=3]", 4, 12),
+        ("test_matrix[W=8,N=2]", 8, 16),
+        ("test_matrix[W=8,N=3]", 8, 24),
+    ];
+    for (case, width, product) in cases {
+        let ir = analyze_top(code, &config, case).unwrap();
+        let tests: Vec<_> = symbol_table::get_tests("prj")
+            .into_iter()
+            .map(|(name, property)| (name.to_string(), property.top.map(|x| x.to_string())))
+            .collect();
+        assert_eq!(tests.len(), 4);
+        assert!(tests.contains(&(case.to_string(), Some(case.to_string()))));
+
+        let mut sim = Simulator::new(ir, None);
+        sim.step(&Event::Initial);
+        assert_eq!(sim.get_var("a").unwrap().width(), width);
+        assert_eq!(sim.get_var("b").unwrap(), Value::new(product, 32, false));
+
+        let ir = analyze_top(code, &config, case).unwrap();
+        let module_name = ir.name.to_string();
+        let result = run_native_testbench(ir, None, module_name).unwrap();
+        assert_eq!(result, TestResult::Pass, "{case}");
+    }
+}
+
+#[test]
+fn temporal_assertions_in_testbench() {
+    let code = |delay: u32| {
+        format!(
+            r#"
+    module Delay (
+        clk: input clock,
+        rst: input reset,
+        i: input logic,
+        o: output logic,
+    ) {{
+        always_ff {{
+            if_reset {{
+                o = 0;
+            }} else {{
+                o = i;
+            }}
+        }}
+    }}
+
+    #[test(test_temporal)]
+    module test_temporal {{
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var req: logic;
+        var ack: logic;
+
+        inst dut: Delay (clk, rst, i: req, o: ack);
+
+        initial {{
+            // Held during reset, before the checks are armed.
+            req = 1;
+            rst.assert();
+            req = 0;
+            clk.next();
+            $assert_implies(req, {delay}, ack);
+            $assert_implies($rose(req), 0, !$past(req));
+            clk.next(2);
+            req = 1;
+            clk.next();
+            req = 0;
+            clk.next(3);
+        }}
+    }}
+    "#
+        )
+    };
+
+    // The testbench's hidden registers have no reset, so the cycles of
+    // `rst.assert()` aren't counted.
+    let cases = [
+        (1, TestResult::Pass),
+        (
+            2,
+            TestResult::Fail(
+                "consequent not met 2 cycle(s) after the antecedent (attempt started at cycle 3)"
+                    .to_string(),
+            ),
+        ),
+    ];
+    for config in Config::all() {
+        for (delay, expected) in &cases {
+            let ir = analyze_top(&code(*delay), &config, "test_temporal").unwrap();
+            let module_name = ir.name.to_string();
+            let result = run_native_testbench(ir, None, module_name).unwrap();
+            assert_eq!(
+                &result, expected,
+                "delay={delay} (jit={}, 4state={})",
+                config.use_jit, config.use_4state,
+            );
+        }
+    }
+}
+
+#[test]
+fn sampled_values_taken_in_reset() {
+    // Like SVA's `$past`, the sample taken at the last edge in reset is seen
+    // at the first edge after it.
+    let code = r#"
+    module Sampler (
+        clk: input clock,
+        rst: input reset,
+        i: input logic,
+    ) {
+        var r: logic;
+        always_ff {
+            if_reset {
+                r = 0;
+            } else {
+                r = i;
+            }
+            $assert_implies(1'b1, 0, $past(i), "past lost across reset");
+        }
+    }
+
+    #[test(test_sampled)]
+    module test_sampled {
+        inst clk: $tb::clock_gen;
+        inst rst: $tb::reset_gen(clk);
+        var i: logic;
+
+        inst dut: Sampler (clk, rst, i);
+
+        initial {
+            i = 1;
+            rst.assert();
+            clk.next(2);
+        }
+    }
+    "#;
+
+    for config in Config::all() {
+        let ir = analyze_top(code, &config, "test_sampled").unwrap();
+        let module_name = ir.name.to_string();
+        let result = run_native_testbench(ir, None, module_name).unwrap();
+        assert_eq!(
+            result,
+            TestResult::Pass,
+            "jit={}, 4state={}",
+            config.use_jit,
+            config.use_4state,
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Cycle and wall-clock limits for a native testbench.
+//!
+//! A testbench waiting on a signal that never toggles would otherwise spin
+//! forever.  [`Watchdog`] is ticked once per clock cycle the testbench
+//! drives, and told of every testbench statement so a loop driving no clock
+//! still meets the wall-clock limit; once a limit is exceeded it fails the
+//! test with the statement the testbench was blocked on and the last
+//! [`HISTORY`] cycles of the top-level signals.
+
+use crate::ir::{ModuleVariables, read_native_value};
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::Instant;
+use veryl_analyzer::attribute::TimeoutItem;
+use veryl_analyzer::ir as air;
+use veryl_analyzer::symbol::Affiliation;
+use veryl_analyzer::value::Value;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::TokenSource;
+
+/// Cycles of signal history shown in a timeout report.
+pub const HISTORY: usize = 8;
+
+/// Signals beyond this many are left out of the report.
+const MAX_SIGNALS: usize = 16;
+
+/// The wall clock is read every this many cycles.
+const CLOCK_INTERVAL: u64 = 64;
+
+/// ...and every this many testbench statements.
+const STATEMENT_INTERVAL: u64 = 4096;
+
+struct Signal {
+    name: String,
+    ptr: *const u8,
+    nb: usize,
+    width: u32,
+}
+
+pub struct Watchdog {
+    timeout: TimeoutItem,
+    start: Instant,
+    use_4state: bool,
+    cycles: u64,
+    statements: u64,
+    signals: Vec<Signal>,
+    /// `(cycle, time, values)` of the latest cycles, oldest first.
+    history: VecDeque<(u64, u64, Vec<Value>)>,
+}
+
+// SAFETY: the signal pointers address the simulator's value buffers, which
+// move together with the `Simulator` owning this watchdog.
+unsafe impl Send for Watchdog {}
+
+impl Watchdog {
+    /// Watches the top-level ports, or the top-level variables of a
+    /// testbench without ports.
+    pub fn new(timeout: TimeoutItem, vars: &ModuleVariables, use_4state: bool) -> Self {
+        let mut sorted: Vec<_> = vars
+            .variables
+            .iter()
+            .filter(|(_, x)| {
+                x.width != 0
+                    && x.affiliation == Affiliation::Module
+                    && x.current_values.len() == 1
+                    && !x.r#type.is_clock()
+            })
+            .collect();
+        sorted.sort_by_key(|(id, _)| **id);
+
+        let is_port = |x: &air::VarKind| {
+            matches!(
+                x,
+                air::VarKind::Input | air::VarKind::Output | air::VarKind::Inout
+            )
+        };
+        let ports = sorted.iter().any(|(_, x)| is_port(&x.kind));
+        let signals = sorted
+            .iter()
+            .filter(|(_, x)| {
+                if ports {
+                    is_port(&x.kind)
+                } else {
+                    x.kind == air::VarKind::Variable
+                }
+            })
+            .take(MAX_SIGNALS)
+            .map(|(_, x)| Signal {
+                name: x.path.to_string(),
+                ptr: x.current_values[0],
+                nb: x.native_bytes,
+                width: x.width as u32,
+            })
+            .collect();
+
+        Self {
+            timeout,
+            start: Instant::now(),
+            use_4state,
+            cycles: 0,
+            statements: 0,
+            signals,
+            history: VecDeque::with_capacity(HISTORY),
+        }
+    }
+
+    /// Records the cycle that just ended at `time`, driven by the testbench
+    /// statement at `token`.  Returns the failure message once a limit is
+    /// exceeded.
+    pub fn tick(&mut self, time: u64, token: &TokenRange) -> Option<String> {
+        self.cycles += 1;
+        self.sample(time);
+
+        let reason = if self.timeout.cycles.is_some_and(|x| self.cycles >= x) {
+            format!("timed out after {} cycles", self.cycles)
+        } else if let Some(limit) = self.timeout.time
+            && self.cycles.is_multiple_of(CLOCK_INTERVAL)
+            && self.start.elapsed() >= limit
+        {
+            format!(
+                "timed out after {:.3}s ({} cycles)",
+                self.start.elapsed().as_secs_f64(),
+                self.cycles
+            )
+        } else {
+            return None;
+        };
+        Some(self.report(&reason, Some(token)))
+    }
+
+    /// Records one testbench statement.  Returns the failure message once
+    /// the wall-clock limit is exceeded, which a testbench looping without
+    /// a clock would otherwise never reach through [`Watchdog::tick`].
+    pub fn statement(&mut self) -> Option<String> {
+        self.statements += 1;
+        let limit = self.timeout.time?;
+        if !self.statements.is_multiple_of(STATEMENT_INTERVAL) || self.start.elapsed() < limit {
+            return None;
+        }
+        let reason = format!(
+            "timed out after {:.3}s ({} cycles) running statements without a clock",
+            self.start.elapsed().as_secs_f64(),
+            self.cycles
+        );
+        Some(self.report(&reason, None))
+    }
+
+    fn sample(&mut self, time: u64) {
+        let mut values = if self.history.len() == HISTORY {
+            self.history.pop_front().map(|x| x.2).unwrap_or_default()
+        } else {
+            Vec::with_capacity(self.signals.len())
+        };
+        values.clear();
+        for x in &self.signals {
+            // SAFETY: `ptr` addresses the variable's payload (and mask).
+            let value = unsafe { read_native_value(x.ptr, x.nb, self.use_4state, x.width, false) };
+            values.push(value);
+        }
+        self.history.push_back((self.cycles, time, values));
+    }
+
+    fn report(&self, reason: &str, token: Option<&TokenRange>) -> String {
+        let mut ret = reason.to_string();
+        if let Some(token) = token
+            && let TokenSource::File { path, .. } = token.beg.source
+        {
+            let _ = write!(
+                ret,
+                "\nblocked at {path}:{}:{}",
+                token.beg.line, token.beg.column
+            );
+        }
+        if self.signals.is_empty() {
+            return ret;
+        }
+
+        let mut rows = vec![vec!["cycle".to_string()], vec!["time".to_string()]];
+        rows.extend(self.signals.iter().map(|x| vec![x.name.clone()]));
+        for (cycle, time, values) in &self.history {
+            rows[0].push(cycle.to_string());
+            rows[1].push(time.to_string());
+            for (row, value) in rows[2..].iter_mut().zip(values) {
+                row.push(format!("{value:x}"));
+            }
+        }
+
+        let columns = self.history.len() + 1;
+        let widths: Vec<_> = (0..columns)
+            .map(|i| rows.iter().map(|x| x[i].len()).max().unwrap_or_default())
+            .collect();
+        let _ = write!(
+            ret,
+            "\nlast {} cycle(s) of top-level signals:",
+            self.history.len()
+        );
+        for row in &rows {
+            ret.push_str("\n  ");
+            for (i, cell) in row.iter().enumerate() {
+                if i == 0 {
+                    let _ = write!(ret, "{cell:<w$}", w = widths[i]);
+                } else {
+                    let _ = write!(ret, "  {cell:>w$}", w = widths[i]);
+                }
+            }
+        }
+        ret
+    }
+}
@@ -26,6 +26,94 @@ pub enum VarHandle {
 pub struct WaveDumper {
     kind: WaveDumperKind,
     path: Option<PathBuf>,
+    filter: WaveFilter,
+}
+
+/// Which signals are dumped, and during which clock cycles.  Signals outside
+/// the selection get no wire in the header, so they cost nothing per cycle.
+#[derive(Clone, Debug, Default)]
+pub struct WaveFilter {
+    /// Hierarchical scopes (`top.u_dut`) whose subtrees are dumped; empty
+    /// dumps every scope.
+    pub scopes: Vec<String>,
+    /// Glob patterns (`*`, `?`) matched against hierarchical signal names
+    /// (`top.u_dut.state`); empty dumps every signal in scope.
+    pub signals: Vec<String>,
+    /// Number of hierarchy levels dumped, the top module being level 1.
+    pub depth: Option<usize>,
+    /// First clock cycle dumped.
+    pub start: Option<u64>,
+    /// Clock cycle at which dumping ends (exclusive).
+    pub end: Option<u64>,
+}
+
+enum ScopeMatch {
+    Skip,
+    /// Not selected itself, but a selected scope lies below.
+    Descend,
+    Dump,
+}
+
+impl WaveFilter {
+    pub fn in_window(&self, cycle: u64) -> bool {
+        self.start.is_none_or(|x| cycle >= x) && self.end.is_none_or(|x| cycle < x)
+    }
+
+    /// Whether signal `name` of `scope`, `depth` levels deep, is dumped.
+    pub fn selects(&self, scope: &str, depth: usize, name: &str) -> bool {
+        matches!(self.scope(scope, depth), ScopeMatch::Dump) && self.selects_signal(scope, name)
+    }
+
+    fn scope(&self, scope: &str, depth: usize) -> ScopeMatch {
+        if self.depth.is_some_and(|x| depth > x) {
+            ScopeMatch::Skip
+        } else if self.scopes.is_empty() || self.scopes.iter().any(|x| is_within(scope, x)) {
+            ScopeMatch::Dump
+        } else if self.scopes.iter().any(|x| is_within(x, scope)) {
+            ScopeMatch::Descend
+        } else {
+            ScopeMatch::Skip
+        }
+    }
+
+    fn selects_signal(&self, scope: &str, name: &str) -> bool {
+        self.signals.is_empty() || {
+            let path = format!("{scope}.{name}");
+            self.signals.iter().any(|x| glob_match(x, &path))
+        }
+    }
+}
+
+/// Whether hierarchical `path` is `scope` itself or lies below it.
+fn is_within(path: &str, scope: &str) -> bool {
+    path.strip_prefix(scope)
+        .is_some_and(|x| x.is_empty() || x.starts_with('.'))
+}
+
+/// Matches `text` against `pattern`, where `*` matches any run of characters
+/// (including `.`) and `?` matches exactly one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it currently covers up to.
+    let mut star = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((sp, st)) = star {
+            p = sp + 1;
+            t = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|x| *x == '*')
 }
 
 enum WaveDumperKind {
@@ -54,6 +142,7 @@ impl WaveDumper {
                 writer: vcd::Writer::new(io),
             }),
             path: None,
+            filter: WaveFilter::default(),
         }
     }
 
@@ -71,6 +160,7 @@ impl WaveDumper {
                 state: FstState::Header(header),
             })),
             path: None,
+            filter: WaveFilter::default(),
         }
     }
 
@@ -79,6 +169,15 @@ impl WaveDumper {
         self
     }
 
+    pub fn with_filter(mut self, filter: WaveFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub fn filter(&self) -> &WaveFilter {
+        &self.filter
+    }
+
     pub fn path(&self) -> Option<&PathBuf> {
         self.path.as_ref()
     }
@@ -228,10 +327,36 @@ impl WaveDumper {
     }
 
     pub fn setup_module(&mut self, module_vars: &ModuleVariables, dump_vars: &mut Vec<DumpVar>) {
-        self.add_module(&sanitize_wave_name(&module_vars.name.to_string()));
+        let filter = self.filter.clone();
+        self.setup_scope(module_vars, "", 1, &filter, dump_vars);
+    }
 
-        for x in module_vars.variables.values() {
+    fn setup_scope(
+        &mut self,
+        module_vars: &ModuleVariables,
+        parent: &str,
+        depth: usize,
+        filter: &WaveFilter,
+        dump_vars: &mut Vec<DumpVar>,
+    ) {
+        let module_name = sanitize_wave_name(&module_vars.name.to_string());
+        let scope = if parent.is_empty() {
+            module_name.clone()
+        } else {
+            format!("{parent}.{module_name}")
+        };
+        let selected = match filter.scope(&scope, depth) {
+            ScopeMatch::Skip => return,
+            ScopeMatch::Descend => false,
+            ScopeMatch::Dump => true,
+        };
+        self.add_module(&module_name);
+
+        for x in module_vars.variables.values().filter(|_| selected) {
             let name = sanitize_wave_name(&x.path.to_string());
+            if !filter.selects_signal(&scope, &name) {
+                continue;
+            }
             let width = x.width as u32;
             // One pointer per array element (scalar = one). Dump a `name[i]`
             // wire per element, not just element [0].
@@ -253,7 +378,7 @@ impl WaveDumper {
         }
 
         for child in &module_vars.children {
-            self.setup_module(child, dump_vars);
+            self.setup_scope(child, &scope, depth + 1, filter, dump_vars);
         }
 
         self.upscope();
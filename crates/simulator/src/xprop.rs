@@ -0,0 +1,487 @@
+//! X-propagation for four-state simulation.
+//!
+//! [`XProp`] selects what an `if`/`case` does when its condition is X:
+//!
+//! * `Standard`: the SystemVerilog rule; an X condition is false, so the
+//!   `else`/`default` branch runs.  This is the only mode the JIT backends
+//!   implement, and the default.
+//! * `Pessimistic`: every variable any candidate branch changes becomes X.
+//! * `TMerge`: every candidate branch runs from the same state and the
+//!   results are merged bit by bit; bits on which all branches agree keep
+//!   their value, the rest become X.
+//!
+//! The non-standard modes keep statements that branch on the interpreter
+//! (see `BackendRegistry::build_chunked`), whose `if`/`case` hand an X
+//! condition to [`Merger`].  The merge snapshots the value buffers and the
+//! FF write log, so it only costs anything when a condition actually is X.
+//! System tasks inside merged branches run once per branch.
+//!
+//! [`Monitor`] is the runtime report behind `veryl test --xprop-report`: it
+//! watches registers and top-level outputs after every step and records where
+//! X first reached each of them, either because a reset left it unset or
+//! because it went from known to X later on.
+
+use crate::ir::write_log::{
+    WRITE_LOG_WIDE_ENTRY_PAYLOAD_BYTES, WriteLogBuffer, ff_commit_from_log,
+};
+use crate::ir::{ModuleVariables, ProtoStatement, Statement};
+use std::cell::RefCell;
+use std::ops::Range;
+use std::path::PathBuf;
+use veryl_analyzer::ir as air;
+use veryl_analyzer::ir::ControlFlow;
+use veryl_analyzer::symbol::Affiliation;
+use veryl_analyzer::value::{MaskCache, Value};
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::TokenSource;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum XProp {
+    #[default]
+    Standard,
+    Pessimistic,
+    TMerge,
+}
+
+/// Truth of a branch condition: `Some` when decided, `None` when X decides
+/// it (no known 1 bit, some X/Z bit).
+pub(crate) fn truth(v: &Value) -> Option<bool> {
+    let (payload, mask_xz) = match v {
+        Value::U64(x) => (x.payload as u128, x.mask_xz as u128),
+        Value::BigUint(x) => {
+            use veryl_analyzer::value::biguint_to_u128;
+            (biguint_to_u128(&x.payload), biguint_to_u128(&x.mask_xz))
+        }
+    };
+    if payload & !mask_xz != 0 {
+        Some(true)
+    } else if mask_xz != 0 {
+        None
+    } else {
+        Some(false)
+    }
+}
+
+/// Whether `stmt` contains an `if`/`case` the merge has to see.
+pub(crate) fn has_branch(stmt: &ProtoStatement) -> bool {
+    match stmt {
+        ProtoStatement::If(x) => {
+            x.cond.is_some() || x.true_side.iter().chain(&x.false_side).any(has_branch)
+        }
+        ProtoStatement::Case(_) => true,
+        ProtoStatement::For(x) => x.body.iter().any(has_branch),
+        ProtoStatement::SequentialBlock(body) => body.iter().any(has_branch),
+        ProtoStatement::CompiledBlock(x) => x.original_stmts.iter().any(has_branch),
+        ProtoStatement::Assign(_)
+        | ProtoStatement::AssignDynamic(_)
+        | ProtoStatement::Break
+        | ProtoStatement::SystemFunctionCall(_)
+        | ProtoStatement::TbMethodCall { .. } => false,
+    }
+}
+
+/// One variable element: `nb` payload bytes followed by `nb` mask bytes.
+struct Slot {
+    /// Byte offset into the FF or comb buffer.
+    offset: usize,
+    ff: bool,
+    /// FF current slot: written through the write log, never in place.
+    logged: bool,
+    nb: usize,
+    width: usize,
+}
+
+/// Merges the branches of an `if`/`case` whose condition is X.  Built once
+/// per `Ir` and shared by its statements.
+pub struct Merger {
+    mode: XProp,
+    ff: *mut u8,
+    ff_len: usize,
+    comb: *mut u8,
+    comb_len: usize,
+    log: *mut WriteLogBuffer,
+    slots: Vec<Slot>,
+}
+
+// SAFETY: the pointers refer to the owning `Ir`'s heap buffers and are only
+// dereferenced by the thread driving that `Ir`.
+unsafe impl Send for Merger {}
+unsafe impl Sync for Merger {}
+
+impl Merger {
+    pub(crate) fn new(
+        mode: XProp,
+        vars: &ModuleVariables,
+        ff: &mut [u8],
+        comb: &mut [u8],
+        log: &mut WriteLogBuffer,
+    ) -> Self {
+        let mut ret = Merger {
+            mode,
+            ff: ff.as_mut_ptr(),
+            ff_len: ff.len(),
+            comb: comb.as_mut_ptr(),
+            comb_len: comb.len(),
+            log,
+            slots: vec![],
+        };
+        ret.collect(vars);
+        ret
+    }
+
+    fn collect(&mut self, vars: &ModuleVariables) {
+        for var in vars.variables.values() {
+            if var.width == 0 {
+                continue;
+            }
+            let nb = var.native_bytes;
+            for (i, &cur) in var.current_values.iter().enumerate() {
+                let next = var.next_values.get(i).copied();
+                if let Some(slot) = self.slot(cur, nb, var.width, next.is_some()) {
+                    self.slots.push(slot);
+                }
+                if let Some(next) = next
+                    && next != cur
+                    && let Some(slot) = self.slot(next, nb, var.width, false)
+                {
+                    self.slots.push(slot);
+                }
+            }
+        }
+        for child in &vars.children {
+            self.collect(child);
+        }
+    }
+
+    fn slot(&self, ptr: *mut u8, nb: usize, width: usize, logged: bool) -> Option<Slot> {
+        let addr = ptr as usize;
+        let within = |base: *mut u8, len: usize| {
+            let base = base as usize;
+            (addr >= base && addr + 2 * nb <= base + len).then(|| addr - base)
+        };
+        if let Some(offset) = within(self.ff, self.ff_len) {
+            Some(Slot {
+                offset,
+                ff: true,
+                logged,
+                nb,
+                width,
+            })
+        } else {
+            within(self.comb, self.comb_len).map(|offset| Slot {
+                offset,
+                ff: false,
+                logged: false,
+                nb,
+                width,
+            })
+        }
+    }
+
+    /// Runs every branch in `sides` from the current state and leaves the
+    /// merged result.  Breaks only if every branch breaks.
+    pub(crate) fn merge(&self, sides: &[&[Statement]], mask_cache: &mut MaskCache) -> ControlFlow {
+        // SAFETY: see `Merger`; the buffers outlive the statements.
+        let (ff, comb, log) = unsafe {
+            (
+                std::slice::from_raw_parts_mut(self.ff, self.ff_len),
+                std::slice::from_raw_parts_mut(self.comb, self.comb_len),
+                &mut *self.log,
+            )
+        };
+        let ff_in = ff.to_vec();
+        let comb_in = comb.to_vec();
+        let (narrow_in, wide_in) = (log.narrow_count, log.wide_count);
+        // FF slots are compared as they will be after commit.
+        let pending = |ff: &[u8], log: &WriteLogBuffer| {
+            let mut next = ff.to_vec();
+            ff_commit_from_log(&mut next, log);
+            next
+        };
+        let base = (pending(&ff_in, log), comb_in.clone());
+
+        let mut views = Vec::with_capacity(sides.len());
+        let mut broke = true;
+        for (i, side) in sides.iter().enumerate() {
+            if i > 0 {
+                ff.copy_from_slice(&ff_in);
+                comb.copy_from_slice(&comb_in);
+                log.narrow_count = narrow_in;
+                log.wide_count = wide_in;
+            }
+            let mut flow = ControlFlow::Continue;
+            for stmt in side.iter() {
+                if stmt.eval_step(mask_cache) == ControlFlow::Break {
+                    flow = ControlFlow::Break;
+                    break;
+                }
+            }
+            broke &= flow == ControlFlow::Break;
+            views.push((pending(ff, log), comb.to_vec()));
+        }
+
+        // Keep the last branch's bytes outside any variable (temporaries),
+        // then write every changed variable's merged value.
+        log.narrow_count = narrow_in;
+        log.wide_count = wide_in;
+        for slot in &self.slots {
+            let span = slot.offset..slot.offset + 2 * slot.nb;
+            let at = |view| pick(view, slot.ff, span.clone());
+            let before = at(&base);
+            if views.iter().all(|v| at(v) == before) {
+                if !slot.logged {
+                    let buf = if slot.ff { &mut *ff } else { &mut *comb };
+                    buf[span.clone()].copy_from_slice(before);
+                }
+                continue;
+            }
+            let merged = match self.mode {
+                XProp::Pessimistic => all_x(slot.nb, slot.width),
+                _ => views.iter().skip(1).fold(at(&views[0]).to_vec(), |acc, v| {
+                    t_merge(&acc, at(v), slot.nb)
+                }),
+            };
+            if slot.logged {
+                push_log(log, slot.offset, &merged, slot.nb);
+            } else {
+                let buf = if slot.ff { &mut *ff } else { &mut *comb };
+                buf[span].copy_from_slice(&merged);
+            }
+        }
+
+        if broke {
+            ControlFlow::Break
+        } else {
+            ControlFlow::Continue
+        }
+    }
+}
+
+/// The bytes of one slot in a `(ff, comb)` snapshot.
+fn pick(view: &(Vec<u8>, Vec<u8>), ff: bool, span: Range<usize>) -> &[u8] {
+    if ff { &view.0[span] } else { &view.1[span] }
+}
+
+/// Bits on which `a` and `b` agree keep their value; the rest become X
+/// (mask 1, payload 0).
+fn t_merge(a: &[u8], b: &[u8], nb: usize) -> Vec<u8> {
+    let mut ret = a.to_vec();
+    for i in 0..nb {
+        let diff = (a[i] ^ b[i]) | (a[nb + i] ^ b[nb + i]);
+        ret[i] = a[i] & !diff;
+        ret[nb + i] = a[nb + i] | diff;
+    }
+    ret
+}
+
+fn all_x(nb: usize, width: usize) -> Vec<u8> {
+    let mut ret = vec![0u8; 2 * nb];
+    for bit in 0..width.min(nb * 8) {
+        ret[nb + bit / 8] |= 1 << (bit % 8);
+    }
+    ret
+}
+
+/// Queue `bytes` (payload then mask) for the FF at `offset`, split the way
+/// the interpreter's own pushes are.
+fn push_log(log: &mut WriteLogBuffer, offset: usize, bytes: &[u8], nb: usize) {
+    if nb <= 8 {
+        for (half, chunk) in bytes.chunks(nb).enumerate() {
+            let mut raw = [0u8; 8];
+            raw[..nb].copy_from_slice(chunk);
+            log.push_narrow(
+                (offset + half * nb) as u32,
+                u64::from_le_bytes(raw),
+                nb as u16,
+            );
+        }
+        return;
+    }
+    for (half, side) in bytes.chunks(nb).enumerate() {
+        for (i, chunk) in side.chunks(WRITE_LOG_WIDE_ENTRY_PAYLOAD_BYTES).enumerate() {
+            let at = offset + half * nb + i * WRITE_LOG_WIDE_ENTRY_PAYLOAD_BYTES;
+            // SAFETY: `chunk` holds `chunk.len()` readable bytes.
+            unsafe { log.push_wide(at as u32, chunk.as_ptr(), chunk.len()) };
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XSignalKind {
+    Register,
+    Output,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XCause {
+    /// Still X after the first reset.
+    NotReset,
+    /// Went from a known value to X.
+    Propagated,
+}
+
+/// Where X first reached one register or output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XEvent {
+    /// Hierarchical name, with `[i]` for an unpacked-array element.
+    pub signal: String,
+    pub kind: XSignalKind,
+    pub cause: XCause,
+    pub time: u64,
+    /// Declaration site.
+    pub location: Option<(PathBuf, u32, u32)>,
+}
+
+impl std::fmt::Display for XEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            XSignalKind::Register => "register",
+            XSignalKind::Output => "output",
+        };
+        write!(
+            f,
+            "X reached {kind} `{}` at time {}",
+            self.signal, self.time
+        )?;
+        match self.cause {
+            XCause::NotReset => write!(f, " (not reset)")?,
+            XCause::Propagated => write!(f, " (was known)")?,
+        }
+        if let Some((file, line, column)) = &self.location {
+            write!(f, " [{}:{line}:{column}]", file.display())?;
+        }
+        Ok(())
+    }
+}
+
+struct Watch {
+    signal: String,
+    kind: XSignalKind,
+    location: Option<(PathBuf, u32, u32)>,
+    ptr: *const u8,
+    nb: usize,
+    known: bool,
+    reported: bool,
+}
+
+/// Watches registers and top-level outputs for the X report.  Registers are
+/// the variables kept in the FF buffer; one the FF refinement moved to the
+/// comb buffer shows up only through the outputs it drives.
+pub struct Monitor {
+    watches: Vec<Watch>,
+    reset_seen: bool,
+    events: Vec<XEvent>,
+}
+
+// SAFETY: as for `Merger`.
+unsafe impl Send for Monitor {}
+
+impl Monitor {
+    pub fn new(vars: &ModuleVariables) -> Self {
+        let mut ret = Monitor {
+            watches: vec![],
+            reset_seen: false,
+            events: vec![],
+        };
+        ret.collect(vars, "", true);
+        ret
+    }
+
+    fn collect(&mut self, vars: &ModuleVariables, prefix: &str, top: bool) {
+        let mut sorted: Vec<_> = vars.variables.iter().collect();
+        sorted.sort_by_key(|(id, _)| **id);
+        for (_, var) in sorted {
+            if var.width == 0
+                || var.affiliation == Affiliation::Function
+                || var.r#type.is_clock()
+                || var.r#type.is_reset()
+            {
+                continue;
+            }
+            let is_ff = !var.next_values.is_empty();
+            let kind = if is_ff && var.kind == air::VarKind::Variable {
+                XSignalKind::Register
+            } else if top && var.kind == air::VarKind::Output {
+                XSignalKind::Output
+            } else {
+                continue;
+            };
+            let array = var.current_values.len() > 1;
+            for (i, &ptr) in var.current_values.iter().enumerate() {
+                let signal = if array {
+                    format!("{prefix}{}[{i}]", var.path)
+                } else {
+                    format!("{prefix}{}", var.path)
+                };
+                self.watches.push(Watch {
+                    signal,
+                    kind,
+                    location: location(&var.token),
+                    ptr,
+                    nb: var.native_bytes,
+                    known: false,
+                    reported: false,
+                });
+            }
+        }
+        for child in &vars.children {
+            self.collect(child, &format!("{prefix}{}.", child.name), false);
+        }
+    }
+
+    /// Check every watched signal after a step; `reset` tells whether the
+    /// step was a reset event.
+    pub fn sample(&mut self, time: u64, reset: bool) {
+        let first_after_reset = reset && !self.reset_seen;
+        self.reset_seen |= reset;
+        for w in &mut self.watches {
+            // SAFETY: `ptr` addresses the element's payload and mask.
+            let mask = unsafe { std::slice::from_raw_parts(w.ptr.add(w.nb), w.nb) };
+            let x = mask.iter().any(|b| *b != 0);
+            if x && !w.reported && (w.known || first_after_reset) {
+                w.reported = true;
+                self.events.push(XEvent {
+                    signal: w.signal.clone(),
+                    kind: w.kind,
+                    cause: if w.known {
+                        XCause::Propagated
+                    } else {
+                        XCause::NotReset
+                    },
+                    time,
+                    location: w.location.clone(),
+                });
+            }
+            w.known = !x;
+        }
+    }
+
+    pub fn events(&self) -> &[XEvent] {
+        &self.events
+    }
+}
+
+fn location(token: &TokenRange) -> Option<(PathBuf, u32, u32)> {
+    match token.beg.source {
+        TokenSource::File { path, .. } => Some((
+            PathBuf::from(path.to_string()),
+            token.beg.line,
+            token.beg.column,
+        )),
+        _ => None,
+    }
+}
+
+thread_local! {
+    static EVENTS: RefCell<Vec<XEvent>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Hands a finished test's report to the runner on this thread.
+pub fn record(events: &[XEvent]) {
+    EVENTS.with(|e| e.borrow_mut().extend_from_slice(events));
+}
+
+/// Reports recorded on this thread since the last call.
+pub fn take() -> Vec<XEvent> {
+    EVENTS.with(|e| std::mem::take(&mut *e.borrow_mut()))
+}
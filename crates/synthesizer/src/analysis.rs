@@ -4,6 +4,7 @@ use std::fmt;
 
 use crate::ir::{CellKind, GateModule, NetDriver, NetId, PortDir};
 use crate::library::CellLibrary;
+use crate::saif::NetActivity;
 
 fn max_float_width(vs: impl IntoIterator<Item = f64>, prec: usize) -> usize {
     vs.into_iter()
@@ -121,6 +122,8 @@ pub fn compute_area(module: &GateModule, library: &dyn CellLibrary) -> AreaRepor
 /// The model is:
 ///   P_cell = leakage + internal_energy × activity × f_clk
 ///   P_ff   = leakage_ff + internal_energy_ff × f_clk     (clock toggles every cycle)
+/// With a SAIF ([`compute_power_annotated`]) each cell's `activity` is instead
+/// its output net's measured toggles per cycle; RAM keeps the global factor.
 /// Net switching (C × V² × f) is intentionally omitted — it would require a
 /// capacitance-per-fanout estimate and adds ~2× complexity for little gain at
 /// this accuracy level.
@@ -138,6 +141,8 @@ pub struct PowerReport {
     pub ram_dynamic_uw: f64,
     pub clock_freq_mhz: f64,
     pub activity: f64,
+    /// Number of SAIF-annotated nets, when per-net activity was used.
+    pub saif_nets: Option<usize>,
 }
 
 /// Per-cell-kind breakdown row in a [`PowerReport`].
@@ -156,11 +161,18 @@ impl fmt::Display for PowerReport {
             "power: {:.4} mW  (leakage {:.4} mW, dynamic {:.4} mW)",
             self.total_mw, self.leakage_mw, self.dynamic_mw
         )?;
-        writeln!(
-            f,
-            "  assumptions: f_clk = {} MHz, activity = {:.2}",
-            self.clock_freq_mhz, self.activity
-        )?;
+        match self.saif_nets {
+            Some(nets) => writeln!(
+                f,
+                "  assumptions: f_clk = {} MHz, activity = SAIF ({} nets annotated)",
+                self.clock_freq_mhz, nets
+            )?,
+            None => writeln!(
+                f,
+                "  assumptions: f_clk = {} MHz, activity = {:.2}",
+                self.clock_freq_mhz, self.activity
+            )?,
+        }
         let kind_counts = self.by_kind.iter().map(|r| r.count);
         let kind_leaks = self.by_kind.iter().map(|r| r.leakage_nw);
         let kind_dyns = self.by_kind.iter().map(|r| r.dynamic_uw);
@@ -216,6 +228,18 @@ pub fn compute_power(
     library: &dyn CellLibrary,
     clock_freq_mhz: f64,
     activity: f64,
+) -> PowerReport {
+    compute_power_annotated(module, library, clock_freq_mhz, activity, None)
+}
+
+/// [`compute_power`] with per-net activity from a SAIF (see
+/// [`crate::saif::annotate`]); `activity` then only applies to RAM ports.
+pub fn compute_power_annotated(
+    module: &GateModule,
+    library: &dyn CellLibrary,
+    clock_freq_mhz: f64,
+    activity: f64,
+    annotation: Option<&NetActivity>,
 ) -> PowerReport {
     let mut buckets: HashMap<CellKind, (usize, f64, f64)> = HashMap::new();
     let mut comb_leak_nw = 0.0_f64;
@@ -223,7 +247,8 @@ pub fn compute_power(
     for cell in &module.cells {
         let info = library.info(cell.kind);
         // P (uW) = energy (pJ/tr) × activity × f_clk (MHz); pJ × MHz = uW.
-        let dyn_uw = info.internal_energy * activity * clock_freq_mhz;
+        let cell_activity = annotation.map_or(activity, |x| x.rate(cell.output));
+        let dyn_uw = info.internal_energy * cell_activity * clock_freq_mhz;
         let entry = buckets.entry(cell.kind).or_insert((0, 0.0, 0.0));
         entry.0 += 1;
         entry.1 += info.leakage;
@@ -277,6 +302,7 @@ pub fn compute_power(
         ram_dynamic_uw,
         clock_freq_mhz,
         activity,
+        saif_nets: annotation.map(|x| x.annotated),
     }
 }
 
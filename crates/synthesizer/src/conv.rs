@@ -29,9 +29,9 @@ use crate::conv::prefix::{absorb_complement, prefix_parallelize};
 use crate::conv::ram::RamCandidate;
 use crate::conv::worklist::{Simpl, dead_cell_elimination, simplify, worklist_simplify};
 use crate::ir::{
-    Cell, CellKind, ClockEdge, FfCell, GateModule, GatePort, NET_CONST0, NET_CONST1, NetDriver,
-    NetId, NetInfo, PortDir, RESERVED_NETS, RamBlock, RamReadPort, RamWritePort, ResetPolarity,
-    ResetSpec,
+    Cell, CellKind, ClockEdge, FfCell, GateDesign, GateInstance, GateModule, GatePort, InstancePin,
+    NET_CONST0, NET_CONST1, NetDriver, NetId, NetInfo, PortDir, RESERVED_NETS, RamBlock,
+    RamReadPort, RamWritePort, ResetPolarity, ResetSpec,
 };
 use crate::library::{CellLibrary, library_for};
 use crate::synthesizer_error::{SynthesizerError, UnsupportedKind};
@@ -56,6 +56,50 @@ thread_local! {
     /// outermost `convert_module` returns (see `DepthGuard`).
     static CHILD_CACHE: cell::RefCell<HashMap<*const (), Rc<GateModule>>> =
         cell::RefCell::new(HashMap::new());
+
+    /// Set for the duration of [`convert_design`]: child instances are kept as
+    /// boundaries (`keep_inst`) instead of flattened, and every converted child
+    /// lands here once, keyed by elaborated `Component` address like
+    /// [`CHILD_CACHE`].
+    static DESIGN: cell::RefCell<Option<DesignState>> = const { cell::RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct DesignState {
+    modules: Vec<GateModule>,
+    index: HashMap<*const (), usize>,
+}
+
+/// Converts `module` and the modules it instantiates into a [`GateDesign`],
+/// one [`GateModule`] per elaborated component. Each module is optimized on
+/// its own; instance pins are ports to the passes, so nothing is optimized
+/// across a boundary.
+pub fn convert_design(
+    module: &air::Module,
+    ram: RamConfig,
+    library: &'static dyn CellLibrary,
+) -> Result<GateDesign, SynthesizerError> {
+    DESIGN.with(|d| *d.borrow_mut() = Some(DesignState::default()));
+    let top = convert_module_with_library(module, ram, library);
+    let state = DESIGN.with(|d| d.borrow_mut().take()).unwrap_or_default();
+    let mut modules = state.modules;
+    modules.push(top?);
+
+    // Parameterized modules elaborate into several components sharing a name.
+    let mut names: HashSet<String> = HashSet::new();
+    for m in &mut modules {
+        let base = m.name.map(|n| n.to_string()).unwrap_or_default();
+        let mut name = base.clone();
+        let mut n = 1;
+        while !names.insert(name.clone()) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        if name != base {
+            m.name = Some(veryl_parser::resource_table::insert_str(&name));
+        }
+    }
+    Ok(GateDesign { modules })
 }
 
 pub fn convert_module(
@@ -235,6 +279,9 @@ pub(crate) struct ConvContext {
     pub ram_config: RamConfig,
     /// Target cell library, scoring the restructure A/B in `finalize`.
     pub library: &'static dyn CellLibrary,
+    /// Child instances kept by `keep_inst`, with their pins as ports local to
+    /// each entry. `finalize` appends the pins after the module's own ports.
+    pub kept_instances: Vec<(GateInstance, Vec<GatePort>)>,
 }
 
 /// One enclosing branch condition. `Neg` (the `else` side) materialises its NOT
@@ -276,6 +323,7 @@ impl ConvContext {
             cond_stack: Vec::new(),
             ram_config,
             library,
+            kept_instances: Vec::new(),
         }
     }
 
@@ -718,7 +766,11 @@ impl ConvContext {
             }
             Declaration::Inst(inst) => {
                 let mut current = init_current_comb(self, decl_idx);
-                self.flatten_inst(inst, &mut current, module_token)?;
+                if DESIGN.with(|d| d.borrow().is_some()) {
+                    self.keep_inst(inst, &mut current, module_token)?;
+                } else {
+                    self.flatten_inst(inst, &mut current, module_token)?;
+                }
                 for (vid, nets) in current {
                     let slot = match self.variables.get(&vid) {
                         Some(s) => s.clone(),
@@ -981,8 +1033,184 @@ impl ConvContext {
         Ok(())
     }
 
+    /// Hierarchy-keeping counterpart of [`Self::flatten_inst`]: the child is
+    /// converted once into the [`DESIGN`] and this module only gets the nets at
+    /// the boundary. Expressions feeding child inputs are synthesized here and
+    /// exposed as output pins; child outputs arrive on fresh input pins and are
+    /// written to their destinations like a flattened child's outputs.
+    fn keep_inst(
+        &mut self,
+        inst: &air::InstDeclaration,
+        current: &mut HashMap<air::VarId, Vec<NetId>>,
+        module_token: &veryl_parser::token_range::TokenRange,
+    ) -> Result<(), SynthesizerError> {
+        let child_module = match inst.component.as_ref() {
+            air::Component::Module(m) => m,
+            air::Component::Interface(_) => {
+                return Err(SynthesizerError::internal(
+                    "Component::Interface unexpectedly reached synthesizer",
+                ));
+            }
+            air::Component::SystemVerilog(_) => {
+                return Err(SynthesizerError::unsupported(
+                    UnsupportedKind::SystemVerilogBlackbox,
+                    module_token,
+                ));
+            }
+        };
+
+        let key = Arc::as_ptr(&inst.component) as *const ();
+        let cached = DESIGN.with(|d| d.borrow().as_ref().and_then(|s| s.index.get(&key).copied()));
+        let module_idx = match cached {
+            Some(idx) => idx,
+            None => {
+                // Converted outside the borrow: the child registers its own
+                // children first, which keeps the design ordered bottom-up.
+                let child =
+                    convert_module_with_library(child_module, self.ram_config, self.library)?;
+                DESIGN.with(|d| {
+                    let mut d = d.borrow_mut();
+                    let state = d.as_mut().expect("design conversion in progress");
+                    state.modules.push(child);
+                    let idx = state.modules.len() - 1;
+                    state.index.insert(key, idx);
+                    idx
+                })
+            }
+        };
+        let child_port: HashMap<Vec<StrId>, (PortDir, usize)> = DESIGN.with(|d| {
+            let d = d.borrow();
+            let child = &d.as_ref().expect("design conversion in progress").modules[module_idx];
+            child
+                .ports
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !child.is_instance_pin(*i))
+                .map(|(_, p)| (p.path.clone(), (p.dir, p.nets.len())))
+                .collect()
+        });
+
+        let mut pins: Vec<InstancePin> = Vec::new();
+        let mut pin_ports: Vec<GatePort> = Vec::new();
+        let mut add_pin = |path: Vec<StrId>, dir: PortDir, nets: Vec<NetId>| {
+            let mut pin_path = vec![inst.name];
+            pin_path.extend(path.iter().copied());
+            pin_ports.push(GatePort {
+                name: inst.name,
+                path: pin_path,
+                dir: match dir {
+                    PortDir::Input => PortDir::Output,
+                    PortDir::Output => PortDir::Input,
+                    PortDir::Inout => PortDir::Inout,
+                },
+                nets,
+            });
+            pins.push(InstancePin {
+                path,
+                dir,
+                port: pin_ports.len() - 1,
+            });
+        };
+
+        for input in &inst.inputs {
+            let port_path = child_module
+                .variables
+                .get(&input.id)
+                .map(|v| v.path.0.clone())
+                .ok_or_else(|| {
+                    SynthesizerError::internal(format!("inst input port {} not found", input.id))
+                })?;
+            let (dir, width) = child_port.get(&port_path).copied().ok_or_else(|| {
+                SynthesizerError::internal(format!("child port {:?} missing", port_path))
+            })?;
+            if !matches!(dir, PortDir::Input | PortDir::Inout) {
+                return Err(SynthesizerError::internal(format!(
+                    "port {:?} on child is not input-capable",
+                    port_path
+                )));
+            }
+            let mut nets = synthesize_expr(self, &input.expr, current, width)?;
+            nets.resize(width, NET_CONST0);
+            add_pin(port_path, dir, nets);
+        }
+
+        let inst_name = inst.name.to_string();
+        for output in &inst.outputs {
+            if output.dst.is_empty() {
+                continue;
+            }
+            let port_path = child_module
+                .variables
+                .get(&output.id)
+                .map(|v| v.path.0.clone())
+                .ok_or_else(|| {
+                    SynthesizerError::internal(format!("inst output port {} not found", output.id))
+                })?;
+            let (dir, width) = child_port.get(&port_path).copied().ok_or_else(|| {
+                SynthesizerError::internal(format!("child port {:?} missing", port_path))
+            })?;
+            if !matches!(dir, PortDir::Output | PortDir::Inout) {
+                return Err(SynthesizerError::internal(format!(
+                    "port {:?} on child is not output-capable",
+                    port_path
+                )));
+            }
+            let label: Vec<String> = port_path.iter().map(|s| s.to_string()).collect();
+            let origin = veryl_parser::resource_table::insert_str(&format!(
+                "{inst_name}.{}",
+                label.join(".")
+            ));
+            let parent_nets: Vec<NetId> = (0..width)
+                .map(|bit| {
+                    let n = self.alloc_net(Some((origin, bit)));
+                    self.nets[n as usize].driver = NetDriver::PortInput;
+                    n
+                })
+                .collect();
+
+            let mut widths: Vec<usize> = Vec::with_capacity(output.dst.len());
+            for dst in &output.dst {
+                widths.push(statement::dst_slice_width(self, dst)?);
+            }
+            let total_dst_width: usize = widths.iter().sum();
+            if total_dst_width > parent_nets.len() {
+                return Err(SynthesizerError::internal(format!(
+                    "inst output dst width {} exceeds child port width {}",
+                    total_dst_width,
+                    parent_nets.len()
+                )));
+            }
+            let mut lo = 0;
+            for (dst, w) in output.dst.iter().zip(widths.iter()).rev() {
+                let slice = parent_nets[lo..lo + w].to_vec();
+                statement::write_to_dst(self, dst, &slice, current)?;
+                lo += w;
+            }
+            add_pin(port_path, dir, parent_nets);
+        }
+
+        self.kept_instances.push((
+            GateInstance {
+                name: inst.name,
+                module: module_idx,
+                pins,
+            },
+            pin_ports,
+        ));
+        Ok(())
+    }
+
     fn finalize(mut self, module: &air::Module) -> Result<GateModule, SynthesizerError> {
-        let ports = self.build_ports(module);
+        let mut ports = self.build_ports(module);
+        let mut instances = Vec::with_capacity(self.kept_instances.len());
+        for (mut inst, pin_ports) in mem::take(&mut self.kept_instances) {
+            let base = ports.len();
+            for pin in &mut inst.pins {
+                pin.port += base;
+            }
+            ports.extend(pin_ports);
+            instances.push(inst);
+        }
         // Tie any undriven output/inout net to GND so downstream analysis
         // doesn't hit an Undriven in the middle of a path.
         for slot in self.variables.values() {
@@ -1035,6 +1263,7 @@ impl ConvContext {
             cells: self.cells,
             ffs: self.ffs,
             ram_blocks,
+            instances,
         };
         // Worklist-based convergence. Each cell is revisited only when one
         // of its inputs has been rewritten since the last visit, instead of
@@ -849,7 +849,7 @@ pub(crate) fn apply_part_select(
 /// analyzer `Value` carries don't-care bits (`mask_xz`). Returns `None` when
 /// the operand is not a literal, carries no don't-cares, or is a BigUint
 /// pattern (>64 bit wildcards are not supported yet).
-fn try_wildcard_pattern(expr: &Expression, width: usize) -> Option<Vec<Option<bool>>> {
+pub(crate) fn try_wildcard_pattern(expr: &Expression, width: usize) -> Option<Vec<Option<bool>>> {
     use veryl_analyzer::value::Value;
     let Expression::Term(factor) = expr else {
         return None;
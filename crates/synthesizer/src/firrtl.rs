@@ -0,0 +1,2299 @@
+//! FIRRTL export of analyzer IR modules.
+//!
+//! Unlike the gate-level exports in [`crate::yosys`], this works on the
+//! analyzer IR directly, so widths, clock/reset types and the instance
+//! hierarchy survive and CIRCT (`firtool`) can optimise and lower the design
+//! or link it against Chisel-generated blocks.
+//!
+//! Procedural blocks are evaluated symbolically into straight-line `node`s:
+//! an `if` merges the values of its branches with `mux`, and every signal
+//! gets exactly one `connect` at the end of the module. `always_ff` targets
+//! become `reg`/`regreset` clocked by the block's clock; arrays keep their
+//! FIRRTL vector type.
+
+mod check;
+
+pub use check::{CheckError, check};
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use veryl_analyzer::Context;
+use veryl_analyzer::ir::{
+    self as air, Component, Declaration, Expression, Factor, Ir as AnalyzerIr, Module, Op,
+    Statement, SystemFunctionInput, SystemFunctionKind, TypeKind, VarId, VarKind,
+};
+use veryl_analyzer::symbol::Affiliation;
+use veryl_analyzer::value::Value;
+use veryl_metadata::{Build, ClockType, ResetType};
+use veryl_parser::resource_table::StrId;
+use veryl_parser::token_range::TokenRange;
+
+use crate::conv::collect_assigned;
+use crate::conv::expression::{try_constant, try_wildcard_pattern};
+use crate::synthesizer_error::{SynthesizerError, UnsupportedKind};
+
+/// FIRRTL specification version written in the file header.
+pub const FIRRTL_VERSION: &str = "3.3.0";
+
+/// How the generic `clock` / `reset` types resolve. Fields mirror
+/// `clock_type` / `reset_type` in the `[build]` section of `Veryl.toml`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirrtlConfig {
+    pub clock_type: ClockType,
+    pub reset_type: ResetType,
+}
+
+impl From<&Build> for FirrtlConfig {
+    fn from(b: &Build) -> Self {
+        FirrtlConfig {
+            clock_type: b.clock_type,
+            reset_type: b.reset_type,
+        }
+    }
+}
+
+/// Exports `top` and every module it instantiates as one FIRRTL circuit.
+pub fn to_firrtl(
+    ir: &AnalyzerIr,
+    top: StrId,
+    config: FirrtlConfig,
+) -> Result<String, SynthesizerError> {
+    let module = ir
+        .components
+        .iter()
+        .find_map(|c| match c {
+            Component::Module(m) if m.name == top => Some(m),
+            _ => None,
+        })
+        .ok_or_else(|| SynthesizerError::top_module_not_found(top.to_string()))?;
+
+    let mut circuit = Circuit {
+        config,
+        names: Namespace::default(),
+        modules: Vec::new(),
+        cache: HashMap::new(),
+        bodies: HashMap::new(),
+    };
+    // Claimed up front so a child can never take the circuit's name.
+    let top_name = circuit.names.claim(&sanitize(&top.to_string()));
+    let info = circuit.module(module, None, Some(top_name))?;
+
+    let mut out = format!("FIRRTL version {FIRRTL_VERSION}\ncircuit {} :\n", info.name);
+    for (i, m) in circuit.modules.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(m);
+    }
+    Ok(out)
+}
+
+// ---------------------------------------------------------------------------
+// Symbolic values
+// ---------------------------------------------------------------------------
+
+/// A contiguous run of bits: either a literal or a slice of a UInt-typed
+/// FIRRTL expression (a signal reference or a `node`).
+#[derive(Clone, Debug, PartialEq)]
+enum Piece {
+    Lit(Vec<bool>),
+    Ref {
+        base: String,
+        lo: usize,
+        width: usize,
+        full: usize,
+    },
+}
+
+impl Piece {
+    fn whole(base: String, width: usize) -> Self {
+        Piece::Ref {
+            base,
+            lo: 0,
+            width,
+            full: width,
+        }
+    }
+
+    fn width(&self) -> usize {
+        match self {
+            Piece::Lit(bits) => bits.len(),
+            Piece::Ref { width, .. } => *width,
+        }
+    }
+
+    fn slice(&self, from: usize, len: usize) -> Piece {
+        match self {
+            Piece::Lit(bits) => Piece::Lit(bits[from..from + len].to_vec()),
+            Piece::Ref { base, lo, full, .. } => Piece::Ref {
+                base: base.clone(),
+                lo: lo + from,
+                width: len,
+                full: *full,
+            },
+        }
+    }
+
+    fn render(&self) -> String {
+        match self {
+            Piece::Lit(bits) => render_lit(bits),
+            Piece::Ref {
+                base,
+                lo,
+                width,
+                full,
+            } => {
+                if *lo == 0 && width == full {
+                    base.clone()
+                } else {
+                    format!("bits({}, {}, {})", base, lo + width - 1, lo)
+                }
+            }
+        }
+    }
+}
+
+/// A value as LSB-first pieces.
+type Bits = Vec<Piece>;
+
+fn bits_width(bits: &[Piece]) -> usize {
+    bits.iter().map(Piece::width).sum()
+}
+
+/// Appends `p`, fusing it with the previous piece when both are literals or
+/// adjacent slices of the same base.
+fn push_piece(out: &mut Bits, p: Piece) {
+    if p.width() == 0 {
+        return;
+    }
+    match (out.last_mut(), p) {
+        (Some(Piece::Lit(prev)), Piece::Lit(next)) => prev.extend(next),
+        (
+            Some(Piece::Ref {
+                base: pb,
+                lo: plo,
+                width: pw,
+                ..
+            }),
+            Piece::Ref {
+                base, lo, width, ..
+            },
+        ) if *pb == base && *plo + *pw == lo => *pw += width,
+        (_, p) => out.push(p),
+    }
+}
+
+fn slice(bits: &[Piece], lo: usize, width: usize) -> Bits {
+    let mut out = Vec::new();
+    let mut pos = 0;
+    for p in bits {
+        let w = p.width();
+        let (beg, end) = (lo.max(pos), (lo + width).min(pos + w));
+        if beg < end {
+            push_piece(&mut out, p.slice(beg - pos, end - beg));
+        }
+        pos += w;
+    }
+    out
+}
+
+/// `bits` with `[lo, lo + width(src))` replaced by `src`.
+fn splice(bits: &[Piece], lo: usize, src: &[Piece]) -> Bits {
+    let total = bits_width(bits);
+    let hi = (lo + bits_width(src)).min(total);
+    let mut out = slice(bits, 0, lo);
+    for p in slice(src, 0, hi - lo) {
+        push_piece(&mut out, p);
+    }
+    for p in slice(bits, hi, total - hi) {
+        push_piece(&mut out, p);
+    }
+    out
+}
+
+fn concat(lo: Bits, hi: Bits) -> Bits {
+    let mut out = lo;
+    for p in hi {
+        push_piece(&mut out, p);
+    }
+    out
+}
+
+fn lit(value: u64, width: usize) -> Bits {
+    vec![Piece::Lit(
+        (0..width)
+            .map(|i| i < 64 && (value >> i) & 1 == 1)
+            .collect(),
+    )]
+}
+
+fn zeros(width: usize) -> Bits {
+    if width == 0 {
+        Vec::new()
+    } else {
+        vec![Piece::Lit(vec![false; width])]
+    }
+}
+
+fn as_lit(bits: &[Piece]) -> Option<&[bool]> {
+    match bits {
+        [Piece::Lit(b)] => Some(b),
+        _ => None,
+    }
+}
+
+/// Known bits of a value; `x`/`z` bits read as 0.
+fn value_bits(value: &Value, width: usize) -> Bits {
+    let payload = value.payload();
+    let mask = value.mask_xz();
+    vec![Piece::Lit(
+        (0..width)
+            .map(|i| payload.bit(i as u64) && !mask.bit(i as u64))
+            .collect(),
+    )]
+}
+
+fn render_lit(bits: &[bool]) -> String {
+    let w = bits.len();
+    if w <= 64 {
+        let v = bits
+            .iter()
+            .rev()
+            .fold(0u64, |acc, &b| (acc << 1) | b as u64);
+        format!("UInt<{w}>({v})")
+    } else {
+        let mut hex = String::new();
+        for chunk in (0..w.div_ceil(4)).rev() {
+            let nibble = (0..4)
+                .filter(|j| bits.get(chunk * 4 + j).copied().unwrap_or(false))
+                .fold(0u32, |acc, j| acc | (1 << j));
+            if hex.is_empty() && nibble == 0 {
+                continue;
+            }
+            hex.push(char::from_digit(nibble, 16).unwrap().to_ascii_uppercase());
+        }
+        if hex.is_empty() {
+            hex.push('0');
+        }
+        format!("UInt<{w}>(0h{hex})")
+    }
+}
+
+/// Renders `bits` as one expression, MSB-first, with a balanced `cat` tree so
+/// wide array reads don't nest thousands of levels deep.
+fn render(bits: &[Piece]) -> String {
+    match bits {
+        [] => "UInt<0>(0)".to_string(),
+        [p] => p.render(),
+        _ => {
+            let mid = bits.len() / 2;
+            format!("cat({}, {})", render(&bits[mid..]), render(&bits[..mid]))
+        }
+    }
+}
+
+/// Position of the highest set bit plus one; the width of an index able to
+/// address `n` positions.
+fn index_width(n: usize) -> usize {
+    if n <= 1 {
+        1
+    } else {
+        (usize::BITS - (n - 1).leading_zeros()) as usize
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Names
+// ---------------------------------------------------------------------------
+
+const KEYWORDS: &[&str] = &[
+    "AsyncReset",
+    "Analog",
+    "Clock",
+    "FIRRTL",
+    "Reset",
+    "SInt",
+    "UInt",
+    "attach",
+    "circuit",
+    "connect",
+    "else",
+    "extmodule",
+    "flip",
+    "inst",
+    "input",
+    "invalidate",
+    "mem",
+    "module",
+    "node",
+    "of",
+    "output",
+    "public",
+    "reg",
+    "regreset",
+    "skip",
+    "version",
+    "when",
+    "wire",
+];
+
+/// Maps an analyzer name (which may carry generate-block `label[i]` segments)
+/// onto a FIRRTL identifier.
+fn sanitize(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    while out.ends_with('_') && out.len() > 1 {
+        out.pop();
+    }
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+fn path_name(path: &air::VarPath) -> String {
+    let segs: Vec<String> = path.0.iter().map(|s| sanitize(&s.to_string())).collect();
+    segs.join("_")
+}
+
+#[derive(Default)]
+struct Namespace {
+    used: HashSet<String>,
+}
+
+impl Namespace {
+    fn claim(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        if KEYWORDS.contains(&name.as_str()) {
+            name.push('_');
+        }
+        let stem = name.clone();
+        let mut n = 0;
+        while !self.used.insert(name.clone()) {
+            n += 1;
+            name = format!("{stem}_{n}");
+        }
+        name
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Circuit
+// ---------------------------------------------------------------------------
+
+#[derive(Clone)]
+struct PortInfo {
+    name: String,
+    width: usize,
+    dims: Vec<usize>,
+    ground: Ground,
+    dir: Dir,
+}
+
+#[derive(Clone)]
+struct ModuleInfo {
+    name: String,
+    ports: HashMap<VarId, PortInfo>,
+}
+
+struct Circuit {
+    config: FirrtlConfig,
+    names: Namespace,
+    /// Emitted module texts, children before their parents.
+    modules: Vec<String>,
+    /// Instantiated components share one `Arc` per elaboration.
+    cache: HashMap<*const Component, ModuleInfo>,
+    /// Elaborations that print identically reuse one module.
+    bodies: HashMap<(String, String), String>,
+}
+
+impl Circuit {
+    fn module(
+        &mut self,
+        m: &Module,
+        key: Option<*const Component>,
+        name: Option<String>,
+    ) -> Result<ModuleInfo, SynthesizerError> {
+        if let Some(info) = key.and_then(|k| self.cache.get(&k)) {
+            return Ok(info.clone());
+        }
+        let (ports, body) = ModuleGen::new(self, m)?.run()?;
+        let base = sanitize(&m.name.to_string());
+        let name = match (name, self.bodies.get(&(base.clone(), body.clone()))) {
+            (Some(name), _) => {
+                self.modules.push(format!("  module {name} :\n{body}"));
+                name
+            }
+            (None, Some(name)) => name.clone(),
+            (None, None) => {
+                let name = self.names.claim(&base);
+                self.modules.push(format!("  module {name} :\n{body}"));
+                self.bodies.insert((base, body), name.clone());
+                name
+            }
+        };
+        let info = ModuleInfo { name, ports };
+        if let Some(k) = key {
+            self.cache.insert(k, info.clone());
+        }
+        Ok(info)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Module lowering
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Ground {
+    UInt,
+    Clock,
+    AsyncReset,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Dir {
+    Input,
+    Output,
+}
+
+#[derive(Clone, Debug)]
+enum Storage {
+    Port(Dir),
+    Wire,
+    /// Param / const: reads fold to the literal.
+    Const(Vec<bool>),
+    /// Function argument or local: only ever lives in a [`Scope`].
+    Local,
+}
+
+#[derive(Clone, Debug)]
+struct Signal {
+    path: String,
+    name: String,
+    /// Set for `always_ff` targets; reads go through the register.
+    reg: Option<String>,
+    /// Width of one element.
+    width: usize,
+    dims: Vec<usize>,
+    ground: Ground,
+    storage: Storage,
+    token: TokenRange,
+}
+
+impl Signal {
+    fn elements(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    fn total(&self) -> usize {
+        self.width * self.elements()
+    }
+
+    fn is_declared(&self) -> bool {
+        matches!(self.storage, Storage::Port(_) | Storage::Wire)
+    }
+}
+
+fn ground_type(ground: Ground, width: usize) -> String {
+    match ground {
+        Ground::UInt => format!("UInt<{width}>"),
+        Ground::Clock => "Clock".to_string(),
+        Ground::AsyncReset => "AsyncReset".to_string(),
+    }
+}
+
+/// `T[d_n]...[d_0]`, so that `x[i_0]...[i_n]` indexes like the source.
+fn vector_type(ground: Ground, width: usize, dims: &[usize]) -> String {
+    let mut ty = ground_type(ground, width);
+    for d in dims.iter().rev() {
+        ty.push_str(&format!("[{d}]"));
+    }
+    ty
+}
+
+/// Reference to flat (row-major) element `k` of `base`.
+fn elem_ref(base: &str, dims: &[usize], k: usize) -> String {
+    let mut idx = Vec::with_capacity(dims.len());
+    let mut rest = k;
+    for d in dims.iter().rev() {
+        idx.push(rest % d);
+        rest /= d;
+    }
+    let mut out = base.to_string();
+    for i in idx.iter().rev() {
+        out.push_str(&format!("[{i}]"));
+    }
+    out
+}
+
+fn flat_index(dims: &[usize], index: &[usize]) -> Option<usize> {
+    if dims.is_empty() || (dims == [1] && index.is_empty()) {
+        return Some(0);
+    }
+    if index.len() != dims.len() {
+        return None;
+    }
+    let mut flat = 0;
+    for (i, d) in index.iter().zip(dims) {
+        if i >= d {
+            return None;
+        }
+        flat = flat * d + i;
+    }
+    Some(flat)
+}
+
+/// Symbolic state of one procedural block: the value each touched variable
+/// holds so far and which of its bits the block has written.
+#[derive(Clone, Default)]
+struct Scope {
+    values: HashMap<VarId, Bits>,
+    written: HashMap<VarId, Vec<bool>>,
+    /// `always_ff` bodies: register reads see the value before the edge.
+    nonblocking: bool,
+}
+
+impl Scope {
+    fn mark(&mut self, id: VarId, total: usize, lo: usize, len: usize) {
+        let mask = self.written.entry(id).or_insert_with(|| vec![false; total]);
+        for b in mask.iter_mut().skip(lo).take(len) {
+            *b = true;
+        }
+    }
+}
+
+/// The bits of a variable one declaration drives.
+struct Drive {
+    bits: Bits,
+    mask: Vec<bool>,
+}
+
+struct RegDrive {
+    decl: usize,
+    next: Drive,
+    init: Option<Drive>,
+}
+
+#[derive(Clone, PartialEq)]
+struct ClockReset {
+    clock: String,
+    reset: Option<String>,
+}
+
+struct ModuleGen<'c, 'm> {
+    circuit: &'c mut Circuit,
+    module: &'m Module,
+    eval_ctx: Context,
+    ns: Namespace,
+    signals: HashMap<VarId, Signal>,
+    ports: HashMap<VarId, PortInfo>,
+    /// `wire` / `inst` declarations.
+    decls: Vec<String>,
+    /// Clock and reset nodes, emitted ahead of the registers using them.
+    prelude: Vec<String>,
+    body: Vec<String>,
+    temp: usize,
+    clocks: HashMap<usize, ClockReset>,
+    /// `asUInt` reads of clock/reset references, so writing one back to the
+    /// same ground type can drop the cast pair.
+    casts: HashMap<String, Ground>,
+    /// First `always_ff` declaration assigning each register.
+    reg_decl: HashMap<VarId, usize>,
+    comb: HashMap<VarId, Vec<Drive>>,
+    ff: HashMap<VarId, Vec<RegDrive>>,
+}
+
+impl<'c, 'm> ModuleGen<'c, 'm> {
+    fn new(circuit: &'c mut Circuit, module: &'m Module) -> Result<Self, SynthesizerError> {
+        let mut eval_ctx = Context::default();
+        let mut vars: Vec<&air::Variable> = module.variables.values().collect();
+        vars.sort_by_key(|v| v.id);
+        for v in &vars {
+            if matches!(v.kind, VarKind::Param | VarKind::Const)
+                && v.r#type.total_array() == Some(1)
+            {
+                eval_ctx.variables.insert(v.id, (*v).clone());
+            }
+        }
+        let mut generator = ModuleGen {
+            circuit,
+            module,
+            eval_ctx,
+            ns: Namespace::default(),
+            signals: HashMap::new(),
+            ports: HashMap::new(),
+            decls: Vec::new(),
+            prelude: Vec::new(),
+            body: Vec::new(),
+            temp: 0,
+            clocks: HashMap::new(),
+            casts: HashMap::new(),
+            reg_decl: HashMap::new(),
+            comb: HashMap::new(),
+            ff: HashMap::new(),
+        };
+        let port_ids: HashSet<VarId> = module.ports.values().copied().collect();
+        for v in vars {
+            if let Some(sig) = generator.signal(v, port_ids.contains(&v.id))? {
+                generator.signals.insert(v.id, sig);
+            }
+        }
+        Ok(generator)
+    }
+
+    fn signal(
+        &mut self,
+        v: &air::Variable,
+        is_port: bool,
+    ) -> Result<Option<Signal>, SynthesizerError> {
+        if matches!(
+            v.r#type.kind,
+            TypeKind::Module(_)
+                | TypeKind::Interface(_)
+                | TypeKind::Modport(_, _)
+                | TypeKind::Package(_)
+                | TypeKind::Instance(_, _)
+                | TypeKind::AbstractInterface(_)
+        ) {
+            return Ok(None);
+        }
+        let path = v.path.to_string();
+        if matches!(v.r#type.kind, TypeKind::SystemVerilog) {
+            return Err(SynthesizerError::unsupported(
+                UnsupportedKind::UnsupportedVariableType {
+                    path,
+                    type_kind: "SystemVerilog".to_string(),
+                },
+                &v.token,
+            ));
+        }
+        let local = v.affiliation == Affiliation::Function;
+        let constant = matches!(v.kind, VarKind::Param | VarKind::Const);
+        let width = match v.r#type.total_width() {
+            Some(w) => w,
+            None if local || constant => return Ok(None),
+            None => {
+                return Err(SynthesizerError::unknown_width(
+                    format!(
+                        "{path} (width unresolved — likely an uninstantiated generic parameter)"
+                    ),
+                    &v.token,
+                ));
+            }
+        };
+        let mut dims = Vec::new();
+        for d in v.r#type.array.iter() {
+            match d {
+                Some(d) => dims.push(*d),
+                None => {
+                    return Err(SynthesizerError::unknown_width(
+                        format!(
+                            "{path} (array dim unresolved — likely an uninstantiated generic parameter)"
+                        ),
+                        &v.token,
+                    ));
+                }
+            }
+        }
+        if width == 0 || dims.contains(&0) {
+            return Ok(None);
+        }
+        let elements: usize = dims.iter().product();
+        let storage = if constant {
+            Storage::Const(const_bits(v, width, elements))
+        } else if local {
+            Storage::Local
+        } else if is_port {
+            match v.kind {
+                VarKind::Input => Storage::Port(Dir::Input),
+                VarKind::Output => Storage::Port(Dir::Output),
+                _ => {
+                    return Err(SynthesizerError::unsupported(
+                        UnsupportedKind::UnsupportedVariableType {
+                            path,
+                            type_kind: "inout".to_string(),
+                        },
+                        &v.token,
+                    ));
+                }
+            }
+        } else {
+            Storage::Wire
+        };
+        let ground = if width != 1 {
+            Ground::UInt
+        } else {
+            match v.r#type.kind {
+                TypeKind::Clock | TypeKind::ClockPosedge | TypeKind::ClockNegedge => Ground::Clock,
+                TypeKind::ResetAsyncHigh | TypeKind::ResetAsyncLow => Ground::AsyncReset,
+                TypeKind::Reset
+                    if matches!(
+                        self.circuit.config.reset_type,
+                        ResetType::AsyncHigh | ResetType::AsyncLow
+                    ) =>
+                {
+                    Ground::AsyncReset
+                }
+                _ => Ground::UInt,
+            }
+        };
+        let name = match storage {
+            Storage::Port(_) | Storage::Wire => self.ns.claim(&path_name(&v.path)),
+            _ => String::new(),
+        };
+        Ok(Some(Signal {
+            path,
+            name,
+            reg: None,
+            width,
+            dims,
+            ground,
+            storage,
+            token: v.token,
+        }))
+    }
+
+    fn run(mut self) -> Result<(HashMap<VarId, PortInfo>, String), SynthesizerError> {
+        let mut ids: Vec<VarId> = self.signals.keys().copied().collect();
+        ids.sort();
+
+        // Registers: every variable an `always_ff` assigns.
+        for (idx, decl) in self.module.declarations.iter().enumerate() {
+            if let Declaration::Ff(ff) = decl {
+                let mut assigned = Vec::new();
+                for st in &ff.statements {
+                    collect_assigned(st, &mut |v| assigned.push(v));
+                }
+                for v in assigned {
+                    if self.signals.get(&v).is_some_and(Signal::is_declared) {
+                        self.reg_decl.entry(v).or_insert(idx);
+                    }
+                }
+            }
+        }
+        for id in &ids {
+            if !self.reg_decl.contains_key(id) {
+                continue;
+            }
+            let sig = &self.signals[id];
+            let reg = if matches!(sig.storage, Storage::Wire) && sig.ground == Ground::UInt {
+                sig.name.clone()
+            } else {
+                self.ns.claim(&format!("{}_reg", sig.name))
+            };
+            self.signals.get_mut(id).unwrap().reg = Some(reg);
+        }
+
+        let mut ports = Vec::new();
+        for id in &ids {
+            let sig = &self.signals[id];
+            let ty = vector_type(sig.ground, sig.width, &sig.dims);
+            match sig.storage {
+                Storage::Port(dir) => {
+                    let kw = if dir == Dir::Input { "input" } else { "output" };
+                    ports.push(format!("{kw} {} : {ty}", sig.name));
+                    self.ports.insert(
+                        *id,
+                        PortInfo {
+                            name: sig.name.clone(),
+                            width: sig.width,
+                            dims: sig.dims.clone(),
+                            ground: sig.ground,
+                            dir,
+                        },
+                    );
+                }
+                Storage::Wire if sig.reg.as_ref() != Some(&sig.name) => {
+                    self.decls.push(format!("wire {} : {ty}", sig.name));
+                }
+                _ => {}
+            }
+        }
+
+        for (idx, decl) in self.module.declarations.iter().enumerate() {
+            if let Declaration::Ff(ff) = decl {
+                let cr = self.clock_reset(ff)?;
+                self.clocks.insert(idx, cr);
+            }
+        }
+        self.prelude = std::mem::take(&mut self.body);
+
+        for (idx, decl) in self.module.declarations.iter().enumerate() {
+            self.declaration(idx, decl)?;
+        }
+
+        let regs = self.finish(&ids)?;
+
+        let mut text = String::new();
+        let lines = ports
+            .iter()
+            .chain(&self.decls)
+            .chain(&self.prelude)
+            .chain(&regs.0)
+            .chain(&self.body)
+            .chain(&regs.1);
+        let mut statements = 0;
+        for (i, line) in lines.enumerate() {
+            text.push_str("    ");
+            text.push_str(line);
+            text.push('\n');
+            statements = i + 1;
+        }
+        if statements == ports.len() {
+            text.push_str("    skip\n");
+        }
+        Ok((self.ports, text))
+    }
+
+    fn fresh(&mut self) -> String {
+        loop {
+            let name = format!("_T_{}", self.temp);
+            self.temp += 1;
+            if self.ns.used.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+
+    /// Binds `expr` to a fresh `node`.
+    fn node(&mut self, expr: String, width: usize) -> Bits {
+        let name = self.fresh();
+        self.body.push(format!("node {name} = {expr}"));
+        vec![Piece::whole(name, width)]
+    }
+
+    /// Like [`Self::node`] but keeps plain references and literals inline.
+    fn operand(&mut self, bits: Bits) -> String {
+        if bits.len() == 1 {
+            bits[0].render()
+        } else {
+            let w = bits_width(&bits);
+            render(&self.node(render(&bits), w))
+        }
+    }
+
+    /// The value of a variable before the current block touches it.
+    fn initial(&mut self, sig: &Signal) -> Bits {
+        match &sig.storage {
+            Storage::Const(bits) => vec![Piece::Lit(bits.clone())],
+            Storage::Local => zeros(sig.total()),
+            Storage::Port(_) | Storage::Wire => {
+                let (base, ground) = match &sig.reg {
+                    Some(reg) => (reg.as_str(), Ground::UInt),
+                    None => (sig.name.as_str(), sig.ground),
+                };
+                let mut out = Vec::new();
+                for k in 0..sig.elements() {
+                    let r = elem_ref(base, &sig.dims, k);
+                    push_piece(&mut out, Piece::whole(self.as_uint(r, ground), sig.width));
+                }
+                out
+            }
+        }
+    }
+
+    /// Reads a reference of type `ground` as UInt.
+    fn as_uint(&mut self, r: String, ground: Ground) -> String {
+        if ground == Ground::UInt {
+            return r;
+        }
+        let expr = format!("asUInt({r})");
+        self.casts.insert(expr.clone(), ground);
+        expr
+    }
+
+    /// Wraps a UInt expression for a clock- or reset-typed sink.
+    fn to_ground(&self, ground: Ground, expr: String) -> String {
+        if ground != Ground::UInt && self.casts.get(&expr) == Some(&ground) {
+            return expr["asUInt(".len()..expr.len() - 1].to_string();
+        }
+        match ground {
+            Ground::UInt => expr,
+            Ground::Clock => format!("asClock({expr})"),
+            Ground::AsyncReset => format!("asAsyncReset({expr})"),
+        }
+    }
+
+    fn sig(&self, id: VarId) -> Result<Signal, SynthesizerError> {
+        self.signals.get(&id).cloned().ok_or_else(|| {
+            SynthesizerError::internal(format!("reference to unknown variable {id}"))
+        })
+    }
+
+    // -- clocks and resets ---------------------------------------------------
+
+    fn direct_ref(
+        &mut self,
+        sig: &Signal,
+        index: &air::VarIndex,
+        select: &air::VarSelect,
+    ) -> Option<String> {
+        if sig.reg.is_some() || !sig.is_declared() || !select.is_empty() {
+            return None;
+        }
+        let k = if index.0.is_empty() {
+            0
+        } else {
+            flat_index(&sig.dims, &index.eval_value(&mut self.eval_ctx)?)?
+        };
+        Some(elem_ref(&sig.name, &sig.dims, k))
+    }
+
+    fn clock_reset(&mut self, ff: &air::FfDeclaration) -> Result<ClockReset, SynthesizerError> {
+        let c = &ff.clock;
+        let sig = self.sig(c.id)?;
+        let negedge = match c.comptime.r#type.kind {
+            TypeKind::ClockNegedge => true,
+            TypeKind::ClockPosedge => false,
+            _ => self.circuit.config.clock_type == ClockType::NegEdge,
+        };
+        let direct = self.direct_ref(&sig, &c.index, &c.select);
+        let clock = match direct {
+            Some(r) if !negedge && sig.ground == Ground::Clock => r,
+            _ => {
+                let mut scope = Scope::default();
+                let bits = self.read_var(c.id, &c.index, &c.select, &c.comptime, &mut scope)?;
+                let v = self.operand(slice(&bits, 0, 1));
+                let expr = if negedge {
+                    format!("asClock(not({v}))")
+                } else {
+                    format!("asClock({v})")
+                };
+                render(&self.node(expr, 1))
+            }
+        };
+
+        let reset = match &ff.reset {
+            None => None,
+            Some(r) => {
+                let sig = self.sig(r.id)?;
+                let (low, sync) = match r.comptime.r#type.kind {
+                    TypeKind::ResetAsyncHigh => (false, false),
+                    TypeKind::ResetAsyncLow => (true, false),
+                    TypeKind::ResetSyncHigh => (false, true),
+                    TypeKind::ResetSyncLow => (true, true),
+                    _ => match self.circuit.config.reset_type {
+                        ResetType::AsyncHigh => (false, false),
+                        ResetType::AsyncLow => (true, false),
+                        ResetType::SyncHigh => (false, true),
+                        ResetType::SyncLow => (true, true),
+                    },
+                };
+                let direct = self.direct_ref(&sig, &r.index, &r.select);
+                let wanted = if sync {
+                    Ground::UInt
+                } else {
+                    Ground::AsyncReset
+                };
+                Some(match direct {
+                    Some(d) if !low && sig.ground == wanted => d,
+                    _ => {
+                        let mut scope = Scope::default();
+                        let bits =
+                            self.read_var(r.id, &r.index, &r.select, &r.comptime, &mut scope)?;
+                        let mut v = self.operand(slice(&bits, 0, 1));
+                        if low {
+                            v = format!("not({v})");
+                        }
+                        if !sync {
+                            v = format!("asAsyncReset({v})");
+                        }
+                        render(&self.node(v, 1))
+                    }
+                })
+            }
+        };
+        Ok(ClockReset { clock, reset })
+    }
+
+    // -- declarations --------------------------------------------------------
+
+    fn declaration(&mut self, idx: usize, decl: &Declaration) -> Result<(), SynthesizerError> {
+        match decl {
+            Declaration::Comb(x) => {
+                let mut scope = Scope::default();
+                self.statements(&x.statements, &mut scope)?;
+                self.record_comb(scope)
+            }
+            Declaration::Ff(x) => self.ff(idx, x),
+            Declaration::Inst(x) => self.inst(x),
+            // Simulation-only blocks and testbench externals have no hardware.
+            Declaration::Initial(_) | Declaration::Final(_) | Declaration::External(_) => Ok(()),
+            Declaration::Unsupported(_) | Declaration::Null => Ok(()),
+        }
+    }
+
+    fn record_comb(&mut self, scope: Scope) -> Result<(), SynthesizerError> {
+        let Scope {
+            mut values,
+            written,
+            ..
+        } = scope;
+        let mut ids: Vec<VarId> = written.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let sig = self.sig(id)?;
+            if !sig.is_declared() {
+                continue;
+            }
+            if sig.reg.is_some() {
+                return Err(SynthesizerError::internal(format!(
+                    "{} is driven both by always_ff and combinational logic",
+                    sig.path
+                )));
+            }
+            let bits = values.remove(&id).unwrap_or_else(|| self.initial(&sig));
+            self.comb.entry(id).or_default().push(Drive {
+                bits,
+                mask: written[&id].clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn ff(&mut self, idx: usize, ff: &air::FfDeclaration) -> Result<(), SynthesizerError> {
+        let (reset_stmts, main_stmts) = match ff.statements.first() {
+            Some(Statement::IfReset(r)) => {
+                let mut main = r.false_side.clone();
+                main.extend_from_slice(&ff.statements[1..]);
+                (Some(&r.true_side), main)
+            }
+            _ => (None, ff.statements.clone()),
+        };
+        let mut next = Scope {
+            nonblocking: true,
+            ..Default::default()
+        };
+        self.statements(&main_stmts, &mut next)?;
+        let mut init = Scope {
+            nonblocking: true,
+            ..Default::default()
+        };
+        if let Some(stmts) = reset_stmts {
+            self.statements(stmts, &mut init)?;
+        }
+
+        let mut ids: Vec<VarId> = next
+            .written
+            .keys()
+            .chain(init.written.keys())
+            .copied()
+            .collect();
+        ids.sort();
+        ids.dedup();
+        for id in ids {
+            let sig = self.sig(id)?;
+            if sig.reg.is_none() {
+                continue;
+            }
+            let take = |scope: &mut Scope, sig: &Signal, this: &mut Self| {
+                scope.written.remove(&id).map(|mask| Drive {
+                    bits: scope
+                        .values
+                        .remove(&id)
+                        .unwrap_or_else(|| this.initial(sig)),
+                    mask,
+                })
+            };
+            let n = take(&mut next, &sig, self).unwrap_or_else(|| Drive {
+                bits: self.initial(&sig),
+                mask: vec![false; sig.total()],
+            });
+            let i = take(&mut init, &sig, self);
+            self.ff.entry(id).or_default().push(RegDrive {
+                decl: idx,
+                next: n,
+                init: i,
+            });
+        }
+        Ok(())
+    }
+
+    fn inst(&mut self, inst: &air::InstDeclaration) -> Result<(), SynthesizerError> {
+        let child = match inst.component.as_ref() {
+            Component::Module(m) => m,
+            Component::SystemVerilog(_) => {
+                return Err(SynthesizerError::unsupported(
+                    UnsupportedKind::SystemVerilogBlackbox,
+                    &inst.token,
+                ));
+            }
+            Component::Interface(_) => {
+                return Err(SynthesizerError::internal(
+                    "Component::Interface unexpectedly reached FIRRTL export",
+                ));
+            }
+        };
+        let key = Arc::as_ptr(&inst.component);
+        let info = self.circuit.module(child, Some(key), None)?;
+        let mut path: Vec<String> = inst
+            .hierarchy
+            .iter()
+            .map(|s| sanitize(&s.to_string()))
+            .collect();
+        path.push(sanitize(&inst.name.to_string()));
+        let name = self.ns.claim(&path.join("_"));
+        self.decls.push(format!("inst {name} of {}", info.name));
+
+        let mut scope = Scope::default();
+        let mut connected = HashSet::new();
+        for input in &inst.inputs {
+            let port = info.ports.get(&input.id).ok_or_else(|| {
+                SynthesizerError::internal(format!("inst input port {} not found", input.id))
+            })?;
+            let elements: usize = port.dims.iter().product();
+            let bits = self.expr(&input.expr, port.width * elements, &mut scope)?;
+            for k in 0..elements {
+                let v = self.operand(slice(&bits, k * port.width, port.width));
+                let sink = elem_ref(&format!("{name}.{}", port.name), &port.dims, k);
+                self.body.push(format!(
+                    "connect {sink}, {}",
+                    self.to_ground(port.ground, v)
+                ));
+            }
+            connected.insert(input.id);
+        }
+        let mut unconnected: Vec<(&VarId, &PortInfo)> = info
+            .ports
+            .iter()
+            .filter(|(id, p)| p.dir == Dir::Input && !connected.contains(*id))
+            .collect();
+        unconnected.sort_by_key(|(id, _)| **id);
+        for (_, port) in unconnected {
+            self.body.push(format!("invalidate {name}.{}", port.name));
+        }
+
+        for output in &inst.outputs {
+            let port = info.ports.get(&output.id).ok_or_else(|| {
+                SynthesizerError::internal(format!("inst output port {} not found", output.id))
+            })?;
+            let mut src = Vec::new();
+            for k in 0..port.dims.iter().product() {
+                let r = elem_ref(&format!("{name}.{}", port.name), &port.dims, k);
+                push_piece(
+                    &mut src,
+                    Piece::whole(self.as_uint(r, port.ground), port.width),
+                );
+            }
+            self.assign(&output.dst, src, &mut scope)?;
+        }
+        self.record_comb(scope)
+    }
+
+    // -- statements ----------------------------------------------------------
+
+    fn statements(
+        &mut self,
+        stmts: &[Statement],
+        scope: &mut Scope,
+    ) -> Result<(), SynthesizerError> {
+        for s in stmts {
+            self.statement(s, scope)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, stmt: &Statement, scope: &mut Scope) -> Result<(), SynthesizerError> {
+        match stmt {
+            Statement::Assign(a) => {
+                if a.dst.is_empty() {
+                    return Ok(());
+                }
+                let mut total = 0;
+                for dst in &a.dst {
+                    total += self.dst_width(dst)?;
+                }
+                if total == 0 {
+                    return Ok(());
+                }
+                let src = self.expr(&a.expr, total, scope)?;
+                self.assign(&a.dst, src, scope)
+            }
+            Statement::If(x) => {
+                let cond = self.truth(&x.cond, scope)?;
+                if let Some(c) = as_lit(&cond) {
+                    let side = if c[0] { &x.true_side } else { &x.false_side };
+                    return self.statements(side, scope);
+                }
+                let cond = self.operand(cond);
+                let mut t = scope.clone();
+                self.statements(&x.true_side, &mut t)?;
+                let mut f = scope.clone();
+                self.statements(&x.false_side, &mut f)?;
+                self.merge(&cond, scope, t, f)
+            }
+            Statement::Case(x) => self.statements(&x.lower_to_nested_if(), scope),
+            Statement::IfReset(_) => Err(SynthesizerError::internal(
+                "nested if_reset reached FIRRTL export",
+            )),
+            Statement::For(x) => Err(SynthesizerError::unsupported(
+                UnsupportedKind::ForStatement,
+                &x.token,
+            )),
+            // $display / $finish etc have no hardware.
+            Statement::SystemFunctionCall(_) => Ok(()),
+            Statement::FunctionCall(call) => self.call(call, scope).map(|_| ()),
+            Statement::TbMethodCall(_) => Err(SynthesizerError::internal(
+                "testbench method call reached FIRRTL export",
+            )),
+            Statement::Fork(_) => Err(SynthesizerError::internal(
+                "fork statement reached FIRRTL export",
+            )),
+            Statement::Break => Err(SynthesizerError::internal(
+                "break statement reached FIRRTL export",
+            )),
+            Statement::Unsupported(_) | Statement::Null => Ok(()),
+        }
+    }
+
+    fn merge(
+        &mut self,
+        cond: &str,
+        base: &mut Scope,
+        t: Scope,
+        f: Scope,
+    ) -> Result<(), SynthesizerError> {
+        let mut ids: Vec<VarId> = t.values.keys().chain(f.values.keys()).copied().collect();
+        ids.sort();
+        ids.dedup();
+        for id in ids {
+            let sig = self.sig(id)?;
+            let old = base
+                .values
+                .get(&id)
+                .cloned()
+                .unwrap_or_else(|| self.initial(&sig));
+            let tb = t.values.get(&id).unwrap_or(&old);
+            let fb = f.values.get(&id).unwrap_or(&old);
+            if tb == fb {
+                base.values.insert(id, tb.clone());
+                continue;
+            }
+            let mut merged = Vec::new();
+            for k in 0..sig.elements() {
+                let tv = slice(tb, k * sig.width, sig.width);
+                let fv = slice(fb, k * sig.width, sig.width);
+                let v = if tv == fv {
+                    tv
+                } else {
+                    let expr = format!("mux({cond}, {}, {})", render(&tv), render(&fv));
+                    self.node(expr, sig.width)
+                };
+                for p in v {
+                    push_piece(&mut merged, p);
+                }
+            }
+            base.values.insert(id, merged);
+        }
+        for side in [t.written, f.written] {
+            for (id, mask) in side {
+                let entry = base
+                    .written
+                    .entry(id)
+                    .or_insert_with(|| vec![false; mask.len()]);
+                for (e, m) in entry.iter_mut().zip(mask) {
+                    *e |= m;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn dst_width(&mut self, dst: &air::AssignDestination) -> Result<usize, SynthesizerError> {
+        let sig = self.sig(dst.id)?;
+        let member_width = match &dst.comptime.part_select {
+            Some(ps) => ps
+                .part_select
+                .last()
+                .and_then(|p| p.r#type.total_width())
+                .unwrap_or(sig.width),
+            None => sig.width,
+        };
+        if dst.select.is_empty() {
+            Ok(member_width)
+        } else if dst.select.is_const() {
+            let (hi, lo) = self.eval_select(&dst.select, &dst.comptime, &dst.token, "dst")?;
+            Ok(hi + 1 - lo)
+        } else if dst.select.is_range() {
+            Err(SynthesizerError::unsupported(
+                UnsupportedKind::DynamicRangeSelect {
+                    what: format!("dst {}", sig.path),
+                },
+                &dst.token,
+            ))
+        } else {
+            Ok(1)
+        }
+    }
+
+    fn eval_select(
+        &mut self,
+        select: &air::VarSelect,
+        ct: &air::Comptime,
+        token: &TokenRange,
+        what: &str,
+    ) -> Result<(usize, usize), SynthesizerError> {
+        select
+            .eval_value(&mut self.eval_ctx, &ct.r#type, false)
+            .ok_or_else(|| SynthesizerError::dynamic_select(what.to_string(), token))
+    }
+
+    /// Distributes `src` over `dsts`, MSB-first as in `{a, b} = src`.
+    fn assign(
+        &mut self,
+        dsts: &[air::AssignDestination],
+        src: Bits,
+        scope: &mut Scope,
+    ) -> Result<(), SynthesizerError> {
+        let mut widths = Vec::with_capacity(dsts.len());
+        for dst in dsts {
+            widths.push(self.dst_width(dst)?);
+        }
+        let total: usize = widths.iter().sum();
+        let src = concat(
+            slice(&src, 0, total),
+            zeros(total.saturating_sub(bits_width(&src))),
+        );
+        let mut lo = 0;
+        for (dst, w) in dsts.iter().zip(widths).rev() {
+            self.write(dst, slice(&src, lo, w), scope)?;
+            lo += w;
+        }
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        dst: &air::AssignDestination,
+        src: Bits,
+        scope: &mut Scope,
+    ) -> Result<(), SynthesizerError> {
+        let sig = self.sig(dst.id)?;
+        let w = sig.width;
+        let (member_offset, member_width) = match &dst.comptime.part_select {
+            Some(ps) => (
+                ps.part_select.iter().map(|p| p.pos).sum(),
+                ps.part_select
+                    .last()
+                    .and_then(|p| p.r#type.total_width())
+                    .unwrap_or(w),
+            ),
+            None => (0, w),
+        };
+
+        // Bit range inside the element, or a dynamic bit index. A non-empty
+        // select is already rebased past any struct member offset.
+        let (offset, len, bit_index) = if dst.select.is_empty() {
+            (member_offset, member_width, None)
+        } else if dst.select.is_const() {
+            if let Some((_, end)) = &dst.select.1
+                && !end.comptime().is_const
+            {
+                return Err(SynthesizerError::unsupported(
+                    UnsupportedKind::DynamicRangeEnd {
+                        what: format!("dst {}", sig.path),
+                    },
+                    &dst.token,
+                ));
+            }
+            let (hi, lo) = self.eval_select(&dst.select, &dst.comptime, &dst.token, "dst")?;
+            (lo, hi + 1 - lo, None)
+        } else if dst.select.is_range() {
+            return Err(SynthesizerError::unsupported(
+                UnsupportedKind::DynamicRangeSelect {
+                    what: format!("dst {}", sig.path),
+                },
+                &dst.token,
+            ));
+        } else if dst.select.0.len() != 1 {
+            return Err(SynthesizerError::unsupported(
+                UnsupportedKind::MultiDimDynamicSelect {
+                    what: format!("dst {}", sig.path),
+                },
+                &dst.token,
+            ));
+        } else {
+            let e = &dst.select.0[0];
+            let idx = self.expr(e, self_width(e), scope)?;
+            (0, w, Some(idx))
+        };
+        if offset + len > w {
+            return Err(SynthesizerError::internal(format!(
+                "assignment to {} out of range: {}+{} of {} bits",
+                sig.path, offset, len, w
+            )));
+        }
+
+        // Target elements, each with an optional match condition.
+        let targets: Vec<(usize, Option<String>)> = if dst.index.0.is_empty() {
+            vec![(0, None)]
+        } else if dst.index.is_const() {
+            let index = dst.index.eval_value(&mut self.eval_ctx).ok_or_else(|| {
+                SynthesizerError::dynamic_select(format!("dst index {}", sig.path), &dst.token)
+            })?;
+            let k = flat_index(&sig.dims, &index).ok_or_else(|| {
+                SynthesizerError::internal(format!("array index out of range for {}", sig.path))
+            })?;
+            vec![(k, None)]
+        } else if sig.dims.len() != 1 {
+            return Err(SynthesizerError::unsupported(
+                UnsupportedKind::DynamicMultiDimIndex {
+                    what: format!("assign destination {}", sig.path),
+                },
+                &dst.token,
+            ));
+        } else {
+            let e = &dst.index.0[0];
+            let iw = self_width(e);
+            let idx = self.expr(e, iw, scope)?;
+            let idx = self.operand(idx);
+            let reachable = if iw >= usize::BITS as usize {
+                sig.elements()
+            } else {
+                sig.elements().min(1 << iw)
+            };
+            (0..reachable)
+                .map(|k| {
+                    let m = self.node(format!("eq({idx}, {})", render(&lit(k as u64, iw))), 1);
+                    (k, Some(render(&m)))
+                })
+                .collect()
+        };
+
+        let mut value = scope
+            .values
+            .get(&dst.id)
+            .cloned()
+            .unwrap_or_else(|| self.initial(&sig));
+        for (k, cond) in targets {
+            let lo = k * w + offset;
+            let old = slice(&value, lo, len);
+            let mut new = match &bit_index {
+                None => src.clone(),
+                Some(idx) => self.write_bit(&old, idx.clone(), slice(&src, 0, 1))?,
+            };
+            if let Some(c) = cond {
+                let expr = format!("mux({c}, {}, {})", render(&new), render(&old));
+                new = self.node(expr, len);
+            }
+            value = splice(&value, lo, &new);
+            scope.mark(dst.id, sig.total(), lo, len);
+        }
+        scope.values.insert(dst.id, value);
+        Ok(())
+    }
+
+    /// `old` with bit `idx` replaced by `bit`, via a one-hot mask.
+    fn write_bit(&mut self, old: &[Piece], idx: Bits, bit: Bits) -> Result<Bits, SynthesizerError> {
+        let len = bits_width(old);
+        let iw = bits_width(&idx);
+        let k = index_width(len).min(iw);
+        let idx_r = self.operand(idx);
+        let amount = if k == iw {
+            idx_r.clone()
+        } else {
+            format!("bits({idx_r}, {}, 0)", k - 1)
+        };
+        let mut mask = self.node(
+            format!("bits(dshl(UInt<1>(1), {amount}), {}, 0)", len - 1),
+            len,
+        );
+        if k < iw {
+            let big = format!("orr(bits({idx_r}, {}, {k}))", iw - 1);
+            mask = self.node(
+                format!("mux({big}, {}, {})", render(&zeros(len)), render(&mask)),
+                len,
+            );
+        }
+        let m = render(&mask);
+        let b = render(&bit);
+        let fill = if len == 1 {
+            b
+        } else {
+            render(&self.node(format!("asUInt(pad(asSInt({b}), {len}))"), len))
+        };
+        Ok(self.node(
+            format!("or(and({}, not({m})), and({m}, {fill}))", render(old)),
+            len,
+        ))
+    }
+
+    fn call(
+        &mut self,
+        call: &air::FunctionCall,
+        scope: &mut Scope,
+    ) -> Result<Bits, SynthesizerError> {
+        let func =
+            self.module.functions.get(&call.id).ok_or_else(|| {
+                SynthesizerError::internal(format!("function {} not found", call.id))
+            })?;
+        let body = func
+            .get_function(call.index.as_deref().unwrap_or(&[]))
+            .ok_or_else(|| {
+                SynthesizerError::internal(format!(
+                    "function {} has no body for the requested variant",
+                    call.id
+                ))
+            })?;
+
+        let mut inner = scope.clone();
+        inner.nonblocking = false;
+        for (path, expr) in &call.inputs {
+            let arg = *body.arg_map.get(path).ok_or_else(|| {
+                SynthesizerError::internal(format!(
+                    "input arg path {:?} not found in function {}",
+                    path, call.id
+                ))
+            })?;
+            let width = self.sig(arg)?.total();
+            let bits = self.expr(expr, width, scope)?;
+            inner.values.insert(arg, bits);
+        }
+        self.statements(&body.statements, &mut inner)?;
+
+        for (path, dsts) in &call.outputs {
+            let arg = *body.arg_map.get(path).ok_or_else(|| {
+                SynthesizerError::internal(format!(
+                    "output arg path {:?} not found in function {}",
+                    path, call.id
+                ))
+            })?;
+            let sig = self.sig(arg)?;
+            let out = inner
+                .values
+                .get(&arg)
+                .cloned()
+                .unwrap_or_else(|| self.initial(&sig));
+            self.assign(dsts, out, scope)?;
+        }
+
+        let ret_width = call.comptime.r#type.total_width().unwrap_or(0);
+        let ret = match body.ret {
+            Some(ret) => {
+                let sig = self.sig(ret)?;
+                inner
+                    .values
+                    .get(&ret)
+                    .cloned()
+                    .unwrap_or_else(|| self.initial(&sig))
+            }
+            None => zeros(ret_width),
+        };
+        Ok(self.resize(ret, ret_width, call.comptime.r#type.signed))
+    }
+
+    // -- expressions ---------------------------------------------------------
+
+    /// Evaluates `expr` at `width` bits (the SV context width), zero- or
+    /// sign-extending narrower results.
+    fn expr(
+        &mut self,
+        expr: &Expression,
+        width: usize,
+        scope: &mut Scope,
+    ) -> Result<Bits, SynthesizerError> {
+        if let Some(v) = try_constant(expr) {
+            return Ok(lit(v, width));
+        }
+        let raw = self.raw(expr, width.max(1), scope)?;
+        Ok(self.resize(raw, width, expr.comptime().r#type.signed))
+    }
+
+    fn resize(&mut self, bits: Bits, width: usize, signed: bool) -> Bits {
+        let w = bits_width(&bits);
+        if w >= width {
+            slice(&bits, 0, width)
+        } else if !signed || w == 0 {
+            concat(bits, zeros(width - w))
+        } else if let Some(b) = as_lit(&bits) {
+            let sign = b[w - 1];
+            concat(bits.clone(), vec![Piece::Lit(vec![sign; width - w])])
+        } else {
+            self.node(
+                format!("asUInt(pad(asSInt({}), {width}))", render(&bits)),
+                width,
+            )
+        }
+    }
+
+    /// 1-bit truth value of `expr` (non-zero test).
+    fn truth(&mut self, expr: &Expression, scope: &mut Scope) -> Result<Bits, SynthesizerError> {
+        let w = self_width(expr);
+        let bits = self.expr(expr, w, scope)?;
+        if w == 1 {
+            return Ok(bits);
+        }
+        if let Some(b) = as_lit(&bits) {
+            return Ok(lit(b.iter().any(|x| *x) as u64, 1));
+        }
+        Ok(self.node(format!("orr({})", render(&bits)), 1))
+    }
+
+    fn mux(&mut self, cond: Bits, a: Bits, b: Bits, width: usize) -> Bits {
+        if let Some(c) = as_lit(&cond) {
+            return if c[0] { a } else { b };
+        }
+        if a == b {
+            return a;
+        }
+        let c = self.operand(cond);
+        self.node(format!("mux({c}, {}, {})", render(&a), render(&b)), width)
+    }
+
+    fn raw(
+        &mut self,
+        expr: &Expression,
+        width: usize,
+        scope: &mut Scope,
+    ) -> Result<Bits, SynthesizerError> {
+        match expr {
+            Expression::Term(factor) => self.factor(factor, scope),
+            Expression::Unary(op, inner, _) => self.unary(*op, inner, width, scope),
+            Expression::Binary(x, op, y, ct) => {
+                self.binary(x, *op, y, width, ct.expr_context.signed, scope)
+            }
+            Expression::Ternary(cond, a, b, _) => {
+                let c = self.truth(cond, scope)?;
+                let a = self.expr(a, width, scope)?;
+                let b = self.expr(b, width, scope)?;
+                Ok(self.mux(c, a, b, width))
+            }
+            Expression::Concatenation(items, _) => {
+                // `{a, b}`: the last item is the low end.
+                let mut out = Vec::new();
+                for (e, repeat) in items.iter().rev() {
+                    let w = e.comptime().r#type.total_width().unwrap_or(0);
+                    if w == 0 {
+                        continue;
+                    }
+                    let bits = self.expr(e, w, scope)?;
+                    let n = match repeat {
+                        Some(r) => r
+                            .eval_value(&mut self.eval_ctx)
+                            .and_then(|v| v.to_usize())
+                            .ok_or_else(|| {
+                                SynthesizerError::internal(
+                                    "non-constant concatenation repeat reached FIRRTL export",
+                                )
+                            })?,
+                        None => 1,
+                    };
+                    for _ in 0..n {
+                        out = concat(out, bits.clone());
+                    }
+                }
+                if out.is_empty() {
+                    out = zeros(1);
+                }
+                Ok(out)
+            }
+            Expression::ArrayLiteral(_, _) | Expression::StructConstructor(_, _, _) => Err(
+                SynthesizerError::internal("array or struct literal reached FIRRTL export"),
+            ),
+        }
+    }
+
+    fn factor(&mut self, factor: &Factor, scope: &mut Scope) -> Result<Bits, SynthesizerError> {
+        match factor {
+            Factor::Value(ct) => {
+                let value = ct.get_value().map_err(|_| {
+                    SynthesizerError::unsupported(UnsupportedKind::NonNumericValueFactor, &ct.token)
+                })?;
+                let width = ct.r#type.total_width().unwrap_or(value.width()).max(1);
+                Ok(value_bits(value, width))
+            }
+            Factor::HierVariable(x) => Err(SynthesizerError::unsupported(
+                UnsupportedKind::NonNumericValueFactor,
+                &x.comptime.token,
+            )),
+            Factor::Variable(id, index, select, ct) => self.read_var(*id, index, select, ct, scope),
+            Factor::FunctionCall(call) => self.call(call, scope),
+            Factor::SystemFunctionCall(call) => self.system_call(call, scope),
+            Factor::Anonymous(ct) => Ok(zeros(ct.r#type.total_width().unwrap_or(0).max(1))),
+            Factor::Unknown(ct) => Err(SynthesizerError::unsupported(
+                UnsupportedKind::UnknownFactor,
+                &ct.token,
+            )),
+        }
+    }
+
+    fn read_var(
+        &mut self,
+        id: VarId,
+        index: &air::VarIndex,
+        select: &air::VarSelect,
+        ct: &air::Comptime,
+        scope: &mut Scope,
+    ) -> Result<Bits, SynthesizerError> {
+        let sig = self.sig(id)?;
+        let w = sig.width;
+        let current = if scope.nonblocking && sig.reg.is_some() {
+            None
+        } else {
+            scope.values.get(&id).cloned()
+        };
+
+        let element = if index.0.is_empty() {
+            current.unwrap_or_else(|| self.initial(&sig))
+        } else if index.is_const() {
+            let idx = index.eval_value(&mut self.eval_ctx).ok_or_else(|| {
+                SynthesizerError::dynamic_select(format!("array index on {}", sig.path), &ct.token)
+            })?;
+            let k = flat_index(&sig.dims, &idx).ok_or_else(|| {
+                SynthesizerError::internal(format!("array index out of range for {}", sig.path))
+            })?;
+            let whole = current.unwrap_or_else(|| self.initial(&sig));
+            slice(&whole, k * w, w)
+        } else if current.is_none() && sig.is_declared() && index.0.len() == sig.dims.len() {
+            // Untouched in this block: index the FIRRTL vector directly.
+            let (base, ground) = match &sig.reg {
+                Some(reg) => (reg.clone(), Ground::UInt),
+                None => (sig.name.clone(), sig.ground),
+            };
+            let mut r = base;
+            for e in &index.0 {
+                let sub = self.expr(e, self_width(e), scope)?;
+                r.push_str(&format!("[{}]", self.operand(sub)));
+            }
+            vec![Piece::whole(self.as_uint(r, ground), w)]
+        } else if sig.dims.len() != 1 {
+            return Err(SynthesizerError::unsupported(
+                UnsupportedKind::DynamicMultiDimIndex {
+                    what: format!("variable {}", sig.path),
+                },
+                &ct.token,
+            ));
+        } else {
+            let whole = current.unwrap_or_else(|| self.initial(&sig));
+            let e = &index.0[0];
+            let iw = self_width(e);
+            let idx = self.expr(e, iw, scope)?;
+            let idx = self.operand(idx);
+            let reachable = if iw >= usize::BITS as usize {
+                sig.elements()
+            } else {
+                sig.elements().min(1 << iw)
+            };
+            let mut acc = slice(&whole, (reachable - 1) * w, w);
+            for k in (0..reachable - 1).rev() {
+                let el = slice(&whole, k * w, w);
+                let expr = format!(
+                    "mux(eq({idx}, {}), {}, {})",
+                    render(&lit(k as u64, iw)),
+                    render(&el),
+                    render(&acc)
+                );
+                acc = self.node(expr, w);
+            }
+            acc
+        };
+
+        // Struct members come either as a base-coordinate `select` or as
+        // `part_select` path metadata, never both.
+        if select.is_empty() {
+            let Some(ps) = &ct.part_select else {
+                return Ok(element);
+            };
+            let offset: usize = ps.part_select.iter().map(|p| p.pos).sum();
+            let width = ps
+                .part_select
+                .last()
+                .and_then(|p| p.r#type.total_width())
+                .ok_or_else(|| {
+                    SynthesizerError::unknown_width(format!("{}.part_select", sig.path), &ct.token)
+                })?;
+            if offset + width > w {
+                return Err(SynthesizerError::internal(format!(
+                    "part_select out of range on {}",
+                    sig.path
+                )));
+            }
+            return Ok(slice(&element, offset, width));
+        }
+        if select.is_const() {
+            if let Some((_, end)) = &select.1
+                && !end.comptime().is_const
+            {
+                return Err(SynthesizerError::unsupported(
+                    UnsupportedKind::DynamicRangeEnd {
+                        what: format!("variable {}", sig.path),
+                    },
+                    &ct.token,
+                ));
+            }
+            let what = format!("variable {}", sig.path);
+            let (hi, lo) = self.eval_select(select, ct, &ct.token, &what)?;
+            if hi >= bits_width(&element) {
+                return Err(SynthesizerError::internal(format!(
+                    "bit select out of range: {}..={} of {}-bit signal",
+                    lo,
+                    hi,
+                    bits_width(&element)
+                )));
+            }
+            return Ok(slice(&element, lo, hi + 1 - lo));
+        }
+        if select.is_range() {
+            return Err(SynthesizerError::unsupported(
+                UnsupportedKind::DynamicRangeSelect {
+                    what: format!("variable {}", sig.path),
+                },
+                &ct.token,
+            ));
+        }
+        if select.0.len() != 1 {
+            return Err(SynthesizerError::unsupported(
+                UnsupportedKind::MultiDimDynamicSelect {
+                    what: format!("variable {}", sig.path),
+                },
+                &ct.token,
+            ));
+        }
+        let e = &select.0[0];
+        let idx = self.expr(e, self_width(e), scope)?;
+        let idx = self.operand(idx);
+        let el = self.operand(element);
+        Ok(self.node(format!("bits(dshr({el}, {idx}), 0, 0)"), 1))
+    }
+
+    fn system_call(
+        &mut self,
+        call: &air::SystemFunctionCall,
+        scope: &mut Scope,
+    ) -> Result<Bits, SynthesizerError> {
+        let ret_width = call.comptime.r#type.total_width().unwrap_or(0).max(1);
+        match &call.kind {
+            SystemFunctionKind::Signed(SystemFunctionInput(inner))
+            | SystemFunctionKind::Unsigned(SystemFunctionInput(inner)) => {
+                self.expr(inner, ret_width, scope)
+            }
+            SystemFunctionKind::Bits(_)
+            | SystemFunctionKind::Size(_)
+            | SystemFunctionKind::Clog2(_) => {
+                let value = call.comptime.get_value().map_err(|_| {
+                    SynthesizerError::unsupported(
+                        UnsupportedKind::SystemFunctionCall,
+                        &call.comptime.token,
+                    )
+                })?;
+                Ok(value_bits(value, ret_width))
+            }
+            SystemFunctionKind::Onehot(SystemFunctionInput(inner)) => {
+                // Exactly one bit set: non-zero and `x & (x - 1) == 0`.
+                let w = self_width(inner);
+                let x = self.expr(inner, w, scope)?;
+                let x = self.operand(x);
+                let one = render(&lit(1, w));
+                let zero = render(&zeros(w));
+                let r = self.node(
+                    format!("and(orr({x}), eq(and({x}, tail(sub({x}, {one}), 1)), {zero}))"),
+                    1,
+                );
+                Ok(self.resize(r, ret_width, false))
+            }
+            _ => Err(SynthesizerError::unsupported(
+                UnsupportedKind::SystemFunctionCall,
+                &call.comptime.token,
+            )),
+        }
+    }
+
+    fn unary(
+        &mut self,
+        op: Op,
+        inner: &Expression,
+        width: usize,
+        scope: &mut Scope,
+    ) -> Result<Bits, SynthesizerError> {
+        let reduce = |prim: &str, invert: bool| {
+            let e = format!("{prim}(X)");
+            if invert { format!("not({e})") } else { e }
+        };
+        let template = match op {
+            Op::BitNot => {
+                let x = self.expr(inner, width, scope)?;
+                return Ok(self.node(format!("not({})", render(&x)), width));
+            }
+            Op::Add => return self.expr(inner, width, scope),
+            Op::Sub => {
+                let x = self.expr(inner, width, scope)?;
+                let zero = render(&zeros(width));
+                return Ok(self.node(format!("tail(sub({zero}, {}), 1)", render(&x)), width));
+            }
+            Op::LogicNot => reduce("orr", true),
+            Op::BitAnd => reduce("andr", false),
+            Op::BitOr => reduce("orr", false),
+            Op::BitXor => reduce("xorr", false),
+            Op::BitNand => reduce("andr", true),
+            Op::BitNor => reduce("orr", true),
+            Op::BitXnor => reduce("xorr", true),
+            _ => {
+                return Err(SynthesizerError::internal(format!(
+                    "unary operator {:?} reached FIRRTL export",
+                    op
+                )));
+            }
+        };
+        let x = self.expr(inner, self_width(inner), scope)?;
+        let r = self.node(template.replace('X', &render(&x)), 1);
+        Ok(self.resize(r, width, false))
+    }
+
+    fn binary(
+        &mut self,
+        x: &Expression,
+        op: Op,
+        y: &Expression,
+        width: usize,
+        signed: bool,
+        scope: &mut Scope,
+    ) -> Result<Bits, SynthesizerError> {
+        let w = width;
+        match op {
+            Op::BitAnd | Op::BitOr | Op::BitXor | Op::BitXnor => {
+                let a = self.expr(x, w, scope)?;
+                let b = self.expr(y, w, scope)?;
+                let (a, b) = (render(&a), render(&b));
+                let e = match op {
+                    Op::BitAnd => format!("and({a}, {b})"),
+                    Op::BitOr => format!("or({a}, {b})"),
+                    Op::BitXor => format!("xor({a}, {b})"),
+                    _ => format!("not(xor({a}, {b}))"),
+                };
+                Ok(self.node(e, w))
+            }
+            Op::LogicAnd | Op::LogicOr => {
+                let a = self.truth(x, scope)?;
+                let b = self.truth(y, scope)?;
+                let prim = if op == Op::LogicAnd { "and" } else { "or" };
+                let r = self.node(format!("{prim}({}, {})", render(&a), render(&b)), 1);
+                Ok(self.resize(r, w, false))
+            }
+            Op::Add | Op::Sub | Op::Mul | Op::Div | Op::Rem => {
+                let a = self.expr(x, w, scope)?;
+                let b = self.expr(y, w, scope)?;
+                let (a, b) = (render(&a), render(&b));
+                let (e, ew) = match op {
+                    Op::Add => (format!("tail(add({a}, {b}), 1)"), w),
+                    Op::Sub => (format!("tail(sub({a}, {b}), 1)"), w),
+                    Op::Mul => (format!("bits(mul({a}, {b}), {}, 0)", w - 1), w),
+                    Op::Div if signed => (format!("asUInt(div(asSInt({a}), asSInt({b})))"), w + 1),
+                    Op::Rem if signed => (format!("asUInt(rem(asSInt({a}), asSInt({b})))"), w),
+                    Op::Div => (format!("div({a}, {b})"), w),
+                    _ => (format!("rem({a}, {b})"), w),
+                };
+                let r = self.node(e, ew);
+                Ok(slice(&r, 0, w))
+            }
+            Op::Eq
+            | Op::Ne
+            | Op::Less
+            | Op::LessEq
+            | Op::Greater
+            | Op::GreaterEq
+            | Op::EqWildcard
+            | Op::NeWildcard => {
+                let cw = x
+                    .comptime()
+                    .r#type
+                    .total_width()
+                    .unwrap_or(w)
+                    .max(y.comptime().r#type.total_width().unwrap_or(w))
+                    .max(1);
+                let negate = matches!(op, Op::Ne | Op::NeWildcard);
+                let wildcard = if matches!(op, Op::EqWildcard | Op::NeWildcard) {
+                    try_wildcard_pattern(y, cw)
+                        .map(|p| (x, p))
+                        .or_else(|| try_wildcard_pattern(x, cw).map(|p| (y, p)))
+                } else {
+                    None
+                };
+                let e = if let Some((sig, pattern)) = wildcard {
+                    let s = self.expr(sig, cw, scope)?;
+                    let mask: Vec<bool> = pattern.iter().map(Option::is_some).collect();
+                    let value: Vec<bool> = pattern.iter().map(|b| *b == Some(true)).collect();
+                    if !mask.contains(&true) {
+                        return Ok(lit(!negate as u64, w));
+                    }
+                    format!(
+                        "eq(and({}, {}), {})",
+                        render(&s),
+                        render_lit(&mask),
+                        render_lit(&value)
+                    )
+                } else {
+                    let a = self.expr(x, cw, scope)?;
+                    let b = self.expr(y, cw, scope)?;
+                    let (a, b) = (render(&a), render(&b));
+                    let prim = match op {
+                        Op::Less => "lt",
+                        Op::LessEq => "leq",
+                        Op::Greater => "gt",
+                        Op::GreaterEq => "geq",
+                        _ => "eq",
+                    };
+                    let ordered = matches!(op, Op::Less | Op::LessEq | Op::Greater | Op::GreaterEq);
+                    if ordered && signed {
+                        format!("{prim}(asSInt({a}), asSInt({b}))")
+                    } else {
+                        format!("{prim}({a}, {b})")
+                    }
+                };
+                let e = if negate { format!("not({e})") } else { e };
+                let r = self.node(e, 1);
+                Ok(self.resize(r, w, false))
+            }
+            Op::LogicShiftL | Op::LogicShiftR | Op::ArithShiftL | Op::ArithShiftR => {
+                let a = self.expr(x, w, scope)?;
+                let arith = op == Op::ArithShiftR && signed;
+                let left = matches!(op, Op::LogicShiftL | Op::ArithShiftL);
+                if let Some(n) = try_constant(y) {
+                    let n = n.min(w as u64) as usize;
+                    return Ok(if left {
+                        concat(zeros(n), slice(&a, 0, w - n))
+                    } else if arith {
+                        let kept = if n == w {
+                            slice(&a, w - 1, 1)
+                        } else {
+                            slice(&a, n, w - n)
+                        };
+                        self.resize(kept, w, true)
+                    } else {
+                        concat(slice(&a, n, w - n), zeros(n))
+                    });
+                }
+                let yw = self_width(y);
+                let amount = self.expr(y, yw, scope)?;
+                let amount = self.operand(amount);
+                // Keep the `dshl` result width bounded: amounts past the
+                // operand width only ever shift everything out.
+                let k = index_width(w).min(yw);
+                let amt = if k == yw {
+                    amount.clone()
+                } else {
+                    format!("bits({amount}, {}, 0)", k - 1)
+                };
+                let a = self.operand(a);
+                let shifted = if left {
+                    self.node(format!("bits(dshl({a}, {amt}), {}, 0)", w - 1), w)
+                } else if arith {
+                    self.node(format!("asUInt(dshr(asSInt({a}), {amt}))"), w)
+                } else {
+                    self.node(format!("dshr({a}, {amt})"), w)
+                };
+                if k == yw {
+                    return Ok(shifted);
+                }
+                let big = self.node(format!("orr(bits({amount}, {}, {k}))", yw - 1), 1);
+                let overflow = if arith {
+                    let sign = vec![Piece::Ref {
+                        base: a,
+                        lo: w - 1,
+                        width: 1,
+                        full: w,
+                    }];
+                    self.resize(sign, w, true)
+                } else {
+                    zeros(w)
+                };
+                Ok(self.mux(big, overflow, shifted, w))
+            }
+            Op::As => self.expr(x, w, scope),
+            Op::Pow => Err(SynthesizerError::unsupported(
+                UnsupportedKind::PowOperator,
+                &x.comptime().token,
+            )),
+            _ => Err(SynthesizerError::internal(format!(
+                "binary operator {:?} reached FIRRTL export",
+                op
+            ))),
+        }
+    }
+
+    // -- finalisation --------------------------------------------------------
+
+    /// Bits `[lo, lo + width)` of a variable merged over its drivers; a bit
+    /// nobody drives comes from `fallback`. Also reports whether any bit was
+    /// driven.
+    fn combine(drives: &[&Drive], lo: usize, width: usize, fallback: &[Piece]) -> (Bits, bool) {
+        let mut out = Vec::new();
+        let mut any = false;
+        let owner = |b: usize| drives.iter().rposition(|d| d.mask[lo + b]);
+        let mut b = 0;
+        while b < width {
+            let o = owner(b);
+            let mut e = b + 1;
+            while e < width && owner(e) == o {
+                e += 1;
+            }
+            let part = match o {
+                Some(i) => {
+                    any = true;
+                    slice(&drives[i].bits, lo + b, e - b)
+                }
+                None => slice(fallback, b, e - b),
+            };
+            for p in part {
+                push_piece(&mut out, p);
+            }
+            b = e;
+        }
+        (out, any)
+    }
+
+    /// Emits the register declarations and the final `connect`s.
+    fn finish(&mut self, ids: &[VarId]) -> Result<(Vec<String>, Vec<String>), SynthesizerError> {
+        let mut regs = Vec::new();
+        let mut finals = Vec::new();
+        for id in ids {
+            let sig = self.signals[id].clone();
+            let w = sig.width;
+            if let Some(reg) = &sig.reg {
+                let first = self.reg_decl[id];
+                let cr = self.clocks[&first].clone();
+                let drives = self.ff.remove(id).unwrap_or_default();
+                if drives.iter().any(|d| self.clocks[&d.decl] != cr) {
+                    return Err(SynthesizerError::unsupported(
+                        UnsupportedKind::UnsupportedVariableType {
+                            path: sig.path.clone(),
+                            type_kind: "multi-clock register".to_string(),
+                        },
+                        &sig.token,
+                    ));
+                }
+                let ty = vector_type(Ground::UInt, w, &sig.dims);
+                let own = self.initial(&Signal {
+                    ground: Ground::UInt,
+                    ..sig.clone()
+                });
+
+                let inits: Vec<&Drive> = drives.iter().filter_map(|d| d.init.as_ref()).collect();
+                let mut init_elems = Vec::new();
+                let mut any_init = false;
+                for k in 0..sig.elements() {
+                    let fallback = slice(&own, k * w, w);
+                    let (bits, any) = Self::combine(&inits, k * w, w, &fallback);
+                    any_init |= any;
+                    init_elems.push(bits);
+                }
+                match &cr.reset {
+                    Some(rst) if any_init => {
+                        let init = match init_elems.as_slice() {
+                            [single] if sig.dims.is_empty() && as_lit(single).is_some() => {
+                                render(single)
+                            }
+                            _ => {
+                                let wire = self.ns.claim(&format!("{reg}_init"));
+                                self.decls.push(format!("wire {wire} : {ty}"));
+                                for (k, bits) in init_elems.iter().enumerate() {
+                                    self.body.push(format!(
+                                        "connect {}, {}",
+                                        elem_ref(&wire, &sig.dims, k),
+                                        render(bits)
+                                    ));
+                                }
+                                wire
+                            }
+                        };
+                        regs.push(format!(
+                            "regreset {reg} : {ty}, {}, {rst}, {init}",
+                            cr.clock
+                        ));
+                    }
+                    _ => regs.push(format!("reg {reg} : {ty}, {}", cr.clock)),
+                }
+
+                let nexts: Vec<&Drive> = drives.iter().map(|d| &d.next).collect();
+                for k in 0..sig.elements() {
+                    let fallback = slice(&own, k * w, w);
+                    let (bits, any) = Self::combine(&nexts, k * w, w, &fallback);
+                    if any {
+                        finals.push(format!(
+                            "connect {}, {}",
+                            elem_ref(reg, &sig.dims, k),
+                            render(&bits)
+                        ));
+                    }
+                }
+                if reg != &sig.name {
+                    if sig.ground == Ground::UInt {
+                        finals.push(format!("connect {}, {reg}", sig.name));
+                    } else {
+                        for k in 0..sig.elements() {
+                            let r = elem_ref(reg, &sig.dims, k);
+                            finals.push(format!(
+                                "connect {}, {}",
+                                elem_ref(&sig.name, &sig.dims, k),
+                                self.to_ground(sig.ground, r)
+                            ));
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if !matches!(sig.storage, Storage::Wire | Storage::Port(Dir::Output)) {
+                continue;
+            }
+            let Some(drives) = self.comb.remove(id) else {
+                finals.push(format!("invalidate {}", sig.name));
+                continue;
+            };
+            let drives: Vec<&Drive> = drives.iter().collect();
+            let undriven = zeros(w);
+            for k in 0..sig.elements() {
+                let (bits, any) = Self::combine(&drives, k * w, w, &undriven);
+                let sink = elem_ref(&sig.name, &sig.dims, k);
+                if any {
+                    finals.push(format!(
+                        "connect {sink}, {}",
+                        self.to_ground(sig.ground, render(&bits))
+                    ));
+                } else {
+                    finals.push(format!("invalidate {sink}"));
+                }
+            }
+        }
+        Ok((regs, finals))
+    }
+}
+
+/// Width an operand has on its own (not context-extended).
+fn self_width(expr: &Expression) -> usize {
+    let ct = expr.comptime();
+    ct.r#type
+        .total_width()
+        .filter(|w| *w > 0)
+        .unwrap_or(ct.expr_context.width)
+        .max(1)
+}
+
+/// Flattened literal of a param / const; unknown or `x` values read as 0.
+fn const_bits(v: &air::Variable, width: usize, elements: usize) -> Vec<bool> {
+    let mut bits = Vec::with_capacity(width * elements);
+    for k in 0..elements {
+        let value = if v.value.len() == 1 {
+            v.value.first()
+        } else {
+            v.value.get(k)
+        };
+        match value {
+            Some(value) => match value_bits(value, width).pop() {
+                Some(Piece::Lit(b)) => bits.extend(b),
+                _ => bits.extend(vec![false; width]),
+            },
+            None => bits.extend(vec![false; width]),
+        }
+    }
+    bits
+}
@@ -0,0 +1,855 @@
+//! Bundled grammar and type check for exported FIRRTL.
+//!
+//! Covers the subset [`to_firrtl`](super::to_firrtl) writes — ground and
+//! vector types, `wire`/`reg`/`regreset`/`inst`/`node`/`connect`/
+//! `invalidate` and the primitive operations — and applies the spec's width,
+//! flow and initialization rules to it. It lets tests validate exports
+//! without a `firtool` install; it is not a general FIRRTL front end.
+
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("line {line}: {message}")]
+pub struct CheckError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// Parses `text` and checks it as a complete FIRRTL circuit.
+pub fn check(text: &str) -> Result<(), CheckError> {
+    let circuit = parse(text)?;
+    let modules: HashMap<&str, &ModuleDef> = circuit
+        .modules
+        .iter()
+        .map(|m| (m.name.as_str(), m))
+        .collect();
+    if !modules.contains_key(circuit.name.as_str()) {
+        return Err(error(
+            circuit.line,
+            format!("circuit '{}' has no module of the same name", circuit.name),
+        ));
+    }
+    let mut graph = HashMap::new();
+    for m in &circuit.modules {
+        let children = ModuleChecker::new(m, &modules).run()?;
+        graph.insert(m.name.as_str(), children);
+    }
+    check_cycles(&circuit, &graph)
+}
+
+fn error(line: usize, message: String) -> CheckError {
+    CheckError { line, message }
+}
+
+// ---------------------------------------------------------------------------
+// Lexing and layout
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Ident(String),
+    Int(String),
+    Punct(char),
+}
+
+struct Line {
+    no: usize,
+    toks: Vec<Tok>,
+}
+
+fn tokenize(no: usize, s: &str) -> Result<Vec<Tok>, CheckError> {
+    let cs: Vec<char> = s.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < cs.len() {
+        let c = cs[i];
+        let word = |i: &mut usize| {
+            let st = *i;
+            *i += 1;
+            while *i < cs.len()
+                && (cs[*i].is_ascii_alphanumeric() || cs[*i] == '_' || cs[*i] == '$')
+            {
+                *i += 1;
+            }
+            cs[st..*i].iter().collect::<String>()
+        };
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == ';' {
+            break;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            out.push(Tok::Ident(word(&mut i)));
+        } else if c.is_ascii_digit()
+            || (c == '-' && cs.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            out.push(Tok::Int(word(&mut i)));
+        } else if "()<>[],:=.".contains(c) {
+            out.push(Tok::Punct(c));
+            i += 1;
+        } else {
+            return Err(error(no, format!("unexpected character '{c}'")));
+        }
+    }
+    Ok(out)
+}
+
+struct Port {
+    name: String,
+    input: bool,
+    ty: Ty,
+    line: usize,
+}
+
+struct ModuleDef {
+    name: String,
+    ports: Vec<Port>,
+    body: Vec<Line>,
+}
+
+struct CircuitDef {
+    name: String,
+    line: usize,
+    modules: Vec<ModuleDef>,
+}
+
+fn parse(text: &str) -> Result<CircuitDef, CheckError> {
+    let mut lines = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let no = i + 1;
+        let content = raw.split(';').next().unwrap_or("");
+        if content.trim().is_empty() {
+            continue;
+        }
+        if content.contains('\t') {
+            return Err(error(no, "tab in indentation".to_string()));
+        }
+        let indent = content.len() - content.trim_start().len();
+        lines.push((no, indent, content.trim()));
+    }
+    let mut iter = lines.into_iter();
+
+    let Some((no, _, header)) = iter.next() else {
+        return Err(error(1, "empty file".to_string()));
+    };
+    let words: Vec<&str> = header.split_whitespace().collect();
+    let version_ok = words.len() == 3
+        && words[0] == "FIRRTL"
+        && words[1] == "version"
+        && words[2].split('.').count() == 3
+        && words[2]
+            .split('.')
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()));
+    if !version_ok {
+        return Err(error(
+            no,
+            "expected 'FIRRTL version <major>.<minor>.<patch>'".to_string(),
+        ));
+    }
+
+    let Some((no, indent, text)) = iter.next() else {
+        return Err(error(no, "missing circuit".to_string()));
+    };
+    let toks = tokenize(no, text)?;
+    let name = match toks.as_slice() {
+        [Tok::Ident(kw), Tok::Ident(name), Tok::Punct(':')] if kw == "circuit" && indent == 0 => {
+            name.clone()
+        }
+        _ => return Err(error(no, "expected 'circuit <name> :'".to_string())),
+    };
+    let mut circuit = CircuitDef {
+        name,
+        line: no,
+        modules: Vec::new(),
+    };
+
+    for (no, indent, text) in iter {
+        let toks = tokenize(no, text)?;
+        if indent <= 2 {
+            match toks.as_slice() {
+                [Tok::Ident(kw), Tok::Ident(name), Tok::Punct(':')]
+                    if kw == "module" && indent == 2 =>
+                {
+                    if circuit.modules.iter().any(|m| &m.name == name) {
+                        return Err(error(no, format!("module '{name}' is defined twice")));
+                    }
+                    circuit.modules.push(ModuleDef {
+                        name: name.clone(),
+                        ports: Vec::new(),
+                        body: Vec::new(),
+                    });
+                    continue;
+                }
+                _ => return Err(error(no, "expected 'module <name> :'".to_string())),
+            }
+        }
+        let Some(module) = circuit.modules.last_mut() else {
+            return Err(error(no, "statement outside of a module".to_string()));
+        };
+        if indent != 4 {
+            return Err(error(no, format!("unexpected indentation {indent}")));
+        }
+        let dir = match toks.first() {
+            Some(Tok::Ident(kw)) if kw == "input" || kw == "output" => Some(kw == "input"),
+            _ => None,
+        };
+        match dir {
+            Some(input) => {
+                if !module.body.is_empty() {
+                    return Err(error(no, "port declared after a statement".to_string()));
+                }
+                let mut c = Cursor::new(&toks, no);
+                c.pos = 1;
+                let name = c.ident()?;
+                c.expect(':')?;
+                let ty = c.ty()?;
+                c.end()?;
+                module.ports.push(Port {
+                    name,
+                    input,
+                    ty,
+                    line: no,
+                });
+            }
+            None => module.body.push(Line { no, toks }),
+        }
+    }
+    Ok(circuit)
+}
+
+struct Cursor<'a> {
+    toks: &'a [Tok],
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(toks: &'a [Tok], line: usize) -> Self {
+        Cursor { toks, pos: 0, line }
+    }
+
+    fn err<T>(&self, message: impl Into<String>) -> Result<T, CheckError> {
+        Err(error(self.line, message.into()))
+    }
+
+    fn peek(&self) -> Option<&'a Tok> {
+        self.toks.get(self.pos)
+    }
+
+    fn peek_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Tok::Punct(c))
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let hit = self.peek_punct(c);
+        if hit {
+            self.pos += 1;
+        }
+        hit
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), CheckError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.err(format!("expected '{c}'"))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, CheckError> {
+        match self.peek() {
+            Some(Tok::Ident(s)) => {
+                self.pos += 1;
+                Ok(s.clone())
+            }
+            _ => self.err("expected identifier"),
+        }
+    }
+
+    fn keyword(&mut self, kw: &str) -> Result<(), CheckError> {
+        match self.peek() {
+            Some(Tok::Ident(s)) if s == kw => {
+                self.pos += 1;
+                Ok(())
+            }
+            _ => self.err(format!("expected '{kw}'")),
+        }
+    }
+
+    fn int(&mut self) -> Result<usize, CheckError> {
+        match self.peek() {
+            Some(Tok::Int(s)) => {
+                self.pos += 1;
+                s.parse()
+                    .or_else(|_| self.err(format!("invalid integer '{s}'")))
+            }
+            _ => self.err("expected integer"),
+        }
+    }
+
+    fn end(&self) -> Result<(), CheckError> {
+        if self.pos == self.toks.len() {
+            Ok(())
+        } else {
+            self.err("unexpected trailing tokens")
+        }
+    }
+
+    fn ty(&mut self) -> Result<Ty, CheckError> {
+        let name = self.ident()?;
+        let mut ty = match name.as_str() {
+            "UInt" | "SInt" => {
+                self.expect('<')?;
+                let w = self.int()?;
+                self.expect('>')?;
+                if name == "UInt" {
+                    Ty::UInt(w)
+                } else {
+                    Ty::SInt(w)
+                }
+            }
+            "Clock" => Ty::Clock,
+            "AsyncReset" => Ty::AsyncReset,
+            "Reset" => Ty::Reset,
+            _ => return self.err(format!("unknown type '{name}'")),
+        };
+        while self.eat('[') {
+            let n = self.int()?;
+            self.expect(']')?;
+            ty = Ty::Vector(Box::new(ty), n);
+        }
+        Ok(ty)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum Ty {
+    UInt(usize),
+    SInt(usize),
+    Clock,
+    AsyncReset,
+    Reset,
+    Vector(Box<Ty>, usize),
+}
+
+impl Ty {
+    /// `(signed, width)` for integer types.
+    fn int(&self) -> Option<(bool, usize)> {
+        match self {
+            Ty::UInt(w) => Some((false, *w)),
+            Ty::SInt(w) => Some((true, *w)),
+            _ => None,
+        }
+    }
+
+    fn make(signed: bool, w: usize) -> Ty {
+        if signed { Ty::SInt(w) } else { Ty::UInt(w) }
+    }
+}
+
+impl std::fmt::Display for Ty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Ty::UInt(w) => write!(f, "UInt<{w}>"),
+            Ty::SInt(w) => write!(f, "SInt<{w}>"),
+            Ty::Clock => write!(f, "Clock"),
+            Ty::AsyncReset => write!(f, "AsyncReset"),
+            Ty::Reset => write!(f, "Reset"),
+            Ty::Vector(t, n) => write!(f, "{t}[{n}]"),
+        }
+    }
+}
+
+/// Whether a value of type `src` may be connected to a sink of type `sink`.
+fn connectable(sink: &Ty, src: &Ty) -> bool {
+    match (sink, src) {
+        (Ty::UInt(a), Ty::UInt(b)) | (Ty::SInt(a), Ty::SInt(b)) => a >= b,
+        (Ty::Clock, Ty::Clock) | (Ty::AsyncReset, Ty::AsyncReset) => true,
+        (Ty::Reset, t) => is_reset(t),
+        (Ty::Vector(a, n), Ty::Vector(b, m)) => n == m && connectable(a, b),
+        _ => false,
+    }
+}
+
+fn is_reset(ty: &Ty) -> bool {
+    matches!(ty, Ty::UInt(1) | Ty::AsyncReset | Ty::Reset)
+}
+
+fn leaves(path: &str, ty: &Ty, out: &mut Vec<String>) {
+    match ty {
+        Ty::Vector(t, n) => {
+            for i in 0..*n {
+                leaves(&format!("{path}[{i}]"), t, out);
+            }
+        }
+        _ => out.push(path.to_string()),
+    }
+}
+
+/// Number of significant bits in a literal, or `None` when it is malformed.
+fn literal_bits(text: &str) -> Option<(bool, usize)> {
+    let (neg, text) = match text.strip_prefix('-') {
+        Some(t) => (true, t),
+        None => (false, text),
+    };
+    let (radix_bits, digits) = if let Some(d) = text.strip_prefix("0h") {
+        (4, d)
+    } else if let Some(d) = text.strip_prefix("0o") {
+        (3, d)
+    } else if let Some(d) = text.strip_prefix("0b") {
+        (1, d)
+    } else {
+        let v: u128 = text.parse().ok()?;
+        return Some((neg, (u128::BITS - v.leading_zeros()) as usize));
+    };
+    let radix = 1 << radix_bits;
+    let digits = digits.trim_start_matches('0');
+    let mut chars = digits.chars();
+    let Some(first) = chars.next() else {
+        return Some((neg, 0));
+    };
+    let lead = first.to_digit(radix)?;
+    if !chars.all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    let bits = (u32::BITS - lead.leading_zeros()) as usize + radix_bits * (digits.len() - 1);
+    Some((neg, bits))
+}
+
+// ---------------------------------------------------------------------------
+// Module checks
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, PartialEq)]
+enum Flow {
+    Source,
+    Sink,
+    Duplex,
+}
+
+enum Kind {
+    Port(bool),
+    Wire,
+    Reg,
+    Node,
+    Inst(String),
+}
+
+struct ModuleChecker<'a> {
+    module: &'a ModuleDef,
+    modules: &'a HashMap<&'a str, &'a ModuleDef>,
+    symbols: HashMap<String, (Kind, Ty)>,
+    /// Leaves that must be driven, with their declaring line.
+    required: Vec<(String, usize)>,
+    driven: HashSet<String>,
+    children: Vec<(String, usize)>,
+}
+
+impl<'a> ModuleChecker<'a> {
+    fn new(module: &'a ModuleDef, modules: &'a HashMap<&'a str, &'a ModuleDef>) -> Self {
+        ModuleChecker {
+            module,
+            modules,
+            symbols: HashMap::new(),
+            required: Vec::new(),
+            driven: HashSet::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Checks the module and returns the modules it instantiates.
+    fn run(mut self) -> Result<Vec<(String, usize)>, CheckError> {
+        for p in &self.module.ports {
+            self.declare(&p.name, p.line, Kind::Port(p.input), p.ty.clone())?;
+            if !p.input {
+                self.require(&p.name, &p.ty, p.line);
+            }
+        }
+        for line in &self.module.body {
+            self.statement(line)?;
+        }
+        for (leaf, line) in &self.required {
+            let mut covered = false;
+            let mut prefix = leaf.as_str();
+            loop {
+                if self.driven.contains(prefix) {
+                    covered = true;
+                    break;
+                }
+                match prefix.rfind('[') {
+                    Some(i) => prefix = &prefix[..i],
+                    None => break,
+                }
+            }
+            if !covered {
+                return Err(error(
+                    *line,
+                    format!(
+                        "'{leaf}' in module '{}' is not fully initialized",
+                        self.module.name
+                    ),
+                ));
+            }
+        }
+        Ok(self.children)
+    }
+
+    fn declare(&mut self, name: &str, line: usize, kind: Kind, ty: Ty) -> Result<(), CheckError> {
+        if self.symbols.contains_key(name) {
+            return Err(error(line, format!("'{name}' is declared twice")));
+        }
+        self.symbols.insert(name.to_string(), (kind, ty));
+        Ok(())
+    }
+
+    fn require(&mut self, path: &str, ty: &Ty, line: usize) {
+        let mut out = Vec::new();
+        leaves(path, ty, &mut out);
+        self.required.extend(out.into_iter().map(|l| (l, line)));
+    }
+
+    fn statement(&mut self, line: &Line) -> Result<(), CheckError> {
+        let mut c = Cursor::new(&line.toks, line.no);
+        let kw = c.ident()?;
+        match kw.as_str() {
+            "skip" => {}
+            "wire" => {
+                let name = c.ident()?;
+                c.expect(':')?;
+                let ty = c.ty()?;
+                self.require(&name, &ty, line.no);
+                self.declare(&name, line.no, Kind::Wire, ty)?;
+            }
+            "reg" | "regreset" => {
+                let name = c.ident()?;
+                c.expect(':')?;
+                let ty = c.ty()?;
+                c.expect(',')?;
+                let clock = self.expr(&mut c)?;
+                if clock != Ty::Clock {
+                    return c.err(format!(
+                        "clock of '{name}' has type {clock}, expected Clock"
+                    ));
+                }
+                if kw == "regreset" {
+                    c.expect(',')?;
+                    let reset = self.expr(&mut c)?;
+                    if !is_reset(&reset) {
+                        return c.err(format!("reset of '{name}' has type {reset}"));
+                    }
+                    c.expect(',')?;
+                    let init = self.expr(&mut c)?;
+                    if !connectable(&ty, &init) {
+                        return c.err(format!(
+                            "init value of type {init} does not fit '{name}' of type {ty}"
+                        ));
+                    }
+                }
+                self.declare(&name, line.no, Kind::Reg, ty)?;
+            }
+            "inst" => {
+                let name = c.ident()?;
+                c.keyword("of")?;
+                let of = c.ident()?;
+                let Some(child) = self.modules.get(of.as_str()) else {
+                    return c.err(format!("instance of unknown module '{of}'"));
+                };
+                for p in &child.ports {
+                    if p.input {
+                        self.require(&format!("{name}.{}", p.name), &p.ty, line.no);
+                    }
+                }
+                self.children.push((of.clone(), line.no));
+                self.declare(&name, line.no, Kind::Inst(of), Ty::UInt(0))?;
+            }
+            "node" => {
+                let name = c.ident()?;
+                c.expect('=')?;
+                let ty = self.expr(&mut c)?;
+                self.declare(&name, line.no, Kind::Node, ty)?;
+            }
+            "connect" => {
+                let first = c.ident()?;
+                let (sink, flow, path) = self.reference(&mut c, first)?;
+                if flow == Flow::Source {
+                    return c.err("connect to a source-flow reference");
+                }
+                c.expect(',')?;
+                let src = self.expr(&mut c)?;
+                if !connectable(&sink, &src) {
+                    return c.err(format!("cannot connect {src} to {sink}"));
+                }
+                if let Some(path) = path {
+                    self.driven.insert(path);
+                }
+            }
+            "invalidate" => {
+                let first = c.ident()?;
+                let (_, flow, path) = self.reference(&mut c, first)?;
+                if flow == Flow::Source {
+                    return c.err("invalidate of a source-flow reference");
+                }
+                if let Some(path) = path {
+                    self.driven.insert(path);
+                }
+            }
+            _ => return c.err(format!("unknown statement '{kw}'")),
+        }
+        c.end()
+    }
+
+    /// Resolves a reference starting with `first`. The path is `None` when
+    /// the reference contains a dynamic index.
+    fn reference(
+        &mut self,
+        c: &mut Cursor,
+        first: String,
+    ) -> Result<(Ty, Flow, Option<String>), CheckError> {
+        let Some((kind, ty)) = self.symbols.get(&first) else {
+            return c.err(format!("reference to undeclared '{first}'"));
+        };
+        let mut path = Some(first.clone());
+        let (mut ty, flow) = match kind {
+            Kind::Port(true) | Kind::Node => (ty.clone(), Flow::Source),
+            Kind::Port(false) | Kind::Wire | Kind::Reg => (ty.clone(), Flow::Duplex),
+            Kind::Inst(of) => {
+                let child = self.modules[of.as_str()];
+                c.expect('.')?;
+                let field = c.ident()?;
+                let Some(port) = child.ports.iter().find(|p| p.name == field) else {
+                    return c.err(format!("module '{of}' has no port '{field}'"));
+                };
+                path = Some(format!("{first}.{field}"));
+                let flow = if port.input { Flow::Sink } else { Flow::Source };
+                (port.ty.clone(), flow)
+            }
+        };
+        while c.eat('[') {
+            let Ty::Vector(elem, n) = ty else {
+                return c.err(format!("index into non-vector type {ty}"));
+            };
+            if let Some(Tok::Int(_)) = c.peek() {
+                let i = c.int()?;
+                if i >= n {
+                    return c.err(format!("index {i} out of range for vector of {n}"));
+                }
+                path = path.map(|p| format!("{p}[{i}]"));
+            } else {
+                let idx = self.expr(c)?;
+                if !matches!(idx, Ty::UInt(_)) {
+                    return c.err(format!("dynamic index has type {idx}, expected UInt"));
+                }
+                path = None;
+            }
+            c.expect(']')?;
+            ty = *elem;
+        }
+        Ok((ty, flow, path))
+    }
+
+    fn expr(&mut self, c: &mut Cursor) -> Result<Ty, CheckError> {
+        let id = c.ident()?;
+        if (id == "UInt" || id == "SInt") && (c.peek_punct('<') || c.peek_punct('(')) {
+            return self.literal(c, id == "SInt");
+        }
+        if c.eat('(') {
+            return self.primop(c, &id);
+        }
+        let (ty, flow, _) = self.reference(c, id)?;
+        if flow == Flow::Sink {
+            return c.err("read of a sink-flow reference");
+        }
+        Ok(ty)
+    }
+
+    fn literal(&mut self, c: &mut Cursor, signed: bool) -> Result<Ty, CheckError> {
+        let width = if c.eat('<') {
+            let w = c.int()?;
+            c.expect('>')?;
+            Some(w)
+        } else {
+            None
+        };
+        c.expect('(')?;
+        let Some(Tok::Int(text)) = c.peek() else {
+            return c.err("expected literal value");
+        };
+        c.pos += 1;
+        c.expect(')')?;
+        let Some((neg, bits)) = literal_bits(text) else {
+            return c.err(format!("malformed literal '{text}'"));
+        };
+        if neg && !signed {
+            return c.err(format!("negative UInt literal '{text}'"));
+        }
+        // Signed literals need a sign bit; a negative magnitude may use it
+        // (slightly lenient: only -2^(w-1) strictly fits in w bits).
+        let need = match (signed, neg) {
+            (false, _) => bits,
+            (true, false) => bits + 1,
+            (true, true) => bits.max(1),
+        };
+        let width = width.unwrap_or(need);
+        if need > width {
+            return c.err(format!("literal '{text}' does not fit in {width} bits"));
+        }
+        Ok(Ty::make(signed, width))
+    }
+
+    fn primop(&mut self, c: &mut Cursor, op: &str) -> Result<Ty, CheckError> {
+        let (nexpr, nint) = match op {
+            "add" | "sub" | "mul" | "div" | "rem" | "lt" | "leq" | "gt" | "geq" | "eq" | "neq"
+            | "dshl" | "dshr" | "and" | "or" | "xor" | "cat" => (2, 0),
+            "pad" | "shl" | "shr" | "head" | "tail" => (1, 1),
+            "bits" => (1, 2),
+            "cvt" | "neg" | "not" | "andr" | "orr" | "xorr" | "asUInt" | "asSInt" | "asClock"
+            | "asAsyncReset" => (1, 0),
+            "mux" => (3, 0),
+            _ => return c.err(format!("unknown primitive operation '{op}'")),
+        };
+        let mut args = Vec::new();
+        let mut ints = Vec::new();
+        for i in 0..nexpr + nint {
+            if i > 0 {
+                c.expect(',')?;
+            }
+            if i < nexpr {
+                args.push(self.expr(c)?);
+            } else {
+                ints.push(c.int()?);
+            }
+        }
+        c.expect(')')?;
+
+        let bad = |c: &Cursor| c.err(format!("invalid operand types for '{op}'"));
+        if op == "mux" {
+            if !matches!(args[0], Ty::UInt(1)) {
+                return c.err(format!("mux condition has type {}", args[0]));
+            }
+            return match (args[1].int(), args[2].int()) {
+                (Some((sa, wa)), Some((sb, wb))) if sa == sb => Ok(Ty::make(sa, wa.max(wb))),
+                _ if args[1] == args[2] => Ok(args[1].clone()),
+                _ => bad(c),
+            };
+        }
+        match op {
+            "asUInt" | "asSInt" => {
+                let w = match &args[0] {
+                    Ty::Clock | Ty::AsyncReset | Ty::Reset => 1,
+                    t => match t.int() {
+                        Some((_, w)) => w,
+                        None => return bad(c),
+                    },
+                };
+                return Ok(Ty::make(op == "asSInt", w));
+            }
+            "asClock" | "asAsyncReset" => {
+                let ok = match &args[0] {
+                    Ty::Clock | Ty::AsyncReset | Ty::Reset => true,
+                    t => t.int().is_some_and(|(_, w)| w == 1),
+                };
+                if !ok {
+                    return bad(c);
+                }
+                return Ok(if op == "asClock" {
+                    Ty::Clock
+                } else {
+                    Ty::AsyncReset
+                });
+            }
+            _ => {}
+        }
+
+        let Some((s0, w0)) = args[0].int() else {
+            return bad(c);
+        };
+        if nexpr == 1 {
+            let n = ints.first().copied().unwrap_or(0);
+            return match op {
+                "pad" => Ok(Ty::make(s0, w0.max(n))),
+                "shl" => Ok(Ty::make(s0, w0 + n)),
+                "shr" => Ok(Ty::make(s0, w0.saturating_sub(n).max(usize::from(s0)))),
+                "head" if n <= w0 => Ok(Ty::UInt(n)),
+                "tail" if n <= w0 => Ok(Ty::UInt(w0 - n)),
+                "bits" => {
+                    let (hi, lo) = (ints[0], ints[1]);
+                    if hi < lo || hi >= w0 {
+                        return c.err(format!("bits({hi}, {lo}) out of range for width {w0}"));
+                    }
+                    Ok(Ty::UInt(hi - lo + 1))
+                }
+                "cvt" => Ok(Ty::SInt(if s0 { w0 } else { w0 + 1 })),
+                "neg" => Ok(Ty::SInt(w0 + 1)),
+                "not" => Ok(Ty::UInt(w0)),
+                "andr" | "orr" | "xorr" => Ok(Ty::UInt(1)),
+                _ => c.err(format!("'{op}' amount {n} exceeds width {w0}")),
+            };
+        }
+
+        let Some((s1, w1)) = args[1].int() else {
+            return bad(c);
+        };
+        match op {
+            "dshl" | "dshr" => {
+                if s1 {
+                    return c.err(format!("'{op}' amount must be UInt"));
+                }
+                if op == "dshr" {
+                    return Ok(Ty::make(s0, w0));
+                }
+                if w1 > 20 {
+                    return c.err(format!("'dshl' amount of {w1} bits is too wide"));
+                }
+                Ok(Ty::make(s0, w0 + (1 << w1) - 1))
+            }
+            "cat" => Ok(Ty::UInt(w0 + w1)),
+            _ if s0 != s1 => bad(c),
+            "add" | "sub" => Ok(Ty::make(s0, w0.max(w1) + 1)),
+            "mul" => Ok(Ty::make(s0, w0 + w1)),
+            "div" => Ok(Ty::make(s0, if s0 { w0 + 1 } else { w0 })),
+            "rem" => Ok(Ty::make(s0, w0.min(w1))),
+            "and" | "or" | "xor" => Ok(Ty::UInt(w0.max(w1))),
+            _ => Ok(Ty::UInt(1)),
+        }
+    }
+}
+
+fn check_cycles(
+    circuit: &CircuitDef,
+    graph: &HashMap<&str, Vec<(String, usize)>>,
+) -> Result<(), CheckError> {
+    // 0 = unvisited, 1 = on the stack, 2 = done
+    fn visit<'a>(
+        name: &'a str,
+        graph: &'a HashMap<&str, Vec<(String, usize)>>,
+        state: &mut HashMap<&'a str, u8>,
+    ) -> Result<(), CheckError> {
+        state.insert(name, 1);
+        for (child, line) in &graph[name] {
+            match state.get(child.as_str()).copied().unwrap_or(0) {
+                0 => visit(child, graph, state)?,
+                1 => {
+                    return Err(error(
+                        *line,
+                        format!("instance of '{child}' in '{name}' forms a cycle"),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        state.insert(name, 2);
+        Ok(())
+    }
+
+    let mut state = HashMap::new();
+    for m in &circuit.modules {
+        if !state.contains_key(m.name.as_str()) {
+            visit(&m.name, graph, &mut state)?;
+        }
+    }
+    Ok(())
+}
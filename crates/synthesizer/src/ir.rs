@@ -32,6 +32,45 @@ pub struct GateModule {
     /// flip-flops plus address mux/decode logic. Empty unless RAM inference
     /// fires (large, single-write-port arrays — see `conv::ram`).
     pub ram_blocks: Vec<RamBlock>,
+    /// Child instances kept as boundaries instead of being flattened. Empty
+    /// unless built as part of a [`GateDesign`].
+    pub instances: Vec<GateInstance>,
+}
+
+/// A module hierarchy kept unflattened, built by [`crate::build_gate_design`].
+/// Each elaborated module appears once and children precede their parents,
+/// so the top module is last.
+#[derive(Clone, Default)]
+pub struct GateDesign {
+    pub modules: Vec<GateModule>,
+}
+
+impl GateDesign {
+    pub fn top(&self) -> Option<&GateModule> {
+        self.modules.last()
+    }
+}
+
+/// A child instance inside a [`GateDesign`] module.
+#[derive(Clone)]
+pub struct GateInstance {
+    pub name: StrId,
+    /// Index of the instantiated module in [`GateDesign::modules`].
+    pub module: usize,
+    pub pins: Vec<InstancePin>,
+}
+
+/// One connected port of a [`GateInstance`]. The connected nets live in the
+/// parent's `ports[port]`, declared as an output when the parent drives the
+/// pin and as an input when the child does, so the optimization passes keep
+/// them intact like any other port.
+#[derive(Clone)]
+pub struct InstancePin {
+    /// Port path in the child module.
+    pub path: Vec<StrId>,
+    /// Direction of the port in the child module.
+    pub dir: PortDir,
+    pub port: usize,
 }
 
 #[derive(Clone)]
@@ -328,6 +367,14 @@ impl GateModule {
             }
         }
     }
+
+    /// Whether `ports[idx]` is a pin of a kept child instance rather than a
+    /// port of this module.
+    pub fn is_instance_pin(&self, idx: usize) -> bool {
+        self.instances
+            .iter()
+            .any(|x| x.pins.iter().any(|p| p.port == idx))
+    }
 }
 
 impl fmt::Display for GateModule {
@@ -2,19 +2,24 @@
 pub mod aig;
 pub mod analysis;
 pub mod conv;
+pub mod firrtl;
 pub mod ir;
 pub mod library;
+pub mod saif;
 pub mod synthesizer_error;
+pub mod yosys;
 
 pub use analysis::{
     AreaReport, PathStep, PowerKindRow, PowerReport, StepKind, TimingReport, compute_power,
-    compute_timing_top_n, port_label,
+    compute_power_annotated, compute_timing_top_n, port_label,
 };
 pub use ir::{
-    Cell, CellKind, ClockEdge, FfCell, GateIr, GateModule, GatePort, NetId, NetInfo, PortDir,
-    RamBlock, RamReadPort, RamWritePort, ResetPolarity, ResetSpec,
+    Cell, CellKind, ClockEdge, FfCell, GateDesign, GateInstance, GateIr, GateModule, GatePort,
+    InstancePin, NetId, NetInfo, PortDir, RamBlock, RamReadPort, RamWritePort, ResetPolarity,
+    ResetSpec,
 };
 pub use library::{CellInfo, CellLibrary, SramModel, library_for};
+pub use saif::{NetActivity, Saif, SaifInstance};
 pub use synthesizer_error::SynthesizerError;
 pub use veryl_metadata::{Library, Synth};
 
@@ -86,6 +91,27 @@ pub fn build_gate_ir_with_library(
     })
 }
 
+/// [`build_gate_ir_with_library`] keeping child instances as boundaries: every
+/// elaborated module is converted once into its own [`GateModule`] instead of
+/// being flattened into the top.
+pub fn build_gate_design(
+    ir: &AnalyzerIr,
+    top: StrId,
+    ram: RamConfig,
+    library: &'static dyn CellLibrary,
+) -> Result<GateDesign, SynthesizerError> {
+    for c in &ir.components {
+        if let veryl_analyzer::ir::Component::Module(m) = c
+            && m.name == top
+        {
+            return conv::convert_design(m, ram, library);
+        }
+    }
+    Err(SynthesizerError::TopModuleNotFound {
+        name: top.to_string(),
+    })
+}
+
 pub struct SynthResult {
     pub gate_ir: GateIr,
     pub area: AreaReport,
@@ -0,0 +1,397 @@
+//! SAIF (Switching Activity Interchange Format) back-annotation for power.
+//!
+//! [`Saif::parse`] reads a backward SAIF file such as the one `veryl test
+//! --saif` writes; [`annotate`] maps one of its instances onto a
+//! [`GateModule`] by name and yields a per-net toggle rate for
+//! [`crate::compute_power_annotated`].
+//!
+//! Names are matched against port paths (`a.b[3]`) and the origins of
+//! non-cell nets (flip-flop outputs), where flattened children carry an
+//! `inst.` prefix mirroring nested SAIF `INSTANCE`s. Activity is normalized
+//! per clock cycle by the busiest clock net the flip-flops use (two
+//! transitions per cycle). Nets the SAIF does not name — synthesized scratch
+//! logic — get a static probability propagated through their cell functions
+//! (inputs assumed independent) and a toggle rate of `2p(1-p)`.
+
+use crate::ir::{CellKind, GateModule, NetDriver, NetId};
+use crate::synthesizer_error::SynthesizerError;
+use std::collections::HashMap;
+
+/// Per-bit activity record of one SAIF net.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SaifNet {
+    pub t0: u64,
+    pub t1: u64,
+    pub tx: u64,
+    pub tc: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SaifInstance {
+    pub name: String,
+    /// Keyed by `(net name, bit)`; a net without an index is bit 0.
+    pub nets: HashMap<(String, usize), SaifNet>,
+    pub children: Vec<SaifInstance>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Saif {
+    pub duration: u64,
+    pub top: SaifInstance,
+}
+
+/// Per-net switching activity of a [`GateModule`], indexed by [`NetId`].
+#[derive(Clone, Debug, Default)]
+pub struct NetActivity {
+    /// Expected transitions per clock cycle.
+    pub rates: Vec<f64>,
+    /// Number of nets whose activity came straight from the SAIF.
+    pub annotated: usize,
+}
+
+impl NetActivity {
+    pub fn rate(&self, net: NetId) -> f64 {
+        self.rates.get(net as usize).copied().unwrap_or(0.0)
+    }
+}
+
+enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+impl Sexp {
+    fn atom(&self) -> Option<&str> {
+        match self {
+            Sexp::Atom(x) => Some(x),
+            Sexp::List(_) => None,
+        }
+    }
+
+    /// `(KEYWORD ...)` → `("KEYWORD", [...])`.
+    fn keyword(&self) -> Option<(&str, &[Sexp])> {
+        match self {
+            Sexp::List(items) => {
+                let (head, rest) = items.split_first()?;
+                Some((head.atom()?, rest))
+            }
+            Sexp::Atom(_) => None,
+        }
+    }
+}
+
+fn error(message: impl Into<String>) -> SynthesizerError {
+    SynthesizerError::Saif {
+        message: message.into(),
+    }
+}
+
+fn tokenize(text: &str) -> Result<Sexp, SynthesizerError> {
+    let mut stack: Vec<Vec<Sexp>> = vec![Vec::new()];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                let list = stack.pop().unwrap();
+                let Some(parent) = stack.last_mut() else {
+                    return Err(error("unbalanced ')'"));
+                };
+                parent.push(Sexp::List(list));
+            }
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => s.push(c),
+                        None => return Err(error("unterminated string")),
+                    }
+                }
+                stack.last_mut().unwrap().push(Sexp::Atom(s));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                // Identifiers keep escaped characters literally, dropping the
+                // backslash.
+                let mut s = String::new();
+                let mut c = Some(c);
+                while let Some(x) = c {
+                    if x == '\\' {
+                        if let Some(y) = chars.next() {
+                            s.push(y);
+                        }
+                    } else {
+                        s.push(x);
+                    }
+                    c = chars.next_if(|y| !y.is_whitespace() && !matches!(y, '(' | ')' | '"'));
+                }
+                stack.last_mut().unwrap().push(Sexp::Atom(s));
+            }
+        }
+    }
+    if stack.len() != 1 {
+        return Err(error("unbalanced '('"));
+    }
+    let mut top = stack.pop().unwrap();
+    match top.len() {
+        1 => Ok(top.pop().unwrap()),
+        0 => Err(error("empty file")),
+        _ => Err(error("trailing content after SAIFILE")),
+    }
+}
+
+/// Splits a trailing `[N]` bit index off a net name.
+fn split_bit(name: &str) -> (String, usize) {
+    if let Some(body) = name.strip_suffix(']')
+        && let Some((base, index)) = body.rsplit_once('[')
+        && let Ok(bit) = index.parse()
+    {
+        (base.to_string(), bit)
+    } else {
+        (name.to_string(), 0)
+    }
+}
+
+fn parse_net(item: &Sexp) -> Option<((String, usize), SaifNet)> {
+    let Sexp::List(items) = item else {
+        return None;
+    };
+    let (name, fields) = items.split_first()?;
+    let mut net = SaifNet::default();
+    for field in fields {
+        let Some((key, value)) = field.keyword() else {
+            continue;
+        };
+        let value = value
+            .first()
+            .and_then(Sexp::atom)
+            .and_then(|x| x.parse().ok());
+        let Some(value) = value else {
+            continue;
+        };
+        match key {
+            "T0" => net.t0 = value,
+            "T1" => net.t1 = value,
+            "TX" => net.tx = value,
+            "TC" => net.tc = value,
+            _ => {}
+        }
+    }
+    Some((split_bit(name.atom()?), net))
+}
+
+fn parse_instance(body: &[Sexp]) -> Result<SaifInstance, SynthesizerError> {
+    let mut inst = SaifInstance::default();
+    let mut rest = body;
+    if let Some(first) = rest.first().and_then(Sexp::atom) {
+        inst.name = first.to_string();
+        rest = &rest[1..];
+        // Optional quoted design name: `(INSTANCE "mod" inst ...)`.
+        if let Some(second) = rest.first().and_then(Sexp::atom) {
+            inst.name = second.to_string();
+            rest = &rest[1..];
+        }
+    }
+    for item in rest {
+        match item.keyword() {
+            Some(("NET", nets)) => inst.nets.extend(nets.iter().filter_map(parse_net)),
+            Some(("INSTANCE", body)) => inst.children.push(parse_instance(body)?),
+            _ => {}
+        }
+    }
+    Ok(inst)
+}
+
+impl Saif {
+    pub fn parse(text: &str) -> Result<Saif, SynthesizerError> {
+        let root = tokenize(text)?;
+        let Some(("SAIFILE", items)) = root.keyword() else {
+            return Err(error("expected (SAIFILE ...)"));
+        };
+        let mut duration = 0;
+        let mut top = None;
+        for item in items {
+            match item.keyword() {
+                Some(("DURATION", value)) => {
+                    duration = value
+                        .first()
+                        .and_then(Sexp::atom)
+                        .and_then(|x| x.parse().ok())
+                        .ok_or_else(|| error("invalid DURATION"))?;
+                }
+                Some(("INSTANCE", body)) if top.is_none() => top = Some(parse_instance(body)?),
+                _ => {}
+            }
+        }
+        let top = top.ok_or_else(|| error("no INSTANCE"))?;
+        Ok(Saif { duration, top })
+    }
+
+    /// The instance at `path` (`/`- or `.`-separated, starting below the top
+    /// instance); the top itself for an empty path.
+    pub fn find(&self, path: &str) -> Option<&SaifInstance> {
+        let mut inst = &self.top;
+        for name in path.split(['/', '.']).filter(|x| !x.is_empty()) {
+            inst = inst.children.iter().find(|x| x.name == name)?;
+        }
+        Some(inst)
+    }
+}
+
+/// Every net of `inst` and its descendants, named as a flattened
+/// [`GateModule`] would (`child.net`).
+fn flatten(inst: &SaifInstance, prefix: &str, out: &mut HashMap<(String, usize), SaifNet>) {
+    for ((name, bit), net) in &inst.nets {
+        out.insert((format!("{prefix}{name}"), *bit), *net);
+    }
+    for child in &inst.children {
+        flatten(child, &format!("{prefix}{}.", child.name), out);
+    }
+}
+
+/// Static probability of a cell's output from its inputs'.
+fn cell_probability(kind: CellKind, p: &[f64]) -> f64 {
+    let and = |a: f64, b: f64| a * b;
+    let or = |a: f64, b: f64| 1.0 - (1.0 - a) * (1.0 - b);
+    match kind {
+        CellKind::Buf => p[0],
+        CellKind::Not => 1.0 - p[0],
+        CellKind::And2 => and(p[0], p[1]),
+        CellKind::Or2 => or(p[0], p[1]),
+        CellKind::Nand2 => 1.0 - and(p[0], p[1]),
+        CellKind::Nor2 => 1.0 - or(p[0], p[1]),
+        CellKind::Xor2 => p[0] + p[1] - 2.0 * p[0] * p[1],
+        CellKind::Xnor2 => 1.0 - (p[0] + p[1] - 2.0 * p[0] * p[1]),
+        CellKind::And3 => p[0] * p[1] * p[2],
+        CellKind::Or3 => or(or(p[0], p[1]), p[2]),
+        CellKind::Nand3 => 1.0 - p[0] * p[1] * p[2],
+        CellKind::Nor3 => 1.0 - or(or(p[0], p[1]), p[2]),
+        CellKind::Ao21 => or(and(p[0], p[1]), p[2]),
+        CellKind::Aoi21 => 1.0 - or(and(p[0], p[1]), p[2]),
+        CellKind::Oa21 => and(or(p[0], p[1]), p[2]),
+        CellKind::Oai21 => 1.0 - and(or(p[0], p[1]), p[2]),
+        CellKind::Ao31 => or(p[0] * p[1] * p[2], p[3]),
+        CellKind::Aoi31 => 1.0 - or(p[0] * p[1] * p[2], p[3]),
+        CellKind::Ao22 => or(and(p[0], p[1]), and(p[2], p[3])),
+        CellKind::Aoi22 => 1.0 - or(and(p[0], p[1]), and(p[2], p[3])),
+        CellKind::Oai22 => 1.0 - and(or(p[0], p[1]), or(p[2], p[3])),
+        CellKind::Mux2 => (1.0 - p[0]) * p[1] + p[0] * p[2],
+    }
+}
+
+/// Maps `inst`'s activity onto `module`'s nets.
+pub fn annotate(module: &GateModule, inst: &SaifInstance) -> Result<NetActivity, SynthesizerError> {
+    let mut saif_nets = HashMap::new();
+    flatten(inst, "", &mut saif_nets);
+
+    let mut found: Vec<Option<SaifNet>> = vec![None; module.nets.len()];
+    for port in &module.ports {
+        let name = port
+            .path
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        for (bit, &net) in port.nets.iter().enumerate() {
+            if let Some(x) = saif_nets.get(&(name.clone(), bit)) {
+                found[net as usize] = Some(*x);
+            }
+        }
+    }
+    // A cell output's origin is only a label inherited from its inputs, so
+    // origins name a net's own value just for FF outputs and the like.
+    for (i, net) in module.nets.iter().enumerate() {
+        if found[i].is_none()
+            && !matches!(net.driver, NetDriver::Cell(_))
+            && let Some((name, bit)) = net.origin
+            && let Some(x) = saif_nets.get(&(name.to_string(), bit))
+        {
+            found[i] = Some(*x);
+        }
+    }
+
+    let cycles = module
+        .ffs
+        .iter()
+        .map(|x| x.clock)
+        .chain(module.ram_blocks.iter().map(|x| x.clock))
+        .filter_map(|x| found[x as usize].map(|x| x.tc / 2))
+        .max()
+        .filter(|x| *x > 0)
+        .ok_or_else(|| {
+            error(format!(
+                "no toggling clock of the design found in instance '{}'",
+                inst.name
+            ))
+        })?;
+
+    // Static probabilities, resolved in dependency order without recursion
+    // (cells are not stored topologically).
+    let mut prob: Vec<Option<f64>> = found
+        .iter()
+        .map(|x| {
+            x.map(|x| {
+                if x.t0 + x.t1 == 0 {
+                    0.5
+                } else {
+                    x.t1 as f64 / (x.t0 + x.t1) as f64
+                }
+            })
+        })
+        .collect();
+    for root in 0..module.nets.len() {
+        let mut stack = vec![root as NetId];
+        while let Some(&net) = stack.last() {
+            if prob[net as usize].is_some() {
+                stack.pop();
+                continue;
+            }
+            let p = match module.nets[net as usize].driver {
+                NetDriver::Const(x) => Some(if x { 1.0 } else { 0.0 }),
+                NetDriver::Cell(idx) => {
+                    let cell = &module.cells[idx];
+                    let pending: Vec<NetId> = cell
+                        .inputs
+                        .iter()
+                        .copied()
+                        .filter(|x| prob[*x as usize].is_none() && !stack.contains(x))
+                        .collect();
+                    if pending.is_empty() {
+                        // A combinational loop falls back to 0.5 on its back edge.
+                        let inputs: Vec<f64> = cell
+                            .inputs
+                            .iter()
+                            .map(|x| prob[*x as usize].unwrap_or(0.5))
+                            .collect();
+                        Some(cell_probability(cell.kind, &inputs))
+                    } else {
+                        stack.extend(pending);
+                        None
+                    }
+                }
+                _ => Some(0.5),
+            };
+            if let Some(p) = p {
+                prob[net as usize] = Some(p);
+                stack.pop();
+            }
+        }
+    }
+
+    let rates = found
+        .iter()
+        .zip(&prob)
+        .map(|(x, p)| match x {
+            Some(x) => x.tc as f64 / cycles as f64,
+            None => {
+                let p = p.unwrap_or(0.5);
+                2.0 * p * (1.0 - p)
+            }
+        })
+        .collect();
+    Ok(NetActivity {
+        rates,
+        annotated: found.iter().filter(|x| x.is_some()).count(),
+    })
+}
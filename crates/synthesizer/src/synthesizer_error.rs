@@ -44,6 +44,10 @@ pub enum SynthesizerError {
         token_source: TokenSource,
     },
 
+    #[diagnostic(severity(Error), code(synth::saif))]
+    #[error("invalid SAIF: {message}")]
+    Saif { message: String },
+
     #[diagnostic(severity(Error), code(synth::internal))]
     #[error("internal error: {message}")]
     Internal { message: String },
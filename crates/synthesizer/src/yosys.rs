@@ -0,0 +1,760 @@
+//! Yosys netlist export of a [`GateDesign`]: RTLIL text (`read_rtlil`) and the
+//! JSON netlist consumed by `read_json` / nextpnr.
+//!
+//! Each module of the design becomes one Yosys module and each kept child
+//! instance a cell whose type is the child module, so the hierarchy survives
+//! the export. Gates map onto Yosys' fine-grained `$_*_` cell library,
+//! flip-flops onto `$_DFF_*` / `$_SDFF_*` and RAM blocks onto `$mem_v2`.
+
+use crate::ir::{
+    CellKind, ClockEdge, GateDesign, GateModule, NET_CONST0, NET_CONST1, NetId, PortDir,
+    RESERVED_NETS, RamBlock, ResetPolarity,
+};
+use fxhash::{FxHashMap, FxHashSet};
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bit {
+    Zero,
+    One,
+    X,
+    Net(NetId),
+}
+
+impl Bit {
+    fn from_net(net: NetId) -> Self {
+        match net {
+            NET_CONST0 => Bit::Zero,
+            NET_CONST1 => Bit::One,
+            _ => Bit::Net(net),
+        }
+    }
+}
+
+enum Param {
+    Int(usize),
+    /// MSB-first bit string, e.g. `"0x1"`.
+    Bits(String),
+    Str(String),
+}
+
+struct CellPort {
+    name: String,
+    output: bool,
+    /// LSB-first.
+    bits: Vec<Bit>,
+}
+
+struct YCell {
+    name: String,
+    kind: String,
+    params: Vec<(&'static str, Param)>,
+    ports: Vec<CellPort>,
+}
+
+struct Wire {
+    name: String,
+    public: bool,
+    port: Option<PortDir>,
+    /// LSB-first; `None` for bits of a named signal that were optimized away.
+    bits: Vec<Option<NetId>>,
+}
+
+/// Backend-neutral view shared by the RTLIL and JSON writers. Net ids are the
+/// gate IR's own, extended past `nets.len()` for the scratch nets introduced
+/// when a compound cell is split into Yosys primitives.
+struct Netlist {
+    name: String,
+    top: bool,
+    wires: Vec<Wire>,
+    cells: Vec<YCell>,
+}
+
+struct Builder {
+    next_net: NetId,
+    cells: Vec<YCell>,
+}
+
+impl Builder {
+    fn fresh(&mut self) -> NetId {
+        let net = self.next_net;
+        self.next_net += 1;
+        net
+    }
+
+    fn gate(&mut self, kind: &str, inputs: &[NetId], output: NetId) {
+        self.gate_pins(kind, &["A", "B", "C", "D"], inputs, output);
+    }
+
+    fn gate_pins(&mut self, kind: &str, pins: &[&'static str], inputs: &[NetId], output: NetId) {
+        let mut ports: Vec<CellPort> = inputs
+            .iter()
+            .zip(pins.iter().copied())
+            .map(|(&n, name)| CellPort {
+                name: name.to_string(),
+                output: false,
+                bits: vec![Bit::from_net(n)],
+            })
+            .collect();
+        ports.push(CellPort {
+            name: "Y".into(),
+            output: true,
+            bits: vec![Bit::Net(output)],
+        });
+        self.push(kind, Vec::new(), ports);
+    }
+
+    /// Two-level cell `outer(inner(a, b), rest..)`.
+    fn cascade(&mut self, inner: &str, outer: &str, inputs: &[NetId], output: NetId) {
+        let t = self.fresh();
+        self.gate(inner, &inputs[..2], t);
+        let mut rest = vec![t];
+        rest.extend_from_slice(&inputs[2..]);
+        self.gate(outer, &rest, output);
+    }
+
+    fn push(&mut self, kind: &str, params: Vec<(&'static str, Param)>, ports: Vec<CellPort>) {
+        let name = format!("$c{}", self.cells.len());
+        self.cells.push(YCell {
+            name,
+            kind: kind.to_string(),
+            params,
+            ports,
+        });
+    }
+
+    fn cell(&mut self, kind: CellKind, i: &[NetId], y: NetId) {
+        match kind {
+            CellKind::Buf => self.gate("$_BUF_", i, y),
+            CellKind::Not => self.gate("$_NOT_", i, y),
+            CellKind::And2 => self.gate("$_AND_", i, y),
+            CellKind::Or2 => self.gate("$_OR_", i, y),
+            CellKind::Nand2 => self.gate("$_NAND_", i, y),
+            CellKind::Nor2 => self.gate("$_NOR_", i, y),
+            CellKind::Xor2 => self.gate("$_XOR_", i, y),
+            CellKind::Xnor2 => self.gate("$_XNOR_", i, y),
+            CellKind::And3 => self.cascade("$_AND_", "$_AND_", i, y),
+            CellKind::Or3 => self.cascade("$_OR_", "$_OR_", i, y),
+            CellKind::Nand3 => self.cascade("$_AND_", "$_NAND_", i, y),
+            CellKind::Nor3 => self.cascade("$_OR_", "$_NOR_", i, y),
+            CellKind::Ao21 => self.cascade("$_AND_", "$_OR_", i, y),
+            CellKind::Aoi21 => self.gate("$_AOI3_", i, y),
+            CellKind::Oa21 => self.cascade("$_OR_", "$_AND_", i, y),
+            CellKind::Oai21 => self.gate("$_OAI3_", i, y),
+            CellKind::Ao31 => {
+                let t = self.fresh();
+                self.cascade("$_AND_", "$_AND_", &i[..3], t);
+                self.gate("$_OR_", &[t, i[3]], y);
+            }
+            CellKind::Aoi31 => self.cascade("$_AND_", "$_AOI3_", i, y),
+            CellKind::Ao22 => {
+                let t = self.fresh();
+                self.gate("$_AOI4_", i, t);
+                self.gate("$_NOT_", &[t], y);
+            }
+            CellKind::Aoi22 => self.gate("$_AOI4_", i, y),
+            CellKind::Oai22 => self.gate("$_OAI4_", i, y),
+            // `$_MUX_` is `S ? B : A`; ours is `[sel, d0, d1]`.
+            CellKind::Mux2 => self.gate_pins("$_MUX_", &["A", "B", "S"], &[i[1], i[2], i[0]], y),
+        }
+    }
+
+    fn ram(&mut self, ram: &RamBlock, memid: &str) {
+        let rd = &ram.read_ports;
+        let wr = &ram.write_ports;
+        let abits = rd
+            .iter()
+            .map(|p| p.addr.len())
+            .chain(wr.iter().map(|p| p.addr.len()))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let addr = |a: &[NetId]| -> Vec<Bit> {
+            let mut bits: Vec<Bit> = a.iter().map(|&n| Bit::from_net(n)).collect();
+            bits.resize(abits, Bit::Zero);
+            bits
+        };
+        let polarity = match ram.clock_edge {
+            ClockEdge::Posedge => "1",
+            ClockEdge::Negedge => "0",
+        };
+        let clk = Bit::from_net(ram.clock);
+        let zeros = |n: usize| "0".repeat(n);
+        let xs = |n: usize| "x".repeat(n);
+
+        // Per-bit write enables; a masked write gates each bit's enable.
+        let mut wr_en = Vec::new();
+        for wp in wr {
+            match &wp.mask {
+                Some(mask) => {
+                    for &m in mask {
+                        let t = self.fresh();
+                        self.gate("$_AND_", &[wp.enable, m], t);
+                        wr_en.push(Bit::Net(t));
+                    }
+                }
+                None => wr_en.extend(std::iter::repeat_n(Bit::from_net(wp.enable), ram.width)),
+            }
+        }
+
+        let params = vec![
+            ("MEMID", Param::Str(format!("\\{memid}"))),
+            ("SIZE", Param::Int(ram.depth)),
+            ("OFFSET", Param::Int(0)),
+            ("ABITS", Param::Int(abits)),
+            ("WIDTH", Param::Int(ram.width)),
+            ("INIT", Param::Bits(xs(ram.bits()))),
+            ("RD_PORTS", Param::Int(rd.len())),
+            (
+                "RD_CLK_ENABLE",
+                Param::Bits(
+                    rd.iter()
+                        .rev()
+                        .map(|p| if p.sync { '1' } else { '0' })
+                        .collect(),
+                ),
+            ),
+            ("RD_CLK_POLARITY", Param::Bits(polarity.repeat(rd.len()))),
+            (
+                "RD_TRANSPARENCY_MASK",
+                Param::Bits(zeros(rd.len() * wr.len())),
+            ),
+            (
+                "RD_COLLISION_X_MASK",
+                Param::Bits(zeros(rd.len() * wr.len())),
+            ),
+            ("RD_WIDE_CONTINUATION", Param::Bits(zeros(rd.len()))),
+            ("RD_CE_OVER_SRST", Param::Bits(zeros(rd.len()))),
+            ("RD_ARST_VALUE", Param::Bits(xs(rd.len() * ram.width))),
+            ("RD_SRST_VALUE", Param::Bits(xs(rd.len() * ram.width))),
+            ("RD_INIT_VALUE", Param::Bits(xs(rd.len() * ram.width))),
+            ("WR_PORTS", Param::Int(wr.len())),
+            ("WR_CLK_ENABLE", Param::Bits("1".repeat(wr.len()))),
+            ("WR_CLK_POLARITY", Param::Bits(polarity.repeat(wr.len()))),
+            ("WR_PRIORITY_MASK", Param::Bits(zeros(wr.len() * wr.len()))),
+            ("WR_WIDE_CONTINUATION", Param::Bits(zeros(wr.len()))),
+        ];
+        let port = |name: &str, output, bits| CellPort {
+            name: name.into(),
+            output,
+            bits,
+        };
+        let ports = vec![
+            port(
+                "RD_CLK",
+                false,
+                rd.iter()
+                    .map(|p| if p.sync { clk } else { Bit::Zero })
+                    .collect(),
+            ),
+            port("RD_EN", false, vec![Bit::One; rd.len()]),
+            port("RD_ARST", false, vec![Bit::Zero; rd.len()]),
+            port("RD_SRST", false, vec![Bit::Zero; rd.len()]),
+            port(
+                "RD_ADDR",
+                false,
+                rd.iter().flat_map(|p| addr(&p.addr)).collect(),
+            ),
+            port(
+                "RD_DATA",
+                true,
+                rd.iter()
+                    .flat_map(|p| p.data.iter().map(|&n| Bit::from_net(n)))
+                    .collect(),
+            ),
+            port("WR_CLK", false, vec![clk; wr.len()]),
+            port("WR_EN", false, wr_en),
+            port(
+                "WR_ADDR",
+                false,
+                wr.iter().flat_map(|p| addr(&p.addr)).collect(),
+            ),
+            port(
+                "WR_DATA",
+                false,
+                wr.iter()
+                    .flat_map(|p| p.data.iter().map(|&n| Bit::from_net(n)))
+                    .collect(),
+            ),
+        ];
+        self.cells.push(YCell {
+            name: format!("\\{memid}"),
+            kind: "$mem_v2".to_string(),
+            params,
+            ports,
+        });
+    }
+}
+
+fn port_name(path: &[veryl_parser::resource_table::StrId]) -> String {
+    path.iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn module_name(module: &GateModule) -> String {
+    module
+        .name
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "top".into())
+}
+
+fn build(design: &GateDesign, module: &GateModule, top: bool) -> Netlist {
+    let name = module_name(module);
+    let mut b = Builder {
+        next_net: module.nets.len() as NetId,
+        cells: Vec::new(),
+    };
+
+    for cell in &module.cells {
+        b.cell(cell.kind, &cell.inputs, cell.output);
+    }
+
+    for ff in &module.ffs {
+        let c = match ff.clock_edge {
+            ClockEdge::Posedge => 'P',
+            ClockEdge::Negedge => 'N',
+        };
+        let v = if ff.reset_value { '1' } else { '0' };
+        let mut ports = vec![CellPort {
+            name: "C".into(),
+            output: false,
+            bits: vec![Bit::from_net(ff.clock)],
+        }];
+        let kind = match &ff.reset {
+            None => format!("$_DFF_{c}_"),
+            Some(reset) => {
+                ports.push(CellPort {
+                    name: "R".into(),
+                    output: false,
+                    bits: vec![Bit::from_net(reset.net)],
+                });
+                let r = match reset.polarity {
+                    ResetPolarity::ActiveHigh => 'P',
+                    ResetPolarity::ActiveLow => 'N',
+                };
+                let prefix = if reset.sync { "$_SDFF_" } else { "$_DFF_" };
+                format!("{prefix}{c}{r}{v}_")
+            }
+        };
+        ports.push(CellPort {
+            name: "D".into(),
+            output: false,
+            bits: vec![Bit::from_net(ff.d)],
+        });
+        ports.push(CellPort {
+            name: "Q".into(),
+            output: true,
+            bits: vec![Bit::Net(ff.q)],
+        });
+        b.push(&kind, Vec::new(), ports);
+    }
+
+    for inst in &module.instances {
+        let ports = inst
+            .pins
+            .iter()
+            .map(|pin| CellPort {
+                name: port_name(&pin.path),
+                output: pin.dir == PortDir::Output,
+                bits: module.ports[pin.port]
+                    .nets
+                    .iter()
+                    .map(|&n| Bit::from_net(n))
+                    .collect(),
+            })
+            .collect();
+        b.cells.push(YCell {
+            name: format!("\\{}", inst.name),
+            kind: format!("\\{}", module_name(&design.modules[inst.module])),
+            params: Vec::new(),
+            ports,
+        });
+    }
+
+    // RAM blocks are named after their variables; disambiguate repeats.
+    let mut memids: FxHashSet<String> = FxHashSet::default();
+    for ram in &module.ram_blocks {
+        let base = ram.name.to_string();
+        let mut memid = base.clone();
+        let mut n = 1;
+        while !memids.insert(memid.clone()) {
+            memid = format!("{base}_{n}");
+            n += 1;
+        }
+        b.ram(ram, &memid);
+    }
+
+    let mut used: FxHashSet<NetId> = FxHashSet::default();
+    for cell in &b.cells {
+        for port in &cell.ports {
+            for bit in &port.bits {
+                if let Bit::Net(n) = bit {
+                    used.insert(*n);
+                }
+            }
+        }
+    }
+
+    let mut wires = Vec::new();
+    let mut names: FxHashSet<String> = FxHashSet::default();
+    let mut named: FxHashSet<NetId> = FxHashSet::default();
+    // Instance pins stay internal wires, named by their net origins below.
+    for (i, port) in module.ports.iter().enumerate() {
+        if module.is_instance_pin(i) {
+            continue;
+        }
+        let name = port_name(&port.path);
+        names.insert(name.clone());
+        named.extend(port.nets.iter().copied());
+        wires.push(Wire {
+            name,
+            public: true,
+            port: Some(port.dir),
+            bits: port.nets.iter().map(|&n| Some(n)).collect(),
+        });
+    }
+
+    // Internal signals keep their source names; scratch nets stay private.
+    let mut by_origin: Vec<(String, Vec<Option<NetId>>)> = Vec::new();
+    let mut origin_index: FxHashMap<String, usize> = FxHashMap::default();
+    let mut scratch = Vec::new();
+    for net in RESERVED_NETS..module.nets.len() as NetId {
+        if named.contains(&net) || !used.contains(&net) {
+            continue;
+        }
+        let origin = module.nets[net as usize]
+            .origin
+            .map(|(name, bit)| (name.to_string(), bit))
+            .filter(|(name, _)| !names.contains(name));
+        let Some((name, bit)) = origin else {
+            scratch.push(net);
+            continue;
+        };
+        let idx = *origin_index.entry(name.clone()).or_insert_with(|| {
+            by_origin.push((name, Vec::new()));
+            by_origin.len() - 1
+        });
+        let bits = &mut by_origin[idx].1;
+        if bits.len() <= bit {
+            bits.resize(bit + 1, None);
+        }
+        if bits[bit].is_none() {
+            bits[bit] = Some(net);
+        } else {
+            scratch.push(net);
+        }
+    }
+    for (name, bits) in by_origin {
+        wires.push(Wire {
+            name,
+            public: true,
+            port: None,
+            bits,
+        });
+    }
+    scratch.extend(module.nets.len() as NetId..b.next_net);
+    for net in scratch {
+        wires.push(Wire {
+            name: format!("$n{net}"),
+            public: false,
+            port: None,
+            bits: vec![Some(net)],
+        });
+    }
+
+    Netlist {
+        name,
+        top,
+        wires,
+        cells: b.cells,
+    }
+}
+
+fn rtlil_id(name: &str, public: bool) -> String {
+    if public {
+        format!("\\{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+fn rtlil_string(s: &str) -> String {
+    let mut ret = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' | '\\' => {
+                ret.push('\\');
+                ret.push(c);
+            }
+            '\n' => ret.push_str("\\n"),
+            _ => ret.push(c),
+        }
+    }
+    ret.push('"');
+    ret
+}
+
+/// Renders the design as RTLIL text for `yosys read_rtlil`.
+pub fn to_rtlil(design: &GateDesign) -> String {
+    let mut ret = String::new();
+    ret.push_str("# Generated by Veryl\n");
+    let top = design.modules.len().saturating_sub(1);
+    for (i, module) in design.modules.iter().enumerate() {
+        write_rtlil_module(&mut ret, &build(design, module, i == top));
+    }
+    ret
+}
+
+fn write_rtlil_module(ret: &mut String, netlist: &Netlist) {
+    // Canonical wire bit for each net: the first wire that names it.
+    let mut canon: FxHashMap<NetId, String> = FxHashMap::default();
+    let mut connects = Vec::new();
+    for wire in &netlist.wires {
+        let id = rtlil_id(&wire.name, wire.public);
+        for (i, bit) in wire.bits.iter().enumerate() {
+            let Some(net) = *bit else {
+                continue;
+            };
+            let sig = if wire.bits.len() == 1 {
+                id.clone()
+            } else {
+                format!("{id} [{i}]")
+            };
+            match Bit::from_net(net) {
+                Bit::Net(n) => {
+                    if let Some(other) = canon.get(&n) {
+                        connects.push((sig, other.clone()));
+                    } else {
+                        canon.insert(n, sig);
+                    }
+                }
+                konst => connects.push((sig, rtlil_const_bit(konst).to_string())),
+            }
+        }
+    }
+    let sigspec = |bits: &[Bit]| -> String {
+        let parts: Vec<String> = bits
+            .iter()
+            .rev()
+            .map(|bit| match bit {
+                Bit::Net(n) => canon[n].clone(),
+                konst => rtlil_const_bit(*konst).to_string(),
+            })
+            .collect();
+        if parts.len() == 1 {
+            parts.into_iter().next().unwrap()
+        } else {
+            format!("{{ {} }}", parts.join(" "))
+        }
+    };
+
+    if netlist.top {
+        ret.push_str("attribute \\top 1\n");
+    }
+    let _ = writeln!(ret, "module {}", rtlil_id(&netlist.name, true));
+    let mut port_index = 0;
+    for wire in &netlist.wires {
+        let _ = write!(ret, "  wire");
+        if wire.bits.len() != 1 {
+            let _ = write!(ret, " width {}", wire.bits.len());
+        }
+        if let Some(dir) = wire.port {
+            port_index += 1;
+            let _ = write!(ret, " {dir} {port_index}");
+        }
+        let _ = writeln!(ret, " {}", rtlil_id(&wire.name, wire.public));
+    }
+    for cell in &netlist.cells {
+        let _ = writeln!(ret, "  cell {} {}", cell.kind, cell.name);
+        for (name, param) in &cell.params {
+            let value = match param {
+                Param::Int(x) => x.to_string(),
+                Param::Bits(x) => format!("{}'{}", x.len(), x),
+                Param::Str(x) => rtlil_string(x),
+            };
+            let _ = writeln!(ret, "    parameter \\{name} {value}");
+        }
+        for port in &cell.ports {
+            let value = if port.bits.is_empty() {
+                "{ }".to_string()
+            } else {
+                sigspec(&port.bits)
+            };
+            let _ = writeln!(ret, "    connect \\{} {value}", port.name);
+        }
+        ret.push_str("  end\n");
+    }
+    for (lhs, rhs) in connects {
+        let _ = writeln!(ret, "  connect {lhs} {rhs}");
+    }
+    ret.push_str("end\n");
+}
+
+fn rtlil_const_bit(bit: Bit) -> &'static str {
+    match bit {
+        Bit::Zero => "1'0",
+        Bit::One => "1'1",
+        _ => "1'x",
+    }
+}
+
+impl Serialize for Bit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Bit::Zero => serializer.serialize_str("0"),
+            Bit::One => serializer.serialize_str("1"),
+            Bit::X => serializer.serialize_str("x"),
+            Bit::Net(n) => serializer.serialize_u32(*n),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonDesign {
+    creator: &'static str,
+    modules: BTreeMap<String, JsonModule>,
+}
+
+#[derive(Serialize)]
+struct JsonModule {
+    attributes: BTreeMap<&'static str, String>,
+    ports: BTreeMap<String, JsonPort>,
+    cells: BTreeMap<String, JsonCell>,
+    netnames: BTreeMap<String, JsonNetname>,
+}
+
+#[derive(Serialize)]
+struct JsonPort {
+    direction: String,
+    bits: Vec<Bit>,
+}
+
+#[derive(Serialize)]
+struct JsonCell {
+    hide_name: u8,
+    #[serde(rename = "type")]
+    kind: String,
+    parameters: BTreeMap<&'static str, String>,
+    attributes: BTreeMap<&'static str, String>,
+    port_directions: BTreeMap<String, &'static str>,
+    connections: BTreeMap<String, Vec<Bit>>,
+}
+
+#[derive(Serialize)]
+struct JsonNetname {
+    hide_name: u8,
+    bits: Vec<Bit>,
+    attributes: BTreeMap<&'static str, String>,
+}
+
+/// Renders the design as a Yosys JSON netlist (the `write_json` schema), for
+/// `yosys read_json` and nextpnr.
+pub fn to_json(design: &GateDesign) -> String {
+    let top = design.modules.len().saturating_sub(1);
+    let modules = design
+        .modules
+        .iter()
+        .enumerate()
+        .map(|(i, module)| {
+            let netlist = build(design, module, i == top);
+            (netlist.name.clone(), json_module(&netlist))
+        })
+        .collect();
+    let design = JsonDesign {
+        creator: "Veryl",
+        modules,
+    };
+    let mut ret = serde_json::to_string_pretty(&design).expect("netlist serializes to JSON");
+    ret.push('\n');
+    ret
+}
+
+fn json_module(netlist: &Netlist) -> JsonModule {
+    let wire_bits = |wire: &Wire| -> Vec<Bit> {
+        wire.bits
+            .iter()
+            .map(|b| b.map(Bit::from_net).unwrap_or(Bit::X))
+            .collect()
+    };
+
+    let ports = netlist
+        .wires
+        .iter()
+        .filter_map(|wire| {
+            let dir = wire.port?;
+            let port = JsonPort {
+                direction: dir.to_string(),
+                bits: wire_bits(wire),
+            };
+            Some((wire.name.clone(), port))
+        })
+        .collect();
+
+    let cells = netlist
+        .cells
+        .iter()
+        .map(|cell| {
+            let parameters = cell
+                .params
+                .iter()
+                .map(|(name, param)| {
+                    let value = match param {
+                        Param::Int(x) => format!("{x:032b}"),
+                        Param::Bits(x) | Param::Str(x) => x.clone(),
+                    };
+                    (*name, value)
+                })
+                .collect();
+            let port_directions = cell
+                .ports
+                .iter()
+                .map(|p| {
+                    let dir = if p.output { "output" } else { "input" };
+                    (p.name.clone(), dir)
+                })
+                .collect();
+            let connections = cell
+                .ports
+                .iter()
+                .map(|p| (p.name.clone(), p.bits.clone()))
+                .collect();
+            let body = JsonCell {
+                hide_name: u8::from(cell.name.starts_with('$')),
+                kind: cell.kind.trim_start_matches('\\').to_string(),
+                parameters,
+                attributes: BTreeMap::new(),
+                port_directions,
+                connections,
+            };
+            (cell.name.trim_start_matches('\\').to_string(), body)
+        })
+        .collect();
+
+    let netnames = netlist
+        .wires
+        .iter()
+        .map(|wire| {
+            let body = JsonNetname {
+                hide_name: u8::from(!wire.public),
+                bits: wire_bits(wire),
+                attributes: BTreeMap::new(),
+            };
+            (wire.name.clone(), body)
+        })
+        .collect();
+
+    let mut attributes = BTreeMap::new();
+    if netlist.top {
+        attributes.insert("top", format!("{:032b}", 1));
+    }
+    JsonModule {
+        attributes,
+        ports,
+        cells,
+        netnames,
+    }
+}
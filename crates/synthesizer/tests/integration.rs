@@ -6,7 +6,8 @@ use veryl_parser::Parser;
 use veryl_parser::resource_table;
 use veryl_synthesizer::ir::{CellKind, NetDriver};
 use veryl_synthesizer::{
-    Library, RamConfig, build_gate_ir, build_gate_ir_with, compute_power, library_for, synthesize,
+    GateDesign, Library, RamConfig, Saif, SynthesizerError, build_gate_design, build_gate_ir,
+    build_gate_ir_with, compute_power, compute_power_annotated, library_for, saif, synthesize,
     synthesize_with,
 };
 
@@ -2596,6 +2597,85 @@ fn power_ff_dynamic_uses_full_clock() {
     );
 }
 
+/// `(INSTANCE tb (INSTANCE dut ...))` with a clock toggling for 10 cycles and
+/// every bit of `a`/`b` spending `t1` of the 20 time units at 1.
+fn activity_saif(t1: u64, tc: u64) -> String {
+    let mut nets = String::from("(clk (T0 10) (T1 10) (TX 0) (TC 20))\n");
+    for name in ["a", "b"] {
+        for i in 0..4 {
+            nets.push_str(&format!(
+                "({name}\\[{i}\\] (T0 {}) (T1 {t1}) (TX 0) (TC {tc}))\n",
+                20 - t1
+            ));
+        }
+    }
+    format!(
+        "(SAIFILE (SAIFVERSION \"2.0\") (DIRECTION \"backward\") (DESIGN \"tb\")
+         (DIVIDER / ) (TIMESCALE 1 us) (DURATION 20)
+         (INSTANCE tb (INSTANCE dut (NET {nets}))))"
+    )
+}
+
+#[test]
+fn power_from_saif_activity() {
+    let code = r#"
+        module Top (
+            clk: input  clock,
+            rst: input  reset,
+            a:   input  logic<4>,
+            b:   input  logic<4>,
+            q:   output logic<4>,
+        ) {
+            always_ff {
+                if_reset {
+                    q = 0;
+                } else {
+                    q = a ^ b;
+                }
+            }
+        }
+    "#;
+    let (ir, top) = analyze(code, "Top");
+    let gate = build_gate_ir(&ir, top).expect("synthesize");
+    let library = library_for(Library::default());
+
+    let quiet = Saif::parse(&activity_saif(0, 0)).expect("parse");
+    let busy = Saif::parse(&activity_saif(10, 10)).expect("parse");
+    assert_eq!(busy.duration, 20);
+    assert!(busy.find("dut.missing").is_none());
+    let quiet = saif::annotate(&gate.module, quiet.find("dut").unwrap()).expect("annotate");
+    let busy = saif::annotate(&gate.module, busy.find("dut").unwrap()).expect("annotate");
+    // clk plus 8 input bits.
+    assert_eq!(busy.annotated, 9);
+
+    let p_quiet = compute_power_annotated(&gate.module, library, 100.0, 0.1, Some(&quiet));
+    let p_busy = compute_power_annotated(&gate.module, library, 100.0, 0.1, Some(&busy));
+    let p_const = compute_power(&gate.module, library, 100.0, 0.1);
+    assert_eq!(p_busy.saif_nets, Some(9));
+    assert_eq!(p_const.saif_nets, None);
+    // Constant inputs keep the XORs still; inputs toggling every cycle make
+    // them switch far more than the 0.1 default assumes.
+    let comb = |p: &veryl_synthesizer::PowerReport| -> f64 {
+        p.by_kind.iter().map(|r| r.dynamic_uw).sum()
+    };
+    assert_eq!(comb(&p_quiet), 0.0);
+    assert!(comb(&p_busy) > comb(&p_const), "{p_busy}\n{p_const}");
+    // FF dynamic still follows the clock alone.
+    assert!((p_busy.ff_dynamic_uw - p_const.ff_dynamic_uw).abs() < 1e-9);
+
+    // A SAIF instance that doesn't contain the design's clock can't be
+    // normalized per cycle.
+    let wrong = Saif::parse(&activity_saif(10, 10)).expect("parse");
+    assert!(matches!(
+        saif::annotate(&gate.module, &wrong.top),
+        Err(SynthesizerError::Saif { .. })
+    ));
+    assert!(matches!(
+        Saif::parse("(SAIFILE (INSTANCE tb)"),
+        Err(SynthesizerError::Saif { .. })
+    ));
+}
+
 #[test]
 fn param_used_as_constant_bit_select() {
     // A parameter used as a bit-select index is compile-time constant but the
@@ -3446,6 +3526,7 @@ fn ram_block_area_timing_power_and_dump() {
                 mask: None,
             }],
         }],
+        instances: Vec::new(),
     };
 
     let lib = library_for(Library::default());
@@ -4420,3 +4501,246 @@ fn package_const_dynamic_select() {
         "MASK[sel] must reduce to sel[0]"
     );
 }
+
+#[test]
+fn yosys_rtlil_export_maps_ports_gates_and_ffs() {
+    let code = r#"
+        module Child (
+            clk: input  clock,
+            rst: input  reset,
+            d  : input  logic,
+            q  : output logic,
+        ) {
+            var r: logic;
+            always_ff (clk, rst) {
+                if_reset {
+                    r = 1;
+                } else {
+                    r = ~d;
+                }
+            }
+            assign q = r ^ d;
+        }
+        module Top (
+            clk: input  clock   ,
+            rst: input  reset   ,
+            a  : input  logic<2>,
+            y  : output logic   ,
+            q  : output logic   ,
+        ) {
+            assign y = a[0] & a[1];
+            inst u_c: Child (clk, rst, d: a[0], q);
+        }
+    "#;
+    let (ir, top) = analyze(code, "Top");
+    let design = build_design(&ir, top);
+    let rtlil = veryl_synthesizer::yosys::to_rtlil(&design);
+
+    // The child is its own module, emitted before its parent.
+    let child = rtlil.find("module \\Child\n").expect("child module");
+    let parent = rtlil
+        .find("attribute \\top 1\nmodule \\Top\n")
+        .expect("top module");
+    assert!(child < parent, "{rtlil}");
+    let (child, parent) = rtlil.split_at(parent);
+    assert!(!child.contains("attribute \\top"), "{rtlil}");
+
+    // Default reset is asynchronous active-low; `r` resets to 1.
+    assert!(child.contains("  cell $_DFF_PN1_ "), "{rtlil}");
+    assert!(child.contains("  wire input 3 \\d\n"), "{rtlil}");
+    assert!(child.contains("  wire output 4 \\q\n"), "{rtlil}");
+
+    // Instance pins are not ports of the parent.
+    assert!(parent.contains("  wire width 2 input 3 \\a\n"), "{rtlil}");
+    assert!(parent.contains("  wire output 4 \\y\n"), "{rtlil}");
+    assert!(parent.contains("  wire output 5 \\q\n"), "{rtlil}");
+    assert!(!parent.contains(" 6 "), "{rtlil}");
+    assert!(parent.contains("  cell $_AND_ "), "{rtlil}");
+    assert!(!parent.contains("$_DFF_"), "{rtlil}");
+    assert!(parent.contains("  cell \\Child \\u_c\n"), "{rtlil}");
+    assert!(parent.contains("    connect \\d \\a [0]\n"), "{rtlil}");
+    assert!(!rtlil.contains("hdlname"), "{rtlil}");
+    assert!(rtlil.trim_end().ends_with("end"), "{rtlil}");
+}
+
+fn build_design(ir: &air::Ir, top: resource_table::StrId) -> GateDesign {
+    build_gate_design(
+        ir,
+        top,
+        RamConfig::default(),
+        library_for(Library::default()),
+    )
+    .expect("synthesize")
+}
+
+#[test]
+fn yosys_json_export_keeps_memories_and_ports() {
+    let code = r#"
+        module Mem (
+            clk:   input  clock     ,
+            we:    input  logic     ,
+            waddr: input  logic<6>  ,
+            wdata: input  logic<32> ,
+            raddr: input  logic<6>  ,
+            rdata: output logic<32> ,
+        ) {
+            var mem: logic<32> [64];
+            always_ff (clk) {
+                if we {
+                    mem[waddr] = wdata;
+                }
+            }
+            assign rdata = mem[raddr];
+        }
+        module Top (
+            clk:   input  clock     ,
+            we:    input  logic     ,
+            waddr: input  logic<6>  ,
+            wdata: input  logic<32> ,
+            raddr: input  logic<6>  ,
+            rdata: output logic<32> ,
+        ) {
+            var r: logic<32>;
+            inst u_mem: Mem (clk, we, waddr, wdata, raddr, rdata: r);
+            always_ff (clk) {
+                rdata = r;
+            }
+        }
+    "#;
+    let (ir, top) = analyze(code, "Top");
+    let gate = build_gate_ir(&ir, top).expect("synthesize");
+    assert_eq!(gate.module.ram_blocks.len(), 1);
+
+    let json = veryl_synthesizer::yosys::to_json(&build_design(&ir, top));
+    let value: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    let modules = value["modules"].as_object().unwrap();
+    assert_eq!(modules.len(), 2, "{json}");
+    assert_eq!(
+        value["modules"]["Top"]["attributes"]["top"],
+        "00000000000000000000000000000001"
+    );
+    assert!(value["modules"]["Mem"]["attributes"]["top"].is_null());
+
+    let u_mem = &value["modules"]["Top"]["cells"]["u_mem"];
+    assert_eq!(u_mem["type"], "Mem");
+    assert_eq!(u_mem["hide_name"], 0);
+    assert_eq!(u_mem["port_directions"]["rdata"], "output");
+    assert_eq!(u_mem["port_directions"]["wdata"], "input");
+    assert_eq!(u_mem["connections"]["wdata"].as_array().unwrap().len(), 32);
+    assert!(value["modules"]["Top"]["ports"]["u_mem.rdata"].is_null());
+
+    let module = &value["modules"]["Mem"];
+    let rdata = &module["ports"]["rdata"];
+    assert_eq!(rdata["direction"], "output");
+    assert_eq!(rdata["bits"].as_array().unwrap().len(), 32);
+    assert_eq!(
+        module["ports"]["waddr"]["bits"].as_array().unwrap().len(),
+        6
+    );
+
+    let mem = &module["cells"]["mem"];
+    assert_eq!(mem["type"], "$mem_v2");
+    assert_eq!(mem["parameters"]["MEMID"], "\\mem");
+    assert_eq!(
+        mem["parameters"]["SIZE"],
+        "00000000000000000000000001000000"
+    );
+    assert_eq!(mem["connections"]["WR_EN"].as_array().unwrap().len(), 32);
+    assert_eq!(mem["connections"]["RD_DATA"].as_array().unwrap().len(), 32);
+
+    // Every connected bit is either a constant or a net declared somewhere.
+    for module in modules.values() {
+        let mut declared = std::collections::HashSet::new();
+        for net in module["netnames"].as_object().unwrap().values() {
+            for bit in net["bits"].as_array().unwrap() {
+                declared.insert(bit.clone());
+            }
+        }
+        for cell in module["cells"].as_object().unwrap().values() {
+            for bits in cell["connections"].as_object().unwrap().values() {
+                for bit in bits.as_array().unwrap() {
+                    assert!(bit.is_string() || declared.contains(bit), "{bit}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn firrtl_export_maps_clock_reset_hierarchy_and_arrays() {
+    let code = r#"
+        module Child (
+            i_clk: input  clock   ,
+            i_rst: input  reset   ,
+            i_d  : input  logic<8>,
+            o_q  : output logic<8>,
+        ) {
+            var mem: logic<8> [4];
+            var ptr: logic<2>;
+            always_ff (i_clk, i_rst) {
+                if_reset {
+                    ptr = 0;
+                } else {
+                    mem[ptr] = i_d;
+                    ptr      = ptr + 1;
+                }
+            }
+            assign o_q = mem[ptr];
+        }
+        module Top (
+            clk: input  clock        ,
+            rst: input  reset_async_low,
+            d  : input  logic<8>     ,
+            sel: input  logic        ,
+            q  : output logic<8>     ,
+        ) {
+            var r: logic<8>;
+            inst u: Child (i_clk: clk, i_rst: rst, i_d: d, o_q: r);
+            always_comb {
+                if sel {
+                    q = r;
+                } else {
+                    q = ~r;
+                }
+            }
+        }
+    "#;
+    let (ir, top) = analyze(code, "Top");
+    let text =
+        veryl_synthesizer::firrtl::to_firrtl(&ir, top, Default::default()).expect("export FIRRTL");
+
+    assert!(text.starts_with("FIRRTL version "), "{text}");
+    assert!(text.contains("circuit Top :"), "{text}");
+    assert!(text.contains("input clk : Clock"), "{text}");
+    assert!(text.contains("input rst : AsyncReset"), "{text}");
+    assert!(text.contains("inst u of Child"), "{text}");
+    assert!(text.contains("UInt<8>[4]"), "{text}");
+    assert!(text.contains("regreset ptr : UInt<2>"), "{text}");
+    // The child is emitted before its parent.
+    assert!(text.find("module Child").unwrap() < text.find("module Top").unwrap());
+
+    if let Err(e) = veryl_synthesizer::firrtl::check(&text) {
+        panic!("{e}\n{text}");
+    }
+}
+
+#[test]
+fn firrtl_check_rejects_malformed_circuits() {
+    use veryl_synthesizer::firrtl::check;
+
+    let ok = "FIRRTL version 3.3.0\ncircuit Top :\n  module Top :\n    input a : UInt<4>\n    output y : UInt<4>\n    connect y, not(a)\n";
+    check(ok).expect("valid circuit");
+
+    // Uninitialized output.
+    let text = ok.replace("    connect y, not(a)\n", "");
+    assert!(check(&text).is_err());
+    // Width grows past the sink.
+    let text = ok.replace("not(a)", "add(a, a)");
+    assert!(check(&text).is_err());
+    // Undeclared reference.
+    let text = ok.replace("not(a)", "not(b)");
+    assert!(check(&text).is_err());
+    // Out-of-range bit extraction.
+    let text = ok.replace("not(a)", "pad(bits(a, 4, 0), 4)");
+    assert!(check(&text).is_err());
+}
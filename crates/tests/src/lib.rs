@@ -122,6 +122,91 @@ mod analyzer {
     include!(concat!(env!("OUT_DIR"), "/test.rs"));
 }
 
+#[cfg(test)]
+mod firrtl {
+    use std::fs;
+    use veryl_analyzer::ir::Component;
+    use veryl_analyzer::{Analyzer, Context};
+    use veryl_metadata::Metadata;
+    use veryl_parser::Parser;
+    use veryl_synthesizer::SynthesizerError;
+    use veryl_synthesizer::firrtl::{FirrtlConfig, check, to_firrtl};
+
+    /// Testcases with at least one module outside the exportable subset. Such
+    /// modules must be reported as unsupported rather than mis-translated; any
+    /// other testcase must export every module.
+    const UNSUPPORTED: &[&str] = &[
+        "04_module",
+        "07_statement",
+        "12_always",
+        "28_msblsb",
+        "42_sv_namespace",
+        "43_sv_member",
+        "49_system_function",
+        "55_generic_module",
+        "56_generic_interface",
+        "61_unsafe_cdc",
+        "69_proto",
+        "82_bind",
+        "87_generic_const",
+        "91_mixin_interface",
+    ];
+
+    fn test(name: &str) {
+        if crate::needs_sub_project(name) {
+            return;
+        }
+
+        let metadata_path = Metadata::search_from_current().unwrap();
+        let metadata = Metadata::load(&metadata_path).unwrap();
+
+        let file = format!("../../testcases/veryl/{}.veryl", name);
+        let input = fs::read_to_string(&file).unwrap();
+        let ret = Parser::parse(&input, &file).unwrap();
+        let prj = &metadata.project.name;
+        let analyzer = Analyzer::new(&metadata);
+        let _ = analyzer.analyze_pass1(prj, &ret.veryl);
+        let _ = Analyzer::analyze_post_pass1();
+        let mut context = Context::default();
+        let mut ir = veryl_analyzer::ir::Ir::default();
+        let _ = analyzer.analyze_pass2(&ret.veryl, &mut context, Some(&mut ir));
+        let _ = Analyzer::analyze_post_pass2(&ir);
+
+        let config = FirrtlConfig::from(&metadata.build);
+        let expect_unsupported = UNSUPPORTED.contains(&name);
+        let mut unsupported = 0;
+        for component in &ir.components {
+            let Component::Module(module) = component else {
+                continue;
+            };
+            match to_firrtl(&ir, module.name, config) {
+                Ok(text) => {
+                    if let Err(err) = check(&text) {
+                        panic!("{}: {err}\n{text}", module.name);
+                    }
+                }
+                Err(
+                    err @ (SynthesizerError::Unsupported { .. }
+                    | SynthesizerError::UnknownWidth { .. }
+                    | SynthesizerError::DynamicSelect { .. }),
+                ) => {
+                    if !expect_unsupported {
+                        panic!("{}: {err}", module.name);
+                    }
+                    unsupported += 1;
+                }
+                Err(err) => panic!("{}: {err}", module.name),
+            }
+        }
+        assert!(
+            !expect_unsupported || unsupported > 0,
+            "{name} exports every module; remove it from UNSUPPORTED"
+        );
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/test.rs"));
+}
+
 #[cfg(test)]
 mod formatter {
     use std::fs;
@@ -48,17 +48,27 @@ impl CmdBuild {
             metadata.output_dir_override = Some(out_dir.canonicalize().into_diagnostic()?);
         }
 
+        let mut defines = defines.to_vec();
+        if let Some(ref profile) = self.opt.profile {
+            metadata.apply_profile(profile)?;
+            for define in &metadata.profile_defines {
+                if !defines.contains(define) {
+                    defines.push(define.clone());
+                }
+            }
+        }
+
         let paths = metadata.paths(&self.opt.files, true, true)?;
 
         let options = AnalyzeOptions {
-            defines,
+            defines: &defines,
             emit_mode: true,
             incremental: true,
             fail_fast: true,
         };
         let AnalyzeOutput {
             mut contexts,
-            incremental,
+            mut incremental,
             check_error,
             filelist_excluded,
         } = pipeline::analyze(metadata, &paths, options, ir, test_filter)?;
@@ -72,9 +82,12 @@ impl CmdBuild {
         };
 
         let mut all_pass = true;
+        let mut assertion_files = HashSet::new();
+        let mut emitted = HashSet::new();
         for context in contexts.drain(..) {
             if !context.skip && !context.path.example {
                 let path = &context.path;
+                emitted.insert(path.src.clone());
                 let (dst, map) = if let Some(ref temp_dir) = temp_dir {
                     let output_dir = metadata.output_dir();
                     let dst_temp = temp_dir
@@ -98,11 +111,23 @@ impl CmdBuild {
 
                 let exclude_check = context.path.prj == "$std";
                 let bundle = temp_dir.is_some();
+                let assertions =
+                    !emitter.assertion_str().is_empty() && !metadata.build.strip_assertions;
+                let sva = assertion_path(&dst);
+                if assertions {
+                    assertion_files.insert(path.dst.clone());
+                }
+                if let Some(inc) = incremental.as_mut() {
+                    inc.set_assertions(&path.src, assertions);
+                }
 
                 if self.opt.check && bundle {
                     // Stage in the temp dir; the bundle is compared as a whole
                     // after the loop.
                     utils::write_file_if_changed(&dst, emitter.as_str().as_bytes())?;
+                    if assertions {
+                        utils::write_file_if_changed(&sva, emitter.assertion_str().as_bytes())?;
+                    }
                 } else if self.opt.check && !exclude_check {
                     let output = fs::read_to_string(&dst).unwrap_or(String::new());
                     if output != emitter.as_str() {
@@ -111,6 +136,15 @@ impl CmdBuild {
                         }
                         all_pass = false;
                     }
+                    if assertions {
+                        let output = fs::read_to_string(&sva).unwrap_or_default();
+                        if output != emitter.assertion_str() {
+                            if !quiet {
+                                print_diff(&path.src, &output, emitter.assertion_str());
+                            }
+                            all_pass = false;
+                        }
+                    }
                 } else {
                     let written = utils::write_file_if_changed(&dst, emitter.as_str().as_bytes())?;
                     if written {
@@ -119,6 +153,16 @@ impl CmdBuild {
 
                     metadata.add_generated_file(dst);
 
+                    if assertions {
+                        let written =
+                            utils::write_file_if_changed(&sva, emitter.assertion_str().as_bytes())?;
+                        if written {
+                            debug!("Output file ({})", sva.to_string_lossy());
+                        }
+
+                        metadata.add_generated_file(sva);
+                    }
+
                     if metadata.build.sourcemap_target != SourceMapTarget::None {
                         let source_map = emitter.source_map();
                         source_map.set_source_content(&context.input);
@@ -141,25 +185,25 @@ impl CmdBuild {
             // context (including parser AST and input string) is dropped here
         }
 
+        // Restored files skip the emit loop, but their checkers from the
+        // previous build are still on disk.
+        if let Some(inc) = incremental.as_mut() {
+            for path in &paths {
+                if !emitted.contains(&path.src) && inc.had_assertions(&path.src) {
+                    assertion_files.insert(path.dst.clone());
+                    inc.set_assertions(&path.src, true);
+                }
+            }
+        }
+
         debug!("Executed emit ({} milliseconds)", stopwatch.lap());
 
+        let filelist_paths =
+            Self::filelist_paths(metadata, &paths, include_tests, &filelist_excluded);
         if !self.opt.check {
-            self.gen_filelist(
-                metadata,
-                &paths,
-                temp_dir,
-                include_tests,
-                &filelist_excluded,
-            )?;
+            self.gen_filelist(metadata, &filelist_paths, temp_dir, &assertion_files)?;
         } else if let Some(temp_dir) = &temp_dir
-            && !self.check_bundle(
-                metadata,
-                &paths,
-                temp_dir,
-                include_tests,
-                &filelist_excluded,
-                quiet,
-            )?
+            && !self.check_bundle(metadata, &filelist_paths, temp_dir, &assertion_files, quiet)?
         {
             all_pass = false;
         }
@@ -193,22 +237,46 @@ impl CmdBuild {
         })
     }
 
-    fn gen_filelist(
-        &self,
-        metadata: &mut Metadata,
+    /// Profile defines, ahead of the files in the filelist.
+    fn gen_filelist_defines(&self, metadata: &Metadata) -> String {
+        let mut text = String::new();
+        for define in &metadata.profile_defines {
+            match metadata.build.filelist_type {
+                FilelistType::Absolute | FilelistType::Relative => {
+                    text.push_str(&format!("+define+{define}\n"));
+                }
+                FilelistType::Flgen => {
+                    text.push_str(&format!("define_macro '{define}'\n"));
+                }
+            }
+        }
+        text
+    }
+
+    /// Files listed in the filelist, in dependency order.
+    fn filelist_paths(
+        metadata: &Metadata,
         paths: &[PathSet],
-        temp_dir: Option<TempDir>,
         include_tests: bool,
         excluded: &HashSet<PathBuf>,
-    ) -> Result<()> {
-        let filelist_path = metadata.filelist_path();
-        let base_path = metadata.output_dir();
-
+    ) -> Vec<PathSet> {
         let mut paths = Self::sort_filelist(metadata, paths, include_tests);
         // Drop entries that were intentionally not emitted (examples/, or
         // testbench files filtered out by `--test`); their .sv may not
         // exist on disk.
         paths.retain(|path| !path.example && !excluded.contains(&path.src));
+        paths
+    }
+
+    fn gen_filelist(
+        &self,
+        metadata: &mut Metadata,
+        paths: &[PathSet],
+        temp_dir: Option<TempDir>,
+        assertion_files: &HashSet<PathBuf>,
+    ) -> Result<()> {
+        let filelist_path = metadata.filelist_path();
+        let base_path = metadata.output_dir();
 
         let text = if let Target::Bundle { path } = &metadata.build.target {
             let temp_dir = temp_dir.unwrap();
@@ -221,6 +289,9 @@ impl CmdBuild {
                     .join(path.dst.strip_prefix(&base_path).into_diagnostic()?);
 
                 text.push_str(&fs::read_to_string(&dst).into_diagnostic()?);
+                if assertion_files.contains(&path.dst) {
+                    text.push_str(&fs::read_to_string(assertion_path(&dst)).into_diagnostic()?);
+                }
             }
 
             if let Some(parent) = target_path.parent()
@@ -235,12 +306,18 @@ impl CmdBuild {
 
             metadata.add_generated_file(target_path.clone());
 
-            self.gen_filelist_line(metadata, &target_path)?
+            let mut text = self.gen_filelist_defines(metadata);
+            text.push_str(&self.gen_filelist_line(metadata, &target_path)?);
+            text
         } else {
-            let mut text = String::new();
+            let mut text = self.gen_filelist_defines(metadata);
             for path in paths {
                 let line = self.gen_filelist_line(metadata, &path.dst)?;
                 text.push_str(&line);
+                if assertion_files.contains(&path.dst) {
+                    let line = self.gen_filelist_line(metadata, &assertion_path(&path.dst))?;
+                    text.push_str(&line);
+                }
             }
             text
         };
@@ -266,8 +343,7 @@ impl CmdBuild {
         metadata: &Metadata,
         paths: &[PathSet],
         temp_dir: &TempDir,
-        include_tests: bool,
-        excluded: &HashSet<PathBuf>,
+        assertion_files: &HashSet<PathBuf>,
         quiet: bool,
     ) -> Result<bool> {
         let Target::Bundle { path } = &metadata.build.target else {
@@ -276,15 +352,15 @@ impl CmdBuild {
         let base_path = metadata.output_dir();
         let target_path = base_path.join(path);
 
-        let mut paths = Self::sort_filelist(metadata, paths, include_tests);
-        paths.retain(|path| !path.example && !excluded.contains(&path.src));
-
         let mut text = String::new();
-        for path in &paths {
+        for path in paths {
             let dst = temp_dir
                 .path()
                 .join(path.dst.strip_prefix(&base_path).into_diagnostic()?);
             text.push_str(&fs::read_to_string(&dst).into_diagnostic()?);
+            if assertion_files.contains(&path.dst) {
+                text.push_str(&fs::read_to_string(assertion_path(&dst)).into_diagnostic()?);
+            }
         }
 
         let output = fs::read_to_string(&target_path).unwrap_or_default();
@@ -359,6 +435,13 @@ impl CmdBuild {
     }
 }
 
+/// Output path of the `#[assert]` checker modules emitted alongside `dst`.
+pub fn assertion_path(dst: &Path) -> PathBuf {
+    let stem = dst.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = dst.extension().unwrap_or_default().to_string_lossy();
+    dst.with_file_name(format!("{stem}_sva.{ext}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -423,6 +506,8 @@ exclude_std = true
             files: Vec::new(),
             check: false,
             out_dir,
+            profile: None,
+            watch: false,
         });
         build
             .exec(metadata, false, true, None, None, &[])
@@ -498,6 +583,100 @@ exclude_std = true
         );
     }
 
+    const ASSERT_FILE: &str = r#"
+    module Foo (
+        i_clk: input  clock   ,
+        i_rst: input  reset   ,
+        i_dat: input  logic<8>,
+        o_dat: output logic<8>,
+    ) {
+        assign o_dat = i_dat;
+
+        #[assert]
+        always_comb {
+            $assert(o_dat == i_dat);
+        }
+    }
+    "#;
+
+    #[test]
+    fn assertion_checker_is_listed_unless_stripped() {
+        let _lock = BUILD_TEST_LOCK.lock().unwrap();
+        let tempdir = tempfile::tempdir().unwrap();
+        let (mut metadata, project_path) =
+            create_project(tempdir.path(), "assert_prj", FilelistType::Relative);
+        fs::write(project_path.join("src/foo.veryl"), ASSERT_FILE).unwrap();
+        let _guard = set_current_dir(&project_path);
+
+        run_build(&mut metadata, None);
+
+        let checker = project_path.join("target/foo_sva.sv");
+        let filelist = fs::read_to_string(project_path.join("assert_prj.f")).unwrap();
+        assert!(checker.exists());
+        assert!(filelist.lines().any(|line| line == "target/foo_sva.sv"));
+        let checker_text = fs::read_to_string(&checker).unwrap();
+        assert!(checker_text.contains("bind assert_prj_Foo assert_prj_Foo_sva u_sva (.*);"));
+        assert!(
+            !fs::read_to_string(project_path.join("target/foo.sv"))
+                .unwrap()
+                .contains("$fatal")
+        );
+
+        fs::remove_file(&checker).unwrap();
+        metadata.build.strip_assertions = true;
+        run_build(&mut metadata, None);
+
+        let filelist = fs::read_to_string(project_path.join("assert_prj.f")).unwrap();
+        assert!(!checker.exists());
+        assert!(!filelist.contains("foo_sva.sv"));
+    }
+
+    #[test]
+    fn profile_overrides_build_and_gets_own_outputs() {
+        let _lock = BUILD_TEST_LOCK.lock().unwrap();
+        let tempdir = tempfile::tempdir().unwrap();
+        let (_, project_path) =
+            create_project(tempdir.path(), "profile_prj", FilelistType::Relative);
+        let toml_path = project_path.join("Veryl.toml");
+        let mut toml = fs::read_to_string(&toml_path).unwrap();
+        toml.push_str(
+            r#"
+[profile.fpga]
+defines = ["FPGA"]
+strip_comments = true
+"#,
+        );
+        fs::write(&toml_path, toml).unwrap();
+        fs::write(
+            project_path.join("src/foo.veryl"),
+            "// note\nmodule Foo {\n    #[ifdef(FPGA)]\n    var a: logic;\n}\n",
+        )
+        .unwrap();
+        let mut metadata = Metadata::load(&toml_path).unwrap();
+        let _guard = set_current_dir(&project_path);
+
+        Analyzer::new(&metadata).clear();
+        let build = CmdBuild::new(OptBuild {
+            files: Vec::new(),
+            check: false,
+            out_dir: None,
+            profile: Some("fpga".to_string()),
+            watch: false,
+        });
+        build
+            .exec(&mut metadata, false, true, None, None, &[])
+            .expect("build should succeed");
+        Analyzer::new(&metadata).clear();
+
+        let out_dir = project_path.join("profile/fpga");
+        let output = fs::read_to_string(out_dir.join("target/foo.sv")).unwrap();
+        assert!(!output.contains("// note"), "{output}");
+        let filelist = fs::read_to_string(out_dir.join("profile_prj.f")).unwrap();
+        assert_eq!(filelist, "+define+FPGA\ntarget/foo.sv\n");
+        assert!(!project_path.join("target/foo.sv").exists());
+        assert!(!project_path.join("profile_prj.f").exists());
+    }
+
     const INC_FILE_A: &str = r#"
     package PackageA {
         const WIDTH: u32 = 8;
@@ -605,7 +784,10 @@ incremental = true
     fn run_check(metadata: &mut Metadata) -> Result<bool> {
         Analyzer::new(metadata).clear();
 
-        let check = crate::cmd_check::CmdCheck::new(crate::OptCheck { files: Vec::new() });
+        let check = crate::cmd_check::CmdCheck::new(crate::OptCheck {
+            files: Vec::new(),
+            watch: false,
+        });
         let ret = check.exec(metadata);
 
         Analyzer::new(metadata).clear();
@@ -663,6 +845,8 @@ exclude_std = true
             files: Vec::new(),
             check: true,
             out_dir: None,
+            profile: None,
+            watch: false,
         });
         let pass = build
             .exec(metadata, false, true, None, None, &[])
@@ -769,6 +953,42 @@ exclude_std = true
         );
     }
 
+    #[test]
+    fn incremental_build_keeps_assertion_checker_of_restored_file() {
+        let _lock = BUILD_TEST_LOCK.lock().unwrap();
+        let tempdir = tempfile::tempdir().unwrap();
+        let (mut metadata, project_path) = write_incremental_project(
+            tempdir.path(),
+            "incremental_sva",
+            &[("foo.veryl", ASSERT_FILE), ("bar.veryl", CLEAN_MODULE)],
+        );
+        let filelist = project_path.join("incremental_sva.f");
+        let lists_checker = || {
+            fs::read_to_string(&filelist)
+                .unwrap()
+                .lines()
+                .any(|line| line.ends_with("foo_sva.sv"))
+        };
+
+        run_build(&mut metadata, None);
+        assert!(lists_checker());
+
+        // Only bar.veryl is emitted again; foo.veryl is restored.
+        fs::write(
+            project_path.join("src/bar.veryl"),
+            CLEAN_MODULE.replace("= 0", "= 1"),
+        )
+        .unwrap();
+        run_build(&mut metadata, None);
+        assert_eq!(crate::incremental::last_restored_count(), 1);
+        assert!(lists_checker());
+
+        // And the flag survives a build that restores everything.
+        run_build(&mut metadata, None);
+        assert_eq!(crate::incremental::last_restored_count(), 2);
+        assert!(lists_checker());
+    }
+
     #[test]
     fn incremental_build_change_in_dependency_rebuilds_dependents() {
         let _lock = BUILD_TEST_LOCK.lock().unwrap();
@@ -808,6 +1028,8 @@ exclude_std = true
             files: Vec::new(),
             check: false,
             out_dir: None,
+            profile: None,
+            watch: false,
         });
         let mut ir = veryl_analyzer::ir::Ir::default();
         build
@@ -1067,6 +1289,8 @@ exclude_std = true
             files: Vec::new(),
             check: false,
             out_dir: Some(out_dir.clone()),
+            profile: None,
+            watch: false,
         });
         let mut ir = veryl_analyzer::ir::Ir::default();
         build
@@ -1225,8 +1449,10 @@ exclude_std = true
             test: None,
             sim: None,
             wave: false,
+            saif: false,
             backend: crate::Backend::Interpret,
             backend_validate: None,
+            sim_threads: None,
             disable_ff_opt: false,
             ignored: false,
             include_ignored: false,
@@ -1234,8 +1460,23 @@ exclude_std = true
             no_capture: false,
             seed: None,
             four_state: false,
-            format: crate::Format::Pretty,
+            xprop: crate::XProp::Standard,
+            xprop_report: false,
+            format: crate::TestFormat::Pretty,
             format_version: None,
+            watch: false,
+            coverage: false,
+            coverage_format: crate::CoverageFormat::Lcov,
+            coverage_output: None,
+            coverage_db: None,
+            replay: None,
+            replay_top: None,
+            replay_scope: None,
+            replay_compare: false,
+            profile: false,
+            profile_output: None,
+            bless: false,
+            mutate: false,
         });
         let all_pass = test.exec(&mut metadata).expect("test run should succeed");
         Analyzer::new(&metadata).clear();
@@ -0,0 +1,108 @@
+use crate::pipeline::{self, AnalyzeOptions};
+use crate::{Backend, OptSim};
+use log::error;
+use miette::{IntoDiagnostic, Result};
+use std::io::{BufRead, IsTerminal, Write};
+use veryl_analyzer::ir::Ir;
+use veryl_metadata::Metadata;
+use veryl_parser::resource_table;
+use veryl_simulator::ir::{Config, build_ir};
+use veryl_simulator::shell::{Flow, Shell};
+use veryl_simulator::simulator::Simulator;
+use veryl_simulator::simulator_error::SimulatorError;
+
+pub struct CmdSim {
+    opt: OptSim,
+}
+
+impl CmdSim {
+    pub fn new(opt: OptSim) -> Self {
+        Self { opt }
+    }
+
+    pub fn exec(&self, metadata: &mut Metadata) -> Result<bool> {
+        let paths = metadata.paths(&self.opt.files, true, true)?;
+
+        // Like `veryl test`, CLI defines extend `[test].defines`.
+        let mut defines = metadata.test.defines.clone();
+        for name in &self.opt.define {
+            if !defines.contains(name) {
+                defines.push(name.clone());
+            }
+        }
+        let options = AnalyzeOptions {
+            defines: &defines,
+            emit_mode: false,
+            incremental: false,
+            fail_fast: true,
+        };
+        let mut ir = Ir::default();
+        let _ = pipeline::analyze(metadata, &paths, options, Some(&mut ir), None)?;
+
+        let (use_jit, aot_c) = match self.opt.backend {
+            Backend::Interpret => (false, false),
+            Backend::Cranelift => (true, false),
+            Backend::Cc => (true, true),
+        };
+        let mut config = Config {
+            use_jit,
+            aot_c,
+            aot_c_event: aot_c,
+            use_4state: self.opt.four_state || metadata.test.four_state,
+            seed: self.opt.seed.or(metadata.test.seed).unwrap_or_default(),
+            force_targets: self.opt.forceable.clone(),
+            ..Config::default()
+        };
+        config.apply_env();
+
+        let top = resource_table::get_str_id(self.opt.top.clone()).ok_or_else(|| {
+            SimulatorError::TopModuleNotFound {
+                module_name: self.opt.top.clone(),
+            }
+        })?;
+        let sim_ir = build_ir(&ir, top, &config)?;
+        let mut sim = Simulator::new(sim_ir, None);
+        sim.init_components(config.seed, &self.opt.top)
+            .map_err(|e| miette::miette!("{e}"))?;
+
+        let mut shell = Shell::new(sim);
+        let mut stdout = std::io::stdout();
+        for path in &self.opt.script {
+            match shell.source(path, &mut stdout) {
+                Ok(Flow::Continue) => {}
+                Ok(Flow::Quit) => return Ok(true),
+                Err(e) => {
+                    error!("{e}");
+                    return Ok(false);
+                }
+            }
+        }
+        if self.opt.batch {
+            return Ok(true);
+        }
+
+        let stdin = std::io::stdin();
+        let interactive = stdin.is_terminal();
+        let mut lines = stdin.lock().lines();
+        loop {
+            if interactive {
+                print!("sim> ");
+                stdout.flush().into_diagnostic()?;
+            }
+            let Some(line) = lines.next() else {
+                break;
+            };
+            match shell.exec(&line.into_diagnostic()?, &mut stdout) {
+                Ok(Flow::Continue) => {}
+                Ok(Flow::Quit) => break,
+                // Piped input stops at the first error, like a script.
+                Err(e) if !interactive => {
+                    error!("{e}");
+                    return Ok(false);
+                }
+                Err(e) => error!("{e}"),
+            }
+        }
+        Ok(true)
+    }
+}
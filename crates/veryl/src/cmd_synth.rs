@@ -1,18 +1,20 @@
 use crate::pipeline::{self, AnalyzeOptions};
 use crate::{Format, OptSynth, check_format_version};
 use log::warn;
-use miette::Result;
+use miette::{IntoDiagnostic, Result};
 use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 use std::env;
+use std::fs;
 use std::time::Instant;
 use veryl_analyzer::ir::{Component, Ir, Module};
 use veryl_metadata::Metadata;
 use veryl_parser::resource_table::{self, PathId};
 use veryl_parser::veryl_token::TokenSource;
 use veryl_synthesizer::{
-    RamConfig, SynthesizerError, compute_power, compute_timing_top_n, library_for, port_label,
-    synthesize_with,
+    GateModule, NetActivity, RamConfig, Saif, SynthesizerError, build_gate_design,
+    compute_power_annotated, compute_timing_top_n, firrtl, library_for, port_label, saif,
+    synthesize_with, yosys,
 };
 
 /// Emitted by `veryl synth --format json`.
@@ -59,6 +61,8 @@ struct PowerJson {
     dynamic_mw: f64,
     clock_freq_mhz: f64,
     activity: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    saif_nets: Option<usize>,
 }
 
 fn print_synth_report_json(report: &SynthReport) {
@@ -77,10 +81,30 @@ impl CmdSynth {
         Self { opt }
     }
 
+    /// Per-net activity from `--saif`, mapped onto the synthesized top.
+    fn load_saif(&self, module: &GateModule) -> Result<Option<NetActivity>> {
+        let Some(path) = &self.opt.saif else {
+            return Ok(None);
+        };
+        let text = fs::read_to_string(path).into_diagnostic()?;
+        let parsed = Saif::parse(&text)?;
+        let instance_path = self.opt.saif_instance.as_deref().unwrap_or("");
+        let Some(instance) = parsed.find(instance_path) else {
+            return Err(SynthesizerError::Saif {
+                message: format!("instance '{instance_path}' not found"),
+            }
+            .into());
+        };
+        Ok(Some(saif::annotate(module, instance)?))
+    }
+
     pub fn exec(&self, metadata: &mut Metadata) -> Result<bool> {
         let paths = metadata.paths(&self.opt.files, true, true)?;
 
-        check_format_version(self.opt.format, self.opt.format_version)?;
+        check_format_version(
+            matches!(self.opt.format, Format::Json),
+            self.opt.format_version,
+        )?;
         let json = matches!(self.opt.format, Format::Json);
         // Short PDK id ("sky130" / "asap7" / ...) via the enum's serde rename.
         let library_name = serde_json::to_value(metadata.synth.library)
@@ -152,6 +176,11 @@ impl CmdSynth {
             }
         };
 
+        if let Some(path) = &self.opt.firrtl {
+            let config = firrtl::FirrtlConfig::from(&metadata.build);
+            fs::write(path, firrtl::to_firrtl(&ir, top_id, config)?).into_diagnostic()?;
+        }
+
         let library = library_for(metadata.synth.library);
 
         let ram_config = RamConfig::from(&metadata.synth);
@@ -185,12 +214,23 @@ impl CmdSynth {
                 return Ok(false);
             }
         };
+        if self.opt.rtlil.is_some() || self.opt.yosys_json.is_some() {
+            let design = build_gate_design(&ir, top_id, ram_config, library)?;
+            if let Some(path) = &self.opt.rtlil {
+                fs::write(path, yosys::to_rtlil(&design)).into_diagnostic()?;
+            }
+            if let Some(path) = &self.opt.yosys_json {
+                fs::write(path, yosys::to_json(&design)).into_diagnostic()?;
+            }
+        }
+        let annotation = self.load_saif(&result.gate_ir.module)?;
         if json {
-            let power = compute_power(
+            let power = compute_power_annotated(
                 &result.gate_ir.module,
                 library,
                 metadata.synth.clock_freq,
                 metadata.synth.activity,
+                annotation.as_ref(),
             );
             print_synth_report_json(&SynthReport {
                 format_version: 1,
@@ -218,6 +258,7 @@ impl CmdSynth {
                     dynamic_mw: power.dynamic_mw,
                     clock_freq_mhz: power.clock_freq_mhz,
                     activity: power.activity,
+                    saif_nets: power.saif_nets,
                 }),
             });
             return Ok(true);
@@ -241,11 +282,12 @@ impl CmdSynth {
         let show_any_report = show_area || show_timing || show_power;
 
         // Computed once; the summary line and the detail block both need it.
-        let power = compute_power(
+        let power = compute_power_annotated(
             &result.gate_ir.module,
             library,
             metadata.synth.clock_freq,
             metadata.synth.activity,
+            annotation.as_ref(),
         );
 
         if self.opt.dump_ir {
@@ -281,10 +323,16 @@ impl CmdSynth {
                 "power:", power.total_mw, power.leakage_mw, power.dynamic_mw,
             );
             // Pad the continuation to the same column as values above.
-            println!(
-                "  {:<8}{:>11} @ f_clk = {} MHz, activity = {:.2}",
-                "", "", power.clock_freq_mhz, power.activity,
-            );
+            match power.saif_nets {
+                Some(nets) => println!(
+                    "  {:<8}{:>11} @ f_clk = {} MHz, activity = SAIF ({} nets annotated)",
+                    "", "", power.clock_freq_mhz, nets,
+                ),
+                None => println!(
+                    "  {:<8}{:>11} @ f_clk = {} MHz, activity = {:.2}",
+                    "", "", power.clock_freq_mhz, power.activity,
+                ),
+            }
         }
 
         if show_area {
@@ -1,22 +1,34 @@
 use crate::cmd_build::CmdBuild;
-use crate::runner::{Cocotb, CocotbSource, Dsim, Vcs, Verilator, Vivado};
-use crate::{Format, OptBuild, OptTest, check_format_version};
+use crate::diff::print_diff;
+use crate::mutation::{self, MutationTest};
+use crate::runner::{self, Cocotb, CocotbSource, Dsim, Vcs, Verilator, Vivado};
+use crate::test_report::{TestReport, TestSuiteReport};
+use crate::{CoverageFormat, OptBuild, OptTest, TestFormat, check_format_version};
 use log::{error, info, warn};
-use miette::Result;
-use std::path::PathBuf;
+use miette::{IntoDiagnostic, Result};
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use veryl_analyzer::attribute::{GoldenItem, TimeoutItem};
 use veryl_analyzer::symbol::TestType;
 use veryl_analyzer::symbol_table;
 use veryl_metadata::WaveFormFormat;
 use veryl_metadata::{ComponentBackendKind, FilelistType, Metadata, SimType, WaveFormTarget};
 use veryl_parser::resource_table::{self, PathId};
 use veryl_parser::text_table;
+use veryl_simulator::assert_buffer;
+use veryl_simulator::coverage;
+use veryl_simulator::covergroup;
 use veryl_simulator::ir::{ComponentLibrary, Config, Ir, ProtoModuleCache, build_ir_cached};
 use veryl_simulator::output_buffer;
+use veryl_simulator::profiler;
+use veryl_simulator::replay::{self, ReplayOptions, Trace};
 use veryl_simulator::simulator::Simulator;
 use veryl_simulator::simulator_error::SimulatorError;
-use veryl_simulator::testbench::{TestResult, run_native_testbench};
-use veryl_simulator::wave_dumper::WaveDumper;
+use veryl_simulator::testbench::{TestResult, run_native_testbench_with};
+use veryl_simulator::wave_dumper::{WaveDumper, WaveFilter};
 use veryl_simulator::wavedrom::{self, SignalKind, classify_signals, parse_wavedrom};
+use veryl_simulator::xprop;
 
 /// A fresh random base seed for a test run, used when neither `--seed` nor
 /// `[test].seed` pins one. A new `RandomState` draws OS entropy at process
@@ -27,35 +39,10 @@ fn random_seed() -> u64 {
     RandomState::new().hash_one(std::process::id())
 }
 
-/// Emitted by `veryl test --format json`.
-#[derive(serde::Serialize)]
-struct TestSuiteReport {
-    /// Bump on any breaking change to the report shape.
-    format_version: u32,
-    backend: String,
-    passed: i32,
-    failed: i32,
-    ignored: usize,
-    tests: Vec<TestReport>,
-}
-
-#[derive(serde::Serialize)]
-struct TestReport {
-    name: String,
-    /// "pass" | "fail" | "error"
-    status: &'static str,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    message: Option<String>,
-    runtime_s: f64,
-    /// Simulation-only wall time: `Simulator::new` + memory preload + the
-    /// cycle loop, excluding the per-test IR build / AOT compile / dlopen that
-    /// `runtime_s` also covers (the boundary a warm Verilator binary re-runs).
-    /// `None` for non-native tests and native tests that never reached execution.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    sim_s: Option<f64>,
-    /// Captured `$display`/`$write` output.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    output: Option<String>,
+/// `file:line`, relative to `base` when inside it.
+pub(crate) fn display_location((path, line): &(PathBuf, u32), base: &Path) -> String {
+    let path = path.strip_prefix(base).unwrap_or(path);
+    format!("{}:{line}", path.to_string_lossy())
 }
 
 /// Load per-test run times recorded by a prior `veryl test`, used to dispatch
@@ -107,6 +94,7 @@ struct NativeTestJob {
     module_name: String,
     sim_ir: Ir,
     dump: Option<WaveDumper>,
+    saif: Option<PathBuf>,
 }
 
 enum NativeOutcome {
@@ -114,17 +102,43 @@ enum NativeOutcome {
     Ran {
         result: std::result::Result<TestResult, SimulatorError>,
         wave_path: Option<PathBuf>,
+        saif_path: Option<PathBuf>,
     },
 }
 
+#[derive(Clone)]
 struct PendingNativeTest {
     test_name: String,
     top: Option<resource_table::StrId>,
     test_path: PathId,
+    timeout: TimeoutItem,
+    golden: Vec<GoldenItem>,
+}
+
+/// The `[test]` timeout limits, which `#[timeout]` overrides per test.
+fn default_timeout(metadata: &Metadata) -> TimeoutItem {
+    let time = metadata.test.timeout_secs.and_then(|x| {
+        let ret = Duration::try_from_secs_f64(x).ok();
+        if ret.is_none() {
+            warn!("Ignoring invalid [test] timeout_secs: {x}");
+        }
+        ret
+    });
+    TimeoutItem {
+        cycles: metadata.test.timeout_cycles,
+        time,
+    }
 }
 
-fn wave_output_path(name: &str, test_path: PathId, metadata: &Metadata) -> PathBuf {
-    let target_name = format!("{}.{}", name, metadata.test.waveform_format.extension());
+/// Where a per-test output (waveform, SAIF) named `name.extension` goes:
+/// next to the test source, or into `[test].waveform_target`'s directory.
+fn test_output_path(
+    name: &str,
+    extension: &str,
+    test_path: PathId,
+    metadata: &Metadata,
+) -> PathBuf {
+    let target_name = format!("{name}.{extension}");
     match &metadata.test.waveform_target {
         WaveFormTarget::Target => PathBuf::from(test_path.to_string())
             .parent()
@@ -134,12 +148,65 @@ fn wave_output_path(name: &str, test_path: PathId, metadata: &Metadata) -> PathB
     }
 }
 
-fn create_wave_dumper(
-    name: &str,
+/// A `#[golden]` file whose content differs from the test's output.
+pub(crate) struct GoldenMismatch {
+    path: PathBuf,
+    expected: String,
+    actual: String,
+}
+
+/// Checks the captured `output` of a passing test, and the files it wrote,
+/// against its golden files; `bless` overwrites them instead.
+pub(crate) fn check_golden(
+    golden: &[GoldenItem],
+    output: &str,
     test_path: PathId,
-    metadata: &Metadata,
-) -> std::result::Result<WaveDumper, SimulatorError> {
-    let path = wave_output_path(name, test_path, metadata);
+    bless: bool,
+) -> std::result::Result<Vec<GoldenMismatch>, String> {
+    let base = PathBuf::from(test_path.to_string());
+    let base = base.parent().unwrap();
+    let mut ret = Vec::new();
+    for item in golden {
+        let path = base.join(item.golden.to_string());
+        let actual = if let Some(output) = item.output {
+            std::fs::read_to_string(output.to_string())
+                .map_err(|e| format!("failed to read output file {output}: {e}"))?
+        } else {
+            output.to_string()
+        };
+
+        if bless {
+            create_parent_dir(&path).map_err(|e| e.to_string())?;
+            std::fs::write(&path, &actual)
+                .map_err(|e| format!("failed to write golden file {}: {e}", path.display()))?;
+            info!("Blessed golden file ({})", path.display());
+            continue;
+        }
+
+        let expected = std::fs::read_to_string(&path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                format!(
+                    "golden file {} does not exist (run with --bless to create it)",
+                    path.display()
+                )
+            } else {
+                format!("failed to read golden file {}: {e}", path.display())
+            }
+        })?;
+        // A checkout with CRLF conversion must still match.
+        let expected = expected.replace("\r\n", "\n");
+        if expected != actual {
+            ret.push(GoldenMismatch {
+                path,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(ret)
+}
+
+fn create_parent_dir(path: &Path) -> std::result::Result<(), SimulatorError> {
     if let Some(parent) = path.parent()
         && !parent.exists()
     {
@@ -147,6 +214,17 @@ fn create_wave_dumper(
             message: format!("failed to create directory {}: {e}", parent.display()),
         })?;
     }
+    Ok(())
+}
+
+fn create_wave_dumper(
+    name: &str,
+    test_path: PathId,
+    metadata: &Metadata,
+) -> std::result::Result<WaveDumper, SimulatorError> {
+    let extension = metadata.test.waveform_format.extension();
+    let path = test_output_path(name, extension, test_path, metadata);
+    create_parent_dir(&path)?;
     let path_str = path.to_string_lossy().to_string();
     info!("  Dumping waveform to {}", path_str);
     let dumper = match metadata.test.waveform_format {
@@ -158,7 +236,15 @@ fn create_wave_dumper(
         }
         WaveFormFormat::Fst => WaveDumper::new_fst(&path_str),
     };
-    Ok(dumper.with_path(path))
+    let test = &metadata.test;
+    let filter = WaveFilter {
+        scopes: test.waveform_scopes.clone(),
+        signals: test.waveform_signals.clone(),
+        depth: test.waveform_depth,
+        start: test.waveform_start,
+        end: test.waveform_end,
+    };
+    Ok(dumper.with_path(path).with_filter(filter))
 }
 
 impl CmdTest {
@@ -174,6 +260,8 @@ impl CmdTest {
             files: self.opt.files.clone(),
             check: false,
             out_dir: None,
+            profile: None,
+            watch: false,
         });
 
         // Mutate metadata so external simulator runners (which read
@@ -302,9 +390,21 @@ impl CmdTest {
                 .or(metadata.test.seed)
                 .unwrap_or_else(random_seed),
             use_4state: self.opt.four_state || metadata.test.four_state,
+            coverage: self.opt.coverage,
+            xprop: self.opt.xprop.into(),
+            xprop_report: self.opt.xprop_report,
             ..Config::default()
         };
         config.apply_env();
+        if let Some(n) = self.opt.sim_threads {
+            config.sim_threads = n;
+        }
+        config.partition_validate = config.sim_threads > 1 && validate;
+        if self.opt.profile {
+            config.profile = true;
+            // Artifacts shared across tests carry no statement origins.
+            config.dut_reuse = false;
+        }
         // Warn once if cc is requested but absent; the fallback is otherwise silent.
         #[cfg(not(target_family = "wasm"))]
         if config.aot_c && !veryl_simulator::backend::aot_c::cc_available() {
@@ -315,19 +415,36 @@ impl CmdTest {
         }
         let mut proto_cache = ProtoModuleCache::default();
 
-        check_format_version(self.opt.format, self.opt.format_version)?;
-        let json = matches!(self.opt.format, Format::Json);
+        check_format_version(self.opt.format == TestFormat::Json, self.opt.format_version)?;
+        // Structured formats keep stdout for the report.
+        let structured = self.opt.format != TestFormat::Pretty;
         let backend_name = match self.opt.backend {
             Backend::Interpret => "interpret",
             Backend::Cranelift => "cranelift",
             Backend::Cc => "cc",
         };
+        let native_simulator = format!("native ({backend_name})");
+        let project_path = metadata.project_path();
         // Native workers push concurrently, so guard the per-test results.
         let reports = std::sync::Mutex::new(Vec::<TestReport>::new());
+        // Coverage hits merged across workers, indexed by point id.
+        let coverage_counts = std::sync::Mutex::new(Vec::<u64>::new());
+        // `$tb::coverage` databases merged across workers.
+        let functional = std::sync::Mutex::new(covergroup::Database::default());
+        covergroup::take();
+        // X report entries, tagged with their test.
+        let xprop_events = std::sync::Mutex::new(Vec::<(String, xprop::XEvent)>::new());
+        // `--profile` timings merged across workers.
+        let profile = std::sync::Mutex::new(profiler::Profile::default());
+        if self.opt.coverage {
+            coverage::clear();
+            coverage::take();
+        }
 
         let mut success = 0;
         let mut failure = 0;
 
+        let default_timeout = default_timeout(metadata);
         let mut pending_native: Vec<PendingNativeTest> = Vec::new();
         let mut non_native_tests = Vec::new();
 
@@ -338,6 +455,8 @@ impl CmdTest {
                         test_name: test.to_string(),
                         top: property.top,
                         test_path: property.path,
+                        timeout: property.timeout.or(default_timeout),
+                        golden: property.golden.clone(),
                     });
                 }
                 _ => {
@@ -346,6 +465,41 @@ impl CmdTest {
             }
         }
 
+        let untimed = non_native_tests
+            .iter()
+            .filter(|(_, property)| property.timeout.or(default_timeout) != TimeoutItem::default())
+            .count();
+        if untimed > 0 {
+            warn!(
+                "Timeouts only apply to native-simulator tests; {untimed} test(s) run without one"
+            );
+        }
+
+        let unchecked = non_native_tests
+            .iter()
+            .filter(|(_, property)| !property.golden.is_empty())
+            .count();
+        if unchecked > 0 {
+            warn!(
+                "Golden files are only checked for native-simulator tests; {unchecked} test(s) run without them"
+            );
+        }
+
+        if self.opt.coverage && !non_native_tests.is_empty() {
+            warn!(
+                "Coverage is only collected from native-simulator tests; {} test(s) are not included",
+                non_native_tests.len()
+            );
+        }
+
+        // `--mutate` re-runs the native tests once the baseline has passed.
+        let mutation_tests = if self.opt.mutate {
+            pending_native.clone()
+        } else {
+            Vec::new()
+        };
+        let mut fresh_timings: Vec<(String, f64)> = Vec::new();
+
         if !pending_native.is_empty() {
             info!("Test seed: {} (reproduce with --seed)", config.seed);
             if let Some(libraries) = component_libraries {
@@ -360,8 +514,9 @@ impl CmdTest {
             // Buffer `$display` output to keep concurrent tests from interleaving.
             // A single worker can't interleave, so stream live; `--no-capture`
             // forces streaming even in parallel.
-            // JSON mode also buffers: capture output into the report, keep stdout clean.
-            let buffered = (num_threads > 1 && !self.opt.no_capture) || json;
+            // Structured formats also buffer: capture output into the report,
+            // keep stdout clean.
+            let buffered = (num_threads > 1 && !self.opt.no_capture) || structured;
             if buffered {
                 info!("Building simulation model");
             }
@@ -406,6 +561,12 @@ impl CmdTest {
                 let text_snap = &text_snapshot;
                 let print_lock = &print_lock;
                 let reports = &reports;
+                let coverage_counts = &coverage_counts;
+                let xprop_events = &xprop_events;
+                let functional = &functional;
+                let profile = &profile;
+                let native_simulator = &native_simulator;
+                let project_path = &project_path;
                 let handles: Vec<_> = (0..num_threads)
                     .map(|_| {
                         s.spawn(move || {
@@ -415,12 +576,13 @@ impl CmdTest {
                             // reuse is rare since each top name is unique.
                             let mut thread_cache = ProtoModuleCache::default();
                             let (mut tally_pass, mut tally_fail) = (0, 0);
-                            let mut tally_waves: Vec<PathBuf> = Vec::new();
+                            let mut tally_outputs: Vec<PathBuf> = Vec::new();
                             let mut tally_timings: Vec<(String, f64)> = Vec::new();
                             loop {
                                 let pending = queue.lock().unwrap().next();
                                 let Some(pending) = pending else { break };
-                                if buffered {
+                                // Golden files compare the output, so capture it.
+                                if buffered || !pending.golden.is_empty() {
                                     output_buffer::enable();
                                 }
                                 // With the `profile` feature, report the build
@@ -447,10 +609,12 @@ impl CmdTest {
                                 #[cfg(feature = "profile")]
                                 let t_run = std::time::Instant::now();
                                 let mut run_secs: Option<f64> = None;
+                                let mut rep_locations: Vec<String> = Vec::new();
                                 let outcome = match build_result {
                                     Ok(job) => {
                                         let wave_path =
                                             job.dump.as_ref().and_then(|d| d.path().cloned());
+                                        let saif_path = job.saif.clone();
                                         if !buffered {
                                             info!("Executing test ({})", pending.test_name);
                                         }
@@ -459,13 +623,39 @@ impl CmdTest {
                                         // run time is the stable per-test cost that
                                         // longest-first scheduling sorts on.
                                         let t_run_sched = std::time::Instant::now();
-                                        let result = run_native_testbench(
+                                        let result = run_native_testbench_with(
                                             job.sim_ir,
                                             job.dump,
+                                            job.saif.as_deref(),
                                             job.module_name,
+                                            None,
+                                            pending.timeout,
                                         );
                                         run_secs = Some(t_run_sched.elapsed().as_secs_f64());
-                                        NativeOutcome::Ran { result, wave_path }
+                                        rep_locations = assert_buffer::take_locations()
+                                            .iter()
+                                            .map(|x| display_location(x, project_path))
+                                            .collect();
+                                        if opt_ref.coverage {
+                                            coverage::merge(
+                                                &mut coverage_counts.lock().unwrap(),
+                                                &coverage::take(),
+                                            );
+                                        }
+                                        functional.lock().unwrap().merge(covergroup::take());
+                                        xprop_events.lock().unwrap().extend(
+                                            xprop::take()
+                                                .into_iter()
+                                                .map(|x| (pending.test_name.clone(), x)),
+                                        );
+                                        if opt_ref.profile {
+                                            profile.lock().unwrap().merge(profiler::take());
+                                        }
+                                        NativeOutcome::Ran {
+                                            result,
+                                            wave_path,
+                                            saif_path,
+                                        }
                                     }
                                     Err(e) => NativeOutcome::ElaborateFailed(e),
                                 };
@@ -495,7 +685,7 @@ impl CmdTest {
                                 match outcome {
                                     NativeOutcome::ElaborateFailed(e) => {
                                         // Buffered output is from IR build; emit before the diag.
-                                        if !output.is_empty() && !json {
+                                        if !output.is_empty() && !structured {
                                             print!("{output}");
                                         }
                                         error!("Failed to elaborate test ({test_name})");
@@ -505,21 +695,73 @@ impl CmdTest {
                                         rep_message = Some(rendered);
                                         tally_fail += 1;
                                     }
-                                    NativeOutcome::Ran { result, wave_path } => {
+                                    NativeOutcome::Ran {
+                                        result,
+                                        wave_path,
+                                        saif_path,
+                                    } => {
                                         if buffered {
                                             info!("Executing test ({test_name})");
                                         }
-                                        if !output.is_empty() && !json {
+                                        if !output.is_empty() && !structured {
                                             print!("{output}");
                                         }
+                                        let result = match result {
+                                            Ok(TestResult::Pass) => match check_golden(
+                                                &pending.golden,
+                                                &output,
+                                                pending.test_path,
+                                                opt_ref.bless,
+                                            ) {
+                                                Ok(x) if x.is_empty() => Ok(TestResult::Pass),
+                                                Ok(x) => {
+                                                    let mut msg = Vec::new();
+                                                    for x in &x {
+                                                        if structured {
+                                                            msg.push(
+                                                                TextDiff::from_lines(
+                                                                    &x.expected,
+                                                                    &x.actual,
+                                                                )
+                                                                .unified_diff()
+                                                                .header(
+                                                                    &x.path.to_string_lossy(),
+                                                                    "output",
+                                                                )
+                                                                .to_string(),
+                                                            );
+                                                        } else {
+                                                            print_diff(
+                                                                &x.path,
+                                                                &x.expected,
+                                                                &x.actual,
+                                                            );
+                                                        }
+                                                    }
+                                                    let paths: Vec<_> = x
+                                                        .iter()
+                                                        .map(|x| x.path.display().to_string())
+                                                        .collect();
+                                                    msg.insert(
+                                                        0,
+                                                        format!(
+                                                            "output differs from golden file {} (run with --bless to update)",
+                                                            paths.join(", ")
+                                                        ),
+                                                    );
+                                                    Ok(TestResult::Fail(msg.join("\n")))
+                                                }
+                                                Err(x) => Ok(TestResult::Fail(x)),
+                                            },
+                                            x => x,
+                                        };
                                         match result {
                                             Ok(TestResult::Pass) => {
                                                 info!("Succeeded test ({test_name})");
                                                 rep_status = "pass";
                                                 tally_pass += 1;
-                                                if let Some(path) = wave_path {
-                                                    tally_waves.push(path);
-                                                }
+                                                tally_outputs.extend(wave_path);
+                                                tally_outputs.extend(saif_path);
                                             }
                                             Ok(TestResult::Fail(msg)) => {
                                                 error!("Failed test ({test_name}): {msg}");
@@ -539,11 +781,13 @@ impl CmdTest {
                                         }
                                     }
                                 }
-                                if json {
+                                if structured {
                                     reports.lock().unwrap().push(TestReport {
                                         name: test_name.to_string(),
                                         status: rep_status,
                                         message: rep_message,
+                                        locations: rep_locations,
+                                        simulator: native_simulator.clone(),
                                         runtime_s,
                                         sim_s,
                                         output: if output.is_empty() {
@@ -556,7 +800,7 @@ impl CmdTest {
                                 use std::io::Write;
                                 let _ = std::io::stdout().flush();
                             }
-                            (tally_pass, tally_fail, tally_waves, tally_timings)
+                            (tally_pass, tally_fail, tally_outputs, tally_timings)
                         })
                     })
                     .collect();
@@ -564,12 +808,12 @@ impl CmdTest {
             });
 
             // Workers already printed each result as it finished; fold their
-            // tallies and register generated waveforms (needs `&mut metadata`).
-            let mut fresh_timings: Vec<(String, f64)> = Vec::new();
-            for (passed, failed, waves, timings) in results {
+            // tallies and register generated waveforms and SAIF files (needs
+            // `&mut metadata`).
+            for (passed, failed, outputs, timings) in results {
                 success += passed;
                 failure += failed;
-                for path in waves {
+                for path in outputs {
                     metadata.add_generated_file(path);
                 }
                 fresh_timings.extend(timings);
@@ -596,28 +840,47 @@ impl CmdTest {
                 TestType::Native => unreachable!(),
             };
 
+            if structured {
+                runner::capture();
+            }
             let t0 = std::time::Instant::now();
-            let ok = runner.run(metadata, *test, property.top, property.path, self.opt.wave)?;
+            let ok = runner.run(metadata, *test, property.top, property.path, self.opt.wave);
             let runtime_s = t0.elapsed().as_secs_f64();
+            let capture = runner::take_capture();
+            let ok = ok?;
             if ok {
                 success += 1;
                 if self.opt.wave {
                     let test_name = test.to_string();
-                    let path = wave_output_path(&test_name, property.path, metadata);
+                    let extension = metadata.test.waveform_format.extension();
+                    let path = test_output_path(&test_name, extension, property.path, metadata);
                     metadata.add_generated_file(path);
                 }
             } else {
                 failure += 1;
             }
-            if json {
+            if structured {
+                let message = if ok {
+                    None
+                } else if capture.failures.is_empty() {
+                    Some(format!("Failed test ({test})"))
+                } else {
+                    Some(capture.failures.join("\n"))
+                };
                 reports.lock().unwrap().push(TestReport {
                     name: test.to_string(),
                     status: if ok { "pass" } else { "fail" },
-                    message: None,
+                    message,
+                    locations: capture
+                        .locations
+                        .iter()
+                        .map(|x| display_location(x, &project_path))
+                        .collect(),
+                    simulator: runner.name().to_lowercase(),
                     runtime_s,
                     // External simulators compile+run in one step; no split.
                     sim_s: None,
-                    output: None,
+                    output: (!capture.output.is_empty()).then_some(capture.output),
                 });
             }
         }
@@ -655,11 +918,13 @@ impl CmdTest {
                     ("fail", Some(msg))
                 }
             };
-            if json {
+            if structured {
                 reports.lock().unwrap().push(TestReport {
                     name: module_name.clone(),
                     status,
                     message,
+                    locations: Vec::new(),
+                    simulator: native_simulator.clone(),
                     runtime_s,
                     sim_s: None,
                     output: None,
@@ -667,6 +932,67 @@ impl CmdTest {
             }
         }
 
+        if let Some(trace) = &self.opt.replay {
+            // clap requires `--replay-top` with `--replay`.
+            let top = self.opt.replay_top.as_deref().unwrap_or_default();
+            let name = format!("replay ({top})");
+            info!("Executing {name}");
+
+            let t0 = std::time::Instant::now();
+            let result = self.run_replay(&ir, top, trace, metadata, &config, &mut proto_cache);
+            let runtime_s = t0.elapsed().as_secs_f64();
+            let (status, message) = match result {
+                Ok(wave_path) => {
+                    info!("Succeeded {name}");
+                    success += 1;
+                    if let Some(path) = wave_path {
+                        metadata.add_generated_file(path);
+                    }
+                    ("pass", None)
+                }
+                Err(e) => {
+                    let msg = e.to_string();
+                    error!("Failed {name}: {msg}");
+                    failure += 1;
+                    ("fail", Some(msg))
+                }
+            };
+            if structured {
+                reports.lock().unwrap().push(TestReport {
+                    name,
+                    status,
+                    message,
+                    locations: Vec::new(),
+                    simulator: native_simulator.clone(),
+                    runtime_s,
+                    sim_s: None,
+                    output: None,
+                });
+            }
+        }
+
+        let mut xprop_events = xprop_events.into_inner().unwrap();
+        xprop_events.sort_by(|a, b| a.0.cmp(&b.0));
+        for (test_name, event) in &xprop_events {
+            warn!("{event} ({test_name})");
+        }
+
+        if self.opt.coverage {
+            let mut counts = coverage_counts.into_inner().unwrap();
+            coverage::merge(&mut counts, &coverage::take());
+            self.write_coverage(metadata, &counts)?;
+        }
+
+        let mut functional = functional.into_inner().unwrap();
+        functional.merge(covergroup::take());
+        let functional = self.write_functional_coverage(functional)?;
+
+        if self.opt.profile {
+            let mut profile = profile.into_inner().unwrap();
+            profile.merge(profiler::take());
+            self.write_profile(metadata, &profile)?;
+        }
+
         let ignored_msg = if ignored_count > 0 {
             format!(", {ignored_count} ignored")
         } else {
@@ -674,13 +1000,23 @@ impl CmdTest {
         };
         let summary = format!("Completed tests : {success} passed, {failure} failed{ignored_msg}");
 
-        if self.opt.wave {
+        if self.opt.wave || self.opt.coverage {
             metadata
                 .save_build_info()
                 .map_err(|e| miette::miette!("{e}"))?;
         }
 
-        if json {
+        if structured {
+            if self.opt.mutate {
+                self.run_mutations(
+                    metadata,
+                    &ir,
+                    &config,
+                    mutation_tests,
+                    &fresh_timings,
+                    failure,
+                )?;
+            }
             let report = TestSuiteReport {
                 format_version: 1,
                 backend: backend_name.to_string(),
@@ -688,22 +1024,280 @@ impl CmdTest {
                 failed: failure,
                 ignored: ignored_count,
                 tests: reports.into_inner().unwrap(),
+                functional_coverage: functional.items(),
             };
-            match serde_json::to_string_pretty(&report) {
-                Ok(s) => println!("{s}"),
-                Err(e) => eprintln!("failed to serialize test report: {e}"),
+            match self.opt.format {
+                TestFormat::Junit => print!("{}", report.junit(&metadata.project.name)),
+                TestFormat::Tap => print!("{}", report.tap()),
+                _ => match report.json() {
+                    Ok(s) => println!("{s}"),
+                    Err(e) => eprintln!("failed to serialize test report: {e}"),
+                },
             }
             return Ok(failure == 0);
         }
 
         if failure == 0 {
             info!("{summary}");
-            Ok(true)
         } else {
             error!("{summary}");
-            Ok(false)
         }
+        if self.opt.mutate {
+            self.run_mutations(
+                metadata,
+                &ir,
+                &config,
+                mutation_tests,
+                &fresh_timings,
+                failure,
+            )?;
+        }
+        Ok(failure == 0)
+    }
+
+    /// Runs `--mutate` over the native tests of a passing run, using the
+    /// baseline run times to bound each mutant's run.
+    fn run_mutations(
+        &self,
+        metadata: &mut Metadata,
+        ir: &veryl_analyzer::ir::Ir,
+        config: &Config,
+        tests: Vec<PendingNativeTest>,
+        timings: &[(String, f64)],
+        failure: i32,
+    ) -> Result<()> {
+        if failure != 0 {
+            warn!("Skipping mutation testing: every test must pass first");
+            return Ok(());
+        }
+        if tests.is_empty() {
+            warn!("Mutation testing only uses native-simulator tests; none were run");
+            return Ok(());
+        }
+
+        let sources = metadata
+            .paths::<PathBuf>(&[], false, false)
+            .map_err(|e| miette::miette!("{e}"))?
+            .into_iter()
+            .filter(|x| !x.example)
+            .filter_map(|x| resource_table::get_path_id(x.src))
+            .collect();
+        let tests: Vec<_> = tests
+            .into_iter()
+            .filter_map(|x| {
+                let top = x
+                    .top
+                    .or_else(|| resource_table::get_str_id(x.test_name.clone()))?;
+                let baseline = timings
+                    .iter()
+                    .find(|(name, _)| *name == x.test_name)
+                    .and_then(|(_, secs)| Duration::try_from_secs_f64(*secs).ok())
+                    .unwrap_or_default();
+                Some(MutationTest {
+                    top,
+                    path: x.test_path,
+                    timeout: x.timeout,
+                    golden: x.golden,
+                    baseline,
+                })
+            })
+            .collect();
+
+        let report = mutation::run(ir, &tests, &sources, config);
+        report.print(&metadata.project_path());
+        Ok(())
     }
+
+    /// Replays the `--replay` trace into `top`; returns the waveform path
+    /// when `--wave` is set.
+    fn run_replay(
+        &self,
+        ir: &veryl_analyzer::ir::Ir,
+        top: &str,
+        trace: &Path,
+        metadata: &Metadata,
+        config: &Config,
+        cache: &mut ProtoModuleCache,
+    ) -> std::result::Result<Option<PathBuf>, SimulatorError> {
+        let trace = Trace::load(trace)?;
+        let top_str_id = resource_table::get_str_id(top.to_string()).ok_or_else(|| {
+            SimulatorError::TopModuleNotFound {
+                module_name: top.to_string(),
+            }
+        })?;
+        let sim_ir = build_ir_cached(ir, top_str_id, config, cache)?;
+
+        let dump = match sim_ir.token.beg.source.get_path() {
+            Some(path) if self.opt.wave => Some(create_wave_dumper(
+                &format!("{top}_replay"),
+                path,
+                metadata,
+            )?),
+            _ => None,
+        };
+        let mut sim = Simulator::new(sim_ir, dump);
+        sim.init_components(config.seed, top)
+            .map_err(|e| SimulatorError::TestFailed {
+                message: e.to_string(),
+            })?;
+
+        let options = ReplayOptions {
+            compare: self.opt.replay_compare,
+            scope: self.opt.replay_scope.clone(),
+            clock_type: metadata.build.clock_type,
+            reset_type: metadata.build.reset_type,
+        };
+        let outcome = replay::run_replay(&mut sim, &trace, &options)?;
+        if let Some(profile) = sim.profile_report() {
+            profiler::record(profile);
+        }
+        for name in &outcome.unmatched {
+            warn!("  No trace signal for port `{name}`; it keeps its initial value");
+        }
+        match outcome.result {
+            TestResult::Pass => {
+                info!("  Replayed {} cycle(s)", outcome.cycles);
+                Ok(sim.dump.and_then(|d| d.into_path()))
+            }
+            TestResult::Fail(message) => Err(SimulatorError::TestFailed { message }),
+        }
+    }
+
+    /// Writes the `--profile` folded stacks and prints the tables.
+    fn write_profile(&self, metadata: &mut Metadata, profile: &profiler::Profile) -> Result<()> {
+        let project = metadata.project_path();
+        let path = self
+            .opt
+            .profile_output
+            .clone()
+            .unwrap_or_else(|| project.join("profile.folded"));
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.exists()
+        {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+        std::fs::write(&path, profile.folded(&project)).into_diagnostic()?;
+
+        // Structured formats keep stdout for the test report.
+        if self.opt.format == TestFormat::Pretty {
+            println!();
+            println!("profile:");
+            print!("{}", profile.table(&project));
+            println!();
+        }
+        info!("Output profile ({})", path.to_string_lossy());
+        metadata.add_generated_file(path);
+        Ok(())
+    }
+
+    /// Merges `db` into the `--coverage-db` file and prints its coverpoints
+    /// and holes. Returns the database that was reported.
+    fn write_functional_coverage(
+        &self,
+        mut db: covergroup::Database,
+    ) -> Result<covergroup::Database> {
+        if let Some(path) = &self.opt.coverage_db {
+            if path.exists() {
+                let text = std::fs::read_to_string(path).into_diagnostic()?;
+                let mut stored: covergroup::Database = serde_json::from_str(&text)
+                    .map_err(|e| miette::miette!("{}: {e}", path.to_string_lossy()))?;
+                stored.merge(db);
+                db = stored;
+            }
+            if let Some(parent) = path.parent()
+                && !parent.as_os_str().is_empty()
+                && !parent.exists()
+            {
+                std::fs::create_dir_all(parent).into_diagnostic()?;
+            }
+            let text = serde_json::to_string_pretty(&db).into_diagnostic()?;
+            std::fs::write(path, text).into_diagnostic()?;
+            info!("Output functional coverage ({})", path.to_string_lossy());
+        }
+
+        // Structured formats keep stdout for the test report.
+        if !db.is_empty() && self.opt.format == TestFormat::Pretty {
+            println!();
+            println!("functional coverage:");
+            print!("{}", db.table(8));
+            println!();
+        }
+        Ok(db)
+    }
+
+    /// Writes the report for `counts` (merged over the run) and prints a
+    /// per-file summary.
+    fn write_coverage(&self, metadata: &mut Metadata, counts: &[u64]) -> Result<()> {
+        let report = coverage::Report::new(&coverage::points(), counts);
+        let project = metadata.project_path();
+        let (text, default) = match self.opt.coverage_format {
+            CoverageFormat::Lcov => (report.lcov(), "lcov.info"),
+            CoverageFormat::Cobertura => {
+                let timestamp = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|x| x.as_secs())
+                    .unwrap_or(0);
+                (report.cobertura(&project, timestamp), "coverage.xml")
+            }
+        };
+        let path = self
+            .opt
+            .coverage_output
+            .clone()
+            .unwrap_or_else(|| project.join(default));
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.exists()
+        {
+            std::fs::create_dir_all(parent).into_diagnostic()?;
+        }
+        std::fs::write(&path, text).into_diagnostic()?;
+
+        // Structured formats keep stdout for the test report.
+        if self.opt.format == TestFormat::Pretty {
+            print_coverage_summary(&report, &project);
+        }
+        info!("Output coverage ({})", path.to_string_lossy());
+        metadata.add_generated_file(path);
+        Ok(())
+    }
+}
+
+fn print_coverage_summary(report: &coverage::Report, project: &Path) {
+    fn cell((covered, total): (usize, usize)) -> String {
+        if total == 0 {
+            "-".to_string()
+        } else {
+            format!(
+                "{covered}/{total} {:.1}%",
+                covered as f64 * 100.0 / total as f64
+            )
+        }
+    }
+    let row = |name: &str, t: &coverage::Totals| {
+        println!(
+            "  {name:<32} {:>18} {:>18} {:>18} {:>14} {:>11}",
+            cell(t.lines),
+            cell(t.branches),
+            cell(t.toggles),
+            cell(t.states),
+            t.transitions
+        );
+    };
+
+    println!();
+    println!("coverage:");
+    println!(
+        "  {:<32} {:>18} {:>18} {:>18} {:>14} {:>11}",
+        "file", "lines", "branches", "toggles", "fsm states", "transitions"
+    );
+    for (path, file) in &report.files {
+        let name = path.strip_prefix(project).unwrap_or(path).to_string_lossy();
+        row(&name, &file.totals());
+    }
+    row("total", &report.totals());
+    println!();
 }
 
 /// Freshens the project's own `[[components]]` interface manifests before
@@ -1144,11 +1738,20 @@ fn prepare_native_test(
     } else {
         None
     };
+    let saif = if opt.saif {
+        let path = test_output_path(test_name, "saif", test_path, metadata);
+        create_parent_dir(&path)?;
+        info!("  Recording activity to {}", path.display());
+        Some(path)
+    } else {
+        None
+    };
 
     Ok(NativeTestJob {
         module_name,
         sim_ir,
         dump,
+        saif,
     })
 }
 
@@ -1269,6 +1872,43 @@ mod tests {
         )
     }
 
+    #[test]
+    fn golden_files_compare_and_bless() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("test.veryl");
+        let test_path = resource_table::insert_path(&source);
+        let output = dir.path().join("out.csv");
+        std::fs::write(&output, "1,2\n").unwrap();
+        let golden = vec![
+            GoldenItem {
+                golden: resource_table::insert_str("golden/test.log"),
+                output: None,
+            },
+            GoldenItem {
+                golden: resource_table::insert_str("golden/test.csv"),
+                output: Some(resource_table::insert_str(&output.to_string_lossy())),
+            },
+        ];
+
+        let ret = check_golden(&golden, "a\n", test_path, false);
+        assert!(ret.is_err_and(|x| x.contains("--bless")));
+
+        let ret = check_golden(&golden, "a\n", test_path, true);
+        assert!(ret.is_ok_and(|x| x.is_empty()));
+        let blessed = dir.path().join("golden/test.csv");
+        assert_eq!(std::fs::read_to_string(&blessed).unwrap(), "1,2\n");
+
+        let ret = check_golden(&golden, "a\n", test_path, false);
+        assert!(ret.is_ok_and(|x| x.is_empty()));
+
+        std::fs::write(&output, "1,3\n").unwrap();
+        let ret = check_golden(&golden, "a\n", test_path, false).unwrap();
+        assert_eq!(ret.len(), 1);
+        assert_eq!(ret[0].path, blessed);
+        assert_eq!(ret[0].expected, "1,2\n");
+        assert_eq!(ret[0].actual, "1,3\n");
+    }
+
     #[test]
     fn artifact_selection_ignores_dependency_cdylibs() {
         let stdout = format!(
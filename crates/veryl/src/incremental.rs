@@ -236,6 +236,21 @@ impl Incremental {
         self.store.put(src, hash, blob.as_deref());
     }
 
+    /// Whether the file emitted an `_sva` assertion checker in the last
+    /// successful build; restored files are not emitted again.
+    pub fn had_assertions(&self, src: &Path) -> bool {
+        self.store
+            .entry(&src.to_string_lossy())
+            .is_some_and(|x| x.assertions)
+    }
+
+    /// Records whether the file emitted an `_sva` assertion checker in this
+    /// build.
+    pub fn set_assertions(&mut self, src: &Path, assertions: bool) {
+        self.store
+            .set_assertions(&src.to_string_lossy(), assertions);
+    }
+
     /// Drains the diagnostics of files restored this build, for the caller to
     /// re-report. Call once, after the restore pass.
     pub fn take_restored_diagnostics(&mut self) -> Vec<CachedDiagnostic> {
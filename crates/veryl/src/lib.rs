@@ -14,6 +14,7 @@ pub mod cmd_migrate;
 pub mod cmd_new;
 pub mod cmd_publish;
 pub mod cmd_register;
+pub mod cmd_sim;
 pub mod cmd_synth;
 pub mod cmd_test;
 pub mod cmd_translate;
@@ -24,10 +25,13 @@ pub mod diff;
 pub mod doc;
 pub mod external_subcommand;
 pub mod incremental;
+pub mod mutation;
 pub mod pipeline;
 pub mod runner;
 pub mod stopwatch;
+pub mod test_report;
 pub mod utils;
+pub mod watch;
 pub use stopwatch::StopWatch;
 
 // ---------------------------------------------------------------------------------------------------------------------
@@ -93,6 +97,7 @@ pub enum Commands {
     Dump(OptDump),
     Test(OptTest),
     Synth(OptSynth),
+    Sim(OptSim),
     Translate(OptTranslate),
     #[command(external_subcommand)]
     External(Vec<OsString>),
@@ -152,6 +157,10 @@ pub struct OptFmt {
 pub struct OptCheck {
     /// Target files
     pub files: Vec<PathBuf>,
+
+    /// Re-run whenever a source, Veryl.toml or Veryl.lock changes
+    #[arg(long)]
+    pub watch: bool,
 }
 
 /// Build the target codes corresponding to the current project
@@ -169,6 +178,16 @@ pub struct OptBuild {
     /// working directory.
     #[arg(long, value_name = "DIR")]
     pub out_dir: Option<PathBuf>,
+
+    /// Build profile from `[profile.<NAME>]` in Veryl.toml. Its overrides
+    /// and defines apply on top of `[build]`, and outputs go to the
+    /// profile's own directory (`--out-dir` still takes precedence).
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Re-run whenever a source, Veryl.toml or Veryl.lock changes
+    #[arg(long)]
+    pub watch: bool,
 }
 
 /// Clean-up the current project
@@ -211,6 +230,10 @@ pub struct OptMigrate {
 pub struct OptDoc {
     /// Target files
     pub files: Vec<PathBuf>,
+
+    /// Re-run whenever a source, Veryl.toml or Veryl.lock changes
+    #[arg(long)]
+    pub watch: bool,
 }
 
 /// Execute tests
@@ -231,6 +254,11 @@ pub struct OptTest {
     #[arg(long)]
     pub wave: bool,
 
+    /// Record per-net switching activity of native tests as `<test>.saif`,
+    /// written next to the waveforms (see `veryl synth --saif`)
+    #[arg(long)]
+    pub saif: bool,
+
     /// Native-simulator code-generation backend (default: cc)
     #[arg(long, value_enum, default_value = "cc")]
     pub backend: Backend,
@@ -238,9 +266,17 @@ pub struct OptTest {
     /// Dual-run the `cc` backend against Cranelift and panic on divergence.
     /// Takes an optional stride: dual-run + diff only every Nth cycle (default
     /// 64 when given with no value); `--backend-validate 1` = every cycle.
+    /// With `--sim-threads`, also checks the partitioned comb settle against
+    /// the single-threaded one.
     #[arg(long, num_args = 0..=1, default_missing_value = "64", value_name = "STRIDE")]
     pub backend_validate: Option<u64>,
 
+    /// Settle combinational logic on up to N threads by splitting it into
+    /// independent partitions (default 1: single-threaded). Pays off on large
+    /// designs; also settable via `VERYL_SIM_THREADS`.
+    #[arg(long, value_name = "N")]
+    pub sim_threads: Option<usize>,
+
     /// Disable FF classification optimization (force all always_ff variables to FF)
     #[arg(long)]
     pub disable_ff_opt: bool,
@@ -275,14 +311,120 @@ pub struct OptTest {
     #[arg(long = "4state")]
     pub four_state: bool,
 
-    /// Output format: `pretty` (human-readable summary, default) or `json`
-    /// (machine-readable report on stdout)
+    /// What a four-state `if`/`case` does when its condition is X: `standard`
+    /// takes the `else`/`default` branch, `pessimistic` makes everything the
+    /// candidate branches assign X, `tmerge` keeps only the bits they agree on
     #[arg(long, value_enum, default_value_t)]
-    pub format: Format,
+    pub xprop: XProp,
+
+    /// Warn where X first reaches each register and top-level output of a
+    /// four-state test, e.g. a register a reset does not initialize
+    #[arg(long)]
+    pub xprop_report: bool,
+
+    /// Output format: `pretty` (human-readable summary, default), or a
+    /// report on stdout: `json`, `junit` (JUnit XML) or `tap` (TAP version 13)
+    #[arg(long, value_enum, default_value_t)]
+    pub format: TestFormat,
 
     /// Report format version (only with `--format json`; currently only 1)
     #[arg(long = "format-version")]
     pub format_version: Option<u32>,
+
+    /// Re-run whenever a source, Veryl.toml or Veryl.lock changes
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Collect line, branch, toggle and FSM coverage from native-simulator
+    /// tests, merged across all tests of the run
+    #[arg(long)]
+    pub coverage: bool,
+
+    /// Coverage report format
+    #[arg(long, value_enum, default_value_t, requires = "coverage")]
+    pub coverage_format: CoverageFormat,
+
+    /// Coverage report path (default: `lcov.info` or `coverage.xml` in the
+    /// project root)
+    #[arg(long, value_name = "FILE", requires = "coverage")]
+    pub coverage_output: Option<PathBuf>,
+
+    /// Merge `$tb::coverage` functional coverage into FILE (created when
+    /// missing), so runs with different seeds or test selections accumulate
+    #[arg(long, value_name = "FILE")]
+    pub coverage_db: Option<PathBuf>,
+
+    /// Drive the inputs of `--replay-top` from a VCD/FST capture, matching
+    /// ports to trace signals by name (`.fst` is read as FST, anything else
+    /// as VCD)
+    #[arg(long, value_name = "FILE", requires = "replay_top")]
+    pub replay: Option<PathBuf>,
+
+    /// Module replayed by `--replay`
+    #[arg(long, value_name = "MODULE", requires = "replay")]
+    pub replay_top: Option<String>,
+
+    /// Only match trace signals directly in this dot-separated scope (e.g.
+    /// `tb.dut`); by default the shallowest signal of each name is used
+    #[arg(long, value_name = "SCOPE", requires = "replay")]
+    pub replay_scope: Option<String>,
+
+    /// Also compare the outputs with the recorded values before each clock
+    /// edge and fail at the first mismatching cycle
+    #[arg(long, requires = "replay")]
+    pub replay_compare: bool,
+
+    /// Time native-simulator tests per module instance, always block and
+    /// backend chunk, and list the statements left on the interpreter
+    #[arg(long)]
+    pub profile: bool,
+
+    /// Flamegraph folded-stack output of `--profile` (default:
+    /// `profile.folded` in the project root)
+    #[arg(long, value_name = "FILE", requires = "profile")]
+    pub profile_output: Option<PathBuf>,
+
+    /// Overwrite the `#[golden]` files of passing tests with their current
+    /// output instead of comparing against them
+    #[arg(long)]
+    pub bless: bool,
+
+    /// After the tests pass, re-run them against mutated designs (flipped
+    /// operators, inverted conditions, stuck-at assignments, dropped reset
+    /// values) and report the mutants no test detects
+    #[arg(long)]
+    pub mutate: bool,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CoverageFormat {
+    /// LCOV tracefile
+    #[default]
+    Lcov,
+    /// Cobertura XML
+    Cobertura,
+}
+
+/// X-propagation mode selected by `veryl test --xprop`.
+#[derive(Clone, Copy, Default, Debug, ValueEnum)]
+pub enum XProp {
+    /// SystemVerilog semantic: an X condition is false
+    #[default]
+    Standard,
+    /// Everything any candidate branch assigns becomes X
+    Pessimistic,
+    /// Bits on which all candidate branches agree keep their value
+    Tmerge,
+}
+
+impl From<XProp> for veryl_simulator::xprop::XProp {
+    fn from(x: XProp) -> Self {
+        match x {
+            XProp::Standard => veryl_simulator::xprop::XProp::Standard,
+            XProp::Pessimistic => veryl_simulator::xprop::XProp::Pessimistic,
+            XProp::Tmerge => veryl_simulator::xprop::XProp::TMerge,
+        }
+    }
 }
 
 /// Native-simulator code-generation backend selected by `veryl test --backend`.
@@ -364,11 +506,20 @@ pub enum Format {
     Json,
 }
 
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TestFormat {
+    #[default]
+    Pretty,
+    Json,
+    Junit,
+    Tap,
+}
+
 /// Mirrors `veryl metadata`'s `--format-version` so `synth`/`test` behave the
 /// same. Only version 1 exists yet.
-pub(crate) fn check_format_version(format: Format, version: Option<u32>) -> miette::Result<()> {
+pub(crate) fn check_format_version(json: bool, version: Option<u32>) -> miette::Result<()> {
     if let Some(v) = version {
-        if !matches!(format, Format::Json) {
+        if !json {
             miette::bail!("--format-version is only supported with --format json");
         }
         if v != 1 {
@@ -417,6 +568,50 @@ pub struct OptDump {
     pub ir: bool,
 }
 
+/// Drive a module interactively on the native simulator.
+///
+/// Reads commands (`set`, `get`, `step`, `run until`, `force`, `wave`, ...;
+/// see `help` at the prompt) from stdin, after any `--script` files.
+#[derive(Args)]
+pub struct OptSim {
+    /// Top module name
+    pub top: String,
+
+    /// Target files
+    #[arg(long = "file", value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    /// Execute the commands in FILE before reading stdin (can be specified
+    /// multiple times)
+    #[arg(long, value_name = "FILE")]
+    pub script: Vec<PathBuf>,
+
+    /// Exit after the scripts instead of reading stdin
+    #[arg(long, requires = "script")]
+    pub batch: bool,
+
+    /// Native-simulator code-generation backend
+    #[arg(long, value_enum, default_value = "cranelift")]
+    pub backend: Backend,
+
+    /// Simulate in four-state (X/Z) mode
+    #[arg(long = "4state")]
+    pub four_state: bool,
+
+    /// Define a name visible to `#[ifdef]` (can be specified multiple times)
+    #[arg(short = 'D', long = "define", value_name = "NAME")]
+    pub define: Vec<String>,
+
+    /// Base seed for user-defined component instances
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Let the `force` command hold the hierarchical variable NAME against
+    /// its drivers (can be specified multiple times)
+    #[arg(long, value_name = "NAME")]
+    pub forceable: Vec<String>,
+}
+
 /// Synthesize to a simple gate-level netlist and report area / critical path.
 ///
 /// Design-parameter knobs (`clock_freq`, `activity`) and the default `top` /
@@ -461,4 +656,29 @@ pub struct OptSynth {
     /// Dump the power estimate (leakage + dynamic breakdown)
     #[arg(long)]
     pub dump_power: bool,
+
+    /// Write the gate-level netlist as Yosys RTLIL text (`read_rtlil`), one
+    /// module per instantiated Veryl module
+    #[arg(long, value_name = "FILE")]
+    pub rtlil: Option<PathBuf>,
+
+    /// Write the gate-level netlist as a Yosys JSON netlist (`read_json`,
+    /// nextpnr), one module per instantiated Veryl module
+    #[arg(long, value_name = "FILE")]
+    pub yosys_json: Option<PathBuf>,
+
+    /// Write the top module and its hierarchy as FIRRTL text (CIRCT
+    /// `firtool`), exported from the analyzer IR before synthesis
+    #[arg(long, value_name = "FILE")]
+    pub firrtl: Option<PathBuf>,
+
+    /// Estimate dynamic power from the per-net switching activity in a SAIF
+    /// file (e.g. from `veryl test --saif`) instead of `synth.activity`
+    #[arg(long, value_name = "FILE")]
+    pub saif: Option<PathBuf>,
+
+    /// Instance of the SAIF to map onto the top module, as a `.`-separated
+    /// path below the SAIF's top instance (default: the top instance itself)
+    #[arg(long, value_name = "PATH", requires = "saif")]
+    pub saif_instance: Option<String>,
 }
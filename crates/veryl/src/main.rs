@@ -125,6 +125,7 @@ fn main() -> Result<ExitCode> {
             | Commands::Doc(_)
             | Commands::Dump(_)
             | Commands::Synth(_)
+            | Commands::Sim(_)
             | Commands::Publish(_)
     ) {
         cmd_test::build_component_manifests(&metadata);
@@ -135,7 +136,20 @@ fn main() -> Result<ExitCode> {
         Commands::Init(x) => cmd_init::CmdInit::new(x).exec(),
         Commands::Fmt(x) => cmd_fmt::CmdFmt::new(x).exec(&mut metadata, opt.quiet),
         // check emits nothing, so it writes no info.toml.
+        Commands::Check(x) if x.watch => {
+            let cmd = cmd_check::CmdCheck::new(x);
+            watch::run(&mut metadata, "check", true, |m| cmd.exec(m))
+        }
         Commands::Check(x) => cmd_check::CmdCheck::new(x).exec(&mut metadata),
+        Commands::Build(x) if x.watch => {
+            let quiet = opt.quiet;
+            let cmd = cmd_build::CmdBuild::new(x);
+            watch::run(&mut metadata, "build", true, |m| {
+                let ret = cmd.exec(m, false, quiet, None, None, &[]);
+                m.save_build_info()?;
+                ret
+            })
+        }
         Commands::Build(x) => {
             let ret =
                 cmd_build::CmdBuild::new(x).exec(&mut metadata, false, opt.quiet, None, None, &[]);
@@ -147,15 +161,28 @@ fn main() -> Result<ExitCode> {
         Commands::Publish(x) => cmd_publish::CmdPublish::new(x).exec(&mut metadata),
         Commands::Register(x) => cmd_register::CmdRegister::new(x).exec(&metadata),
         Commands::Migrate(x) => cmd_migrate::CmdMigrate::new(x).exec(&mut metadata, opt.quiet),
+        Commands::Doc(x) if x.watch => {
+            let cmd = cmd_doc::CmdDoc::new(x);
+            watch::run(&mut metadata, "doc", false, |m| cmd.exec(m))
+        }
         Commands::Doc(x) => cmd_doc::CmdDoc::new(x).exec(&mut metadata),
         Commands::Metadata(x) => cmd_metadata::CmdMetadata::new(x).exec(&mut metadata),
         Commands::Dump(x) => cmd_dump::CmdDump::new(x).exec(&mut metadata),
+        Commands::Test(x) if x.watch => {
+            let cmd = cmd_test::CmdTest::new(x);
+            watch::run(&mut metadata, "test", true, |m| {
+                let ret = cmd.exec(m);
+                m.save_build_info()?;
+                ret
+            })
+        }
         Commands::Test(x) => {
             let ret = cmd_test::CmdTest::new(x).exec(&mut metadata);
             metadata.save_build_info()?;
             ret
         }
         Commands::Synth(x) => cmd_synth::CmdSynth::new(x).exec(&mut metadata),
+        Commands::Sim(x) => cmd_sim::CmdSim::new(x).exec(&mut metadata),
         Commands::Translate(x) => cmd_translate::CmdTranslate::new(x).exec(),
         Commands::External(_) => unreachable!(),
     };
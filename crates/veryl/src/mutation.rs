@@ -0,0 +1,272 @@
+//! `veryl test --mutate`: re-run the native tests against systematically
+//! mutated designs and report the mutants no test kills.
+
+use crate::cmd_test::{check_golden, display_location};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+use veryl_analyzer::attribute::{GoldenItem, TimeoutItem};
+use veryl_analyzer::ir::mutation::{self, Mutation};
+use veryl_analyzer::ir::{Component, Ir};
+use veryl_parser::resource_table::{self, PathId, StrId};
+use veryl_parser::text_table;
+use veryl_parser::veryl_token::TokenSource;
+use veryl_simulator::ir::{Config, ProtoModuleCache, build_ir_cached};
+use veryl_simulator::testbench::{TestResult, run_native_testbench_with};
+use veryl_simulator::{assert_buffer, covergroup, output_buffer, xprop};
+
+/// A passing native test, with the run time measured by the baseline run.
+pub struct MutationTest {
+    pub top: StrId,
+    pub path: PathId,
+    pub timeout: TimeoutItem,
+    pub golden: Vec<GoldenItem>,
+    pub baseline: Duration,
+}
+
+pub struct MutationReport {
+    pub killed: Vec<Mutation>,
+    pub survived: Vec<Mutation>,
+    /// Mutants which failed to build or elaborate; left out of the score.
+    pub invalid: Vec<Mutation>,
+}
+
+impl MutationReport {
+    /// Percentage of the valid mutants that were killed.
+    pub fn score(&self) -> f64 {
+        let scored = self.killed.len() + self.survived.len();
+        if scored == 0 {
+            100.0
+        } else {
+            self.killed.len() as f64 * 100.0 / scored as f64
+        }
+    }
+
+    pub fn print(&self, base: &Path) {
+        let total = self.killed.len() + self.survived.len() + self.invalid.len();
+        for x in &self.survived {
+            warn!("Mutant survived at {}: {}", location(x, base), x.kind);
+        }
+        for x in &self.invalid {
+            info!("Mutant invalid at {}: {}", location(x, base), x.kind);
+        }
+        let score = self.score();
+        info!(
+            "Mutation testing : {total} mutants, {} killed, {} survived, {} invalid \
+             ({score:.1}% score)",
+            self.killed.len(),
+            self.survived.len(),
+            self.invalid.len()
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Killed,
+    Survived,
+    Invalid,
+}
+
+fn location(mutation: &Mutation, base: &Path) -> String {
+    let token = &mutation.token.beg;
+    match token.source {
+        TokenSource::File { path, .. } => {
+            let path = resource_table::get_path_value(path).unwrap_or_default();
+            display_location(&(path, token.line), base)
+        }
+        _ => format!("{}:{}", token.source, token.line),
+    }
+}
+
+/// A mutant is killed if it makes a test fail, time out or mismatch its
+/// golden files. Runs are capped at ten times the baseline, so a mutant that
+/// stalls the design (e.g. a counter that never reaches its bound) is killed
+/// by the timeout. A mutant the simulator can't build or start is invalid:
+/// no test ran against it.
+fn outcome(top: &Component, test: &MutationTest, config: &Config) -> Outcome {
+    let ir = Ir {
+        components: vec![top.clone()],
+    };
+    // The mutated top shares its name with the original; a fresh cache keeps
+    // the elaborations apart while unmutated chunks still hit the
+    // cross-test artifact cache.
+    let mut cache = ProtoModuleCache::default();
+    let timeout = TimeoutItem {
+        cycles: test.timeout.cycles,
+        time: Some((test.baseline * 10).max(Duration::from_secs(1))),
+    };
+
+    output_buffer::enable();
+    let result = build_ir_cached(&ir, test.top, config, &mut cache).and_then(|sim_ir| {
+        let module_name = sim_ir.name.to_string();
+        run_native_testbench_with(sim_ir, None, None, module_name, None, timeout)
+    });
+    let output = output_buffer::take();
+    assert_buffer::take_locations();
+    covergroup::take();
+    xprop::take();
+
+    match result {
+        Ok(TestResult::Pass) => match check_golden(&test.golden, &output, test.path, false) {
+            Ok(x) if x.is_empty() => Outcome::Survived,
+            _ => Outcome::Killed,
+        },
+        Ok(TestResult::Fail(_)) => Outcome::Killed,
+        Err(_) => Outcome::Invalid,
+    }
+}
+
+/// Combines the outcomes of the tests a mutant affects. Any kill decides it,
+/// so the remaining tests are not run; the mutant is invalid only if none of
+/// them ran against it.
+fn combine(outcomes: impl IntoIterator<Item = Outcome>) -> Outcome {
+    let mut ret = None;
+    for x in outcomes {
+        match x {
+            Outcome::Killed => return Outcome::Killed,
+            Outcome::Survived => ret = Some(Outcome::Survived),
+            Outcome::Invalid => {
+                ret.get_or_insert(Outcome::Invalid);
+            }
+        }
+    }
+    ret.unwrap_or(Outcome::Survived)
+}
+
+/// Mutate the designs under `tests` and re-run the tests that exercise each
+/// mutant. Only sites in `sources` (the project's own files) are mutated.
+pub fn run(
+    ir: &Ir,
+    tests: &[MutationTest],
+    sources: &HashSet<PathId>,
+    config: &Config,
+) -> MutationReport {
+    let mut config = config.clone();
+    config.coverage = false;
+    config.profile = false;
+    config.xprop_report = false;
+
+    let tops: Vec<_> = tests
+        .iter()
+        .map(|test| {
+            ir.components
+                .iter()
+                .find(|x| matches!(x, Component::Module(x) if x.name == test.top))
+        })
+        .collect();
+
+    let filter = |token: &veryl_parser::token_range::TokenRange| {
+        matches!(
+            token.beg.source,
+            TokenSource::File { path, .. } if sources.contains(&path)
+        )
+    };
+    let mut mutations = Vec::new();
+    let mut seen = HashSet::new();
+    for top in tops.iter().flatten() {
+        for x in mutation::collect(top, &filter) {
+            if seen.insert(x) {
+                mutations.push(x);
+            }
+        }
+    }
+    info!("Running {} mutants", mutations.len());
+
+    let num_threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(mutations.len())
+        .max(1);
+    let queue = std::sync::Mutex::new(mutations.iter());
+    let resource_snapshot = resource_table::export_tables();
+    let text_snapshot = text_table::export_tables();
+
+    let results: Vec<Vec<(Mutation, Outcome)>> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..num_threads)
+            .map(|_| {
+                s.spawn(|| {
+                    resource_table::import_tables(&resource_snapshot);
+                    text_table::import_tables(&text_snapshot);
+                    let mut ret = Vec::new();
+                    loop {
+                        let mutation = queue.lock().unwrap().next();
+                        let Some(mutation) = mutation else { break };
+                        let outcomes = tests.iter().zip(&tops).filter_map(|(test, top)| {
+                            let mut top = (*top)?.clone();
+                            (mutation::apply(&mut top, mutation) > 0)
+                                .then(|| outcome(&top, test, &config))
+                        });
+                        ret.push((*mutation, combine(outcomes)));
+                    }
+                    ret
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut results: Vec<_> = results.into_iter().flatten().collect();
+    results.sort_by_key(|(x, _)| {
+        let token = &x.token.beg;
+        (token.source.to_string(), token.line, token.column)
+    });
+    let select = |outcome| {
+        results
+            .iter()
+            .filter(|(_, x)| *x == outcome)
+            .map(|(x, _)| *x)
+            .collect()
+    };
+    MutationReport {
+        killed: select(Outcome::Killed),
+        survived: select(Outcome::Survived),
+        invalid: select(Outcome::Invalid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veryl_analyzer::ir::mutation::MutationKind;
+    use veryl_parser::token_range::TokenRange;
+
+    #[test]
+    fn invalid_mutants_are_not_scored() {
+        let mutation = Mutation {
+            kind: MutationKind::InvertCondition,
+            token: TokenRange::default(),
+        };
+        let report = MutationReport {
+            killed: vec![mutation],
+            survived: vec![mutation],
+            invalid: vec![mutation, mutation],
+        };
+        assert_eq!(report.score(), 50.0);
+
+        let report = MutationReport {
+            killed: vec![],
+            survived: vec![],
+            invalid: vec![mutation],
+        };
+        assert_eq!(report.score(), 100.0);
+    }
+
+    #[test]
+    fn any_affected_test_kills_mutant() {
+        use Outcome::*;
+        assert!(combine([Invalid, Killed]) == Killed);
+        assert!(combine([Survived, Invalid, Killed]) == Killed);
+        assert!(combine([Invalid, Survived]) == Survived);
+        assert!(combine([Survived, Invalid]) == Survived);
+        assert!(combine([Invalid, Invalid]) == Invalid);
+        assert!(combine([]) == Survived);
+
+        // A kill stops the remaining tests from running.
+        let mut ran = 0;
+        let outcomes = [Killed, Invalid].into_iter().inspect(|_| ran += 1);
+        assert!(combine(outcomes) == Killed);
+        assert_eq!(ran, 1);
+    }
+}
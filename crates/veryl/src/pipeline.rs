@@ -168,6 +168,12 @@ impl CheckError {
             .retain(|x| !matches!(x, Diag::Cached(_)) || !fresh.contains(&x.dedup_key()));
     }
 
+    /// Number of (errors, warnings) reported.
+    pub fn counts(&self) -> (usize, usize) {
+        let errors = self.related.iter().filter(|x| x.is_error()).count();
+        (errors, self.related.len() - errors)
+    }
+
     pub fn check_err(self) -> Result<Self> {
         if self.related.iter().all(|x| !x.is_error()) {
             Ok(self)
@@ -3,6 +3,7 @@ use log::{Level, debug, log_enabled};
 use miette::{IntoDiagnostic, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::cell::RefCell;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tokio::process::Command;
@@ -21,6 +22,52 @@ pub use vcs::*;
 pub use verilator::*;
 pub use vivado::*;
 
+/// Tool output and failures of the external-simulator test running on this
+/// thread, collected for `veryl test --format junit|tap|json`.
+#[derive(Default)]
+pub struct Capture {
+    /// Every line the tools printed.
+    pub output: String,
+    /// Error and fatal lines.
+    pub failures: Vec<String>,
+    /// `.veryl` locations the failures were mapped back to.
+    pub locations: Vec<(PathBuf, u32)>,
+}
+
+thread_local! {
+    static CAPTURE: RefCell<Option<Capture>> = const { RefCell::new(None) };
+}
+
+/// Start collecting runner output on this thread.
+pub fn capture() {
+    CAPTURE.with(|x| *x.borrow_mut() = Some(Capture::default()));
+}
+
+/// Stop collecting and return what was collected since `capture`.
+pub fn take_capture() -> Capture {
+    CAPTURE.with(|x| x.borrow_mut().take().unwrap_or_default())
+}
+
+/// Captured lines go to the report instead of stdout.
+fn capturing() -> bool {
+    CAPTURE.with(|x| x.borrow().is_some())
+}
+
+fn capture_failure(line: &str) {
+    static RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\^ from: (?<path>.+):(?<line>[0-9]+):[0-9]+$").unwrap());
+    CAPTURE.with(|x| {
+        if let Some(capture) = x.borrow_mut().as_mut() {
+            for caps in line.lines().filter_map(|x| RE.captures(x)) {
+                if let Ok(line) = caps["line"].parse() {
+                    capture.locations.push((PathBuf::from(&caps["path"]), line));
+                }
+            }
+            capture.failures.push(line.to_string());
+        }
+    });
+}
+
 fn new_cmd(program: &str) -> Command {
     #[cfg(windows)]
     {
@@ -48,6 +95,17 @@ pub trait Runner {
 
     fn failure(&mut self);
 
+    /// One raw line of tool output; shown in debug mode.
+    fn output(&self, line: &str) {
+        CAPTURE.with(|x| {
+            if let Some(capture) = x.borrow_mut().as_mut() {
+                capture.output.push_str(line);
+                capture.output.push('\n');
+            }
+        });
+        self.debug(line);
+    }
+
     fn debug(&self, line: &str) {
         if log_enabled!(Level::Debug) {
             debug!("{} : {}", self.name(), line);
@@ -57,7 +115,7 @@ pub trait Runner {
     fn info(&self, line: &str) {
         static STYLE: Lazy<Style> =
             Lazy::new(|| Style::new().fg_color(Some(AnsiColor::Green.into())));
-        if !log_enabled!(Level::Debug) {
+        if !log_enabled!(Level::Debug) && !capturing() {
             println!("{}{}{}", STYLE.render(), line, STYLE.render_reset());
         }
     }
@@ -65,7 +123,7 @@ pub trait Runner {
     fn warning(&mut self, line: &str) {
         static STYLE: Lazy<Style> =
             Lazy::new(|| Style::new().fg_color(Some(AnsiColor::Yellow.into())));
-        if !log_enabled!(Level::Debug) {
+        if !log_enabled!(Level::Debug) && !capturing() {
             println!("{}{}{}", STYLE.render(), line, STYLE.render_reset());
         }
     }
@@ -73,18 +131,20 @@ pub trait Runner {
     fn error(&mut self, line: &str) {
         static STYLE: Lazy<Style> =
             Lazy::new(|| Style::new().fg_color(Some(AnsiColor::Red.into())));
-        if !log_enabled!(Level::Debug) {
+        if !log_enabled!(Level::Debug) && !capturing() {
             println!("{}{}{}", STYLE.render(), line, STYLE.render_reset());
         }
+        capture_failure(line);
         self.failure();
     }
 
     fn fatal(&mut self, line: &str) {
         static STYLE: Lazy<Style> =
             Lazy::new(|| Style::new().fg_color(Some(AnsiColor::Red.into())).bold());
-        if !log_enabled!(Level::Debug) {
+        if !log_enabled!(Level::Debug) && !capturing() {
             println!("{}{}{}", STYLE.render(), line, STYLE.render_reset());
         }
+        capture_failure(line);
         self.failure();
     }
 }
@@ -47,7 +47,7 @@ impl Cocotb {
     }
 
     fn parse_line(&mut self, line: &str, force_error: bool) {
-        self.debug(line); // Show all lines in debug mode by default
+        self.output(line); // Show all lines in debug mode by default
 
         // Check for keyword presence to set line's state
         if force_error {
@@ -47,7 +47,7 @@ impl Vcs {
     }
 
     fn parse_line(&mut self, line: &str) {
-        self.debug(line);
+        self.output(line);
 
         match self.state {
             State::Idle => {
@@ -54,7 +54,7 @@ impl Verilator {
     }
 
     fn parse_line(&mut self, line: &str) {
-        self.debug(line);
+        self.output(line);
 
         match self.state {
             State::Idle => {
@@ -46,7 +46,7 @@ impl Vivado {
     }
 
     fn parse_line(&mut self, line: &str) {
-        self.debug(line);
+        self.output(line);
 
         match self.state {
             State::Idle => {
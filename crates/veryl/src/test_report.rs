@@ -0,0 +1,270 @@
+//! Machine-readable `veryl test` reports: `--format json`, `junit` and `tap`.
+
+use std::fmt::Write;
+
+/// Emitted by `veryl test --format json`.
+#[derive(serde::Serialize)]
+pub struct TestSuiteReport {
+    /// Bump on any breaking change to the report shape.
+    pub format_version: u32,
+    pub backend: String,
+    pub passed: i32,
+    pub failed: i32,
+    pub ignored: usize,
+    pub tests: Vec<TestReport>,
+    /// `$tb::coverage` coverpoints and crosses, merged over the run.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub functional_coverage: Vec<veryl_simulator::covergroup::Item>,
+}
+
+#[derive(serde::Serialize)]
+pub struct TestReport {
+    pub name: String,
+    /// "pass" | "fail" | "error"
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// `file:line` of the failed assertions, relative to the project root.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<String>,
+    /// `native (<backend>)` or the external simulator, e.g. `verilator`.
+    pub simulator: String,
+    pub runtime_s: f64,
+    /// Simulation-only wall time: `Simulator::new` + memory preload + the
+    /// cycle loop, excluding the per-test IR build / AOT compile / dlopen that
+    /// `runtime_s` also covers (the boundary a warm Verilator binary re-runs).
+    /// `None` for non-native tests and native tests that never reached execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sim_s: Option<f64>,
+    /// Captured `$display`/`$write` output (the tool output for external
+    /// simulators).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl TestReport {
+    /// One-line failure summary, led by the first failed assertion.
+    fn summary(&self) -> String {
+        let message = self.message.as_deref().unwrap_or_default();
+        let first = message.lines().next().unwrap_or_default();
+        match self.locations.first() {
+            Some(location) if first.is_empty() => location.clone(),
+            Some(location) => format!("{location}: {first}"),
+            None => first.to_string(),
+        }
+    }
+
+    /// Full failure text: the message, then where each assertion failed.
+    fn detail(&self) -> String {
+        let mut ret = self.message.clone().unwrap_or_default();
+        for location in &self.locations {
+            if !ret.is_empty() {
+                ret.push('\n');
+            }
+            let _ = write!(ret, "at {location}");
+        }
+        ret
+    }
+}
+
+impl TestSuiteReport {
+    pub fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// JUnit XML with one `testsuite` named after the project.
+    pub fn junit(&self, name: &str) -> String {
+        let errors = self.tests.iter().filter(|x| x.status == "error").count();
+        let failures = self.tests.iter().filter(|x| x.status == "fail").count();
+        let time: f64 = self.tests.iter().map(|x| x.runtime_s).sum();
+        let suite = format!(
+            r#"name="{}" tests="{}" failures="{failures}" errors="{errors}" skipped="{}" time="{time:.3}""#,
+            escape(name),
+            self.tests.len() + self.ignored,
+            self.ignored,
+        );
+
+        let mut ret = String::new();
+        let _ = writeln!(ret, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(ret, "<testsuites {suite}>");
+        let _ = writeln!(ret, "  <testsuite {suite}>");
+        let _ = writeln!(ret, "    <properties>");
+        let _ = writeln!(
+            ret,
+            r#"      <property name="backend" value="{}"/>"#,
+            escape(&self.backend)
+        );
+        let _ = writeln!(ret, "    </properties>");
+        for test in &self.tests {
+            let _ = writeln!(
+                ret,
+                r#"    <testcase name="{}" classname="{}" time="{:.3}">"#,
+                escape(&test.name),
+                escape(name),
+                test.runtime_s
+            );
+            let _ = writeln!(ret, "      <properties>");
+            let _ = writeln!(
+                ret,
+                r#"        <property name="simulator" value="{}"/>"#,
+                escape(&test.simulator)
+            );
+            let _ = writeln!(ret, "      </properties>");
+            let tag = match test.status {
+                "fail" => Some("failure"),
+                "error" => Some("error"),
+                _ => None,
+            };
+            if let Some(tag) = tag {
+                let _ = writeln!(
+                    ret,
+                    r#"      <{tag} message="{}" type="{tag}">{}</{tag}>"#,
+                    escape(&test.summary()),
+                    escape(&test.detail())
+                );
+            }
+            if let Some(output) = &test.output {
+                let _ = writeln!(ret, "      <system-out>{}</system-out>", escape(output));
+            }
+            let _ = writeln!(ret, "    </testcase>");
+        }
+        let _ = writeln!(ret, "  </testsuite>");
+        let _ = writeln!(ret, "</testsuites>");
+        ret
+    }
+
+    /// TAP version 13, details in a YAML block under each test.
+    pub fn tap(&self) -> String {
+        let mut ret = String::new();
+        let _ = writeln!(ret, "TAP version 13");
+        let _ = writeln!(ret, "1..{}", self.tests.len());
+        for (i, test) in self.tests.iter().enumerate() {
+            let ok = if test.status == "pass" {
+                "ok"
+            } else {
+                "not ok"
+            };
+            let _ = writeln!(ret, "{ok} {} - {}", i + 1, test.name);
+            let _ = writeln!(ret, "  ---");
+            let _ = writeln!(ret, "  duration_ms: {:.3}", test.runtime_s * 1e3);
+            let _ = writeln!(ret, "  simulator: {}", yaml_string(&test.simulator));
+            if test.status != "pass" {
+                let _ = writeln!(ret, "  severity: {}", test.status);
+                let _ = writeln!(ret, "  message: {}", yaml_string(&test.summary()));
+                if !test.locations.is_empty() {
+                    let _ = writeln!(ret, "  at:");
+                    for location in &test.locations {
+                        let _ = writeln!(ret, "    - {}", yaml_string(location));
+                    }
+                }
+                if let Some(message) = &test.message {
+                    yaml_block(&mut ret, "data", message);
+                }
+            }
+            if let Some(output) = &test.output {
+                yaml_block(&mut ret, "output", output);
+            }
+            let _ = writeln!(ret, "  ...");
+        }
+        if self.ignored > 0 {
+            let _ = writeln!(ret, "# {} test(s) ignored", self.ignored);
+        }
+        ret
+    }
+}
+
+fn escape(x: &str) -> String {
+    let mut ret = String::with_capacity(x.len());
+    for c in x.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            // Simulator output may carry ANSI escapes, which XML 1.0 forbids.
+            '\t' | '\n' | '\r' => ret.push(c),
+            c if c < ' ' => {}
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+fn yaml_string(x: &str) -> String {
+    serde_json::to_string(x).unwrap_or_default()
+}
+
+fn yaml_block(ret: &mut String, key: &str, text: &str) {
+    let _ = writeln!(ret, "  {key}: |-");
+    for line in text.lines() {
+        let _ = writeln!(ret, "    {line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> TestSuiteReport {
+        TestSuiteReport {
+            format_version: 1,
+            backend: "cranelift".to_string(),
+            passed: 1,
+            failed: 1,
+            ignored: 1,
+            tests: vec![
+                TestReport {
+                    name: "test_pass".to_string(),
+                    status: "pass",
+                    message: None,
+                    locations: vec![],
+                    simulator: "native (cranelift)".to_string(),
+                    runtime_s: 0.25,
+                    sim_s: Some(0.125),
+                    output: Some("count = 3\n".to_string()),
+                },
+                TestReport {
+                    name: "test_fail".to_string(),
+                    status: "fail",
+                    message: Some("mismatch: a=5 <b>=9".to_string()),
+                    locations: vec!["src/tb.veryl:12".to_string()],
+                    simulator: "verilator".to_string(),
+                    runtime_s: 1.5,
+                    sim_s: None,
+                    output: None,
+                },
+            ],
+            functional_coverage: vec![],
+        }
+    }
+
+    #[test]
+    fn junit_reports_failures_with_location() {
+        let xml = report().junit("proj");
+        assert!(xml.contains(
+            r#"<testsuite name="proj" tests="3" failures="1" errors="0" skipped="1" time="1.750">"#
+        ));
+        assert!(xml.contains(r#"<testcase name="test_pass" classname="proj" time="0.250">"#));
+        assert!(xml.contains(r#"<property name="simulator" value="native (cranelift)"/>"#));
+        assert!(xml.contains("<system-out>count = 3\n</system-out>"));
+        assert!(xml.contains(
+            r#"<failure message="src/tb.veryl:12: mismatch: a=5 &lt;b&gt;=9" type="failure">mismatch: a=5 &lt;b&gt;=9
+at src/tb.veryl:12</failure>"#
+        ));
+    }
+
+    #[test]
+    fn tap_reports_each_test() {
+        let tap = report().tap();
+        let lines: Vec<_> = tap.lines().collect();
+        assert_eq!(lines[0], "TAP version 13");
+        assert_eq!(lines[1], "1..2");
+        assert!(lines.contains(&"ok 1 - test_pass"));
+        assert!(lines.contains(&"not ok 2 - test_fail"));
+        assert!(lines.contains(&r#"  message: "src/tb.veryl:12: mismatch: a=5 <b>=9""#));
+        assert!(lines.contains(&r#"    - "src/tb.veryl:12""#));
+        assert!(lines.contains(&"  simulator: \"verilator\""));
+        assert!(lines.contains(&"    count = 3"));
+        assert_eq!(lines.last(), Some(&"# 1 test(s) ignored"));
+    }
+}
@@ -0,0 +1,259 @@
+//! `--watch` for `build`, `check`, `test` and `doc`: re-run the command
+//! whenever a project source, a dependency source, `Veryl.toml` or
+//! `Veryl.lock` changes.
+//!
+//! Changes are found by polling modification times, so no platform notifier
+//! is needed. `build`, `check` and `test` cycles run with the fragment cache
+//! enabled (see [`crate::incremental`]); unchanged files are restored from the
+//! store instead of being re-analysed and re-emitted. `doc` has no fragment
+//! cache and re-renders the whole project on every cycle.
+
+use crate::StopWatch;
+use crate::incremental::last_restored_count;
+use crate::pipeline::CheckError;
+use log::{info, warn};
+use miette::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use veryl_analyzer::Analyzer;
+use veryl_metadata::Metadata;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+/// Quiet period after a change, so an editor's save burst triggers one cycle.
+const SETTLE: Duration = Duration::from_millis(100);
+
+/// Modification time of every watched file; `None` when it is missing.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<PathBuf, Option<SystemTime>>);
+
+impl Snapshot {
+    pub fn take(paths: &BTreeSet<PathBuf>) -> Self {
+        Snapshot(
+            paths
+                .iter()
+                .map(|x| (x.clone(), fs::metadata(x).and_then(|x| x.modified()).ok()))
+                .collect(),
+        )
+    }
+
+    /// Files added, removed or modified in `newer`.
+    pub fn changed(&self, newer: &Snapshot) -> Vec<PathBuf> {
+        let mut ret: Vec<_> = self
+            .0
+            .iter()
+            .filter(|(path, time)| newer.0.get(*path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .collect();
+        ret.extend(newer.0.keys().filter(|x| !self.0.contains_key(*x)).cloned());
+        ret
+    }
+
+    /// Files of `self` modified or removed in `newer`; files new to `newer`
+    /// are ignored.
+    fn modified(&self, newer: &Snapshot) -> Vec<PathBuf> {
+        self.0
+            .iter()
+            .filter(|(path, time)| newer.0.get(*path).is_some_and(|x| x != *time))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+/// What to watch: the project's source directories are re-scanned on every
+/// poll so added files are noticed, while dependency sources are fixed per
+/// cycle since they only change through `Veryl.lock`.
+#[derive(Default)]
+pub struct Watched {
+    roots: Vec<PathBuf>,
+    files: BTreeSet<PathBuf>,
+}
+
+impl Watched {
+    pub fn new(metadata: &mut Metadata) -> Self {
+        let mut ret = Watched::default();
+        let project = metadata.project_path();
+        if metadata.build.sources.is_empty() {
+            ret.roots.push(project.clone());
+        }
+        ret.roots
+            .extend(metadata.build.sources.iter().map(|x| project.join(x)));
+        ret.roots.push(project.join("examples"));
+        ret.files.insert(metadata.metadata_path.clone());
+        ret.files.insert(metadata.lockfile_path.clone());
+        // Missing dependencies are reported by the cycle itself.
+        if let Ok(paths) = metadata.paths::<PathBuf>(&[], true, true) {
+            ret.files.extend(
+                paths
+                    .into_iter()
+                    .filter(|x| !ret.roots.iter().any(|root| x.src.starts_with(root)))
+                    .map(|x| x.src),
+            );
+        }
+        ret
+    }
+
+    pub fn collect(&self) -> BTreeSet<PathBuf> {
+        let mut ret = self.files.clone();
+        for root in self.roots.iter().filter(|x| x.exists()) {
+            if let Ok(files) = veryl_path::gather_files_with_extension(root, "veryl", false) {
+                ret.extend(files);
+            }
+        }
+        ret
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot::take(&self.collect())
+    }
+}
+
+/// Runs `cycle` once and then again after every change, until interrupted.
+/// Only returns if `Veryl.toml` is changed into something unloadable.
+///
+/// `cached` tells whether `cycle` goes through the fragment cache, so the
+/// summary only reports restored files for commands that restore any.
+pub fn run<F>(metadata: &mut Metadata, command: &str, cached: bool, mut cycle: F) -> Result<bool>
+where
+    F: FnMut(&mut Metadata) -> Result<bool>,
+{
+    let mut watched = Watched::new(metadata);
+    loop {
+        metadata.build.incremental = true;
+        Analyzer::new(metadata).clear();
+
+        let before = watched.snapshot();
+        let mut stopwatch = StopWatch::new();
+        let ret = cycle(metadata);
+        summarize(command, cached, ret, stopwatch.lap());
+
+        watched = Watched::new(metadata);
+        let mut current = watched.snapshot();
+        let mut changed = before.modified(&current);
+        if changed.is_empty() {
+            info!("Watching {} files for changes", current.0.len());
+        }
+        while changed.is_empty() {
+            thread::sleep(POLL_INTERVAL);
+            let next = watched.snapshot();
+            changed = current.changed(&next);
+            current = next;
+        }
+        thread::sleep(SETTLE);
+
+        for path in &changed {
+            info!("Changed {}", display(metadata, path));
+        }
+        if changed.contains(&metadata.metadata_path) {
+            let path = metadata.metadata_path.clone();
+            *metadata = Metadata::load(path)?;
+            watched = Watched::new(metadata);
+        }
+    }
+}
+
+fn summarize(command: &str, cached: bool, ret: Result<bool>, elapsed: u128) {
+    match ret {
+        Ok(true) if cached => info!(
+            "Finished {command} ({elapsed} milliseconds, {} files restored from cache)",
+            last_restored_count()
+        ),
+        Ok(true) => info!("Finished {command} ({elapsed} milliseconds)"),
+        Ok(false) => warn!("Failed {command} ({elapsed} milliseconds)"),
+        Err(err) => {
+            let (errors, warnings) = err
+                .downcast_ref::<CheckError>()
+                .map(CheckError::counts)
+                .unwrap_or((1, 0));
+            eprintln!("{err:?}");
+            warn!(
+                "Failed {command} ({errors} errors, {warnings} warnings, {elapsed} milliseconds)"
+            );
+        }
+    }
+}
+
+fn display(metadata: &Metadata, path: &Path) -> String {
+    path.strip_prefix(metadata.project_path())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_detects_modified_added_and_removed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.veryl");
+        let b = dir.path().join("b.veryl");
+        fs::write(&a, "").unwrap();
+
+        let paths: BTreeSet<_> = [a.clone(), b.clone()].into();
+        let before = Snapshot::take(&paths);
+        assert!(before.changed(&Snapshot::take(&paths)).is_empty());
+
+        fs::write(&b, "").unwrap();
+        let added = Snapshot::take(&paths);
+        assert_eq!(before.changed(&added), vec![b.clone()]);
+
+        let file = fs::File::options().write(true).open(&a).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        fs::remove_file(&b).unwrap();
+        let after = Snapshot::take(&paths);
+        assert_eq!(added.changed(&after), vec![a.clone(), b]);
+        assert_eq!(before.modified(&after), vec![a]);
+
+        let c = dir.path().join("c.veryl");
+        let wider = Snapshot::take(&[c.clone()].into());
+        assert_eq!(Snapshot::default().changed(&wider), vec![c]);
+    }
+
+    #[test]
+    fn run_reruns_cycle_after_source_edit() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = dir.path().join("Veryl.toml");
+        let src = dir.path().join("src");
+        let file = src.join("a.veryl");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(
+            &toml,
+            r#"[project]
+name = "watch_prj"
+version = "0.1.0"
+
+[build]
+sources = ["src"]
+target = {type = "directory", path = "target"}
+exclude_std = true
+"#,
+        )
+        .unwrap();
+        fs::write(&file, "module A {}\n").unwrap();
+        let mut metadata = Metadata::load(&toml).unwrap();
+
+        let mut seen = Vec::new();
+        let ret = run(&mut metadata, "build", true, |_| {
+            seen.push(fs::read_to_string(&file).unwrap());
+            if seen.len() == 1 {
+                // Edited while the watcher is polling.
+                let file = file.clone();
+                thread::spawn(move || {
+                    thread::sleep(POLL_INTERVAL * 2);
+                    fs::write(file, "module B {}\n").unwrap();
+                });
+            } else {
+                // An unloadable `Veryl.toml` is the only way out of the loop.
+                fs::write(&toml, "[project\n").unwrap();
+            }
+            Ok(true)
+        });
+
+        assert!(ret.is_err());
+        assert_eq!(seen, ["module A {}\n", "module B {}\n"]);
+    }
+}